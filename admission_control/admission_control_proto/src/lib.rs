@@ -6,8 +6,16 @@ pub mod proto;
 use failure::prelude::*;
 use logger::prelude::*;
 use mempool::MempoolAddTransactionStatus;
+use nextgen_crypto::Signature;
 use proto_conv::{FromProto, IntoProto};
-use types::vm_error::VMStatus;
+use types::{
+    access_path::AccessPath, account_address::AccountAddress,
+    get_with_proof::UpdateToLatestLedgerResponse, transaction::Version, vm_error::VMStatus,
+};
+
+pub use mempool::{
+    proto::shared::mempool_status::MempoolTransactionStatus, GasPriceEstimate, GasPricePercentiles,
+};
 
 /// AC response status of submit_transaction to clients.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -125,3 +133,143 @@ impl FromProto for SubmitTransactionResponse {
         })
     }
 }
+
+/// Rust structure for EventSubscription protobuf definition.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EventSubscription {
+    /// The access path to the event stream.
+    pub access_path: AccessPath,
+    /// The sequence number of the first event on this stream to push.
+    pub start_event_seq_num: u64,
+}
+
+impl IntoProto for EventSubscription {
+    type ProtoType = crate::proto::admission_control::EventSubscription;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_access_path(self.access_path.into_proto());
+        proto.set_start_event_seq_num(self.start_event_seq_num);
+        proto
+    }
+}
+
+impl FromProto for EventSubscription {
+    type ProtoType = crate::proto::admission_control::EventSubscription;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(EventSubscription {
+            access_path: AccessPath::from_proto(object.take_access_path())?,
+            start_event_seq_num: object.get_start_event_seq_num(),
+        })
+    }
+}
+
+/// Rust structure for SubscribeEventsRequest protobuf definition.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SubscribeEventsRequest {
+    /// The version of the latest ledger info the client knows and trusts.
+    pub client_known_version: Version,
+    /// The event streams to subscribe to.
+    pub subscriptions: Vec<EventSubscription>,
+}
+
+impl IntoProto for SubscribeEventsRequest {
+    type ProtoType = crate::proto::admission_control::SubscribeEventsRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_client_known_version(self.client_known_version);
+        proto.set_subscriptions(::protobuf::RepeatedField::from_vec(
+            self.subscriptions
+                .into_iter()
+                .map(EventSubscription::into_proto)
+                .collect(),
+        ));
+        proto
+    }
+}
+
+impl FromProto for SubscribeEventsRequest {
+    type ProtoType = crate::proto::admission_control::SubscribeEventsRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(SubscribeEventsRequest {
+            client_known_version: object.get_client_known_version(),
+            subscriptions: object
+                .take_subscriptions()
+                .into_iter()
+                .map(EventSubscription::from_proto)
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
+/// Rust structure for GetTransactionStatusRequest protobuf definition.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GetTransactionStatusRequest {
+    /// The version of the latest ledger info the client knows and trusts.
+    pub client_known_version: Version,
+    /// The sender of the transaction.
+    pub sender: AccountAddress,
+    /// The sequence number of the transaction.
+    pub sequence_number: u64,
+}
+
+impl IntoProto for GetTransactionStatusRequest {
+    type ProtoType = crate::proto::admission_control::GetTransactionStatusRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_client_known_version(self.client_known_version);
+        proto.set_sender(self.sender.into_proto());
+        proto.set_sequence_number(self.sequence_number);
+        proto
+    }
+}
+
+impl FromProto for GetTransactionStatusRequest {
+    type ProtoType = crate::proto::admission_control::GetTransactionStatusRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(GetTransactionStatusRequest {
+            client_known_version: object.get_client_known_version(),
+            sender: AccountAddress::from_proto(object.take_sender())?,
+            sequence_number: object.get_sequence_number(),
+        })
+    }
+}
+
+/// Rust structure for GetTransactionStatusResponse protobuf definition.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GetTransactionStatusResponse<Sig> {
+    /// The status of the transaction in mempool, `Unknown` if it is committed.
+    pub mempool_status: MempoolTransactionStatus,
+    /// The transaction with proof if it is committed, or the proof of the current sequence number
+    /// of the sender otherwise.
+    pub ledger_response: UpdateToLatestLedgerResponse<Sig>,
+}
+
+impl<Sig: Signature> IntoProto for GetTransactionStatusResponse<Sig> {
+    type ProtoType = crate::proto::admission_control::GetTransactionStatusResponse;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_mempool_status(self.mempool_status);
+        proto.set_ledger_response(self.ledger_response.into_proto());
+        proto
+    }
+}
+
+impl<Sig: Signature> FromProto for GetTransactionStatusResponse<Sig> {
+    type ProtoType = crate::proto::admission_control::GetTransactionStatusResponse;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(GetTransactionStatusResponse {
+            mempool_status: object.get_mempool_status(),
+            ledger_response: UpdateToLatestLedgerResponse::from_proto(
+                object.take_ledger_response(),
+            )?,
+        })
+    }
+}
@@ -3,7 +3,7 @@
 
 #![allow(bare_trait_objects)]
 
-use mempool::proto::shared::mempool_status;
+use mempool::proto::shared::{gas_price, mempool_status};
 use types::proto::*;
 
 /// Auto generated proto src files
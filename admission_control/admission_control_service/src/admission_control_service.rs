@@ -8,18 +8,29 @@
 use crate::OP_COUNTERS;
 use admission_control_proto::{
     proto::{
-        admission_control::{SubmitTransactionRequest, SubmitTransactionResponse},
+        admission_control::{
+            GetGasPriceEstimateRequest, GetGasPriceEstimateResponse, GetTransactionStatusRequest,
+            GetTransactionStatusResponse, SubmitTransactionRequest, SubmitTransactionResponse,
+            SubscribeEventsRequest,
+        },
         admission_control_grpc::AdmissionControl,
     },
-    AdmissionControlStatus,
+    AdmissionControlStatus, EventSubscription, MempoolTransactionStatus,
 };
 use failure::prelude::*;
 use futures::future::Future;
-use futures03::executor::block_on;
-use grpc_helpers::provide_grpc_response;
+use futures03::{
+    executor::block_on,
+    stream::{self, Stream, StreamExt},
+};
+use grpc_helpers::{provide_grpc_response, provide_grpc_stream_response};
 use logger::prelude::*;
 use mempool::proto::{
-    mempool::{AddTransactionWithValidationRequest, HealthCheckRequest},
+    mempool::{
+        AddTransactionWithValidationRequest,
+        GetGasPriceEstimateRequest as MempoolGetGasPriceEstimateRequest,
+        GetTransactionStatusRequest as MempoolGetTransactionStatusRequest, HealthCheckRequest,
+    },
     mempool_client::MempoolClientTrait,
     shared::mempool_status::{
         MempoolAddTransactionStatus,
@@ -28,11 +39,12 @@ use mempool::proto::{
 };
 use metrics::counters::SVC_COUNTERS;
 use proto_conv::{FromProto, IntoProto};
-use std::sync::Arc;
+use std::{pin::Pin, sync::Arc};
 use storage_client::StorageRead;
 use types::{
+    get_with_proof::{RequestItem, ResponseItem},
     proto::get_with_proof::{UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse},
-    transaction::SignedTransaction,
+    transaction::{SignedTransaction, Version},
 };
 use vm_validator::vm_validator::{get_account_state, TransactionValidation};
 
@@ -40,6 +52,9 @@ use vm_validator::vm_validator::{get_account_state, TransactionValidation};
 #[path = "unit_tests/admission_control_service_test.rs"]
 mod admission_control_service_test;
 
+/// The maximum number of events pushed on each event stream in one response of `SubscribeEvents`.
+const MAX_EVENTS_PER_SUBSCRIPTION_PER_RESPONSE: u64 = 100;
+
 /// Struct implementing trait (service handle) AdmissionControlService.
 #[derive(Clone)]
 pub struct AdmissionControlService<M, V> {
@@ -206,6 +221,187 @@ where
         );
         Ok(rust_resp.into_proto())
     }
+
+    /// Subscribe to the event streams in the request. Returns a stream of responses, each of which
+    /// carries the events committed on the event streams since the previous response.
+    pub(crate) fn subscribe_events_inner(
+        &self,
+        req: SubscribeEventsRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<UpdateToLatestLedgerResponse>> + Send>>> {
+        let rust_req = admission_control_proto::SubscribeEventsRequest::from_proto(req)?;
+        ensure!(
+            !rust_req.subscriptions.is_empty(),
+            "No event stream to subscribe to."
+        );
+        let commits = self.storage_read_client.subscribe_to_commits()?;
+        let subscriber = EventSubscriber {
+            storage_read_client: Arc::clone(&self.storage_read_client),
+            commits,
+            client_known_version: rust_req.client_known_version,
+            subscriptions: rust_req.subscriptions,
+            has_more_events: false,
+        };
+        // The stream ends after the first error.
+        Ok(Box::pin(stream::unfold(Some(subscriber), |subscriber| {
+            async move {
+                let mut subscriber = subscriber?;
+                match subscriber.next_response().await {
+                    Ok(Some(response)) => Some((Ok(response), Some(subscriber))),
+                    Ok(None) => None,
+                    Err(e) => Some((Err(e), None)),
+                }
+            }
+        })))
+    }
+
+    /// Look up the transaction in Storage, with proof, and its status in Mempool.
+    pub(crate) fn get_transaction_status_inner(
+        &self,
+        req: GetTransactionStatusRequest,
+    ) -> Result<GetTransactionStatusResponse> {
+        let rust_req = admission_control_proto::GetTransactionStatusRequest::from_proto(req)?;
+        // Mempool is asked first, so a transaction committed in between is found in Storage
+        // instead of being reported as unknown.
+        let mempool_status = match &self.mempool_client {
+            Some(mempool_client) => {
+                let mut mempool_req = MempoolGetTransactionStatusRequest::new();
+                mempool_req.set_sender(rust_req.sender.into_proto());
+                mempool_req.set_sequence_number(rust_req.sequence_number);
+                mempool_client
+                    .get_transaction_status(&mempool_req)?
+                    .get_status()
+            }
+            None => MempoolTransactionStatus::Unknown,
+        };
+
+        let requested_items = vec![RequestItem::GetAccountTransactionBySequenceNumber {
+            account: rust_req.sender,
+            sequence_number: rust_req.sequence_number,
+            fetch_events: true,
+        }];
+        let (response_items, ledger_info_with_sigs, validator_change_events) = self
+            .storage_read_client
+            .update_to_latest_ledger(rust_req.client_known_version, requested_items)?;
+        let is_committed = match response_items.as_slice() {
+            [ResponseItem::GetAccountTransactionBySequenceNumber {
+                signed_transaction_with_proof,
+                ..
+            }] => signed_transaction_with_proof.is_some(),
+            _ => bail!("Unexpected response items: {:?}", response_items),
+        };
+        let rust_resp = admission_control_proto::GetTransactionStatusResponse {
+            mempool_status: if is_committed {
+                MempoolTransactionStatus::Unknown
+            } else {
+                mempool_status
+            },
+            ledger_response: types::get_with_proof::UpdateToLatestLedgerResponse::new(
+                response_items,
+                ledger_info_with_sigs,
+                validator_change_events,
+            ),
+        };
+        Ok(rust_resp.into_proto())
+    }
+
+    /// Pass the request for gas price estimate to Mempool.
+    pub(crate) fn get_gas_price_estimate_inner(
+        &self,
+        _req: GetGasPriceEstimateRequest,
+    ) -> Result<GetGasPriceEstimateResponse> {
+        match &self.mempool_client {
+            Some(mempool_client) => {
+                let mut mempool_resp = mempool_client
+                    .get_gas_price_estimate(&MempoolGetGasPriceEstimateRequest::new())?;
+                let mut response = GetGasPriceEstimateResponse::new();
+                response.set_estimate(mempool_resp.take_estimate());
+                Ok(response)
+            }
+            None => Err(format_err!("Mempool is not initialized")),
+        }
+    }
+}
+
+/// Keeps track of the events pushed to a subscriber of `SubscribeEvents`.
+struct EventSubscriber {
+    /// gRPC client to send read requests to Storage.
+    storage_read_client: Arc<dyn StorageRead>,
+    /// The versions of ledger infos committed by Storage.
+    commits: Pin<Box<dyn Stream<Item = Result<Version>> + Send>>,
+    /// The version of the ledger info in the last response, or the one the subscriber knew
+    /// initially if nothing has been pushed yet.
+    client_known_version: Version,
+    /// The event streams subscribed to, each with the sequence number of the next event to push.
+    subscriptions: Vec<EventSubscription>,
+    /// Whether there might be more committed events than the last response could carry.
+    has_more_events: bool,
+}
+
+impl EventSubscriber {
+    /// Waits until new events are committed on the subscribed event streams and returns them with
+    /// proofs. Returns `None` if Storage stops sending commit notifications.
+    async fn next_response(&mut self) -> Result<Option<UpdateToLatestLedgerResponse>> {
+        loop {
+            if !self.has_more_events {
+                match self.commits.next().await {
+                    Some(version) => version?,
+                    None => return Ok(None),
+                };
+            }
+
+            let requested_items = self
+                .subscriptions
+                .iter()
+                .map(|subscription| RequestItem::GetEventsByEventAccessPath {
+                    access_path: subscription.access_path.clone(),
+                    start_event_seq_num: subscription.start_event_seq_num,
+                    ascending: true,
+                    limit: MAX_EVENTS_PER_SUBSCRIPTION_PER_RESPONSE,
+                })
+                .collect::<Vec<_>>();
+            let (response_items, ledger_info_with_sigs, validator_change_events) = self
+                .storage_read_client
+                .update_to_latest_ledger_async(self.client_known_version, requested_items)
+                .await?;
+            ensure!(
+                response_items.len() == self.subscriptions.len(),
+                "Expecting {} response items, got {}.",
+                self.subscriptions.len(),
+                response_items.len(),
+            );
+
+            let mut num_new_events = 0;
+            self.has_more_events = false;
+            for (subscription, response_item) in
+                self.subscriptions.iter_mut().zip(response_items.iter())
+            {
+                match response_item {
+                    ResponseItem::GetEventsByEventAccessPath {
+                        events_with_proof, ..
+                    } => {
+                        let num_events = events_with_proof.len() as u64;
+                        subscription.start_event_seq_num += num_events;
+                        num_new_events += num_events;
+                        if num_events == MAX_EVENTS_PER_SUBSCRIPTION_PER_RESPONSE {
+                            self.has_more_events = true;
+                        }
+                    }
+                    _ => bail!("Unexpected response item: {:?}", response_item),
+                }
+            }
+            if num_new_events == 0 {
+                continue;
+            }
+
+            self.client_known_version = ledger_info_with_sigs.ledger_info().version();
+            let rust_resp = types::get_with_proof::UpdateToLatestLedgerResponse::new(
+                response_items,
+                ledger_info_with_sigs,
+                validator_change_events,
+            );
+            return Ok(Some(rust_resp.into_proto()));
+        }
+    }
 }
 
 impl<M: 'static, V> AdmissionControl for AdmissionControlService<M, V>
@@ -248,4 +444,49 @@ where
         let resp = self.update_to_latest_ledger_inner(req);
         provide_grpc_response(resp, ctx, sink);
     }
+
+    /// This API is used to receive new events on a set of event streams with proofs as soon as
+    /// they are committed, instead of polling `update_to_latest_ledger`.
+    fn subscribe_events(
+        &mut self,
+        ctx: grpcio::RpcContext<'_>,
+        req: SubscribeEventsRequest,
+        sink: grpcio::ServerStreamingSink<
+            types::proto::get_with_proof::UpdateToLatestLedgerResponse,
+        >,
+    ) {
+        debug!("[GRPC] AdmissionControl::subscribe_events");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.subscribe_events_inner(req);
+        provide_grpc_stream_response(resp, ctx, sink);
+    }
+
+    /// This API is used to find out what happened to a submitted transaction: whether it is
+    /// committed, with proof, or otherwise its status in Mempool.
+    fn get_transaction_status(
+        &mut self,
+        ctx: grpcio::RpcContext<'_>,
+        req: GetTransactionStatusRequest,
+        sink: grpcio::UnarySink<GetTransactionStatusResponse>,
+    ) {
+        debug!("[GRPC] AdmissionControl::get_transaction_status");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.get_transaction_status_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    /// This API is used to pick a gas unit price for a new transaction based on the gas unit
+    /// prices of pending and recently committed transactions. AC will not directly process this
+    /// request but pass it to Mempool instead.
+    fn get_gas_price_estimate(
+        &mut self,
+        ctx: grpcio::RpcContext<'_>,
+        req: GetGasPriceEstimateRequest,
+        sink: grpcio::UnarySink<GetGasPriceEstimateResponse>,
+    ) {
+        debug!("[GRPC] AdmissionControl::get_gas_price_estimate");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.get_gas_price_estimate_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
 }
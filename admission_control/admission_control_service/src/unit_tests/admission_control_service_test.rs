@@ -6,21 +6,31 @@ use crate::{
         AdmissionControlService, SubmitTransactionRequest,
         SubmitTransactionResponse as ProtoSubmitTransactionResponse,
     },
-    unit_tests::LocalMockMempool,
+    unit_tests::{mock_gas_price_estimate, LocalMockMempool},
+};
+use admission_control_proto::{
+    proto::admission_control::GetGasPriceEstimateRequest, AdmissionControlStatus,
+    EventSubscription, GasPriceEstimate, GetTransactionStatusRequest, GetTransactionStatusResponse,
+    MempoolTransactionStatus, SubmitTransactionResponse, SubscribeEventsRequest,
 };
-use admission_control_proto::{AdmissionControlStatus, SubmitTransactionResponse};
 
 use assert_matches::assert_matches;
 use crypto::hash::CryptoHash;
+use futures03::{executor::block_on, stream::StreamExt};
 use mempool::proto::shared::mempool_status::MempoolAddTransactionStatusCode;
 use nextgen_crypto::{ed25519::*, test_utils::TEST_SEED, SigningKey};
-use proto_conv::FromProto;
+use proto_conv::{FromProto, IntoProto};
 use protobuf::{Message, UnknownFields};
 use rand::SeedableRng;
 use std::sync::Arc;
-use storage_service::mocks::mock_storage_client::MockStorageReadClient;
+use storage_service::mocks::mock_storage_client::{
+    MockStorageReadClient, MOCK_LEDGER_VERSION, MOCK_NUM_EVENTS_PER_ACCESS_PATH,
+    MOCK_NUM_TXNS_PER_ACCOUNT,
+};
 use types::{
+    access_path::AccessPath,
     account_address::{AccountAddress, ADDRESS_LENGTH},
+    get_with_proof::{ResponseItem, UpdateToLatestLedgerResponse},
     test_helpers::transaction_test_helpers::get_test_signed_txn,
     transaction::RawTransactionBytes,
     vm_error::{ExecutionStatus, VMStatus, VMValidationStatus},
@@ -278,3 +288,122 @@ fn test_submit_txn_inner_mempool() {
         AdmissionControlStatus::Accepted,
     );
 }
+
+#[test]
+fn test_subscribe_events() {
+    let ac_service = create_ac_service_for_ut();
+    let access_path = AccessPath::new_for_account(AccountAddress::random());
+    let subscribe = |start_event_seq_num| {
+        let req = SubscribeEventsRequest {
+            client_known_version: 0,
+            subscriptions: vec![EventSubscription {
+                access_path: access_path.clone(),
+                start_event_seq_num,
+            }],
+        };
+        ac_service.subscribe_events_inner(req.into_proto())
+    };
+
+    // All the events committed so far are pushed at once.
+    let responses = block_on(subscribe(0).unwrap().collect::<Vec<_>>());
+    assert_eq!(responses.len(), 1);
+    let response =
+        UpdateToLatestLedgerResponse::from_proto(responses.into_iter().next().unwrap().unwrap())
+            .unwrap();
+    assert_eq!(
+        response.ledger_info_with_sigs.ledger_info().version(),
+        MOCK_LEDGER_VERSION
+    );
+    match &response.response_items[..] {
+        [ResponseItem::GetEventsByEventAccessPath {
+            events_with_proof, ..
+        }] => {
+            let seq_nums = events_with_proof
+                .iter()
+                .map(|event_with_proof| event_with_proof.event.sequence_number())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                seq_nums,
+                (0..MOCK_NUM_EVENTS_PER_ACCESS_PATH).collect::<Vec<_>>()
+            );
+        }
+        _ => panic!("Unexpected response items: {:?}", response.response_items),
+    }
+
+    // Nothing is pushed if there is no new event.
+    let responses = block_on(
+        subscribe(MOCK_NUM_EVENTS_PER_ACCESS_PATH)
+            .unwrap()
+            .collect::<Vec<_>>(),
+    );
+    assert!(responses.is_empty());
+
+    // Subscribing to nothing is rejected.
+    let req = SubscribeEventsRequest {
+        client_known_version: 0,
+        subscriptions: vec![],
+    };
+    assert!(ac_service.subscribe_events_inner(req.into_proto()).is_err());
+}
+
+#[test]
+fn test_get_transaction_status() {
+    let ac_service = create_ac_service_for_ut();
+    let sender = AccountAddress::random();
+    let get_status = |sequence_number| {
+        let req = GetTransactionStatusRequest {
+            client_known_version: 0,
+            sender,
+            sequence_number,
+        };
+        GetTransactionStatusResponse::<Ed25519Signature>::from_proto(
+            ac_service
+                .get_transaction_status_inner(req.into_proto())
+                .unwrap(),
+        )
+        .unwrap()
+    };
+
+    // A committed transaction comes with proof.
+    let response = get_status(MOCK_NUM_TXNS_PER_ACCOUNT - 1);
+    assert_eq!(response.mempool_status, MempoolTransactionStatus::Unknown);
+    match &response.ledger_response.response_items[..] {
+        [ResponseItem::GetAccountTransactionBySequenceNumber {
+            signed_transaction_with_proof: Some(signed_transaction_with_proof),
+            ..
+        }] => assert_eq!(
+            signed_transaction_with_proof.signed_transaction.sender(),
+            sender
+        ),
+        _ => panic!(
+            "Unexpected response items: {:?}",
+            response.ledger_response.response_items
+        ),
+    }
+
+    // Otherwise the status in mempool is reported.
+    let response = get_status(MOCK_NUM_TXNS_PER_ACCOUNT);
+    assert_eq!(response.mempool_status, MempoolTransactionStatus::Ready);
+    match &response.ledger_response.response_items[..] {
+        [ResponseItem::GetAccountTransactionBySequenceNumber {
+            signed_transaction_with_proof: None,
+            ..
+        }] => (),
+        _ => panic!(
+            "Unexpected response items: {:?}",
+            response.ledger_response.response_items
+        ),
+    }
+}
+
+#[test]
+fn test_get_gas_price_estimate() {
+    let ac_service = create_ac_service_for_ut();
+    let mut response = ac_service
+        .get_gas_price_estimate_inner(GetGasPriceEstimateRequest::new())
+        .unwrap();
+    assert_eq!(
+        GasPriceEstimate::from_proto(response.take_estimate()).unwrap(),
+        mock_gas_price_estimate()
+    );
+}
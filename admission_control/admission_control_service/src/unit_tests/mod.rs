@@ -1,15 +1,21 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use mempool::proto::{
-    mempool::{
-        AddTransactionWithValidationRequest, AddTransactionWithValidationResponse,
-        HealthCheckRequest, HealthCheckResponse,
+use mempool::{
+    proto::{
+        mempool::{
+            AddTransactionWithValidationRequest, AddTransactionWithValidationResponse,
+            GetGasPriceEstimateRequest, GetGasPriceEstimateResponse, GetTransactionStatusRequest,
+            GetTransactionStatusResponse, HealthCheckRequest, HealthCheckResponse,
+        },
+        mempool_client::MempoolClientTrait,
+        shared::mempool_status::{
+            MempoolAddTransactionStatus, MempoolAddTransactionStatusCode, MempoolTransactionStatus,
+        },
     },
-    mempool_client::MempoolClientTrait,
-    shared::mempool_status::{MempoolAddTransactionStatus, MempoolAddTransactionStatusCode},
+    GasPriceEstimate, GasPricePercentiles,
 };
-use proto_conv::FromProto;
+use proto_conv::{FromProto, IntoProto};
 use std::time::SystemTime;
 use types::{account_address::ADDRESS_LENGTH, transaction::SignedTransaction};
 
@@ -64,4 +70,36 @@ impl MempoolClientTrait for LocalMockMempool {
         ret.set_is_healthy(duration_ms > 500 || duration_ms < 300);
         Ok(ret)
     }
+    fn get_transaction_status(
+        &self,
+        _req: &GetTransactionStatusRequest,
+    ) -> ::grpcio::Result<GetTransactionStatusResponse> {
+        let mut ret = GetTransactionStatusResponse::new();
+        ret.set_status(MempoolTransactionStatus::Ready);
+        Ok(ret)
+    }
+    fn get_gas_price_estimate(
+        &self,
+        _req: &GetGasPriceEstimateRequest,
+    ) -> ::grpcio::Result<GetGasPriceEstimateResponse> {
+        let mut ret = GetGasPriceEstimateResponse::new();
+        ret.set_estimate(mock_gas_price_estimate().into_proto());
+        Ok(ret)
+    }
+}
+
+// The gas price estimate returned by LocalMockMempool
+pub fn mock_gas_price_estimate() -> GasPriceEstimate {
+    GasPriceEstimate {
+        pending: GasPricePercentiles {
+            num_transactions: 3,
+            min: 1,
+            p25: 1,
+            p50: 2,
+            p75: 3,
+            p90: 3,
+            max: 3,
+        },
+        recently_included: GasPricePercentiles::default(),
+    }
 }
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{commands::*, grpc_client::GRPCClient, AccountData, AccountStatus};
-use admission_control_proto::proto::admission_control::SubmitTransactionRequest;
+use admission_control_proto::{
+    proto::admission_control::SubmitTransactionRequest, MempoolTransactionStatus,
+};
 use config::trusted_peers::TrustedPeersConfig;
 use failure::prelude::*;
 use futures::{future::Future, stream::Stream};
@@ -18,6 +20,7 @@ use proto_conv::IntoProto;
 use rust_decimal::Decimal;
 use serde_json;
 use std::{
+    cmp::max,
     collections::{BTreeMap, HashMap},
     convert::TryFrom,
     fmt, fs,
@@ -305,19 +308,30 @@ impl ClientProxy {
             stdout().flush().unwrap();
             max_iterations -= 1;
 
-            if let Ok(Some((_, Some(events)))) =
-                self.client
-                    .get_txn_by_acc_seq(account, sequence_number - 1, true)
+            match self
+                .client
+                .get_transaction_status(account, sequence_number - 1)
             {
-                println!("transaction is stored!");
-                if events.is_empty() {
-                    println!("no events emitted");
+                Ok((Some((_, events)), _)) => {
+                    println!("transaction is stored!");
+                    if events.map_or(true, |events| events.is_empty()) {
+                        println!("no events emitted");
+                    }
+                    break;
+                }
+                // The transaction will never be committed once mempool drops it.
+                Ok((None, status @ MempoolTransactionStatus::Expired))
+                | Ok((None, status @ MempoolTransactionStatus::Evicted))
+                | Ok((None, status @ MempoolTransactionStatus::Rejected)) => {
+                    println!("transaction is dropped by mempool: {:?}", status);
+                    break;
+                }
+                _ => {
+                    if max_iterations == 0 {
+                        panic!("wait_for_transaction timeout");
+                    }
+                    print!(".");
                 }
-                break;
-            } else if max_iterations == 0 {
-                panic!("wait_for_transaction timeout");
-            } else {
-                print!(".");
             }
             thread::sleep(time::Duration::from_millis(10));
         }
@@ -387,14 +401,18 @@ impl ClientProxy {
         let num_coins = Self::convert_to_micro_libras(space_delim_strings[3])?;
 
         let gas_unit_price = if space_delim_strings.len() > 4 {
-            Some(space_delim_strings[4].parse::<u64>().map_err(|error| {
-                format_parse_data_error(
-                    "gas_unit_price",
-                    InputType::UnsignedInt,
-                    space_delim_strings[4],
-                    error,
-                )
-            })?)
+            if space_delim_strings[4] == "auto" {
+                Some(self.estimate_gas_unit_price()?)
+            } else {
+                Some(space_delim_strings[4].parse::<u64>().map_err(|error| {
+                    format_parse_data_error(
+                        "gas_unit_price",
+                        InputType::UnsignedInt,
+                        space_delim_strings[4],
+                        error,
+                    )
+                })?)
+            }
         } else {
             None
         };
@@ -424,6 +442,16 @@ impl ClientProxy {
         )
     }
 
+    /// Picks a gas unit price based on the gas unit prices of the transactions pending in mempool
+    /// and the recently committed ones: the larger of the two medians, so the transaction is not
+    /// behind most of the others.
+    pub fn estimate_gas_unit_price(&self) -> Result<u64> {
+        let estimate = self.client.get_gas_price_estimate()?;
+        let gas_unit_price = max(estimate.pending.p50, estimate.recently_included.p50);
+        println!("Using estimated gas unit price {}", gas_unit_price);
+        Ok(gas_unit_price)
+    }
+
     /// Compile move program
     pub fn compile_program(&mut self, space_delim_strings: &[&str]) -> Result<String> {
         let address = self.get_account_address_from_parameter(space_delim_strings[1])?;
@@ -5,12 +5,15 @@ use crate::AccountData;
 use admission_control_proto::{
     proto::{
         admission_control::{
-            SubmitTransactionRequest, SubmitTransactionResponse as ProtoSubmitTransactionResponse,
+            GetGasPriceEstimateRequest, SubmitTransactionRequest,
+            SubmitTransactionResponse as ProtoSubmitTransactionResponse,
         },
         admission_control_grpc::AdmissionControlClient,
     },
-    AdmissionControlStatus, SubmitTransactionResponse,
+    AdmissionControlStatus, GasPriceEstimate, GetTransactionStatusRequest,
+    GetTransactionStatusResponse, MempoolTransactionStatus, SubmitTransactionResponse,
 };
+use crypto::HashValue;
 use failure::prelude::*;
 use futures::Future;
 use grpcio::{CallOption, ChannelBuilder, EnvBuilder};
@@ -27,6 +30,7 @@ use types::{
     get_with_proof::{
         RequestItem, ResponseItem, UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse,
     },
+    ledger_info::LedgerInfo,
     transaction::{SignedTransaction, Version},
     validator_verifier::ValidatorVerifier,
     vm_error::{VMStatus, VMValidationStatus},
@@ -236,6 +240,77 @@ impl GRPCClient {
         Ok(signed_txn_with_proof.map(|t| (t.signed_transaction, t.events)))
     }
 
+    /// Get the status of a transaction from validator by account and sequence number. Returns the
+    /// transaction with its events if it is committed, together with its status in mempool,
+    /// which is `Unknown` if it is committed.
+    pub fn get_transaction_status(
+        &self,
+        account: AccountAddress,
+        sequence_number: u64,
+    ) -> Result<(
+        Option<(SignedTransaction, Option<Vec<ContractEvent>>)>,
+        MempoolTransactionStatus,
+    )> {
+        let req = GetTransactionStatusRequest {
+            client_known_version: 0,
+            sender: account,
+            sequence_number,
+        };
+        let proto_resp = self
+            .client
+            .get_transaction_status_opt(&req.into_proto(), Self::get_default_grpc_call_option())?;
+        let mut resp = GetTransactionStatusResponse::<Ed25519Signature>::from_proto(proto_resp)?;
+
+        // The ledger response is checked as if it was for the equivalent UpdateToLatestLedger
+        // request.
+        let req_item = RequestItem::GetAccountTransactionBySequenceNumber {
+            account,
+            sequence_number,
+            fetch_events: true,
+        };
+        resp.ledger_response.verify(
+            Arc::clone(&self.validator_verifier),
+            &UpdateToLatestLedgerRequest::new(0, vec![req_item]),
+        )?;
+        let (signed_txn_with_proof, _) = resp
+            .ledger_response
+            .response_items
+            .remove(0)
+            .into_get_account_txn_by_seq_num_response()?;
+
+        Ok((
+            signed_txn_with_proof.map(|t| (t.signed_transaction, t.events)),
+            resp.mempool_status,
+        ))
+    }
+
+    /// Get percentiles of the gas unit prices of pending and recently committed transactions from
+    /// validator.
+    pub fn get_gas_price_estimate(&self) -> Result<GasPriceEstimate> {
+        let mut resp = self.client.get_gas_price_estimate_opt(
+            &GetGasPriceEstimateRequest::new(),
+            Self::get_default_grpc_call_option(),
+        )?;
+        GasPriceEstimate::from_proto(resp.take_estimate())
+    }
+
+    /// Get the latest ledger info from validator, with a proof that the ledger only grew since
+    /// `known_version`, at which the root hash of the transaction accumulator was
+    /// `known_accumulator_hash`.
+    pub fn get_consistent_ledger_info(
+        &self,
+        known_version: Version,
+        known_accumulator_hash: HashValue,
+    ) -> Result<LedgerInfo> {
+        let req_item = RequestItem::GetAccumulatorConsistencyProof {
+            known_version,
+            known_accumulator_hash,
+        };
+        // The consistency proof is checked when verifying the response.
+        let response = self.get_with_proof_sync(vec![req_item])?;
+        Ok(response.ledger_info_with_sigs.ledger_info().clone())
+    }
+
     /// Get transactions in range (start_version..start_version + limit - 1) from validator.
     pub fn get_txn_by_range(
         &self,
@@ -13,7 +13,7 @@ impl Command for TransferCommand {
     fn get_params_help(&self) -> &'static str {
         "\n\t<sender_account_address>|<sender_account_ref_id> \
          <receiver_account_address>|<receiver_account_ref_id> <number_of_coins> \
-         [gas_unit_price_in_micro_libras (default=0)] [max_gas_amount_in_micro_libras (default 100000)] \
+         [gas_unit_price_in_micro_libras|auto (default=0)] [max_gas_amount_in_micro_libras (default 100000)] \
          Suffix 'b' is for blocking. "
     }
     fn get_description(&self) -> &'static str {
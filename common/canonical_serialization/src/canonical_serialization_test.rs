@@ -245,6 +245,18 @@ fn test_vec_impl() {
     assert_eq!(vec, de_vec);
 }
 
+#[test]
+fn test_u128_impl() {
+    let values = vec![0u128, 1, u128::from(std::u64::MAX) + 1, std::u128::MAX];
+
+    let mut serializer = SimpleSerializer::<Vec<u8>>::new();
+    serializer.encode_struct(&values).unwrap();
+    let serialized_bytes = serializer.get_output();
+    assert_eq!(serialized_bytes.len(), 4 + 16 * values.len());
+    let de_values: Vec<u128> = SimpleDeserializer::deserialize(&serialized_bytes).unwrap();
+    assert_eq!(values, de_values);
+}
+
 #[test]
 fn test_vectors_1() {
     let bar = Bar {
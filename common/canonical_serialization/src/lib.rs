@@ -18,7 +18,7 @@
 //! (All unsigned integers are encoded in little-endian representation unless specified otherwise)
 //!
 //! 1. The encoding of an unsigned 64-bit integer is defined as its little-endian representation
-//!    in 8 bytes
+//!    in 8 bytes (16 bytes for an unsigned 128-bit integer)
 //!
 //! 2. The encoding of an item (byte array) is defined as:
 //!    [length in bytes, represented as 4-byte integer] || [item in bytes]
@@ -78,6 +78,8 @@ pub trait CanonicalSerializer {
 
     fn encode_optional<T: CanonicalSerialize>(&mut self, v: &Option<T>) -> Result<&mut Self>;
 
+    fn encode_u128(&mut self, v: u128) -> Result<&mut Self>;
+
     fn encode_u64(&mut self, v: u64) -> Result<&mut Self>;
 
     fn encode_u32(&mut self, v: u32) -> Result<&mut Self>;
@@ -164,6 +166,11 @@ where
         Ok(self)
     }
 
+    fn encode_u128(&mut self, v: u128) -> Result<&mut Self> {
+        self.output.write_u128::<Endianness>(v)?;
+        Ok(self)
+    }
+
     fn encode_u64(&mut self, v: u64) -> Result<&mut Self> {
         self.output.write_u64::<Endianness>(v)?;
         Ok(self)
@@ -269,6 +276,8 @@ pub trait CanonicalDeserializer {
 
     fn decode_optional<T: CanonicalDeserialize>(&mut self) -> Result<Option<T>>;
 
+    fn decode_u128(&mut self) -> Result<u128>;
+
     fn decode_u64(&mut self) -> Result<u64>;
 
     fn decode_u32(&mut self) -> Result<u32>;
@@ -330,6 +339,11 @@ impl<'a> CanonicalDeserializer for SimpleDeserializer<'a> {
         }
     }
 
+    fn decode_u128(&mut self) -> Result<u128> {
+        let num = self.raw_bytes.read_u128::<Endianness>()?;
+        Ok(num)
+    }
+
     fn decode_u64(&mut self) -> Result<u64> {
         let num = self.raw_bytes.read_u64::<Endianness>()?;
         Ok(num)
@@ -517,6 +531,23 @@ impl CanonicalDeserialize for u64 {
     }
 }
 
+impl CanonicalSerialize for u128 {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        serializer.encode_u128(*self)?;
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for u128 {
+    fn deserialize(deserializer: &mut impl CanonicalDeserializer) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let num = deserializer.decode_u128()?;
+        Ok(num)
+    }
+}
+
 impl CanonicalSerialize for i64 {
     fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
         serializer.encode_u64(*self as u64)?;
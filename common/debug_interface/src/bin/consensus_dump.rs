@@ -0,0 +1,158 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use debug_interface::{
+    proto::node_debug_interface::{
+        BlockSummary, DumpConsensusStateResponse, QuorumCertSummary, TimeoutCertificateSummary,
+    },
+    NodeDebugClient,
+};
+use std::collections::HashMap;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "consensus_dump",
+    author = "Libra",
+    about = "Renders the consensus state of a validator through its debug interface"
+)]
+struct Args {
+    /// Address of the debug interface of the validator
+    #[structopt(short = "a", long = "address", default_value = "localhost")]
+    pub address: String,
+    /// Port of the debug interface of the validator
+    #[structopt(short = "p", long = "port", default_value = "6191")]
+    pub port: u16,
+}
+
+/// The first bytes of an id are enough to tell the blocks apart.
+fn short_id(id: &[u8]) -> String {
+    hex::encode(&id[..std::cmp::min(id.len(), 4)])
+}
+
+fn render_block_tree(state: &DumpConsensusStateResponse) {
+    let mut children: HashMap<&[u8], Vec<&BlockSummary>> = HashMap::new();
+    let mut root = None;
+    for block in state.get_blocks() {
+        if block.get_id() == state.get_root_id() {
+            root = Some(block);
+        } else {
+            children
+                .entry(block.get_parent_id())
+                .or_default()
+                .push(block);
+        }
+    }
+    println!("Block tree ({} blocks):", state.get_blocks().len());
+    match root {
+        Some(root) => render_block(root, &children, state, 1),
+        None => println!("  <root not found>"),
+    }
+}
+
+fn render_block(
+    block: &BlockSummary,
+    children: &HashMap<&[u8], Vec<&BlockSummary>>,
+    state: &DumpConsensusStateResponse,
+    depth: usize,
+) {
+    let author = if block.get_author().is_empty() {
+        "none".to_string()
+    } else {
+        short_id(block.get_author())
+    };
+    let mut marks = vec![];
+    if block.get_id() == state.get_root_id() {
+        marks.push("root");
+    }
+    if block.get_id() == state.get_highest_quorum_cert().get_certified_block_id() {
+        marks.push("highest QC");
+    }
+    if block.get_id() == state.get_highest_ledger_info().get_certified_block_id() {
+        marks.push("highest ledger info");
+    }
+    println!(
+        "{}{} round {} height {} author {}{}",
+        "  ".repeat(depth),
+        short_id(block.get_id()),
+        block.get_round(),
+        block.get_height(),
+        author,
+        if marks.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", marks.join(", "))
+        }
+    );
+    let mut block_children = children.get(block.get_id()).cloned().unwrap_or_default();
+    block_children.sort_by_key(|child| child.get_round());
+    for child in block_children {
+        render_block(child, children, state, depth + 1);
+    }
+}
+
+fn render_quorum_cert(name: &str, qc: &QuorumCertSummary) {
+    println!(
+        "{}: certifies {} at round {}, commits {}, version {}, {} signatures",
+        name,
+        short_id(qc.get_certified_block_id()),
+        qc.get_certified_block_round(),
+        if qc.get_committed_block_id().is_empty() {
+            "none".to_string()
+        } else {
+            short_id(qc.get_committed_block_id())
+        },
+        qc.get_version(),
+        qc.get_num_signatures()
+    );
+}
+
+fn render_timeout_certificate(name: &str, tc: Option<&TimeoutCertificateSummary>) {
+    match tc {
+        Some(tc) => println!(
+            "{}: round {}, {} timeouts",
+            name,
+            tc.get_round(),
+            tc.get_num_timeouts()
+        ),
+        None => println!("{}: none", name),
+    }
+}
+
+fn main() {
+    let args = Args::from_args();
+    let client = NodeDebugClient::new(&args.address, args.port);
+    let state = client
+        .dump_consensus_state()
+        .unwrap_or_else(|e| panic!("Failed to dump the consensus state: {}", e));
+
+    render_block_tree(&state);
+    render_quorum_cert("Highest QC", state.get_highest_quorum_cert());
+    render_quorum_cert("Highest ledger info", state.get_highest_ledger_info());
+    render_timeout_certificate(
+        "Highest local timeout certificate",
+        if state.has_highest_local_timeout_certificate() {
+            Some(state.get_highest_local_timeout_certificate())
+        } else {
+            None
+        },
+    );
+    render_timeout_certificate(
+        "Highest received timeout certificate",
+        if state.has_highest_received_timeout_certificate() {
+            Some(state.get_highest_received_timeout_certificate())
+        } else {
+            None
+        },
+    );
+    println!(
+        "Pacemaker: round {}, times out in {} ms",
+        state.get_current_round(),
+        state.get_current_round_deadline_ms()
+    );
+    println!(
+        "ConsensusState: last vote round {}, preferred block round {}",
+        state.get_last_vote_round(),
+        state.get_preferred_block_round()
+    );
+}
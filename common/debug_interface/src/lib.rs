@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::proto::{
-    node_debug_interface::{DumpJemallocHeapProfileRequest, GetNodeDetailsRequest},
+    node_debug_interface::{
+        DumpConsensusStateRequest, DumpConsensusStateResponse, DumpJemallocHeapProfileRequest,
+        GetNodeDetailsRequest,
+    },
     node_debug_interface_grpc::NodeDebugInterfaceClient,
 };
 use failure::prelude::*;
@@ -67,4 +70,11 @@ impl NodeDebugClient {
 
         Ok(response.status_code)
     }
+
+    pub fn dump_consensus_state(&self) -> Result<DumpConsensusStateResponse> {
+        Ok(self
+            .client
+            .dump_consensus_state(&DumpConsensusStateRequest::new())
+            .context("Unable to query the consensus state")?)
+    }
 }
@@ -5,22 +5,38 @@
 
 use crate::proto::{
     node_debug_interface::{
-        DumpJemallocHeapProfileRequest, DumpJemallocHeapProfileResponse, GetNodeDetailsRequest,
-        GetNodeDetailsResponse,
+        DumpConsensusStateRequest, DumpConsensusStateResponse, DumpJemallocHeapProfileRequest,
+        DumpJemallocHeapProfileResponse, GetNodeDetailsRequest, GetNodeDetailsResponse,
     },
     node_debug_interface_grpc::NodeDebugInterface,
 };
 use futures::Future;
+use grpcio::{RpcStatus, RpcStatusCode};
 use logger::prelude::*;
 use metrics::counters::COUNTER_ADMISSION_CONTROL_CANNOT_SEND_REPLY;
+use std::sync::Arc;
+
+/// Provides the state of the consensus running in the node.
+pub trait ConsensusDebugInfo: Send + Sync {
+    fn dump_consensus_state(&self) -> DumpConsensusStateResponse;
+}
 
 #[derive(Clone, Default)]
-pub struct NodeDebugService {}
+pub struct NodeDebugService {
+    consensus: Option<Arc<dyn ConsensusDebugInfo>>,
+}
 
 impl NodeDebugService {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Creates a debug service that can also dump the state of the consensus of the node.
+    pub fn new_with_consensus(consensus: Arc<dyn ConsensusDebugInfo>) -> Self {
+        Self {
+            consensus: Some(consensus),
+        }
+    }
 }
 
 impl NodeDebugInterface for NodeDebugService {
@@ -52,6 +68,28 @@ impl NodeDebugInterface for NodeDebugService {
         let f = sink.success(resp).map_err(default_reply_error_logger);
         ctx.spawn(f)
     }
+
+    fn dump_consensus_state(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        _req: DumpConsensusStateRequest,
+        sink: ::grpcio::UnarySink<DumpConsensusStateResponse>,
+    ) {
+        info!("[GRPC] dump_consensus_state");
+        match &self.consensus {
+            Some(consensus) => ctx.spawn(
+                sink.success(consensus.dump_consensus_state())
+                    .map_err(default_reply_error_logger),
+            ),
+            None => ctx.spawn(
+                sink.fail(RpcStatus::new(
+                    RpcStatusCode::Unavailable,
+                    Some("Consensus is not running in this node".to_string()),
+                ))
+                .map_err(default_reply_error_logger),
+            ),
+        }
+    }
 }
 
 fn default_reply_error_logger<T: ::std::fmt::Debug>(e: T) {
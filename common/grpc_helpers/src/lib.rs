@@ -3,7 +3,7 @@
 
 use failure::{prelude::*, Result};
 use futures::{compat::Future01CompatExt, future::Future, prelude::*};
-use futures_01::future::Future as Future01;
+use futures_01::{future::Future as Future01, sink::Sink as Sink01};
 use grpcio::{ChannelBuilder, EnvBuilder, ServerBuilder};
 use logger::prelude::*;
 use metrics::counters::SVC_COUNTERS;
@@ -53,6 +53,46 @@ pub fn provide_grpc_response<ResponseType: std::fmt::Debug>(
     SVC_COUNTERS.resp(&ctx, success);
 }
 
+/// This is a helper method to stream responses to the GRPC context of a server streaming call.
+/// Each item of the stream is sent as one response. An error from the stream fails the call.
+/// It's also logging any errors and incrementing relevant counters.
+pub fn provide_grpc_stream_response<ResponseType, S>(
+    resp: Result<S>,
+    ctx: ::grpcio::RpcContext<'_>,
+    sink: ::grpcio::ServerStreamingSink<ResponseType>,
+) where
+    ResponseType: std::fmt::Debug + Send + 'static,
+    S: Stream<Item = Result<ResponseType>> + Send + Unpin + 'static,
+{
+    let method = from_utf8(ctx.method())
+        .expect("Unable to convert function name to string")
+        .to_string();
+    let mut success = true;
+    match resp {
+        Ok(stream) => {
+            let stream = stream
+                .map_ok(|resp| (resp, ::grpcio::WriteFlags::default()))
+                .map_err(move |e| {
+                    ::grpcio::Error::RpcFailure(create_grpc_invalid_arg_status(&method, e))
+                })
+                .compat();
+            let f = sink
+                .send_all(stream)
+                .map(|_| ())
+                .map_err(default_reply_error_logger);
+            ctx.spawn(f)
+        }
+        Err(e) => {
+            success = false;
+            let f = sink
+                .fail(create_grpc_invalid_arg_status(&method, e))
+                .map_err(default_reply_error_logger);
+            ctx.spawn(f)
+        }
+    }
+    SVC_COUNTERS.resp(&ctx, success);
+}
+
 pub fn spawn_service_thread(
     service: ::grpcio::Service,
     service_host_address: String,
@@ -21,6 +21,9 @@ pub enum SecurityEvent {
     /// Executor received an invalid transactions chunk
     InvalidChunkExecutor,
 
+    /// Executor received an invalid state snapshot chunk
+    InvalidStateSnapshotChunkExecutor,
+
     /// Mempool received an invalid network event
     InvalidNetworkEventMP,
 
@@ -39,6 +42,9 @@ pub enum SecurityEvent {
     /// Consensus received an invalid sync info message
     InvalidSyncInfoMsg,
 
+    /// Consensus received an invalid batch of transactions
+    InvalidTxnBatch,
+
     /// A block being committed or executed is invalid
     InvalidBlock,
 
@@ -81,6 +81,7 @@ impl SwarmConfig {
                 log_collector: template.log_collector.clone(),
                 vm_config: template.vm_config.clone(),
                 secret_service: template.secret_service.clone(),
+                safety_rules: template.safety_rules.clone(),
             };
 
             config.base.peer_id = node_id.clone();
@@ -15,6 +15,8 @@ use logger::LoggerType;
 use nextgen_crypto::{
     ed25519::*,
     test_utils::TEST_SEED,
+    traits::Uniform,
+    vrf::ecvrf::{VRFPrivateKey, VRFPublicKey},
     x25519::{self, X25519StaticPrivateKey, X25519StaticPublicKey},
 };
 use rand::{rngs::StdRng, SeedableRng};
@@ -27,7 +29,9 @@ use proto_conv::FromProtoBytes;
 use types::transaction::{SignedTransaction, SCRIPT_HASH_LENGTH};
 
 use crate::{
-    config::ConsensusProposerType::{FixedProposer, RotatingProposer},
+    config::ConsensusProposerType::{
+        FixedProposer, ReputationProposer, RotatingProposer, VrfProposer,
+    },
     seed_peers::{SeedPeersConfig, SeedPeersConfigHelpers},
     trusted_peers::{
         deserialize_key, deserialize_opt_key, serialize_key, serialize_opt_key,
@@ -81,6 +85,9 @@ pub struct NodeConfig {
 
     #[serde(default)]
     pub secret_service: SecretServiceConfig,
+
+    #[serde(default)]
+    pub safety_rules: SafetyRulesConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -160,6 +167,16 @@ pub struct KeyPairs {
     #[serde(serialize_with = "serialize_key")]
     #[serde(deserialize_with = "deserialize_key")]
     consensus_public_key: Ed25519PublicKey,
+
+    // The VRF keys are only required by the VRF proposer election.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_opt_key")]
+    #[serde(deserialize_with = "deserialize_opt_key")]
+    vrf_private_key: Option<VRFPrivateKey>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_opt_key")]
+    #[serde(deserialize_with = "deserialize_opt_key")]
+    vrf_public_key: Option<VRFPublicKey>,
 }
 
 // required for serialization
@@ -169,6 +186,8 @@ impl Default for KeyPairs {
         let (net_private_sig, net_public_sig) = compat::generate_keypair(&mut rng);
         let (consensus_private_sig, consensus_public_sig) = compat::generate_keypair(&mut rng);
         let (private_kex, public_kex) = x25519::compat::generate_keypair(&mut rng);
+        let vrf_private_key = VRFPrivateKey::generate_for_testing(&mut rng);
+        let vrf_public_key = (&vrf_private_key).into();
         Self {
             network_signing_private_key: Some(net_private_sig),
             network_signing_public_key: net_public_sig,
@@ -176,6 +195,8 @@ impl Default for KeyPairs {
             network_identity_public_key: public_kex,
             consensus_private_key: Some(consensus_private_sig),
             consensus_public_key: consensus_public_sig,
+            vrf_private_key: Some(vrf_private_key),
+            vrf_public_key: Some(vrf_public_key),
         }
     }
 }
@@ -206,11 +227,16 @@ impl KeyPairs {
 
     // used in testing to fill the structure with test keypairs
     pub fn load(private_keys: TrustedPeerPrivateKeys) -> Self {
-        let (network_signing_private_key, network_identity_private_key, consensus_private_key) =
-            private_keys.get_key_triplet();
+        let (
+            network_signing_private_key,
+            network_identity_private_key,
+            consensus_private_key,
+            vrf_private_key,
+        ) = private_keys.get_keys();
         let network_signing_public_key = (&network_signing_private_key).into();
         let network_identity_public_key = (&network_identity_private_key).into();
         let consensus_public_key = (&consensus_private_key).into();
+        let vrf_public_key = (&vrf_private_key).into();
         Self {
             network_signing_private_key: Some(network_signing_private_key),
             network_signing_public_key,
@@ -218,6 +244,8 @@ impl KeyPairs {
             network_identity_public_key,
             consensus_private_key: Some(consensus_private_key),
             consensus_public_key,
+            vrf_private_key: Some(vrf_private_key),
+            vrf_public_key: Some(vrf_public_key),
         }
     }
     // getters for private keys
@@ -239,6 +267,11 @@ impl KeyPairs {
     pub fn take_consensus_private(&mut self) -> Option<Ed25519PrivateKey> {
         std::mem::replace(&mut self.consensus_private_key, None)
     }
+
+    /// Beware, this destroys the private key from this NodeConfig
+    pub fn take_vrf_private(&mut self) -> Option<VRFPrivateKey> {
+        std::mem::replace(&mut self.vrf_private_key, None)
+    }
     // getters for public keys
     pub fn get_network_signing_public(&self) -> &Ed25519PublicKey {
         &self.network_signing_public_key
@@ -249,6 +282,9 @@ impl KeyPairs {
     pub fn get_consensus_public(&self) -> &Ed25519PublicKey {
         &self.consensus_public_key
     }
+    pub fn get_vrf_public(&self) -> &Option<VRFPublicKey> {
+        &self.vrf_public_key
+    }
     // getters for keypairs
     pub fn get_network_identity_keypair(&self) -> (X25519StaticPrivateKey, X25519StaticPublicKey) {
         (
@@ -449,6 +485,51 @@ impl Default for AdmissionControlConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SafetyRulesConfig {
+    // Where the consensus safety rules run: in the consensus process ("local") or in a separate
+    // process reached on address:safety_rules_port ("remote")
+    pub backend: String,
+    pub address: String,
+    pub safety_rules_port: u16,
+    // Remote safety rules only: storage of the consensus state (relative to the data directory)
+    pub storage_dir: PathBuf,
+    // Remote safety rules only: hex encoded id of the consensus key in the secret service, a new
+    // key is generated if empty
+    pub consensus_key_id: String,
+}
+
+impl Default for SafetyRulesConfig {
+    fn default() -> SafetyRulesConfig {
+        SafetyRulesConfig {
+            backend: "local".to_string(),
+            address: "localhost".to_string(),
+            safety_rules_port: 6187,
+            storage_dir: PathBuf::from("safety_rules_db"),
+            consensus_key_id: "".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SafetyRulesBackend {
+    // The safety rules run in the consensus process
+    Local,
+    // The safety rules run in a separate process
+    Remote,
+}
+
+impl SafetyRulesConfig {
+    pub fn get_backend(&self) -> SafetyRulesBackend {
+        match self.backend.as_str() {
+            "local" => SafetyRulesBackend::Local,
+            "remote" => SafetyRulesBackend::Remote,
+            &_ => unimplemented!("Invalid safety rules backend: {}", self.backend),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(default)]
 pub struct DebugInterfaceConfig {
@@ -479,6 +560,7 @@ pub struct StorageConfig {
     pub port: u16,
     pub dir: PathBuf,
     pub grpc_max_receive_len: Option<i32>,
+    pub storage_mode: StorageMode,
 }
 
 impl StorageConfig {
@@ -494,10 +576,29 @@ impl Default for StorageConfig {
             port: 6184,
             dir: PathBuf::from("libradb"),
             grpc_max_receive_len: Some(100_000_000),
+            storage_mode: StorageMode::default(),
         }
     }
 }
 
+/// How much of the history of the ledger the storage keeps. The full history is kept unless
+/// pruning is configured explicitly.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", content = "window")]
+pub enum StorageMode {
+    /// Keep the full history.
+    Archive,
+    /// Other than the latest version, keep this many historical versions readable. The state,
+    /// transactions and events of older versions are pruned.
+    Pruned(u64),
+}
+
+impl Default for StorageMode {
+    fn default() -> StorageMode {
+        StorageMode::Archive
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(default)]
 pub struct NetworkConfig {
@@ -536,6 +637,18 @@ pub struct ConsensusConfig {
     contiguous_rounds: u32,
     max_pruned_blocks_in_mem: Option<u64>,
     pacemaker_initial_timeout_ms: Option<u64>,
+    // Vote on the ordering of the blocks and execute them in the background: the execution
+    // results are certified by the ledger infos of the following rounds. The execution mode is
+    // shared by the validator set: all the validators must set it alike, the votes that don't
+    // follow it are rejected.
+    pipelined_execution: bool,
+    // Disseminate the transactions in batches ahead of the proposals, which only carry the
+    // digests of the batches.
+    batch_dissemination: bool,
+    // Maximum number of transactions per batch
+    max_batch_size: u64,
+    // How often a validator pulls a new batch of transactions from its mempool
+    batch_interval_ms: u64,
 }
 
 impl Default for ConsensusConfig {
@@ -546,16 +659,24 @@ impl Default for ConsensusConfig {
             contiguous_rounds: 2,
             max_pruned_blocks_in_mem: None,
             pacemaker_initial_timeout_ms: None,
+            pipelined_execution: false,
+            batch_dissemination: false,
+            max_batch_size: 50,
+            batch_interval_ms: 100,
         }
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ConsensusProposerType {
     // Choose the smallest PeerId as the proposer
     FixedProposer,
     // Round robin rotation of proposers
     RotatingProposer,
+    // Proposers weighted by their recent activity in the committed blocks
+    ReputationProposer,
+    // Unpredictable proposers chosen by the VRF output of a recent certified block
+    VrfProposer,
 }
 
 impl ConsensusConfig {
@@ -563,6 +684,8 @@ impl ConsensusConfig {
         match self.proposer_type.as_str() {
             "fixed_proposer" => FixedProposer,
             "rotating_proposer" => RotatingProposer,
+            "reputation_proposer" => ReputationProposer,
+            "vrf_proposer" => VrfProposer,
             &_ => unimplemented!("Invalid proposer type: {}", self.proposer_type),
         }
     }
@@ -582,6 +705,22 @@ impl ConsensusConfig {
     pub fn pacemaker_initial_timeout_ms(&self) -> &Option<u64> {
         &self.pacemaker_initial_timeout_ms
     }
+
+    pub fn pipelined_execution(&self) -> bool {
+        self.pipelined_execution
+    }
+
+    pub fn batch_dissemination(&self) -> bool {
+        self.batch_dissemination
+    }
+
+    pub fn max_batch_size(&self) -> u64 {
+        self.max_batch_size
+    }
+
+    pub fn batch_interval_ms(&self) -> u64 {
+        self.batch_interval_ms
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -595,6 +734,12 @@ pub struct MempoolConfig {
     // max number of transactions per user in Mempool
     pub capacity_per_user: usize,
     pub sequence_cache_capacity: usize,
+    // max number of removed transactions whose removal reason (e.g. expired) is remembered
+    pub removed_transaction_cache_capacity: usize,
+    // number of recently committed txns whose gas prices are used for gas price estimation
+    pub gas_price_history_size: usize,
+    // persist accepted transactions on disk, so they are restored after node restart
+    pub persist_transactions: bool,
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
     pub mempool_service_port: u16,
@@ -611,6 +756,9 @@ impl Default for MempoolConfig {
             capacity: 10_000_000,
             capacity_per_user: 100,
             sequence_cache_capacity: 1000,
+            removed_transaction_cache_capacity: 10_000,
+            gas_price_history_size: 1000,
+            persist_transactions: false,
             system_transaction_timeout_secs: 86400,
             address: "localhost".to_string(),
             mempool_service_port: 6182,
@@ -624,6 +772,11 @@ impl Default for MempoolConfig {
 pub struct StateSyncConfig {
     pub address: String,
     pub service_port: u16,
+    // If set, a node that has nothing but genesis bootstraps by downloading the account states
+    // at the target version instead of replaying all the transactions since genesis.
+    pub enable_snapshot_sync: bool,
+    // Max number of account states to request in one chunk when syncing a state snapshot.
+    pub snapshot_chunk_size: u64,
 }
 
 impl Default for StateSyncConfig {
@@ -631,6 +784,8 @@ impl Default for StateSyncConfig {
         Self {
             address: "localhost".to_string(),
             service_port: 6186,
+            enable_snapshot_sync: false,
+            snapshot_chunk_size: 1000,
         }
     }
 }
@@ -825,6 +980,7 @@ impl NodeConfigHelpers {
         config.network.listen_address = randomize_tcp_port(&config.network.listen_address);
         config.state_sync.service_port = get_available_port();
         config.secret_service.secret_service_port = get_available_port();
+        config.safety_rules.safety_rules_port = get_available_port();
         config.storage.port = get_available_port();
     }
 }
@@ -3,7 +3,8 @@
 
 use nextgen_crypto::{
     ed25519::{compat, *},
-    traits::ValidKeyStringExt,
+    traits::{Uniform, ValidKeyStringExt},
+    vrf::ecvrf::{VRFPrivateKey, VRFPublicKey},
     x25519::{self, X25519StaticPrivateKey, X25519StaticPublicKey},
 };
 use rand::{rngs::StdRng, SeedableRng};
@@ -35,20 +36,34 @@ pub struct TrustedPeer {
     #[serde(deserialize_with = "deserialize_key")]
     #[serde(rename = "c")]
     consensus_pubkey: Ed25519PublicKey,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_opt_key")]
+    #[serde(deserialize_with = "deserialize_opt_key")]
+    #[serde(rename = "v")]
+    vrf_pubkey: Option<VRFPublicKey>,
 }
 
 pub struct TrustedPeerPrivateKeys {
     network_signing_private_key: Ed25519PrivateKey,
     network_identity_private_key: X25519StaticPrivateKey,
     consensus_private_key: Ed25519PrivateKey,
+    vrf_private_key: VRFPrivateKey,
 }
 
 impl TrustedPeerPrivateKeys {
-    pub fn get_key_triplet(self) -> (Ed25519PrivateKey, X25519StaticPrivateKey, Ed25519PrivateKey) {
+    pub fn get_keys(
+        self,
+    ) -> (
+        Ed25519PrivateKey,
+        X25519StaticPrivateKey,
+        Ed25519PrivateKey,
+        VRFPrivateKey,
+    ) {
         (
             self.network_signing_private_key,
             self.network_identity_private_key,
             self.consensus_private_key,
+            self.vrf_private_key,
         )
     }
 }
@@ -63,6 +78,9 @@ impl TrustedPeer {
     pub fn get_consensus_public(&self) -> &Ed25519PublicKey {
         &self.consensus_pubkey
     }
+    pub fn get_vrf_public(&self) -> &Option<VRFPublicKey> {
+        &self.vrf_pubkey
+    }
 }
 
 pub fn serialize_key<S, K>(key: &K, serializer: S) -> Result<S::Ok, S::Error>
@@ -169,6 +187,20 @@ impl TrustedPeersConfig {
         res
     }
 
+    /// Returns a map of AccountAddress to its VRF PublicKey, for the peers that have one.
+    pub fn get_trusted_vrf_peers(&self) -> HashMap<AccountAddress, VRFPublicKey> {
+        self.peers
+            .iter()
+            .filter_map(|(account, keys)| {
+                Some((
+                    AccountAddress::try_from(account.clone())
+                        .expect("Failed to parse account addr"),
+                    keys.vrf_pubkey.clone()?,
+                ))
+            })
+            .collect()
+    }
+
     /// Returns a map of AccountAddress to a pair of PublicKeys for network peering. The first
     /// PublicKey is the one used for signing, whereas the second is to determine eligible members
     /// of the network.
@@ -226,11 +258,14 @@ impl TrustedPeersConfigHelpers {
             let (private0, public0) = compat::generate_keypair(&mut fast_rng);
             let (private1, public1) = x25519::compat::generate_keypair(&mut fast_rng);
             let (private2, public2) = compat::generate_keypair(&mut fast_rng);
+            let private3 = VRFPrivateKey::generate_for_testing(&mut fast_rng);
+            let public3 = (&private3).into();
             // save the public_key in peers hashmap
             let peer = TrustedPeer {
                 network_signing_pubkey: public0,
                 network_identity_pubkey: public1,
                 consensus_pubkey: public2,
+                vrf_pubkey: Some(public3),
             };
             let peer_id = AccountAddress::from_public_key(&peer.consensus_pubkey);
             peers.insert(peer_id.to_string(), peer);
@@ -239,6 +274,7 @@ impl TrustedPeersConfigHelpers {
                 network_signing_private_key: private0,
                 network_identity_private_key: private1,
                 consensus_private_key: private2,
+                vrf_private_key: private3,
             };
             peers_private_keys.insert(peer_id.to_string(), private_keys);
         }
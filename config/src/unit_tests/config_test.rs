@@ -26,3 +26,29 @@ fn verify_all_configs() {
         }
     }
 }
+
+#[test]
+fn test_storage_mode() {
+    let config: StorageConfig = toml::from_str("").unwrap();
+    assert_eq!(config.storage_mode, StorageMode::default());
+    assert_eq!(config.storage_mode, StorageMode::Archive);
+
+    let config: StorageConfig = toml::from_str(
+        r#"
+        [storage_mode]
+        type = "Archive"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.storage_mode, StorageMode::Archive);
+
+    let config: StorageConfig = toml::from_str(
+        r#"
+        [storage_mode]
+        type = "Pruned"
+        window = 100
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.storage_mode, StorageMode::Pruned(100));
+}
@@ -0,0 +1,690 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dissemination of the transactions ahead of the proposals.
+//!
+//! Instead of embedding the transactions in its proposals, every validator regularly pulls a batch
+//! of transactions from its mempool and broadcasts it to the other validators. The blocks only
+//! refer to the batches they include by author and digest, so that the upload bandwidth of the
+//! proposer no longer limits the size of the blocks. A validator fetches the batches it misses
+//! before voting for a proposal, and drops the batches once they are committed. The batches are
+//! persisted: the pending blocks that refer to them are executed again after a restart.
+
+use crate::{
+    chained_bft::{network::ConsensusNetworkImpl, QuorumCert},
+    counters,
+    state_replication::{StateComputeResult, StateComputer, TxnManager},
+    util::time_service::TimeService,
+};
+use canonical_serialization::{
+    CanonicalDeserialize, CanonicalDeserializer, CanonicalSerialize, CanonicalSerializer,
+};
+use crypto::{
+    hash::{CryptoHash, CryptoHasher, TransactionBatchHasher},
+    HashValue,
+};
+use failure::prelude::*;
+use futures::{future, Future, FutureExt};
+use logger::prelude::*;
+use nextgen_crypto::ed25519::*;
+use proto_conv::{FromProto, IntoProto};
+use serde::{Deserialize, Serialize};
+use state_synchronizer::SyncStatus;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    convert::TryFrom,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use types::{
+    account_address::AccountAddress,
+    account_state_blob::AccountStateRangeWithProof,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{SignedTransaction, TransactionListWithProof},
+};
+
+#[cfg(test)]
+#[path = "batch_manager_test.rs"]
+mod batch_manager_test;
+
+/// The batches of an author that are not committed yet, beyond which its new batches are dropped
+/// unless a block refers to them.
+pub(crate) const MAX_BATCHES_PER_AUTHOR: usize = 100;
+/// Timeout of the requests fetching the missing batches from a peer.
+const BATCH_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A batch of transactions pulled by a validator from its mempool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TxnBatch {
+    author: AccountAddress,
+    transactions: Vec<SignedTransaction>,
+    /// Computed from the author and the transactions: a batch received from the network can't
+    /// claim another digest.
+    digest: HashValue,
+}
+
+impl TxnBatch {
+    pub fn new(author: AccountAddress, transactions: Vec<SignedTransaction>) -> Self {
+        let mut state = TransactionBatchHasher::default();
+        state.write(author.as_ref());
+        for txn in &transactions {
+            state.write(txn.hash().as_ref());
+        }
+        Self {
+            author,
+            transactions,
+            digest: state.finish(),
+        }
+    }
+
+    pub fn author(&self) -> AccountAddress {
+        self.author
+    }
+
+    pub fn transactions(&self) -> &Vec<SignedTransaction> {
+        &self.transactions
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.digest
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    pub fn batch_ref(&self) -> BatchRef {
+        BatchRef {
+            author: self.author,
+            digest: self.digest,
+        }
+    }
+}
+
+impl FromProto for TxnBatch {
+    type ProtoType = network::proto::TxnBatch;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let author = AccountAddress::try_from(object.get_author())?;
+        let transactions = object
+            .take_transactions()
+            .into_iter()
+            .map(SignedTransaction::from_proto)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(author, transactions))
+    }
+}
+
+impl IntoProto for TxnBatch {
+    type ProtoType = network::proto::TxnBatch;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_author(self.author.into());
+        proto.set_transactions(
+            self.transactions
+                .into_iter()
+                .map(IntoProto::into_proto)
+                .collect(),
+        );
+        proto
+    }
+}
+
+/// Refers to a batch of transactions disseminated by its author.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BatchRef {
+    author: AccountAddress,
+    digest: HashValue,
+}
+
+impl BatchRef {
+    pub fn author(&self) -> AccountAddress {
+        self.author
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.digest
+    }
+}
+
+impl CanonicalSerialize for BatchRef {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        serializer
+            .encode_struct(&self.author)?
+            .encode_raw_bytes(self.digest.as_ref())?;
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for BatchRef {
+    fn deserialize(deserializer: &mut impl CanonicalDeserializer) -> Result<Self> {
+        let author = deserializer.decode_struct()?;
+        let digest =
+            HashValue::from_slice(&deserializer.decode_bytes_with_len(HashValue::LENGTH as u32)?)?;
+        Ok(Self { author, digest })
+    }
+}
+
+/// The payload of the blocks in the batch dissemination mode: the batches of transactions the
+/// block includes, which are executed in order.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BatchPayload {
+    batches: Vec<BatchRef>,
+}
+
+impl BatchPayload {
+    pub fn new(batches: Vec<BatchRef>) -> Self {
+        Self { batches }
+    }
+
+    pub fn batches(&self) -> &[BatchRef] {
+        &self.batches
+    }
+}
+
+impl CanonicalSerialize for BatchPayload {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        serializer.encode_vec(&self.batches)?;
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for BatchPayload {
+    fn deserialize(deserializer: &mut impl CanonicalDeserializer) -> Result<Self> {
+        Ok(Self {
+            batches: deserializer.decode_vec()?,
+        })
+    }
+}
+
+/// Persists the batches, so that the pending blocks referring to them can be executed again after
+/// a restart.
+pub trait PersistentBatchStorage: Send + Sync {
+    fn save_batch(&self, batch: &TxnBatch) -> Result<()>;
+
+    fn delete_batches(&self, digests: Vec<HashValue>) -> Result<()>;
+
+    fn get_batches(&self) -> Result<Vec<TxnBatch>>;
+}
+
+#[derive(Default)]
+struct BatchStoreInner {
+    batches: HashMap<HashValue, Arc<TxnBatch>>,
+    // The digests of the batches in the order of their arrival, which is the order in which the
+    // proposers include them. The batches of the blocks pruned without being committed are still
+    // pending, so they are proposed again.
+    arrival_order: VecDeque<HashValue>,
+    num_batches_per_author: HashMap<AccountAddress, usize>,
+    // The batches of the last committed block. This validator commits a block as soon as it holds
+    // the certificate of its 3-chain, the other validators only once they receive it: meanwhile
+    // they may still need to fetch the batches to execute the block.
+    committed: HashMap<HashValue, Arc<TxnBatch>>,
+}
+
+/// Keeps the batches of transactions disseminated by the validators (including this one) until
+/// they are committed.
+#[derive(Default)]
+pub struct BatchStore {
+    inner: Mutex<BatchStoreInner>,
+    storage: Option<Arc<dyn PersistentBatchStorage>>,
+}
+
+impl BatchStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persists the batches in the given storage, and restores the batches persisted before.
+    pub fn new_with_storage(storage: Arc<dyn PersistentBatchStorage>) -> Result<Self> {
+        let batches = storage.get_batches()?;
+        let store = Self {
+            inner: Mutex::new(BatchStoreInner::default()),
+            storage: Some(storage),
+        };
+        {
+            let mut inner = store.inner.lock().unwrap();
+            for batch in batches {
+                Self::insert_inner(&mut inner, batch);
+            }
+            info!("Restored {} batches", inner.batches.len());
+        }
+        Ok(store)
+    }
+
+    /// Returns false if the batch is already known, or if its author has too many batches
+    /// pending already.
+    pub fn insert(&self, batch: TxnBatch) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let num_batches = inner
+            .num_batches_per_author
+            .get(&batch.author())
+            .cloned()
+            .unwrap_or(0);
+        if num_batches >= MAX_BATCHES_PER_AUTHOR {
+            debug!(
+                "Dropping batch {}: {} has too many pending batches",
+                batch.digest(),
+                batch.author()
+            );
+            return false;
+        }
+        self.insert_and_persist(&mut inner, batch)
+    }
+
+    /// Inserts a batch a block refers to, whatever the number of pending batches of its author.
+    pub fn insert_referenced(&self, batch: TxnBatch) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        self.insert_and_persist(&mut inner, batch)
+    }
+
+    fn insert_and_persist(&self, inner: &mut BatchStoreInner, batch: TxnBatch) -> bool {
+        if inner.batches.contains_key(&batch.digest())
+            || inner.committed.contains_key(&batch.digest())
+        {
+            return false;
+        }
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.save_batch(&batch) {
+                error!("Failed to persist batch {}: {:?}", batch.digest(), e);
+            }
+        }
+        Self::insert_inner(inner, batch)
+    }
+
+    fn insert_inner(inner: &mut BatchStoreInner, batch: TxnBatch) -> bool {
+        let digest = batch.digest();
+        if inner.batches.contains_key(&digest) {
+            return false;
+        }
+        *inner
+            .num_batches_per_author
+            .entry(batch.author())
+            .or_insert(0) += 1;
+        inner.batches.insert(digest, Arc::new(batch));
+        inner.arrival_order.push_back(digest);
+        true
+    }
+
+    /// Returns the batch with the given digest, pending or committed by the last block.
+    pub fn get(&self, digest: &HashValue) -> Option<Arc<TxnBatch>> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .batches
+            .get(digest)
+            .or_else(|| inner.committed.get(digest))
+            .map(Arc::clone)
+    }
+
+    /// Returns the batches of the given payload that are not known.
+    pub fn missing(&self, payload: &BatchPayload) -> Vec<BatchRef> {
+        let inner = self.inner.lock().unwrap();
+        payload
+            .batches()
+            .iter()
+            .filter(|batch_ref| {
+                !inner.batches.contains_key(&batch_ref.digest())
+                    && !inner.committed.contains_key(&batch_ref.digest())
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns all the pending batches in the order of their arrival.
+    pub fn batches(&self) -> Vec<Arc<TxnBatch>> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .arrival_order
+            .iter()
+            .filter_map(|digest| inner.batches.get(digest))
+            .map(Arc::clone)
+            .collect()
+    }
+
+    /// The number of batches of the given author that are not committed yet
+    pub fn num_batches_of(&self, author: AccountAddress) -> usize {
+        self.inner
+            .lock()
+            .unwrap()
+            .num_batches_per_author
+            .get(&author)
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// Drops the given pending batches and returns them.
+    pub fn remove(&self, digests: &HashSet<HashValue>) -> Vec<Arc<TxnBatch>> {
+        let mut inner = self.inner.lock().unwrap();
+        let removed: Vec<_> = digests
+            .iter()
+            .filter_map(|digest| inner.batches.remove(digest))
+            .collect();
+        if removed.is_empty() {
+            return removed;
+        }
+        for batch in &removed {
+            let author = batch.author();
+            if let Some(num_batches) = inner.num_batches_per_author.get_mut(&author) {
+                *num_batches -= 1;
+                if *num_batches == 0 {
+                    inner.num_batches_per_author.remove(&author);
+                }
+            }
+        }
+        inner
+            .arrival_order
+            .retain(|digest| !digests.contains(digest));
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.delete_batches(removed.iter().map(|b| b.digest()).collect()) {
+                error!("Failed to delete {} batches: {:?}", removed.len(), e);
+            }
+        }
+        removed
+    }
+
+    /// Drops the batches of a committed block, which are still served until the next block is
+    /// committed, and the ones of the block committed before.
+    pub fn commit(&self, digests: &HashSet<HashValue>) {
+        let committed = self
+            .remove(digests)
+            .into_iter()
+            .map(|batch| (batch.digest(), batch))
+            .collect();
+        self.inner.lock().unwrap().committed = committed;
+    }
+}
+
+/// Resolves the payloads of the blocks into transactions, fetching the missing batches from the
+/// other validators.
+pub struct BatchManager {
+    store: Arc<BatchStore>,
+    network: ConsensusNetworkImpl,
+}
+
+impl BatchManager {
+    pub fn new(store: Arc<BatchStore>, network: ConsensusNetworkImpl) -> Self {
+        Self { store, network }
+    }
+
+    pub fn store(&self) -> &Arc<BatchStore> {
+        &self.store
+    }
+
+    /// Fetches the batches of the payload that are not known yet: each author keeps its batches
+    /// until they are committed, the given peer (typically the proposer) is asked last.
+    pub async fn fetch_missing(
+        &self,
+        payload: &BatchPayload,
+        peer: Option<AccountAddress>,
+    ) -> Result<()> {
+        let mut missing = self.store.missing(payload);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let mut sources = vec![];
+        for source in missing.iter().map(BatchRef::author).chain(peer.into_iter()) {
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+        for source in sources {
+            let digests: Vec<_> = missing.iter().map(BatchRef::digest).collect();
+            match self
+                .network
+                .request_batches(digests.clone(), source, BATCH_FETCH_TIMEOUT)
+                .await
+            {
+                Ok(batches) => {
+                    for batch in batches {
+                        if digests.contains(&batch.digest()) && self.store.insert_referenced(batch)
+                        {
+                            counters::FETCHED_BATCHES_COUNT.inc();
+                        }
+                    }
+                }
+                Err(e) => warn!("Failed to fetch batches from {}: {:?}", source, e),
+            }
+            missing = self.store.missing(payload);
+            if missing.is_empty() {
+                return Ok(());
+            }
+        }
+        bail!("Failed to fetch {} batches", missing.len())
+    }
+
+    /// Returns the transactions of the batches of the payload, in order.
+    pub fn transactions(&self, payload: &BatchPayload) -> Result<Vec<SignedTransaction>> {
+        let mut transactions = vec![];
+        for batch_ref in payload.batches() {
+            let batch = self
+                .store
+                .get(&batch_ref.digest())
+                .ok_or_else(|| format_err!("Missing batch {}", batch_ref.digest()))?;
+            transactions.extend(batch.transactions().iter().cloned());
+        }
+        Ok(transactions)
+    }
+}
+
+/// Regularly pulls a batch of transactions from the mempool and disseminates it to the other
+/// validators.
+pub struct BatchGenerator {
+    author: AccountAddress,
+    store: Arc<BatchStore>,
+    mempool: Arc<dyn TxnManager<Payload = Vec<SignedTransaction>>>,
+    network: ConsensusNetworkImpl,
+    time_service: Arc<dyn TimeService>,
+    max_batch_size: u64,
+    interval: Duration,
+}
+
+impl BatchGenerator {
+    pub fn new(
+        author: AccountAddress,
+        store: Arc<BatchStore>,
+        mempool: Arc<dyn TxnManager<Payload = Vec<SignedTransaction>>>,
+        network: ConsensusNetworkImpl,
+        time_service: Arc<dyn TimeService>,
+        max_batch_size: u64,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            author,
+            store,
+            mempool,
+            network,
+            time_service,
+            max_batch_size,
+            interval,
+        }
+    }
+
+    pub async fn run(self) {
+        loop {
+            self.time_service.sleep(self.interval).await;
+            if let Err(e) = self.generate_batch().await {
+                warn!("Failed to generate a batch: {:?}", e);
+            }
+        }
+    }
+
+    /// Pulls the transactions that are not batched yet and broadcasts them, if any.
+    async fn generate_batch(&self) -> Result<Option<TxnBatch>> {
+        if self.store.num_batches_of(self.author) >= MAX_BATCHES_PER_AUTHOR {
+            return Ok(None);
+        }
+        let pending_batches = self.store.batches();
+        let transactions = self
+            .mempool
+            .pull_txns(
+                self.max_batch_size,
+                pending_batches
+                    .iter()
+                    .map(|batch| batch.transactions())
+                    .collect(),
+            )
+            .await?;
+        if transactions.is_empty() {
+            return Ok(None);
+        }
+        let batch = TxnBatch::new(self.author, transactions);
+        counters::NUM_TXNS_PER_BATCH.observe(batch.len() as f64);
+        self.store.insert(batch.clone());
+        self.network.broadcast_batch(batch.clone()).await;
+        Ok(Some(batch))
+    }
+}
+
+/// Proposes the batches disseminated ahead of the proposals instead of pulling the transactions
+/// from the mempool.
+pub struct BatchTxnManager {
+    manager: Arc<BatchManager>,
+    mempool: Arc<dyn TxnManager<Payload = Vec<SignedTransaction>>>,
+}
+
+impl BatchTxnManager {
+    pub fn new(
+        manager: Arc<BatchManager>,
+        mempool: Arc<dyn TxnManager<Payload = Vec<SignedTransaction>>>,
+    ) -> Self {
+        Self { manager, mempool }
+    }
+}
+
+impl TxnManager for BatchTxnManager {
+    type Payload = BatchPayload;
+
+    /// The returned future is fulfilled with the oldest batches that are not pending in the
+    /// branch, up to `max_size` transactions.
+    fn pull_txns(
+        &self,
+        max_size: u64,
+        exclude_payloads: Vec<&Self::Payload>,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Payload>> + Send>> {
+        let exclude_digests: HashSet<_> = exclude_payloads
+            .into_iter()
+            .flat_map(|payload| payload.batches().iter().map(BatchRef::digest))
+            .collect();
+        let mut size = 0;
+        let mut batches = vec![];
+        for batch in self.manager.store().batches() {
+            if exclude_digests.contains(&batch.digest()) || size + batch.len() as u64 > max_size {
+                continue;
+            }
+            size += batch.len() as u64;
+            batches.push(batch.batch_ref());
+        }
+        future::ready(Ok(BatchPayload::new(batches))).boxed()
+    }
+
+    fn commit_txns<'a>(
+        &'a self,
+        payload: &Self::Payload,
+        compute_result: &StateComputeResult,
+        // Monotonic timestamp_usecs of committed blocks is used to GC expired transactions.
+        timestamp_usecs: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        let txns = match self.manager.transactions(payload) {
+            Ok(txns) => txns,
+            Err(e) => return future::ready(Err(e)).boxed(),
+        };
+        self.manager.store().commit(
+            &payload
+                .batches()
+                .iter()
+                .map(BatchRef::digest)
+                .collect::<HashSet<_>>(),
+        );
+        self.mempool
+            .commit_txns(&txns, compute_result, timestamp_usecs)
+    }
+
+    fn fetch_payload<'a>(
+        &'a self,
+        payload: &'a Self::Payload,
+        author: AccountAddress,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        self.manager.fetch_missing(payload, Some(author)).boxed()
+    }
+}
+
+/// Executes the transactions of the batches the blocks refer to.
+pub struct BatchStateComputer {
+    manager: Arc<BatchManager>,
+    executor: Arc<dyn StateComputer<Payload = Vec<SignedTransaction>>>,
+}
+
+impl BatchStateComputer {
+    pub fn new(
+        manager: Arc<BatchManager>,
+        executor: Arc<dyn StateComputer<Payload = Vec<SignedTransaction>>>,
+    ) -> Self {
+        Self { manager, executor }
+    }
+}
+
+impl StateComputer for BatchStateComputer {
+    type Payload = BatchPayload;
+
+    fn compute(
+        &self,
+        parent_block_id: HashValue,
+        block_id: HashValue,
+        author: Option<AccountAddress>,
+        payload: &Self::Payload,
+    ) -> Pin<Box<dyn Future<Output = Result<StateComputeResult>> + Send>> {
+        let manager = Arc::clone(&self.manager);
+        let executor = Arc::clone(&self.executor);
+        let payload = payload.clone();
+        async move {
+            // The batches of the blocks retrieved while syncing up are not fetched before voting:
+            // their proposer holds them as well.
+            manager.fetch_missing(&payload, author).await?;
+            let txns = manager.transactions(&payload)?;
+            executor
+                .compute(parent_block_id, block_id, author, &txns)
+                .await
+        }
+            .boxed()
+    }
+
+    fn commit(
+        &self,
+        commit: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        self.executor.commit(commit)
+    }
+
+    fn sync_to(
+        &self,
+        commit: QuorumCert,
+    ) -> Pin<Box<dyn Future<Output = Result<SyncStatus>> + Send>> {
+        self.executor.sync_to(commit)
+    }
+
+    fn get_chunk(
+        &self,
+        start_version: u64,
+        target_version: u64,
+        batch_size: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<TransactionListWithProof>> + Send>> {
+        self.executor
+            .get_chunk(start_version, target_version, batch_size)
+    }
+
+    fn get_account_state_chunk(
+        &self,
+        version: u64,
+        start_key: HashValue,
+        limit: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateRangeWithProof, Vec<HashValue>)>> + Send>>
+    {
+        self.executor
+            .get_account_state_chunk(version, start_key, limit)
+    }
+}
@@ -0,0 +1,341 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    batch_manager::{
+        BatchGenerator, BatchManager, BatchPayload, BatchStore, BatchTxnManager,
+        PersistentBatchStorage, TxnBatch, MAX_BATCHES_PER_AUTHOR,
+    },
+    chained_bft::{epoch_manager::EpochManager, network::ConsensusNetworkImpl},
+    state_replication::{StateComputeResult, TxnManager},
+    util::time_service::ClockTimeService,
+};
+use canonical_serialization::{SimpleDeserializer, SimpleSerializer};
+use channel;
+use crypto::HashValue;
+use failure::Result;
+use futures::{executor::block_on, future, Future, FutureExt};
+use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
+use nextgen_crypto::ed25519::*;
+use proto_conv::{FromProto, IntoProto};
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::runtime::Runtime;
+use types::{
+    account_address::AccountAddress, test_helpers::transaction_test_helpers::get_test_signed_txn,
+    transaction::SignedTransaction, validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+
+fn random_txns(num_txns: usize) -> Vec<SignedTransaction> {
+    (0..num_txns)
+        .map(|_| {
+            let (private_key, public_key) = compat::generate_keypair(None);
+            SignedTransaction::from_proto(get_test_signed_txn(
+                AccountAddress::random(),
+                0,
+                private_key,
+                public_key,
+                None,
+            ))
+            .unwrap()
+        })
+        .collect()
+}
+
+/// A network of a single validator: the batches are not sent anywhere.
+fn single_validator_network(author: AccountAddress) -> ConsensusNetworkImpl {
+    let (network_reqs_tx, _) = channel::new_test(8);
+    let (_, consensus_rx) = channel::new_test(8);
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random(None);
+    ConsensusNetworkImpl::new(
+        author,
+        ConsensusNetworkSender::new(network_reqs_tx),
+        ConsensusNetworkEvents::new(consensus_rx),
+        Arc::new(EpochManager::new(
+            0,
+            Arc::new(ValidatorVerifier::new_single(author, signer.public_key())),
+        )),
+    )
+}
+
+/// Serves the given transactions, except the excluded ones, and records the committed ones.
+struct MockMempool {
+    txns: Vec<SignedTransaction>,
+    committed_txns: Mutex<Vec<SignedTransaction>>,
+}
+
+impl MockMempool {
+    fn new(txns: Vec<SignedTransaction>) -> Self {
+        Self {
+            txns,
+            committed_txns: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl TxnManager for MockMempool {
+    type Payload = Vec<SignedTransaction>;
+
+    fn pull_txns(
+        &self,
+        max_size: u64,
+        exclude_txns: Vec<&Self::Payload>,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Payload>> + Send>> {
+        let txns = self
+            .txns
+            .iter()
+            .filter(|txn| !exclude_txns.iter().any(|payload| payload.contains(txn)))
+            .take(max_size as usize)
+            .cloned()
+            .collect();
+        future::ready(Ok(txns)).boxed()
+    }
+
+    fn commit_txns<'a>(
+        &'a self,
+        txns: &Self::Payload,
+        _compute_result: &StateComputeResult,
+        _timestamp_usecs: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        self.committed_txns
+            .lock()
+            .unwrap()
+            .extend(txns.iter().cloned());
+        future::ready(Ok(())).boxed()
+    }
+}
+
+#[test]
+fn test_txn_batch_digest() {
+    let author = AccountAddress::random();
+    let txns = random_txns(3);
+    let batch = TxnBatch::new(author, txns.clone());
+    assert_eq!(batch.len(), 3);
+    assert_eq!(batch, TxnBatch::new(author, txns.clone()));
+    // The digest covers the author and the transactions.
+    assert_ne!(
+        batch.digest(),
+        TxnBatch::new(AccountAddress::random(), txns.clone()).digest()
+    );
+    assert_ne!(
+        batch.digest(),
+        TxnBatch::new(author, txns[..2].to_vec()).digest()
+    );
+    // The digest of a batch received from the network is computed locally.
+    assert_eq!(
+        TxnBatch::from_proto(batch.clone().into_proto()).unwrap(),
+        batch
+    );
+}
+
+#[test]
+fn test_batch_payload_serialization() {
+    let batches = vec![
+        TxnBatch::new(AccountAddress::random(), random_txns(1)),
+        TxnBatch::new(AccountAddress::random(), random_txns(2)),
+    ];
+    let payload = BatchPayload::new(batches.iter().map(TxnBatch::batch_ref).collect());
+    let bytes = SimpleSerializer::<Vec<u8>>::serialize(&payload).unwrap();
+    let deserialized: BatchPayload = SimpleDeserializer::deserialize(&bytes).unwrap();
+    assert_eq!(deserialized, payload);
+    assert_eq!(deserialized.batches()[1].digest(), batches[1].digest());
+}
+
+#[test]
+fn test_batch_store() {
+    let store = BatchStore::new();
+    let a = TxnBatch::new(AccountAddress::random(), random_txns(1));
+    let b = TxnBatch::new(AccountAddress::random(), random_txns(2));
+    assert!(store.insert(a.clone()));
+    assert!(!store.insert(a.clone()));
+    let payload = BatchPayload::new(vec![a.batch_ref(), b.batch_ref()]);
+    assert_eq!(store.missing(&payload), vec![b.batch_ref()]);
+
+    assert!(store.insert(b.clone()));
+    assert!(store.missing(&payload).is_empty());
+    let batches: Vec<_> = store
+        .batches()
+        .into_iter()
+        .map(|batch| batch.digest())
+        .collect();
+    assert_eq!(batches, vec![a.digest(), b.digest()]);
+
+    store.remove(&vec![a.digest()].into_iter().collect());
+    assert!(store.get(&a.digest()).is_none());
+    assert_eq!(store.missing(&payload), vec![a.batch_ref()]);
+}
+
+#[test]
+fn test_batch_store_serves_last_committed_batches() {
+    let store = BatchStore::new();
+    let a = TxnBatch::new(AccountAddress::random(), random_txns(1));
+    let b = TxnBatch::new(AccountAddress::random(), random_txns(1));
+    store.insert(a.clone());
+    store.insert(b.clone());
+
+    // The batches of a committed block are no longer pending, but the other validators can still
+    // fetch them.
+    store.commit(&vec![a.digest()].into_iter().collect());
+    assert!(store.get(&a.digest()).is_some());
+    assert!(store
+        .missing(&BatchPayload::new(vec![a.batch_ref()]))
+        .is_empty());
+    assert!(!store.insert(a.clone()));
+    let pending: Vec<_> = store
+        .batches()
+        .into_iter()
+        .map(|batch| batch.digest())
+        .collect();
+    assert_eq!(pending, vec![b.digest()]);
+    assert_eq!(store.num_batches_of(a.author()), 0);
+
+    // They are dropped once the next block is committed.
+    store.commit(&vec![b.digest()].into_iter().collect());
+    assert!(store.get(&a.digest()).is_none());
+    assert!(store.get(&b.digest()).is_some());
+    assert!(store.batches().is_empty());
+}
+
+#[test]
+fn test_batch_store_limits_batches_per_author() {
+    let store = BatchStore::new();
+    let author = AccountAddress::random();
+    for _ in 0..MAX_BATCHES_PER_AUTHOR {
+        assert!(store.insert(TxnBatch::new(author, random_txns(1))));
+    }
+    assert_eq!(store.num_batches_of(author), MAX_BATCHES_PER_AUTHOR);
+    assert!(!store.insert(TxnBatch::new(author, random_txns(1))));
+    // The other authors are not affected.
+    assert!(store.insert(TxnBatch::new(AccountAddress::random(), random_txns(1))));
+    // A batch a block refers to is kept anyway.
+    let referenced = TxnBatch::new(author, random_txns(1));
+    assert!(store.insert_referenced(referenced.clone()));
+    assert_eq!(store.num_batches_of(author), MAX_BATCHES_PER_AUTHOR + 1);
+
+    store.remove(&vec![referenced.digest()].into_iter().collect());
+    assert_eq!(store.num_batches_of(author), MAX_BATCHES_PER_AUTHOR);
+}
+
+/// Keeps the persisted batches in memory.
+#[derive(Default)]
+struct MockBatchStorage {
+    batches: Mutex<Vec<TxnBatch>>,
+}
+
+impl PersistentBatchStorage for MockBatchStorage {
+    fn save_batch(&self, batch: &TxnBatch) -> Result<()> {
+        self.batches.lock().unwrap().push(batch.clone());
+        Ok(())
+    }
+
+    fn delete_batches(&self, digests: Vec<HashValue>) -> Result<()> {
+        self.batches
+            .lock()
+            .unwrap()
+            .retain(|batch| !digests.contains(&batch.digest()));
+        Ok(())
+    }
+
+    fn get_batches(&self) -> Result<Vec<TxnBatch>> {
+        Ok(self.batches.lock().unwrap().clone())
+    }
+}
+
+#[test]
+fn test_batch_store_restores_persisted_batches() {
+    let storage = Arc::new(MockBatchStorage::default());
+    let a = TxnBatch::new(AccountAddress::random(), random_txns(1));
+    let b = TxnBatch::new(AccountAddress::random(), random_txns(2));
+    {
+        let store = BatchStore::new_with_storage(Arc::clone(&storage)).unwrap();
+        store.insert(a.clone());
+        store.insert(b.clone());
+        store.remove(&vec![a.digest()].into_iter().collect());
+    }
+
+    // The batches that are not committed are known again after a restart.
+    let store = BatchStore::new_with_storage(storage).unwrap();
+    assert!(store.get(&a.digest()).is_none());
+    assert_eq!(
+        store.get(&b.digest()).map(|batch| TxnBatch::clone(&batch)),
+        Some(b)
+    );
+}
+
+#[test]
+fn test_pull_and_commit_batches() {
+    let author = AccountAddress::random();
+    let store = Arc::new(BatchStore::new());
+    let mempool = Arc::new(MockMempool::new(vec![]));
+    let manager = Arc::new(BatchManager::new(
+        Arc::clone(&store),
+        single_validator_network(author),
+    ));
+    let txn_manager = BatchTxnManager::new(manager, mempool.clone());
+
+    let a = TxnBatch::new(author, random_txns(2));
+    let b = TxnBatch::new(author, random_txns(3));
+    let c = TxnBatch::new(author, random_txns(2));
+    for batch in &[&a, &b, &c] {
+        store.insert(TxnBatch::clone(batch));
+    }
+
+    // The oldest batches that fit in the block are proposed.
+    let payload = block_on(txn_manager.pull_txns(4, vec![])).unwrap();
+    assert_eq!(payload.batches(), &[a.batch_ref(), c.batch_ref()][..]);
+    // The batches pending in the branch are not proposed again.
+    let next_payload = block_on(txn_manager.pull_txns(5, vec![&payload])).unwrap();
+    assert_eq!(next_payload.batches(), &[b.batch_ref()][..]);
+
+    // The transactions of the committed batches are committed in order and the batches dropped.
+    let compute_result = StateComputeResult {
+        new_state_id: HashValue::random(),
+        compute_status: vec![true; 4],
+        num_successful_txns: 4,
+        validators: None,
+    };
+    block_on(txn_manager.commit_txns(&payload, &compute_result, 0)).unwrap();
+    let mut expected_txns = a.transactions().clone();
+    expected_txns.extend(c.transactions().iter().cloned());
+    assert_eq!(*mempool.committed_txns.lock().unwrap(), expected_txns);
+    let remaining: HashSet<_> = store
+        .batches()
+        .into_iter()
+        .map(|batch| batch.digest())
+        .collect();
+    assert_eq!(remaining, vec![b.digest()].into_iter().collect());
+
+    // The batches a payload refers to are known locally.
+    block_on(txn_manager.fetch_payload(&next_payload, author)).unwrap();
+}
+
+#[test]
+fn test_generate_batch() {
+    let runtime = Runtime::new().unwrap();
+    let author = AccountAddress::random();
+    let store = Arc::new(BatchStore::new());
+    let txns = random_txns(5);
+    let generator = BatchGenerator::new(
+        author,
+        Arc::clone(&store),
+        Arc::new(MockMempool::new(txns.clone())),
+        single_validator_network(author),
+        Arc::new(ClockTimeService::new(runtime.executor())),
+        3,
+        Duration::from_millis(100),
+    );
+
+    let first = block_on(generator.generate_batch()).unwrap().unwrap();
+    assert_eq!(first.author(), author);
+    assert_eq!(first.transactions(), &txns[..3].to_vec());
+    // The transactions of the pending batches are not batched again.
+    let second = block_on(generator.generate_batch()).unwrap().unwrap();
+    assert_eq!(second.transactions(), &txns[3..].to_vec());
+    assert!(block_on(generator.generate_batch()).unwrap().is_none());
+    assert_eq!(store.batches().len(), 2);
+}
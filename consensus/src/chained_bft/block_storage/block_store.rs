@@ -18,6 +18,7 @@ use logger::prelude::*;
 
 use crate::{chained_bft::persistent_storage::RecoveryData, state_replication::StateComputeResult};
 use crypto::hash::CryptoHash;
+use futures::compat::Future01CompatExt;
 use mirai_annotations::checked_precondition;
 use nextgen_crypto::ed25519::*;
 use std::{
@@ -63,6 +64,11 @@ pub struct BlockStore<T> {
     /// The persistent storage backing up the in-memory data structure, every write should go
     /// through this before in-memory tree.
     storage: Arc<dyn PersistentStorage<T>>,
+    /// In the pipelined execution mode, the blocks are inserted before being executed: the votes
+    /// only certify their ordering.
+    pipelined_execution: bool,
+    /// Serializes the executions of the blocks inserted before being executed.
+    execution_lock: futures_locks::Mutex<()>,
 }
 
 impl<T: Payload> BlockStore<T> {
@@ -73,6 +79,7 @@ impl<T: Payload> BlockStore<T> {
         state_computer: Arc<dyn StateComputer<Payload = T>>,
         enforce_increasing_timestamps: bool,
         max_pruned_blocks_in_mem: usize,
+        pipelined_execution: bool,
     ) -> Self {
         let (root, blocks, quorum_certs) = initial_data.take();
         let inner = Arc::new(RwLock::new(
@@ -82,6 +89,7 @@ impl<T: Payload> BlockStore<T> {
                 quorum_certs,
                 Arc::clone(&state_computer),
                 max_pruned_blocks_in_mem,
+                pipelined_execution,
             )
             .await,
         ));
@@ -91,6 +99,8 @@ impl<T: Payload> BlockStore<T> {
             state_computer,
             enforce_increasing_timestamps,
             storage,
+            pipelined_execution,
+            execution_lock: futures_locks::Mutex::new(()),
         }
     }
 
@@ -100,15 +110,38 @@ impl<T: Payload> BlockStore<T> {
         quorum_certs: Vec<QuorumCert>,
         state_computer: Arc<dyn StateComputer<Payload = T>>,
         max_pruned_blocks_in_mem: usize,
+        pipelined_execution: bool,
     ) -> BlockTree<T> {
-        let mut tree = BlockTree::new(root.0, root.1, root.2, max_pruned_blocks_in_mem);
+        let root_state = if pipelined_execution {
+            // The quorum certificates only certify the ordering of the blocks: the execution
+            // results of the root are the ones of the ledger info that commits it.
+            let ledger_info = root.2.ledger_info().ledger_info();
+            ExecutedState {
+                state_id: ledger_info.transaction_accumulator_hash(),
+                version: ledger_info.version(),
+            }
+        } else {
+            root.1.certified_state()
+        };
+        let mut tree = BlockTree::new(root.0, root_state, root.1, root.2, max_pruned_blocks_in_mem);
         let quorum_certs = quorum_certs
             .into_iter()
             .map(|qc| (qc.certified_block_id(), qc))
             .collect::<HashMap<_, _>>();
         for block in blocks {
+            if pipelined_execution {
+                // The pending blocks are executed again when needed.
+                tree.insert_unexecuted_block(block)
+                    .expect("Block insertion failed while build the tree");
+                continue;
+            }
             let compute_res = state_computer
-                .compute(block.parent_id(), block.id(), block.get_payload())
+                .compute(
+                    block.parent_id(),
+                    block.id(),
+                    block.author(),
+                    block.get_payload(),
+                )
                 .await
                 .expect("fail to rebuild scratchpad");
             let version = tree
@@ -151,6 +184,7 @@ impl<T: Payload> BlockStore<T> {
             quorum_certs,
             Arc::clone(&self.state_computer),
             self.inner.read().unwrap().max_pruned_blocks_in_mem(),
+            self.pipelined_execution,
         )
         .await;
         let to_remove = self.inner.read().unwrap().get_all_block_id();
@@ -165,10 +199,22 @@ impl<T: Payload> BlockStore<T> {
         &self.validator_signer
     }
 
+    /// Returns the blocks of the tree, from the root (the last committed block) to the leaves.
+    pub fn tree_blocks(&self) -> Vec<Arc<Block<T>>> {
+        self.inner.read().unwrap().get_tree_blocks()
+    }
+
+    /// Returns true if the blocks are inserted before being executed.
+    pub fn pipelined_execution(&self) -> bool {
+        self.pipelined_execution
+    }
+
     /// Execute and insert a block if it passes all validation tests.
     /// Returns the Arc to the block kept in the block store after persisting it to storage
     ///
     /// This function assumes that the ancestors are present (returns MissingParent otherwise).
+    /// In the pipelined execution mode, the block is inserted without being executed: see
+    /// `execute_block`.
     ///
     /// Duplicate inserts will return the previously inserted block (
     /// note that it is considered a valid non-error case, for example, it can happen if a validator
@@ -180,19 +226,87 @@ impl<T: Payload> BlockStore<T> {
         if let Some(existing_block) = self.inner.read().unwrap().get_block(block.id()) {
             return Ok(existing_block);
         }
-        let (parent_id, parent_exec_version) = match self.verify_and_get_parent_info(&block) {
-            Ok(t) => t,
-            Err(e) => {
-                security_log(SecurityEvent::InvalidBlock)
-                    .error(&e)
-                    .data(&block)
-                    .log();
-                return Err(e);
-            }
-        };
+        if let Err(e) = self.verify_block(&block) {
+            security_log(SecurityEvent::InvalidBlock)
+                .error(&e)
+                .data(&block)
+                .log();
+            return Err(e);
+        }
+        if self.pipelined_execution {
+            self.storage
+                .save_tree(vec![block.clone()], vec![])
+                .map_err(|_| InsertError::StorageFailure)?;
+            return self
+                .inner
+                .write()
+                .unwrap()
+                .insert_unexecuted_block(block)
+                .map_err(|e| e.into());
+        }
+        let (state, compute_res) = self.execute(&block).await?;
+        self.storage
+            .save_tree(vec![block.clone()], vec![])
+            .map_err(|_| InsertError::StorageFailure)?;
+        self.inner
+            .write()
+            .unwrap()
+            .insert_block(block, state, compute_res)
+            .map_err(|e| e.into())
+    }
+
+    /// Executes a block of the tree along with its ancestors that are not executed yet, and
+    /// returns its execution results. The blocks are only inserted before being executed in the
+    /// pipelined execution mode: otherwise the execution results are returned right away.
+    pub async fn execute_block(&self, block_id: HashValue) -> Result<ExecutedState, InsertError> {
+        if let Some(state) = self.get_state_for_block(block_id) {
+            return Ok(state);
+        }
+        // The execution of a block starts from the state of its parent: the blocks are executed
+        // one at a time.
+        let _guard = self
+            .execution_lock
+            .lock()
+            .compat()
+            .await
+            .expect("Failed to acquire the execution lock");
+        let mut unexecuted_blocks = vec![];
+        let mut block_id_to_execute = block_id;
+        while self.get_state_for_block(block_id_to_execute).is_none() {
+            let block = self
+                .get_block(block_id_to_execute)
+                .ok_or_else(|| InsertError::MissingParentBlock(block_id_to_execute))?;
+            block_id_to_execute = block.parent_id();
+            unexecuted_blocks.push(block);
+        }
+        for block in unexecuted_blocks.into_iter().rev() {
+            let (state, compute_res) = self.execute(&block).await?;
+            self.inner
+                .write()
+                .unwrap()
+                .set_execution_result(block.id(), state, compute_res)?;
+        }
+        self.get_state_for_block(block_id)
+            .ok_or_else(|| InsertError::MissingParentBlock(block_id))
+    }
+
+    /// Executes a block on top of the state of its parent.
+    async fn execute(
+        &self,
+        block: &Block<T>,
+    ) -> Result<(ExecutedState, StateComputeResult), InsertError> {
+        let parent_exec_version = self
+            .get_state_for_block(block.parent_id())
+            .ok_or(InsertError::ParentVersionNotFound)?
+            .version;
         let compute_res = self
             .state_computer
-            .compute(parent_id, block.id(), block.get_payload())
+            .compute(
+                block.parent_id(),
+                block.id(),
+                block.author(),
+                block.get_payload(),
+            )
             .await
             .map_err(|e| {
                 error!("Execution failure for block {}: {:?}", block, e);
@@ -205,14 +319,12 @@ impl<T: Payload> BlockStore<T> {
             state_id: compute_res.new_state_id,
             version,
         };
-        self.storage
-            .save_tree(vec![block.clone()], vec![])
-            .map_err(|_| InsertError::StorageFailure)?;
-        self.inner
-            .write()
-            .unwrap()
-            .insert_block(block, state, compute_res)
-            .map_err(|e| e.into())
+        Ok((state, compute_res))
+    }
+
+    /// Returns the ids of the blocks inserted in the tree that are not executed yet.
+    pub fn unexecuted_block_ids(&self) -> Vec<HashValue> {
+        self.inner.read().unwrap().get_unexecuted_block_ids()
     }
 
     /// Check if we're far away from this ledger info and need to sync.
@@ -251,6 +363,21 @@ impl<T: Payload> BlockStore<T> {
 
     /// Validates quorum certificates and inserts it into block tree assuming dependencies exist.
     pub fn insert_single_quorum_cert(&self, qc: QuorumCert) -> Result<(), InsertError> {
+        if self.pipelined_execution {
+            // The quorum certificate only certifies the ordering of the block.
+            if !self.block_exists(qc.certified_block_id()) {
+                return Err(InsertError::MissingParentBlock(qc.certified_block_id()));
+            }
+            self.storage
+                .save_tree(vec![], vec![qc.clone()])
+                .map_err(|_| InsertError::StorageFailure)?;
+            return self
+                .inner
+                .write()
+                .unwrap()
+                .insert_quorum_cert(qc)
+                .map_err(|e| e.into());
+        }
         // Ensure executed state is consistent with Quorum Cert, otherwise persist the quorum's
         // state and hopefully we restart and agree with it.
         let executed_state = self
@@ -323,23 +450,23 @@ impl<T: Payload> BlockStore<T> {
         id_to_remove
     }
 
-    /// If block id information is found, returns the ledger info placeholder, otherwise, return
-    /// a placeholder with info of the genesis block.
-    pub fn ledger_info_placeholder(&self, id: Option<HashValue>) -> LedgerInfo {
+    /// If block id information is found, returns the ledger info placeholder for the given epoch,
+    /// otherwise, return a placeholder with info of the genesis block.
+    pub fn ledger_info_placeholder(&self, id: Option<HashValue>, epoch: u64) -> LedgerInfo {
         let block_id = match id {
-            None => return Self::zero_ledger_info_placeholder(),
+            None => return Self::zero_ledger_info_placeholder(epoch),
             Some(id) => id,
         };
         let block = match self.get_block(block_id) {
             Some(b) => b,
             None => {
-                return Self::zero_ledger_info_placeholder();
+                return Self::zero_ledger_info_placeholder(epoch);
             }
         };
         let (state_id, version) = match self.get_state_for_block(block_id) {
             Some(state) => (state.state_id, state.version),
             None => {
-                return Self::zero_ledger_info_placeholder();
+                return Self::zero_ledger_info_placeholder(epoch);
             }
         };
         LedgerInfo::new(
@@ -347,7 +474,7 @@ impl<T: Payload> BlockStore<T> {
             state_id,
             HashValue::zero(),
             block_id,
-            0, // TODO [Reconfiguration] use the real epoch number.
+            epoch,
             block.timestamp_usecs(),
         )
     }
@@ -355,21 +482,18 @@ impl<T: Payload> BlockStore<T> {
     /// Used in case we're using a ledger info just as a placeholder for signing the votes / QCs
     /// and there is no real block committed.
     /// It's all pretty much zeroes.
-    fn zero_ledger_info_placeholder() -> LedgerInfo {
+    fn zero_ledger_info_placeholder(epoch: u64) -> LedgerInfo {
         LedgerInfo::new(
             0,
             HashValue::zero(),
             HashValue::zero(),
             HashValue::zero(),
-            0,
+            epoch,
             0,
         )
     }
 
-    fn verify_and_get_parent_info(
-        &self,
-        block: &Block<T>,
-    ) -> Result<(HashValue, u64), InsertError> {
+    fn verify_block(&self, block: &Block<T>) -> Result<(), InsertError> {
         if block.round() <= self.inner.read().unwrap().root().round() {
             return Err(InsertError::OldBlock);
         }
@@ -406,11 +530,7 @@ impl<T: Payload> BlockStore<T> {
                 return Err(InsertError::NonIncreasingTimestamp);
             }
         }
-        let parent_id = parent.id();
-        match self.inner.read().unwrap().get_state_for_block(parent_id) {
-            Some(ExecutedState { version, .. }) => Ok((parent.id(), version)),
-            None => Err(InsertError::ParentVersionNotFound),
-        }
+        Ok(())
     }
 }
 
@@ -460,6 +580,7 @@ impl<T: Payload> BlockReader for BlockStore<T> {
         &self,
         parent: Arc<Block<Self::Payload>>,
         payload: Self::Payload,
+        epoch: u64,
         round: Round,
         timestamp_usecs: u64,
     ) -> Block<Self::Payload> {
@@ -474,6 +595,7 @@ impl<T: Payload> BlockReader for BlockStore<T> {
         Block::make_block(
             parent.as_ref(),
             payload,
+            epoch,
             round,
             timestamp_usecs,
             quorum_cert,
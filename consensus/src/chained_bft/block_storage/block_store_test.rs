@@ -10,7 +10,8 @@ use crate::chained_bft::{
     },
     safety::vote_msg::VoteMsg,
     test_utils::{
-        build_empty_tree, build_empty_tree_with_custom_signing, placeholder_certificate_for_block,
+        build_empty_tree, build_empty_tree_with_custom_signing,
+        build_empty_tree_with_pipelined_execution, placeholder_certificate_for_block,
         placeholder_ledger_info, TreeInserter,
     },
 };
@@ -53,7 +54,7 @@ fn build_simple_tree() -> (Vec<Arc<Block<Vec<usize>>>>, Arc<BlockStore<Vec<usize
 fn test_block_store_create_block() {
     let block_store = build_empty_tree();
     let genesis = block_store.root();
-    let a1 = block_store.create_block(Arc::clone(&genesis), vec![1], 1, 1);
+    let a1 = block_store.create_block(Arc::clone(&genesis), vec![1], 0, 1, 1);
     assert_eq!(a1.parent_id(), genesis.id());
     assert_eq!(a1.round(), 1);
     assert_eq!(a1.height(), 1);
@@ -76,7 +77,7 @@ fn test_block_store_create_block() {
     );
     block_store.insert_vote_and_qc(vote_msg, 1);
 
-    let b1 = block_store.create_block(Arc::clone(&a1_ref), vec![2], 2, 2);
+    let b1 = block_store.create_block(Arc::clone(&a1_ref), vec![2], 0, 2, 2);
     assert_eq!(b1.parent_id(), a1_ref.id());
     assert_eq!(b1.round(), 2);
     assert_eq!(b1.height(), 2);
@@ -398,6 +399,7 @@ fn test_illegal_timestamp() {
     let block_with_illegal_timestamp = Block::<Vec<usize>>::new_internal(
         vec![],
         genesis.id(),
+        0,
         1,
         1,
         // This timestamp is illegal, it is the same as genesis
@@ -519,3 +521,43 @@ fn test_need_sync_for_qc() {
         false
     );
 }
+
+#[test]
+fn test_pipelined_execution() {
+    let block_store = build_empty_tree_with_pipelined_execution();
+    let mut inserter = TreeInserter::new(block_store.clone());
+    let genesis = block_store.root();
+    // Genesis -> A1 -> A2
+    //        | -> B1
+    let a1 = inserter.insert_block(genesis.as_ref(), 1);
+    let a2 = inserter.insert_block(a1.as_ref(), 2);
+    let b1 = inserter.insert_block(genesis.as_ref(), 3);
+
+    // The blocks are inserted and certified before being executed.
+    for block in &[&a1, &a2, &b1] {
+        assert!(block_store.get_state_for_block(block.id()).is_none());
+    }
+    assert_eq!(block_store.highest_certified_block(), a1);
+    assert_eq!(
+        block_store
+            .unexecuted_block_ids()
+            .into_iter()
+            .collect::<HashSet<_>>(),
+        vec![a1.id(), a2.id(), b1.id()].into_iter().collect()
+    );
+
+    // Executing a block executes its ancestors first.
+    let a2_state = block_on(block_store.execute_block(a2.id())).unwrap();
+    assert_eq!(block_store.get_state_for_block(a2.id()), Some(a2_state));
+    assert!(block_store.get_state_for_block(a1.id()).is_some());
+    assert!(block_store.get_compute_result(a2.id()).is_some());
+    assert_eq!(block_store.unexecuted_block_ids(), vec![b1.id()]);
+    // The execution results are returned right away once the block is executed.
+    assert_eq!(
+        block_on(block_store.execute_block(a2.id())).unwrap(),
+        a2_state
+    );
+
+    block_on(block_store.execute_block(b1.id())).unwrap();
+    assert!(block_store.unexecuted_block_ids().is_empty());
+}
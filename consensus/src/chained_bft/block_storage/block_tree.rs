@@ -21,7 +21,7 @@ use std::{
     collections::{
         hash_map::Entry::{Occupied, Vacant},
         vec_deque::VecDeque,
-        HashMap,
+        HashMap, HashSet,
     },
     fmt::Debug,
     sync::Arc,
@@ -41,6 +41,9 @@ pub struct BlockTree<T> {
     id_to_child: HashMap<HashValue, Vec<Arc<Block<T>>>>,
     /// Mapping between proposals(Block) to execution results.
     id_to_state: HashMap<HashValue, ExecutedState>,
+    /// The blocks inserted before being executed (pipelined execution only): they have no
+    /// execution results yet.
+    unexecuted_block_ids: HashSet<HashValue>,
     /// Keeps the state compute results of the executed blocks.
     /// The state compute results is calculated for all the pending blocks prior to insertion to
     /// the tree (the initial root node might not have it, because it's been already
@@ -77,6 +80,7 @@ where
 {
     pub(super) fn new(
         root: Block<T>,
+        root_state: ExecutedState,
         root_quorum_cert: QuorumCert,
         root_ledger_info: QuorumCert,
         max_pruned_blocks_in_mem: usize,
@@ -102,7 +106,7 @@ where
         );
 
         let mut id_to_state = HashMap::new();
-        id_to_state.insert(root.id(), root_quorum_cert.certified_state());
+        id_to_state.insert(root.id(), root_state);
 
         let pruned_block_ids = VecDeque::with_capacity(max_pruned_blocks_in_mem);
 
@@ -110,6 +114,7 @@ where
             id_to_block,
             id_to_child: HashMap::new(),
             id_to_state,
+            unexecuted_block_ids: HashSet::new(),
             id_to_compute_result: HashMap::new(),
             root: Arc::clone(&root),
             highest_certified_block: Arc::clone(&root),
@@ -128,6 +133,7 @@ where
         // Remove the block from the store
         self.id_to_block.remove(&block_id);
         self.id_to_state.remove(&block_id);
+        self.unexecuted_block_ids.remove(&block_id);
         self.id_to_compute_result.remove(&block_id);
         self.id_to_votes.remove(&block_id);
         self.id_to_quorum_cert.remove(&block_id);
@@ -196,6 +202,22 @@ where
         block: Block<T>,
         state: ExecutedState,
         compute_result: StateComputeResult,
+    ) -> Result<Arc<Block<T>>, BlockTreeError> {
+        self.insert_block_with_execution_result(block, Some((state, compute_result)))
+    }
+
+    /// Inserts a block that is executed later on (see `set_execution_result`).
+    pub(super) fn insert_unexecuted_block(
+        &mut self,
+        block: Block<T>,
+    ) -> Result<Arc<Block<T>>, BlockTreeError> {
+        self.insert_block_with_execution_result(block, None)
+    }
+
+    fn insert_block_with_execution_result(
+        &mut self,
+        block: Block<T>,
+        execution_result: Option<(ExecutedState, StateComputeResult)>,
     ) -> Result<Arc<Block<T>>, BlockTreeError> {
         if !self.block_exists(block.parent_id()) {
             return Err(BlockTreeError::BlockNotFound {
@@ -210,7 +232,11 @@ where
                        previous_block,
                        block.id(),
                        block);
-                checked_verify_eq!(*self.id_to_state.get(&block.id()).unwrap(), state);
+                if let (Some(previous_state), Some((state, _))) =
+                    (self.id_to_state.get(&block.id()), &execution_result)
+                {
+                    checked_verify_eq!(previous_state, state);
+                }
                 Ok(previous_block.clone())
             }
             _ => {
@@ -221,14 +247,42 @@ where
                 children.push(block.clone());
                 counters::NUM_BLOCKS_IN_TREE.inc();
                 self.id_to_block.insert(block.id(), block.clone());
-                self.id_to_state.insert(block.id(), state);
-                self.id_to_compute_result
-                    .insert(block.id(), Arc::new(compute_result));
+                match execution_result {
+                    Some((state, compute_result)) => {
+                        self.id_to_state.insert(block.id(), state);
+                        self.id_to_compute_result
+                            .insert(block.id(), Arc::new(compute_result));
+                    }
+                    None => {
+                        self.unexecuted_block_ids.insert(block.id());
+                    }
+                }
                 Ok(block)
             }
         }
     }
 
+    /// Records the execution results of a block inserted before being executed.
+    pub(super) fn set_execution_result(
+        &mut self,
+        block_id: HashValue,
+        state: ExecutedState,
+        compute_result: StateComputeResult,
+    ) -> Result<(), BlockTreeError> {
+        if !self.unexecuted_block_ids.remove(&block_id) {
+            return Err(BlockTreeError::BlockNotFound { id: block_id });
+        }
+        self.id_to_state.insert(block_id, state);
+        self.id_to_compute_result
+            .insert(block_id, Arc::new(compute_result));
+        Ok(())
+    }
+
+    /// Returns the ids of the blocks that are not executed yet.
+    pub(super) fn get_unexecuted_block_ids(&self) -> Vec<HashValue> {
+        self.unexecuted_block_ids.iter().cloned().collect()
+    }
+
     pub(super) fn insert_quorum_cert(&mut self, qc: QuorumCert) -> Result<(), BlockTreeError> {
         let block_id = qc.certified_block_id();
         let qc = Arc::new(qc);
@@ -422,6 +476,20 @@ where
         self.max_pruned_blocks_in_mem
     }
 
+    /// Returns the blocks of the tree, from the root to the leaves
+    pub(super) fn get_tree_blocks(&self) -> Vec<Arc<Block<T>>> {
+        let mut res = vec![];
+        let mut to_visit = VecDeque::new();
+        to_visit.push_back(Arc::clone(&self.root));
+        while let Some(block) = to_visit.pop_front() {
+            if let Some(children) = self.id_to_child.get(&block.id()) {
+                to_visit.extend(children.iter().cloned());
+            }
+            res.push(block);
+        }
+        res
+    }
+
     pub(super) fn get_all_block_id(&self) -> Vec<HashValue> {
         self.id_to_block.keys().cloned().collect()
     }
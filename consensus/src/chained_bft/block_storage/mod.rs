@@ -192,7 +192,7 @@ pub trait BlockReader: Send + Sync {
         block: Arc<Block<Self::Payload>>,
     ) -> Option<Vec<Arc<Block<Self::Payload>>>>;
 
-    /// Generates and returns a block with the given parent and payload.
+    /// Generates and returns a block of the given epoch with the given parent and payload.
     /// Note that it does not add the block to the tree, just generates it.
     /// The main reason we want this function in the BlockStore is the fact that the signer required
     /// for signing the newly created block is held by the block store.
@@ -204,6 +204,7 @@ pub trait BlockReader: Send + Sync {
         &self,
         parent: Arc<Block<Self::Payload>>,
         payload: Self::Payload,
+        epoch: u64,
         round: Round,
         timestamp_usecs: u64,
     ) -> Block<Self::Payload>;
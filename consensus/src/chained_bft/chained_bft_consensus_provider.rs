@@ -2,52 +2,64 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    batch_manager::{
+        BatchGenerator, BatchManager, BatchPayload, BatchStateComputer, BatchStore,
+        BatchTxnManager, PersistentBatchStorage,
+    },
     chained_bft::{
-        chained_bft_smr::ChainedBftSMR, network::ConsensusNetworkImpl,
-        persistent_storage::PersistentStorage,
+        chained_bft_smr::ChainedBftSMR,
+        common::Payload,
+        epoch_manager::EpochManager,
+        network::ConsensusNetworkImpl,
+        persistent_storage::{PersistentStorage, RecoveryData},
     },
-    consensus_provider::ConsensusProvider,
-    counters,
+    consensus_provider::{create_storage_read_client, ConsensusProvider},
     state_computer::ExecutionProxy,
-    state_replication::StateMachineReplication,
+    state_replication::{StateComputer, StateMachineReplication, TxnManager},
     txn_manager::MempoolProxy,
+    util::time_service::ClockTimeService,
 };
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
-use nextgen_crypto::ed25519::*;
+use nextgen_crypto::{ed25519::*, vrf::ecvrf::VRFPrivateKey};
 
 use crate::chained_bft::{
     chained_bft_smr::ChainedBftSMRConfig, common::Author, persistent_storage::StorageWriteProxy,
 };
-use config::config::{ConsensusProposerType::FixedProposer, NodeConfig};
+use config::config::NodeConfig;
+use debug_interface::node_debug_service::ConsensusDebugInfo;
 use execution_proto::proto::execution_grpc::ExecutionClient;
 use failure::prelude::*;
+use futures::{FutureExt, TryFutureExt};
 use logger::prelude::*;
 use mempool::proto::mempool_grpc::MempoolClient;
 use state_synchronizer::StateSyncClient;
-use std::{convert::TryFrom, sync::Arc};
-use tokio::runtime;
+use std::{convert::TryFrom, sync::Arc, time::Duration};
+use tokio::runtime::{self, Runtime};
 use types::{
-    account_address::AccountAddress, transaction::SignedTransaction,
-    validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier,
+    account_address::AccountAddress,
+    account_config::core_code_address,
+    get_with_proof::{RequestItem, ResponseItem},
+    transaction::SignedTransaction,
+    validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
 };
 
 struct InitialSetup {
     author: Author,
     signer: ValidatorSigner<Ed25519PrivateKey>,
-    quorum_size: usize,
-    peers: Arc<Vec<Author>>,
-    validator: Arc<ValidatorVerifier<Ed25519PublicKey>>,
+    vrf_private_key: Option<VRFPrivateKey>,
+    epoch_manager: Arc<EpochManager>,
 }
 
 /// Supports the implementation of ConsensusProvider using LibraBFT.
-pub struct ChainedBftProvider {
-    smr: ChainedBftSMR<Vec<SignedTransaction>>,
-    mempool_client: Arc<MempoolClient>,
-    execution_client: Arc<ExecutionClient>,
-    synchronizer_client: Arc<StateSyncClient>,
+pub struct ChainedBftProvider<T> {
+    smr: ChainedBftSMR<T>,
+    txn_manager: Arc<dyn TxnManager<Payload = T>>,
+    state_computer: Arc<dyn StateComputer<Payload = T>>,
 }
 
-impl ChainedBftProvider {
+impl ChainedBftProvider<Vec<SignedTransaction>> {
+    /// The proposals carry the transactions pulled from the mempool.
     pub fn new(
         node_config: &mut NodeConfig,
         network_sender: ConsensusNetworkSender,
@@ -56,52 +68,123 @@ impl ChainedBftProvider {
         execution_client: Arc<ExecutionClient>,
         synchronizer_client: Arc<StateSyncClient>,
     ) -> Self {
-        let runtime = runtime::Builder::new()
-            .name_prefix("consensus-")
-            .build()
-            .expect("Failed to create Tokio runtime!");
-
+        let runtime = Self::create_runtime();
         let initial_setup = Self::initialize_setup(node_config);
+        let (storage, initial_data) = StorageWriteProxy::start(node_config);
         let network = ConsensusNetworkImpl::new(
             initial_setup.author,
-            network_sender.clone(),
+            network_sender,
             network_events,
-            Arc::clone(&initial_setup.peers),
-            Arc::clone(&initial_setup.validator),
+            Arc::clone(&initial_setup.epoch_manager),
         );
-        let proposer = {
-            if node_config.consensus.get_proposer_type() == FixedProposer {
-                vec![Self::choose_leader(&initial_setup)]
-            } else {
-                initial_setup.validator.get_ordered_account_addresses()
-            }
-        };
-        debug!("[Consensus] My peer: {:?}", initial_setup.author);
-        debug!("[Consensus] Chosen proposer: {:?}", proposer);
-        let config = ChainedBftSMRConfig::from_node_config(&node_config.consensus);
+        Self {
+            smr: Self::create_smr(
+                node_config,
+                initial_setup,
+                storage,
+                initial_data,
+                network,
+                runtime,
+            ),
+            txn_manager: Arc::new(MempoolProxy::new(mempool_client)),
+            state_computer: Arc::new(ExecutionProxy::new(execution_client, synchronizer_client)),
+        }
+    }
+}
+
+impl ChainedBftProvider<BatchPayload> {
+    /// The validators disseminate the transactions in batches ahead of the proposals, which only
+    /// refer to the batches.
+    pub fn new_with_batch_dissemination(
+        node_config: &mut NodeConfig,
+        network_sender: ConsensusNetworkSender,
+        network_events: ConsensusNetworkEvents,
+        mempool_client: Arc<MempoolClient>,
+        execution_client: Arc<ExecutionClient>,
+        synchronizer_client: Arc<StateSyncClient>,
+    ) -> Self {
+        let runtime = Self::create_runtime();
+        let initial_setup = Self::initialize_setup(node_config);
         let (storage, initial_data) = StorageWriteProxy::start(node_config);
+        // The batches the pending blocks refer to must survive a restart.
+        let batch_store = Arc::new(
+            BatchStore::new_with_storage(Arc::clone(&storage) as Arc<dyn PersistentBatchStorage>)
+                .expect("Unable to restore the batches of transactions"),
+        );
+        let mut network = ConsensusNetworkImpl::new(
+            initial_setup.author,
+            network_sender,
+            network_events,
+            Arc::clone(&initial_setup.epoch_manager),
+        );
+        network.set_batch_store(Arc::clone(&batch_store));
+        let mempool = Arc::new(MempoolProxy::new(mempool_client));
+        let batch_generator = BatchGenerator::new(
+            initial_setup.author,
+            Arc::clone(&batch_store),
+            Arc::clone(&mempool) as Arc<dyn TxnManager<Payload = Vec<SignedTransaction>>>,
+            network.clone(),
+            Arc::new(ClockTimeService::new(runtime.executor())),
+            node_config.consensus.max_batch_size(),
+            Duration::from_millis(node_config.consensus.batch_interval_ms()),
+        );
+        runtime
+            .executor()
+            .spawn(batch_generator.run().boxed().unit_error().compat());
+        let batch_manager = Arc::new(BatchManager::new(batch_store, network.clone()));
+        Self {
+            smr: Self::create_smr(
+                node_config,
+                initial_setup,
+                storage,
+                initial_data,
+                network,
+                runtime,
+            ),
+            txn_manager: Arc::new(BatchTxnManager::new(Arc::clone(&batch_manager), mempool)),
+            state_computer: Arc::new(BatchStateComputer::new(
+                batch_manager,
+                Arc::new(ExecutionProxy::new(execution_client, synchronizer_client)),
+            )),
+        }
+    }
+}
+
+impl<T: Payload> ChainedBftProvider<T> {
+    fn create_runtime() -> Runtime {
+        runtime::Builder::new()
+            .name_prefix("consensus-")
+            .build()
+            .expect("Failed to create Tokio runtime!")
+    }
+
+    fn create_smr(
+        node_config: &NodeConfig,
+        initial_setup: InitialSetup,
+        storage: Arc<StorageWriteProxy>,
+        initial_data: RecoveryData<T>,
+        network: ConsensusNetworkImpl,
+        runtime: Runtime,
+    ) -> ChainedBftSMR<T> {
+        debug!("[Consensus] My peer: {:?}", initial_setup.author);
+        let config = ChainedBftSMRConfig::from_node_config(node_config);
         info!(
             "Starting up the consensus state machine with recovery data - {:?}, {:?}",
             initial_data.state(),
             initial_data.highest_timeout_certificates()
         );
-        let smr = ChainedBftSMR::new(
+        ChainedBftSMR::new(
             initial_setup.author,
-            initial_setup.quorum_size,
             initial_setup.signer,
-            proposer,
+            initial_setup.vrf_private_key,
+            initial_setup.epoch_manager,
             network,
             runtime,
             config,
             storage,
             initial_data,
-        );
-        Self {
-            smr,
-            mempool_client,
-            execution_client,
-            synchronizer_client,
-        }
+        )
+        .with_storage_read_client(create_storage_read_client(node_config))
     }
 
     /// Retrieve the initial "state" for consensus. This function is synchronous and returns after
@@ -120,59 +203,109 @@ impl ChainedBftProvider {
             "Failed to move a Consensus private key from a NodeConfig, key absent or already read",
         );
 
+        // The VRF key is only required by the VRF proposer election.
+        let vrf_private_key = node_config.base.peer_keypairs.take_vrf_private();
         let signer = ValidatorSigner::new(author, private_key);
-        let peers_with_public_keys = node_config.base.trusted_peers.get_trusted_consensus_peers();
-        let peers_with_nextgen_public_keys = peers_with_public_keys
-            .clone()
-            .into_iter()
-            .map(|(k, v)| (AccountAddress::clone(&k), v))
-            .collect();
-        let peers = Arc::new(
-            peers_with_public_keys
-                .keys()
-                .map(AccountAddress::clone)
-                .collect(),
+        let epoch_manager = Arc::new(
+            initial_epoch_manager(node_config).expect("Unable to recover the epoch from storage"),
         );
-        let validator = Arc::new(ValidatorVerifier::new(peers_with_nextgen_public_keys));
-        counters::EPOCH_NUM.set(0); // No reconfiguration yet, so it is always zero
-        counters::CURRENT_EPOCH_NUM_VALIDATORS.set(validator.len() as i64);
-        counters::CURRENT_EPOCH_QUORUM_SIZE.set(validator.quorum_size() as i64);
-        debug!("[Consensus]: quorum_size = {:?}", validator.quorum_size());
         InitialSetup {
             author,
             signer,
-            quorum_size: validator.quorum_size(),
-            peers,
-            validator,
+            vrf_private_key,
+            epoch_manager,
         }
     }
+}
 
-    /// Choose a proposer that is going to be the single leader (relevant for a mock fixed proposer
-    /// election only).
-    fn choose_leader(initial_setup: &InitialSetup) -> Author {
-        // As it is just a tmp hack function, pick the max PeerId to be a proposer.
-        // TODO: VRF will be integrated later.
-        *initial_setup
-            .peers
-            .iter()
-            .max()
-            .expect("No trusted peers found!")
-    }
+/// The epoch manager to start with (see `initial_epoch`), shared by the consensus process and the
+/// safety rules node.
+pub(crate) fn initial_epoch_manager(node_config: &NodeConfig) -> Result<EpochManager> {
+    let peers_with_public_keys = node_config.base.trusted_peers.get_trusted_consensus_peers();
+    let peers_with_nextgen_public_keys = peers_with_public_keys
+        .into_iter()
+        .map(|(k, v)| (AccountAddress::clone(&k), v))
+        .collect();
+    let genesis_validator = ValidatorVerifier::new(peers_with_nextgen_public_keys);
+    let (epoch, validator) = initial_epoch(node_config, genesis_validator)?;
+    debug!(
+        "[Consensus]: epoch = {}, quorum_size = {:?}",
+        epoch,
+        validator.quorum_size()
+    );
+    Ok(EpochManager::new(epoch, Arc::new(validator))
+        .with_vrf_public_keys(node_config.base.trusted_peers.get_trusted_vrf_peers())
+        .with_pipelined_execution(node_config.consensus.pipelined_execution()))
 }
 
-impl ConsensusProvider for ChainedBftProvider {
+/// The epoch and the validators to start with, recovered from the latest committed ledger info
+/// in storage and the validator set on chain at its version, so a restarted node rejoins the
+/// epoch the others are in. If the validator set changed at that version, the ledger info ended
+/// its epoch and the node starts in the next one. The validators of the trusted peers config are
+/// only used as long as no validator set has been published on chain.
+fn initial_epoch(
+    node_config: &NodeConfig,
+    genesis_validator: ValidatorVerifier<Ed25519PublicKey>,
+) -> Result<(u64, ValidatorVerifier<Ed25519PublicKey>)> {
+    let read_client = create_storage_read_client(node_config);
+    let (response_items, ledger_info_with_sigs, _) = read_client.update_to_latest_ledger(
+        0, /* client_known_version */
+        vec![RequestItem::GetAccountState {
+            address: core_code_address(),
+        }],
+    )?;
+    let ledger_info = ledger_info_with_sigs.ledger_info();
+    let validator_set = match response_items.as_slice() {
+        [ResponseItem::GetAccountState {
+            account_state_with_proof,
+        }] => EpochManager::validator_set_from_blob(account_state_with_proof.blob.as_ref())?,
+        _ => bail!(
+            "Unexpected response to the account state request: {:?}",
+            response_items
+        ),
+    };
+    // Genesis starts the first epoch rather than ending one.
+    let previous_validator_set = match ledger_info.version() {
+        0 => validator_set.clone(),
+        version => {
+            let (blob, _) = read_client
+                .get_account_state_with_proof_by_version(core_code_address(), version - 1)?;
+            EpochManager::validator_set_from_blob(blob.as_ref())?
+        }
+    };
+    let epoch = EpochManager::epoch_after_commit(
+        ledger_info.epoch_num(),
+        validator_set.as_ref(),
+        previous_validator_set.as_ref(),
+    );
+    let validator = match validator_set {
+        Some(validator_set) => EpochManager::validator_verifier(&validator_set),
+        None => genesis_validator,
+    };
+    info!(
+        "Recovered epoch {} from the ledger info of epoch {} at version {}",
+        epoch,
+        ledger_info.epoch_num(),
+        ledger_info.version()
+    );
+    Ok((epoch, validator))
+}
+
+impl<T: Payload> ConsensusProvider for ChainedBftProvider<T> {
     fn start(&mut self) -> Result<()> {
-        let txn_manager = Arc::new(MempoolProxy::new(self.mempool_client.clone()));
-        let state_computer = Arc::new(ExecutionProxy::new(
-            self.execution_client.clone(),
-            self.synchronizer_client.clone(),
-        ));
         debug!("Starting consensus provider.");
-        self.smr.start(txn_manager, state_computer)
+        self.smr.start(
+            Arc::clone(&self.txn_manager),
+            Arc::clone(&self.state_computer),
+        )
     }
 
     fn stop(&mut self) {
         self.smr.stop();
         debug!("Consensus provider stopped.");
     }
+
+    fn debug_info(&self) -> Option<Arc<dyn ConsensusDebugInfo>> {
+        self.smr.debug_info()
+    }
 }
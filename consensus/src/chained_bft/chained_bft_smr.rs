@@ -6,6 +6,8 @@ use crate::{
         block_storage::{BlockReader, BlockStore},
         common::{Payload, Round},
         consensus_types::{proposal_msg::ProposalMsg, timeout_msg::TimeoutMsg},
+        debug_info::ChainedBftDebugInfo,
+        epoch_manager::EpochManager,
         event_processor::{EventProcessor, ProcessProposalResult},
         liveness::{
             local_pacemaker::{ExponentialTimeInterval, LocalPacemaker},
@@ -13,19 +15,26 @@ use crate::{
             pacemaker_timeout_manager::HighestTimeoutCertificates,
             proposal_generator::ProposalGenerator,
             proposer_election::ProposerElection,
+            reputation_proposer_election::ReputationProposer,
             rotating_proposer_election::RotatingProposer,
+            vrf_proposer_election::VrfProposer,
         },
         network::{
-            BlockRetrievalRequest, ChunkRetrievalRequest, ConsensusNetworkImpl, NetworkReceivers,
+            AccountStateChunkRetrievalRequest, BlockRetrievalRequest, ChunkRetrievalRequest,
+            ConsensusNetworkImpl, NetworkReceivers,
         },
         persistent_storage::{PersistentLivenessStorage, PersistentStorage, RecoveryData},
-        safety::{safety_rules::SafetyRules, vote_msg::VoteMsg},
+        safety::{
+            local_safety_rules::LocalSafetyRules, remote_safety_rules::RemoteSafetyRules,
+            t_safety_rules::TSafetyRules, vote_msg::VoteMsg,
+        },
     },
     counters,
     state_replication::{StateComputer, StateMachineReplication, TxnManager},
     util::time_service::{ClockTimeService, TimeService},
 };
 use channel;
+use crypto::HashValue;
 use failure::prelude::*;
 use futures::{
     compat::Future01CompatExt,
@@ -33,15 +42,21 @@ use futures::{
     future::{FutureExt, TryFutureExt},
     stream::StreamExt,
 };
-use nextgen_crypto::ed25519::*;
+use nextgen_crypto::{
+    ed25519::*,
+    vrf::ecvrf::{VRFPrivateKey, VRFPublicKey},
+};
 use state_synchronizer::SyncStatus;
-use types::validator_signer::ValidatorSigner;
+use storage_client::StorageRead;
+use types::{validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier};
 
 use crate::chained_bft::{common::Author, consensus_types::sync_info::SyncInfo};
-use config::config::ConsensusConfig;
+use config::config::{ConsensusProposerType, NodeConfig, SafetyRulesBackend, SafetyRulesConfig};
+use debug_interface::node_debug_service::ConsensusDebugInfo;
 use futures::sink::SinkExt;
 use logger::prelude::*;
 use std::{
+    collections::HashMap,
     sync::{Arc, RwLock},
     thread,
     time::Duration,
@@ -50,6 +65,9 @@ use tokio::runtime::{Runtime, TaskExecutor};
 
 type ConcurrentEventProcessor<T> = futures_locks::RwLock<EventProcessor<T>>;
 
+/// Number of certified blocks per validator considered by the reputation proposer election.
+const REPUTATION_WINDOW_PER_VALIDATOR: usize = 10;
+
 /// Consensus configuration derived from ConsensusConfig
 pub struct ChainedBftSMRConfig {
     /// Keep up to this number of committed blocks before cleaning them up from the block store.
@@ -60,16 +78,26 @@ pub struct ChainedBftSMRConfig {
     pub contiguous_rounds: u32,
     /// Max block size (number of transactions) that consensus pulls from mempool
     pub max_block_size: u64,
+    /// How the proposers are chosen among the validators of an epoch
+    pub proposer_type: ConsensusProposerType,
+    /// Where the safety rules run
+    pub safety_rules: SafetyRulesConfig,
+    /// Vote on the ordering of the blocks and execute them in the background
+    pub pipelined_execution: bool,
 }
 
 impl ChainedBftSMRConfig {
-    pub fn from_node_config(cfg: &ConsensusConfig) -> ChainedBftSMRConfig {
+    pub fn from_node_config(node_cfg: &NodeConfig) -> ChainedBftSMRConfig {
+        let cfg = &node_cfg.consensus;
         let pacemaker_initial_timeout_ms = cfg.pacemaker_initial_timeout_ms().unwrap_or(1000);
         ChainedBftSMRConfig {
             max_pruned_blocks_in_mem: cfg.max_pruned_blocks_in_mem().unwrap_or(10000) as usize,
             pacemaker_initial_timeout: Duration::from_millis(pacemaker_initial_timeout_ms),
             contiguous_rounds: cfg.contiguous_rounds(),
             max_block_size: cfg.max_block_size(),
+            proposer_type: cfg.get_proposer_type(),
+            safety_rules: node_cfg.safety_rules.clone(),
+            pipelined_execution: cfg.pipelined_execution(),
         }
     }
 }
@@ -79,24 +107,27 @@ impl ChainedBftSMRConfig {
 /// ConsensusProvider for the e2e flow.
 pub struct ChainedBftSMR<T> {
     author: Author,
-    // TODO [Reconfiguration] quorum size is just a function of current validator set.
-    quorum_size: usize,
     signer: Option<ValidatorSigner<Ed25519PrivateKey>>,
-    proposers: Vec<Author>,
+    // Required to prove the proposals of this replica with the VRF proposer election
+    vrf_private_key: Option<Arc<VRFPrivateKey>>,
+    epoch_manager: Arc<EpochManager>,
     runtime: Option<Runtime>,
     block_store: Option<Arc<BlockStore<T>>>,
+    debug_info: Option<Arc<ChainedBftDebugInfo<T>>>,
     network: ConsensusNetworkImpl,
     config: ChainedBftSMRConfig,
     storage: Arc<dyn PersistentStorage<T>>,
+    // Required to prove the reconfigurations to the remote safety rules
+    storage_read_client: Option<Arc<dyn StorageRead>>,
     initial_data: Option<RecoveryData<T>>,
 }
 
 impl<T: Payload> ChainedBftSMR<T> {
     pub fn new(
         author: Author,
-        quorum_size: usize,
         signer: ValidatorSigner<Ed25519PrivateKey>,
-        proposers: Vec<Author>,
+        vrf_private_key: Option<VRFPrivateKey>,
+        epoch_manager: Arc<EpochManager>,
         network: ConsensusNetworkImpl,
         runtime: Runtime,
         config: ChainedBftSMRConfig,
@@ -105,23 +136,38 @@ impl<T: Payload> ChainedBftSMR<T> {
     ) -> Self {
         Self {
             author,
-            quorum_size,
             signer: Some(signer),
-            proposers,
+            vrf_private_key: vrf_private_key.map(Arc::new),
+            epoch_manager,
             runtime: Some(runtime),
             block_store: None,
+            debug_info: None,
             network,
             config,
             storage,
+            storage_read_client: None,
             initial_data: Some(initial_data),
         }
     }
 
+    /// Sets the client the remote safety rules read the proofs of the reconfigurations with.
+    pub fn with_storage_read_client(mut self, storage_read_client: Arc<dyn StorageRead>) -> Self {
+        self.storage_read_client = Some(storage_read_client);
+        self
+    }
+
     #[cfg(test)]
     pub fn block_store(&self) -> Option<Arc<BlockStore<T>>> {
         self.block_store.clone()
     }
 
+    /// Returns the provider of the consensus state for the debug interface, once started.
+    pub fn debug_info(&self) -> Option<Arc<dyn ConsensusDebugInfo>> {
+        self.debug_info
+            .as_ref()
+            .map(|debug_info| Arc::clone(debug_info) as Arc<dyn ConsensusDebugInfo>)
+    }
+
     fn create_pacemaker(
         &self,
         executor: TaskExecutor,
@@ -149,18 +195,82 @@ impl<T: Payload> ChainedBftSMR<T> {
             time_service,
             new_round_events_sender,
             external_timeout_sender,
-            self.quorum_size,
+            self.epoch_manager.quorum_size(),
             highest_timeout_certificates,
         ))
     }
 
-    /// Create a proposer election handler based on proposers
-    fn create_proposer_election(&self) -> Arc<dyn ProposerElection<T> + Send + Sync> {
-        assert!(!self.proposers.is_empty());
-        Arc::new(RotatingProposer::new(
-            self.proposers.clone(),
-            self.config.contiguous_rounds,
-        ))
+    /// Create the safety rules: in the consensus process or remote
+    fn create_safety_rules(
+        &self,
+        block_store: Arc<BlockStore<T>>,
+        consensus_state: ConsensusState,
+        signer: ValidatorSigner<Ed25519PrivateKey>,
+    ) -> Arc<RwLock<dyn TSafetyRules<T> + Send + Sync>> {
+        let config = &self.config.safety_rules;
+        match config.get_backend() {
+            SafetyRulesBackend::Local => Arc::new(RwLock::new(LocalSafetyRules::new(
+                block_store,
+                consensus_state,
+                signer,
+            ))),
+            // The remote safety rules keep their own consensus state.
+            SafetyRulesBackend::Remote => Arc::new(RwLock::new(
+                RemoteSafetyRules::new(
+                    self.author,
+                    block_store,
+                    &config.address,
+                    config.safety_rules_port,
+                    self.storage_read_client
+                        .clone()
+                        .expect("The remote safety rules require a storage read client"),
+                )
+                .expect("Unable to connect to the remote safety rules"),
+            )),
+        }
+    }
+
+    /// Create a proposer election handler based on the validators of an epoch
+    fn create_proposer_election(
+        proposer_type: ConsensusProposerType,
+        contiguous_rounds: u32,
+        validators: &ValidatorVerifier<Ed25519PublicKey>,
+        vrf_public_keys: &HashMap<Author, VRFPublicKey>,
+        vrf_private_key: Option<Arc<VRFPrivateKey>>,
+        block_store: Arc<BlockStore<T>>,
+        storage: Arc<dyn PersistentStorage<T>>,
+    ) -> Arc<dyn ProposerElection<T> + Send + Sync> {
+        let proposers = match proposer_type {
+            // Pick the max PeerId to be the single leader (relevant for a mock fixed proposer
+            // election only).
+            ConsensusProposerType::FixedProposer => vec![validators
+                .get_ordered_account_addresses()
+                .into_iter()
+                .max()
+                .expect("No validators found!")],
+            _ => validators.get_ordered_account_addresses(),
+        };
+        debug!("[Consensus] Chosen proposers: {:?}", proposers);
+        assert!(!proposers.is_empty());
+        match proposer_type {
+            ConsensusProposerType::ReputationProposer => {
+                let window_size = REPUTATION_WINDOW_PER_VALIDATOR * proposers.len();
+                Arc::new(ReputationProposer::new(
+                    proposers,
+                    contiguous_rounds,
+                    window_size,
+                    block_store,
+                    storage,
+                ))
+            }
+            ConsensusProposerType::VrfProposer => Arc::new(VrfProposer::new(
+                validators,
+                vrf_public_keys,
+                vrf_private_key,
+                block_store,
+            )),
+            _ => Arc::new(RotatingProposer::new(proposers, contiguous_rounds)),
+        }
     }
 
     async fn process_new_round_events(
@@ -227,34 +337,52 @@ impl<T: Payload> ChainedBftSMR<T> {
     }
 
     async fn process_winning_proposals(
+        executor: TaskExecutor,
         mut receiver: channel::Receiver<ProposalMsg<T>>,
         event_processor: ConcurrentEventProcessor<T>,
+        block_store: Arc<BlockStore<T>>,
     ) {
         while let Some(proposal_info) = receiver.next().await {
-            let guard = event_processor.read().compat().await.unwrap();
-            guard.process_winning_proposal(proposal_info).await;
+            let block_to_execute = {
+                let guard = event_processor.read().compat().await.unwrap();
+                guard.process_winning_proposal(proposal_info).await
+            };
+            // The pipelined execution runs in the background without holding the event
+            // processor, so that the next rounds are not held up by the execution.
+            if let Some(block_id) = block_to_execute {
+                executor.spawn(
+                    Self::execute_block(Arc::clone(&block_store), block_id)
+                        .boxed()
+                        .unit_error()
+                        .compat(),
+                );
+            }
+        }
+    }
+
+    async fn execute_block(block_store: Arc<BlockStore<T>>, block_id: HashValue) {
+        if let Err(e) = block_store.execute_block(block_id).await {
+            error!("Failed to execute the block {}: {:?}", block_id, e);
         }
     }
 
     async fn process_votes(
         mut receiver: channel::Receiver<VoteMsg>,
         event_processor: ConcurrentEventProcessor<T>,
-        quorum_size: usize,
     ) {
         while let Some(vote) = receiver.next().await {
             let guard = event_processor.read().compat().await.unwrap();
-            guard.process_vote(vote, quorum_size).await;
+            guard.process_vote(vote).await;
         }
     }
 
     async fn process_timeout_msg(
         mut receiver: channel::Receiver<TimeoutMsg>,
         event_processor: ConcurrentEventProcessor<T>,
-        quorum_size: usize,
     ) {
         while let Some(timeout_msg) = receiver.next().await {
             let mut guard = event_processor.write().compat().await.unwrap();
-            guard.process_timeout_msg(timeout_msg, quorum_size).await;
+            guard.process_timeout_msg(timeout_msg).await;
         }
     }
 
@@ -308,6 +436,16 @@ impl<T: Payload> ChainedBftSMR<T> {
         }
     }
 
+    async fn process_account_state_chunk_retrievals(
+        mut receiver: channel::Receiver<AccountStateChunkRetrievalRequest>,
+        event_processor: ConcurrentEventProcessor<T>,
+    ) {
+        while let Some(request) = receiver.next().await {
+            let guard = event_processor.read().compat().await.unwrap();
+            guard.process_account_state_chunk_retrieval(request).await;
+        }
+    }
+
     fn start_event_processing(
         &self,
         event_processor: ConcurrentEventProcessor<T>,
@@ -317,6 +455,7 @@ impl<T: Payload> ChainedBftSMR<T> {
         network_receivers: NetworkReceivers<T>,
         pacemaker_timeout_sender_rx: channel::Receiver<Round>,
         winning_proposals_sender: channel::Sender<ProposalMsg<T>>,
+        block_store: Arc<BlockStore<T>>,
     ) {
         executor.spawn(
             Self::process_new_round_events(new_round_events_receiver, event_processor.clone())
@@ -338,10 +477,15 @@ impl<T: Payload> ChainedBftSMR<T> {
         );
 
         executor.spawn(
-            Self::process_winning_proposals(winning_proposals_receiver, event_processor.clone())
-                .boxed()
-                .unit_error()
-                .compat(),
+            Self::process_winning_proposals(
+                executor.clone(),
+                winning_proposals_receiver,
+                event_processor.clone(),
+                block_store,
+            )
+            .boxed()
+            .unit_error()
+            .compat(),
         );
 
         executor.spawn(
@@ -365,10 +509,9 @@ impl<T: Payload> ChainedBftSMR<T> {
         );
 
         executor.spawn(
-            Self::process_votes(
-                network_receivers.votes,
+            Self::process_account_state_chunk_retrievals(
+                network_receivers.account_state_chunk_retrieval,
                 event_processor.clone(),
-                self.quorum_size,
             )
             .boxed()
             .unit_error()
@@ -376,14 +519,17 @@ impl<T: Payload> ChainedBftSMR<T> {
         );
 
         executor.spawn(
-            Self::process_timeout_msg(
-                network_receivers.timeout_msgs,
-                event_processor.clone(),
-                self.quorum_size,
-            )
-            .boxed()
-            .unit_error()
-            .compat(),
+            Self::process_votes(network_receivers.votes, event_processor.clone())
+                .boxed()
+                .unit_error()
+                .compat(),
+        );
+
+        executor.spawn(
+            Self::process_timeout_msg(network_receivers.timeout_msgs, event_processor.clone())
+                .boxed()
+                .unit_error()
+                .compat(),
         );
 
         executor.spawn(
@@ -464,10 +610,11 @@ impl<T: Payload> StateMachineReplication for ChainedBftSMR<T> {
             let block_store = Arc::new(block_on(BlockStore::new(
                 Arc::clone(&self.storage),
                 initial_data,
-                signer,
+                signer.clone(),
                 Arc::clone(&state_computer),
                 true,
                 self.config.max_pruned_blocks_in_mem,
+                self.config.pipelined_execution,
             )));
 
             self.block_store = Some(Arc::clone(&block_store));
@@ -482,10 +629,8 @@ impl<T: Payload> StateMachineReplication for ChainedBftSMR<T> {
                 true,
             );
 
-            let safety_rules = Arc::new(RwLock::new(SafetyRules::new(
-                block_store.clone(),
-                consensus_state,
-            )));
+            let safety_rules =
+                self.create_safety_rules(block_store.clone(), consensus_state, signer);
 
             let (external_timeout_sender, external_timeout_receiver) =
                 channel::new(1_024, &counters::PENDING_PACEMAKER_TIMEOUTS);
@@ -502,14 +647,36 @@ impl<T: Payload> StateMachineReplication for ChainedBftSMR<T> {
                 external_timeout_sender,
             );
 
+            self.debug_info = Some(Arc::new(ChainedBftDebugInfo::new(
+                Arc::clone(&block_store),
+                Arc::clone(&pacemaker),
+                Arc::clone(&safety_rules),
+            )));
+
             let (winning_proposals_sender, winning_proposals_receiver) =
                 channel::new(1_024, &counters::PENDING_WINNING_PROPOSALS);
-            let proposer_election = self.create_proposer_election();
+            let proposer_type = self.config.proposer_type;
+            let contiguous_rounds = self.config.contiguous_rounds;
+            let vrf_private_key = self.vrf_private_key.clone();
+            let election_block_store = Arc::clone(&block_store);
+            let election_storage = Arc::clone(&self.storage);
+            let election_epoch_manager = Arc::clone(&self.epoch_manager);
             let event_processor = futures_locks::RwLock::new(EventProcessor::new(
                 self.author,
                 Arc::clone(&block_store),
                 Arc::clone(&pacemaker),
-                Arc::clone(&proposer_election),
+                Arc::clone(&self.epoch_manager),
+                Box::new(move |validators| {
+                    Self::create_proposer_election(
+                        proposer_type,
+                        contiguous_rounds,
+                        validators,
+                        election_epoch_manager.vrf_public_keys(),
+                        vrf_private_key.clone(),
+                        Arc::clone(&election_block_store),
+                        Arc::clone(&election_storage),
+                    )
+                }),
                 proposal_generator,
                 safety_rules,
                 state_computer,
@@ -528,6 +695,7 @@ impl<T: Payload> StateMachineReplication for ChainedBftSMR<T> {
                 network_receivers,
                 external_timeout_receiver,
                 winning_proposals_sender,
+                block_store,
             );
         } else {
             panic!("start called twice on the same Chained BFT SMR!");
@@ -7,8 +7,9 @@ use crate::{
         chained_bft_smr::{ChainedBftSMR, ChainedBftSMRConfig},
         common::Author,
         consensus_types::proposal_msg::ProposalMsg,
+        epoch_manager::EpochManager,
         network::ConsensusNetworkImpl,
-        network_tests::NetworkPlayground,
+        network_tests::{ByzantineBehavior, NetworkPlayground},
         safety::vote_msg::VoteMsg,
         test_utils::{MockStateComputer, MockStorage, MockTransactionManager, TestPayload},
     },
@@ -27,8 +28,15 @@ use crate::chained_bft::{
     persistent_storage::RecoveryData,
     test_utils::{consensus_runtime, with_smr_id},
 };
-use config::config::ConsensusProposerType::{self, FixedProposer, RotatingProposer};
-use std::{collections::HashMap, time::Duration};
+use config::config::{
+    ConsensusProposerType::{self, FixedProposer, RotatingProposer},
+    SafetyRulesConfig,
+};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 use tokio::runtime;
 use types::ledger_info::LedgerInfoWithSignatures;
 
@@ -37,11 +45,11 @@ struct SMRNode {
     author: Author,
     signer: ValidatorSigner<Ed25519PrivateKey>,
     validator: Arc<ValidatorVerifier<Ed25519PublicKey>>,
-    peers: Arc<Vec<Author>>,
-    proposer: Vec<Author>,
+    proposer_type: ConsensusProposerType,
     smr_id: usize,
     smr: ChainedBftSMR<TestPayload>,
     commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures<Ed25519Signature>>,
+    state_computer: Arc<MockStateComputer>,
     mempool: Arc<MockTransactionManager>,
     mempool_notif_receiver: mpsc::Receiver<usize>,
     storage: Arc<MockStorage<TestPayload>>,
@@ -49,12 +57,10 @@ struct SMRNode {
 
 impl SMRNode {
     fn start(
-        quorum_size: usize,
         playground: &mut NetworkPlayground,
         signer: ValidatorSigner<Ed25519PrivateKey>,
         validator: Arc<ValidatorVerifier<Ed25519PublicKey>>,
-        peers: Arc<Vec<Author>>,
-        proposer: Vec<Author>,
+        proposer_type: ConsensusProposerType,
         smr_id: usize,
         storage: Arc<MockStorage<TestPayload>>,
         initial_data: RecoveryData<TestPayload>,
@@ -71,12 +77,12 @@ impl SMRNode {
             .after_start(with_smr_id(signer.author().short_str()))
             .build()
             .expect("Failed to create Tokio runtime!");
+        let epoch_manager = Arc::new(EpochManager::new(0, Arc::clone(&validator)));
         let network = ConsensusNetworkImpl::new(
             author,
             network_sender,
             network_events,
-            Arc::clone(&peers),
-            Arc::clone(&validator),
+            Arc::clone(&epoch_manager),
         );
 
         let config = ChainedBftSMRConfig {
@@ -84,12 +90,15 @@ impl SMRNode {
             pacemaker_initial_timeout: Duration::from_secs(3),
             contiguous_rounds: 2,
             max_block_size: 50,
+            proposer_type,
+            safety_rules: SafetyRulesConfig::default(),
+            pipelined_execution: false,
         };
         let mut smr = ChainedBftSMR::new(
             author,
-            quorum_size,
             signer.clone(),
-            proposer.clone(),
+            None,
+            epoch_manager,
             network,
             runtime,
             config,
@@ -101,45 +110,51 @@ impl SMRNode {
         let mut mp = MockTransactionManager::new();
         let commit_receiver = mp.take_commit_receiver();
         let mempool = Arc::new(mp);
-        smr.start(
-            mempool.clone(),
-            Arc::new(MockStateComputer::new(commit_cb_sender.clone())),
-        )
-        .expect("Failed to start SMR!");
+        let state_computer = Arc::new(MockStateComputer::new(commit_cb_sender.clone()));
+        smr.start(mempool.clone(), state_computer.clone())
+            .expect("Failed to start SMR!");
         Self {
             author,
             signer,
             validator,
-            peers,
-            proposer,
+            proposer_type,
             smr_id,
             smr,
             commit_cb_receiver,
+            state_computer,
             mempool,
             mempool_notif_receiver: commit_receiver,
             storage,
         }
     }
 
-    fn restart(mut self, quorum_size: usize, playground: &mut NetworkPlayground) -> Self {
+    fn restart(mut self, playground: &mut NetworkPlayground) -> Self {
         self.smr.stop();
         let recover_data = self
             .storage
             .get_recovery_data()
             .unwrap_or_else(|e| panic!("fail to restart due to: {}", e));
         Self::start(
-            quorum_size,
             playground,
             self.signer,
             self.validator,
-            self.peers,
-            self.proposer,
+            self.proposer_type,
             self.smr_id + 10,
             self.storage,
             recover_data,
         )
     }
 
+    /// From now on, the messages sent by this node are altered by the playground according to
+    /// the given behaviors.
+    fn make_byzantine(
+        &self,
+        playground: &mut NetworkPlayground,
+        behaviors: Vec<ByzantineBehavior>,
+    ) {
+        playground.make_byzantine(self.author, self.signer.clone(), behaviors);
+    }
+
     fn start_num_nodes(
         num_nodes: usize,
         quorum_size: usize,
@@ -157,28 +172,20 @@ impl SMRNode {
             );
             signers.push(random_validator_signer);
         }
+        // The fixed proposer is the validator with the max author: make it the first node.
+        signers.sort_by_key(|signer| Reverse(signer.author()));
         let validator_verifier = Arc::new(
             ValidatorVerifier::new_with_quorum_size(author_to_public_keys, quorum_size)
                 .expect("Invalid quorum_size."),
         );
-        let peers: Arc<Vec<Author>> =
-            Arc::new(signers.iter().map(|signer| signer.author()).collect());
-        let proposer = {
-            match proposer_type {
-                FixedProposer => vec![peers[0]],
-                RotatingProposer => validator_verifier.get_ordered_account_addresses(),
-            }
-        };
         let mut nodes = vec![];
         for smr_id in 0..num_nodes {
             let (storage, initial_data) = MockStorage::start_for_testing();
             nodes.push(Self::start(
-                quorum_size,
                 playground,
                 signers.remove(0),
                 Arc::clone(&validator_verifier),
-                Arc::clone(&peers),
-                proposer.clone(),
+                proposer_type,
                 smr_id,
                 storage,
                 initial_data,
@@ -202,6 +209,38 @@ fn verify_finality_proof(
     }
 }
 
+/// Asserts that the blocks committed by the given nodes all lie on a single chain.
+fn assert_consistent_commits(nodes: &[&SMRNode]) {
+    let mut parents = HashMap::new();
+    for node in nodes {
+        parents.extend(node.state_computer.executed_blocks());
+    }
+    // The committed blocks with their ancestors executed by any of the nodes.
+    let committed: HashMap<_, _> = nodes
+        .iter()
+        .flat_map(|node| node.state_computer.committed_block_ids())
+        .map(|block_id| {
+            let mut ancestors = HashSet::new();
+            let mut ancestor = Some(block_id);
+            while let Some(id) = ancestor {
+                ancestors.insert(id);
+                ancestor = parents.get(&id).cloned();
+            }
+            (block_id, ancestors)
+        })
+        .collect();
+    for (block_id, ancestors) in &committed {
+        for (other_block_id, other_ancestors) in &committed {
+            assert!(
+                ancestors.contains(other_block_id) || other_ancestors.contains(block_id),
+                "conflicting commits of blocks {} and {}",
+                block_id,
+                other_block_id
+            );
+        }
+    }
+}
+
 #[test]
 /// Should receive a new proposal upon start
 fn basic_start_test() {
@@ -350,7 +389,7 @@ fn basic_commit_and_restart() {
     playground = NetworkPlayground::new(runtime.executor());
     nodes = nodes
         .into_iter()
-        .map(|node| node.restart(2, &mut playground))
+        .map(|node| node.restart(&mut playground))
         .collect();
 
     block_on(async {
@@ -794,3 +833,79 @@ fn chain_with_nil_blocks() {
         assert!(nodes[2].smr.block_store().unwrap().root().round() >= 1)
     });
 }
+
+#[test]
+/// A Byzantine node that proposes conflicting blocks and votes for all of them cannot make the
+/// honest nodes commit conflicting blocks, nor prevent them from committing.
+fn byzantine_equivocating_proposer_and_voter() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.executor());
+    let nodes = SMRNode::start_num_nodes(4, 3, &mut playground, RotatingProposer);
+    nodes[0].make_byzantine(
+        &mut playground,
+        vec![
+            ByzantineBehavior::EquivocatingProposer,
+            ByzantineBehavior::DoubleVoter,
+        ],
+    );
+    let honest_nodes: Vec<_> = nodes[1..].iter().collect();
+    block_on(async {
+        playground
+            .wait_for_messages(200, NetworkPlayground::take_all)
+            .await;
+    });
+    assert!(playground.num_equivocated_rounds() > 0);
+    let equivocating_voters = playground.equivocating_voters();
+    assert!(equivocating_voters.contains(&nodes[0].author));
+    for node in &honest_nodes {
+        assert!(!equivocating_voters.contains(&node.author));
+        assert!(node.smr.block_store().unwrap().root().round() > 0);
+    }
+    assert_consistent_commits(&honest_nodes);
+}
+
+#[test]
+/// The honest nodes keep committing while a Byzantine node never sends its votes.
+fn byzantine_vote_withholder() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.executor());
+    let nodes = SMRNode::start_num_nodes(4, 3, &mut playground, RotatingProposer);
+    nodes[3].make_byzantine(&mut playground, vec![ByzantineBehavior::VoteWithholder]);
+    let honest_nodes: Vec<_> = nodes[..3].iter().collect();
+    block_on(async {
+        let votes = playground
+            .wait_for_messages(30, NetworkPlayground::votes_only)
+            .await;
+        assert!(votes.iter().all(|(author, _)| *author != nodes[3].author));
+    });
+    assert!(playground.equivocating_voters().is_empty());
+    for node in &honest_nodes {
+        assert!(node.smr.block_store().unwrap().root().round() > 0);
+    }
+    assert_consistent_commits(&honest_nodes);
+}
+
+#[test]
+/// The proposals extending a forged quorum certificate are never accepted by the honest nodes.
+fn byzantine_bogus_parent_qc() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.executor());
+    let nodes = SMRNode::start_num_nodes(4, 3, &mut playground, RotatingProposer);
+    nodes[0].make_byzantine(&mut playground, vec![ByzantineBehavior::BogusParentQc]);
+    let honest_nodes: Vec<_> = nodes[1..].iter().collect();
+    block_on(async {
+        playground
+            .wait_for_messages(200, NetworkPlayground::take_all)
+            .await;
+    });
+    let bogus_proposals = playground.delivered_proposals_of(nodes[0].author);
+    assert!(!bogus_proposals.is_empty());
+    for node in &honest_nodes {
+        let block_store = node.smr.block_store().unwrap();
+        for block_id in &bogus_proposals {
+            assert!(block_store.get_block(*block_id).is_none());
+        }
+        assert!(block_store.root().round() > 0);
+    }
+    assert_consistent_commits(&honest_nodes);
+}
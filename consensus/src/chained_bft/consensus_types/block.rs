@@ -5,6 +5,7 @@ use crate::{
     chained_bft::{
         common::{Author, Height, Round},
         consensus_types::quorum_cert::QuorumCert,
+        epoch_manager::EpochManager,
         safety::vote_msg::VoteMsgVerificationError,
     },
     state_replication::ExecutedState,
@@ -31,7 +32,6 @@ use std::{
 use types::{
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     validator_signer::ValidatorSigner,
-    validator_verifier::ValidatorVerifier,
 };
 
 #[cfg(test)]
@@ -44,6 +44,8 @@ pub enum BlockVerificationError {
     InvalidBlockId,
     /// Round must not be smaller than height and should be higher than parent's round.
     InvalidBlockRound,
+    /// Epoch must not be smaller than the epoch of the QC carried by the block.
+    InvalidBlockEpoch,
     /// NIL block must not carry payload.
     NilBlockWithPayload,
     /// QC carried by the block does not certify its own parent.
@@ -52,6 +54,10 @@ pub enum BlockVerificationError {
     QCVerificationError(VoteMsgVerificationError),
     /// The signature verification of this block failed.
     SigVerifyError,
+    /// The validators of the epoch of this block are unknown.
+    UnknownEpoch(u64),
+    /// The VRF proof carried by this block is invalid (or carried by a NIL block).
+    InvalidVrfProof,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
@@ -77,6 +83,9 @@ pub struct Block<T> {
     parent_id: HashValue,
     /// T of the block (e.g. one or more transaction(s)
     payload: T,
+    /// The epoch of the validator set that proposed the block: an epoch ends once a block changing
+    /// the validator set is committed.
+    epoch: u64,
     /// The round of a block is an internal monotonically increasing counter used by Consensus
     /// protocol.
     round: Round,
@@ -105,6 +114,10 @@ pub struct Block<T> {
     quorum_cert: QuorumCert,
     /// If a block is a real proposal, contains its author and signature.
     block_source: BlockSource,
+    /// Proof that the author is the leader of the block round, for the proposer elections based
+    /// on a verifiable random function. It is part of the block id (and thus of the data signed
+    /// by the author and certified by the QCs), so that the randomness it carries is agreed on.
+    vrf_proof: Option<Vec<u8>>,
 }
 
 impl<T> Display for Block<T> {
@@ -116,8 +129,8 @@ impl<T> Display for Block<T> {
         };
         write!(
             f,
-            "[id: {}{}, round: {:02}, parent_id: {}]",
-            self.id, nil_marker, self.round, self.parent_id
+            "[id: {}{}, epoch: {}, round: {:02}, parent_id: {}]",
+            self.id, nil_marker, self.epoch, self.round, self.parent_id
         )
     }
 }
@@ -162,6 +175,7 @@ where
             id: genesis_id,
             payload: T::default(),
             parent_id: HashValue::zero(),
+            epoch: 0,
             round: 0,
             height: 0,
             timestamp_usecs: 0, // The beginning of UNIX TIME
@@ -170,6 +184,7 @@ where
                 author: genesis_validator_signer.author(),
                 signature,
             },
+            vrf_proof: None,
         }
     }
 
@@ -178,6 +193,7 @@ where
     pub fn new_internal(
         payload: T,
         parent_id: HashValue,
+        epoch: u64,
         round: Round,
         height: Height,
         timestamp_usecs: u64,
@@ -187,11 +203,13 @@ where
         let block_internal = BlockSerializer {
             parent_id,
             payload: &payload,
+            epoch,
             round,
             height,
             timestamp_usecs,
             quorum_cert: &quorum_cert,
             author: Some(validator_signer.author()),
+            vrf_proof: &None,
         };
 
         let id = block_internal.hash();
@@ -203,6 +221,7 @@ where
             id,
             payload,
             parent_id,
+            epoch,
             round,
             height,
             timestamp_usecs,
@@ -211,12 +230,14 @@ where
                 author: validator_signer.author(),
                 signature,
             },
+            vrf_proof: None,
         }
     }
 
     pub fn make_block(
         parent_block: &Block<T>,
         payload: T,
+        epoch: u64,
         round: Round,
         timestamp_usecs: u64,
         quorum_cert: QuorumCert,
@@ -225,9 +246,11 @@ where
         // A block must carry a QC to its parent.
         checked_precondition_eq!(quorum_cert.certified_block_id(), parent_block.id());
         checked_precondition!(round > parent_block.round());
+        checked_precondition!(epoch >= parent_block.epoch());
         Block::new_internal(
             payload,
             parent_block.id(),
+            epoch,
             round,
             // Height is always parent's height + 1 because it's just the position in the chain.
             parent_block.height() + 1,
@@ -239,9 +262,15 @@ where
 
     /// The NIL blocks are special: they're not carrying any real payload and are generated
     /// independently by different validators just to fill in the round with some QC.
-    pub fn make_nil_block(parent_block: &Block<T>, round: Round, quorum_cert: QuorumCert) -> Self {
+    pub fn make_nil_block(
+        parent_block: &Block<T>,
+        epoch: u64,
+        round: Round,
+        quorum_cert: QuorumCert,
+    ) -> Self {
         checked_precondition_eq!(quorum_cert.certified_block_id(), parent_block.id());
         checked_precondition!(round > parent_block.round());
+        checked_precondition!(epoch >= parent_block.epoch());
 
         let payload = T::default();
         // We want all the NIL blocks to agree on the timestamps even though they're generated
@@ -251,6 +280,7 @@ where
         let block_serializer = BlockSerializer {
             parent_id: parent_block.id(),
             payload: &payload,
+            epoch,
             round,
             height: parent_block.height() + 1,
             timestamp_usecs,
@@ -258,6 +288,7 @@ where
             // the author here doesn't really matter for as long as all the NIL Blocks are hashing
             // the same value, hence use the special genesis author for hashing.
             author: None,
+            vrf_proof: &None,
         };
 
         let id = block_serializer.hash();
@@ -266,21 +297,55 @@ where
             id,
             payload,
             parent_id: parent_block.id(),
+            epoch,
             round,
             height: parent_block.height() + 1,
             timestamp_usecs,
             quorum_cert,
             block_source: BlockSource::NilBlock,
+            vrf_proof: None,
         }
     }
 
+    /// Attaches the proof that the author is the leader of the block round: the proof is part of
+    /// the block id, which is signed again by the author.
+    pub fn with_vrf_proof(
+        mut self,
+        vrf_proof: Vec<u8>,
+        validator_signer: &ValidatorSigner<Ed25519PrivateKey>,
+    ) -> Self {
+        checked_precondition_eq!(self.author(), Some(validator_signer.author()));
+        self.vrf_proof = Some(vrf_proof);
+        self.id = self.hash();
+        let signature = validator_signer
+            .sign_message(self.id)
+            .expect("Failed to sign message");
+        self.block_source = BlockSource::Proposal {
+            author: validator_signer.author(),
+            signature,
+        };
+        self
+    }
+
+    /// Replaces the signature of the author of the block (e.g., with the signature of the block
+    /// id produced by the remote safety rules).
+    pub fn with_signature(mut self, signature: Ed25519Signature) -> Self {
+        let author = self
+            .author()
+            .expect("Only the proposed blocks carry a signature");
+        self.block_source = BlockSource::Proposal { author, signature };
+        self
+    }
+
     pub fn get_payload(&self) -> &T {
         &self.payload
     }
 
+    /// Verifies the block against the validators of its epoch and its quorum certificate against
+    /// the validators of the epoch it was formed in.
     pub fn verify(
         &self,
-        validator: &ValidatorVerifier<Ed25519PublicKey>,
+        epoch_manager: &EpochManager,
     ) -> ::std::result::Result<(), BlockVerificationError> {
         if self.is_genesis_block() {
             return Ok(());
@@ -296,16 +361,29 @@ where
         {
             return Err(BlockVerificationError::InvalidBlockRound);
         }
+        if self.quorum_cert().epoch() > self.epoch() {
+            return Err(BlockVerificationError::InvalidBlockEpoch);
+        }
         if let BlockSource::Proposal { author, signature } = &self.block_source {
-            validator
+            epoch_manager
+                .validators_for_epoch(self.epoch())
+                .ok_or_else(|| BlockVerificationError::UnknownEpoch(self.epoch()))?
                 .verify_signature(*author, self.hash(), signature)
                 .map_err(|_| BlockVerificationError::SigVerifyError)?;
+            if let Some(vrf_proof) = &self.vrf_proof {
+                if !epoch_manager.verify_vrf_proof(*author, &self.quorum_cert, vrf_proof) {
+                    return Err(BlockVerificationError::InvalidVrfProof);
+                }
+            }
         } else if self.payload != T::default() {
             // NIL block must not carry payload
             return Err(BlockVerificationError::NilBlockWithPayload);
+        } else if self.vrf_proof.is_some() {
+            // NIL block isn't proposed by anyone
+            return Err(BlockVerificationError::InvalidVrfProof);
         }
-        self.quorum_cert
-            .verify(validator)
+        epoch_manager
+            .verify_quorum_cert(&self.quorum_cert)
             .map_err(BlockVerificationError::QCVerificationError)
     }
 
@@ -321,6 +399,10 @@ where
         self.height
     }
 
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
     pub fn round(&self) -> Round {
         self.round
     }
@@ -349,10 +431,15 @@ where
         }
     }
 
+    pub fn vrf_proof(&self) -> Option<&[u8]> {
+        self.vrf_proof.as_ref().map(Vec::as_slice)
+    }
+
     pub fn is_genesis_block(&self) -> bool {
         self.id() == *GENESIS_BLOCK_ID
             && self.payload == T::default()
             && self.parent_id == HashValue::zero()
+            && self.epoch == 0
             && self.round == 0
             && self.height == 0
             && self.timestamp_usecs == 0
@@ -378,11 +465,13 @@ where
         let block_internal = BlockSerializer {
             parent_id: self.parent_id,
             payload: &self.payload,
+            epoch: self.epoch,
             round: self.round,
             height: self.height,
             timestamp_usecs: self.timestamp_usecs,
             quorum_cert: &self.quorum_cert,
             author,
+            vrf_proof: &self.vrf_proof,
         };
         block_internal.hash()
     }
@@ -393,11 +482,13 @@ where
 struct BlockSerializer<'a, T> {
     parent_id: HashValue,
     payload: &'a T,
+    epoch: u64,
     round: Round,
     height: Height,
     timestamp_usecs: u64,
     quorum_cert: &'a QuorumCert,
     author: Option<Author>,
+    vrf_proof: &'a Option<Vec<u8>>,
 }
 
 impl<'a, T> CryptoHash for BlockSerializer<'a, T>
@@ -422,12 +513,17 @@ where
     fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
         serializer
             .encode_u64(self.timestamp_usecs)?
+            .encode_u64(self.epoch)?
             .encode_u64(self.round)?
             .encode_u64(self.height)?
             .encode_struct(self.payload)?
             .encode_raw_bytes(self.parent_id.as_ref())?
             .encode_raw_bytes(self.quorum_cert.certified_block_id().as_ref())?
             .encode_optional(&self.author)?;
+        // Only appended when present so that the ids of the blocks without VRF proof are unchanged.
+        if let Some(vrf_proof) = self.vrf_proof {
+            serializer.encode_variable_length_bytes(vrf_proof)?;
+        }
         Ok(())
     }
 }
@@ -459,6 +555,7 @@ where
                 .expect("fail to serialize payload")
                 .into(),
         );
+        proto.set_epoch(self.epoch());
         proto.set_round(self.round());
         proto.set_height(self.height());
         proto.set_quorum_cert(self.quorum_cert().clone().into_proto());
@@ -466,6 +563,9 @@ where
             proto.set_signature(signature.to_bytes().as_ref().into());
             proto.set_author(author.into());
         }
+        if let Some(vrf_proof) = self.vrf_proof {
+            proto.set_vrf_proof(vrf_proof.into());
+        }
         proto
     }
 }
@@ -481,6 +581,7 @@ where
         let parent_id = HashValue::from_slice(object.get_parent_id())?;
         let payload = from_slice(object.get_payload())?;
         let timestamp_usecs = object.get_timestamp_usecs();
+        let epoch = object.get_epoch();
         let round = object.get_round();
         let height = object.get_height();
         let quorum_cert = QuorumCert::from_proto(object.take_quorum_cert())?;
@@ -492,15 +593,22 @@ where
                 signature: Ed25519Signature::try_from(object.get_signature())?,
             }
         };
+        let vrf_proof = if object.get_vrf_proof().is_empty() {
+            None
+        } else {
+            Some(object.get_vrf_proof().to_vec())
+        };
         Ok(Block {
             id,
             parent_id,
             payload,
+            epoch,
             round,
             timestamp_usecs,
             height,
             quorum_cert,
             block_source,
+            vrf_proof,
         })
     }
 }
@@ -7,6 +7,7 @@ use crate::chained_bft::{
         block::{Block, BlockSource},
         quorum_cert::QuorumCert,
     },
+    epoch_manager::EpochManager,
     test_utils::placeholder_certificate_for_block,
 };
 
@@ -44,7 +45,7 @@ prop_compose! {
     ) -> Block<Vec<usize>> {
         Block::new_internal(
             vec![payload],
-            parent_id,
+            parent_id, 0,
             round,
             height,
             get_current_timestamp().as_micros() as u64,
@@ -91,6 +92,7 @@ prop_compose! {
                 timestamp_usecs: get_current_timestamp().as_micros() as u64,
                 id: fake_id,
                 payload: block.get_payload().clone(),
+                epoch: block.epoch(),
                 round: block.round(),
                 height: block.height(),
                 parent_id: block.parent_id(),
@@ -99,6 +101,7 @@ prop_compose! {
                     author: block.author().unwrap(),
                     signature: block.signature().unwrap().clone(),
                 },
+                vrf_proof: None,
             }
         }
 }
@@ -201,7 +204,7 @@ fn test_nil_block() {
     let genesis_block = Block::make_genesis_block();
     let quorum_cert = QuorumCert::certificate_for_genesis();
 
-    let nil_block = Block::make_nil_block(&genesis_block, 1, quorum_cert);
+    let nil_block = Block::make_nil_block(&genesis_block, 0, 1, quorum_cert);
     assert_eq!(
         nil_block.quorum_cert().certified_block_id(),
         genesis_block.id()
@@ -212,7 +215,8 @@ fn test_nil_block() {
     assert!(nil_block.author().is_none());
 
     let dummy_verifier = Arc::new(ValidatorVerifier::<Ed25519PublicKey>::new(HashMap::new()));
-    assert!(nil_block.verify(dummy_verifier.as_ref()).is_ok());
+    let epoch_manager = EpochManager::new(0, dummy_verifier);
+    assert!(nil_block.verify(&epoch_manager).is_ok());
 
     let signer = ValidatorSigner::random(None);
     let payload = 101;
@@ -228,6 +232,7 @@ fn test_nil_block() {
     let nil_block_child = Block::make_block(
         &nil_block,
         payload,
+        0,
         2,
         get_current_timestamp().as_micros() as u64,
         nil_block_qc.clone(),
@@ -248,6 +253,7 @@ fn test_block_relation() {
     let next_block = Block::make_block(
         &genesis_block,
         payload,
+        0,
         1,
         get_current_timestamp().as_micros() as u64,
         quorum_cert,
@@ -278,6 +284,7 @@ fn test_block_qc() {
     let a1 = Block::make_block(
         &genesis_block,
         payload,
+        0,
         1,
         get_current_timestamp().as_micros() as u64,
         genesis_qc.clone(),
@@ -298,6 +305,7 @@ fn test_block_qc() {
         Block::make_block(
             &a1,
             payload,
+            0,
             2,
             get_current_timestamp().as_micros() as u64,
             genesis_qc.clone(),
@@ -310,6 +318,7 @@ fn test_block_qc() {
     let a2 = Block::make_block(
         &a1,
         payload,
+        0,
         2,
         get_current_timestamp().as_micros() as u64,
         a1_qc.clone(),
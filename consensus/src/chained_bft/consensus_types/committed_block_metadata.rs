@@ -0,0 +1,49 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Payload, Round},
+    consensus_types::block::Block,
+};
+use serde::{Deserialize, Serialize};
+
+/// The part of a committed block that outlives the block in the block tree: who proposed it and
+/// who signed the QC it carries. It is persisted when the block is committed so that the
+/// reputation of the validators is computed from the same committed history on every replica,
+/// regardless of how many blocks each of them keeps in memory.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct CommittedBlockMetadata {
+    round: Round,
+    author: Option<Author>,
+    signers: Vec<Author>,
+}
+
+impl CommittedBlockMetadata {
+    pub fn new<T: Payload>(block: &Block<T>) -> Self {
+        let mut signers: Vec<_> = block
+            .quorum_cert()
+            .ledger_info()
+            .signatures()
+            .keys()
+            .cloned()
+            .collect();
+        signers.sort();
+        Self {
+            round: block.round(),
+            author: block.author(),
+            signers,
+        }
+    }
+
+    pub fn round(&self) -> Round {
+        self.round
+    }
+
+    pub fn author(&self) -> Option<Author> {
+        self.author
+    }
+
+    pub fn signers(&self) -> &[Author] {
+        &self.signers
+    }
+}
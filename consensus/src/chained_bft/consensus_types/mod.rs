@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub(crate) mod block;
+pub(crate) mod committed_block_metadata;
 pub(crate) mod proposal_msg;
 pub(crate) mod quorum_cert;
 pub(crate) mod sync_info;
@@ -4,13 +4,12 @@
 use crate::chained_bft::{
     common::{Author, Payload},
     consensus_types::{block::Block, sync_info::SyncInfo},
+    epoch_manager::EpochManager,
 };
 use failure::prelude::*;
 use network::proto::Proposal as ProtoProposal;
-use nextgen_crypto::ed25519::*;
 use proto_conv::{FromProto, IntoProto};
 use std::fmt;
-use types::validator_verifier::ValidatorVerifier;
 
 /// ProposalMsg contains the required information for the proposer election protocol to make its
 /// choice (typically depends on round and proposer info).
@@ -21,12 +20,12 @@ pub struct ProposalMsg<T> {
 }
 
 impl<T: Payload> ProposalMsg<T> {
-    pub fn verify(&self, validator: &ValidatorVerifier<Ed25519PublicKey>) -> Result<()> {
+    pub fn verify(&self, epoch_manager: &EpochManager) -> Result<()> {
         if self.proposal.is_nil_block() {
             return Err(format_err!("Proposal {} for a NIL block", self.proposal));
         }
         self.proposal
-            .verify(validator)
+            .verify(epoch_manager)
             .map_err(|e| format_err!("{:?}", e))?;
         ensure!(
             self.proposal.round() > 0,
@@ -42,7 +41,11 @@ impl<T: Payload> ProposalMsg<T> {
         let previous_round = self.proposal.round() - 1;
         if let Some(tc) = self.sync_info.highest_timeout_certificate() {
             let previous_round = self.proposal.round() - 1;
-            tc.verify(validator).map_err(|e| format_err!("{:?}", e))?;
+            let validator = epoch_manager
+                .validators_for_epoch(self.proposal.epoch())
+                .ok_or_else(|| format_err!("Unknown epoch {}", self.proposal.epoch()))?;
+            tc.verify(validator.as_ref())
+                .map_err(|e| format_err!("{:?}", e))?;
             ensure!(
                 tc.round() == previous_round,
                 "Proposal for {} has a timeout certificate with an incorrect round={}",
@@ -68,9 +71,8 @@ impl<T: Payload> ProposalMsg<T> {
                 self.proposal
             ));
         }
-        self.sync_info
-            .highest_ledger_info()
-            .verify(validator)
+        epoch_manager
+            .verify_quorum_cert(self.sync_info.highest_ledger_info())
             .map_err(|e| format_err!("{:?}", e))?;
 
         Ok(())
@@ -96,6 +96,11 @@ impl QuorumCert {
         &self.signed_ledger_info
     }
 
+    /// The epoch of the validators that formed this quorum certificate
+    pub fn epoch(&self) -> u64 {
+        self.ledger_info().ledger_info().epoch_num()
+    }
+
     pub fn certified_parent_block_id(&self) -> HashValue {
         self.certified_parent_block_id
     }
@@ -2,16 +2,14 @@ use crate::chained_bft::consensus_types::{
     quorum_cert::QuorumCert, timeout_msg::PacemakerTimeoutCertificate,
 };
 use network;
-use nextgen_crypto::ed25519::*;
 
 use crate::chained_bft::{
     consensus_types::timeout_msg::PacemakerTimeoutCertificateVerificationError,
-    safety::vote_msg::VoteMsgVerificationError,
+    epoch_manager::EpochManager, safety::vote_msg::VoteMsgVerificationError,
 };
 use proto_conv::{FromProto, IntoProto};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
-use types::validator_verifier::ValidatorVerifier;
 
 #[derive(Deserialize, Serialize, Clone, Debug, Eq, PartialEq)]
 /// This struct describes basic synchronization metadata.
@@ -87,14 +85,13 @@ impl SyncInfo {
         self.highest_timeout_cert.as_ref()
     }
 
-    pub fn verify(
-        &self,
-        validator: &ValidatorVerifier<Ed25519PublicKey>,
-    ) -> Result<(), SyncInfoVerificationError> {
-        self.highest_quorum_cert.verify(validator)?;
-        self.highest_ledger_info.verify(validator)?;
+    /// The quorum certificates are verified against the validators of the epochs they were
+    /// formed in, the timeout certificate against the validators of the current epoch.
+    pub fn verify(&self, epoch_manager: &EpochManager) -> Result<(), SyncInfoVerificationError> {
+        epoch_manager.verify_quorum_cert(&self.highest_quorum_cert)?;
+        epoch_manager.verify_quorum_cert(&self.highest_ledger_info)?;
         if let Some(tc) = &self.highest_timeout_cert {
-            tc.verify(validator)?;
+            tc.verify(epoch_manager.validators().as_ref())?;
         }
         Ok(())
     }
@@ -93,7 +93,24 @@ impl PacemakerTimeout {
         }
     }
 
-    fn pacemaker_timeout_digest(author: AccountAddress, round: Round) -> HashValue {
+    /// Creates a PacemakerTimeout out of a signature of its digest by the author (e.g., by
+    /// remote safety rules).
+    pub fn new_with_signature(
+        round: Round,
+        author: Author,
+        signature: Ed25519Signature,
+        vote: Option<VoteMsg>,
+    ) -> Self {
+        PacemakerTimeout {
+            round,
+            author,
+            signature,
+            vote,
+        }
+    }
+
+    /// The digest signed by the author of a PacemakerTimeout for the given round.
+    pub fn pacemaker_timeout_digest(author: AccountAddress, round: Round) -> HashValue {
         PacemakerTimeoutSerializer { round, author }.hash()
     }
 
@@ -230,7 +247,23 @@ impl TimeoutMsg {
         }
     }
 
-    fn new_round_digest(pacemaker_timeout_digest: HashValue) -> HashValue {
+    /// Creates a TimeoutMsg out of a signature of its digest by the author of the pacemaker
+    /// timeout (e.g., by remote safety rules).
+    pub fn new_with_signature(
+        sync_info: SyncInfo,
+        pacemaker_timeout: PacemakerTimeout,
+        signature: Ed25519Signature,
+    ) -> TimeoutMsg {
+        TimeoutMsg {
+            sync_info,
+            pacemaker_timeout,
+            signature,
+        }
+    }
+
+    /// The digest signed by the author of a TimeoutMsg carrying a PacemakerTimeout with the given
+    /// digest.
+    pub fn new_round_digest(pacemaker_timeout_digest: HashValue) -> HashValue {
         TimeoutMsgSerializer {
             pacemaker_timeout_digest,
         }
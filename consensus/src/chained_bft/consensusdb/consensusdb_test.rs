@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
+use nextgen_crypto::ed25519::Ed25519PrivateKey;
 use tempfile::tempdir;
+use types::{account_address::AccountAddress, validator_signer::ValidatorSigner};
 
 #[test]
 fn test_put_get() {
@@ -53,3 +55,60 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_blocks::<i64>().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
 }
+
+#[test]
+fn test_get_committed_blocks() {
+    let tmp_dir = tempdir().unwrap();
+    let db = ConsensusDB::new(&tmp_dir);
+    assert!(db.get_committed_blocks(10, 10).unwrap().is_empty());
+
+    let genesis = Block::<i64>::make_genesis_block();
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0u8; 32]);
+    let committed_blocks: Vec<_> = [1, 2, 4, 7]
+        .iter()
+        .map(|round| {
+            CommittedBlockMetadata::new(&Block::make_block(
+                &genesis,
+                0,
+                0,
+                *round,
+                1,
+                QuorumCert::certificate_for_genesis(),
+                &signer,
+            ))
+        })
+        .collect();
+    db.save_committed_blocks(committed_blocks.clone()).unwrap();
+
+    let rounds = |max_round, limit| -> Vec<Round> {
+        db.get_committed_blocks(max_round, limit)
+            .unwrap()
+            .iter()
+            .map(CommittedBlockMetadata::round)
+            .collect()
+    };
+    assert_eq!(rounds(10, 10), vec![7, 4, 2, 1]);
+    assert_eq!(rounds(6, 10), vec![4, 2, 1]);
+    assert_eq!(rounds(6, 2), vec![4, 2]);
+    assert_eq!(rounds(0, 10), Vec::<Round>::new());
+    assert_eq!(
+        db.get_committed_blocks(7, 1).unwrap(),
+        vec![committed_blocks[3].clone()]
+    );
+}
+
+#[test]
+fn test_put_delete_txn_batches() {
+    let tmp_dir = tempdir().unwrap();
+    let db = ConsensusDB::new(&tmp_dir);
+    assert!(db.get_txn_batches().unwrap().is_empty());
+
+    let a = TxnBatch::new(AccountAddress::random(), vec![]);
+    let b = TxnBatch::new(AccountAddress::random(), vec![]);
+    db.save_txn_batch(&a).unwrap();
+    db.save_txn_batch(&b).unwrap();
+    assert_eq!(db.get_txn_batches().unwrap().len(), 2);
+
+    db.delete_txn_batches(vec![a.digest()]).unwrap();
+    assert_eq!(db.get_txn_batches().unwrap(), vec![b]);
+}
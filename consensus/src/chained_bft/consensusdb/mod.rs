@@ -5,19 +5,28 @@
 mod consensusdb_test;
 mod schema;
 
-use crate::chained_bft::{
-    common::Payload,
-    consensus_types::{block::Block, quorum_cert::QuorumCert},
-    consensusdb::schema::{
-        block::BlockSchema,
-        quorum_certificate::QCSchema,
-        single_entry::{SingleEntryKey, SingleEntrySchema},
+use crate::{
+    batch_manager::TxnBatch,
+    chained_bft::{
+        common::{Payload, Round},
+        consensus_types::{
+            block::Block, committed_block_metadata::CommittedBlockMetadata, quorum_cert::QuorumCert,
+        },
+        consensusdb::schema::{
+            block::BlockSchema,
+            committed_block::CommittedBlockSchema,
+            quorum_certificate::QCSchema,
+            single_entry::{SingleEntryKey, SingleEntrySchema},
+            txn_batch::TxnBatchSchema,
+        },
     },
 };
 use crypto::HashValue;
 use failure::prelude::*;
 use logger::prelude::*;
-use schema::{BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
+use schema::{
+    BLOCK_CF_NAME, COMMITTED_BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME, TXN_BATCH_CF_NAME,
+};
 use schemadb::{
     ColumnFamilyOptions, ColumnFamilyOptionsMap, ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME,
 };
@@ -25,6 +34,7 @@ use std::{collections::HashMap, iter::Iterator, path::Path, time::Instant};
 
 type HighestTimeoutCertificates = Vec<u8>;
 type ConsensusStateData = Vec<u8>;
+type LastProposedRoundData = Vec<u8>;
 
 pub struct ConsensusDB {
     db: DB,
@@ -38,8 +48,10 @@ impl ConsensusDB {
                 ColumnFamilyOptions::default(),
             ),
             (BLOCK_CF_NAME, ColumnFamilyOptions::default()),
+            (COMMITTED_BLOCK_CF_NAME, ColumnFamilyOptions::default()),
             (QC_CF_NAME, ColumnFamilyOptions::default()),
             (SINGLE_ENTRY_CF_NAME, ColumnFamilyOptions::default()),
+            (TXN_BATCH_CF_NAME, ColumnFamilyOptions::default()),
         ]
         .iter()
         .cloned()
@@ -108,6 +120,12 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    pub fn save_last_proposed_round(&self, round: LastProposedRoundData) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<SingleEntrySchema>(&SingleEntryKey::LastProposedRound, &round)?;
+        self.commit(batch)
+    }
+
     pub fn save_blocks_and_quorum_certificates<T: Payload>(
         &self,
         block_data: Vec<Block<T>>,
@@ -145,6 +163,69 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    pub fn save_committed_blocks(
+        &self,
+        committed_blocks: Vec<CommittedBlockMetadata>,
+    ) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        committed_blocks
+            .iter()
+            .map(|metadata| batch.put::<CommittedBlockSchema>(&metadata.round(), metadata))
+            .collect::<Result<()>>()?;
+        self.commit(batch)
+    }
+
+    /// Get the metadata of at most `limit` committed blocks whose round is not higher than
+    /// `max_round`, the most recent first.
+    pub fn get_committed_blocks(
+        &self,
+        max_round: Round,
+        limit: usize,
+    ) -> Result<Vec<CommittedBlockMetadata>> {
+        let mut committed_blocks = Vec::new();
+        let mut seek_round = max_round;
+        let mut iter = self
+            .db
+            .iter::<CommittedBlockSchema>(ReadOptions::default())?;
+        while committed_blocks.len() < limit {
+            iter.seek_for_prev(&seek_round)?;
+            match iter.next().transpose()? {
+                Some((round, metadata)) if round <= seek_round => {
+                    committed_blocks.push(metadata);
+                    if round == 0 {
+                        break;
+                    }
+                    seek_round = round - 1;
+                }
+                _ => break,
+            }
+        }
+        Ok(committed_blocks)
+    }
+
+    pub fn save_txn_batch(&self, txn_batch: &TxnBatch) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<TxnBatchSchema>(&txn_batch.digest(), txn_batch)?;
+        self.commit(batch)
+    }
+
+    pub fn delete_txn_batches(&self, digests: Vec<HashValue>) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        digests
+            .iter()
+            .map(|digest| batch.delete::<TxnBatchSchema>(digest))
+            .collect::<Result<_>>()?;
+        self.commit(batch)
+    }
+
+    /// Get all the batches of transactions.
+    pub fn get_txn_batches(&self) -> Result<Vec<TxnBatch>> {
+        let mut iter = self.db.iter::<TxnBatchSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        iter.map(|res| res.map(|(_digest, txn_batch)| txn_batch))
+            .collect()
+    }
+
     /// Write the whole schema batch including all data necessary to mutate the ledger
     /// state of some transaction by leveraging rocksdb atomicity support.
     fn commit(&self, batch: SchemaBatch) -> Result<()> {
@@ -158,11 +239,17 @@ impl ConsensusDB {
     }
 
     /// Get latest consensus state (we only store the latest state).
-    fn get_state(&self) -> Result<Option<Vec<u8>>> {
+    pub fn get_state(&self) -> Result<Option<Vec<u8>>> {
         self.db
             .get::<SingleEntrySchema>(&SingleEntryKey::ConsensusState)
     }
 
+    /// Get the round of the last proposal signed by the safety rules.
+    pub fn get_last_proposed_round(&self) -> Result<Option<Vec<u8>>> {
+        self.db
+            .get::<SingleEntrySchema>(&SingleEntryKey::LastProposedRound)
+    }
+
     /// Get all consensus blocks.
     fn get_blocks<T: Payload>(&self) -> Result<HashMap<HashValue, Block<T>>> {
         let mut iter = self.db.iter::<BlockSchema<T>>(ReadOptions::default())?;
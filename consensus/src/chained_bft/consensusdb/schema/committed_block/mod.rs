@@ -0,0 +1,55 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the metadata of the committed blocks.
+//!
+//! Serialized committed block metadata identified by the round of the block. The round is
+//! serialized in big endian so that the committed blocks are ordered by round.
+//! ```text
+//! |<--key-->|<--------value-------->|
+//! |  round  | CommittedBlockMetadata |
+//! ```
+
+use super::{ensure_slice_len_eq, COMMITTED_BLOCK_CF_NAME};
+use crate::chained_bft::{
+    common::Round, consensus_types::committed_block_metadata::CommittedBlockMetadata,
+};
+use byteorder::{BigEndian, ReadBytesExt};
+use failure::prelude::*;
+use rmp_serde::{from_slice, to_vec_named};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(
+    CommittedBlockSchema,
+    Round,
+    CommittedBlockMetadata,
+    COMMITTED_BLOCK_CF_NAME
+);
+
+impl KeyCodec<CommittedBlockSchema> for Round {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Round>())?;
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
+impl ValueCodec<CommittedBlockSchema> for CommittedBlockMetadata {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(to_vec_named(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(from_slice(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
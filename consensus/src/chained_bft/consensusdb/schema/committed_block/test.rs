@@ -0,0 +1,12 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::chained_bft::consensus_types::block::Block;
+use schemadb::schema::assert_encode_decode;
+
+#[test]
+fn test_encode_decode() {
+    let metadata = CommittedBlockMetadata::new(&Block::<i64>::make_genesis_block());
+    assert_encode_decode::<CommittedBlockSchema>(&metadata.round(), &metadata);
+}
@@ -2,15 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub(crate) mod block;
+pub(crate) mod committed_block;
 pub(crate) mod quorum_certificate;
 pub(crate) mod single_entry;
+pub(crate) mod txn_batch;
 
 use failure::prelude::*;
 use schemadb::ColumnFamilyName;
 
 pub(super) const BLOCK_CF_NAME: ColumnFamilyName = "block";
+pub(super) const COMMITTED_BLOCK_CF_NAME: ColumnFamilyName = "committed_block";
 pub(super) const QC_CF_NAME: ColumnFamilyName = "quorum_certificate";
 pub(super) const SINGLE_ENTRY_CF_NAME: ColumnFamilyName = "single_entry";
+pub(super) const TXN_BATCH_CF_NAME: ColumnFamilyName = "txn_batch";
 
 fn ensure_slice_len_eq(data: &[u8], len: usize) -> Result<()> {
     ensure!(
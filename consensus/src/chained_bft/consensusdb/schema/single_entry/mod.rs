@@ -35,6 +35,8 @@ pub enum SingleEntryKey {
     ConsensusState = 0,
     // Used to store the highest timeout certificates
     HighestTimeoutCertificates = 1,
+    // Used to store the round of the last proposal signed by the safety rules
+    LastProposedRound = 2,
 }
 
 impl KeyCodec<SingleEntrySchema> for SingleEntryKey {
@@ -0,0 +1,46 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the batches of transactions disseminated
+//! ahead of the proposals.
+//!
+//! Serialized batch bytes identified by the digest of the batch.
+//! ```text
+//! |<--key-->|<--value-->|
+//! |  digest |  TxnBatch |
+//! ```
+
+use super::TXN_BATCH_CF_NAME;
+use crate::batch_manager::TxnBatch;
+use crypto::HashValue;
+use failure::prelude::*;
+use proto_conv::{FromProtoBytes, IntoProtoBytes};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+
+define_schema!(TxnBatchSchema, HashValue, TxnBatch, TXN_BATCH_CF_NAME);
+
+impl KeyCodec<TxnBatchSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<TxnBatchSchema> for TxnBatch {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.clone().into_proto_bytes()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::from_proto_bytes(data)
+    }
+}
+
+#[cfg(test)]
+mod test;
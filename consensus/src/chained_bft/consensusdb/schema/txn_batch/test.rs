@@ -0,0 +1,12 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use schemadb::schema::assert_encode_decode;
+use types::account_address::AccountAddress;
+
+#[test]
+fn test_encode_decode() {
+    let batch = TxnBatch::new(AccountAddress::random(), vec![]);
+    assert_encode_decode::<TxnBatchSchema>(&batch.digest(), &batch);
+}
@@ -0,0 +1,125 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    block_storage::{BlockReader, BlockStore},
+    common::Payload,
+    consensus_types::{
+        block::Block, quorum_cert::QuorumCert, timeout_msg::PacemakerTimeoutCertificate,
+    },
+    liveness::pacemaker::Pacemaker,
+    safety::t_safety_rules::TSafetyRules,
+};
+use debug_interface::{
+    node_debug_service::ConsensusDebugInfo,
+    proto::node_debug_interface::{
+        BlockSummary, DumpConsensusStateResponse, QuorumCertSummary, TimeoutCertificateSummary,
+    },
+};
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+#[cfg(test)]
+#[path = "debug_info_test.rs"]
+mod debug_info_test;
+
+/// Dumps the state of a running chained BFT consensus for the debug interface: the block tree
+/// and the highest certificates known by the block store, the round of the pacemaker and the
+/// consensus state of the safety rules.
+pub struct ChainedBftDebugInfo<T> {
+    block_store: Arc<BlockStore<T>>,
+    pacemaker: Arc<dyn Pacemaker>,
+    safety_rules: Arc<RwLock<dyn TSafetyRules<T> + Send + Sync>>,
+}
+
+impl<T: Payload> ChainedBftDebugInfo<T> {
+    pub fn new(
+        block_store: Arc<BlockStore<T>>,
+        pacemaker: Arc<dyn Pacemaker>,
+        safety_rules: Arc<RwLock<dyn TSafetyRules<T> + Send + Sync>>,
+    ) -> Self {
+        Self {
+            block_store,
+            pacemaker,
+            safety_rules,
+        }
+    }
+
+    fn block_summary(block: &Block<T>) -> BlockSummary {
+        let mut summary = BlockSummary::new();
+        summary.set_id(block.id().to_vec());
+        summary.set_parent_id(block.parent_id().to_vec());
+        summary.set_round(block.round());
+        summary.set_height(block.height());
+        if let Some(author) = block.author() {
+            summary.set_author(author.to_vec());
+        }
+        summary.set_timestamp_usecs(block.timestamp_usecs());
+        summary
+    }
+
+    fn quorum_cert_summary(qc: &QuorumCert) -> QuorumCertSummary {
+        let mut summary = QuorumCertSummary::new();
+        summary.set_certified_block_id(qc.certified_block_id().to_vec());
+        summary.set_certified_block_round(qc.certified_block_round());
+        if let Some(committed_block_id) = qc.committed_block_id() {
+            summary.set_committed_block_id(committed_block_id.to_vec());
+        }
+        summary.set_version(qc.ledger_info().ledger_info().version());
+        summary.set_num_signatures(qc.ledger_info().signatures().len() as u32);
+        summary
+    }
+
+    fn timeout_certificate_summary(tc: &PacemakerTimeoutCertificate) -> TimeoutCertificateSummary {
+        let mut summary = TimeoutCertificateSummary::new();
+        summary.set_round(tc.round());
+        summary.set_num_timeouts(tc.timeouts().len() as u32);
+        summary
+    }
+}
+
+impl<T: Payload> ConsensusDebugInfo for ChainedBftDebugInfo<T> {
+    fn dump_consensus_state(&self) -> DumpConsensusStateResponse {
+        let mut response = DumpConsensusStateResponse::new();
+
+        response.set_root_id(self.block_store.root().id().to_vec());
+        response.set_blocks(protobuf::RepeatedField::from_vec(
+            self.block_store
+                .tree_blocks()
+                .iter()
+                .map(|block| Self::block_summary(block))
+                .collect(),
+        ));
+        response.set_highest_quorum_cert(Self::quorum_cert_summary(
+            &self.block_store.highest_quorum_cert(),
+        ));
+        response.set_highest_ledger_info(Self::quorum_cert_summary(
+            &self.block_store.highest_ledger_info(),
+        ));
+
+        let highest_timeout_certificates = self.pacemaker.highest_timeout_certificates();
+        if let Some(tc) = highest_timeout_certificates.highest_local_timeout_certificate() {
+            response.set_highest_local_timeout_certificate(Self::timeout_certificate_summary(tc));
+        }
+        if let Some(tc) = highest_timeout_certificates.highest_received_timeout_certificate() {
+            response
+                .set_highest_received_timeout_certificate(Self::timeout_certificate_summary(tc));
+        }
+        response.set_current_round(self.pacemaker.current_round());
+        let deadline = self.pacemaker.current_round_deadline();
+        let now = Instant::now();
+        let time_left = if deadline > now {
+            deadline - now
+        } else {
+            Duration::from_secs(0)
+        };
+        response.set_current_round_deadline_ms(time_left.as_millis() as u64);
+
+        let consensus_state = self.safety_rules.read().unwrap().consensus_state();
+        response.set_last_vote_round(consensus_state.last_vote_round());
+        response.set_preferred_block_round(consensus_state.preferred_block_round());
+        response
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        block_storage::BlockReader,
+        debug_info::ChainedBftDebugInfo,
+        liveness::{
+            local_pacemaker::{ExponentialTimeInterval, LocalPacemaker},
+            pacemaker::Pacemaker,
+            pacemaker_timeout_manager::HighestTimeoutCertificates,
+        },
+        persistent_storage::PersistentStorage,
+        safety::{local_safety_rules::LocalSafetyRules, safety_rules::ConsensusState},
+        test_utils::{build_empty_tree, consensus_runtime, MockStorage, TestPayload, TreeInserter},
+    },
+    util::time_service::ClockTimeService,
+};
+use channel;
+use debug_interface::node_debug_service::ConsensusDebugInfo;
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+#[test]
+fn test_dump_consensus_state() {
+    let runtime = consensus_runtime();
+    let block_store = build_empty_tree();
+    let mut inserter = TreeInserter::new(block_store.clone());
+    let genesis = block_store.root();
+    // genesis <- a1 <- a2
+    //        \<- b1
+    let a1 = inserter.insert_block(genesis.as_ref(), 1);
+    let a2 = inserter.insert_block(a1.as_ref(), 2);
+    let b1 = inserter.insert_block(genesis.as_ref(), 3);
+
+    let (new_round_events_sender, _new_round_events_receiver) = channel::new_test(1_024);
+    let (pacemaker_timeout_sender, _pacemaker_timeout_receiver) = channel::new_test(1_024);
+    let pacemaker = Arc::new(LocalPacemaker::new(
+        runtime.executor(),
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+        Box::new(ExponentialTimeInterval::fixed(Duration::new(60, 0))),
+        0,
+        0,
+        Arc::new(ClockTimeService::new(runtime.executor())),
+        new_round_events_sender,
+        pacemaker_timeout_sender,
+        1,
+        HighestTimeoutCertificates::default(),
+    ));
+    let safety_rules = Arc::new(RwLock::new(LocalSafetyRules::new(
+        block_store.clone(),
+        ConsensusState::default(),
+        block_store.signer().clone(),
+    )));
+    let debug_info = ChainedBftDebugInfo::new(block_store.clone(), pacemaker.clone(), safety_rules);
+
+    let dump = debug_info.dump_consensus_state();
+    assert_eq!(dump.get_root_id(), genesis.id().as_ref());
+    // The block tree is dumped from the root to the leaves.
+    assert_eq!(dump.get_blocks().len(), 4);
+    assert_eq!(dump.get_blocks()[0].get_id(), genesis.id().as_ref());
+    let block_ids: HashSet<_> = dump
+        .get_blocks()
+        .iter()
+        .map(|block| block.get_id().to_vec())
+        .collect();
+    for block in &[&a1, &a2, &b1] {
+        assert!(block_ids.contains(&block.id().to_vec()));
+    }
+    let dumped_a2 = dump
+        .get_blocks()
+        .iter()
+        .find(|block| block.get_id() == a2.id().as_ref())
+        .unwrap();
+    assert_eq!(dumped_a2.get_parent_id(), a1.id().as_ref());
+    assert_eq!(dumped_a2.get_round(), 2);
+
+    assert_eq!(
+        dump.get_highest_quorum_cert().get_certified_block_id(),
+        a1.id().as_ref()
+    );
+    assert_eq!(
+        dump.get_highest_quorum_cert().get_certified_block_round(),
+        1
+    );
+    assert!(!dump.has_highest_local_timeout_certificate());
+    assert!(!dump.has_highest_received_timeout_certificate());
+    assert_eq!(dump.get_current_round(), pacemaker.current_round());
+    assert_eq!(dump.get_last_vote_round(), 0);
+    assert_eq!(dump.get_preferred_block_round(), 0);
+}
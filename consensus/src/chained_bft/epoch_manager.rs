@@ -0,0 +1,228 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        common::Author,
+        consensus_types::quorum_cert::QuorumCert,
+        liveness::vrf_proposer_election::vrf_input,
+        safety::vote_msg::{VoteMsg, VoteMsgVerificationError},
+    },
+    counters,
+    state_replication::ExecutedState,
+};
+use failure::prelude::*;
+use logger::prelude::*;
+use nextgen_crypto::{
+    ed25519::*,
+    vrf::ecvrf::{Proof, VRFPublicKey},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    sync::{Arc, RwLock},
+};
+use types::{
+    account_state_blob::AccountStateBlob,
+    validator_set::{validator_set_path, ValidatorSet},
+    validator_verifier::ValidatorVerifier,
+};
+
+struct EpochState {
+    // The current epoch
+    epoch: u64,
+    // Validators of the current epoch and of all the epochs known since the start: the quorum
+    // certificates formed at the end of an epoch are still carried by the first blocks of the
+    // next one, so they must remain verifiable.
+    validators: HashMap<u64, Arc<ValidatorVerifier<Ed25519PublicKey>>>,
+}
+
+/// EpochManager keeps track of the current epoch and of its validator set.
+/// An epoch ends once a block that changes the validator set is committed: from then on the
+/// messages are verified against the new validators, and the messages of the previous epochs are
+/// considered stale.
+/// EpochManager is shared between the network (message verification and broadcasting) and the
+/// event processor (quorum size, proposer election, starting a new epoch).
+pub struct EpochManager {
+    state: RwLock<EpochState>,
+    // VRF public keys of the validators, registered along their consensus keys in the trusted
+    // peers config: they are dedicated to the VRF proposer election.
+    vrf_public_keys: HashMap<Author, VRFPublicKey>,
+    // Whether the validators vote on the ordering of the blocks and execute them in the
+    // background: all the validators of the set must agree on it, the votes of the two modes
+    // never form a quorum certificate together.
+    pipelined_execution: bool,
+}
+
+impl EpochManager {
+    pub fn new(epoch: u64, validators: Arc<ValidatorVerifier<Ed25519PublicKey>>) -> Self {
+        Self::update_counters(epoch, validators.as_ref());
+        let mut epoch_validators = HashMap::new();
+        epoch_validators.insert(epoch, validators);
+        Self {
+            state: RwLock::new(EpochState {
+                epoch,
+                validators: epoch_validators,
+            }),
+            vrf_public_keys: HashMap::new(),
+            pipelined_execution: false,
+        }
+    }
+
+    /// Sets the execution mode of the validators.
+    pub fn with_pipelined_execution(mut self, pipelined_execution: bool) -> Self {
+        self.pipelined_execution = pipelined_execution;
+        self
+    }
+
+    /// Whether the validators vote on the ordering of the blocks only
+    pub fn pipelined_execution(&self) -> bool {
+        self.pipelined_execution
+    }
+
+    /// Verifies that the vote follows the execution mode of the validators: the votes carry no
+    /// execution results in the pipelined execution mode, and always carry them otherwise.
+    pub fn verify_execution_mode(&self, vote: &VoteMsg) -> Result<(), VoteMsgVerificationError> {
+        let unexecuted = vote.executed_state() == ExecutedState::unexecuted();
+        if unexecuted != self.pipelined_execution {
+            return Err(VoteMsgVerificationError::ExecutionModeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Registers the VRF public keys of the validators.
+    pub fn with_vrf_public_keys(mut self, vrf_public_keys: HashMap<Author, VRFPublicKey>) -> Self {
+        self.vrf_public_keys = vrf_public_keys;
+        self
+    }
+
+    /// VRF public keys of the validators
+    pub fn vrf_public_keys(&self) -> &HashMap<Author, VRFPublicKey> {
+        &self.vrf_public_keys
+    }
+
+    /// Returns true if the VRF proof carried by a block of the given author is valid for the
+    /// quorum certificate the block extends.
+    pub fn verify_vrf_proof(&self, author: Author, quorum_cert: &QuorumCert, proof: &[u8]) -> bool {
+        let public_key = match self.vrf_public_keys.get(&author) {
+            Some(public_key) => public_key,
+            None => return false,
+        };
+        match Proof::try_from(proof) {
+            Ok(proof) => public_key
+                .verify(&proof, vrf_input(quorum_cert).as_ref())
+                .is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// The current epoch
+    pub fn epoch(&self) -> u64 {
+        self.state.read().unwrap().epoch
+    }
+
+    /// Validators of the current epoch
+    pub fn validators(&self) -> Arc<ValidatorVerifier<Ed25519PublicKey>> {
+        let state = self.state.read().unwrap();
+        Arc::clone(
+            state
+                .validators
+                .get(&state.epoch)
+                .expect("Validators of the current epoch are unknown"),
+        )
+    }
+
+    /// Validators of the given epoch if known
+    pub fn validators_for_epoch(
+        &self,
+        epoch: u64,
+    ) -> Option<Arc<ValidatorVerifier<Ed25519PublicKey>>> {
+        self.state.read().unwrap().validators.get(&epoch).cloned()
+    }
+
+    /// Ordered addresses of the validators of the current epoch
+    pub fn peers(&self) -> Vec<Author> {
+        self.validators().get_ordered_account_addresses()
+    }
+
+    /// Quorum size of the current epoch
+    pub fn quorum_size(&self) -> usize {
+        self.validators().quorum_size()
+    }
+
+    /// Verifies the quorum certificate against the validators of the epoch it was formed in.
+    pub fn verify_quorum_cert(&self, qc: &QuorumCert) -> Result<(), VoteMsgVerificationError> {
+        let validators = self
+            .validators_for_epoch(qc.epoch())
+            .ok_or_else(|| VoteMsgVerificationError::UnknownEpoch(qc.epoch()))?;
+        qc.verify(validators.as_ref())
+    }
+
+    /// Ends the current epoch and starts the next one with the given validator set.
+    /// Returns the validators of the new epoch.
+    pub fn start_new_epoch(
+        &self,
+        validator_set: &ValidatorSet,
+    ) -> Arc<ValidatorVerifier<Ed25519PublicKey>> {
+        let validators = Arc::new(Self::validator_verifier(validator_set));
+        let mut state = self.state.write().unwrap();
+        state.epoch += 1;
+        let epoch = state.epoch;
+        state.validators.insert(epoch, Arc::clone(&validators));
+        Self::update_counters(epoch, validators.as_ref());
+        info!(
+            "Starting epoch {} with {} validators, quorum size {}",
+            epoch,
+            validators.len(),
+            validators.quorum_size()
+        );
+        validators
+    }
+
+    /// Builds the verifier of the consensus signatures of the given validator set.
+    pub fn validator_verifier(validator_set: &ValidatorSet) -> ValidatorVerifier<Ed25519PublicKey> {
+        ValidatorVerifier::new(
+            validator_set
+                .payload()
+                .iter()
+                .map(|keys| (*keys.account_address(), keys.consensus_public_key().clone()))
+                .collect(),
+        )
+    }
+
+    /// The epoch to restart in after a ledger info of `epoch` was committed. A ledger info at
+    /// which the validator set changed ends its epoch, just like committing its block does.
+    pub fn epoch_after_commit(
+        epoch: u64,
+        validator_set: Option<&ValidatorSet>,
+        previous_validator_set: Option<&ValidatorSet>,
+    ) -> u64 {
+        if validator_set != previous_validator_set {
+            epoch + 1
+        } else {
+            epoch
+        }
+    }
+
+    /// Reads the validator set from the account state of the core code address. Returns `None`
+    /// as long as no validator set has been published on chain.
+    pub fn validator_set_from_blob(
+        blob: Option<&AccountStateBlob>,
+    ) -> Result<Option<ValidatorSet>> {
+        let blob = match blob {
+            Some(blob) => blob,
+            None => return Ok(None),
+        };
+        let account_map = BTreeMap::try_from(blob)?;
+        if !account_map.contains_key(&validator_set_path()) {
+            return Ok(None);
+        }
+        Ok(Some(ValidatorSet::make_from(&account_map)?))
+    }
+
+    fn update_counters(epoch: u64, validators: &ValidatorVerifier<Ed25519PublicKey>) {
+        counters::EPOCH_NUM.set(epoch as i64);
+        counters::CURRENT_EPOCH_NUM_VALIDATORS.set(validators.len() as i64);
+        counters::CURRENT_EPOCH_QUORUM_SIZE.set(validators.quorum_size() as i64);
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        consensus_types::quorum_cert::QuorumCert,
+        epoch_manager::EpochManager,
+        safety::vote_msg::{VoteMsg, VoteMsgVerificationError},
+        test_utils::placeholder_ledger_info,
+    },
+    state_replication::ExecutedState,
+};
+use canonical_serialization::SimpleSerializer;
+use crypto::HashValue;
+use nextgen_crypto::{ed25519::*, x25519};
+use std::{collections::BTreeMap, convert::TryFrom, sync::Arc};
+use types::{
+    account_state_blob::AccountStateBlob,
+    validator_public_keys::ValidatorPublicKeys,
+    validator_set::{validator_set_path, ValidatorSet},
+    validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+
+fn validator_set(signers: &[ValidatorSigner<Ed25519PrivateKey>]) -> ValidatorSet {
+    ValidatorSet::new(
+        signers
+            .iter()
+            .map(|signer| {
+                let (_, network_identity_public_key) = x25519::compat::generate_keypair(None);
+                ValidatorPublicKeys::new(
+                    signer.author(),
+                    signer.public_key(),
+                    signer.public_key(),
+                    network_identity_public_key,
+                )
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn test_initial_epoch() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0u8; 32]);
+    let validators = Arc::new(ValidatorVerifier::new_single(
+        signer.author(),
+        signer.public_key(),
+    ));
+    let epoch_manager = EpochManager::new(0, validators);
+
+    assert_eq!(epoch_manager.epoch(), 0);
+    assert_eq!(epoch_manager.peers(), vec![signer.author()]);
+    assert_eq!(epoch_manager.quorum_size(), 1);
+    assert!(epoch_manager.validators_for_epoch(0).is_some());
+    assert!(epoch_manager.validators_for_epoch(1).is_none());
+    assert_eq!(
+        epoch_manager.verify_quorum_cert(&QuorumCert::certificate_for_genesis()),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_start_new_epoch() {
+    let signers: Vec<_> = (0..4)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i as u8; 32]))
+        .collect();
+    let validators = Arc::new(ValidatorVerifier::new_single(
+        signers[0].author(),
+        signers[0].public_key(),
+    ));
+    let epoch_manager = EpochManager::new(0, validators);
+
+    let new_validators = epoch_manager.start_new_epoch(&validator_set(&signers));
+    assert_eq!(epoch_manager.epoch(), 1);
+    assert_eq!(new_validators.len(), 4);
+    assert_eq!(epoch_manager.quorum_size(), new_validators.quorum_size());
+    let mut expected_peers: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+    expected_peers.sort();
+    let mut peers = epoch_manager.peers();
+    peers.sort();
+    assert_eq!(peers, expected_peers);
+
+    // The validators of the previous epoch are still known.
+    assert_eq!(
+        epoch_manager.validators_for_epoch(0).map(|v| v.len()),
+        Some(1)
+    );
+    assert!(epoch_manager.validators_for_epoch(2).is_none());
+}
+
+#[test]
+fn test_verify_quorum_cert_of_unknown_epoch() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0u8; 32]);
+    let validators = Arc::new(ValidatorVerifier::new_single(
+        signer.author(),
+        signer.public_key(),
+    ));
+    let epoch_manager = EpochManager::new(1, validators);
+
+    assert_eq!(
+        epoch_manager.verify_quorum_cert(&QuorumCert::certificate_for_genesis()),
+        Err(VoteMsgVerificationError::UnknownEpoch(0))
+    );
+}
+
+#[test]
+fn test_verify_execution_mode() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0u8; 32]);
+    let vote = |executed_state| {
+        VoteMsg::new(
+            HashValue::random(),
+            executed_state,
+            1,
+            HashValue::random(),
+            0,
+            HashValue::random(),
+            0,
+            signer.author(),
+            placeholder_ledger_info(),
+            &signer,
+        )
+    };
+    let executed_vote = vote(ExecutedState::state_for_genesis());
+    let unexecuted_vote = vote(ExecutedState::unexecuted());
+    let validators = Arc::new(ValidatorVerifier::new_single(
+        signer.author(),
+        signer.public_key(),
+    ));
+
+    let epoch_manager = EpochManager::new(0, Arc::clone(&validators));
+    assert_eq!(epoch_manager.verify_execution_mode(&executed_vote), Ok(()));
+    assert_eq!(
+        epoch_manager.verify_execution_mode(&unexecuted_vote),
+        Err(VoteMsgVerificationError::ExecutionModeMismatch)
+    );
+
+    let epoch_manager = EpochManager::new(0, validators).with_pipelined_execution(true);
+    assert_eq!(
+        epoch_manager.verify_execution_mode(&unexecuted_vote),
+        Ok(())
+    );
+    assert_eq!(
+        epoch_manager.verify_execution_mode(&executed_vote),
+        Err(VoteMsgVerificationError::ExecutionModeMismatch)
+    );
+}
+
+fn account_state_blob(validator_set: &ValidatorSet) -> AccountStateBlob {
+    let mut account_map = BTreeMap::new();
+    account_map.insert(
+        validator_set_path(),
+        SimpleSerializer::serialize(validator_set).unwrap(),
+    );
+    AccountStateBlob::try_from(&account_map).unwrap()
+}
+
+#[test]
+fn test_restart_at_epoch_boundary() {
+    let signers: Vec<_> = (0..4)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i as u8; 32]))
+        .collect();
+    let old_validator_set = validator_set(&signers[..3]);
+    let new_validator_set = validator_set(&signers);
+
+    // The latest committed ledger info of epoch 2 is at a version where the validator set
+    // changed, so the node restarts in epoch 3 with the new validators.
+    let blob = account_state_blob(&new_validator_set);
+    let previous_blob = account_state_blob(&old_validator_set);
+    let validator_set = EpochManager::validator_set_from_blob(Some(&blob)).unwrap();
+    let previous_validator_set =
+        EpochManager::validator_set_from_blob(Some(&previous_blob)).unwrap();
+    assert_eq!(validator_set.as_ref(), Some(&new_validator_set));
+    assert_eq!(
+        EpochManager::epoch_after_commit(
+            2,
+            validator_set.as_ref(),
+            previous_validator_set.as_ref()
+        ),
+        3
+    );
+    let validators = EpochManager::validator_verifier(&validator_set.unwrap());
+    assert_eq!(validators.len(), 4);
+
+    // Within an epoch the node restarts in the epoch of the ledger info.
+    assert_eq!(
+        EpochManager::epoch_after_commit(
+            2,
+            previous_validator_set.as_ref(),
+            previous_validator_set.as_ref()
+        ),
+        2
+    );
+    // Publishing the first validator set on chain ends the epoch of the trusted peers config.
+    assert_eq!(
+        EpochManager::epoch_after_commit(0, Some(&old_validator_set), None),
+        1
+    );
+}
+
+#[test]
+fn test_validator_set_from_blob() {
+    // No account or no validator set yet.
+    assert_eq!(EpochManager::validator_set_from_blob(None).unwrap(), None);
+    let empty_blob = AccountStateBlob::try_from(&BTreeMap::new()).unwrap();
+    assert_eq!(
+        EpochManager::validator_set_from_blob(Some(&empty_blob)).unwrap(),
+        None
+    );
+
+    // Undecodable account states and validator sets are errors rather than a fallback to the
+    // genesis validators.
+    assert!(
+        EpochManager::validator_set_from_blob(Some(&AccountStateBlob::from(vec![1, 2, 3])))
+            .is_err()
+    );
+    let mut account_map = BTreeMap::new();
+    account_map.insert(validator_set_path(), vec![0xff]);
+    let bad_blob = AccountStateBlob::try_from(&account_map).unwrap();
+    assert!(EpochManager::validator_set_from_blob(Some(&bad_blob)).is_err());
+}
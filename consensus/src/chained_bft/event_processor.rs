@@ -11,30 +11,33 @@ use crate::{
         common::{Author, Payload, Round},
         consensus_types::{
             block::Block,
+            committed_block_metadata::CommittedBlockMetadata,
             proposal_msg::ProposalMsg,
             quorum_cert::QuorumCert,
             sync_info::SyncInfo,
-            timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate, TimeoutMsg},
+            timeout_msg::{PacemakerTimeoutCertificate, TimeoutMsg},
         },
+        epoch_manager::EpochManager,
         liveness::{
             pacemaker::{NewRoundEvent, NewRoundReason, Pacemaker},
             proposal_generator::ProposalGenerator,
-            proposer_election::ProposerElection,
+            proposer_election::{ProposerElection, ProposerElectionFactory},
         },
         network::{
-            BlockRetrievalRequest, BlockRetrievalResponse, ChunkRetrievalRequest,
-            ConsensusNetworkImpl,
+            AccountStateChunkRetrievalRequest, BlockRetrievalRequest, BlockRetrievalResponse,
+            ChunkRetrievalRequest, ConsensusNetworkImpl,
         },
         persistent_storage::PersistentStorage,
-        safety::{safety_rules::SafetyRules, vote_msg::VoteMsg},
+        safety::{t_safety_rules::TSafetyRules, vote_msg::VoteMsg},
         sync_manager::{SyncManager, SyncMgrContext},
     },
     counters,
-    state_replication::{StateComputer, TxnManager},
+    state_replication::{ExecutedState, StateComputer, TxnManager},
     util::time_service::{
         duration_since_epoch, wait_if_possible, TimeService, WaitingError, WaitingSuccess,
     },
 };
+use crypto::HashValue;
 use logger::prelude::*;
 use network::proto::BlockRetrievalStatus;
 use nextgen_crypto::ed25519::*;
@@ -43,7 +46,7 @@ use std::{
     time::Duration,
 };
 use termion::color::*;
-use types::ledger_info::LedgerInfoWithSignatures;
+use types::{ledger_info::LedgerInfoWithSignatures, validator_set::ValidatorSet};
 
 /// Result of initial proposal processing
 /// - Done(proposal_option) indicates that the proposal is processed, and `proposal_option` contains
@@ -64,9 +67,12 @@ pub struct EventProcessor<T> {
     author: Author,
     block_store: Arc<BlockStore<T>>,
     pacemaker: Arc<dyn Pacemaker>,
-    proposer_election: Arc<dyn ProposerElection<T> + Send + Sync>,
+    epoch_manager: Arc<EpochManager>,
+    // Proposer election of the current epoch, replaced at every reconfiguration.
+    proposer_election: RwLock<Arc<dyn ProposerElection<T> + Send + Sync>>,
+    proposer_election_factory: ProposerElectionFactory<T>,
     proposal_generator: ProposalGenerator<T>,
-    safety_rules: Arc<RwLock<SafetyRules<T>>>,
+    safety_rules: Arc<RwLock<dyn TSafetyRules<T> + Send + Sync>>,
     state_computer: Arc<dyn StateComputer<Payload = T>>,
     txn_manager: Arc<dyn TxnManager<Payload = T>>,
     network: ConsensusNetworkImpl,
@@ -83,9 +89,10 @@ impl<T: Payload> EventProcessor<T> {
         author: Author,
         block_store: Arc<BlockStore<T>>,
         pacemaker: Arc<dyn Pacemaker>,
-        proposer_election: Arc<dyn ProposerElection<T> + Send + Sync>,
+        epoch_manager: Arc<EpochManager>,
+        proposer_election_factory: ProposerElectionFactory<T>,
         proposal_generator: ProposalGenerator<T>,
-        safety_rules: Arc<RwLock<SafetyRules<T>>>,
+        safety_rules: Arc<RwLock<dyn TSafetyRules<T> + Send + Sync>>,
         state_computer: Arc<dyn StateComputer<Payload = T>>,
         txn_manager: Arc<dyn TxnManager<Payload = T>>,
         network: ConsensusNetworkImpl,
@@ -99,11 +106,16 @@ impl<T: Payload> EventProcessor<T> {
             network.clone(),
             Arc::clone(&state_computer),
         );
+        let proposer_election = RwLock::new(proposer_election_factory(
+            epoch_manager.validators().as_ref(),
+        ));
         Self {
             author,
             block_store,
             pacemaker,
+            epoch_manager,
             proposer_election,
+            proposer_election_factory,
             proposal_generator,
             safety_rules,
             state_computer,
@@ -117,6 +129,11 @@ impl<T: Payload> EventProcessor<T> {
         }
     }
 
+    /// Proposer election of the current epoch.
+    fn proposer_election(&self) -> Arc<dyn ProposerElection<T> + Send + Sync> {
+        Arc::clone(&self.proposer_election.read().unwrap())
+    }
+
     /// Leader:
     ///
     /// This event is triggered by a new quorum certificate at the previous round or a
@@ -142,7 +159,7 @@ impl<T: Payload> EventProcessor<T> {
             }
         };
         if self
-            .proposer_election
+            .proposer_election()
             .is_valid_proposer(self.author, new_round_event.round)
             .is_none()
         {
@@ -153,6 +170,7 @@ impl<T: Payload> EventProcessor<T> {
         let proposal = match self
             .proposal_generator
             .generate_proposal(
+                self.epoch_manager.epoch(),
                 new_round_event.round,
                 self.pacemaker.current_round_deadline(),
             )
@@ -162,7 +180,23 @@ impl<T: Payload> EventProcessor<T> {
                 error!("Error while generating proposal: {:?}", e);
                 return;
             }
+            Ok(proposal) => match self
+                .proposer_election()
+                .prove_proposal(proposal.quorum_cert())
+            {
+                Some(vrf_proof) => proposal.with_vrf_proof(vrf_proof, self.block_store.signer()),
+                None => proposal,
+            },
+        };
+        // The proposal is signed by the safety rules, which might keep the consensus key out of
+        // the consensus process.
+        let signed_proposal = self.safety_rules.write().unwrap().sign_proposal(proposal);
+        let proposal = match signed_proposal {
             Ok(proposal) => proposal,
+            Err(e) => {
+                error!("Failed to sign the proposal due to {:?}", e);
+                return;
+            }
         };
         let mut network = self.network.clone();
         debug!("Propose {}", proposal);
@@ -212,6 +246,16 @@ impl<T: Payload> EventProcessor<T> {
                 .certified_block_round(),
         )
         .await;
+        let current_epoch = self.epoch_manager.epoch();
+        if proposal.proposal.epoch() != current_epoch {
+            warn!(
+                "Proposal {} is ignored because its epoch {} != current epoch {}",
+                proposal,
+                proposal.proposal.epoch(),
+                current_epoch
+            );
+            return ProcessProposalResult::Done(None);
+        }
         if proposal.proposal.round() < self.pacemaker.current_round() {
             warn!(
                 "Proposal {} is ignored because its round {} < current round {}",
@@ -222,7 +266,7 @@ impl<T: Payload> EventProcessor<T> {
             return ProcessProposalResult::Done(None);
         }
         if self
-            .proposer_election
+            .proposer_election()
             .is_valid_proposer(proposal.proposer(), proposal.proposal.round())
             .is_none()
         {
@@ -287,7 +331,7 @@ impl<T: Payload> EventProcessor<T> {
             return None;
         }
 
-        self.proposer_election.process_proposal(proposal_msg)
+        self.proposer_election().process_proposal(proposal_msg)
     }
 
     /// Takes mutable reference to avoid race with other processing and perform state
@@ -314,7 +358,7 @@ impl<T: Payload> EventProcessor<T> {
     /// a pacemaker timeout certificate is formed with 2f+1 timeouts, the next proposer will be
     /// able to chain a proposal block to a highest quorum certificate such that all honest replicas
     /// can vote for it.
-    pub async fn process_timeout_msg(&mut self, timeout_msg: TimeoutMsg, quorum_size: usize) {
+    pub async fn process_timeout_msg(&mut self, timeout_msg: TimeoutMsg) {
         debug!(
             "Received timeout msg for round {} from {}",
             timeout_msg.pacemaker_timeout().round(),
@@ -340,7 +384,7 @@ impl<T: Payload> EventProcessor<T> {
             return;
         };
         if let Some(vote) = timeout_msg.pacemaker_timeout().vote_msg() {
-            if let Some(_qc) = self.add_vote(vote.clone(), quorum_size).await {
+            if let Some(_qc) = self.add_vote(vote.clone()).await {
                 counters::TIMEOUT_VOTES_FORM_QC_COUNT.inc();
             }
         }
@@ -470,9 +514,16 @@ impl<T: Payload> EventProcessor<T> {
             .write()
             .unwrap()
             .increase_last_vote_round(round);
-        if let Some(consensus_state) = consensus_state {
-            if let Err(e) = self.storage.save_consensus_state(consensus_state) {
-                error!("Failed to persist consensus state after increasing the last vote round due to {:?}", e);
+        match consensus_state {
+            Ok(Some(consensus_state)) => {
+                if let Err(e) = self.storage.save_consensus_state(consensus_state) {
+                    error!("Failed to persist consensus state after increasing the last vote round due to {:?}", e);
+                    return None;
+                }
+            }
+            Ok(None) => (),
+            Err(e) => {
+                error!("Failed to increase the last vote round due to {:?}", e);
                 return None;
             }
         }
@@ -487,22 +538,35 @@ impl<T: Payload> EventProcessor<T> {
             "Round {} timed out and {}, expected round proposer was {:?}, broadcasting new round to all replicas",
             round,
             if last_vote_round == round { "already executed and voted at this round" } else { "will never vote at this round" },
-            self.proposer_election.get_valid_proposers(round),
+            self.proposer_election().get_valid_proposers(round),
         );
 
-        Some(TimeoutMsg::new(
-            SyncInfo::new(
-                self.block_store.highest_quorum_cert().as_ref().clone(),
-                self.block_store.highest_ledger_info().as_ref().clone(),
-                self.pacemaker.highest_timeout_certificate(),
-            ),
-            PacemakerTimeout::new(round, self.block_store.signer(), vote_msg_to_attach),
-            self.block_store.signer(),
-        ))
+        let sync_info = SyncInfo::new(
+            self.block_store.highest_quorum_cert().as_ref().clone(),
+            self.block_store.highest_ledger_info().as_ref().clone(),
+            self.pacemaker.highest_timeout_certificate(),
+        );
+        let timeout_msg =
+            self.safety_rules
+                .write()
+                .unwrap()
+                .sign_timeout(round, vote_msg_to_attach, sync_info);
+        match timeout_msg {
+            Ok(timeout_msg) => Some(timeout_msg),
+            Err(e) => {
+                error!(
+                    "Failed to sign the timeout for round {} due to {:?}",
+                    round, e
+                );
+                None
+            }
+        }
     }
 
     async fn gen_nil_vote(&self, round: Round) -> failure::Result<VoteMsg> {
-        let block = self.proposal_generator.generate_nil_block(round)?;
+        let block = self
+            .proposal_generator
+            .generate_nil_block(self.epoch_manager.epoch(), round)?;
         self.execute_and_vote(block).await
     }
 
@@ -511,7 +575,12 @@ impl<T: Payload> EventProcessor<T> {
         qc: &QuorumCert,
         tc: Option<&PacemakerTimeoutCertificate>,
     ) {
-        self.safety_rules.write().unwrap().update(qc);
+        if let Err(e) = self.safety_rules.write().unwrap().update(qc) {
+            error!(
+                "Failed to update the safety rules with {} due to {:?}",
+                qc, e
+            );
+        }
 
         let mut highest_commit_round = None;
         if let Some(new_commit) = qc.committed_block_id() {
@@ -531,7 +600,11 @@ impl<T: Payload> EventProcessor<T> {
     /// 2. Try to vote for it following the safety rules.
     /// 3. In case a validator chooses to vote, send the vote to the representatives at the next
     /// position.
-    pub async fn process_winning_proposal(&self, proposal_msg: ProposalMsg<T>) {
+    /// Returns the id of the block to execute in the background in the pipelined execution mode.
+    pub async fn process_winning_proposal(
+        &self,
+        proposal_msg: ProposalMsg<T>,
+    ) -> Option<HashValue> {
         if let Some(time_to_receival) = duration_since_epoch().checked_sub(Duration::from_micros(
             proposal_msg.proposal.timestamp_usecs(),
         )) {
@@ -539,9 +612,10 @@ impl<T: Payload> EventProcessor<T> {
         }
 
         let proposal_round = proposal_msg.proposal.round();
+        let proposal_id = proposal_msg.proposal.id();
         let vote_msg = match self.execute_and_vote(proposal_msg.proposal).await {
             Err(_) => {
-                return;
+                return None;
             }
             Ok(vote_msg) => vote_msg,
         };
@@ -551,10 +625,18 @@ impl<T: Payload> EventProcessor<T> {
             .unwrap()
             .replace((vote_msg.clone(), proposal_round));
         let recipients = self
-            .proposer_election
+            .proposer_election()
             .get_valid_proposers(proposal_round + 1);
         debug!("{}Voted: {} {}", Fg(Green), Fg(Reset), vote_msg);
         self.network.send_vote(vote_msg, recipients).await;
+
+        // With the pipelined execution, the block is executed while the next round is going on:
+        // the caller executes it in the background, without holding the event processor.
+        if self.block_store.pipelined_execution() {
+            Some(proposal_id)
+        } else {
+            None
+        }
     }
 
     async fn wait_before_vote_if_needed(
@@ -617,13 +699,25 @@ impl<T: Payload> EventProcessor<T> {
     }
 
     /// The function generates a VoteMsg for a given proposed_block:
-    /// * first execute the block and add it to the block store
+    /// * first fetch the data its payload refers to if needed
+    /// * execute the block and add it to the block store
     /// * then verify the voting rules
     /// * save the updated state to consensus DB
     /// * return a VoteMsg with the LedgerInfo to be committed in case the vote gathers QC.
     ///
     /// This function assumes that it might be called from different tasks concurrently.
     async fn execute_and_vote(&self, proposed_block: Block<T>) -> failure::Result<VoteMsg> {
+        // The payload may only refer to data disseminated ahead of the proposal: it has to be
+        // available before voting.
+        if let Some(author) = proposed_block.author() {
+            self.txn_manager
+                .fetch_payload(proposed_block.get_payload(), author)
+                .await
+                .map_err(|e| {
+                    debug!("Failed to fetch the payload of {}: {:?}", proposed_block, e);
+                    e
+                })?;
+        }
         let block = self
             .sync_manager
             .execute_and_insert_block(proposed_block)
@@ -663,26 +757,31 @@ impl<T: Payload> EventProcessor<T> {
             })?;
 
         let proposal_id = vote_info.proposal_id();
-        let executed_state = self
-            .block_store
-            .get_state_for_block(proposal_id)
-            .expect("Block proposed_block: no execution state found for inserted block.");
+        let executed_state = if self.block_store.pipelined_execution() {
+            // The vote only certifies the ordering of the block: its execution results are
+            // certified by the ledger info of the vote that commits it. The potential commit is
+            // an ancestor of the block, which is usually executed by now.
+            if let Some(commit_id) = vote_info.potential_commit_id() {
+                self.block_store.execute_block(commit_id).await?;
+            }
+            ExecutedState::unexecuted()
+        } else {
+            self.block_store
+                .get_state_for_block(proposal_id)
+                .expect("Block proposed_block: no execution state found for inserted block.")
+        };
 
         let ledger_info_placeholder = self
             .block_store
-            .ledger_info_placeholder(vote_info.potential_commit_id());
-        Ok(VoteMsg::new(
-            proposal_id,
-            executed_state,
-            block.round(),
-            vote_info.parent_block_id(),
-            vote_info.parent_block_round(),
-            vote_info.grandparent_block_id(),
-            vote_info.grandparent_block_round(),
-            self.author,
-            ledger_info_placeholder,
-            self.block_store.signer(),
-        ))
+            .ledger_info_placeholder(vote_info.potential_commit_id(), block.epoch());
+        self.safety_rules
+            .write()
+            .unwrap()
+            .sign_vote(&vote_info, executed_state, ledger_info_placeholder)
+            .map_err(|e| {
+                error!("Failed to sign the vote for {}: {:?}", block, e);
+                e
+            })
     }
 
     /// Upon new vote:
@@ -691,11 +790,11 @@ impl<T: Payload> EventProcessor<T> {
     /// 2. Add the vote to the store and check whether it finishes a QC.
     /// 3. Once the QC successfully formed, notify the Pacemaker.
     #[allow(clippy::collapsible_if)] // Collapsing here would make if look ugly
-    pub async fn process_vote(&self, vote: VoteMsg, quorum_size: usize) {
+    pub async fn process_vote(&self, vote: VoteMsg) {
         // Check whether this validator is a valid recipient of the vote.
         let next_round = vote.round() + 1;
         if self
-            .proposer_election
+            .proposer_election()
             .is_valid_proposer(self.author, next_round)
             .is_none()
         {
@@ -711,7 +810,7 @@ impl<T: Payload> EventProcessor<T> {
             return;
         }
 
-        self.add_vote(vote, quorum_size).await;
+        self.add_vote(vote).await;
     }
 
     /// Add a vote. Fetch missing dependencies if required.
@@ -722,13 +821,24 @@ impl<T: Payload> EventProcessor<T> {
     /// 2) pass the new QC to the pacemaker, which can generate a new round in return.
     /// The function returns an Option for a newly generate QuorumCert in case it's been
     /// successfully added with all its dependencies.
-    async fn add_vote(&self, vote: VoteMsg, quorum_size: usize) -> Option<Arc<QuorumCert>> {
+    async fn add_vote(&self, vote: VoteMsg) -> Option<Arc<QuorumCert>> {
         let deadline = self.pacemaker.current_round_deadline();
         let preferred_peer = Some(vote.author());
-        // TODO [Reconfiguration] Verify epoch of the vote message.
+        // Votes of the previous epochs can't form a QC with the votes of the current validators.
+        let current_epoch = self.epoch_manager.epoch();
+        if vote.epoch() != current_epoch {
+            debug!(
+                "Received {}, but its epoch {} != current epoch {}, ignore.",
+                vote,
+                vote.epoch(),
+                current_epoch
+            );
+            return None;
+        }
         // Add the vote and check whether it completes a new QC.
-        if let VoteReceptionResult::NewQuorumCertificate(qc) =
-            self.block_store.insert_vote(vote, quorum_size)
+        if let VoteReceptionResult::NewQuorumCertificate(qc) = self
+            .block_store
+            .insert_vote(vote, self.epoch_manager.quorum_size())
         {
             if self.block_store.need_fetch_for_quorum_cert(&qc) == NeedFetchResult::NeedFetch {
                 if let Err(e) = self
@@ -754,10 +864,12 @@ impl<T: Payload> EventProcessor<T> {
 
     /// Upon (potentially) new commit:
     /// 0. Verify that this commit is newer than the current root.
-    /// 1. Notify state computer with the finality proof.
+    /// 1. Persist the metadata of the committed blocks and notify state computer with the finality
+    /// proof.
     /// 2. After the state is finalized, update the txn manager with the status of the committed
     /// transactions.
-    /// 3. Prune the tree.
+    /// 3. Start a new epoch if one of the committed blocks changes the validator set.
+    /// 4. Prune the tree.
     async fn process_commit(
         &self,
         committed_block: Arc<Block<T>>,
@@ -775,7 +887,34 @@ impl<T: Payload> EventProcessor<T> {
             committed_block.id()
         );
 
-        if let Err(e) = self.state_computer.commit(finality_proof).await {
+        // With the pipelined execution, the committed blocks might not be executed yet.
+        if let Err(e) = self.block_store.execute_block(committed_block.id()).await {
+            error!(
+                "Failed to execute the committed block {}: {:?}",
+                committed_block, e
+            );
+            return;
+        }
+
+        // Multiple blocks might be committed at once: all the blocks in the path from the old
+        // root to the new root are committed.
+        let committed_blocks = self
+            .block_store
+            .path_from_root(Arc::clone(&committed_block))
+            .unwrap_or_else(Vec::new);
+        // The committed history outlives the pruned blocks: it is the source of the reputation of
+        // the validators.
+        if let Err(e) = self.storage.save_committed_blocks(
+            committed_blocks
+                .iter()
+                .map(|block| CommittedBlockMetadata::new(block.as_ref()))
+                .collect(),
+        ) {
+            error!("Failed to persist the committed blocks metadata: {:?}", e);
+            return;
+        }
+
+        if let Err(e) = self.state_computer.commit(finality_proof.clone()).await {
             // We assume that state computer cannot enter an inconsistent state that might
             // violate safety of the protocol. Specifically, an executor service is going to panic
             // if it fails to persist the commit requests, which would crash the whole process
@@ -786,14 +925,9 @@ impl<T: Payload> EventProcessor<T> {
             );
             return;
         }
-        // At this moment the new state is persisted and we can notify the clients.
-        // Multiple blocks might be committed at once: notify about all the transactions in the
-        // path from the old root to the new root.
-        for committed in self
-            .block_store
-            .path_from_root(Arc::clone(&committed_block))
-            .unwrap_or_else(Vec::new)
-        {
+        // At this moment the new state is persisted and we can notify the clients about all the
+        // committed transactions.
+        for committed in committed_blocks {
             if let Some(time_to_commit) = duration_since_epoch()
                 .checked_sub(Duration::from_micros(committed.timestamp_usecs()))
             {
@@ -814,12 +948,37 @@ impl<T: Payload> EventProcessor<T> {
             {
                 error!("Failed to notify mempool: {:?}", e);
             }
+            if let Some(validator_set) = compute_result.validators.as_ref() {
+                self.start_new_epoch(validator_set, &finality_proof);
+            }
         }
         counters::LAST_COMMITTED_ROUND.set(committed_block.round() as i64);
         debug!("{}Committed{} {}", Fg(Blue), Fg(Reset), *committed_block);
         self.block_store.prune_tree(committed_block.id());
     }
 
+    /// The validator set committed by a reconfiguration block takes effect right away: the
+    /// following rounds are led and certified by the validators of the new epoch. The safety rules
+    /// are handed the ledger info that committed it, to start the new epoch on their side.
+    fn start_new_epoch(
+        &self,
+        validator_set: &ValidatorSet,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures<Ed25519Signature>,
+    ) {
+        if let Err(e) = self
+            .safety_rules
+            .write()
+            .unwrap()
+            .start_new_epoch(ledger_info_with_sigs)
+        {
+            error!("Failed to start a new epoch in the safety rules: {:?}", e);
+        }
+        let validators = self.epoch_manager.start_new_epoch(validator_set);
+        *self.proposer_election.write().unwrap() =
+            (self.proposer_election_factory)(validators.as_ref());
+        self.pacemaker.start_new_epoch(validators.quorum_size());
+    }
+
     /// Retrieve a n chained blocks from the block store starting from
     /// an initial parent id, returning with <n (as many as possible) if
     /// id or its ancestors can not be found.
@@ -870,6 +1029,24 @@ impl<T: Payload> EventProcessor<T> {
         }
     }
 
+    /// Retrieve the chunk of account states from storage and send it back.
+    pub async fn process_account_state_chunk_retrieval(
+        &self,
+        request: AccountStateChunkRetrievalRequest,
+    ) {
+        let response = self
+            .sync_manager
+            .get_account_state_chunk(request.version, request.start_key, request.limit)
+            .await;
+
+        if let Err(e) = request.response_sender.send(response) {
+            error!(
+                "Failed to return the requested account state chunk: {:?}",
+                e
+            );
+        }
+    }
+
     /// Inspect the current consensus state.
     #[cfg(test)]
     pub fn consensus_state(&self) -> ConsensusState {
@@ -12,6 +12,7 @@ use crate::{
             sync_info::SyncInfo,
             timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate, TimeoutMsg},
         },
+        epoch_manager::EpochManager,
         event_processor::{EventProcessor, ProcessProposalResult},
         liveness::{
             local_pacemaker::{ExponentialTimeInterval, LocalPacemaker},
@@ -25,8 +26,7 @@ use crate::{
         network_tests::NetworkPlayground,
         persistent_storage::{PersistentStorage, RecoveryData},
         safety::{
-            safety_rules::{ConsensusState, SafetyRules},
-            vote_msg::VoteMsg,
+            local_safety_rules::LocalSafetyRules, safety_rules::ConsensusState, vote_msg::VoteMsg,
         },
         test_utils::{
             consensus_runtime, placeholder_certificate_for_block, placeholder_ledger_info,
@@ -50,6 +50,7 @@ use network::{
 use nextgen_crypto::ed25519::*;
 use proto_conv::FromProto;
 use std::{
+    collections::HashMap,
     sync::{Arc, RwLock},
     time::Duration,
 };
@@ -68,7 +69,7 @@ struct NodeSetup {
     storage: Arc<MockStorage<TestPayload>>,
     signer: ValidatorSigner<Ed25519PrivateKey>,
     proposer_author: Author,
-    peers: Arc<Vec<Author>>,
+    validators: Arc<ValidatorVerifier<Ed25519PublicKey>>,
     pacemaker: Arc<dyn Pacemaker>,
     #[allow(dead_code)]
     commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures<Ed25519Signature>>,
@@ -90,6 +91,7 @@ impl NodeSetup {
             Arc::new(MockStateComputer::new(commit_cb_sender)),
             true,
             10, // max pruned blocks in mem
+            false,
         )))
     }
 
@@ -133,14 +135,17 @@ impl NodeSetup {
         num_nodes: usize,
     ) -> Vec<NodeSetup> {
         let mut signers = vec![];
-        let mut peers = vec![];
+        let mut author_to_public_keys = HashMap::new();
         for i in 0..num_nodes {
             let signer = ValidatorSigner::random([i as u8; 32]);
-            peers.push(signer.author());
+            author_to_public_keys.insert(signer.author(), signer.public_key());
             signers.push(signer);
         }
-        let proposer_author = peers[0];
-        let peers_ref = Arc::new(peers);
+        let proposer_author = signers[0].author();
+        let validators = Arc::new(
+            ValidatorVerifier::new_with_quorum_size(author_to_public_keys, 1)
+                .expect("Invalid quorum_size."),
+        );
         let mut nodes = vec![];
         for signer in signers.iter().take(num_nodes) {
             let (storage, initial_data) = MockStorage::<TestPayload>::start_for_testing();
@@ -149,7 +154,7 @@ impl NodeSetup {
                 executor.clone(),
                 signer.clone(),
                 proposer_author,
-                Arc::clone(&peers_ref),
+                Arc::clone(&validators),
                 storage,
                 initial_data,
             ));
@@ -162,7 +167,7 @@ impl NodeSetup {
         executor: TaskExecutor,
         signer: ValidatorSigner<Ed25519PrivateKey>,
         proposer_author: Author,
-        peers: Arc<Vec<Author>>,
+        validators: Arc<ValidatorVerifier<Ed25519PublicKey>>,
         storage: Arc<MockStorage<TestPayload>>,
         initial_data: RecoveryData<TestPayload>,
     ) -> Self {
@@ -173,14 +178,13 @@ impl NodeSetup {
         let author = signer.author();
 
         playground.add_node(author, consensus_tx, network_reqs_rx);
-        let validator = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+        let epoch_manager = Arc::new(EpochManager::new(0, Arc::clone(&validators)));
 
         let network = ConsensusNetworkImpl::new(
             signer.author(),
             network_sender,
             network_events,
-            Arc::clone(&peers),
-            Arc::new(validator),
+            Arc::clone(&epoch_manager),
         );
         let consensus_state = initial_data.state();
 
@@ -193,9 +197,10 @@ impl NodeSetup {
             1,
             true,
         );
-        let safety_rules = Arc::new(RwLock::new(SafetyRules::new(
+        let safety_rules = Arc::new(RwLock::new(LocalSafetyRules::new(
             block_store.clone(),
             consensus_state,
+            signer.clone(),
         )));
 
         let (pacemaker, new_rounds_receiver) =
@@ -208,7 +213,8 @@ impl NodeSetup {
             author,
             Arc::clone(&block_store),
             Arc::clone(&pacemaker),
-            Arc::clone(&proposer_election),
+            epoch_manager,
+            Box::new(move |_| Arc::clone(&proposer_election)),
             proposal_generator,
             safety_rules,
             Arc::new(MockStateComputer::new(commit_cb_sender)),
@@ -226,7 +232,7 @@ impl NodeSetup {
             storage,
             signer,
             proposer_author,
-            peers,
+            validators,
             pacemaker,
             commit_cb_receiver,
         }
@@ -242,7 +248,7 @@ impl NodeSetup {
             executor,
             self.signer,
             self.proposer_author,
-            self.peers,
+            self.validators,
             self.storage,
             recover_data,
         )
@@ -348,6 +354,7 @@ fn process_successful_proposal_test() {
             proposal: Block::make_block(
                 genesis.as_ref(),
                 vec![1],
+                0,
                 1,
                 1,
                 genesis_qc.clone(),
@@ -392,6 +399,7 @@ fn process_old_proposal_test() {
     let new_block = Block::make_block(
         genesis.as_ref(),
         vec![1],
+        0,
         1,
         1,
         genesis_qc.clone(),
@@ -401,6 +409,7 @@ fn process_old_proposal_test() {
     let old_block = Block::make_block(
         genesis.as_ref(),
         vec![1],
+        0,
         1,
         2,
         genesis_qc.clone(),
@@ -453,6 +462,7 @@ fn process_round_mismatch_test() {
     let correct_block = Block::make_block(
         genesis.as_ref(),
         vec![1],
+        0,
         1,
         1,
         genesis_qc.clone(),
@@ -461,6 +471,7 @@ fn process_round_mismatch_test() {
     let block_skip_round = Block::make_block(
         genesis.as_ref(),
         vec![1],
+        0,
         2,
         2,
         genesis_qc.clone(),
@@ -501,7 +512,7 @@ fn process_new_round_msg_test() {
     let genesis = non_proposer.block_store.root();
     let block_0 = non_proposer
         .block_store
-        .create_block(genesis, vec![1], 1, 1);
+        .create_block(genesis, vec![1], 0, 1, 1);
     let block_0_id = block_0.id();
     block_on(
         non_proposer
@@ -547,18 +558,19 @@ fn process_new_round_msg_test() {
 
     // As the static proposer processes the new round message it should learn about
     // block_0_quorum_cert at round 1.
-    block_on(static_proposer.event_processor.process_timeout_msg(
-        TimeoutMsg::new(
-            SyncInfo::new(
-                block_0_quorum_cert,
-                QuorumCert::certificate_for_genesis(),
-                None,
-            ),
-            PacemakerTimeout::new(2, &non_proposer.signer, None),
-            &non_proposer.signer,
-        ),
-        2,
-    ));
+    block_on(
+        static_proposer
+            .event_processor
+            .process_timeout_msg(TimeoutMsg::new(
+                SyncInfo::new(
+                    block_0_quorum_cert,
+                    QuorumCert::certificate_for_genesis(),
+                    None,
+                ),
+                PacemakerTimeout::new(2, &non_proposer.signer, None),
+                &non_proposer.signer,
+            )),
+    );
     assert_eq!(
         static_proposer
             .block_store
@@ -583,6 +595,7 @@ fn process_proposer_mismatch_test() {
     let correct_block = Block::make_block(
         genesis.as_ref(),
         vec![1],
+        0,
         1,
         1,
         genesis_qc.clone(),
@@ -591,6 +604,7 @@ fn process_proposer_mismatch_test() {
     let block_incorrect_proposer = Block::make_block(
         genesis.as_ref(),
         vec![1],
+        0,
         1,
         1,
         genesis_qc.clone(),
@@ -619,6 +633,55 @@ fn process_proposer_mismatch_test() {
     });
 }
 
+#[test]
+/// We don't vote for proposals that belong to an epoch other than the current one
+fn process_epoch_mismatch_test() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.executor());
+    let mut nodes = NodeSetup::create_nodes(&mut playground, runtime.executor(), 1);
+    let node = nodes.pop().unwrap();
+    let genesis = node.block_store.root();
+    let genesis_qc = QuorumCert::certificate_for_genesis();
+    let block_wrong_epoch = Block::make_block(
+        genesis.as_ref(),
+        vec![1],
+        1,
+        1,
+        1,
+        genesis_qc.clone(),
+        node.block_store.signer(),
+    );
+    let correct_block = Block::make_block(
+        genesis.as_ref(),
+        vec![1],
+        0,
+        1,
+        1,
+        genesis_qc.clone(),
+        node.block_store.signer(),
+    );
+    block_on(async move {
+        let bad_proposal = ProposalMsg::<TestPayload> {
+            proposal: block_wrong_epoch,
+            sync_info: SyncInfo::new(genesis_qc.clone(), genesis_qc.clone(), None),
+        };
+        assert_eq!(
+            node.event_processor.process_proposal(bad_proposal).await,
+            ProcessProposalResult::Done(None)
+        );
+        let good_proposal = ProposalMsg::<TestPayload> {
+            proposal: correct_block,
+            sync_info: SyncInfo::new(genesis_qc.clone(), genesis_qc.clone(), None),
+        };
+        assert_eq!(
+            node.event_processor
+                .process_proposal(good_proposal.clone())
+                .await,
+            ProcessProposalResult::Done(Some(good_proposal))
+        );
+    });
+}
+
 #[test]
 /// We allow to 'skips' round if proposal carries timeout certificate for next round
 fn process_timeout_certificate_test() {
@@ -634,6 +697,7 @@ fn process_timeout_certificate_test() {
     let correct_block = Block::make_block(
         genesis.as_ref(),
         vec![1],
+        0,
         1,
         1,
         genesis_qc.clone(),
@@ -642,6 +706,7 @@ fn process_timeout_certificate_test() {
     let block_skip_round = Block::make_block(
         genesis.as_ref(),
         vec![1],
+        0,
         2,
         2,
         genesis_qc.clone(),
@@ -703,7 +768,7 @@ fn process_votes_basic_test() {
         let new_round_event = node.new_rounds_receiver.next().await.unwrap();
         assert_eq!(new_round_event.reason, NewRoundReason::QCReady);
         assert_eq!(new_round_event.round, 1);
-        node.event_processor.process_vote(vote_msg, 1).await;
+        node.event_processor.process_vote(vote_msg).await;
         let new_round_event = node.new_rounds_receiver.next().await.unwrap();
         // This is event from processing qc for round 1
         assert_eq!(new_round_event.reason, NewRoundReason::QCReady);
@@ -726,6 +791,7 @@ fn process_block_retrieval() {
     let block = Block::make_block(
         genesis.as_ref(),
         vec![1],
+        0,
         1,
         1,
         genesis_qc.clone(),
@@ -823,6 +889,7 @@ fn basic_restart_test() {
                 proposal: Block::make_block(
                     genesis.as_ref(),
                     vec![1],
+                    0,
                     i,
                     1,
                     genesis_qc.clone(),
@@ -403,6 +403,14 @@ impl Pacemaker for LocalPacemaker {
             .cloned()
     }
 
+    fn highest_timeout_certificates(&self) -> HighestTimeoutCertificates {
+        let guard = self.inner.read().unwrap();
+        guard
+            .pacemaker_timeout_manager
+            .highest_timeout_certificates()
+            .clone()
+    }
+
     fn process_certificates(
         &self,
         qc_round: Round,
@@ -438,4 +446,12 @@ impl Pacemaker for LocalPacemaker {
         }
         async {}.boxed()
     }
+
+    fn start_new_epoch(&self, timeout_certificate_quorum_size: usize) {
+        self.inner
+            .write()
+            .unwrap()
+            .pacemaker_timeout_manager
+            .start_new_epoch(timeout_certificate_quorum_size);
+    }
 }
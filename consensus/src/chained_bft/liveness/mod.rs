@@ -6,9 +6,15 @@ pub(crate) mod pacemaker;
 pub(crate) mod pacemaker_timeout_manager;
 pub(crate) mod proposal_generator;
 pub(crate) mod proposer_election;
+pub(crate) mod reputation_proposer_election;
 pub(crate) mod rotating_proposer_election;
+pub(crate) mod vrf_proposer_election;
 
 #[cfg(test)]
 mod local_pacemaker_test;
 #[cfg(test)]
+mod reputation_proposer_test;
+#[cfg(test)]
 mod rotating_proposer_test;
+#[cfg(test)]
+mod vrf_proposer_test;
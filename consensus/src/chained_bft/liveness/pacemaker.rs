@@ -4,6 +4,7 @@
 use crate::chained_bft::{
     common::Round,
     consensus_types::timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate},
+    liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
 };
 use futures::Future;
 use std::{
@@ -68,6 +69,9 @@ pub trait Pacemaker: Send + Sync {
     /// remotely received)
     fn highest_timeout_certificate(&self) -> Option<PacemakerTimeoutCertificate>;
 
+    /// Returns the highest timeout certificates gathered locally and received from other replicas
+    fn highest_timeout_certificates(&self) -> HighestTimeoutCertificates;
+
     /// Function to update current round based on received certificates.
     /// Both round of latest received QC and timeout certificates are taken into account.
     /// This function guarantees to update pacemaker state when promise that it returns is fulfilled
@@ -83,4 +87,8 @@ pub trait Pacemaker: Send + Sync {
         &self,
         pacemaker_timeout: PacemakerTimeout,
     ) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    /// The function is invoked upon the start of a new epoch: the timeouts of the previous epoch
+    /// are not going to form a timeout certificate anymore.
+    fn start_new_epoch(&self, timeout_certificate_quorum_size: usize);
 }
@@ -35,6 +35,16 @@ impl HighestTimeoutCertificates {
         }
     }
 
+    /// Returns the highest timeout certificate gathered locally
+    pub fn highest_local_timeout_certificate(&self) -> Option<&PacemakerTimeoutCertificate> {
+        self.highest_local_timeout_certificate.as_ref()
+    }
+
+    /// Returns the highest timeout certificate received from another replica
+    pub fn highest_received_timeout_certificate(&self) -> Option<&PacemakerTimeoutCertificate> {
+        self.highest_received_timeout_certificate.as_ref()
+    }
+
     /// Return a optional reference to the highest timeout certificate (locally generated or
     /// remotely received)
     pub fn highest_timeout_certificate(&self) -> Option<&PacemakerTimeoutCertificate> {
@@ -219,4 +229,16 @@ impl PacemakerTimeoutManager {
         self.highest_timeout_certificates
             .highest_timeout_certificate()
     }
+
+    /// Returns the highest timeout certificates gathered locally and received
+    pub fn highest_timeout_certificates(&self) -> &HighestTimeoutCertificates {
+        &self.highest_timeout_certificates
+    }
+
+    /// Discards the timeouts received from the validators of the previous epoch and updates the
+    /// quorum size to the one of the new epoch.
+    pub fn start_new_epoch(&mut self, timeout_certificate_quorum_size: usize) {
+        self.timeout_certificate_quorum_size = timeout_certificate_quorum_size;
+        self.author_to_received_timeouts.clear();
+    }
 }
@@ -44,7 +44,8 @@ pub enum ProposalGenerationError {
 /// round.
 /// ProposalGenerator is the one choosing the branch to extend:
 /// - height is determined as parent.height + 1,
-/// - round is given by the caller (typically determined by Pacemaker).
+/// - round is given by the caller (typically determined by Pacemaker),
+/// - epoch is given by the caller (the current epoch of the validator).
 /// The transactions for the proposed block are delivered by TxnManager.
 ///
 /// TxnManager should be aware of the pending transactions in the branch that it is extending,
@@ -84,7 +85,11 @@ impl<T: Payload> ProposalGenerator<T> {
     }
 
     /// Creates a NIL block proposal extending the highest certified block from the block store.
-    pub fn generate_nil_block(&self, round: Round) -> Result<Block<T>, ProposalGenerationError> {
+    pub fn generate_nil_block(
+        &self,
+        epoch: u64,
+        round: Round,
+    ) -> Result<Block<T>, ProposalGenerationError> {
         let hqc_block = self.block_store.highest_certified_block();
         if hqc_block.round() >= round {
             // The given round is too low.
@@ -96,6 +101,7 @@ impl<T: Payload> ProposalGenerator<T> {
             .ok_or_else(|| ProposalGenerationError::GivenRoundTooLow(hqc_block.round()))?;
         Ok(Block::make_nil_block(
             hqc_block.as_ref(),
+            epoch,
             round,
             hqc_block_qc.as_ref().clone(),
         ))
@@ -114,6 +120,7 @@ impl<T: Payload> ProposalGenerator<T> {
     /// error.
     pub async fn generate_proposal(
         &self,
+        epoch: u64,
         round: Round,
         round_deadline: Instant,
     ) -> Result<Block<T>, ProposalGenerationError> {
@@ -228,6 +235,7 @@ impl<T: Payload> ProposalGenerator<T> {
             Ok(txns) => Ok(block_store.create_block(
                 hqc_block,
                 txns,
+                epoch,
                 round,
                 block_timestamp.as_micros() as u64,
             )),
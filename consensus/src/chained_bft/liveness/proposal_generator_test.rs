@@ -35,14 +35,15 @@ fn test_proposal_generation_empty_tree() {
     let genesis = block_store.root();
 
     // Generate proposals for an empty tree.
-    let proposal = block_on(proposal_generator.generate_proposal(1, minute_from_now())).unwrap();
+    let proposal = block_on(proposal_generator.generate_proposal(0, 1, minute_from_now())).unwrap();
     assert_eq!(proposal.parent_id(), genesis.id());
     assert_eq!(proposal.round(), 1);
     assert_eq!(proposal.height(), 1);
     assert_eq!(proposal.quorum_cert().certified_block_id(), genesis.id());
 
     // Duplicate proposals on the same round are not allowed
-    let proposal_err = block_on(proposal_generator.generate_proposal(1, minute_from_now())).err();
+    let proposal_err =
+        block_on(proposal_generator.generate_proposal(0, 1, minute_from_now())).err();
     assert_eq!(
         proposal_err.unwrap(),
         ProposalGenerationError::AlreadyProposed(1)
@@ -67,7 +68,7 @@ fn test_proposal_generation_parent() {
     // With no certifications the parent is genesis
     // generate proposals for an empty tree.
     assert_eq!(
-        block_on(proposal_generator.generate_proposal(10, minute_from_now()))
+        block_on(proposal_generator.generate_proposal(0, 10, minute_from_now()))
             .unwrap()
             .parent_id(),
         genesis.id()
@@ -88,7 +89,7 @@ fn test_proposal_generation_parent() {
     );
     block_store.insert_vote_and_qc(vote_msg_a1, 1);
     let a1_child_res =
-        block_on(proposal_generator.generate_proposal(11, minute_from_now())).unwrap();
+        block_on(proposal_generator.generate_proposal(0, 11, minute_from_now())).unwrap();
     assert_eq!(a1_child_res.parent_id(), a1.id());
     assert_eq!(a1_child_res.round(), 11);
     assert_eq!(a1_child_res.height(), 2);
@@ -110,7 +111,7 @@ fn test_proposal_generation_parent() {
 
     block_store.insert_vote_and_qc(vote_msg_b1, 1);
     let b1_child_res =
-        block_on(proposal_generator.generate_proposal(12, minute_from_now())).unwrap();
+        block_on(proposal_generator.generate_proposal(0, 12, minute_from_now())).unwrap();
     assert_eq!(b1_child_res.parent_id(), b1.id());
     assert_eq!(b1_child_res.round(), 12);
     assert_eq!(b1_child_res.height(), 2);
@@ -144,7 +145,8 @@ fn test_old_proposal_generation() {
     );
     block_store.insert_vote_and_qc(vote_msg_a1, 1);
 
-    let proposal_err = block_on(proposal_generator.generate_proposal(1, minute_from_now())).err();
+    let proposal_err =
+        block_on(proposal_generator.generate_proposal(0, 1, minute_from_now())).err();
     assert_eq!(
         proposal_err.unwrap(),
         ProposalGenerationError::GivenRoundTooLow(1)
@@ -3,8 +3,11 @@
 
 use crate::chained_bft::{
     common::{Author, Round},
-    consensus_types::proposal_msg::ProposalMsg,
+    consensus_types::{proposal_msg::ProposalMsg, quorum_cert::QuorumCert},
 };
+use nextgen_crypto::ed25519::*;
+use std::sync::Arc;
+use types::validator_verifier::ValidatorVerifier;
 
 /// ProposerElection incorporates the logic of choosing a leader among multiple candidates.
 /// We are open to a possibility for having multiple proposers per round, the ultimate choice
@@ -23,4 +26,19 @@ pub trait ProposerElection<T> {
     /// proposer election is going to notify the client about the chosen proposal via a dedicated
     /// channel (to be passed in constructor).
     fn process_proposal(&self, proposal: ProposalMsg<T>) -> Option<ProposalMsg<T>>;
+
+    /// Returns the proof that this replica is a valid proposer of a block extending the given
+    /// quorum certificate, for the election protocols that require a proof (the proof is part of
+    /// the block id).
+    fn prove_proposal(&self, _quorum_cert: &QuorumCert) -> Option<Vec<u8>> {
+        None
+    }
 }
+
+/// Creates the proposer election of an epoch out of its validators: invoked at startup and then
+/// every time the validator set changes.
+pub type ProposerElectionFactory<T> = Box<
+    dyn Fn(&ValidatorVerifier<Ed25519PublicKey>) -> Arc<dyn ProposerElection<T> + Send + Sync>
+        + Send
+        + Sync,
+>;
@@ -0,0 +1,206 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    block_storage::BlockReader,
+    common::{Author, Payload, Round},
+    consensus_types::{
+        block::Block, committed_block_metadata::CommittedBlockMetadata, proposal_msg::ProposalMsg,
+    },
+    liveness::proposer_election::ProposerElection,
+    persistent_storage::PersistentStorage,
+};
+use crypto::HashValue;
+use failure::prelude::*;
+use logger::prelude::*;
+use std::{cmp::min, collections::HashSet, sync::Arc};
+
+/// Weight of a validator that proposed a block or signed a QC in the window.
+const ACTIVE_WEIGHT: u64 = 100;
+/// Weight of a validator that didn't show any activity in the window: it is still chosen once in
+/// a while, so that a validator that recovered can rebuild its reputation by proposing.
+const INACTIVE_WEIGHT: u64 = 1;
+
+/// The reputation proposer weights the proposers according to their recent activity: the
+/// validators that proposed blocks or whose votes made it to QCs in a sliding window of certified
+/// blocks are much more likely to be chosen than the ones that didn't (e.g., crashed validators),
+/// which otherwise would cost a full pacemaker timeout every time their turn comes.
+/// The choice is a deterministic function of the round and of the certified chain the proposal
+/// extends, so that all the honest replicas evaluating a proposal agree on it: the window is made
+/// of the ancestors of the certified block, read from the block tree down to its root and from
+/// the committed blocks metadata persisted at commit time below it.
+pub struct ReputationProposer<T> {
+    // Ordering of the candidates (all honest replicas must agree on this)
+    proposers: Vec<Author>,
+    // Number of contiguous rounds (i.e. round numbers increase by 1) a proposer is active
+    // in a row
+    contiguous_rounds: u32,
+    // Number of certified blocks considered for the reputation
+    window_size: usize,
+    // Source of the certified blocks that are not committed yet
+    block_reader: Arc<dyn BlockReader<Payload = T> + Send + Sync>,
+    // Source of the committed history
+    storage: Arc<dyn PersistentStorage<T>>,
+}
+
+impl<T: Payload> ReputationProposer<T> {
+    pub fn new(
+        proposers: Vec<Author>,
+        contiguous_rounds: u32,
+        window_size: usize,
+        block_reader: Arc<dyn BlockReader<Payload = T> + Send + Sync>,
+        storage: Arc<dyn PersistentStorage<T>>,
+    ) -> Self {
+        assert!(!proposers.is_empty());
+        Self {
+            proposers,
+            contiguous_rounds,
+            window_size,
+            block_reader,
+            storage,
+        }
+    }
+
+    /// Metadata of the most recent blocks proposed before the given round in the certified chain
+    /// ending with the given block, the most recent first.
+    fn window(
+        &self,
+        mut block: Arc<Block<T>>,
+        round: Round,
+    ) -> Result<Vec<CommittedBlockMetadata>> {
+        let mut window = vec![];
+        loop {
+            if window.len() == self.window_size {
+                return Ok(window);
+            }
+            if block.round() < round {
+                window.push(CommittedBlockMetadata::new(block.as_ref()));
+            }
+            if block.is_genesis_block() {
+                return Ok(window);
+            }
+            match self.block_reader.get_block(block.parent_id()) {
+                Some(parent) => block = parent,
+                None => break,
+            }
+        }
+        // The ancestors of the oldest block in memory are committed.
+        let max_round = match min(block.round(), round).checked_sub(1) {
+            Some(max_round) => max_round,
+            None => return Ok(window),
+        };
+        let limit = self.window_size - window.len();
+        window.extend(
+            self.storage
+                .get_committed_blocks(max_round, limit)
+                .map_err(|e| {
+                    format_err!(
+                        "Unable to read the committed blocks up to round {}: {:?}",
+                        max_round,
+                        e
+                    )
+                })?,
+        );
+        Ok(window)
+    }
+
+    /// The proposer of the given round for a proposal extending the given certified block.
+    fn get_proposer_extending(
+        &self,
+        certified_block: Arc<Block<T>>,
+        round: Round,
+    ) -> Result<Author> {
+        // All the rounds a proposer is active in a row are given the same proposer: the window
+        // only includes the blocks proposed before the first of these rounds.
+        let slot = round / u64::from(self.contiguous_rounds);
+        let mut active_authors = HashSet::new();
+        for metadata in self.window(certified_block, slot * u64::from(self.contiguous_rounds))? {
+            active_authors.extend(metadata.author());
+            active_authors.extend(metadata.signers().iter().cloned());
+        }
+        // Without any known activity (e.g., right after genesis) all the proposers are equal.
+        let weights: Vec<u64> = self
+            .proposers
+            .iter()
+            .map(|author| {
+                if active_authors.is_empty() || active_authors.contains(author) {
+                    ACTIVE_WEIGHT
+                } else {
+                    INACTIVE_WEIGHT
+                }
+            })
+            .collect();
+        let total_weight: u64 = weights.iter().sum();
+        let mut target = Self::seed(slot) % total_weight;
+        for (author, weight) in self.proposers.iter().zip(weights) {
+            if target < weight {
+                return Ok(*author);
+            }
+            target -= weight;
+        }
+        unreachable!("The target is lower than the total weight")
+    }
+
+    /// The proposer of the given round for a proposal extending the highest certified block known
+    /// locally, which is the block the next proposal extends in the common case.
+    fn get_proposer(&self, round: Round) -> Result<Author> {
+        self.get_proposer_extending(self.block_reader.highest_certified_block(), round)
+    }
+
+    /// Pseudo random value derived from the slot only, so that it is the same for everyone.
+    fn seed(slot: u64) -> u64 {
+        let hash = HashValue::from_iter_sha3(vec![&slot.to_le_bytes()[..]]);
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hash.to_vec()[..8]);
+        u64::from_le_bytes(bytes)
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for ReputationProposer<T> {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        match self.get_proposer(round) {
+            Ok(proposer) if proposer == author => Some(author),
+            Ok(_) => None,
+            Err(e) => {
+                error!("Unable to choose the proposer of round {}: {:?}", round, e);
+                None
+            }
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        match self.get_proposer(round) {
+            Ok(proposer) => vec![proposer],
+            Err(e) => {
+                error!("Unable to choose the proposer of round {}: {:?}", round, e);
+                vec![]
+            }
+        }
+    }
+
+    fn process_proposal(&self, proposal: ProposalMsg<T>) -> Option<ProposalMsg<T>> {
+        // The proposer is chosen according to the certified chain the proposal extends, which is
+        // the same for all the replicas evaluating the proposal.
+        let certified_block = match self
+            .block_reader
+            .get_block(proposal.proposal.quorum_cert().certified_block_id())
+        {
+            Some(block) => block,
+            None => {
+                warn!("The parent of proposal {} is unknown", proposal);
+                return None;
+            }
+        };
+        match self.get_proposer_extending(certified_block, proposal.proposal.round()) {
+            Ok(proposer) if proposer == proposal.proposer() => Some(proposal),
+            Ok(_) => None,
+            Err(e) => {
+                error!(
+                    "Unable to choose the proposer of proposal {}: {:?}",
+                    proposal, e
+                );
+                None
+            }
+        }
+    }
+}
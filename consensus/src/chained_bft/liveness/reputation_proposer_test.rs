@@ -0,0 +1,237 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    block_storage::{BlockReader, BlockStore},
+    common::{Author, Round},
+    consensus_types::{
+        block::Block, committed_block_metadata::CommittedBlockMetadata, proposal_msg::ProposalMsg,
+        quorum_cert::QuorumCert, sync_info::SyncInfo,
+    },
+    liveness::{
+        proposer_election::ProposerElection, reputation_proposer_election::ReputationProposer,
+    },
+    persistent_storage::PersistentStorage,
+    test_utils::{
+        build_empty_tree_with_custom_signing, placeholder_certificate_for_block, MockStorage,
+        TestPayload, TreeInserter,
+    },
+};
+use nextgen_crypto::ed25519::*;
+use std::sync::Arc;
+use types::validator_signer::ValidatorSigner;
+
+fn signers() -> Vec<ValidatorSigner<Ed25519PrivateKey>> {
+    (0..3)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i as u8; 32]))
+        .collect()
+}
+
+/// Builds a chain of 10 blocks proposed and certified by the first validator only.
+fn build_active_chain(
+    signers: &[ValidatorSigner<Ed25519PrivateKey>],
+) -> (Arc<BlockStore<TestPayload>>, Vec<Arc<Block<TestPayload>>>) {
+    let block_store = build_empty_tree_with_custom_signing(signers[0].clone());
+    let mut inserter = TreeInserter::new(block_store.clone());
+    let mut blocks = vec![];
+    let mut block = block_store.root();
+    for round in 1..=10 {
+        block = inserter.insert_block(block.as_ref(), round);
+        blocks.push(block.clone());
+    }
+    (block_store, blocks)
+}
+
+/// The author of the only proposal extending the given certified block at the given round that
+/// is accepted by the proposer election.
+fn proposer_extending(
+    pe: &dyn ProposerElection<TestPayload>,
+    signers: &[ValidatorSigner<Ed25519PrivateKey>],
+    certified_block: &Block<TestPayload>,
+    round: Round,
+) -> Author {
+    let quorum_cert = placeholder_certificate_for_block(
+        vec![&signers[0]],
+        certified_block.id(),
+        certified_block.round(),
+        certified_block.quorum_cert().certified_block_id(),
+        certified_block.quorum_cert().certified_block_round(),
+        certified_block.quorum_cert().certified_parent_block_id(),
+        certified_block.quorum_cert().certified_parent_block_round(),
+    );
+    let accepted: Vec<_> = signers
+        .iter()
+        .filter(|signer| {
+            let proposal = ProposalMsg {
+                proposal: Block::make_block(
+                    certified_block,
+                    vec![round as usize],
+                    0,
+                    round,
+                    certified_block.timestamp_usecs() + 1,
+                    quorum_cert.clone(),
+                    signer,
+                ),
+                sync_info: SyncInfo::new(quorum_cert.clone(), quorum_cert.clone(), None),
+            };
+            pe.process_proposal(proposal).is_some()
+        })
+        .map(|signer| signer.author())
+        .collect();
+    assert_eq!(accepted.len(), 1);
+    accepted[0]
+}
+
+#[test]
+fn test_reputation_proposer_without_history() {
+    let signers = signers();
+    let proposers: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+    let block_store = build_empty_tree_with_custom_signing(signers[0].clone());
+    let (storage, _) = MockStorage::<TestPayload>::start_for_testing();
+    let pe: Arc<dyn ProposerElection<TestPayload>> = Arc::new(ReputationProposer::new(
+        proposers.clone(),
+        1,
+        10,
+        block_store,
+        storage,
+    ));
+
+    // Without any certified block all the proposers get their turn.
+    let elected: Vec<_> = (0..300)
+        .map(|round| pe.get_valid_proposers(round)[0])
+        .collect();
+    for proposer in &proposers {
+        assert!(elected.contains(proposer));
+    }
+    for (round, author) in elected.iter().enumerate() {
+        assert_eq!(pe.is_valid_proposer(*author, round as u64), Some(*author));
+    }
+}
+
+#[test]
+fn test_reputation_proposer_favors_active_validators() {
+    let signers = signers();
+    let proposers: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+    let (block_store, blocks) = build_active_chain(&signers);
+    let (storage, _) = MockStorage::<TestPayload>::start_for_testing();
+    let pe = ReputationProposer::new(proposers.clone(), 1, 10, block_store, storage);
+
+    let last_block = blocks.last().unwrap();
+    let active_proposer_rounds = (20..1020)
+        .filter(|round| proposer_extending(&pe, &signers, last_block, *round) == proposers[0])
+        .count();
+    assert!(active_proposer_rounds > 900);
+}
+
+#[test]
+fn test_reputation_proposer_window_follows_certified_chain() {
+    let signers = signers();
+    let proposers: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+    let (block_store, _) = build_active_chain(&signers);
+    let genesis = block_store.root();
+    let (storage, _) = MockStorage::<TestPayload>::start_for_testing();
+    let pe = ReputationProposer::new(proposers.clone(), 1, 10, block_store, storage);
+
+    // A proposal forking from genesis doesn't inherit the activity of the chain it abandons: all
+    // the proposers get their turn.
+    let elected: Vec<_> = (20..320)
+        .map(|round| proposer_extending(&pe, &signers, genesis.as_ref(), round))
+        .collect();
+    for proposer in &proposers {
+        assert!(elected.contains(proposer));
+    }
+}
+
+#[test]
+fn test_reputation_proposer_committed_history() {
+    let signers = signers();
+    let proposers: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+    // A replica keeps the whole chain in memory.
+    let (block_store, blocks) = build_active_chain(&signers);
+    let (storage, _) = MockStorage::<TestPayload>::start_for_testing();
+    let pe = ReputationProposer::new(proposers.clone(), 1, 10, block_store, storage);
+
+    // Another replica committed the chain: its ancestry is read from the committed history.
+    let (other_block_store, other_blocks) = build_active_chain(&signers);
+    let (other_storage, _) = MockStorage::<TestPayload>::start_for_testing();
+    other_storage
+        .save_committed_blocks(
+            other_blocks
+                .iter()
+                .map(|block| CommittedBlockMetadata::new(block.as_ref()))
+                .collect(),
+        )
+        .unwrap();
+    let last_block = other_blocks.last().unwrap();
+    other_block_store.prune_tree(last_block.id());
+    let other_pe =
+        ReputationProposer::new(proposers.clone(), 1, 10, other_block_store, other_storage);
+
+    // Both replicas make the same choices for the proposals extending the same certified block.
+    for round in 11..200 {
+        assert_eq!(
+            proposer_extending(&pe, &signers, blocks.last().unwrap(), round),
+            proposer_extending(&other_pe, &signers, last_block, round)
+        );
+    }
+}
+
+#[test]
+fn test_reputation_proposer_contiguous_rounds() {
+    let signers = signers();
+    let proposers: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+    let block_store = build_empty_tree_with_custom_signing(signers[0].clone());
+    let (storage, _) = MockStorage::<TestPayload>::start_for_testing();
+    let pe: Arc<dyn ProposerElection<TestPayload>> = Arc::new(ReputationProposer::new(
+        proposers,
+        2,
+        10,
+        block_store,
+        storage,
+    ));
+
+    for slot in 0..100 {
+        assert_eq!(
+            pe.get_valid_proposers(2 * slot),
+            pe.get_valid_proposers(2 * slot + 1)
+        );
+    }
+}
+
+#[test]
+fn test_reputation_proposer_process_proposal() {
+    let signers = signers();
+    let proposers: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+    let block_store = build_empty_tree_with_custom_signing(signers[0].clone());
+    let (storage, _) = MockStorage::<TestPayload>::start_for_testing();
+    let pe: Arc<dyn ProposerElection<TestPayload>> = Arc::new(ReputationProposer::new(
+        proposers.clone(),
+        1,
+        10,
+        block_store,
+        storage,
+    ));
+
+    let genesis_block = Block::make_genesis_block();
+    let quorum_cert = QuorumCert::certificate_for_genesis();
+    let chosen_author = pe.get_valid_proposers(1)[0];
+    for signer in &signers {
+        let proposal = ProposalMsg {
+            proposal: Block::make_block(
+                &genesis_block,
+                vec![1],
+                0,
+                1,
+                1,
+                quorum_cert.clone(),
+                signer,
+            ),
+            sync_info: SyncInfo::new(quorum_cert.clone(), quorum_cert.clone(), None),
+        };
+        if signer.author() == chosen_author {
+            assert_eq!(pe.process_proposal(proposal.clone()), Some(proposal));
+        } else {
+            assert_eq!(pe.process_proposal(proposal), None);
+        }
+    }
+}
@@ -31,6 +31,7 @@ fn test_rotating_proposer() {
         proposal: Block::make_block(
             &genesis_block,
             1,
+            0,
             1,
             1,
             quorum_cert.clone(),
@@ -42,6 +43,7 @@ fn test_rotating_proposer() {
         proposal: Block::make_block(
             &genesis_block,
             2,
+            0,
             1,
             2,
             quorum_cert.clone(),
@@ -53,6 +55,7 @@ fn test_rotating_proposer() {
         proposal: Block::make_block(
             &genesis_block,
             3,
+            0,
             2,
             3,
             quorum_cert.clone(),
@@ -100,6 +103,7 @@ fn test_rotating_proposer_with_three_contiguous_rounds() {
         proposal: Block::make_block(
             &genesis_block,
             1,
+            0,
             1,
             1,
             quorum_cert.clone(),
@@ -111,6 +115,7 @@ fn test_rotating_proposer_with_three_contiguous_rounds() {
         proposal: Block::make_block(
             &genesis_block,
             2,
+            0,
             1,
             2,
             quorum_cert.clone(),
@@ -122,6 +127,7 @@ fn test_rotating_proposer_with_three_contiguous_rounds() {
         proposal: Block::make_block(
             &genesis_block,
             3,
+            0,
             2,
             3,
             quorum_cert.clone(),
@@ -166,6 +172,7 @@ fn test_fixed_proposer() {
         proposal: Block::make_block(
             &genesis_block,
             1,
+            0,
             1,
             1,
             quorum_cert.clone(),
@@ -177,6 +184,7 @@ fn test_fixed_proposer() {
         proposal: Block::make_block(
             &genesis_block,
             2,
+            0,
             1,
             2,
             quorum_cert.clone(),
@@ -188,6 +196,7 @@ fn test_fixed_proposer() {
         proposal: Block::make_block(
             &genesis_block,
             2,
+            0,
             2,
             3,
             quorum_cert.clone(),
@@ -0,0 +1,183 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    block_storage::BlockReader,
+    common::{Author, Payload, Round},
+    consensus_types::{block::Block, proposal_msg::ProposalMsg, quorum_cert::QuorumCert},
+    liveness::proposer_election::ProposerElection,
+};
+use crypto::{hash::CryptoHash, HashValue};
+use logger::prelude::*;
+use nextgen_crypto::{
+    ed25519::*,
+    vrf::ecvrf::{Output, Proof, VRFPrivateKey, VRFPublicKey},
+};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc};
+use types::validator_verifier::ValidatorVerifier;
+
+/// The leader of a round is derived from the VRF output carried by the certified block that is at
+/// least this number of rounds older: the replicas that are about to vote for a block, to collect
+/// the votes or to propose the next block all know this certified block.
+const RANDOMNESS_ROUNDS_DELAY: Round = 2;
+
+/// The VRF input of a proposal is the hash of the quorum certificate it extends.
+pub fn vrf_input(quorum_cert: &QuorumCert) -> HashValue {
+    quorum_cert.ledger_info().ledger_info().hash()
+}
+
+/// The VRF proposer maps a round to an author that can't be predicted much in advance, which
+/// protects the upcoming leaders from targeted denial of service attacks.
+/// Every proposal carries the proof of the VRF evaluated by its author with its dedicated VRF key
+/// over the hash of the quorum certificate it extends; the proof is part of the block id.
+/// The leader of a round is then chosen according to the VRF output of the block
+/// `RANDOMNESS_ROUNDS_DELAY` rounds before in the certified chain the proposal extends: it is
+/// known by everyone once this block has been proposed, and by nobody before.
+pub struct VrfProposer<T> {
+    // Ordering of proposers to choose from (all honest replicas must agree on this): the
+    // validators with a registered VRF public key
+    proposers: Vec<Author>,
+    // VRF public keys of the proposers
+    vrf_public_keys: HashMap<Author, VRFPublicKey>,
+    // VRF private key of this replica, required to prove its proposals
+    vrf_private_key: Option<Arc<VRFPrivateKey>>,
+    // Source of the certified blocks carrying the VRF proofs
+    block_reader: Arc<dyn BlockReader<Payload = T> + Send + Sync>,
+}
+
+impl<T: Payload> VrfProposer<T> {
+    pub fn new(
+        validators: &ValidatorVerifier<Ed25519PublicKey>,
+        vrf_public_keys: &HashMap<Author, VRFPublicKey>,
+        vrf_private_key: Option<Arc<VRFPrivateKey>>,
+        block_reader: Arc<dyn BlockReader<Payload = T> + Send + Sync>,
+    ) -> Self {
+        let proposers: Vec<_> = validators
+            .get_ordered_account_addresses()
+            .into_iter()
+            .filter(|author| vrf_public_keys.contains_key(author))
+            .collect();
+        assert!(
+            !proposers.is_empty(),
+            "No validator has a VRF public key registered"
+        );
+        let vrf_public_keys = proposers
+            .iter()
+            .map(|author| (*author, vrf_public_keys[author].clone()))
+            .collect();
+        Self {
+            proposers,
+            vrf_public_keys,
+            vrf_private_key,
+            block_reader,
+        }
+    }
+
+    /// VRF output of the most recent block old enough to choose the leader of the given round in
+    /// the certified chain ending with the given block (None if there is no such block with a
+    /// VRF proof, e.g., right after genesis).
+    fn randomness(&self, mut block: Arc<Block<T>>, round: Round) -> Option<Output> {
+        loop {
+            if block.round() + RANDOMNESS_ROUNDS_DELAY <= round {
+                // The proofs are part of the ids of the certified blocks, and were verified
+                // along with them.
+                if let Some(proof) = block.vrf_proof().and_then(|p| Proof::try_from(p).ok()) {
+                    return Some(Output::from(&proof));
+                }
+            }
+            if block.is_genesis_block() {
+                return None;
+            }
+            block = self.block_reader.get_block(block.parent_id())?;
+        }
+    }
+
+    /// The leader of the given round for a proposal extending the given certified block.
+    fn get_proposer_extending(&self, certified_block: Arc<Block<T>>, round: Round) -> Author {
+        let round_bytes = round.to_le_bytes();
+        let seed = match self.randomness(certified_block, round) {
+            Some(output) => {
+                HashValue::from_iter_sha3(vec![&output.to_bytes()[..], &round_bytes[..]])
+            }
+            None => HashValue::from_iter_sha3(vec![&round_bytes[..]]),
+        };
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&seed.to_vec()[..8]);
+        self.proposers[(u64::from_le_bytes(bytes) % self.proposers.len() as u64) as usize]
+    }
+
+    /// The leader of the given round for a proposal extending the highest certified block known
+    /// locally, which is the block the next proposal extends in the common case.
+    fn get_proposer(&self, round: Round) -> Author {
+        self.get_proposer_extending(self.block_reader.highest_certified_block(), round)
+    }
+
+    fn verify_proof(&self, proposal: &ProposalMsg<T>) -> bool {
+        let public_key = match self.vrf_public_keys.get(&proposal.proposer()) {
+            Some(public_key) => public_key,
+            None => return false,
+        };
+        let proof = match proposal
+            .proposal
+            .vrf_proof()
+            .and_then(|p| Proof::try_from(p).ok())
+        {
+            Some(proof) => proof,
+            None => return false,
+        };
+        let input = vrf_input(proposal.proposal.quorum_cert());
+        public_key.verify(&proof, input.as_ref()).is_ok()
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for VrfProposer<T> {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        if self.get_proposer(round) == author {
+            Some(author)
+        } else {
+            None
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        vec![self.get_proposer(round)]
+    }
+
+    fn process_proposal(&self, proposal: ProposalMsg<T>) -> Option<ProposalMsg<T>> {
+        // The leader is chosen according to the certified chain the proposal extends, which is
+        // the same for all the replicas evaluating the proposal.
+        let certified_block = match self
+            .block_reader
+            .get_block(proposal.proposal.quorum_cert().certified_block_id())
+        {
+            Some(block) => block,
+            None => {
+                warn!("The parent of proposal {} is unknown", proposal);
+                return None;
+            }
+        };
+        if self.get_proposer_extending(certified_block, proposal.proposal.round())
+            != proposal.proposer()
+        {
+            return None;
+        }
+        if !self.verify_proof(&proposal) {
+            warn!("Proposal {} carries an invalid VRF proof", proposal);
+            return None;
+        }
+        Some(proposal)
+    }
+
+    fn prove_proposal(&self, quorum_cert: &QuorumCert) -> Option<Vec<u8>> {
+        match &self.vrf_private_key {
+            Some(vrf_private_key) => {
+                let proof = vrf_private_key.prove(vrf_input(quorum_cert).as_ref());
+                Some(proof.to_bytes().to_vec())
+            }
+            None => {
+                error!("No VRF private key to prove a proposal");
+                None
+            }
+        }
+    }
+}
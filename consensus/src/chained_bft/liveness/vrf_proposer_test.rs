@@ -0,0 +1,230 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    block_storage::BlockReader,
+    common::Author,
+    consensus_types::{
+        block::{Block, BlockVerificationError},
+        proposal_msg::ProposalMsg,
+        quorum_cert::QuorumCert,
+        sync_info::SyncInfo,
+    },
+    epoch_manager::EpochManager,
+    liveness::{proposer_election::ProposerElection, vrf_proposer_election::VrfProposer},
+    test_utils::{build_empty_tree_with_custom_signing, TestPayload, TreeInserter},
+};
+use futures::executor::block_on;
+use nextgen_crypto::{
+    ed25519::*,
+    traits::Uniform,
+    vrf::ecvrf::{VRFPrivateKey, VRFPublicKey},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{collections::HashMap, sync::Arc};
+use types::{validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier};
+
+struct VrfSetup {
+    signers: Vec<ValidatorSigner<Ed25519PrivateKey>>,
+    validators: ValidatorVerifier<Ed25519PublicKey>,
+    vrf_public_keys: HashMap<Author, VRFPublicKey>,
+}
+
+impl VrfSetup {
+    fn new() -> Self {
+        let signers: Vec<_> = (0..3)
+            .map(|i| {
+                let mut rng = StdRng::from_seed([i as u8; 32]);
+                ValidatorSigner::new(None, Ed25519PrivateKey::generate_for_testing(&mut rng))
+            })
+            .collect();
+        let validators = ValidatorVerifier::new(
+            signers
+                .iter()
+                .map(|signer| (signer.author(), signer.public_key()))
+                .collect(),
+        );
+        let vrf_public_keys = signers
+            .iter()
+            .enumerate()
+            .map(|(i, signer)| (signer.author(), (&Self::vrf_private_key(i as u8)).into()))
+            .collect();
+        Self {
+            signers,
+            validators,
+            vrf_public_keys,
+        }
+    }
+
+    /// The VRF key of the i-th validator, independent from its consensus key
+    fn vrf_private_key(i: u8) -> VRFPrivateKey {
+        let mut rng = StdRng::from_seed([i + 100; 32]);
+        VRFPrivateKey::generate_for_testing(&mut rng)
+    }
+
+    /// Proposer election of the i-th validator, able to prove its own proposals
+    fn proposer_election(
+        &self,
+        i: u8,
+        block_reader: Arc<dyn BlockReader<Payload = TestPayload> + Send + Sync>,
+    ) -> VrfProposer<TestPayload> {
+        VrfProposer::new(
+            &self.validators,
+            &self.vrf_public_keys,
+            Some(Arc::new(Self::vrf_private_key(i))),
+            block_reader,
+        )
+    }
+
+    fn epoch_manager(&self) -> EpochManager {
+        EpochManager::new(0, Arc::new(self.validators.clone()))
+            .with_vrf_public_keys(self.vrf_public_keys.clone())
+    }
+}
+
+/// Attaches the VRF proof of the given proposer election to the proposal.
+fn prove(
+    pe: &VrfProposer<TestPayload>,
+    block: Block<TestPayload>,
+    signer: &ValidatorSigner<Ed25519PrivateKey>,
+) -> Block<TestPayload> {
+    let vrf_proof = pe.prove_proposal(block.quorum_cert()).unwrap();
+    block.with_vrf_proof(vrf_proof, signer)
+}
+
+fn make_proposal(signer: &ValidatorSigner<Ed25519PrivateKey>) -> ProposalMsg<TestPayload> {
+    let genesis_block = Block::make_genesis_block();
+    let quorum_cert = QuorumCert::certificate_for_genesis();
+    ProposalMsg {
+        proposal: Block::make_block(
+            &genesis_block,
+            vec![1],
+            0,
+            1,
+            1,
+            quorum_cert.clone(),
+            signer,
+        ),
+        sync_info: SyncInfo::new(quorum_cert.clone(), quorum_cert, None),
+    }
+}
+
+#[test]
+fn test_vrf_proposer_process_proposal() {
+    let setup = VrfSetup::new();
+    let block_store = build_empty_tree_with_custom_signing(setup.signers[0].clone());
+    let elections: Vec<_> = (0..3)
+        .map(|i| setup.proposer_election(i, block_store.clone()))
+        .collect();
+    let chosen_author = elections[0].get_valid_proposers(1)[0];
+
+    let epoch_manager = setup.epoch_manager();
+    for (i, signer) in setup.signers.iter().enumerate() {
+        let proposal = make_proposal(signer);
+        let proven_block = prove(&elections[i], proposal.proposal.clone(), signer);
+        assert!(proven_block.vrf_proof().is_some());
+        // The proof is part of the block id, which is signed again.
+        assert_ne!(proven_block.id(), proposal.proposal.id());
+        assert!(proven_block.verify(&epoch_manager).is_ok());
+        let proven_proposal = ProposalMsg {
+            proposal: proven_block,
+            sync_info: proposal.sync_info.clone(),
+        };
+        for pe in &elections {
+            if signer.author() == chosen_author {
+                assert_eq!(
+                    pe.process_proposal(proven_proposal.clone()),
+                    Some(proven_proposal.clone())
+                );
+                // The leader must prove its proposal.
+                assert_eq!(pe.process_proposal(proposal.clone()), None);
+            } else {
+                assert_eq!(pe.process_proposal(proven_proposal.clone()), None);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_vrf_proposer_rejects_proof_of_another_validator() {
+    let setup = VrfSetup::new();
+    let block_store = build_empty_tree_with_custom_signing(setup.signers[0].clone());
+    let elections: Vec<_> = (0..3)
+        .map(|i| setup.proposer_election(i, block_store.clone()))
+        .collect();
+    let chosen_author = elections[0].get_valid_proposers(1)[0];
+    let (leader, leader_signer) = setup
+        .signers
+        .iter()
+        .enumerate()
+        .find(|(_, signer)| signer.author() == chosen_author)
+        .unwrap();
+    let other = (leader + 1) % setup.signers.len();
+
+    let epoch_manager = setup.epoch_manager();
+    let proposal = make_proposal(leader_signer);
+    let forged_proposal = ProposalMsg {
+        proposal: prove(&elections[other], proposal.proposal.clone(), leader_signer),
+        sync_info: proposal.sync_info.clone(),
+    };
+    match forged_proposal.proposal.verify(&epoch_manager) {
+        Err(BlockVerificationError::InvalidVrfProof) => (),
+        result => panic!("Unexpected verification result: {:?}", result),
+    }
+    assert_eq!(elections[0].process_proposal(forged_proposal), None);
+
+    let garbage_proposal = ProposalMsg {
+        proposal: proposal
+            .proposal
+            .clone()
+            .with_vrf_proof(vec![0u8; 3], leader_signer),
+        sync_info: proposal.sync_info,
+    };
+    match garbage_proposal.proposal.verify(&epoch_manager) {
+        Err(BlockVerificationError::InvalidVrfProof) => (),
+        result => panic!("Unexpected verification result: {:?}", result),
+    }
+    assert_eq!(elections[0].process_proposal(garbage_proposal), None);
+}
+
+#[test]
+fn test_vrf_proposer_uses_certified_randomness() {
+    let setup = VrfSetup::new();
+    let block_store = build_empty_tree_with_custom_signing(setup.signers[0].clone());
+    let elections: Vec<_> = (0..3)
+        .map(|i| setup.proposer_election(i, block_store.clone()))
+        .collect();
+    let leaders_before: Vec<_> = (1..100)
+        .map(|round| elections[0].get_valid_proposers(round))
+        .collect();
+
+    // Certify a block of round 1 carrying a VRF proof.
+    let proven_block = prove(
+        &elections[0],
+        make_proposal(&setup.signers[0]).proposal,
+        &setup.signers[0],
+    );
+    let block = block_on(block_store.insert_block_with_qc(proven_block)).unwrap();
+    let mut inserter = TreeInserter::new(block_store.clone());
+    inserter.insert_block(block.as_ref(), 2);
+    assert_eq!(block_store.highest_certified_block().id(), block.id());
+
+    let leaders_after: Vec<_> = (1..100)
+        .map(|round| elections[0].get_valid_proposers(round))
+        .collect();
+    // The rounds right after the certified block aren't affected by its VRF output.
+    assert_eq!(leaders_before[..2], leaders_after[..2]);
+    assert_ne!(leaders_before[2..], leaders_after[2..]);
+
+    // All the replicas with the same certified blocks agree on the leaders.
+    for round in 0..100 {
+        assert_eq!(
+            elections[0].get_valid_proposers(round),
+            elections[1].get_valid_proposers(round)
+        );
+        assert_eq!(
+            elections[0].get_valid_proposers(round),
+            elections[2].get_valid_proposers(round)
+        );
+    }
+}
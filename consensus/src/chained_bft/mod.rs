@@ -3,16 +3,18 @@
 
 mod common;
 mod consensus_types;
-mod consensusdb;
+pub(crate) mod consensusdb;
 mod liveness;
-mod safety;
+pub(crate) mod safety;
 
 mod block_storage;
 pub mod chained_bft_consensus_provider;
 pub use consensus_types::quorum_cert::QuorumCert;
 mod chained_bft_smr;
+mod debug_info;
+pub(crate) mod epoch_manager;
 mod event_processor;
-mod network;
+pub(crate) mod network;
 
 pub mod persistent_storage;
 mod sync_manager;
@@ -20,6 +22,8 @@ mod sync_manager;
 #[cfg(test)]
 mod chained_bft_smr_test;
 #[cfg(test)]
+mod epoch_manager_test;
+#[cfg(test)]
 mod event_processor_test;
 #[cfg(test)]
 mod network_tests;
@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    batch_manager::{BatchStore, TxnBatch},
     chained_bft::{
         block_storage::BlockRetrievalFailure,
         common::{Author, Payload},
         consensus_types::{
             block::Block, proposal_msg::ProposalMsg, sync_info::SyncInfo, timeout_msg::TimeoutMsg,
         },
-        safety::vote_msg::VoteMsg,
+        epoch_manager::EpochManager,
+        safety::vote_msg::{VoteMsg, VoteMsgVerificationError},
     },
     counters,
 };
@@ -22,10 +24,12 @@ use futures::{
 };
 use logger::prelude::*;
 use network::{
-    proto::{BlockRetrievalStatus, ConsensusMsg, RequestBlock, RespondBlock, RespondChunk},
+    proto::{
+        BlockRetrievalStatus, ConsensusMsg, RequestBatches, RequestBlock, RespondAccountStateChunk,
+        RespondBatches, RespondBlock, RespondChunk,
+    },
     validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender, Event, RpcError},
 };
-use nextgen_crypto::ed25519::*;
 use proto_conv::{FromProto, IntoProto};
 use protobuf::Message;
 use std::{
@@ -34,8 +38,8 @@ use std::{
 };
 use tokio::runtime::TaskExecutor;
 use types::{
-    account_address::AccountAddress, transaction::TransactionListWithProof,
-    validator_verifier::ValidatorVerifier,
+    account_address::AccountAddress, account_state_blob::AccountStateRangeWithProof,
+    transaction::TransactionListWithProof,
 };
 
 /// The response sent back from EventProcessor for the BlockRetrievalRequest.
@@ -88,6 +92,17 @@ pub struct ChunkRetrievalRequest {
     pub response_sender: oneshot::Sender<Result<TransactionListWithProof, failure::Error>>,
 }
 
+/// Represents a request to get up to limit account states of the state snapshot at version,
+/// starting from start_key, with the oneshot sender to deliver the response.
+#[derive(Debug)]
+pub struct AccountStateChunkRetrievalRequest {
+    pub version: u64,
+    pub start_key: HashValue,
+    pub limit: u64,
+    pub response_sender:
+        oneshot::Sender<Result<(AccountStateRangeWithProof, Vec<HashValue>), failure::Error>>,
+}
+
 /// Just a convenience struct to keep all the network proxy receiving queues in one place.
 /// Will be returned by the networking trait upon startup.
 pub struct NetworkReceivers<T> {
@@ -96,6 +111,7 @@ pub struct NetworkReceivers<T> {
     pub block_retrieval: channel::Receiver<BlockRetrievalRequest<T>>,
     pub timeout_msgs: channel::Receiver<TimeoutMsg>,
     pub chunk_retrieval: channel::Receiver<ChunkRetrievalRequest>,
+    pub account_state_chunk_retrieval: channel::Receiver<AccountStateChunkRetrievalRequest>,
     pub sync_info_msgs: channel::Receiver<(SyncInfo, AccountAddress)>,
 }
 
@@ -109,8 +125,11 @@ pub struct ConsensusNetworkImpl {
     // Note that we do not support self rpc requests as it might cause infinite recursive calls.
     self_sender: channel::Sender<Result<Event<ConsensusMsg>, failure::Error>>,
     self_receiver: Option<channel::Receiver<Result<Event<ConsensusMsg>, failure::Error>>>,
-    peers: Arc<Vec<Author>>,
-    validator: Arc<ValidatorVerifier<Ed25519PublicKey>>,
+    // The messages are broadcasted to and verified against the validators of the current epoch.
+    epoch_manager: Arc<EpochManager>,
+    // Keeps the transaction batches disseminated by the validators (batch dissemination mode
+    // only): the batches received from the network are added to it and served from it.
+    batch_store: Option<Arc<BatchStore>>,
 }
 
 impl Clone for ConsensusNetworkImpl {
@@ -121,8 +140,8 @@ impl Clone for ConsensusNetworkImpl {
             network_events: None,
             self_sender: self.self_sender.clone(),
             self_receiver: None,
-            peers: self.peers.clone(),
-            validator: Arc::clone(&self.validator),
+            epoch_manager: Arc::clone(&self.epoch_manager),
+            batch_store: self.batch_store.clone(),
         }
     }
 }
@@ -132,8 +151,7 @@ impl ConsensusNetworkImpl {
         author: Author,
         network_sender: ConsensusNetworkSender,
         network_events: ConsensusNetworkEvents,
-        peers: Arc<Vec<Author>>,
-        validator: Arc<ValidatorVerifier<Ed25519PublicKey>>,
+        epoch_manager: Arc<EpochManager>,
     ) -> Self {
         let (self_sender, self_receiver) = channel::new(1_024, &counters::PENDING_SELF_MESSAGES);
         ConsensusNetworkImpl {
@@ -142,11 +160,17 @@ impl ConsensusNetworkImpl {
             network_events: Some(network_events),
             self_sender,
             self_receiver: Some(self_receiver),
-            peers,
-            validator,
+            epoch_manager,
+            batch_store: None,
         }
     }
 
+    /// Enables the batch dissemination protocol: the received batches are kept in the given
+    /// store, which serves the batch requests of the peers. Must be called before `start`.
+    pub fn set_batch_store(&mut self, batch_store: Arc<BatchStore>) {
+        self.batch_store = Some(batch_store);
+    }
+
     /// Establishes the initial connections with the peers and returns the receivers.
     pub fn start<T: Payload>(&mut self, executor: &TaskExecutor) -> NetworkReceivers<T> {
         let (proposal_tx, proposal_rx) = channel::new(1_024, &counters::PENDING_PROPOSAL);
@@ -155,6 +179,8 @@ impl ConsensusNetworkImpl {
             channel::new(1_024, &counters::PENDING_BLOCK_REQUESTS);
         let (chunk_request_tx, chunk_request_rx) =
             channel::new(1_024, &counters::PENDING_CHUNK_REQUESTS);
+        let (account_state_chunk_request_tx, account_state_chunk_request_rx) =
+            channel::new(1_024, &counters::PENDING_ACCOUNT_STATE_CHUNK_REQUESTS);
         let (timeout_msg_tx, timeout_msg_rx) =
             channel::new(1_024, &counters::PENDING_NEW_ROUND_MESSAGES);
         let (sync_info_tx, sync_info_rx) = channel::new(1_024, &counters::PENDING_SYNC_INFO_MSGS);
@@ -168,17 +194,20 @@ impl ConsensusNetworkImpl {
             .take()
             .expect("[consensus]: self receiver is already taken");
         let all_events = select(network_events, own_msgs);
-        let validator = Arc::clone(&self.validator);
+        let epoch_manager = Arc::clone(&self.epoch_manager);
+        let batch_store = self.batch_store.clone();
         executor.spawn(
             NetworkTask {
                 proposal_tx,
                 vote_tx,
                 block_request_tx,
                 chunk_request_tx,
+                account_state_chunk_request_tx,
                 timeout_msg_tx,
                 sync_info_tx,
                 all_events,
-                validator,
+                epoch_manager,
+                batch_store,
             }
             .run()
             .boxed()
@@ -191,6 +220,7 @@ impl ConsensusNetworkImpl {
             block_retrieval: block_request_rx,
             timeout_msgs: timeout_msg_rx,
             chunk_retrieval: chunk_request_rx,
+            account_state_chunk_retrieval: account_state_chunk_request_rx,
             sync_info_msgs: sync_info_rx,
         }
     }
@@ -221,7 +251,7 @@ impl ConsensusNetworkImpl {
         let mut blocks = vec![];
         for block in res_block.take_blocks().into_iter() {
             if let Ok(block) = Block::from_proto(block) {
-                if block.verify(self.validator.as_ref()).is_err() {
+                if block.verify(self.epoch_manager.as_ref()).is_err() {
                     return Err(BlockRetrievalFailure::InvalidSignature);
                 }
                 blocks.push(block);
@@ -256,7 +286,7 @@ impl ConsensusNetworkImpl {
     }
 
     async fn broadcast(&mut self, msg: ConsensusMsg) {
-        for peer in self.peers.iter() {
+        for peer in self.epoch_manager.peers().iter() {
             if self.author == *peer {
                 let self_msg = Event::Message((self.author, msg.clone()));
                 if let Err(err) = self.self_sender.send(Ok(self_msg)).await {
@@ -307,6 +337,48 @@ impl ConsensusNetworkImpl {
         self.broadcast(msg).await
     }
 
+    /// Sends the given batch of transactions to all the other validators ahead of the proposals
+    /// that refer to it.
+    ///
+    /// The future is fulfilled as soon as the messages are added to the internal network channel
+    /// (does not indicate whether the messages are delivered or sent out).
+    pub async fn broadcast_batch(&self, batch: TxnBatch) {
+        let mut network_sender = self.network_sender.clone();
+        let batch = batch.into_proto();
+        for peer in self.epoch_manager.peers() {
+            if peer == self.author {
+                continue;
+            }
+            if let Err(e) = network_sender.send_batch(peer, batch.clone()).await {
+                error!("Failed to send a batch to peer {:?}: {:?}", peer, e);
+            }
+        }
+    }
+
+    /// Tries to retrieve the batches of the given digests from the given peer. The response only
+    /// includes the requested batches known by the peer.
+    pub async fn request_batches(
+        &self,
+        digests: Vec<HashValue>,
+        from: Author,
+        timeout: Duration,
+    ) -> failure::Result<Vec<TxnBatch>> {
+        if from == self.author {
+            return Err(format_err!("Can't request batches from self"));
+        }
+        let mut req_msg = RequestBatches::new();
+        req_msg.set_digests(digests.into_iter().map(Into::into).collect());
+        let mut network_sender = self.network_sender.clone();
+        let mut response = network_sender
+            .request_batches(from, req_msg, timeout)
+            .await?;
+        let mut batches = vec![];
+        for batch in response.take_batches().into_iter() {
+            batches.push(TxnBatch::from_proto(batch)?);
+        }
+        Ok(batches)
+    }
+
     /// Sends the given sync info to the given author.
     /// The future is fulfilled as soon as the message is added to the internal network channel
     /// (does not indicate whether the message is delivered or sent out).
@@ -332,10 +404,12 @@ struct NetworkTask<T, S> {
     vote_tx: channel::Sender<VoteMsg>,
     block_request_tx: channel::Sender<BlockRetrievalRequest<T>>,
     chunk_request_tx: channel::Sender<ChunkRetrievalRequest>,
+    account_state_chunk_request_tx: channel::Sender<AccountStateChunkRetrievalRequest>,
     timeout_msg_tx: channel::Sender<TimeoutMsg>,
     sync_info_tx: channel::Sender<(SyncInfo, AccountAddress)>,
     all_events: S,
-    validator: Arc<ValidatorVerifier<Ed25519PublicKey>>,
+    epoch_manager: Arc<EpochManager>,
+    batch_store: Option<Arc<BatchStore>>,
 }
 
 impl<T, S> NetworkTask<T, S>
@@ -348,13 +422,15 @@ where
             match message {
                 Event::Message((peer_id, mut msg)) => {
                     let r = if msg.has_proposal() {
-                        self.process_proposal(&mut msg).await
+                        self.process_proposal(&mut msg, peer_id).await
                     } else if msg.has_vote() {
                         self.process_vote(&mut msg).await
                     } else if msg.has_timeout_msg() {
                         self.process_timeout_msg(&mut msg).await
                     } else if msg.has_sync_info() {
                         self.process_sync_info(&mut msg, peer_id).await
+                    } else if msg.has_txn_batch() {
+                        self.process_txn_batch(&mut msg, peer_id)
                     } else {
                         warn!("Unexpected msg from {}: {:?}", peer_id, msg);
                         continue;
@@ -368,6 +444,11 @@ where
                         self.process_request_block(&mut msg, callback).await
                     } else if msg.has_request_chunk() {
                         self.process_request_chunk(&mut msg, callback).await
+                    } else if msg.has_request_account_state_chunk() {
+                        self.process_request_account_state_chunk(&mut msg, callback)
+                            .await
+                    } else if msg.has_request_batches() {
+                        self.process_request_batches(&mut msg, callback)
                     } else {
                         warn!("Unexpected RPC from {}: {:?}", peer_id, msg);
                         continue;
@@ -386,9 +467,37 @@ where
         }
     }
 
-    async fn process_proposal<'a>(&'a mut self, msg: &'a mut ConsensusMsg) -> failure::Result<()> {
+    async fn process_proposal<'a>(
+        &'a mut self,
+        msg: &'a mut ConsensusMsg,
+        peer: AccountAddress,
+    ) -> failure::Result<()> {
         let proposal = ProposalMsg::<T>::from_proto(msg.take_proposal())?;
-        proposal.verify(self.validator.as_ref()).map_err(|e| {
+        if self
+            .epoch_manager
+            .validators_for_epoch(proposal.proposal.epoch())
+            .is_none()
+        {
+            // The proposal belongs to an epoch that has not started locally yet: it can't be
+            // verified, but its sync info carries the certificates of the previous epoch that
+            // commit the reconfiguration, which are used to catch up.
+            debug!(
+                "Received proposal {} of unknown epoch {}, processing its sync info",
+                proposal,
+                proposal.proposal.epoch()
+            );
+            let sync_info = proposal.sync_info;
+            sync_info.verify(self.epoch_manager.as_ref()).map_err(|e| {
+                security_log(SecurityEvent::InvalidSyncInfoMsg)
+                    .error(&e)
+                    .data(&sync_info)
+                    .log();
+                e
+            })?;
+            self.sync_info_tx.send((sync_info, peer)).await?;
+            return Ok(());
+        }
+        proposal.verify(self.epoch_manager.as_ref()).map_err(|e| {
             security_log(SecurityEvent::InvalidConsensusProposal)
                 .error(&e)
                 .data(&proposal)
@@ -403,13 +512,20 @@ where
     async fn process_vote<'a>(&'a mut self, msg: &'a mut ConsensusMsg) -> failure::Result<()> {
         let vote = VoteMsg::from_proto(msg.take_vote())?;
         debug!("Received {}", vote);
-        vote.verify(self.validator.as_ref()).map_err(|e| {
-            security_log(SecurityEvent::InvalidConsensusVote)
-                .error(&e)
-                .data(&vote)
-                .log();
-            e
-        })?;
+        let validator = self
+            .epoch_manager
+            .validators_for_epoch(vote.epoch())
+            .ok_or_else(|| VoteMsgVerificationError::UnknownEpoch(vote.epoch()));
+        validator
+            .and_then(|validator| vote.verify(validator.as_ref()))
+            .and_then(|()| self.epoch_manager.verify_execution_mode(&vote))
+            .map_err(|e| {
+                security_log(SecurityEvent::InvalidConsensusVote)
+                    .error(&e)
+                    .data(&vote)
+                    .log();
+                e
+            })?;
         self.vote_tx.send(vote).await?;
         Ok(())
     }
@@ -419,13 +535,15 @@ where
         msg: &'a mut ConsensusMsg,
     ) -> failure::Result<()> {
         let timeout_msg = TimeoutMsg::from_proto(msg.take_timeout_msg())?;
-        timeout_msg.verify(self.validator.as_ref()).map_err(|e| {
-            security_log(SecurityEvent::InvalidConsensusRound)
-                .error(&e)
-                .data(&timeout_msg)
-                .log();
-            e
-        })?;
+        timeout_msg
+            .verify(self.epoch_manager.validators().as_ref())
+            .map_err(|e| {
+                security_log(SecurityEvent::InvalidConsensusRound)
+                    .error(&e)
+                    .data(&timeout_msg)
+                    .log();
+                e
+            })?;
         self.timeout_msg_tx.send(timeout_msg).await?;
         Ok(())
     }
@@ -436,7 +554,7 @@ where
         peer: AccountAddress,
     ) -> failure::Result<()> {
         let sync_info = SyncInfo::from_proto(msg.take_sync_info())?;
-        sync_info.verify(self.validator.as_ref()).map_err(|e| {
+        sync_info.verify(self.epoch_manager.as_ref()).map_err(|e| {
             security_log(SecurityEvent::InvalidSyncInfoMsg)
                 .error(&e)
                 .data(&sync_info)
@@ -447,6 +565,67 @@ where
         Ok(())
     }
 
+    fn process_txn_batch(
+        &mut self,
+        msg: &mut ConsensusMsg,
+        peer: AccountAddress,
+    ) -> failure::Result<()> {
+        let batch_store = self
+            .batch_store
+            .as_ref()
+            .ok_or_else(|| format_err!("Batch dissemination is disabled"))?;
+        let batch = TxnBatch::from_proto(msg.take_txn_batch())?;
+        // A validator only disseminates its own batches.
+        if batch.author() != peer || !self.epoch_manager.peers().contains(&peer) {
+            security_log(SecurityEvent::InvalidTxnBatch)
+                .data(&peer)
+                .data(&batch)
+                .log();
+            return Err(format_err!(
+                "Batch {} of {} sent by {}",
+                batch.digest(),
+                batch.author(),
+                peer
+            ));
+        }
+        debug!("Received batch {} of {} txns", batch.digest(), batch.len());
+        batch_store.insert(batch);
+        Ok(())
+    }
+
+    fn process_request_batches(
+        &mut self,
+        msg: &mut ConsensusMsg,
+        callback: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> failure::Result<()> {
+        let batch_store = self
+            .batch_store
+            .as_ref()
+            .ok_or_else(|| format_err!("Batch dissemination is disabled"))?;
+        let mut batches = vec![];
+        for digest in msg.get_request_batches().get_digests() {
+            if let Some(batch) = batch_store.get(&HashValue::from_slice(digest)?) {
+                batches.push(TxnBatch::clone(&batch).into_proto());
+            }
+        }
+        debug!(
+            "Received request_batches RPC, responding with {} batches",
+            batches.len()
+        );
+        let mut response_msg = ConsensusMsg::new();
+        let mut response = RespondBatches::new();
+        response.set_batches(protobuf::RepeatedField::from_vec(batches));
+        response_msg.set_respond_batches(response);
+        let response_data = Bytes::from(
+            response_msg
+                .write_to_bytes()
+                .expect("fail to serialize proto"),
+        );
+        callback
+            .send(Ok(response_data))
+            .map_err(|_| format_err!("handling inbound rpc call timed out"))
+    }
+
     async fn process_request_chunk<'a>(
         &'a mut self,
         msg: &'a mut ConsensusMsg,
@@ -484,6 +663,50 @@ where
             .map_err(|_| format_err!("handling inbound rpc call timed out"))
     }
 
+    async fn process_request_account_state_chunk<'a>(
+        &'a mut self,
+        msg: &'a mut ConsensusMsg,
+        callback: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> failure::Result<()> {
+        let req = msg.take_request_account_state_chunk();
+        let start_key = HashValue::from_slice(req.get_start_key())?;
+        debug!(
+            "Received request_account_state_chunk RPC for version: {} start key: {:x} limit: {}",
+            req.version, start_key, req.limit
+        );
+        let (tx, rx) = oneshot::channel();
+        let request = AccountStateChunkRetrievalRequest {
+            version: req.version,
+            start_key,
+            limit: req.limit,
+            response_sender: tx,
+        };
+        self.account_state_chunk_request_tx.send(request).await?;
+        callback
+            .send(match rx.await? {
+                Ok((account_state_chunk, ledger_frozen_subtree_hashes)) => {
+                    let mut response_msg = ConsensusMsg::new();
+                    let mut response = RespondAccountStateChunk::new();
+                    response.set_account_state_chunk(account_state_chunk.into_proto());
+                    response.set_ledger_frozen_subtree_hashes(protobuf::RepeatedField::from_vec(
+                        ledger_frozen_subtree_hashes
+                            .into_iter()
+                            .map(|hash| hash.to_vec().into())
+                            .collect(),
+                    ));
+                    response_msg.set_respond_account_state_chunk(response);
+                    let response_data = Bytes::from(
+                        response_msg
+                            .write_to_bytes()
+                            .expect("fail to serialize proto"),
+                    );
+                    Ok(response_data)
+                }
+                Err(err) => Err(RpcError::ApplicationError(err)),
+            })
+            .map_err(|_| format_err!("handling inbound rpc call timed out"))
+    }
+
     async fn process_request_block<'a>(
         &'a mut self,
         msg: &'a mut ConsensusMsg,
@@ -3,13 +3,17 @@
 
 use crate::{
     chained_bft::{
-        common::Author,
+        common::{Author, Round},
         consensus_types::{
             block::Block, proposal_msg::ProposalMsg, quorum_cert::QuorumCert, sync_info::SyncInfo,
         },
+        epoch_manager::EpochManager,
         network::{BlockRetrievalResponse, ConsensusNetworkImpl, NetworkReceivers},
         safety::vote_msg::VoteMsg,
-        test_utils::{consensus_runtime, placeholder_ledger_info},
+        test_utils::{
+            consensus_runtime, placeholder_certificate_for_block, placeholder_ledger_info,
+            TestPayload,
+        },
     },
     state_replication::ExecutedState,
 };
@@ -18,12 +22,16 @@ use crypto::HashValue;
 use futures::{channel::mpsc, executor::block_on, FutureExt, SinkExt, StreamExt, TryFutureExt};
 use network::{
     interface::{NetworkNotification, NetworkRequest},
-    proto::{BlockRetrievalStatus, ConsensusMsg, QuorumCert as ProtoQuorumCert, RequestChunk},
-    protocols::rpc::InboundRpcRequest,
+    proto::{
+        BlockRetrievalStatus, ConsensusMsg, QuorumCert as ProtoQuorumCert,
+        RequestAccountStateChunk, RequestChunk,
+    },
+    protocols::{direct_send::Message, rpc::InboundRpcRequest},
     validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender},
 };
 use nextgen_crypto::ed25519::*;
-use proto_conv::FromProto;
+use proto_conv::{FromProto, IntoProto};
+use protobuf::Message as ProtobufMessage;
 use std::{
     collections::{HashMap, HashSet},
     sync::{Arc, Mutex, RwLock},
@@ -32,6 +40,8 @@ use std::{
 use tokio::runtime::TaskExecutor;
 use types::{
     account_address::AccountAddress,
+    account_state_blob::{AccountStateBlob, AccountStateRangeWithProof},
+    proof::{AccountStateRangeProof, AccumulatorProof, SparseMerkleProof, SparseMerkleRangeProof},
     proto::ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     test_helpers::transaction_test_helpers::get_test_signed_txn,
     transaction::{SignedTransaction, TransactionInfo, TransactionListWithProof},
@@ -61,6 +71,12 @@ pub struct NetworkPlayground {
     drop_config: Arc<RwLock<DropConfig>>,
     /// An executor for spawning node outbound network event handlers
     executor: TaskExecutor,
+    /// Nodes whose direct-send messages are altered according to their Byzantine behaviors.
+    byzantine_nodes: HashMap<Author, ByzantineNode>,
+    /// The delivered proposals by round.
+    proposals: HashMap<Round, HashMap<HashValue, Block<TestPayload>>>,
+    /// The ids of the blocks voted by each author at each round in the delivered messages.
+    votes: HashMap<(Author, Round), HashSet<HashValue>>,
 }
 
 impl NetworkPlayground {
@@ -73,6 +89,9 @@ impl NetworkPlayground {
             outbound_msgs_rx,
             drop_config: Arc::new(RwLock::new(DropConfig(HashMap::new()))),
             executor,
+            byzantine_nodes: HashMap::new(),
+            proposals: HashMap::new(),
+            votes: HashMap::new(),
         }
     }
 
@@ -232,9 +251,13 @@ impl NetworkPlayground {
 
             // Deliver and copy message it if it's not dropped
             if !self.is_message_dropped(&src, &net_req) {
-                let msg_copy = self.deliver_message(src, net_req).await;
-                if msg_inspector(&msg_copy) {
-                    msg_copies.push(msg_copy);
+                // A Byzantine node might send several messages instead of one, or none at all.
+                for net_req in self.apply_byzantine_behaviors(src, net_req) {
+                    let msg_copy = self.deliver_message(src, net_req).await;
+                    self.record_message(&msg_copy);
+                    if msg_inspector(&msg_copy) && msg_copies.len() < num_messages {
+                        msg_copies.push(msg_copy);
+                    }
                 }
             }
         }
@@ -289,6 +312,235 @@ impl NetworkPlayground {
             .unwrap()
             .stop_drop_message_for(src, dst)
     }
+
+    /// From now on, the direct-send messages of the given node are altered according to the
+    /// given behaviors. The node signs the messages it did not send with the given signer.
+    pub fn make_byzantine(
+        &mut self,
+        author: Author,
+        signer: ValidatorSigner<Ed25519PrivateKey>,
+        behaviors: Vec<ByzantineBehavior>,
+    ) {
+        self.byzantine_nodes
+            .insert(author, ByzantineNode { signer, behaviors });
+    }
+
+    /// Returns the authors that voted for different blocks at a same round in the delivered
+    /// messages.
+    pub fn equivocating_voters(&self) -> HashSet<Author> {
+        self.votes
+            .iter()
+            .filter(|(_, block_ids)| block_ids.len() > 1)
+            .map(|((author, _), _)| *author)
+            .collect()
+    }
+
+    /// Returns the number of rounds with different delivered proposals.
+    pub fn num_equivocated_rounds(&self) -> usize {
+        self.proposals
+            .values()
+            .filter(|proposals| proposals.len() > 1)
+            .count()
+    }
+
+    /// Returns the ids of the delivered proposals of the given author.
+    pub fn delivered_proposals_of(&self, author: Author) -> Vec<HashValue> {
+        self.proposals
+            .values()
+            .flat_map(|proposals| proposals.values())
+            .filter(|block| block.author() == Some(author))
+            .map(|block| block.id())
+            .collect()
+    }
+
+    fn record_message(&mut self, msg_copy: &(Author, ConsensusMsg)) {
+        let (src, msg) = msg_copy;
+        if msg.has_proposal() {
+            let proposal =
+                ProposalMsg::<TestPayload>::from_proto(msg.get_proposal().clone()).unwrap();
+            self.proposals
+                .entry(proposal.proposal.round())
+                .or_default()
+                .insert(proposal.proposal.id(), proposal.proposal);
+        }
+        let vote = if msg.has_vote() {
+            Some(msg.get_vote())
+        } else if msg.has_timeout_msg() && msg.get_timeout_msg().get_pacemaker_timeout().has_vote()
+        {
+            Some(msg.get_timeout_msg().get_pacemaker_timeout().get_vote())
+        } else {
+            None
+        };
+        if let Some(vote) = vote {
+            let vote = VoteMsg::from_proto(vote.clone()).unwrap();
+            self.votes
+                .entry((*src, vote.round()))
+                .or_default()
+                .insert(vote.proposed_block_id());
+        }
+    }
+
+    /// Alters a direct-send message from a Byzantine node according to its behaviors.
+    fn apply_byzantine_behaviors(
+        &self,
+        src: Author,
+        net_req: NetworkRequest,
+    ) -> Vec<NetworkRequest> {
+        let node = match self.byzantine_nodes.get(&src) {
+            Some(node) => node,
+            None => return vec![net_req],
+        };
+        let (dst, message) = match net_req {
+            NetworkRequest::SendMessage(dst, message) => (dst, message),
+            net_req => return vec![net_req],
+        };
+        let mut msgs: Vec<ConsensusMsg> =
+            vec![::protobuf::parse_from_bytes(message.mdata.as_ref()).unwrap()];
+        for behavior in &node.behaviors {
+            msgs = msgs
+                .into_iter()
+                .flat_map(|msg| self.apply_byzantine_behavior(*behavior, node, dst, msg))
+                .collect();
+        }
+        msgs.into_iter()
+            .map(|msg| {
+                NetworkRequest::SendMessage(
+                    dst,
+                    Message {
+                        protocol: message.protocol.clone(),
+                        mdata: msg.write_to_bytes().unwrap().into(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn apply_byzantine_behavior(
+        &self,
+        behavior: ByzantineBehavior,
+        node: &ByzantineNode,
+        dst: Author,
+        mut msg: ConsensusMsg,
+    ) -> Vec<ConsensusMsg> {
+        match behavior {
+            ByzantineBehavior::EquivocatingProposer if msg.has_proposal() => {
+                // The nodes at odd positions receive a conflicting proposal.
+                let mut authors: Vec<_> =
+                    self.drop_config.read().unwrap().0.keys().cloned().collect();
+                authors.sort();
+                if authors.iter().position(|author| *author == dst).unwrap() % 2 == 0 {
+                    return vec![msg];
+                }
+                let proposal = ProposalMsg::<TestPayload>::from_proto(msg.take_proposal()).unwrap();
+                let block = proposal.proposal;
+                let mut payload = block.get_payload().clone();
+                payload.push(0);
+                let conflicting_block = Block::new_internal(
+                    payload,
+                    block.parent_id(),
+                    block.epoch(),
+                    block.round(),
+                    block.height(),
+                    block.timestamp_usecs(),
+                    block.quorum_cert().clone(),
+                    &node.signer,
+                );
+                vec![Self::proposal_msg(conflicting_block, proposal.sync_info)]
+            }
+            ByzantineBehavior::DoubleVoter if msg.has_vote() => {
+                let vote = VoteMsg::from_proto(msg.get_vote().clone()).unwrap();
+                let mut msgs = vec![msg];
+                // Also vote for all the other known proposals of the round.
+                let conflicting_blocks = self
+                    .proposals
+                    .get(&vote.round())
+                    .into_iter()
+                    .flat_map(|proposals| proposals.values())
+                    .filter(|block| block.id() != vote.proposed_block_id());
+                for block in conflicting_blocks {
+                    let conflicting_vote = VoteMsg::new(
+                        block.id(),
+                        vote.executed_state(),
+                        block.round(),
+                        block.parent_id(),
+                        block.quorum_cert().certified_block_round(),
+                        block.quorum_cert().certified_parent_block_id(),
+                        block.quorum_cert().certified_parent_block_round(),
+                        vote.author(),
+                        vote.ledger_info().clone(),
+                        &node.signer,
+                    );
+                    let mut conflicting_msg = ConsensusMsg::new();
+                    conflicting_msg.set_vote(conflicting_vote.into_proto());
+                    msgs.push(conflicting_msg);
+                }
+                msgs
+            }
+            ByzantineBehavior::VoteWithholder if msg.has_vote() => vec![],
+            ByzantineBehavior::VoteWithholder if msg.has_timeout_msg() => {
+                msg.mut_timeout_msg().mut_pacemaker_timeout().clear_vote();
+                vec![msg]
+            }
+            ByzantineBehavior::BogusParentQc if msg.has_proposal() => {
+                let proposal = ProposalMsg::<TestPayload>::from_proto(msg.take_proposal()).unwrap();
+                let block = proposal.proposal;
+                // A certificate for an unknown block, only signed by the Byzantine node.
+                let bogus_qc = placeholder_certificate_for_block(
+                    vec![&node.signer],
+                    HashValue::random(),
+                    block.round() - 1,
+                    block.quorum_cert().certified_block_id(),
+                    block.quorum_cert().certified_block_round(),
+                    block.quorum_cert().certified_parent_block_id(),
+                    block.quorum_cert().certified_parent_block_round(),
+                );
+                let bogus_block = Block::new_internal(
+                    block.get_payload().clone(),
+                    bogus_qc.certified_block_id(),
+                    block.epoch(),
+                    block.round(),
+                    block.height(),
+                    block.timestamp_usecs(),
+                    bogus_qc,
+                    &node.signer,
+                );
+                vec![Self::proposal_msg(bogus_block, proposal.sync_info)]
+            }
+            _ => vec![msg],
+        }
+    }
+
+    fn proposal_msg(block: Block<TestPayload>, sync_info: SyncInfo) -> ConsensusMsg {
+        let mut msg = ConsensusMsg::new();
+        msg.set_proposal(
+            ProposalMsg {
+                proposal: block,
+                sync_info,
+            }
+            .into_proto(),
+        );
+        msg
+    }
+}
+
+/// Misbehaviors injected by the playground in the direct-send messages of a Byzantine node, whose
+/// consensus implementation is otherwise honest.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ByzantineBehavior {
+    /// Sends a conflicting proposal for the same round to the nodes at odd positions (in the
+    /// order of their authors).
+    EquivocatingProposer,
+    /// Along with each vote, votes for the other delivered proposals of the same round.
+    DoubleVoter,
+    /// Never sends its votes, including the ones carried by its timeout messages.
+    VoteWithholder,
+    /// Proposes blocks extending a quorum certificate it forged for an unknown block.
+    BogusParentQc,
+}
+
+struct ByzantineNode {
+    signer: ValidatorSigner<Ed25519PrivateKey>,
+    behaviors: Vec<ByzantineBehavior>,
 }
 
 struct DropConfig(HashMap<Author, HashSet<Author>>);
@@ -354,8 +606,7 @@ fn test_network_api() {
             peers[i],
             network_sender,
             network_events,
-            Arc::new(peers.clone()),
-            Arc::clone(&validator),
+            Arc::new(EpochManager::new(0, Arc::clone(&validator))),
         );
         receivers.push(node.start(&runtime.executor()));
         nodes.push(node);
@@ -375,7 +626,15 @@ fn test_network_api() {
     let previous_block = Block::make_genesis_block();
     let previous_qc = QuorumCert::certificate_for_genesis();
     let proposal = ProposalMsg {
-        proposal: Block::make_block(&previous_block, 0, 1, 0, previous_qc.clone(), &signers[0]),
+        proposal: Block::make_block(
+            &previous_block,
+            0,
+            0,
+            1,
+            0,
+            previous_qc.clone(),
+            &signers[0],
+        ),
         sync_info: SyncInfo::new(previous_qc.clone(), previous_qc.clone(), None),
     };
     block_on(async move {
@@ -430,8 +689,7 @@ fn test_rpc() {
             peers[i],
             network_sender.clone(),
             network_events,
-            Arc::clone(&peers),
-            Arc::clone(&validator),
+            Arc::new(EpochManager::new(0, Arc::clone(&validator))),
         );
         senders.push(network_sender);
         receivers.push(node.start(&runtime.executor()));
@@ -492,6 +750,31 @@ fn test_rpc() {
         .executor()
         .spawn(on_request_chunk.boxed().unit_error().compat());
 
+    // verify request account state chunk rpc
+    let mut account_state_chunk_retrieval = receiver_1.account_state_chunk_retrieval;
+    let on_request_account_state_chunk = async move {
+        while let Some(request) = account_state_chunk_retrieval.next().await {
+            let info =
+                TransactionInfo::new(HashValue::zero(), HashValue::zero(), HashValue::zero(), 0);
+            let proof = AccountStateRangeProof::new(
+                AccumulatorProof::new(vec![]),
+                info,
+                SparseMerkleRangeProof::new(SparseMerkleProof::new(None, vec![]), vec![]),
+            );
+            let account_blobs = vec![(request.start_key, AccountStateBlob::from(vec![1u8]))];
+            request
+                .response_sender
+                .send(Ok((
+                    AccountStateRangeWithProof::new(request.version, account_blobs, proof),
+                    vec![HashValue::zero()],
+                )))
+                .unwrap();
+        }
+    };
+    runtime
+        .executor()
+        .spawn(on_request_account_state_chunk.boxed().unit_error().compat());
+
     block_on(async move {
         let mut ledger_info = LedgerInfo::new();
         ledger_info.set_transaction_accumulator_hash(HashValue::zero().to_vec());
@@ -531,5 +814,17 @@ fn test_rpc() {
             .await
             .unwrap();
         assert_eq!(chunk.get_txn_list_with_proof().get_transactions().len(), 1);
+
+        let mut req = RequestAccountStateChunk::new();
+        req.set_version(1);
+        req.set_start_key(HashValue::zero().to_vec().into());
+        req.set_limit(1);
+        let chunk = senders[0]
+            .request_account_state_chunk(peers[1], req, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(chunk.get_account_state_chunk().get_version(), 1);
+        assert_eq!(chunk.get_account_state_chunk().get_account_blobs().len(), 1);
+        assert_eq!(chunk.get_ledger_frozen_subtree_hashes().len(), 1);
     });
 }
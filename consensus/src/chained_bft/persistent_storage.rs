@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    batch_manager::{PersistentBatchStorage, TxnBatch},
     chained_bft::{
-        common::Payload,
-        consensus_types::{block::Block, quorum_cert::QuorumCert},
+        common::{Payload, Round},
+        consensus_types::{
+            block::Block, committed_block_metadata::CommittedBlockMetadata, quorum_cert::QuorumCert,
+        },
         consensusdb::ConsensusDB,
         liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
         safety::safety_rules::ConsensusState,
@@ -51,6 +54,18 @@ pub trait PersistentStorage<T>: PersistentLivenessStorage + Send + Sync {
     /// Persist the consensus state.
     fn save_consensus_state(&self, state: ConsensusState) -> Result<()>;
 
+    /// Persist the metadata of newly committed blocks. Unlike the blocks of the tree, this
+    /// committed history is never pruned.
+    fn save_committed_blocks(&self, committed_blocks: Vec<CommittedBlockMetadata>) -> Result<()>;
+
+    /// Get the metadata of at most `limit` committed blocks whose round is not higher than
+    /// `max_round`, the most recent first.
+    fn get_committed_blocks(
+        &self,
+        max_round: Round,
+        limit: usize,
+    ) -> Result<Vec<CommittedBlockMetadata>>;
+
     /// When the node restart, construct the instance and returned the data read from db.
     /// This could guarantee we only read once during start, and we would panic if the
     /// read fails.
@@ -284,6 +299,20 @@ impl PersistentLivenessStorage for StorageWriteProxy {
     }
 }
 
+impl PersistentBatchStorage for StorageWriteProxy {
+    fn save_batch(&self, batch: &TxnBatch) -> Result<()> {
+        self.db.save_txn_batch(batch)
+    }
+
+    fn delete_batches(&self, digests: Vec<HashValue>) -> Result<()> {
+        self.db.delete_txn_batches(digests)
+    }
+
+    fn get_batches(&self) -> Result<Vec<TxnBatch>> {
+        self.db.get_txn_batches()
+    }
+}
+
 impl<T: Payload> PersistentStorage<T> for StorageWriteProxy {
     fn persistent_liveness_storage(&self) -> Box<dyn PersistentLivenessStorage> {
         Box::new(StorageWriteProxy::new(Arc::clone(&self.db)))
@@ -307,6 +336,18 @@ impl<T: Payload> PersistentStorage<T> for StorageWriteProxy {
         self.db.save_state(to_vec_named(&state)?)
     }
 
+    fn save_committed_blocks(&self, committed_blocks: Vec<CommittedBlockMetadata>) -> Result<()> {
+        self.db.save_committed_blocks(committed_blocks)
+    }
+
+    fn get_committed_blocks(
+        &self,
+        max_round: Round,
+        limit: usize,
+    ) -> Result<Vec<CommittedBlockMetadata>> {
+        self.db.get_committed_blocks(max_round, limit)
+    }
+
     fn start(config: &NodeConfig) -> (Arc<Self>, RecoveryData<T>) {
         info!("Start consensus recovery.");
         let read_client = create_storage_read_client(config);
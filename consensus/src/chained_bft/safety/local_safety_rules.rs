@@ -0,0 +1,120 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        block_storage::BlockReader,
+        common::{Payload, Round},
+        consensus_types::{
+            block::Block,
+            quorum_cert::QuorumCert,
+            sync_info::SyncInfo,
+            timeout_msg::{PacemakerTimeout, TimeoutMsg},
+        },
+        safety::{
+            safety_rules::{ConsensusState, SafetyRules, VoteInfo},
+            t_safety_rules::TSafetyRules,
+            vote_msg::VoteMsg,
+        },
+    },
+    state_replication::ExecutedState,
+};
+use failure::prelude::*;
+use nextgen_crypto::ed25519::*;
+use std::sync::Arc;
+use types::{
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+};
+
+/// Safety rules running in the consensus process: the consensus state is persisted by the event
+/// processor and the consensus messages are signed with the consensus key held in memory.
+pub struct LocalSafetyRules<T> {
+    safety_rules: SafetyRules<T>,
+    signer: ValidatorSigner<Ed25519PrivateKey>,
+}
+
+impl<T: Payload> LocalSafetyRules<T> {
+    pub fn new(
+        block_tree: Arc<dyn BlockReader<Payload = T>>,
+        state: ConsensusState,
+        signer: ValidatorSigner<Ed25519PrivateKey>,
+    ) -> Self {
+        Self {
+            safety_rules: SafetyRules::new(block_tree, state),
+            signer,
+        }
+    }
+}
+
+impl<T: Payload> TSafetyRules<T> for LocalSafetyRules<T> {
+    fn update(&mut self, qc: &QuorumCert) -> Result<()> {
+        self.safety_rules.update(qc);
+        Ok(())
+    }
+
+    fn increase_last_vote_round(&mut self, round: Round) -> Result<Option<ConsensusState>> {
+        Ok(self.safety_rules.increase_last_vote_round(round))
+    }
+
+    fn consensus_state(&self) -> ConsensusState {
+        self.safety_rules.consensus_state()
+    }
+
+    fn voting_rule(&mut self, proposed_block: Arc<Block<T>>) -> Result<VoteInfo> {
+        Ok(self.safety_rules.voting_rule(proposed_block)?)
+    }
+
+    fn sign_vote(
+        &mut self,
+        vote_info: &VoteInfo,
+        executed_state: ExecutedState,
+        ledger_info_placeholder: LedgerInfo,
+    ) -> Result<VoteMsg> {
+        Ok(VoteMsg::new(
+            vote_info.proposal_id(),
+            executed_state,
+            vote_info.proposal_round(),
+            vote_info.parent_block_id(),
+            vote_info.parent_block_round(),
+            vote_info.grandparent_block_id(),
+            vote_info.grandparent_block_round(),
+            self.signer.author(),
+            ledger_info_placeholder,
+            &self.signer,
+        ))
+    }
+
+    fn sign_proposal(&mut self, proposal: Block<T>) -> Result<Block<T>> {
+        ensure!(
+            proposal.author() == Some(self.signer.author()),
+            "Proposal {} is not authored by {}",
+            proposal,
+            self.signer.author()
+        );
+        let signature = self.signer.sign_message(proposal.id())?;
+        Ok(proposal.with_signature(signature))
+    }
+
+    fn sign_timeout(
+        &mut self,
+        round: Round,
+        vote: Option<VoteMsg>,
+        sync_info: SyncInfo,
+    ) -> Result<TimeoutMsg> {
+        Ok(TimeoutMsg::new(
+            sync_info,
+            PacemakerTimeout::new(round, &self.signer, vote),
+            &self.signer,
+        ))
+    }
+
+    /// The local safety rules rely on the epoch of the consensus process, there is nothing to
+    /// advance.
+    fn start_new_epoch(
+        &mut self,
+        _ledger_info_with_sigs: &LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(bare_trait_objects)]
+
+pub mod safety_rules;
+pub mod safety_rules_grpc;
+
+use types::proto::{account_state_blob, ledger_info};
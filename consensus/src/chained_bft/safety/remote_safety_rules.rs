@@ -0,0 +1,229 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        block_storage::BlockReader,
+        common::{Author, Payload, Round},
+        consensus_types::{
+            block::Block,
+            quorum_cert::QuorumCert,
+            sync_info::SyncInfo,
+            timeout_msg::{PacemakerTimeout, TimeoutMsg},
+        },
+        safety::{
+            proto::{
+                safety_rules::{
+                    GetConsensusStateRequest, IncreaseLastVoteRoundRequest, SignProposalRequest,
+                    SignTimeoutRequest, SignVoteRequest, StartNewEpochRequest, UpdateRequest,
+                },
+                safety_rules_grpc::SafetyRulesServiceClient,
+            },
+            safety_rules::{ConsensusState, SafetyRules, VoteInfo},
+            t_safety_rules::TSafetyRules,
+            vote_msg::VoteMsg,
+        },
+    },
+    state_replication::ExecutedState,
+};
+use failure::prelude::*;
+use grpcio::{ChannelBuilder, EnvBuilder};
+use logger::prelude::*;
+use nextgen_crypto::ed25519::*;
+use proto_conv::{FromProto, IntoProto};
+use protobuf::Message;
+use std::{convert::TryFrom, sync::Arc};
+use storage_client::StorageRead;
+use types::{
+    account_config::core_code_address,
+    get_with_proof::{RequestItem, ResponseItem},
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+};
+
+/// Client of the safety rules running in a separate process (see `SafetyRulesService`).
+/// The remote safety rules are the source of truth: they apply the voting rules on their own
+/// before signing a vote. The rules are also evaluated locally against the block tree in order
+/// to build the vote information and to reject the proposals early.
+/// The remote safety rules follow the epochs on their own: the reconfigurations are sent to them
+/// along with the proof of the new validator set, read from storage.
+pub struct RemoteSafetyRules<T> {
+    author: Author,
+    block_tree: Arc<dyn BlockReader<Payload = T>>,
+    // Mirror of the remote consensus state
+    safety_rules: SafetyRules<T>,
+    client: SafetyRulesServiceClient,
+    storage_read_client: Arc<dyn StorageRead>,
+}
+
+impl<T: Payload> RemoteSafetyRules<T> {
+    /// Connects to the remote safety rules and fetches their consensus state.
+    pub fn new(
+        author: Author,
+        block_tree: Arc<dyn BlockReader<Payload = T>>,
+        address: &str,
+        port: u16,
+        storage_read_client: Arc<dyn StorageRead>,
+    ) -> Result<Self> {
+        let env = Arc::new(EnvBuilder::new().name_prefix("grpc-safety-rules-").build());
+        let channel = ChannelBuilder::new(env).connect(&format!("{}:{}", address, port));
+        let client = SafetyRulesServiceClient::new(channel);
+        let mut response = client.get_consensus_state(&GetConsensusStateRequest::new())?;
+        let state = ConsensusState::from_proto(response.take_consensus_state())?;
+        info!("Remote safety rules at {}:{}: {}", address, port, state);
+        Ok(Self {
+            author,
+            block_tree: Arc::clone(&block_tree),
+            safety_rules: SafetyRules::new(block_tree, state),
+            client,
+            storage_read_client,
+        })
+    }
+}
+
+impl<T: Payload> TSafetyRules<T> for RemoteSafetyRules<T> {
+    fn update(&mut self, qc: &QuorumCert) -> Result<()> {
+        self.safety_rules.update(qc);
+        let mut request = UpdateRequest::new();
+        request.set_quorum_cert(qc.clone().into_proto().write_to_bytes()?);
+        self.client.update(&request)?;
+        Ok(())
+    }
+
+    fn increase_last_vote_round(&mut self, round: Round) -> Result<Option<ConsensusState>> {
+        let mut request = IncreaseLastVoteRoundRequest::new();
+        request.set_round(round);
+        let mut response = self.client.increase_last_vote_round(&request)?;
+        self.safety_rules.increase_last_vote_round(round);
+        if response.get_increased() {
+            Ok(Some(ConsensusState::from_proto(
+                response.take_consensus_state(),
+            )?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn consensus_state(&self) -> ConsensusState {
+        self.safety_rules.consensus_state()
+    }
+
+    fn voting_rule(&mut self, proposed_block: Arc<Block<T>>) -> Result<VoteInfo> {
+        Ok(self.safety_rules.voting_rule(proposed_block)?)
+    }
+
+    fn sign_vote(
+        &mut self,
+        vote_info: &VoteInfo,
+        executed_state: ExecutedState,
+        ledger_info_placeholder: LedgerInfo,
+    ) -> Result<VoteMsg> {
+        let proposed_block = self
+            .block_tree
+            .get_block(vote_info.proposal_id())
+            .ok_or_else(|| format_err!("Proposal {} not found", vote_info.proposal_id()))?;
+        let mut request = SignVoteRequest::new();
+        request.set_proposed_block_id(vote_info.proposal_id().to_vec());
+        request.set_executed_state_id(executed_state.state_id.to_vec());
+        request.set_version(executed_state.version);
+        request.set_round(vote_info.proposal_round());
+        request.set_quorum_cert(
+            proposed_block
+                .quorum_cert()
+                .clone()
+                .into_proto()
+                .write_to_bytes()?,
+        );
+        request.set_ledger_info_placeholder(ledger_info_placeholder.into_proto());
+        let mut response = self.client.sign_vote(&request)?;
+        Ok(VoteMsg::new_with_signature(
+            vote_info.proposal_id(),
+            executed_state,
+            vote_info.proposal_round(),
+            vote_info.parent_block_id(),
+            vote_info.parent_block_round(),
+            vote_info.grandparent_block_id(),
+            vote_info.grandparent_block_round(),
+            self.author,
+            LedgerInfo::from_proto(response.take_ledger_info())?,
+            Ed25519Signature::try_from(response.get_signature())?,
+        ))
+    }
+
+    fn sign_proposal(&mut self, proposal: Block<T>) -> Result<Block<T>> {
+        ensure!(
+            proposal.author() == Some(self.author),
+            "Proposal {} is not authored by {}",
+            proposal,
+            self.author
+        );
+        let mut request = SignProposalRequest::new();
+        request.set_block_id(proposal.id().to_vec());
+        request.set_round(proposal.round());
+        request.set_quorum_cert(
+            proposal
+                .quorum_cert()
+                .clone()
+                .into_proto()
+                .write_to_bytes()?,
+        );
+        let response = self.client.sign_proposal(&request)?;
+        Ok(proposal.with_signature(Ed25519Signature::try_from(response.get_signature())?))
+    }
+
+    fn sign_timeout(
+        &mut self,
+        round: Round,
+        vote: Option<VoteMsg>,
+        sync_info: SyncInfo,
+    ) -> Result<TimeoutMsg> {
+        let mut request = SignTimeoutRequest::new();
+        request.set_round(round);
+        let response = self.client.sign_timeout(&request)?;
+        Ok(TimeoutMsg::new_with_signature(
+            sync_info,
+            PacemakerTimeout::new_with_signature(
+                round,
+                self.author,
+                Ed25519Signature::try_from(response.get_pacemaker_timeout_signature())?,
+                vote,
+            ),
+            Ed25519Signature::try_from(response.get_timeout_msg_signature())?,
+        ))
+    }
+
+    fn start_new_epoch(
+        &mut self,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Result<()> {
+        let (response_items, latest_ledger_info, _) =
+            self.storage_read_client.update_to_latest_ledger(
+                0, /* client_known_version */
+                vec![RequestItem::GetAccountState {
+                    address: core_code_address(),
+                }],
+            )?;
+        // The account state is proven against the latest ledger info in storage, which is the
+        // reconfiguration one as long as nothing was committed after it.
+        ensure!(
+            latest_ledger_info.ledger_info() == ledger_info_with_sigs.ledger_info(),
+            "Storage is at {} instead of the reconfiguration {}",
+            latest_ledger_info.ledger_info(),
+            ledger_info_with_sigs.ledger_info()
+        );
+        let account_state_with_proof = match response_items.into_iter().next() {
+            Some(ResponseItem::GetAccountState {
+                account_state_with_proof,
+            }) => account_state_with_proof,
+            item => bail!(
+                "Unexpected response to the account state request: {:?}",
+                item
+            ),
+        };
+        let mut request = StartNewEpochRequest::new();
+        request.set_ledger_info_with_sigs(ledger_info_with_sigs.clone().into_proto());
+        request.set_validator_set_account_state(account_state_with_proof.into_proto());
+        let response = self.client.start_new_epoch(&request)?;
+        info!("Remote safety rules started epoch {}", response.get_epoch());
+        Ok(())
+    }
+}
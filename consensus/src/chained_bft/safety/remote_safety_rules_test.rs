@@ -0,0 +1,416 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        block_storage::{BlockReader, BlockStore},
+        common::{Author, Round},
+        consensus_types::{block::Block, sync_info::SyncInfo},
+        consensusdb::ConsensusDB,
+        epoch_manager::EpochManager,
+        safety::{
+            proto::safety_rules_grpc, remote_safety_rules::RemoteSafetyRules,
+            safety_rules::ConsensusState, safety_rules_server::SafetyRulesServer,
+            t_safety_rules::TSafetyRules,
+        },
+        test_utils::{
+            build_empty_tree, placeholder_certificate_for_block, placeholder_ledger_info,
+            TreeInserter,
+        },
+    },
+    state_replication::ExecutedState,
+};
+use config::utils::get_available_port;
+use crypto::{hash::CryptoHash, HashValue};
+use grpc_helpers::{spawn_service_thread, ServerHandle};
+use grpcio::{ChannelBuilder, EnvBuilder};
+use nextgen_crypto::ed25519::*;
+use rmp_serde::from_slice;
+use secret_service::{
+    crypto_wrappers::GenericPublicKey,
+    proto::secret_service_grpc::{self, SecretServiceClient},
+    secret_service_client::ConsensusKeyManager,
+    secret_service_server::SecretServiceServer,
+};
+use std::{collections::HashMap, sync::Arc};
+use storage_service::mocks::mock_storage_client::MockStorageReadClient;
+use tempfile::{tempdir, TempDir};
+use types::{
+    account_state_blob::AccountStateWithProof,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{AccountStateProof, AccumulatorProof, SparseMerkleProof},
+    transaction::TransactionInfo,
+    validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+
+struct SafetyRulesSetup {
+    port: u16,
+    db: Arc<ConsensusDB>,
+    server: SafetyRulesServer,
+    epoch_manager: Arc<EpochManager>,
+    public_key: Ed25519PublicKey,
+    // The services are shut down when their handles are dropped
+    _handles: Vec<ServerHandle>,
+    _db_dir: TempDir,
+}
+
+impl SafetyRulesSetup {
+    /// Spawns a secret service and a safety rules server signing through it on behalf of the given
+    /// author. The server accepts the quorum certificates formed by the validator signing the
+    /// blocks of the given tree.
+    fn new(author: Author, block_tree: &BlockStore<Vec<usize>>) -> Self {
+        let secret_service_port = get_available_port();
+        let secret_service_handle = spawn_service_thread(
+            secret_service_grpc::create_secret_service(SecretServiceServer::new()),
+            "localhost".to_string(),
+            secret_service_port,
+            "secret_service",
+        );
+        let env = Arc::new(EnvBuilder::new().build());
+        let channel =
+            ChannelBuilder::new(env).connect(&format!("localhost:{}", secret_service_port));
+        let key_manager =
+            ConsensusKeyManager::new(Arc::new(SecretServiceClient::new(channel))).unwrap();
+        let public_key = match key_manager.get_consensus_public_key().unwrap() {
+            GenericPublicKey::Ed(public_key) => public_key,
+            GenericPublicKey::BLS(_) => panic!("Expected an Ed25519 consensus key"),
+        };
+
+        let db_dir = tempdir().unwrap();
+        let db = Arc::new(ConsensusDB::new(&db_dir));
+        let validators = ValidatorVerifier::new_single(
+            block_tree.signer().author(),
+            block_tree.signer().public_key(),
+        );
+        let epoch_manager = Arc::new(EpochManager::new(0, Arc::new(validators)));
+        let server = SafetyRulesServer::new(
+            author,
+            db.clone(),
+            Arc::new(key_manager),
+            epoch_manager.clone(),
+        )
+        .unwrap();
+        let port = get_available_port();
+        let safety_rules_handle = spawn_service_thread(
+            safety_rules_grpc::create_safety_rules_service(server.clone()),
+            "localhost".to_string(),
+            port,
+            "safety_rules",
+        );
+        Self {
+            port,
+            db,
+            server,
+            epoch_manager,
+            public_key,
+            _handles: vec![secret_service_handle, safety_rules_handle],
+            _db_dir: db_dir,
+        }
+    }
+
+    fn persisted_state(&self) -> ConsensusState {
+        from_slice(&self.db.get_state().unwrap().unwrap()[..]).unwrap()
+    }
+
+    fn connect(
+        &self,
+        author: Author,
+        block_tree: &Arc<BlockStore<Vec<usize>>>,
+    ) -> RemoteSafetyRules<Vec<usize>> {
+        RemoteSafetyRules::new(
+            author,
+            block_tree.clone(),
+            "localhost",
+            self.port,
+            Arc::new(MockStorageReadClient),
+        )
+        .unwrap()
+    }
+}
+
+fn signed_ledger_info(
+    signer: &ValidatorSigner<Ed25519PrivateKey>,
+    ledger_info: LedgerInfo,
+) -> LedgerInfoWithSignatures<Ed25519Signature> {
+    let mut signatures = HashMap::new();
+    signatures.insert(
+        signer.author(),
+        signer.sign_message(ledger_info.hash()).unwrap(),
+    );
+    LedgerInfoWithSignatures::new(ledger_info, signatures)
+}
+
+fn empty_account_state_with_proof(version: u64) -> AccountStateWithProof {
+    AccountStateWithProof::new(
+        version,
+        None,
+        AccountStateProof::new(
+            AccumulatorProof::new(vec![]),
+            TransactionInfo::new(HashValue::zero(), HashValue::zero(), HashValue::zero(), 0),
+            SparseMerkleProof::new(None, vec![]),
+        ),
+    )
+}
+
+#[test]
+fn test_remote_safety_rules_sign_vote() {
+    let block_tree = build_empty_tree();
+    let author = Author::random();
+    let setup = SafetyRulesSetup::new(author, &block_tree);
+    let mut inserter = TreeInserter::new(block_tree.clone());
+    let mut safety_rules = setup.connect(author, &block_tree);
+    assert_eq!(safety_rules.consensus_state(), ConsensusState::default());
+
+    let genesis = block_tree.root();
+    let a1 = inserter.insert_block(genesis.as_ref(), 1);
+    let a2 = inserter.insert_block(a1.as_ref(), 2);
+    let executed_state = ExecutedState {
+        state_id: HashValue::random(),
+        version: 1,
+    };
+
+    safety_rules.update(a1.quorum_cert()).unwrap();
+    let vote_info = safety_rules.voting_rule(a1.clone()).unwrap();
+    let vote = safety_rules
+        .sign_vote(&vote_info, executed_state, placeholder_ledger_info())
+        .unwrap();
+    assert_eq!(vote.author(), author);
+    assert_eq!(vote.round(), 1);
+    // The vote is signed with the consensus key kept by the secret service.
+    let validator = ValidatorVerifier::new_single(author, setup.public_key.clone());
+    assert!(vote.verify(&validator).is_ok());
+    // The new state is persisted by the remote safety rules.
+    assert_eq!(setup.persisted_state().last_vote_round(), 1);
+
+    // The remote safety rules don't sign a second vote for the same round.
+    assert!(safety_rules
+        .sign_vote(&vote_info, executed_state, placeholder_ledger_info())
+        .is_err());
+
+    safety_rules.update(a2.quorum_cert()).unwrap();
+    let vote_info = safety_rules.voting_rule(a2.clone()).unwrap();
+    let vote = safety_rules
+        .sign_vote(&vote_info, executed_state, placeholder_ledger_info())
+        .unwrap();
+    assert!(vote.verify(&validator).is_ok());
+    assert_eq!(setup.persisted_state().last_vote_round(), 2);
+}
+
+#[test]
+fn test_remote_safety_rules_increase_last_vote_round() {
+    let block_tree = build_empty_tree();
+    let author = Author::random();
+    let setup = SafetyRulesSetup::new(author, &block_tree);
+    let mut inserter = TreeInserter::new(block_tree.clone());
+    let mut safety_rules = setup.connect(author, &block_tree);
+
+    let state = safety_rules.increase_last_vote_round(3).unwrap().unwrap();
+    assert_eq!(state.last_vote_round(), 3);
+    assert_eq!(safety_rules.consensus_state(), state);
+    assert_eq!(setup.persisted_state(), state);
+    assert_eq!(safety_rules.increase_last_vote_round(2).unwrap(), None);
+
+    // A consensus process that reconnects recovers the state of the remote safety rules.
+    let mut safety_rules = setup.connect(author, &block_tree);
+    assert_eq!(safety_rules.consensus_state(), state);
+
+    // The local voting rule rejects the old proposals before reaching the remote safety rules.
+    let a1 = inserter.insert_block(block_tree.root().as_ref(), 2);
+    assert!(safety_rules.voting_rule(a1).is_err());
+}
+
+#[test]
+fn test_remote_safety_rules_reject_unknown_quorum_cert() {
+    let block_tree = build_empty_tree();
+    let setup = SafetyRulesSetup::new(Author::random(), &block_tree);
+    let mut inserter = TreeInserter::new(block_tree.clone());
+    let mut safety_rules = setup.connect(Author::random(), &block_tree);
+
+    let genesis = block_tree.root();
+    let a1 = inserter.insert_block(genesis.as_ref(), 1);
+    let a2 = inserter.insert_block(a1.as_ref(), 2);
+    safety_rules.update(a2.quorum_cert()).unwrap();
+    assert_eq!(
+        setup.persisted_state().preferred_block_round(),
+        a2.quorum_cert().certified_parent_block_round()
+    );
+
+    // A quorum certificate formed by a validator unknown to the server doesn't update its state.
+    let forged_qc = placeholder_certificate_for_block(
+        vec![&ValidatorSigner::random(None)],
+        a2.id(),
+        a2.round(),
+        a1.id(),
+        a1.round(),
+        genesis.id(),
+        genesis.round(),
+    );
+    assert!(safety_rules.update(&forged_qc).is_err());
+    assert_eq!(
+        setup.persisted_state().preferred_block_round(),
+        a2.quorum_cert().certified_parent_block_round()
+    );
+}
+
+#[test]
+fn test_remote_safety_rules_sign_proposal_and_timeout() {
+    let block_tree = build_empty_tree();
+    // The proposals are authored by the signer of the block store.
+    let author = block_tree.signer().author();
+    let setup = SafetyRulesSetup::new(author, &block_tree);
+    let mut inserter = TreeInserter::new(block_tree.clone());
+    let mut safety_rules = setup.connect(author, &block_tree);
+    let validator = ValidatorVerifier::new_single(author, setup.public_key.clone());
+
+    let genesis = block_tree.root();
+    let a1 = inserter.insert_block(genesis.as_ref(), 1);
+    let proposal = Block::make_block(
+        genesis.as_ref(),
+        vec![1],
+        0,
+        2,
+        genesis.timestamp_usecs() + 1,
+        a1.quorum_cert().clone(),
+        block_tree.signer(),
+    );
+    let proposal = safety_rules.sign_proposal(proposal).unwrap();
+    // The proposal is signed with the consensus key kept by the secret service.
+    assert!(validator
+        .verify_signature(author, proposal.id(), proposal.signature().unwrap())
+        .is_ok());
+
+    let sync_info = SyncInfo::new(
+        block_tree.highest_quorum_cert().as_ref().clone(),
+        block_tree.highest_ledger_info().as_ref().clone(),
+        None,
+    );
+    let timeout_msg = safety_rules.sign_timeout(3, None, sync_info).unwrap();
+    assert_eq!(timeout_msg.author(), author);
+    assert!(timeout_msg.verify(&validator).is_ok());
+    // The remote safety rules don't vote at the round they timed out at.
+    assert_eq!(setup.persisted_state().last_vote_round(), 3);
+}
+
+#[test]
+fn test_remote_safety_rules_sign_single_proposal_per_round() {
+    let block_tree = build_empty_tree();
+    let author = block_tree.signer().author();
+    let setup = SafetyRulesSetup::new(author, &block_tree);
+    let mut inserter = TreeInserter::new(block_tree.clone());
+    let mut safety_rules = setup.connect(author, &block_tree);
+
+    let genesis = block_tree.root();
+    let a1 = inserter.insert_block(genesis.as_ref(), 1);
+    let make_proposal = |payload, round| {
+        Block::make_block(
+            genesis.as_ref(),
+            payload,
+            0,
+            round,
+            genesis.timestamp_usecs() + round,
+            a1.quorum_cert().clone(),
+            block_tree.signer(),
+        )
+    };
+    assert!(safety_rules
+        .sign_proposal(make_proposal(vec![1], 2))
+        .is_ok());
+    // An equivocating proposal is not signed, and neither is a proposal of a lower round.
+    assert!(safety_rules
+        .sign_proposal(make_proposal(vec![2], 2))
+        .is_err());
+    assert!(safety_rules
+        .sign_proposal(make_proposal(vec![3], 1))
+        .is_err());
+
+    // The round of the last proposal is persisted, so that a restarted server doesn't sign it
+    // again.
+    let last_proposed_round: Round =
+        from_slice(&setup.db.get_last_proposed_round().unwrap().unwrap()[..]).unwrap();
+    assert_eq!(last_proposed_round, 2);
+    assert!(safety_rules
+        .sign_proposal(make_proposal(vec![4], 3))
+        .is_ok());
+}
+
+#[test]
+fn test_remote_safety_rules_vote_commits_potential_commit() {
+    let block_tree = build_empty_tree();
+    let author = Author::random();
+    let setup = SafetyRulesSetup::new(author, &block_tree);
+    let mut inserter = TreeInserter::new(block_tree.clone());
+    let mut safety_rules = setup.connect(author, &block_tree);
+
+    // genesis <- a1 <- a2 <- a3: a vote for a3 commits a1.
+    let genesis = block_tree.root();
+    let a1 = inserter.insert_block(genesis.as_ref(), 1);
+    let a2 = inserter.insert_block(a1.as_ref(), 2);
+    let a3 = inserter.insert_block(a2.as_ref(), 3);
+    let executed_state = ExecutedState {
+        state_id: HashValue::random(),
+        version: 3,
+    };
+    let ledger_info =
+        |block_id| LedgerInfo::new(1, HashValue::random(), HashValue::zero(), block_id, 0, 0);
+
+    let vote_info = safety_rules.voting_rule(a3.clone()).unwrap();
+    assert_eq!(vote_info.potential_commit_id(), Some(a1.id()));
+    // The ledger info must commit the potential commit of the vote.
+    assert!(safety_rules
+        .sign_vote(&vote_info, executed_state, ledger_info(a2.id()))
+        .is_err());
+    assert!(safety_rules
+        .sign_vote(&vote_info, executed_state, ledger_info(HashValue::random()))
+        .is_err());
+    // The rejected votes didn't consume the round.
+    let vote = safety_rules
+        .sign_vote(&vote_info, executed_state, ledger_info(a1.id()))
+        .unwrap();
+    assert_eq!(vote.ledger_info().consensus_block_id(), a1.id());
+    assert_eq!(setup.persisted_state().last_vote_round(), 3);
+}
+
+#[test]
+fn test_remote_safety_rules_reject_unverified_reconfiguration() {
+    let block_tree = build_empty_tree();
+    let setup = SafetyRulesSetup::new(Author::random(), &block_tree);
+    let ledger_info = |epoch| {
+        LedgerInfo::new(
+            1,
+            HashValue::random(),
+            HashValue::zero(),
+            HashValue::random(),
+            epoch,
+            0,
+        )
+    };
+    let account_state = empty_account_state_with_proof(1);
+
+    // The ledger info must be certified by the validators of the current epoch.
+    let unknown_signer = ValidatorSigner::random(None);
+    assert!(setup
+        .server
+        .start_new_epoch_inner(
+            &signed_ledger_info(&unknown_signer, ledger_info(0)),
+            &account_state
+        )
+        .is_err());
+    // The ledger info must belong to the current epoch.
+    assert!(setup
+        .server
+        .start_new_epoch_inner(
+            &signed_ledger_info(block_tree.signer(), ledger_info(1)),
+            &account_state
+        )
+        .is_err());
+    // The validator set must be proven against the ledger info.
+    assert!(setup
+        .server
+        .start_new_epoch_inner(
+            &signed_ledger_info(block_tree.signer(), ledger_info(0)),
+            &account_state
+        )
+        .is_err());
+    assert_eq!(setup.epoch_manager.epoch(), 0);
+}
@@ -6,11 +6,14 @@ use crate::{
         block_storage::BlockReader,
         common::{Payload, Round},
         consensus_types::{block::Block, quorum_cert::QuorumCert},
+        safety::proto::safety_rules::ConsensusState as ProtoConsensusState,
     },
     counters,
 };
 
 use crypto::HashValue;
+use failure::Result as ProtoResult;
+use proto_conv::{FromProto, IntoProto};
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::{Display, Formatter},
@@ -53,6 +56,10 @@ impl VoteInfo {
         self.proposal_id
     }
 
+    pub fn proposal_round(&self) -> Round {
+        self.proposal_round
+    }
+
     pub fn consensus_state(&self) -> &ConsensusState {
         &self.consensus_state
     }
@@ -164,7 +171,7 @@ impl ConsensusState {
 
     /// Set the last vote round that ensures safety.  If the last vote round increases, return
     /// the new consensus state based with the updated last vote round.  Otherwise, return None.
-    fn set_last_vote_round(&mut self, last_vote_round: Round) -> Option<ConsensusState> {
+    pub(crate) fn set_last_vote_round(&mut self, last_vote_round: Round) -> Option<ConsensusState> {
         if last_vote_round <= self.last_vote_round {
             None
         } else {
@@ -179,6 +186,58 @@ impl ConsensusState {
         self.preferred_block_round = preferred_block_round;
         counters::PREFERRED_BLOCK_ROUND.set(preferred_block_round as i64);
     }
+
+    /// Preferred block rule: choose the highest 2-chain head.
+    pub(crate) fn update_preferred_block_round(&mut self, two_chain_head_round: Round) {
+        if two_chain_head_round >= self.preferred_block_round {
+            self.set_preferred_block_round(two_chain_head_round);
+        }
+    }
+
+    /// Voting rules for a proposal at the given round extending a parent at the given round:
+    /// the round must be higher than the last vote round and the parent round must not be lower
+    /// than the preferred block round. The last vote round is increased in case of success.
+    pub(crate) fn vote(
+        &mut self,
+        proposal_round: Round,
+        parent_block_round: Round,
+    ) -> Result<(), ProposalReject> {
+        if proposal_round <= self.last_vote_round {
+            return Err(ProposalReject::OldProposal {
+                proposal_round,
+                last_vote_round: self.last_vote_round,
+            });
+        }
+        if parent_block_round < self.preferred_block_round {
+            return Err(ProposalReject::ProposalRoundLowerThenPreferredBlock {
+                preferred_block_round: self.preferred_block_round,
+            });
+        }
+        self.set_last_vote_round(proposal_round);
+        Ok(())
+    }
+}
+
+impl IntoProto for ConsensusState {
+    type ProtoType = ProtoConsensusState;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_last_vote_round(self.last_vote_round);
+        proto.set_preferred_block_round(self.preferred_block_round);
+        proto
+    }
+}
+
+impl FromProto for ConsensusState {
+    type ProtoType = ProtoConsensusState;
+
+    fn from_proto(object: Self::ProtoType) -> ProtoResult<Self> {
+        Ok(Self {
+            last_vote_round: object.get_last_vote_round(),
+            preferred_block_round: object.get_preferred_block_round(),
+        })
+    }
 }
 
 /// SafetyRules is responsible for two things that are critical for the safety of the consensus:
@@ -213,9 +272,8 @@ impl<T: Payload> SafetyRules<T> {
         // Preferred block rule: choose the highest 2-chain head.
         if let Some(one_chain_head) = self.block_tree.get_block(qc.certified_block_id()) {
             if let Some(two_chain_head) = self.block_tree.get_block(one_chain_head.parent_id()) {
-                if two_chain_head.round() >= self.state.preferred_block_round() {
-                    self.state.set_preferred_block_round(two_chain_head.round());
-                }
+                self.state
+                    .update_preferred_block_round(two_chain_head.round());
             }
         }
     }
@@ -290,36 +348,27 @@ impl<T: Payload> SafetyRules<T> {
             proposed_block.quorum_cert().certified_block_round()
         );
 
-        let respects_preferred_block = parent_block_round >= self.state.preferred_block_round();
-        if respects_preferred_block {
-            self.state.set_last_vote_round(proposed_block.round());
-
-            // If the vote for the given proposal is gathered into QC, then this QC might eventually
-            // commit another block following the rules defined in
-            // `commit_rule_for_certified_block()` function.
-            let potential_commit =
-                self.commit_rule_for_certified_block(Arc::clone(&proposed_block));
-            let potential_commit_id = match potential_commit {
-                None => None,
-                Some(commit_block) => Some(commit_block.id()),
-            };
-
-            Ok(VoteInfo {
-                proposal_id: proposed_block.id(),
-                proposal_round: proposed_block.round(),
-                consensus_state: self.state.clone(),
-                potential_commit_id,
-                parent_block_id: proposed_block.quorum_cert().certified_block_id(),
-                parent_block_round: proposed_block.quorum_cert().certified_block_round(),
-                grandparent_block_id: proposed_block.quorum_cert().certified_parent_block_id(),
-                grandparent_block_round: proposed_block
-                    .quorum_cert()
-                    .certified_parent_block_round(),
-            })
-        } else {
-            Err(ProposalReject::ProposalRoundLowerThenPreferredBlock {
-                preferred_block_round: self.state.preferred_block_round(),
-            })
-        }
+        self.state
+            .vote(proposed_block.round(), parent_block_round)?;
+
+        // If the vote for the given proposal is gathered into QC, then this QC might eventually
+        // commit another block following the rules defined in
+        // `commit_rule_for_certified_block()` function.
+        let potential_commit = self.commit_rule_for_certified_block(Arc::clone(&proposed_block));
+        let potential_commit_id = match potential_commit {
+            None => None,
+            Some(commit_block) => Some(commit_block.id()),
+        };
+
+        Ok(VoteInfo {
+            proposal_id: proposed_block.id(),
+            proposal_round: proposed_block.round(),
+            consensus_state: self.state.clone(),
+            potential_commit_id,
+            parent_block_id: proposed_block.quorum_cert().certified_block_id(),
+            parent_block_round: proposed_block.quorum_cert().certified_block_round(),
+            grandparent_block_id: proposed_block.quorum_cert().certified_parent_block_id(),
+            grandparent_block_round: proposed_block.quorum_cert().certified_parent_block_round(),
+        })
     }
 }
@@ -0,0 +1,440 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        common::{Author, Round},
+        consensus_types::{
+            quorum_cert::QuorumCert,
+            timeout_msg::{PacemakerTimeout, TimeoutMsg},
+        },
+        consensusdb::ConsensusDB,
+        epoch_manager::EpochManager,
+        safety::{
+            proto::{
+                safety_rules::{
+                    GetConsensusStateRequest, GetConsensusStateResponse,
+                    IncreaseLastVoteRoundRequest, IncreaseLastVoteRoundResponse,
+                    SignProposalRequest, SignProposalResponse, SignTimeoutRequest,
+                    SignTimeoutResponse, SignVoteRequest, SignVoteResponse, StartNewEpochRequest,
+                    StartNewEpochResponse, UpdateRequest, UpdateResponse,
+                },
+                safety_rules_grpc,
+            },
+            safety_rules::ConsensusState,
+            vote_msg::VoteMsg,
+        },
+    },
+    state_replication::ExecutedState,
+};
+use crypto::{hash::CryptoHash, HashValue};
+use failure::prelude::*;
+use grpc_helpers::provide_grpc_response;
+use logger::prelude::*;
+use nextgen_crypto::ed25519::*;
+use proto_conv::{FromProto, IntoProto};
+use rmp_serde::{from_slice, to_vec_named};
+use secret_service::{
+    crypto_wrappers::GenericSignature, secret_service_client::ConsensusKeyManager,
+};
+use std::sync::{Arc, Mutex};
+use types::{
+    account_config::core_code_address,
+    account_state_blob::AccountStateWithProof,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+};
+
+/// The safety rules server keeps the consensus state in its own storage and signs the votes, the
+/// proposals and the timeouts through the secret service, it is meant to run in a process
+/// isolated from the rest of consensus (see `SafetyRulesNode`).
+/// The voting rules are applied to every vote before it is signed, and the consensus state is
+/// persisted before the signature is returned: a vote is never signed twice for the same round.
+/// The quorum certificates are verified against the validators known to the server before they
+/// update the consensus state or get extended by a proposal: the server follows the epochs on its
+/// own, from the reconfigurations certified by the validators it knows.
+#[derive(Clone)]
+pub struct SafetyRulesServer {
+    author: Author,
+    // The up-to-date consensus state, the Mutex serializes the requests
+    state: Arc<Mutex<ConsensusState>>,
+    // The round of the last proposal signed, a proposal is never signed twice for the same round
+    last_proposed_round: Arc<Mutex<Round>>,
+    db: Arc<ConsensusDB>,
+    key_manager: Arc<ConsensusKeyManager>,
+    epoch_manager: Arc<EpochManager>,
+}
+
+impl SafetyRulesServer {
+    /// Recovers the consensus state from the storage.
+    pub fn new(
+        author: Author,
+        db: Arc<ConsensusDB>,
+        key_manager: Arc<ConsensusKeyManager>,
+        epoch_manager: Arc<EpochManager>,
+    ) -> Result<Self> {
+        let state = match db.get_state()? {
+            Some(state) => from_slice(&state[..])?,
+            None => ConsensusState::default(),
+        };
+        let last_proposed_round = match db.get_last_proposed_round()? {
+            Some(round) => from_slice(&round[..])?,
+            None => 0,
+        };
+        info!(
+            "Safety rules recovered {}, last proposed round {}",
+            state, last_proposed_round
+        );
+        Ok(Self {
+            author,
+            state: Arc::new(Mutex::new(state)),
+            last_proposed_round: Arc::new(Mutex::new(last_proposed_round)),
+            db,
+            key_manager,
+            epoch_manager,
+        })
+    }
+
+    /// Returns the up-to-date consensus state.
+    pub fn consensus_state(&self) -> ConsensusState {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Learns about a new quorum certificate: the parent of the certified block is the head of a
+    /// 2-chain, which might become the preferred block.
+    pub fn update_inner(&self, qc: &QuorumCert) -> Result<ConsensusState> {
+        self.verify_quorum_cert(qc)?;
+        let mut state = self.state.lock().unwrap();
+        let mut new_state = state.clone();
+        new_state.update_preferred_block_round(qc.certified_parent_block_round());
+        if new_state != *state {
+            self.save_state(&new_state)?;
+            *state = new_state;
+        }
+        Ok(state.clone())
+    }
+
+    /// Stops voting at the rounds lower or equal to the given one, returns the new state if the
+    /// last vote round was increased.
+    pub fn increase_last_vote_round_inner(&self, round: Round) -> Result<Option<ConsensusState>> {
+        let mut state = self.state.lock().unwrap();
+        let mut new_state = state.clone();
+        match new_state.set_last_vote_round(round) {
+            Some(updated_state) => {
+                self.save_state(&updated_state)?;
+                *state = updated_state.clone();
+                Ok(Some(updated_state))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Applies the voting rules to the vote and signs its ledger info if the vote passes them.
+    /// The parent and the grandparent of the proposed block are the blocks certified by the
+    /// quorum certificate it carries: the ledger info may only commit the block that this
+    /// quorum certificate commits along with the vote. Returns the signed ledger info, its
+    /// signature and the new consensus state.
+    pub fn sign_vote_inner(
+        &self,
+        proposed_block_id: HashValue,
+        executed_state: ExecutedState,
+        round: Round,
+        qc: &QuorumCert,
+        mut ledger_info: LedgerInfo,
+    ) -> Result<(LedgerInfo, Ed25519Signature, ConsensusState)> {
+        self.verify_quorum_cert(qc)?;
+        let parent_block_id = qc.certified_block_id();
+        let parent_block_round = qc.certified_block_round();
+        let grandparent_block_id = qc.certified_parent_block_id();
+        let grandparent_block_round = qc.certified_parent_block_round();
+        ensure!(
+            parent_block_round < round,
+            "The vote at round {} extends a quorum certificate of round {}",
+            round,
+            parent_block_round
+        );
+        if !ledger_info.is_zero() {
+            ensure!(
+                ledger_info.epoch_num() == self.epoch_manager.epoch(),
+                "The ledger info of the vote at round {} is in epoch {}, current epoch is {}",
+                round,
+                ledger_info.epoch_num(),
+                self.epoch_manager.epoch()
+            );
+            // The 3-chain commit rule (see `SafetyRules::commit_rule_for_certified_block`).
+            let potential_commit_id = if grandparent_block_round + 1 == parent_block_round
+                && parent_block_round + 1 == round
+            {
+                Some(grandparent_block_id)
+            } else {
+                None
+            };
+            ensure!(
+                potential_commit_id == Some(ledger_info.consensus_block_id()),
+                "The vote at round {} doesn't commit the block {} of its ledger info",
+                round,
+                ledger_info.consensus_block_id()
+            );
+        }
+        let mut state = self.state.lock().unwrap();
+        let mut new_state = state.clone();
+        new_state.vote(round, parent_block_round)?;
+        // The state is persisted before signing: in case of a crash the vote can't be signed again.
+        self.save_state(&new_state)?;
+        *state = new_state.clone();
+
+        ledger_info.set_consensus_data_hash(VoteMsg::vote_digest(
+            proposed_block_id,
+            executed_state,
+            round,
+            parent_block_id,
+            parent_block_round,
+            grandparent_block_id,
+            grandparent_block_round,
+        ));
+        let signature = self.sign(ledger_info.hash())?;
+        Ok((ledger_info, signature, new_state))
+    }
+
+    /// Signs the id of a block proposed at the given round, the block must extend a valid quorum
+    /// certificate of a lower round. A single proposal is signed per round: the rounds lower or
+    /// equal to the one of the last proposal signed are rejected.
+    pub fn sign_proposal_inner(
+        &self,
+        block_id: HashValue,
+        round: Round,
+        qc: &QuorumCert,
+    ) -> Result<Ed25519Signature> {
+        self.verify_quorum_cert(qc)?;
+        ensure!(
+            qc.certified_block_round() < round,
+            "The proposal at round {} extends a quorum certificate of round {}",
+            round,
+            qc.certified_block_round()
+        );
+        let mut last_proposed_round = self.last_proposed_round.lock().unwrap();
+        ensure!(
+            *last_proposed_round < round,
+            "A proposal was already signed at round {}, can't sign one at round {}",
+            *last_proposed_round,
+            round
+        );
+        // The round is persisted before signing: in case of a crash the proposal can't be signed
+        // again.
+        self.db.save_last_proposed_round(to_vec_named(&round)?)?;
+        *last_proposed_round = round;
+        self.sign(block_id)
+    }
+
+    /// Stops voting at the given round and signs the timeout for it. Returns the signatures of the
+    /// pacemaker timeout and of the timeout message and the new consensus state.
+    pub fn sign_timeout_inner(
+        &self,
+        round: Round,
+    ) -> Result<(Ed25519Signature, Ed25519Signature, ConsensusState)> {
+        // A validator doesn't vote at the rounds it timed out at.
+        self.increase_last_vote_round_inner(round)?;
+        let pacemaker_timeout_digest =
+            PacemakerTimeout::pacemaker_timeout_digest(self.author, round);
+        let pacemaker_timeout_signature = self.sign(pacemaker_timeout_digest)?;
+        let timeout_msg_signature =
+            self.sign(TimeoutMsg::new_round_digest(pacemaker_timeout_digest))?;
+        Ok((
+            pacemaker_timeout_signature,
+            timeout_msg_signature,
+            self.consensus_state(),
+        ))
+    }
+
+    /// Ends the current epoch once its reconfiguration is committed: the ledger info must be
+    /// certified by the validators of the current epoch, and the validator set of the next epoch
+    /// is read from the account state of the core code address proven against it.
+    /// Returns the new epoch.
+    pub fn start_new_epoch_inner(
+        &self,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures<Ed25519Signature>,
+        account_state_with_proof: &AccountStateWithProof,
+    ) -> Result<u64> {
+        // Serializes the epoch changes with the requests relying on the current epoch.
+        let _state = self.state.lock().unwrap();
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        // A nominal ledger info passes the verification of the signatures.
+        ensure!(
+            !ledger_info.is_zero(),
+            "The ledger info {} doesn't certify any version",
+            ledger_info
+        );
+        ensure!(
+            ledger_info.epoch_num() == self.epoch_manager.epoch(),
+            "The ledger info {} doesn't end the current epoch {}",
+            ledger_info,
+            self.epoch_manager.epoch()
+        );
+        ledger_info_with_sigs
+            .verify(self.epoch_manager.validators().as_ref())
+            .map_err(|e| format_err!("Invalid ledger info {}: {:?}", ledger_info, e))?;
+        account_state_with_proof.verify(ledger_info, ledger_info.version(), core_code_address())?;
+        let validator_set =
+            EpochManager::validator_set_from_blob(account_state_with_proof.blob.as_ref())?
+                .ok_or_else(|| format_err!("No validator set on chain at {}", ledger_info))?;
+        self.epoch_manager.start_new_epoch(&validator_set);
+        Ok(self.epoch_manager.epoch())
+    }
+
+    /// Only the quorum certificates formed by the validators known to the server are accepted:
+    /// these are the validators of the trusted peers config, a compromised consensus process can't
+    /// forge a quorum certificate to raise the preferred block round.
+    fn verify_quorum_cert(&self, qc: &QuorumCert) -> Result<()> {
+        self.epoch_manager
+            .verify_quorum_cert(qc)
+            .map_err(|e| format_err!("Invalid quorum certificate {}: {}", qc, e))
+    }
+
+    fn sign(&self, message: HashValue) -> Result<Ed25519Signature> {
+        match self.key_manager.sign_consensus_message(&message)? {
+            GenericSignature::Ed(signature) => Ok(signature),
+            GenericSignature::BLS(_) => bail!("The consensus key is not an Ed25519 key"),
+        }
+    }
+
+    fn save_state(&self, state: &ConsensusState) -> Result<()> {
+        self.db.save_state(to_vec_named(state)?)
+    }
+
+    fn process_update(&self, req: &UpdateRequest) -> Result<UpdateResponse> {
+        let qc = QuorumCert::from_proto(protobuf::parse_from_bytes(req.get_quorum_cert())?)?;
+        let mut response = UpdateResponse::new();
+        response.set_consensus_state(self.update_inner(&qc)?.into_proto());
+        Ok(response)
+    }
+
+    fn process_increase_last_vote_round(
+        &self,
+        req: &IncreaseLastVoteRoundRequest,
+    ) -> Result<IncreaseLastVoteRoundResponse> {
+        let new_state = self.increase_last_vote_round_inner(req.get_round())?;
+        let mut response = IncreaseLastVoteRoundResponse::new();
+        response.set_increased(new_state.is_some());
+        response.set_consensus_state(self.consensus_state().into_proto());
+        Ok(response)
+    }
+
+    fn process_sign_vote(&self, mut req: SignVoteRequest) -> Result<SignVoteResponse> {
+        let (ledger_info, signature, state) = self.sign_vote_inner(
+            HashValue::from_slice(req.get_proposed_block_id())?,
+            ExecutedState {
+                state_id: HashValue::from_slice(req.get_executed_state_id())?,
+                version: req.get_version(),
+            },
+            req.get_round(),
+            &QuorumCert::from_proto(protobuf::parse_from_bytes(req.get_quorum_cert())?)?,
+            LedgerInfo::from_proto(req.take_ledger_info_placeholder())?,
+        )?;
+        let mut response = SignVoteResponse::new();
+        response.set_ledger_info(ledger_info.into_proto());
+        response.set_signature(signature.to_bytes().to_vec());
+        response.set_consensus_state(state.into_proto());
+        Ok(response)
+    }
+
+    fn process_sign_proposal(&self, req: &SignProposalRequest) -> Result<SignProposalResponse> {
+        let qc = QuorumCert::from_proto(protobuf::parse_from_bytes(req.get_quorum_cert())?)?;
+        let signature = self.sign_proposal_inner(
+            HashValue::from_slice(req.get_block_id())?,
+            req.get_round(),
+            &qc,
+        )?;
+        let mut response = SignProposalResponse::new();
+        response.set_signature(signature.to_bytes().to_vec());
+        Ok(response)
+    }
+
+    fn process_sign_timeout(&self, req: &SignTimeoutRequest) -> Result<SignTimeoutResponse> {
+        let (pacemaker_timeout_signature, timeout_msg_signature, state) =
+            self.sign_timeout_inner(req.get_round())?;
+        let mut response = SignTimeoutResponse::new();
+        response.set_pacemaker_timeout_signature(pacemaker_timeout_signature.to_bytes().to_vec());
+        response.set_timeout_msg_signature(timeout_msg_signature.to_bytes().to_vec());
+        response.set_consensus_state(state.into_proto());
+        Ok(response)
+    }
+
+    fn process_start_new_epoch(
+        &self,
+        mut req: StartNewEpochRequest,
+    ) -> Result<StartNewEpochResponse> {
+        let epoch = self.start_new_epoch_inner(
+            &LedgerInfoWithSignatures::from_proto(req.take_ledger_info_with_sigs())?,
+            &AccountStateWithProof::from_proto(req.take_validator_set_account_state())?,
+        )?;
+        let mut response = StartNewEpochResponse::new();
+        response.set_epoch(epoch);
+        Ok(response)
+    }
+}
+
+impl safety_rules_grpc::SafetyRulesService for SafetyRulesServer {
+    fn get_consensus_state(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        _req: GetConsensusStateRequest,
+        sink: ::grpcio::UnarySink<GetConsensusStateResponse>,
+    ) {
+        let mut response = GetConsensusStateResponse::new();
+        response.set_consensus_state(self.consensus_state().into_proto());
+        provide_grpc_response(Ok(response), ctx, sink);
+    }
+
+    fn update(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: UpdateRequest,
+        sink: ::grpcio::UnarySink<UpdateResponse>,
+    ) {
+        provide_grpc_response(self.process_update(&req), ctx, sink);
+    }
+
+    fn increase_last_vote_round(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: IncreaseLastVoteRoundRequest,
+        sink: ::grpcio::UnarySink<IncreaseLastVoteRoundResponse>,
+    ) {
+        provide_grpc_response(self.process_increase_last_vote_round(&req), ctx, sink);
+    }
+
+    fn sign_vote(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: SignVoteRequest,
+        sink: ::grpcio::UnarySink<SignVoteResponse>,
+    ) {
+        provide_grpc_response(self.process_sign_vote(req), ctx, sink);
+    }
+
+    fn sign_proposal(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: SignProposalRequest,
+        sink: ::grpcio::UnarySink<SignProposalResponse>,
+    ) {
+        provide_grpc_response(self.process_sign_proposal(&req), ctx, sink);
+    }
+
+    fn sign_timeout(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: SignTimeoutRequest,
+        sink: ::grpcio::UnarySink<SignTimeoutResponse>,
+    ) {
+        provide_grpc_response(self.process_sign_timeout(&req), ctx, sink);
+    }
+
+    fn start_new_epoch(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: StartNewEpochRequest,
+        sink: ::grpcio::UnarySink<StartNewEpochResponse>,
+    ) {
+        provide_grpc_response(self.process_start_new_epoch(req), ctx, sink);
+    }
+}
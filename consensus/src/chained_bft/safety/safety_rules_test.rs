@@ -315,6 +315,7 @@ fn test_voting() {
     let dummy_parent = Arc::new(Block::make_block(
         a1.as_ref(),
         vec![100],
+        0,
         100,
         123,
         placeholder_certificate_for_block(
@@ -331,6 +332,7 @@ fn test_voting() {
     let dummy_block = Arc::new(Block::make_block(
         dummy_parent.as_ref(),
         vec![100],
+        0,
         dummy_parent.round() + 1,
         123,
         placeholder_certificate_for_block(
@@ -0,0 +1,69 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        common::Round,
+        consensus_types::{
+            block::Block, quorum_cert::QuorumCert, sync_info::SyncInfo, timeout_msg::TimeoutMsg,
+        },
+        safety::{
+            safety_rules::{ConsensusState, VoteInfo},
+            vote_msg::VoteMsg,
+        },
+    },
+    state_replication::ExecutedState,
+};
+use failure::Result;
+use nextgen_crypto::ed25519::*;
+use std::sync::Arc;
+use types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
+
+/// Interface of the safety rules as seen by the event processor: on top of the voting and commit
+/// rules, the safety rules sign the votes, the proposals and the timeouts, so that an
+/// implementation can keep both the consensus state and the consensus key out of the consensus
+/// process (e.g., in a remote process). In that case the consensus process cannot equivocate even
+/// if it is compromised.
+pub trait TSafetyRules<T> {
+    /// Learn about a new quorum certificate (might update the preferred block round).
+    fn update(&mut self, qc: &QuorumCert) -> Result<()>;
+
+    /// Stop voting at the rounds lower or equal to the given one, returns the new state if the
+    /// last vote round was increased.
+    fn increase_last_vote_round(&mut self, round: Round) -> Result<Option<ConsensusState>>;
+
+    /// Clones the up-to-date state of consensus (for monitoring / debugging purposes)
+    fn consensus_state(&self) -> ConsensusState;
+
+    /// Attempts to vote for a given proposal following the voting rules.
+    fn voting_rule(&mut self, proposed_block: Arc<Block<T>>) -> Result<VoteInfo>;
+
+    /// Signs the vote for a proposal that passed the voting rules: the consensus data hash of the
+    /// ledger info placeholder is set according to the vote.
+    fn sign_vote(
+        &mut self,
+        vote_info: &VoteInfo,
+        executed_state: ExecutedState,
+        ledger_info_placeholder: LedgerInfo,
+    ) -> Result<VoteMsg>;
+
+    /// Signs the id of a block proposed by this validator.
+    fn sign_proposal(&mut self, proposal: Block<T>) -> Result<Block<T>>;
+
+    /// Signs the timeout for a round at which this validator won't vote anymore (the last vote
+    /// round is expected to be increased beforehand), attaching the given vote and sync info.
+    fn sign_timeout(
+        &mut self,
+        round: Round,
+        vote: Option<VoteMsg>,
+        sync_info: SyncInfo,
+    ) -> Result<TimeoutMsg>;
+
+    /// Starts the next epoch once the given ledger info committed a reconfiguration: the safety
+    /// rules that don't share the epoch of the consensus process verify the reconfiguration on
+    /// their own.
+    fn start_new_epoch(
+        &mut self,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Result<()>;
+}
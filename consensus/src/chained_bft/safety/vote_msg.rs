@@ -34,6 +34,12 @@ pub enum VoteMsgVerificationError {
     /// The signature doesn't pass verification
     #[fail(display = "SigVerifyError: {}", _0)]
     SigVerifyError(VerifyError),
+    /// The validators of the epoch the message belongs to are unknown
+    #[fail(display = "UnknownEpoch: {}", _0)]
+    UnknownEpoch(u64),
+    /// The vote doesn't follow the execution mode of the validators
+    #[fail(display = "ExecutionModeMismatch")]
+    ExecutionModeMismatch,
 }
 
 // Internal use only. Contains all the fields in VoteMsgSerializer that contributes to the
@@ -147,6 +153,34 @@ impl VoteMsg {
         let li_sig = validator_signer
             .sign_message(ledger_info_placeholder.hash())
             .expect("Failed to sign LedgerInfo");
+        Self::new_with_signature(
+            proposed_block_id,
+            executed_state,
+            round,
+            parent_block_id,
+            parent_block_round,
+            grandparent_block_id,
+            grandparent_block_round,
+            author,
+            ledger_info_placeholder,
+            li_sig,
+        )
+    }
+
+    /// Creates a vote out of a LedgerInfo signed by the author (e.g., by remote safety rules),
+    /// the consensus data hash of the LedgerInfo is expected to be the digest of the vote.
+    pub fn new_with_signature(
+        proposed_block_id: HashValue,
+        executed_state: ExecutedState,
+        round: Round,
+        parent_block_id: HashValue,
+        parent_block_round: Round,
+        grandparent_block_id: HashValue,
+        grandparent_block_round: Round,
+        author: Author,
+        ledger_info: LedgerInfo,
+        signature: Ed25519Signature,
+    ) -> Self {
         Self {
             proposed_block_id,
             executed_state,
@@ -156,8 +190,8 @@ impl VoteMsg {
             grandparent_block_id,
             grandparent_block_round,
             author,
-            ledger_info: ledger_info_placeholder,
-            signature: li_sig,
+            ledger_info,
+            signature,
         }
     }
 
@@ -206,6 +240,11 @@ impl VoteMsg {
         &self.ledger_info
     }
 
+    /// Return the epoch of the vote
+    pub fn epoch(&self) -> u64 {
+        self.ledger_info.epoch_num()
+    }
+
     /// Return the signature of the vote
     pub fn signature(&self) -> &Ed25519Signature {
         &self.signature
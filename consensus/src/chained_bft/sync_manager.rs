@@ -26,7 +26,10 @@ use std::{
     time::{Duration, Instant},
 };
 use termion::color::*;
-use types::{account_address::AccountAddress, transaction::TransactionListWithProof};
+use types::{
+    account_address::AccountAddress, account_state_blob::AccountStateRangeWithProof,
+    transaction::TransactionListWithProof,
+};
 
 /// SyncManager is responsible for fetching dependencies and 'catching up' for given qc/ledger info
 pub struct SyncManager<T> {
@@ -118,6 +121,18 @@ where
             .await
     }
 
+    /// Get a chunk of account states of the state snapshot at `version`
+    pub async fn get_account_state_chunk(
+        &self,
+        version: u64,
+        start_key: HashValue,
+        limit: u64,
+    ) -> failure::Result<(AccountStateRangeWithProof, Vec<HashValue>)> {
+        self.state_computer
+            .get_account_state_chunk(version, start_key, limit)
+            .await
+    }
+
     pub async fn execute_and_insert_block(
         &self,
         block: Block<T>,
@@ -11,19 +11,45 @@ use futures::{channel::mpsc, Future, FutureExt};
 use logger::prelude::*;
 use nextgen_crypto::ed25519::*;
 use state_synchronizer::SyncStatus;
-use std::pin::Pin;
+use std::{collections::HashMap, pin::Pin, sync::Mutex};
 use termion::color::*;
-use types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionListWithProof};
+use types::{
+    account_address::AccountAddress, account_state_blob::AccountStateRangeWithProof,
+    ledger_info::LedgerInfoWithSignatures, transaction::TransactionListWithProof,
+};
 
 pub struct MockStateComputer {
     commit_callback: mpsc::UnboundedSender<LedgerInfoWithSignatures<Ed25519Signature>>,
+    // The parent of every executed block.
+    executed_blocks: Mutex<HashMap<HashValue, HashValue>>,
+    // The ledger infos committed or synced to, in order.
+    committed: Mutex<Vec<LedgerInfoWithSignatures<Ed25519Signature>>>,
 }
 
 impl MockStateComputer {
     pub fn new(
         commit_callback: mpsc::UnboundedSender<LedgerInfoWithSignatures<Ed25519Signature>>,
     ) -> Self {
-        MockStateComputer { commit_callback }
+        MockStateComputer {
+            commit_callback,
+            executed_blocks: Mutex::new(HashMap::new()),
+            committed: Mutex::new(vec![]),
+        }
+    }
+
+    /// Returns the parent of every block executed so far.
+    pub fn executed_blocks(&self) -> HashMap<HashValue, HashValue> {
+        self.executed_blocks.lock().unwrap().clone()
+    }
+
+    /// Returns the ids of the blocks committed or synced to so far, in order.
+    pub fn committed_block_ids(&self) -> Vec<HashValue> {
+        self.committed
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|ledger_info| ledger_info.ledger_info().consensus_block_id())
+            .collect()
     }
 }
 
@@ -31,10 +57,15 @@ impl StateComputer for MockStateComputer {
     type Payload = Vec<usize>;
     fn compute(
         &self,
-        _parent_id: HashValue,
-        _block_id: HashValue,
+        parent_id: HashValue,
+        block_id: HashValue,
+        _author: Option<AccountAddress>,
         _transactions: &Self::Payload,
     ) -> Pin<Box<dyn Future<Output = Result<StateComputeResult>> + Send>> {
+        self.executed_blocks
+            .lock()
+            .unwrap()
+            .insert(block_id, parent_id);
         async move {
             Ok(StateComputeResult {
                 new_state_id: *ACCUMULATOR_PLACEHOLDER_HASH,
@@ -50,6 +81,7 @@ impl StateComputer for MockStateComputer {
         &self,
         commit: LedgerInfoWithSignatures<Ed25519Signature>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        self.committed.lock().unwrap().push(commit.clone());
         self.commit_callback
             .unbounded_send(commit)
             .expect("Fail to notify about commit.");
@@ -66,6 +98,10 @@ impl StateComputer for MockStateComputer {
             Fg(Reset),
             commit.ledger_info().ledger_info().consensus_block_id()
         );
+        self.committed
+            .lock()
+            .unwrap()
+            .push(commit.ledger_info().clone());
         self.commit_callback
             .unbounded_send(commit.ledger_info().clone())
             .expect("Fail to notify about sync");
@@ -80,4 +116,14 @@ impl StateComputer for MockStateComputer {
     ) -> Pin<Box<dyn Future<Output = Result<TransactionListWithProof>> + Send>> {
         async move { Err(format_err!("not implemented")) }.boxed()
     }
+
+    fn get_account_state_chunk(
+        &self,
+        _: u64,
+        _: HashValue,
+        _: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateRangeWithProof, Vec<HashValue>)>> + Send>>
+    {
+        async move { Err(format_err!("not implemented")) }.boxed()
+    }
 }
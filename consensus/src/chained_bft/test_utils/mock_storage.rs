@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::chained_bft::{
-    common::Payload,
-    consensus_types::{block::Block, quorum_cert::QuorumCert},
+    common::{Payload, Round},
+    consensus_types::{
+        block::Block, committed_block_metadata::CommittedBlockMetadata, quorum_cert::QuorumCert,
+    },
     liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
     persistent_storage::{PersistentLivenessStorage, PersistentStorage, RecoveryData},
     safety::safety_rules::ConsensusState,
@@ -12,7 +14,7 @@ use config::config::{NodeConfig, NodeConfigHelpers};
 use crypto::HashValue;
 use failure::Result;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     sync::{Arc, Mutex},
 };
 
@@ -21,6 +23,7 @@ pub struct MockSharedStorage<T> {
     pub block: Mutex<HashMap<HashValue, Block<T>>>,
     pub qc: Mutex<HashMap<HashValue, QuorumCert>>,
     pub state: Mutex<ConsensusState>,
+    pub committed_blocks: Mutex<BTreeMap<Round, CommittedBlockMetadata>>,
 
     // Liveness state
     pub highest_timeout_certificates: Mutex<HighestTimeoutCertificates>,
@@ -141,11 +144,37 @@ impl<T: Payload> PersistentStorage<T> for MockStorage<T> {
         Ok(())
     }
 
+    fn save_committed_blocks(&self, committed_blocks: Vec<CommittedBlockMetadata>) -> Result<()> {
+        let mut stored = self.shared_storage.committed_blocks.lock().unwrap();
+        for metadata in committed_blocks {
+            stored.insert(metadata.round(), metadata);
+        }
+        Ok(())
+    }
+
+    fn get_committed_blocks(
+        &self,
+        max_round: Round,
+        limit: usize,
+    ) -> Result<Vec<CommittedBlockMetadata>> {
+        Ok(self
+            .shared_storage
+            .committed_blocks
+            .lock()
+            .unwrap()
+            .range(..=max_round)
+            .rev()
+            .take(limit)
+            .map(|(_, metadata)| metadata.clone())
+            .collect())
+    }
+
     fn start(_config: &NodeConfig) -> (Arc<Self>, RecoveryData<T>) {
         let shared_storage = Arc::new(MockSharedStorage {
             block: Mutex::new(HashMap::new()),
             qc: Mutex::new(HashMap::new()),
             state: Mutex::new(ConsensusState::default()),
+            committed_blocks: Mutex::new(BTreeMap::new()),
             highest_timeout_certificates: Mutex::new(HighestTimeoutCertificates::new(None, None)),
         });
         let storage = MockStorage {
@@ -198,6 +227,14 @@ impl<T: Payload> PersistentStorage<T> for EmptyStorage {
         Ok(())
     }
 
+    fn save_committed_blocks(&self, _: Vec<CommittedBlockMetadata>) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_committed_blocks(&self, _: Round, _: usize) -> Result<Vec<CommittedBlockMetadata>> {
+        Ok(vec![])
+    }
+
     fn start(_: &NodeConfig) -> (Arc<Self>, RecoveryData<T>) {
         let genesis = Block::make_genesis_block();
         let genesis_qc = QuorumCert::certificate_for_genesis();
@@ -40,6 +40,18 @@ pub fn build_empty_tree() -> Arc<BlockStore<Vec<usize>>> {
 
 pub fn build_empty_tree_with_custom_signing(
     my_signer: ValidatorSigner<Ed25519PrivateKey>,
+) -> Arc<BlockStore<Vec<usize>>> {
+    build_empty_tree_with_execution_mode(my_signer, false)
+}
+
+/// Builds an empty tree that inserts the blocks before executing them.
+pub fn build_empty_tree_with_pipelined_execution() -> Arc<BlockStore<Vec<usize>>> {
+    build_empty_tree_with_execution_mode(ValidatorSigner::random(None), true)
+}
+
+fn build_empty_tree_with_execution_mode(
+    my_signer: ValidatorSigner<Ed25519PrivateKey>,
+    pipelined_execution: bool,
 ) -> Arc<BlockStore<Vec<usize>>> {
     let (commit_cb_sender, _commit_cb_receiver) =
         mpsc::unbounded::<LedgerInfoWithSignatures<Ed25519Signature>>();
@@ -51,6 +63,7 @@ pub fn build_empty_tree_with_custom_signing(
         Arc::new(MockStateComputer::new(commit_cb_sender)),
         true,
         10, // max pruned blocks in mem
+        pipelined_execution,
     )))
 }
 
@@ -99,6 +112,7 @@ impl TreeInserter {
         block_on(self.block_store.insert_block_with_qc(Block::make_block(
             parent,
             vec![self.payload_val],
+            parent.epoch(),
             round,
             parent.timestamp_usecs() + 1,
             parent_qc,
@@ -133,6 +147,7 @@ impl TreeInserter {
         let new_block = Block::new_internal(
             block.get_payload().clone(),
             block.parent_id(),
+            block.epoch(),
             block.round(),
             block.height(),
             block.timestamp_usecs(),
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use config::config::NodeConfig;
+use debug_interface::node_debug_service::ConsensusDebugInfo;
 use failure::prelude::*;
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
 
@@ -24,6 +25,10 @@ pub trait ConsensusProvider {
 
     /// Stop the consensus operations. The function returns after graceful shutdown.
     fn stop(&mut self);
+
+    /// Returns the provider of the consensus state for the debug interface, if consensus is
+    /// running.
+    fn debug_info(&self) -> Option<Arc<dyn ConsensusDebugInfo>>;
 }
 
 /// Helper function to create a ConsensusProvider based on configuration
@@ -33,14 +38,27 @@ pub fn make_consensus_provider(
     network_receiver: ConsensusNetworkEvents,
     state_sync_client: Arc<StateSyncClient>,
 ) -> Box<dyn ConsensusProvider> {
-    Box::new(ChainedBftProvider::new(
-        node_config,
-        network_sender,
-        network_receiver,
-        create_mempool_client(node_config),
-        create_execution_client(node_config),
-        state_sync_client,
-    ))
+    let mempool_client = create_mempool_client(node_config);
+    let execution_client = create_execution_client(node_config);
+    if node_config.consensus.batch_dissemination() {
+        Box::new(ChainedBftProvider::new_with_batch_dissemination(
+            node_config,
+            network_sender,
+            network_receiver,
+            mempool_client,
+            execution_client,
+            state_sync_client,
+        ))
+    } else {
+        Box::new(ChainedBftProvider::new(
+            node_config,
+            network_sender,
+            network_receiver,
+            mempool_client,
+            execution_client,
+            state_sync_client,
+        ))
+    }
 }
 /// Create a mempool client assuming the mempool is running on localhost
 fn create_mempool_client(config: &NodeConfig) -> Arc<MempoolClient> {
@@ -86,6 +86,15 @@ pub static ref SYNC_INFO_MSGS_SENT_COUNT: IntCounter = OP_COUNTERS.counter("sync
 /// Counts the number of times the sync info message has been received since last restart.
 pub static ref SYNC_INFO_MSGS_RECEIVED_COUNT: IntCounter = OP_COUNTERS.counter("sync_info_msg_received_count");
 
+//////////////////////
+// BATCH DISSEMINATION COUNTERS
+//////////////////////
+/// Histogram for the number of txns per batch generated by this validator.
+pub static ref NUM_TXNS_PER_BATCH: Histogram = OP_COUNTERS.histogram("num_txns_per_batch");
+
+/// Count the number of batches fetched from the other validators since last restart.
+pub static ref FETCHED_BATCHES_COUNT: IntCounter = OP_COUNTERS.counter("fetched_batches_count");
+
 //////////////////////
 // RECONFIGURATION COUNTERS
 //////////////////////
@@ -192,6 +201,10 @@ pub static ref PENDING_BLOCK_REQUESTS: IntGauge = OP_COUNTERS.gauge("pending_blo
 /// Count of the pending inbound chunk requests
 pub static ref PENDING_CHUNK_REQUESTS: IntGauge = OP_COUNTERS.gauge("pending_chunk_requests");
 
+/// Count of the pending inbound account state chunk requests
+pub static ref PENDING_ACCOUNT_STATE_CHUNK_REQUESTS: IntGauge =
+    OP_COUNTERS.gauge("pending_account_state_chunk_requests");
+
 /// Count of the pending inbound new round messages
 pub static ref PENDING_NEW_ROUND_MESSAGES: IntGauge = OP_COUNTERS.gauge("pending_new_round_messages");
 
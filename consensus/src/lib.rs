@@ -13,6 +13,7 @@
 #[macro_use]
 extern crate failure;
 
+mod batch_manager;
 mod chained_bft;
 mod util;
 
@@ -22,6 +23,9 @@ pub mod consensus_provider;
 
 mod counters;
 
+/// Runs the consensus safety rules in a separate process.
+pub mod safety_rules_node;
+
 mod state_computer;
 mod state_replication;
 mod txn_manager;
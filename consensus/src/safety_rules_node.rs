@@ -0,0 +1,101 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The safety rules node runs the consensus safety rules in their own process.
+//! It accepts connections on node_config.safety_rules.address:safety_rules_port from the
+//! consensus process of the validator (configured with the "remote" safety rules backend), keeps
+//! the consensus state in its own storage and signs the votes, the proposals and the timeouts
+//! through the secret service running on node_config.secret_service.address:secret_service_port.
+//! The quorum certificates are verified against the validators of the latest epoch in storage
+//! (or the trusted peers before the first reconfiguration) at startup, the following epochs are
+//! started from the reconfigurations certified by the validators of the current one.
+//! For an example on how to run the safety rules node see bin/safety_rules.rs.
+
+use crate::chained_bft::{
+    chained_bft_consensus_provider::initial_epoch_manager,
+    consensusdb::ConsensusDB,
+    safety::{proto::safety_rules_grpc, safety_rules_server::SafetyRulesServer},
+};
+use config::config::NodeConfig;
+use crypto::HashValue;
+use failure::prelude::*;
+use grpc_helpers::spawn_service_thread;
+use grpcio::{ChannelBuilder, EnvBuilder};
+use logger::prelude::*;
+use secret_service::{
+    crypto_wrappers::KeyID, proto::secret_service_grpc::SecretServiceClient,
+    secret_service_client::ConsensusKeyManager,
+};
+use std::{convert::TryFrom, sync::Arc, thread};
+use types::account_address::AccountAddress;
+
+/// Safety rules node is run in a separate process and keeps the consensus safe even if the
+/// consensus process is compromised.
+pub struct SafetyRulesNode {
+    node_config: NodeConfig,
+}
+
+impl SafetyRulesNode {
+    /// Instantiates the node with a config file.
+    pub fn new(node_config: NodeConfig) -> Self {
+        SafetyRulesNode { node_config }
+    }
+
+    /// Starts the safety rules service
+    pub fn run(&self) -> Result<()> {
+        info!("Starting safety rules node");
+        let config = &self.node_config.safety_rules;
+
+        let key_manager = Arc::new(self.create_key_manager()?);
+        let db = Arc::new(ConsensusDB::new(
+            self.node_config
+                .base
+                .data_dir_path
+                .join(&config.storage_dir),
+        ));
+        let author = AccountAddress::try_from(self.node_config.base.peer_id.clone())?;
+        let epoch_manager = Arc::new(initial_epoch_manager(&self.node_config)?);
+        let server = SafetyRulesServer::new(author, db, key_manager, epoch_manager)?;
+        let service = safety_rules_grpc::create_safety_rules_service(server);
+        let _service_handle = spawn_service_thread(
+            service,
+            config.address.clone(),
+            config.safety_rules_port,
+            "safety_rules",
+        );
+
+        info!(
+            "Started safety rules node on port {}",
+            config.safety_rules_port
+        );
+
+        loop {
+            thread::park();
+        }
+    }
+
+    fn create_key_manager(&self) -> Result<ConsensusKeyManager> {
+        let secret_service_config = &self.node_config.secret_service;
+        let env = Arc::new(EnvBuilder::new().name_prefix("grpc-safety-rules-").build());
+        let channel = ChannelBuilder::new(env).connect(&format!(
+            "{}:{}",
+            secret_service_config.address, secret_service_config.secret_service_port
+        ));
+        let secret_service = Arc::new(SecretServiceClient::new(channel));
+
+        let consensus_key_id = &self.node_config.safety_rules.consensus_key_id;
+        if consensus_key_id.is_empty() {
+            let key_manager = ConsensusKeyManager::new(secret_service)?;
+            // The validator set must be updated with the new key for the votes to be accepted.
+            warn!(
+                "Generated a new consensus key {} in the secret service, public key: {:?}",
+                hex::encode(key_manager.key_id().to_vec()),
+                key_manager.get_consensus_public_key()?
+            );
+            Ok(key_manager)
+        } else {
+            let key_id = KeyID(HashValue::from_slice(&hex::decode(consensus_key_id)?)?);
+            Ok(ConsensusKeyManager::from_key_id(secret_service, key_id))
+        }
+    }
+}
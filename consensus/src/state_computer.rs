@@ -19,6 +19,8 @@ use proto_conv::{FromProto, IntoProto};
 use state_synchronizer::{StateSyncClient, SyncStatus};
 use std::{pin::Pin, sync::Arc, time::Instant};
 use types::{
+    account_address::AccountAddress,
+    account_state_blob::AccountStateRangeWithProof,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{SignedTransaction, TransactionListWithProof, TransactionStatus},
 };
@@ -85,6 +87,8 @@ impl StateComputer for ExecutionProxy {
         parent_block_id: HashValue,
         // The id of a current block.
         block_id: HashValue,
+        // The payload holds the transactions themselves.
+        _author: Option<AccountAddress>,
         // Transactions to execute.
         transactions: &Self::Payload,
     ) -> Pin<Box<dyn Future<Output = Result<StateComputeResult>> + Send>> {
@@ -181,4 +185,16 @@ impl StateComputer for ExecutionProxy {
             .get_chunk(start_version, target_version, batch_size)
             .boxed()
     }
+
+    fn get_account_state_chunk(
+        &self,
+        version: u64,
+        start_key: HashValue,
+        limit: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateRangeWithProof, Vec<HashValue>)>> + Send>>
+    {
+        self.synchronizer
+            .get_account_state_chunk(version, start_key, limit)
+            .boxed()
+    }
 }
@@ -5,12 +5,14 @@ use crate::chained_bft::QuorumCert;
 use canonical_serialization::{CanonicalSerialize, CanonicalSerializer};
 use crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
 use failure::Result;
-use futures::Future;
+use futures::{future, Future, FutureExt};
 use nextgen_crypto::ed25519::*;
 use serde::{Deserialize, Serialize};
 use state_synchronizer::SyncStatus;
 use std::{pin::Pin, sync::Arc};
 use types::{
+    account_address::AccountAddress,
+    account_state_blob::AccountStateRangeWithProof,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{TransactionListWithProof, Version},
     validator_set::ValidatorSet,
@@ -35,7 +37,6 @@ pub struct StateComputeResult {
     pub num_successful_txns: u64,
     /// If set, these are the validator public keys that will be used to start the next epoch
     /// immediately after this state is committed
-    /// TODO [Reconfiguration] the validators are currently ignored, no reconfiguration yet.
     pub validators: Option<ValidatorSet>,
 }
 
@@ -61,6 +62,18 @@ pub trait TxnManager: Send + Sync {
         // Monotonic timestamp_usecs of committed blocks is used to GC expired transactions.
         timestamp_usecs: u64,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+    /// Makes sure that the data the payload of a proposal refers to is available locally before
+    /// voting for it (e.g., fetches the batches of transactions referenced by their digests). The
+    /// payloads that carry the transactions themselves are always available.
+    fn fetch_payload<'a>(
+        &'a self,
+        _payload: &'a Self::Payload,
+        // The author of the proposal, which has all the data its payload refers to.
+        _author: AccountAddress,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        future::ready(Ok(())).boxed()
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -76,6 +89,15 @@ impl ExecutedState {
             version: 0,
         }
     }
+
+    /// The state carried by the votes in the pipelined execution mode, where the votes only
+    /// certify the ordering of the blocks.
+    pub fn unexecuted() -> Self {
+        ExecutedState {
+            state_id: HashValue::zero(),
+            version: 0,
+        }
+    }
 }
 
 impl CanonicalSerialize for ExecutedState {
@@ -103,6 +125,8 @@ pub trait StateComputer: Send + Sync {
         parent_block_id: HashValue,
         // The id of a current block.
         block_id: HashValue,
+        // The author of the current block, if any, from whom its payload can be fetched.
+        author: Option<AccountAddress>,
         // Transactions to execute.
         transactions: &Self::Payload,
     ) -> Pin<Box<dyn Future<Output = Result<StateComputeResult>> + Send>>;
@@ -126,6 +150,15 @@ pub trait StateComputer: Send + Sync {
         target_version: u64,
         batch_size: u64,
     ) -> Pin<Box<dyn Future<Output = Result<TransactionListWithProof>> + Send>>;
+
+    /// Get a chunk of account states of the state snapshot at `version`, together with the
+    /// frozen subtree root hashes of the transaction accumulator at that version
+    fn get_account_state_chunk(
+        &self,
+        version: u64,
+        start_key: HashValue,
+        limit: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateRangeWithProof, Vec<HashValue>)>> + Send>>;
 }
 
 pub trait StateMachineReplication {
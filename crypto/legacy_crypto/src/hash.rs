@@ -551,6 +551,15 @@ define_hasher! {
     (VoteMsgHasher, VOTE_MSG_HASHER, b"VoteMsg")
 }
 
+define_hasher! {
+    /// The hasher used to compute the digest of a batch of transactions disseminated by consensus.
+    (
+        TransactionBatchHasher,
+        TRANSACTION_BATCH_HASHER,
+        b"TransactionBatch"
+    )
+}
+
 define_hasher! {
     /// The hasher used to compute the hash of a ContractEvent object.
     (ContractEventHasher, CONTRACT_EVENT_HASHER, b"ContractEvent")
@@ -74,7 +74,7 @@ pub const PROOF_LENGTH: usize = 80;
 pub struct VRFPrivateKey(ed25519_PrivateKey);
 
 /// An ECVRF public key
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct VRFPublicKey(ed25519_PublicKey);
 
 /// A longer private key which is slightly optimized for proof generation.
@@ -128,12 +128,29 @@ impl TryFrom<&[u8]> for VRFPrivateKey {
     type Error = CryptoMaterialError;
 
     fn try_from(bytes: &[u8]) -> std::result::Result<VRFPrivateKey, CryptoMaterialError> {
+        if bytes.len() != ed25519_dalek::SECRET_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
         Ok(VRFPrivateKey(
-            ed25519_PrivateKey::from_bytes(bytes).unwrap(),
+            ed25519_PrivateKey::from_bytes(bytes)
+                .map_err(|_| CryptoMaterialError::DeserializationError)?,
         ))
     }
 }
 
+impl ValidKey for VRFPrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl Clone for VRFPrivateKey {
+    fn clone(&self) -> Self {
+        VRFPrivateKey::try_from(&self.0.to_bytes()[..]).unwrap()
+    }
+}
+
 impl Deref for VRFPrivateKey {
     type Target = ed25519_PrivateKey;
 
@@ -168,6 +185,12 @@ impl TryFrom<&[u8]> for VRFPublicKey {
     }
 }
 
+impl ValidKey for VRFPublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+}
+
 impl VRFPublicKey {
     /// Given a [`Proof`] and an input, returns whether or not the proof is valid for the input
     /// and public key
@@ -279,6 +302,10 @@ impl TryFrom<&[u8]> for Proof {
     type Error = CryptoMaterialError;
 
     fn try_from(bytes: &[u8]) -> std::result::Result<Proof, CryptoMaterialError> {
+        if bytes.len() != PROOF_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+
         let mut c_buf = [0u8; 32];
         c_buf[..16].copy_from_slice(&bytes[32..48]);
         let mut s_buf = [0u8; 32];
@@ -286,7 +313,7 @@ impl TryFrom<&[u8]> for Proof {
         Ok(Proof {
             gamma: CompressedEdwardsY::from_slice(&bytes[..32])
                 .decompress()
-                .unwrap(),
+                .ok_or(CryptoMaterialError::DeserializationError)?,
             c: ed25519_Scalar::from_bits(c_buf),
             s: ed25519_Scalar::from_bits(s_buf),
         })
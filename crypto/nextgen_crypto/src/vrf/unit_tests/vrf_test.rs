@@ -178,6 +178,15 @@ fn test_output_from_proof() {
     }
 }
 
+#[test]
+fn test_proof_from_invalid_bytes() {
+    assert!(Proof::try_from(&[0u8; PROOF_LENGTH - 1][..]).is_err());
+    // Not the encoding of a point of the curve
+    let mut bytes = [0u8; PROOF_LENGTH];
+    bytes[0] = 2;
+    assert!(Proof::try_from(&bytes[..]).is_err());
+}
+
 proptest! {
     #[test]
     fn test_prove_and_verify(
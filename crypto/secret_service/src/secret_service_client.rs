@@ -42,6 +42,19 @@ impl ConsensusKeyManager {
         })
     }
 
+    /// Saves a reference to the secret service and uses a signing key it already holds.
+    pub fn from_key_id(secret_service: Arc<SecretServiceClient>, signing_keyid: KeyID) -> Self {
+        Self {
+            secret_service,
+            signing_keyid,
+        }
+    }
+
+    /// Returns the id of the signing key in the secret service.
+    pub fn key_id(&self) -> &KeyID {
+        &self.signing_keyid
+    }
+
     /// Asks the secret service for the public key and returns it.
     pub fn get_consensus_public_key(&self) -> Result<GenericPublicKey> {
         let mut pk_req: PublicKeyRequest = PublicKeyRequest::new();
@@ -15,6 +15,7 @@ use nextgen_crypto::ed25519::*;
 use proptest_derive::Arbitrary;
 use proto_conv::{FromProto, IntoProto};
 use types::{
+    account_state_blob::AccountStateRangeWithProof,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{SignedTransaction, TransactionListWithProof, TransactionStatus, Version},
     validator_set::ValidatorSet,
@@ -200,3 +201,31 @@ pub struct ExecuteChunkRequest {
 #[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
 #[ProtoType(crate::proto::execution::ExecuteChunkResponse)]
 pub struct ExecuteChunkResponse {}
+
+#[derive(Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+#[ProtoType(crate::proto::execution::RestoreStateSnapshotChunkRequest)]
+pub struct RestoreStateSnapshotChunkRequest {
+    pub account_state_chunk: AccountStateRangeWithProof,
+    pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+    pub ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+#[ProtoType(crate::proto::execution::RestoreStateSnapshotChunkResponse)]
+pub struct RestoreStateSnapshotChunkResponse {}
+
+#[derive(Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+#[ProtoType(crate::proto::execution::GetStateSnapshotRestoreProgressRequest)]
+pub struct GetStateSnapshotRestoreProgressRequest {
+    pub version: Version,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+#[ProtoType(crate::proto::execution::GetStateSnapshotRestoreProgressResponse)]
+pub struct GetStateSnapshotRestoreProgressResponse {
+    pub next_key: HashValue,
+}
@@ -3,7 +3,9 @@
 
 use crate::{
     CommitBlockRequest, CommitBlockResponse, ExecuteBlockRequest, ExecuteBlockResponse,
-    ExecuteChunkRequest, ExecuteChunkResponse,
+    ExecuteChunkRequest, ExecuteChunkResponse, GetStateSnapshotRestoreProgressRequest,
+    GetStateSnapshotRestoreProgressResponse, RestoreStateSnapshotChunkRequest,
+    RestoreStateSnapshotChunkResponse,
 };
 use proptest::prelude::*;
 use proptest_helpers::with_stack_size;
@@ -26,6 +28,20 @@ proptest! {
     fn test_execute_chunk_request_roundtrip(execute_chunk_request in any::<ExecuteChunkRequest>()) {
         assert_protobuf_encode_decode(&execute_chunk_request);
     }
+
+    #[test]
+    fn test_restore_state_snapshot_chunk_request_roundtrip(
+        restore_state_snapshot_chunk_request in any::<RestoreStateSnapshotChunkRequest>()
+    ) {
+        assert_protobuf_encode_decode(&restore_state_snapshot_chunk_request);
+    }
+
+    #[test]
+    fn test_get_state_snapshot_restore_progress_request_roundtrip(
+        request in any::<GetStateSnapshotRestoreProgressRequest>()
+    ) {
+        assert_protobuf_encode_decode(&request);
+    }
 }
 
 proptest! {
@@ -38,6 +54,20 @@ proptest! {
     fn test_execute_chunk_response_roundtrip(execute_chunk_response in any::<ExecuteChunkResponse>()) {
         assert_protobuf_encode_decode(&execute_chunk_response);
     }
+
+    #[test]
+    fn test_restore_state_snapshot_chunk_response_roundtrip(
+        restore_state_snapshot_chunk_response in any::<RestoreStateSnapshotChunkResponse>()
+    ) {
+        assert_protobuf_encode_decode(&restore_state_snapshot_chunk_response);
+    }
+
+    #[test]
+    fn test_get_state_snapshot_restore_progress_response_roundtrip(
+        response in any::<GetStateSnapshotRestoreProgressResponse>()
+    ) {
+        assert_protobuf_encode_decode(&response);
+    }
 }
 
 #[test]
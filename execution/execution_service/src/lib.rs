@@ -4,7 +4,10 @@
 #![feature(async_await)]
 
 use config::config::NodeConfig;
-use execution_proto::{CommitBlockRequest, ExecuteBlockRequest, ExecuteChunkRequest};
+use execution_proto::{
+    CommitBlockRequest, ExecuteBlockRequest, ExecuteChunkRequest,
+    GetStateSnapshotRestoreProgressRequest, RestoreStateSnapshotChunkRequest,
+};
 use executor::Executor;
 use failure::Result;
 use futures01::future::Future;
@@ -117,6 +120,63 @@ impl execution_proto::proto::execution_grpc::Execution for ExecutionService {
             }
         }
     }
+
+    fn restore_state_snapshot_chunk(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        request: execution_proto::proto::execution::RestoreStateSnapshotChunkRequest,
+        sink: grpcio::UnarySink<
+            execution_proto::proto::execution::RestoreStateSnapshotChunkResponse,
+        >,
+    ) {
+        match RestoreStateSnapshotChunkRequest::from_proto(request) {
+            Ok(req) => {
+                let fut = process_response(
+                    self.executor.restore_state_snapshot_chunk(
+                        req.account_state_chunk,
+                        req.ledger_frozen_subtree_hashes,
+                        req.ledger_info_with_sigs,
+                    ),
+                    sink,
+                )
+                .boxed()
+                .unit_error()
+                .compat();
+                ctx.spawn(fut);
+            }
+            Err(err) => {
+                let fut = process_conversion_error(err, sink);
+                ctx.spawn(fut);
+            }
+        }
+    }
+
+    fn get_state_snapshot_restore_progress(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        request: execution_proto::proto::execution::GetStateSnapshotRestoreProgressRequest,
+        sink: grpcio::UnarySink<
+            execution_proto::proto::execution::GetStateSnapshotRestoreProgressResponse,
+        >,
+    ) {
+        match GetStateSnapshotRestoreProgressRequest::from_proto(request) {
+            Ok(req) => {
+                let fut = process_response(
+                    self.executor
+                        .get_state_snapshot_restore_progress(req.version),
+                    sink,
+                )
+                .boxed()
+                .unit_error()
+                .compat();
+                ctx.spawn(fut);
+            }
+            Err(err) => {
+                let fut = process_conversion_error(err, sink);
+                ctx.spawn(fut);
+            }
+        }
+    }
 }
 
 async fn process_response<T>(
@@ -18,6 +18,7 @@ use types::{
     account_address::AccountAddress,
     account_config::{association_address, get_account_resource_or_default},
     account_state_blob::AccountStateWithProof,
+    contract_event::EventFilter,
     get_with_proof::{verify_update_to_latest_ledger_response, RequestItem},
     test_helpers::transaction_test_helpers::get_test_signed_txn as get_test_signed_txn_proto,
     transaction::{
@@ -235,6 +236,20 @@ fn test_execution_with_storage() {
             ascending: false,
             limit: 10,
         },
+        RequestItem::GetAccountTransactions {
+            account: genesis_account,
+            start_sequence_number: 0,
+            limit: 10,
+            fetch_events: false,
+        },
+        RequestItem::GetEventsByVersionRange {
+            start_version: 1,
+            limit: 10,
+            filter: EventFilter {
+                access_path_prefix: Some(AccessPath::new(account3, vec![])),
+                resource_type: None,
+            },
+        },
     ];
 
     let (mut response_items, ledger_info_with_sigs, _validator_change_events) = storage_read_client
@@ -376,6 +391,40 @@ fn test_execution_with_storage() {
         .unwrap();
     assert_eq!(account3_received_events.len(), 3);
 
+    let (genesis_account_txns, proof_of_current_sequence_number) = response_items
+        .pop()
+        .unwrap()
+        .into_get_account_txns_response()
+        .unwrap();
+    assert_eq!(genesis_account_txns.len(), 3);
+    for (txn, expected_txn) in genesis_account_txns.iter().zip(&block1) {
+        verify_committed_txn_status(Some(txn), expected_txn).unwrap();
+    }
+    assert!(proof_of_current_sequence_number.is_some());
+
+    let event_list_with_proof = response_items
+        .pop()
+        .unwrap()
+        .into_get_events_by_version_range_response()
+        .unwrap();
+    // Fewer events than the limit are on account3, so the range extends to the latest version.
+    assert_eq!(event_list_with_proof.first_transaction_version, Some(1));
+    assert_eq!(
+        event_list_with_proof.events.len() as u64,
+        ledger_info_with_sigs.ledger_info().version()
+    );
+    let account3_prefix = AccessPath::new(account3, vec![]);
+    let account3_events: Vec<_> = event_list_with_proof
+        .events
+        .iter()
+        .flatten()
+        .filter(|e| e.access_path().starts_with(&account3_prefix))
+        .collect();
+    assert_eq!(account3_events.len(), 3);
+    assert!(account3_events
+        .iter()
+        .all(|e| e.access_path() == &AccessPath::new_for_received_event(account3)));
+
     // Execution the 2nd block.
     let execute_block_request = ExecuteBlockRequest::new(block2.clone(), block1_id, block2_id);
     let execute_block_response = execution_client
@@ -12,7 +12,10 @@ use crypto::{
     hash::{CryptoHash, EventAccumulatorHasher, TransactionAccumulatorHasher},
     HashValue,
 };
-use execution_proto::{CommitBlockResponse, ExecuteBlockResponse, ExecuteChunkResponse};
+use execution_proto::{
+    CommitBlockResponse, ExecuteBlockResponse, ExecuteChunkResponse,
+    GetStateSnapshotRestoreProgressResponse, RestoreStateSnapshotChunkResponse,
+};
 use failure::prelude::*;
 use futures::channel::oneshot;
 use logger::prelude::*;
@@ -28,7 +31,7 @@ use std::{
 use storage_client::{StorageRead, StorageWrite, VerifiedStateView};
 use types::{
     account_address::AccountAddress,
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateRangeWithProof},
     ledger_info::LedgerInfoWithSignatures,
     proof::SparseMerkleProof,
     transaction::{
@@ -95,6 +98,7 @@ where
         previous_frozen_subtrees_in_accumulator: Vec<HashValue>,
         previous_num_elements_in_accumulator: u64,
         last_committed_block_id: HashValue,
+        restoring_state_snapshot: bool,
         storage_read_client: Arc<dyn StorageRead>,
         storage_write_client: Arc<dyn StorageWrite>,
         vm_config: VMConfig,
@@ -111,7 +115,11 @@ where
             blocks_to_store: VecDeque::new(),
             storage_read_client,
             storage_write_client,
-            mode: Mode::Normal,
+            mode: if restoring_state_snapshot {
+                Mode::Syncing
+            } else {
+                Mode::Normal
+            },
             vm_config,
             phantom: PhantomData,
         }
@@ -262,6 +270,41 @@ where
                     .send(res.map(|_| ExecuteChunkResponse {}))
                     .expect("Failed to send execute chunk response.");
             }
+            Command::RestoreStateSnapshotChunk {
+                account_state_chunk,
+                ledger_frozen_subtree_hashes,
+                ledger_info_with_sigs,
+                resp_sender,
+            } => {
+                let res = self
+                    .restore_state_snapshot_chunk(
+                        account_state_chunk.clone(),
+                        ledger_frozen_subtree_hashes,
+                        ledger_info_with_sigs.clone(),
+                    )
+                    .map_err(|e| {
+                        security_log(SecurityEvent::InvalidStateSnapshotChunkExecutor)
+                            .error(&e)
+                            .data(account_state_chunk)
+                            .data(ledger_info_with_sigs)
+                            .log();
+                        e
+                    });
+                resp_sender
+                    .send(res.map(|_| RestoreStateSnapshotChunkResponse {}))
+                    .expect("Failed to send restore state snapshot chunk response.");
+            }
+            Command::GetStateSnapshotRestoreProgress {
+                version,
+                resp_sender,
+            } => {
+                let res = self
+                    .next_state_snapshot_key(version)
+                    .map(|next_key| GetStateSnapshotRestoreProgressResponse { next_key });
+                resp_sender
+                    .send(res)
+                    .expect("Failed to send get state snapshot restore progress response.");
+            }
         }
     }
 
@@ -421,6 +464,96 @@ where
         Ok(())
     }
 
+    /// Returns the key the next chunk of the state snapshot at `version` should start with, per
+    /// the progress persisted in storage. A snapshot at a different version than the one being
+    /// restored starts from the beginning.
+    fn next_state_snapshot_key(&self, version: Version) -> Result<HashValue> {
+        Ok(
+            match self
+                .storage_read_client
+                .get_state_snapshot_restore_progress()?
+            {
+                Some(progress) if progress.version == version => progress.next_key,
+                _ => HashValue::zero(),
+            },
+        )
+    }
+
+    /// Verifies a chunk of account states of the state snapshot at the version of the provided
+    /// ledger info and persists it. The chunk must start right after the previous one, or from
+    /// the very beginning if it is the first chunk of this snapshot. After the last chunk, the
+    /// snapshot is finalized and the in-memory state is reset to the snapshot version.
+    fn restore_state_snapshot_chunk(
+        &mut self,
+        account_state_chunk: AccountStateRangeWithProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Result<()> {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let version = ledger_info.version();
+        let num_committed_txns = self.committed_transaction_accumulator.num_elements();
+        ensure!(
+            version >= num_committed_txns,
+            "State snapshot too old. Number of committed transactions: {}. Snapshot version: {}.",
+            num_committed_txns,
+            version,
+        );
+
+        let start_key = self.next_state_snapshot_key(version)?;
+        // The chunk size is decided by the sender, so there is no limit to check against.
+        let num_accounts = account_state_chunk.account_blobs.len() as u64;
+        account_state_chunk.verify(ledger_info, version, start_key, num_accounts)?;
+
+        if let Mode::Normal = self.mode {
+            self.mode = Mode::Syncing;
+            info!("Start restoring state snapshot at version {}...", version);
+        }
+
+        let txn_info = account_state_chunk.proof.transaction_info().clone();
+        match account_state_chunk.next_start_key() {
+            Some(_) => {
+                self.storage_write_client
+                    .save_account_state_chunk(version, account_state_chunk.account_blobs)?;
+            }
+            None => {
+                // This is the last chunk of the snapshot, so we also need to verify the frozen
+                // subtrees before finalizing it.
+                ensure!(
+                    ledger_frozen_subtree_hashes.len() == (version + 1).count_ones() as usize,
+                    "Wrong number of frozen subtrees for version {}: {}.",
+                    version,
+                    ledger_frozen_subtree_hashes.len(),
+                );
+                let accumulator = Accumulator::<TransactionAccumulatorHasher>::new(
+                    ledger_frozen_subtree_hashes.clone(),
+                    version + 1,
+                );
+                ensure!(
+                    accumulator.root_hash() == ledger_info.transaction_accumulator_hash(),
+                    "Frozen subtrees do not match the root hash in ledger info."
+                );
+
+                self.storage_write_client
+                    .save_account_state_chunk(version, account_state_chunk.account_blobs)?;
+                self.storage_write_client.finalize_state_snapshot(
+                    txn_info.clone(),
+                    ledger_frozen_subtree_hashes,
+                    ledger_info_with_sigs.clone(),
+                )?;
+
+                self.committed_state_tree =
+                    Rc::new(SparseMerkleTree::new(txn_info.state_root_hash()));
+                self.committed_transaction_accumulator = Rc::new(accumulator);
+                self.committed_timestamp_usecs = ledger_info.timestamp_usecs();
+                self.block_tree.reset(ledger_info.consensus_block_id());
+                self.mode = Mode::Normal;
+                info!("Restored state snapshot at version {}.", version);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Verifies proofs using provided ledger info. Also verifies that the version of the first
     /// transaction matches the latest committed transaction. If the first few transaction happens
     /// to be older, returns how many need to be skipped and the first version to be committed.
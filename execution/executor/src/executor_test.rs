@@ -44,7 +44,8 @@ fn get_config() -> NodeConfig {
 }
 
 fn create_storage_server(config: &mut NodeConfig) -> (grpcio::Server, mpsc::Receiver<()>) {
-    let (service, shutdown_receiver) = StorageService::new(&config.storage.get_dir());
+    let (service, shutdown_receiver) =
+        StorageService::new(&config.storage.get_dir(), config.storage.storage_mode);
     let mut server = ServerBuilder::new(Arc::new(EnvBuilder::new().build()))
         .register_service(create_storage(service))
         .bind("localhost", 0)
@@ -445,6 +446,61 @@ fn test_executor_execute_chunk_restart() {
     shutdown_receiver.recv().unwrap();
 }
 
+#[test]
+fn test_executor_restore_state_snapshot() {
+    let num_blocks = 10;
+
+    // Build the ledger we take the snapshot from.
+    let source_executor = TestExecutor::new();
+    for i in 0..num_blocks {
+        execute_and_commit_block(&source_executor, i);
+    }
+    let source_storage_client = StorageReadServiceClient::new(
+        Arc::new(EnvBuilder::new().build()),
+        "localhost",
+        source_executor._config.storage.port,
+    );
+    let (_, ledger_info, _) = source_storage_client
+        .update_to_latest_ledger(0, vec![])
+        .unwrap();
+    let version = ledger_info.ledger_info().version();
+    assert_eq!(version, num_blocks);
+
+    // Restore the snapshot in a fresh node, chunk by chunk.
+    let executor = TestExecutor::new();
+    let storage_client = StorageReadServiceClient::new(
+        Arc::new(EnvBuilder::new().build()),
+        "localhost",
+        executor._config.storage.port,
+    );
+    let mut start_key = Some(HashValue::zero());
+    while let Some(key) = start_key {
+        let (account_state_chunk, ledger_frozen_subtree_hashes) = source_storage_client
+            .get_account_state_chunk(key, 4 /* limit */, version)
+            .unwrap();
+        start_key = account_state_chunk.next_start_key();
+        block_on(executor.restore_state_snapshot_chunk(
+            account_state_chunk,
+            ledger_frozen_subtree_hashes,
+            ledger_info.clone(),
+        ))
+        .unwrap()
+        .unwrap();
+    }
+    let (_, li, _) = storage_client.update_to_latest_ledger(0, vec![]).unwrap();
+    assert_eq!(li, ledger_info);
+
+    // Both nodes should be able to continue from the snapshot version and end up with the same
+    // ledger.
+    execute_and_commit_block(&source_executor, num_blocks);
+    execute_and_commit_block(&executor, num_blocks);
+    let (_, source_li, _) = source_storage_client
+        .update_to_latest_ledger(0, vec![])
+        .unwrap();
+    let (_, li, _) = storage_client.update_to_latest_ledger(0, vec![]).unwrap();
+    assert_eq!(li, source_li);
+}
+
 struct TestBlock {
     txns: Vec<SignedTransaction>,
     parent_id: HashValue,
@@ -16,7 +16,10 @@ use crypto::{
     hash::{GENESIS_BLOCK_ID, PRE_GENESIS_BLOCK_ID, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
 };
-use execution_proto::{CommitBlockResponse, ExecuteBlockResponse, ExecuteChunkResponse};
+use execution_proto::{
+    CommitBlockResponse, ExecuteBlockResponse, ExecuteChunkResponse,
+    GetStateSnapshotRestoreProgressResponse, RestoreStateSnapshotChunkResponse,
+};
 use failure::{format_err, Result};
 use futures::{channel::oneshot, executor::block_on};
 use lazy_static::lazy_static;
@@ -29,8 +32,9 @@ use std::{
 };
 use storage_client::{StorageRead, StorageWrite};
 use types::{
+    account_state_blob::AccountStateRangeWithProof,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    transaction::{SignedTransaction, TransactionListWithProof},
+    transaction::{SignedTransaction, TransactionListWithProof, Version},
 };
 use vm_runtime::VMExecutor;
 
@@ -70,6 +74,7 @@ where
             num_elements_in_accumulator,
             committed_timestamp_usecs,
             committed_block_id,
+            restoring_state_snapshot,
         ) = match startup_info {
             Some(info) => {
                 info!("Startup info read from DB: {:?}.", info);
@@ -80,6 +85,7 @@ where
                     info.latest_version + 1,
                     ledger_info.timestamp_usecs(),
                     ledger_info.consensus_block_id(),
+                    info.state_snapshot_restore.is_some(),
                 )
             }
             None => {
@@ -90,6 +96,7 @@ where
                     0,
                     0,
                     *PRE_GENESIS_BLOCK_ID,
+                    false,
                 )
             }
         };
@@ -109,6 +116,7 @@ where
                             frozen_subtrees_in_accumulator,
                             num_elements_in_accumulator,
                             committed_block_id,
+                            restoring_state_snapshot,
                             storage_read_client,
                             storage_write_client,
                             vm_config,
@@ -260,6 +268,70 @@ where
         }
         resp_receiver
     }
+
+    /// Verifies and persists a chunk of account states of the state snapshot at the version of
+    /// `ledger_info_with_sigs`, so a new node can bootstrap without replaying all the
+    /// transactions. The chunks are expected in ascending order of keys. Once a chunk reaches the
+    /// end of the state, the snapshot is finalized and the executor continues from its version.
+    pub fn restore_state_snapshot_chunk(
+        &self,
+        account_state_chunk: AccountStateRangeWithProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> oneshot::Receiver<Result<RestoreStateSnapshotChunkResponse>> {
+        debug!(
+            "Received request to restore state snapshot chunk. Chunk size: {}. Snapshot version: \
+             {}.",
+            account_state_chunk.account_blobs.len(),
+            ledger_info_with_sigs.ledger_info().version(),
+        );
+
+        let (resp_sender, resp_receiver) = oneshot::channel();
+        match self
+            .command_sender
+            .lock()
+            .expect("Failed to lock mutex.")
+            .as_ref()
+        {
+            Some(sender) => sender
+                .send(Command::RestoreStateSnapshotChunk {
+                    account_state_chunk,
+                    ledger_frozen_subtree_hashes,
+                    ledger_info_with_sigs,
+                    resp_sender,
+                })
+                .expect("Did block processor thread panic?"),
+            None => resp_sender
+                .send(Err(format_err!("Executor is shutting down.")))
+                .expect("Failed to send error message."),
+        }
+        resp_receiver
+    }
+
+    /// Returns the key the next chunk of the state snapshot at `version` should start with.
+    pub fn get_state_snapshot_restore_progress(
+        &self,
+        version: Version,
+    ) -> oneshot::Receiver<Result<GetStateSnapshotRestoreProgressResponse>> {
+        let (resp_sender, resp_receiver) = oneshot::channel();
+        match self
+            .command_sender
+            .lock()
+            .expect("Failed to lock mutex.")
+            .as_ref()
+        {
+            Some(sender) => sender
+                .send(Command::GetStateSnapshotRestoreProgress {
+                    version,
+                    resp_sender,
+                })
+                .expect("Did block processor thread panic?"),
+            None => resp_sender
+                .send(Err(format_err!("Executor is shutting down.")))
+                .expect("Failed to send error message."),
+        }
+        resp_receiver
+    }
 }
 
 impl<V> Drop for Executor<V> {
@@ -296,4 +368,14 @@ enum Command {
         ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
         resp_sender: oneshot::Sender<Result<ExecuteChunkResponse>>,
     },
+    RestoreStateSnapshotChunk {
+        account_state_chunk: AccountStateRangeWithProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+        resp_sender: oneshot::Sender<Result<RestoreStateSnapshotChunkResponse>>,
+    },
+    GetStateSnapshotRestoreProgress {
+        version: Version,
+        resp_sender: oneshot::Sender<Result<GetStateSnapshotRestoreProgressResponse>>,
+    },
 }
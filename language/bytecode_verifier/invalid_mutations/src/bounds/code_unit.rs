@@ -8,8 +8,8 @@ use vm::{
     errors::{VMStaticViolation, VerificationError},
     file_format::{
         AddressPoolIndex, ByteArrayPoolIndex, Bytecode, CodeOffset, CompiledModuleMut,
-        FieldDefinitionIndex, FunctionHandleIndex, LocalIndex, StringPoolIndex,
-        StructDefinitionIndex, TableIndex, NO_TYPE_ACTUALS,
+        FieldDefinitionIndex, FunctionHandleIndex, LocalIndex, LocalsSignatureIndex,
+        StringPoolIndex, StructDefinitionIndex, TableIndex, NO_TYPE_ACTUALS,
     },
     internals::ModuleIndex,
     IndexKind,
@@ -172,6 +172,7 @@ impl<'a> ApplyCodeUnitBoundsContext<'a> {
         let function_handles_len = self.module.function_handles.len();
         let field_defs_len = self.module.field_defs.len();
         let struct_defs_len = self.module.struct_defs.len();
+        let locals_signatures_len = self.module.locals_signatures.len();
 
         mutations
             .iter()
@@ -266,6 +267,53 @@ impl<'a> ApplyCodeUnitBoundsContext<'a> {
                     MoveLoc(_) => locals_bytecode!(locals_len, bytecode_idx, offset, MoveLoc),
                     StLoc(_) => locals_bytecode!(locals_len, bytecode_idx, offset, StLoc),
                     BorrowLoc(_) => locals_bytecode!(locals_len, bytecode_idx, offset, BorrowLoc),
+                    VecPack(_, num) => {
+                        let new_idx = (locals_signatures_len + offset) as TableIndex;
+                        (
+                            VecPack(LocalsSignatureIndex::new(new_idx), num),
+                            VMStaticViolation::CodeUnitIndexOutOfBounds(
+                                LocalsSignatureIndex::KIND,
+                                bytecode_idx,
+                                locals_signatures_len,
+                                new_idx as usize,
+                            ),
+                        )
+                    }
+                    VecLen(_) => new_bytecode!(
+                        locals_signatures_len,
+                        bytecode_idx,
+                        offset,
+                        LocalsSignatureIndex,
+                        VecLen
+                    ),
+                    VecBorrow(_) => new_bytecode!(
+                        locals_signatures_len,
+                        bytecode_idx,
+                        offset,
+                        LocalsSignatureIndex,
+                        VecBorrow
+                    ),
+                    VecPush(_) => new_bytecode!(
+                        locals_signatures_len,
+                        bytecode_idx,
+                        offset,
+                        LocalsSignatureIndex,
+                        VecPush
+                    ),
+                    VecPop(_) => new_bytecode!(
+                        locals_signatures_len,
+                        bytecode_idx,
+                        offset,
+                        LocalsSignatureIndex,
+                        VecPop
+                    ),
+                    VecSwap(_) => new_bytecode!(
+                        locals_signatures_len,
+                        bytecode_idx,
+                        offset,
+                        LocalsSignatureIndex,
+                        VecSwap
+                    ),
 
                     // List out the other options explicitly so there's a compile error if a new
                     // bytecode gets added.
@@ -274,7 +322,7 @@ impl<'a> ApplyCodeUnitBoundsContext<'a> {
                     | Or | And | Not | Eq | Neq | Lt | Gt | Le | Ge | Abort
                     | GetTxnGasUnitPrice | GetTxnMaxGasUnits | GetGasRemaining
                     | GetTxnSenderAddress | CreateAccount | EmitEvent | GetTxnSequenceNumber
-                    | GetTxnPublicKey => {
+                    | GetTxnPublicKey | LdU8(_) | LdU128(_) | CastU8 | CastU64 | CastU128 => {
                         panic!("Bytecode has no internal index: {:?}", code[bytecode_idx])
                     }
                 };
@@ -312,7 +360,13 @@ fn is_interesting(bytecode: &Bytecode) -> bool {
         | CopyLoc(_)
         | MoveLoc(_)
         | StLoc(_)
-        | BorrowLoc(_) => true,
+        | BorrowLoc(_)
+        | VecPack(_, _)
+        | VecLen(_)
+        | VecBorrow(_)
+        | VecPush(_)
+        | VecPop(_)
+        | VecSwap(_) => true,
 
         // List out the other options explicitly so there's a compile error if a new
         // bytecode gets added.
@@ -320,6 +374,6 @@ fn is_interesting(bytecode: &Bytecode) -> bool {
         | Add | Sub | Mul | Mod | Div | BitOr | BitAnd | Xor | Or | And | Not | Eq | Neq | Lt
         | Gt | Le | Ge | Abort | GetTxnGasUnitPrice | GetTxnMaxGasUnits | GetGasRemaining
         | GetTxnSenderAddress | CreateAccount | EmitEvent | GetTxnSequenceNumber
-        | GetTxnPublicKey => false,
+        | GetTxnPublicKey | LdU8(_) | LdU128(_) | CastU8 | CastU64 | CastU128 => false,
     }
 }
@@ -346,6 +346,21 @@ impl AbstractState {
             });
     }
 
+    /// update self to reflect a borrow of an element of the vector referenced by nonce by
+    /// new_nonce; since the element index is not known statically, the borrow is treated as a
+    /// borrow of the entire vector
+    pub fn borrow_element_from_nonce(&mut self, nonce: Nonce, new_nonce: Nonce) {
+        let mut nonce_set = match self.borrows.remove(&nonce) {
+            Some(BorrowInfo::BorrowedBy(x)) => x,
+            Some(BorrowInfo::FieldsBorrowedBy(y)) => {
+                Self::get_union_of_sets(&BTreeSet::new(), &y)
+            }
+            None => BTreeSet::new(),
+        };
+        nonce_set.insert(new_nonce);
+        self.borrows.insert(nonce, BorrowInfo::BorrowedBy(nonce_set));
+    }
+
     /// update self to reflect a borrow of a value local@idx by new_nonce
     pub fn borrow_from_local_value(&mut self, idx: LocalIndex, new_nonce: Nonce) {
         checked_verify!(self.locals[&idx].is_value());
@@ -125,23 +125,31 @@ pub(crate) fn check_signature_refs(
 }
 
 /// Check that this token is structurally correct.
-/// In particular, check that the token has a reference only at the top level.
+/// In particular, check that the token has a reference only at the top level, which also rules
+/// out vectors of references.
 pub(crate) fn check_structure(token: &SignatureToken) -> Option<VMStaticViolation> {
     use SignatureToken::*;
 
-    let inner_token_opt = match token {
-        Reference(token) => Some(token),
-        MutableReference(token) => Some(token),
-        Bool | U64 | String | ByteArray | Address | Struct(_, _) | TypeParameter(_) => None,
-    };
-    if let Some(inner_token) = inner_token_opt {
-        if inner_token.is_reference() {
-            return Some(VMStaticViolation::InvalidSignatureToken(
-                token.clone(),
-                token.signature_token_kind(),
-                inner_token.signature_token_kind(),
-            ));
+    match token {
+        Reference(inner_token) | MutableReference(inner_token) | Vector(inner_token) => {
+            if inner_token.is_reference() {
+                Some(VMStaticViolation::InvalidSignatureToken(
+                    token.clone(),
+                    token.signature_token_kind(),
+                    inner_token.signature_token_kind(),
+                ))
+            } else {
+                check_structure(inner_token)
+            }
         }
+        Bool
+        | U8
+        | U64
+        | U128
+        | String
+        | ByteArray
+        | Address
+        | Struct(_, _)
+        | TypeParameter(_) => None,
     }
-    None
 }
@@ -76,6 +76,8 @@ impl<'a> StackUsageVerifier<'a> {
             Bytecode::Branch(_) | Bytecode::BorrowField(_) => 0,
 
             Bytecode::LdConst(_)
+            | Bytecode::LdU8(_)
+            | Bytecode::LdU128(_)
             | Bytecode::LdAddr(_)
             | Bytecode::LdStr(_)
             | Bytecode::LdTrue
@@ -138,6 +140,8 @@ impl<'a> StackUsageVerifier<'a> {
 
             Bytecode::Not => 0,
 
+            Bytecode::CastU8 | Bytecode::CastU64 | Bytecode::CastU128 => 0,
+
             Bytecode::FreezeRef => 0,
             Bytecode::Exists(_, _) => 0,
             Bytecode::BorrowGlobal(_, _) => 0,
@@ -155,6 +159,16 @@ impl<'a> StackUsageVerifier<'a> {
             Bytecode::EmitEvent => -3,
 
             Bytecode::LdByteArray(_) => 1,
+
+            Bytecode::VecPack(_, num) => {
+                // A count that does not fit in the stack is caught as a negative stack size
+                let num_elements = (*num).min(i32::max_value() as u64) as i32;
+                1 - num_elements
+            }
+            Bytecode::VecLen(_) | Bytecode::VecPop(_) => 0,
+            Bytecode::VecBorrow(_) => -1,
+            Bytecode::VecPush(_) => -2,
+            Bytecode::VecSwap(_) => -3,
         }
     }
 }
@@ -13,7 +13,10 @@ use std::collections::{BTreeMap, BTreeSet};
 use vm::{
     access::ModuleAccess,
     errors::VMStaticViolation,
-    file_format::{Bytecode, CompiledModule, FunctionDefinition, Kind, LocalIndex, SignatureToken},
+    file_format::{
+        Bytecode, CompiledModule, FunctionDefinition, Kind, LocalIndex, LocalsSignatureIndex,
+        SignatureToken,
+    },
     views::{
         FunctionDefinitionView, FunctionSignatureView, LocalsSignatureView, SignatureTokenView,
         StructDefinitionView, ViewInternals,
@@ -118,6 +121,37 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
         existing_borrows.is_empty()
     }
 
+    /// Returns the element type of a vector instruction, which is the only type in the locals
+    /// signature at `idx`. References cannot be stored in vectors.
+    fn vector_element_type(&self, idx: LocalsSignatureIndex) -> Option<SignatureToken> {
+        let type_actuals = &self.module.locals_signature_at(idx).0;
+        if type_actuals.len() == 1 && !type_actuals[0].is_reference() {
+            Some(type_actuals[0].clone())
+        } else {
+            None
+        }
+    }
+
+    /// Checks that `signature` is a reference to a vector of the element type at `idx`, and
+    /// returns that element type. If `mutable` is true the reference must be mutable.
+    fn vector_reference_element_type(
+        &self,
+        idx: LocalsSignatureIndex,
+        signature: &SignatureToken,
+        mutable: bool,
+    ) -> Option<SignatureToken> {
+        let element_type = self.vector_element_type(idx)?;
+        let inner_signature = match signature {
+            SignatureToken::MutableReference(inner_signature) => inner_signature,
+            SignatureToken::Reference(inner_signature) if !mutable => inner_signature,
+            _ => return None,
+        };
+        match &**inner_signature {
+            SignatureToken::Vector(token) if **token == element_type => Some(element_type),
+            _ => None,
+        }
+    }
+
     fn execute_inner(
         &mut self,
         mut state: &mut AbstractState,
@@ -289,6 +323,22 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
                 Ok(())
             }
 
+            Bytecode::LdU8(_) => {
+                self.stack.push(StackAbstractValue {
+                    signature: SignatureToken::U8,
+                    value: AbstractValue::full_value(Kind::Unrestricted),
+                });
+                Ok(())
+            }
+
+            Bytecode::LdU128(_) => {
+                self.stack.push(StackAbstractValue {
+                    signature: SignatureToken::U128,
+                    value: AbstractValue::full_value(Kind::Unrestricted),
+                });
+                Ok(())
+            }
+
             Bytecode::LdAddr(_) => {
                 self.stack.push(StackAbstractValue {
                     signature: SignatureToken::Address,
@@ -585,11 +635,9 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
             | Bytecode::Xor => {
                 let operand1 = self.stack.pop().unwrap();
                 let operand2 = self.stack.pop().unwrap();
-                if operand1.signature == SignatureToken::U64
-                    && operand2.signature == SignatureToken::U64
-                {
+                if operand1.signature.is_integer() && operand1.signature == operand2.signature {
                     self.stack.push(StackAbstractValue {
-                        signature: SignatureToken::U64,
+                        signature: operand1.signature,
                         value: AbstractValue::full_value(Kind::Unrestricted),
                     });
                     Ok(())
@@ -598,6 +646,23 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
                 }
             }
 
+            Bytecode::CastU8 | Bytecode::CastU64 | Bytecode::CastU128 => {
+                let operand = self.stack.pop().unwrap();
+                if !operand.signature.is_integer() {
+                    return Err(VMStaticViolation::IntegerOpTypeMismatchError(offset));
+                }
+                let signature = match bytecode {
+                    Bytecode::CastU8 => SignatureToken::U8,
+                    Bytecode::CastU64 => SignatureToken::U64,
+                    _ => SignatureToken::U128,
+                };
+                self.stack.push(StackAbstractValue {
+                    signature,
+                    value: AbstractValue::full_value(Kind::Unrestricted),
+                });
+                Ok(())
+            }
+
             Bytecode::Or | Bytecode::And => {
                 let operand1 = self.stack.pop().unwrap();
                 let operand2 = self.stack.pop().unwrap();
@@ -652,9 +717,7 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
             Bytecode::Lt | Bytecode::Gt | Bytecode::Le | Bytecode::Ge => {
                 let operand1 = self.stack.pop().unwrap();
                 let operand2 = self.stack.pop().unwrap();
-                if operand1.signature == SignatureToken::U64
-                    && operand2.signature == SignatureToken::U64
-                {
+                if operand1.signature.is_integer() && operand1.signature == operand2.signature {
                     self.stack.push(StackAbstractValue {
                         signature: SignatureToken::Bool,
                         value: AbstractValue::full_value(Kind::Unrestricted),
@@ -800,6 +863,136 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
                 }
                 Ok(())
             }
+
+            Bytecode::VecPack(idx, num) => {
+                let element_type = match self.vector_element_type(*idx) {
+                    Some(element_type) => element_type,
+                    None => return Err(VMStaticViolation::VecPackTypeMismatchError(offset)),
+                };
+                for _ in 0..*num {
+                    let arg = self.stack.pop().unwrap();
+                    if arg.signature != element_type {
+                        return Err(VMStaticViolation::VecPackTypeMismatchError(offset));
+                    }
+                }
+                let kind = SignatureTokenView::new(self.module, &element_type).kind();
+                self.stack.push(StackAbstractValue {
+                    signature: SignatureToken::Vector(Box::new(element_type)),
+                    value: AbstractValue::full_value(kind),
+                });
+                Ok(())
+            }
+
+            Bytecode::VecLen(idx) => {
+                let operand = self.stack.pop().unwrap();
+                if self
+                    .vector_reference_element_type(*idx, &operand.signature, false)
+                    .is_none()
+                {
+                    return Err(VMStaticViolation::VecTypeMismatchError(offset));
+                }
+                let operand_nonce = operand.value.extract_nonce().unwrap().clone();
+                if operand.signature.is_mutable_reference() {
+                    let borrowed_nonces = state.borrowed_nonces(operand_nonce.clone());
+                    if !self.freeze_ok(&state, borrowed_nonces) {
+                        return Err(VMStaticViolation::VecExistsBorrowError(offset));
+                    }
+                }
+                state.destroy_nonce(operand_nonce);
+                self.stack.push(StackAbstractValue {
+                    signature: SignatureToken::U64,
+                    value: AbstractValue::full_value(Kind::Unrestricted),
+                });
+                Ok(())
+            }
+
+            Bytecode::VecBorrow(idx) => {
+                let index_operand = self.stack.pop().unwrap();
+                let vec_operand = self.stack.pop().unwrap();
+                let element_type =
+                    match self.vector_reference_element_type(*idx, &vec_operand.signature, false) {
+                        Some(element_type) => element_type,
+                        None => return Err(VMStaticViolation::VecTypeMismatchError(offset)),
+                    };
+                if index_operand.signature != SignatureToken::U64 {
+                    return Err(VMStaticViolation::VecTypeMismatchError(offset));
+                }
+                let operand_nonce = vec_operand.value.extract_nonce().unwrap().clone();
+                let signature = if vec_operand.signature.is_mutable_reference() {
+                    let borrowed_nonces = state.borrowed_nonces(operand_nonce.clone());
+                    if !Self::write_borrow_ok(borrowed_nonces) {
+                        return Err(VMStaticViolation::VecExistsBorrowError(offset));
+                    }
+                    SignatureToken::MutableReference(Box::new(element_type))
+                } else {
+                    SignatureToken::Reference(Box::new(element_type))
+                };
+                let nonce = self.get_nonce(&mut state);
+                state.borrow_element_from_nonce(operand_nonce.clone(), nonce.clone());
+                state.destroy_nonce(operand_nonce);
+                self.stack.push(StackAbstractValue {
+                    signature,
+                    value: AbstractValue::Reference(nonce),
+                });
+                Ok(())
+            }
+
+            Bytecode::VecPush(idx) => {
+                let val_operand = self.stack.pop().unwrap();
+                let vec_operand = self.stack.pop().unwrap();
+                let element_type =
+                    self.vector_reference_element_type(*idx, &vec_operand.signature, true);
+                if element_type != Some(val_operand.signature) {
+                    Err(VMStaticViolation::VecTypeMismatchError(offset))
+                } else if state.is_full(&vec_operand.value) {
+                    let operand_nonce = vec_operand.value.extract_nonce().unwrap().clone();
+                    state.destroy_nonce(operand_nonce);
+                    Ok(())
+                } else {
+                    Err(VMStaticViolation::VecExistsBorrowError(offset))
+                }
+            }
+
+            Bytecode::VecPop(idx) => {
+                let vec_operand = self.stack.pop().unwrap();
+                match self.vector_reference_element_type(*idx, &vec_operand.signature, true) {
+                    Some(element_type) => {
+                        if state.is_full(&vec_operand.value) {
+                            let operand_nonce = vec_operand.value.extract_nonce().unwrap().clone();
+                            state.destroy_nonce(operand_nonce);
+                            let kind = SignatureTokenView::new(self.module, &element_type).kind();
+                            self.stack.push(StackAbstractValue {
+                                signature: element_type,
+                                value: AbstractValue::full_value(kind),
+                            });
+                            Ok(())
+                        } else {
+                            Err(VMStaticViolation::VecExistsBorrowError(offset))
+                        }
+                    }
+                    None => Err(VMStaticViolation::VecTypeMismatchError(offset)),
+                }
+            }
+
+            Bytecode::VecSwap(idx) => {
+                let index_operand1 = self.stack.pop().unwrap();
+                let index_operand2 = self.stack.pop().unwrap();
+                let vec_operand = self.stack.pop().unwrap();
+                if index_operand1.signature != SignatureToken::U64
+                    || index_operand2.signature != SignatureToken::U64
+                    || self
+                        .vector_reference_element_type(*idx, &vec_operand.signature, true)
+                        .is_none()
+                {
+                    Err(VMStaticViolation::VecTypeMismatchError(offset))
+                } else if state.is_full(&vec_operand.value) {
+                    let operand_nonce = vec_operand.value.extract_nonce().unwrap().clone();
+                    state.destroy_nonce(operand_nonce);
+                    Ok(())
+                } else {
+                    Err(VMStaticViolation::VecExistsBorrowError(offset))
+                }
+            }
         }
     }
 }
@@ -19,6 +19,10 @@ fn test_sig_token_structure() {
     assert_eq!(check_structure(&ref_token), None);
     let mut_ref_token = SignatureToken::MutableReference(Box::new(struct_token.clone()));
     assert_eq!(check_structure(&mut_ref_token), None);
+    let vector_token = SignatureToken::Vector(Box::new(struct_token.clone()));
+    assert_eq!(check_structure(&vector_token), None);
+    let ref_vector_token = SignatureToken::Reference(Box::new(vector_token.clone()));
+    assert_eq!(check_structure(&ref_vector_token), None);
 
     // Invalid cases.
     let ref_ref_token = SignatureToken::Reference(Box::new(ref_token.clone()));
@@ -39,4 +43,22 @@ fn test_sig_token_structure() {
             SignatureTokenKind::MutableReference,
         ))
     );
+    let vector_ref_token = SignatureToken::Vector(Box::new(ref_token.clone()));
+    assert_eq!(
+        check_structure(&vector_ref_token),
+        Some(VMStaticViolation::InvalidSignatureToken(
+            vector_ref_token.clone(),
+            SignatureTokenKind::Value,
+            SignatureTokenKind::Reference,
+        ))
+    );
+    let ref_vector_ref_token = SignatureToken::Reference(Box::new(vector_ref_token.clone()));
+    assert_eq!(
+        check_structure(&ref_vector_ref_token),
+        Some(VMStaticViolation::InvalidSignatureToken(
+            vector_ref_token.clone(),
+            SignatureTokenKind::Value,
+            SignatureTokenKind::Reference,
+        ))
+    );
 }
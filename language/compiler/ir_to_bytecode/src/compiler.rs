@@ -79,11 +79,14 @@ enum InferredType {
 
     // Signature tokens
     Bool,
+    U8,
     U64,
+    U128,
     String,
     ByteArray,
     Address,
     Struct(StructHandleIndex),
+    Vector(Box<InferredType>),
     Reference(Box<InferredType>),
     MutableReference(Box<InferredType>),
 }
@@ -94,11 +97,17 @@ impl InferredType {
         use SignatureToken as S;
         match sig_token {
             S::Bool => I::Bool,
+            S::U8 => I::U8,
             S::U64 => I::U64,
+            S::U128 => I::U128,
             S::String => I::String,
             S::ByteArray => I::ByteArray,
             S::Address => I::Address,
             S::Struct(si, _) => I::Struct(*si),
+            S::Vector(s_inner) => {
+                let i_inner = Self::from_signature_token(&*s_inner);
+                I::Vector(Box::new(i_inner))
+            }
             S::Reference(s_inner) => {
                 let i_inner = Self::from_signature_token(&*s_inner);
                 I::Reference(Box::new(i_inner))
@@ -117,10 +126,13 @@ impl InferredType {
         match self {
             InferredType::Anything => bail!("could not infer struct type"),
             InferredType::Bool => bail!("no struct type for Bool"),
+            InferredType::U8 => bail!("no struct type for U8"),
             InferredType::U64 => bail!("no struct type for U64"),
+            InferredType::U128 => bail!("no struct type for U128"),
             InferredType::String => bail!("no struct type for String"),
             InferredType::ByteArray => bail!("no struct type for ByteArray"),
             InferredType::Address => bail!("no struct type for Address"),
+            InferredType::Vector(_) => bail!("no struct type for Vector"),
             InferredType::Reference(inner) | InferredType::MutableReference(inner) => {
                 inner.get_struct_handle()
             }
@@ -1241,7 +1253,9 @@ impl<S: Scope + Sized> Compiler<S> {
     ) -> Result<SignatureToken> {
         match sig_token {
             SignatureToken::Bool
+            | SignatureToken::U8
             | SignatureToken::U64
+            | SignatureToken::U128
             | SignatureToken::String
             | SignatureToken::ByteArray
             | SignatureToken::Address
@@ -1270,6 +1284,9 @@ impl<S: Scope + Sized> Compiler<S> {
                 )?;
                 Ok(SignatureToken::Struct(local_sh_idx, vec![]))
             }
+            SignatureToken::Vector(sub_sig_token) => Ok(SignatureToken::Vector(Box::new(
+                self.import_signature_token(module_name, *sub_sig_token)?,
+            ))),
             SignatureToken::Reference(sub_sig_token) => Ok(SignatureToken::Reference(Box::new(
                 self.import_signature_token(module_name, *sub_sig_token)?,
             ))),
@@ -1510,6 +1527,17 @@ impl<S: Scope + Sized> Compiler<S> {
         Ok(idx)
     }
 
+    // Vector bytecodes carry their element type as a single element locals signature
+    fn make_vector_element_type(
+        &mut self,
+        elem_type: &Type,
+    ) -> Result<(LocalsSignatureIndex, Box<InferredType>)> {
+        let elem_token = self.build_signature_token(elem_type)?;
+        let inferred = Box::new(InferredType::from_signature_token(&elem_token));
+        let types_idx = self.make_locals_signature(&LocalsSignature(vec![elem_token]))?;
+        Ok((types_idx, inferred))
+    }
+
     fn make_module_handle(
         &mut self,
         addr_idx: AddressPoolIndex,
@@ -1676,9 +1704,14 @@ impl<S: Scope + Sized> Compiler<S> {
     fn build_signature_token(&mut self, t: &Type) -> Result<SignatureToken> {
         match t {
             Type::Address => Ok(SignatureToken::Address),
+            Type::U8 => Ok(SignatureToken::U8),
             Type::U64 => Ok(SignatureToken::U64),
+            Type::U128 => Ok(SignatureToken::U128),
             Type::Bool => Ok(SignatureToken::Bool),
             Type::ByteArray => Ok(SignatureToken::ByteArray),
+            Type::Vector(inner_type) => Ok(SignatureToken::Vector(Box::new(
+                self.build_signature_token(inner_type)?,
+            ))),
             Type::Reference(is_mutable, inner_type) => {
                 let inner_token = Box::new(self.build_signature_token(inner_type)?);
                 if *is_mutable {
@@ -2013,11 +2046,21 @@ impl<S: Scope + Sized> Compiler<S> {
                     function_frame.push()?;
                     Ok(self.make_singleton_vec_deque(InferredType::Address))
                 }
+                CopyableVal::U8(i) => {
+                    code.code.push(Bytecode::LdU8(*i));
+                    function_frame.push()?;
+                    Ok(self.make_singleton_vec_deque(InferredType::U8))
+                }
                 CopyableVal::U64(i) => {
                     code.code.push(Bytecode::LdConst(*i));
                     function_frame.push()?;
                     Ok(self.make_singleton_vec_deque(InferredType::U64))
                 }
+                CopyableVal::U128(i) => {
+                    code.code.push(Bytecode::LdU128(*i));
+                    function_frame.push()?;
+                    Ok(self.make_singleton_vec_deque(InferredType::U128))
+                }
                 CopyableVal::ByteArray(buf) => {
                     let buf_idx = self.make_byte_array(buf)?;
                     code.code.push(Bytecode::LdByteArray(buf_idx));
@@ -2061,41 +2104,49 @@ impl<S: Scope + Sized> Compiler<S> {
                 }
             }
             Exp::BinopExp(e1, op, e2) => {
-                self.compile_expression(e1, code, function_frame)?;
+                let lhs_type = self
+                    .compile_expression(e1, code, function_frame)?
+                    .pop_front();
                 self.compile_expression(e2, code, function_frame)?;
                 function_frame.pop()?;
+                // Arithmetic operators produce a value of the same integer type as their operands
+                let int_type = match lhs_type {
+                    Some(InferredType::U8) => InferredType::U8,
+                    Some(InferredType::U128) => InferredType::U128,
+                    _ => InferredType::U64,
+                };
                 match op {
                     BinOp::Add => {
                         code.code.push(Bytecode::Add);
-                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                        Ok(self.make_singleton_vec_deque(int_type))
                     }
                     BinOp::Sub => {
                         code.code.push(Bytecode::Sub);
-                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                        Ok(self.make_singleton_vec_deque(int_type))
                     }
                     BinOp::Mul => {
                         code.code.push(Bytecode::Mul);
-                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                        Ok(self.make_singleton_vec_deque(int_type))
                     }
                     BinOp::Mod => {
                         code.code.push(Bytecode::Mod);
-                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                        Ok(self.make_singleton_vec_deque(int_type))
                     }
                     BinOp::Div => {
                         code.code.push(Bytecode::Div);
-                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                        Ok(self.make_singleton_vec_deque(int_type))
                     }
                     BinOp::BitOr => {
                         code.code.push(Bytecode::BitOr);
-                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                        Ok(self.make_singleton_vec_deque(int_type))
                     }
                     BinOp::BitAnd => {
                         code.code.push(Bytecode::BitAnd);
-                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                        Ok(self.make_singleton_vec_deque(int_type))
                     }
                     BinOp::Xor => {
                         code.code.push(Bytecode::Xor);
-                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                        Ok(self.make_singleton_vec_deque(int_type))
                     }
                     BinOp::Or => {
                         code.code.push(Bytecode::Or);
@@ -2378,6 +2429,80 @@ impl<S: Scope + Sized> Compiler<S> {
                         };
                         Ok(self.make_singleton_vec_deque(InferredType::Reference(inner_token)))
                     }
+                    Builtin::VecPack(elem_type, num) => {
+                        let (types_idx, elem_token) = self.make_vector_element_type(elem_type)?;
+                        code.code.push(Bytecode::VecPack(types_idx, *num));
+                        for _ in 0..*num {
+                            function_frame.pop()?;
+                        }
+                        function_frame.push()?;
+                        Ok(self.make_singleton_vec_deque(InferredType::Vector(elem_token)))
+                    }
+                    Builtin::VecLen(elem_type) => {
+                        let (types_idx, _) = self.make_vector_element_type(elem_type)?;
+                        code.code.push(Bytecode::VecLen(types_idx));
+                        function_frame.pop()?; // pop vector ref
+                        function_frame.push()?; // push length
+                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                    }
+                    Builtin::VecBorrow(elem_type) => {
+                        let (types_idx, elem_token) = self.make_vector_element_type(elem_type)?;
+                        code.code.push(Bytecode::VecBorrow(types_idx));
+                        function_frame.pop()?; // pop index
+                        function_frame.pop()?; // pop vector ref
+                        function_frame.push()?; // push element ref
+                        // The element reference has the same mutability as the vector reference
+                        match argument_types.pop_front() {
+                            Some(InferredType::MutableReference(_)) => Ok(
+                                self.make_singleton_vec_deque(InferredType::MutableReference(
+                                    elem_token,
+                                )),
+                            ),
+                            _ => Ok(self.make_singleton_vec_deque(InferredType::Reference(
+                                elem_token,
+                            ))),
+                        }
+                    }
+                    Builtin::VecPush(elem_type) => {
+                        let (types_idx, _) = self.make_vector_element_type(elem_type)?;
+                        code.code.push(Bytecode::VecPush(types_idx));
+                        function_frame.pop()?; // pop value
+                        function_frame.pop()?; // pop vector ref
+                        Ok(VecDeque::new())
+                    }
+                    Builtin::VecPop(elem_type) => {
+                        let (types_idx, elem_token) = self.make_vector_element_type(elem_type)?;
+                        code.code.push(Bytecode::VecPop(types_idx));
+                        function_frame.pop()?; // pop vector ref
+                        function_frame.push()?; // push element
+                        Ok(self.make_singleton_vec_deque(*elem_token))
+                    }
+                    Builtin::VecSwap(elem_type) => {
+                        let (types_idx, _) = self.make_vector_element_type(elem_type)?;
+                        code.code.push(Bytecode::VecSwap(types_idx));
+                        function_frame.pop()?; // pop second index
+                        function_frame.pop()?; // pop first index
+                        function_frame.pop()?; // pop vector ref
+                        Ok(VecDeque::new())
+                    }
+                    Builtin::ToU8 => {
+                        code.code.push(Bytecode::CastU8);
+                        function_frame.pop()?;
+                        function_frame.push()?;
+                        Ok(self.make_singleton_vec_deque(InferredType::U8))
+                    }
+                    Builtin::ToU64 => {
+                        code.code.push(Bytecode::CastU64);
+                        function_frame.pop()?;
+                        function_frame.push()?;
+                        Ok(self.make_singleton_vec_deque(InferredType::U64))
+                    }
+                    Builtin::ToU128 => {
+                        code.code.push(Bytecode::CastU128);
+                        function_frame.pop()?;
+                        function_frame.push()?;
+                        Ok(self.make_singleton_vec_deque(InferredType::U128))
+                    }
                     _ => bail!("unsupported builtin function: {}", function),
                 }
             }
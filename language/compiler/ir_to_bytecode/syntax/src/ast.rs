@@ -215,8 +215,12 @@ pub struct QualifiedStructIdent {
 pub enum Type {
     /// `address`
     Address,
+    /// `u8`
+    U8,
     /// `u64`
     U64,
+    /// `u128`
+    U128,
     /// `bool`
     Bool,
     /// `bytearray`
@@ -225,6 +229,8 @@ pub enum Type {
     String,
     /// A module defined struct
     Struct(QualifiedStructIdent),
+    /// `vector<t>`, a variable sized collection of values of type `t`
+    Vector(Box<Type>),
     /// A reference type, the bool flag indicates whether the reference is mutable
     Reference(bool, Box<Type>),
 }
@@ -277,6 +283,28 @@ pub enum Builtin {
 
     /// Convert a mutable reference into an immutable one
     Freeze,
+
+    /// Vectors,
+    /// Create a vector of the given element type from the given number of values
+    VecPack(Type, u64),
+    /// Get the length of a vector
+    VecLen(Type),
+    /// Borrow an element of a vector, the reference has the mutability of the vector reference
+    VecBorrow(Type),
+    /// Append a value to the end of a vector
+    VecPush(Type),
+    /// Remove and return the last value of a vector
+    VecPop(Type),
+    /// Swap two elements of a vector
+    VecSwap(Type),
+
+    /// Integer casts,
+    /// Cast an integer into a `u8`, aborting if the value does not fit
+    ToU8,
+    /// Cast an integer into a `u64`, aborting if the value does not fit
+    ToU64,
+    /// Cast an integer into a `u128`
+    ToU128,
 }
 
 /// Enum for different function calls
@@ -376,8 +404,12 @@ pub struct Block {
 pub enum CopyableVal {
     /// An address in the global storage
     Address(AccountAddress),
+    /// An unsigned 8-bit integer
+    U8(u8),
     /// An unsigned 64-bit integer
     U64(u64),
+    /// An unsigned 128-bit integer
+    U128(u128),
     /// true or false
     Bool(bool),
     /// `b"<bytes>"`
@@ -619,11 +651,21 @@ impl Type {
         Type::Address
     }
 
+    /// Creates a new u8 type
+    pub fn u8() -> Type {
+        Type::U8
+    }
+
     /// Creates a new u64 type
     pub fn u64() -> Type {
         Type::U64
     }
 
+    /// Creates a new u128 type
+    pub fn u128() -> Type {
+        Type::U128
+    }
+
     /// Creates a new bool type
     pub fn bool() -> Type {
         Type::Bool
@@ -1109,12 +1151,15 @@ impl fmt::Display for QualifiedStructIdent {
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Type::U8 => write!(f, "u8"),
             Type::U64 => write!(f, "u64"),
+            Type::U128 => write!(f, "u128"),
             Type::Bool => write!(f, "bool"),
             Type::Address => write!(f, "address"),
             Type::ByteArray => write!(f, "bytearray"),
             Type::String => write!(f, "string"),
             Type::Struct(ident) => write!(f, "{}", ident),
+            Type::Vector(t) => write!(f, "vector<{}>", t),
             Type::Reference(is_mutable, t) => {
                 write!(f, "&{}{}", if *is_mutable { "mut " } else { "" }, t)
             }
@@ -1146,6 +1191,15 @@ impl fmt::Display for Builtin {
             Builtin::MoveFrom(t) => write!(f, "move_from<{}>", t),
             Builtin::MoveToSender(t) => write!(f, "move_to_sender<{}>", t),
             Builtin::Freeze => write!(f, "freeze"),
+            Builtin::VecPack(t, n) => write!(f, "vec_pack<{}, {}>", t, n),
+            Builtin::VecLen(t) => write!(f, "vec_len<{}>", t),
+            Builtin::VecBorrow(t) => write!(f, "vec_borrow<{}>", t),
+            Builtin::VecPush(t) => write!(f, "vec_push<{}>", t),
+            Builtin::VecPop(t) => write!(f, "vec_pop<{}>", t),
+            Builtin::VecSwap(t) => write!(f, "vec_swap<{}>", t),
+            Builtin::ToU8 => write!(f, "to_u8"),
+            Builtin::ToU64 => write!(f, "to_u64"),
+            Builtin::ToU128 => write!(f, "to_u128"),
         }
     }
 }
@@ -1254,7 +1308,9 @@ impl fmt::Display for Block {
 impl fmt::Display for CopyableVal {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            CopyableVal::U8(v) => write!(f, "{}u8", v),
             CopyableVal::U64(v) => write!(f, "{}", v),
+            CopyableVal::U128(v) => write!(f, "{}u128", v),
             CopyableVal::Bool(v) => write!(f, "{}", v),
             CopyableVal::ByteArray(v) => write!(f, "{}", v),
             CopyableVal::Address(v) => write!(f, "0x{}", hex::encode(&v)),
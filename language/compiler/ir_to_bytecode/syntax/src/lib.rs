@@ -19,7 +19,9 @@
 //!
 //! g ∈ GroundType ::=
 //!   | bool
+//!   | u8        // unsigned 8 bit integer
 //!   | u64       // unsigned 64 bit integer
+//!   | u128      // unsigned 128 bit integer
 //!   | address   // 32 byte account address
 //!   | bytearray // immutable, arbitrarily sized array of bytes
 //!
@@ -28,9 +30,11 @@
 //!   | Self      // current module
 //!
 //! t ∈ BaseType ::=
-//!   | g     // ground type
-//!   | k#d.n // struct 'n' declared in the module referenced by 'd' with kind 'k'
-//!           // the kind 'k' cannot differ from the declared kind
+//!   | g         // ground type
+//!   | k#d.n     // struct 'n' declared in the module referenced by 'd' with kind 'k'
+//!               // the kind 'k' cannot differ from the declared kind
+//!   | vector<t> // variable sized collection of values of type 't'
+//!               // a vector has the kind of its elements
 //!
 //! 𝛕 ∈ Type ::=
 //!   | t      // base type
@@ -46,12 +50,16 @@
 //! ## Values
 //! ```text
 //! u ∈ Unsigned64        // Unsigned, 64-bit Integer
+//! u8 ∈ Unsigned8        // Unsigned, 8-bit Integer
+//! u128 ∈ Unsigned128    // Unsigned, 128-bit Integer
 //! addr ∈ AccountAddress // addresses of blockchain accounts
 //! bytes ∈ ByteArray     // byte array of arbitrary length
 //! v ∈ Value ::=
 //!   | true
 //!   | false
 //!   | u        // u64 literal
+//!   | u8u8     // u8 literal, e.g. 255u8
+//!   | u128u128 // u128 literal, e.g. 42u128
 //!   | 0xaddr   // 32 byte address literal
 //!   | b"bytes" // arbitrary length bytearray literal
 //! ```
@@ -82,7 +90,8 @@
 //!   | !e_1
 //!   | e_1 || e_2
 //!   | e_1 && e_2
-//!   // u64 operators
+//!   // integer operators, both operands must have the same integer type
+//!   // arithmetic operators fail on overflow
 //!   | e_1 >= e_2
 //!   | e_1 <= e_2
 //!   | e_1 > e_2
@@ -136,6 +145,23 @@
 //!                               // gives the sequence number for this transaction
 //!   | get_gas_remaining()       // type: 'unit -> u64'
 //!                               // gives the amount of gas gas units remaining before the transaction execution will be forced to halt execution
+//!   | vec_pack<t, u>(e_1, ..., e_u) // type: 't * ... * t -> vector<t>'
+//!                                   // creates a vector from the 'u' given values
+//!   | vec_len<t>(e)             // type: '&vector<t> -> u64' or '&mut vector<t> -> u64'
+//!                               // gives the number of elements in the vector
+//!   | vec_borrow<t>(e_1, e_2)   // type: '&vector<t> * u64 -> &t' or '&mut vector<t> * u64 -> &mut t'
+//!                               // borrows the element at index 'e_2', fails if the index is out of bounds
+//!   | vec_push<t>(e_1, e_2)     // type: '&mut vector<t> * t -> unit'
+//!                               // appends a value to the end of the vector
+//!   | vec_pop<t>(e)             // type: '&mut vector<t> -> t'
+//!                               // removes the last value of the vector, fails if the vector is empty
+//!   | vec_swap<t>(e_1, e_2, e_3) // type: '&mut vector<t> * u64 * u64 -> unit'
+//!                               // swaps the elements at indices 'e_2' and 'e_3', fails if either is out of bounds
+//!   | to_u8(e)                  // type: 'u8 -> u8', 'u64 -> u8' or 'u128 -> u8'
+//!                               // fails if the value does not fit in a u8
+//!   | to_u64(e)                 // type: 'u8 -> u64', 'u64 -> u64' or 'u128 -> u64'
+//!                               // fails if the value does not fit in a u64
+//!   | to_u128(e)                // type: 'u8 -> u128', 'u64 -> u128' or 'u128 -> u128'
 //!
 //! call ∈ Call ::=
 //!   | mop
@@ -531,7 +531,10 @@ impl<'a> BoogieTranslator<'a> {
             SignatureToken::Reference(t) | SignatureToken::MutableReference(t) => {
                 format!("{}_ref", self.format_type(&*t))
             }
-            SignatureToken::TypeParameter(_) => "unsupported".into(),
+            SignatureToken::U8
+            | SignatureToken::U128
+            | SignatureToken::Vector(_)
+            | SignatureToken::TypeParameter(_) => "unsupported".into(),
         }
     }
 
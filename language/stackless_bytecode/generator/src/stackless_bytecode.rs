@@ -1,6 +1,6 @@
 use vm::file_format::{
     AddressPoolIndex, ByteArrayPoolIndex, CodeOffset, FieldDefinitionIndex, FunctionHandleIndex,
-    LocalIndex, StringPoolIndex, StructDefinitionIndex,
+    LocalIndex, LocalsSignatureIndex, StringPoolIndex, StructDefinitionIndex,
 };
 
 type TempIndex = usize;
@@ -30,6 +30,14 @@ pub enum StacklessBytecode {
     BorrowGlobal(TempIndex, TempIndex, StructDefinitionIndex), /* t1 = borrow_global<struct_index>(t2) */
     Exists(TempIndex, TempIndex, StructDefinitionIndex),       // t1 = exists<struct_index>(t2)
 
+    VecPack(TempIndex, LocalsSignatureIndex, Vec<TempIndex>), /* t1 = vector with t2_vec as
+                                                               * elements */
+    VecLen(TempIndex, TempIndex, LocalsSignatureIndex), // t1 = len(t2)
+    VecBorrow(TempIndex, TempIndex, TempIndex, LocalsSignatureIndex), // t1 = &t2[t3]
+    VecPush(TempIndex, TempIndex, LocalsSignatureIndex), // push(t1, t2)
+    VecPop(TempIndex, TempIndex, LocalsSignatureIndex),  // t1 = pop(t2)
+    VecSwap(TempIndex, TempIndex, TempIndex, LocalsSignatureIndex), // swap(t1, t2, t3)
+
     GetGasRemaining(TempIndex),
     GetTxnSequenceNumber(TempIndex),
     GetTxnPublicKey(TempIndex),
@@ -40,11 +48,17 @@ pub enum StacklessBytecode {
 
     LdTrue(TempIndex),
     LdFalse(TempIndex),
+    LdU8(TempIndex, u8),
     LdConst(TempIndex, u64),
+    LdU128(TempIndex, u128),
     LdAddr(TempIndex, AddressPoolIndex),
     LdByteArray(TempIndex, ByteArrayPoolIndex),
     LdStr(TempIndex, StringPoolIndex),
 
+    CastU8(TempIndex, TempIndex),   // t1 = (u8) t2
+    CastU64(TempIndex, TempIndex),  // t1 = (u64) t2
+    CastU128(TempIndex, TempIndex), // t1 = (u128) t2
+
     Not(TempIndex, TempIndex),            // t1 = !t2
     Add(TempIndex, TempIndex, TempIndex), // t1 = t2 binop t3
     Sub(TempIndex, TempIndex, TempIndex),
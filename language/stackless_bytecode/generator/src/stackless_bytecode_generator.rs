@@ -2,7 +2,8 @@ use crate::stackless_bytecode::StacklessBytecode;
 use vm::{
     access::ModuleAccess,
     file_format::{
-        Bytecode, CompiledModule, FieldDefinitionIndex, FunctionDefinition, SignatureToken,
+        Bytecode, CompiledModule, FieldDefinitionIndex, FunctionDefinition,
+        LocalsSignatureIndex, SignatureToken,
     },
     views::{
         FieldDefinitionView, FunctionDefinitionView, FunctionSignatureView, LocalsSignatureView,
@@ -87,6 +88,10 @@ impl<'a> StacklessBytecodeGenerator<'a> {
             .clone()
     }
 
+    fn get_vector_element_signature(&self, types_idx: LocalsSignatureIndex) -> SignatureToken {
+        self.module.locals_signature_at(types_idx).0[0].clone()
+    }
+
     #[allow(clippy::cognitive_complexity)]
     pub fn generate_bytecode(&mut self, bytecode: &Bytecode) {
         match bytecode {
@@ -174,6 +179,48 @@ impl<'a> StacklessBytecodeGenerator<'a> {
                 }
             }
 
+            Bytecode::LdU8(number) => {
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                self.local_types.push(SignatureToken::U8);
+                self.code.push(StacklessBytecode::LdU8(temp_index, *number));
+                self.temp_count += 1;
+            }
+
+            Bytecode::LdU128(number) => {
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                self.local_types.push(SignatureToken::U128);
+                self.code
+                    .push(StacklessBytecode::LdU128(temp_index, *number));
+                self.temp_count += 1;
+            }
+
+            Bytecode::CastU8 | Bytecode::CastU64 | Bytecode::CastU128 => {
+                let operand_index = self.temp_stack.pop().unwrap();
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                self.temp_count += 1;
+                match bytecode {
+                    Bytecode::CastU8 => {
+                        self.local_types.push(SignatureToken::U8);
+                        self.code
+                            .push(StacklessBytecode::CastU8(temp_index, operand_index));
+                    }
+                    Bytecode::CastU64 => {
+                        self.local_types.push(SignatureToken::U64);
+                        self.code
+                            .push(StacklessBytecode::CastU64(temp_index, operand_index));
+                    }
+                    Bytecode::CastU128 => {
+                        self.local_types.push(SignatureToken::U128);
+                        self.code
+                            .push(StacklessBytecode::CastU128(temp_index, operand_index));
+                    }
+                    _ => {}
+                }
+            }
+
             Bytecode::LdConst(number) => {
                 let temp_index = self.temp_count;
                 self.temp_stack.push(temp_index);
@@ -368,8 +415,9 @@ impl<'a> StacklessBytecodeGenerator<'a> {
             | Bytecode::Xor => {
                 let operand2_index = self.temp_stack.pop().unwrap();
                 let operand1_index = self.temp_stack.pop().unwrap();
+                let operand_type = self.local_types[operand1_index].clone();
                 let temp_index = self.temp_count;
-                self.local_types.push(SignatureToken::U64);
+                self.local_types.push(operand_type);
                 self.temp_stack.push(temp_index);
                 self.temp_count += 1;
                 match bytecode {
@@ -631,6 +679,91 @@ impl<'a> StacklessBytecodeGenerator<'a> {
                     .push(StacklessBytecode::GetTxnPublicKey(temp_index));
                 self.temp_count += 1;
             }
+            Bytecode::VecPack(types_idx, num) => {
+                let element_signature = self.get_vector_element_signature(*types_idx);
+                let mut element_temps = vec![];
+                for _ in 0..*num {
+                    element_temps.push(self.temp_stack.pop().unwrap());
+                }
+                element_temps.reverse();
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                self.local_types
+                    .push(SignatureToken::Vector(Box::new(element_signature)));
+                self.code.push(StacklessBytecode::VecPack(
+                    temp_index,
+                    *types_idx,
+                    element_temps,
+                ));
+                self.temp_count += 1;
+            }
+            Bytecode::VecLen(types_idx) => {
+                let vector_ref_index = self.temp_stack.pop().unwrap();
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                self.local_types.push(SignatureToken::U64);
+                self.code.push(StacklessBytecode::VecLen(
+                    temp_index,
+                    vector_ref_index,
+                    *types_idx,
+                ));
+                self.temp_count += 1;
+            }
+            Bytecode::VecBorrow(types_idx) => {
+                let index_operand = self.temp_stack.pop().unwrap();
+                let vector_ref_index = self.temp_stack.pop().unwrap();
+                let vector_ref_sig = self.local_types[vector_ref_index].clone();
+                let element_signature = self.get_vector_element_signature(*types_idx);
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                if vector_ref_sig.is_mutable_reference() {
+                    self.local_types
+                        .push(SignatureToken::MutableReference(Box::new(element_signature)));
+                } else {
+                    self.local_types
+                        .push(SignatureToken::Reference(Box::new(element_signature)));
+                }
+                self.code.push(StacklessBytecode::VecBorrow(
+                    temp_index,
+                    vector_ref_index,
+                    index_operand,
+                    *types_idx,
+                ));
+                self.temp_count += 1;
+            }
+            Bytecode::VecPush(types_idx) => {
+                let value_operand = self.temp_stack.pop().unwrap();
+                let vector_ref_index = self.temp_stack.pop().unwrap();
+                self.code.push(StacklessBytecode::VecPush(
+                    vector_ref_index,
+                    value_operand,
+                    *types_idx,
+                ));
+            }
+            Bytecode::VecPop(types_idx) => {
+                let vector_ref_index = self.temp_stack.pop().unwrap();
+                let element_signature = self.get_vector_element_signature(*types_idx);
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                self.local_types.push(element_signature);
+                self.code.push(StacklessBytecode::VecPop(
+                    temp_index,
+                    vector_ref_index,
+                    *types_idx,
+                ));
+                self.temp_count += 1;
+            }
+            Bytecode::VecSwap(types_idx) => {
+                let index2_operand = self.temp_stack.pop().unwrap();
+                let index1_operand = self.temp_stack.pop().unwrap();
+                let vector_ref_index = self.temp_stack.pop().unwrap();
+                self.code.push(StacklessBytecode::VecSwap(
+                    vector_ref_index,
+                    index1_operand,
+                    index2_operand,
+                    *types_idx,
+                ));
+            }
             Bytecode::CreateAccount => {
                 let temp_index = self.temp_stack.pop().unwrap();
                 self.code.push(StacklessBytecode::CreateAccount(temp_index));
@@ -71,6 +71,8 @@ fn output_to_csv(path: &Path, data: HashMap<String, Vec<u64>>) {
 // The only instruction that we don't implement here is `EmitEvent`. This is on purpose -- the emit
 // event instruction will be changing soon, so it's not worth implementing at the moment until we
 // have decided the semantics of the instruction.
+// The vector instructions are not synthesized yet either, their costs in the gas schedule are
+// estimates for now.
 fn stack_instructions(options: &Opt) {
     use Bytecode::*;
     let stack_opcodes: Vec<Bytecode> = vec![
@@ -112,7 +114,12 @@ fn stack_instructions(options: &Opt) {
         Abort,
         LdFalse,
         LdTrue,
+        LdU8(0),
         LdConst(0),
+        LdU128(0),
+        CastU8,
+        CastU64,
+        CastU128,
         LdStr(StringPoolIndex::new(0)),
         LdByteArray(ByteArrayPoolIndex::new(0)),
         LdAddr(AddressPoolIndex::new(0)),
@@ -68,7 +68,9 @@ impl SignatureTy {
 lazy_static! {
     static ref BASE_SIG_TOKENS: Vec<SignatureToken> = vec![
         SignatureToken::Bool,
+        SignatureToken::U8,
         SignatureToken::U64,
+        SignatureToken::U128,
         SignatureToken::String,
         SignatureToken::ByteArray,
         SignatureToken::Address,
@@ -118,12 +120,24 @@ fn bools(num: u64) -> Vec<SignatureTy> {
         .collect()
 }
 
+fn u8s(num: u64) -> Vec<SignatureTy> {
+    (0..num)
+        .map(|_| ty_of_sig_tok(SignatureToken::U8))
+        .collect()
+}
+
 fn u64s(num: u64) -> Vec<SignatureTy> {
     (0..num)
         .map(|_| ty_of_sig_tok(SignatureToken::U64))
         .collect()
 }
 
+fn u128s(num: u64) -> Vec<SignatureTy> {
+    (0..num)
+        .map(|_| ty_of_sig_tok(SignatureToken::U128))
+        .collect()
+}
+
 fn simple_addrs(num: u64) -> Vec<SignatureTy> {
     (0..num)
         .map(|_| ty_of_sig_tok(SignatureToken::Address))
@@ -203,7 +217,21 @@ pub fn call_details(op: &Bytecode) -> Vec<CallDetails> {
             ref_values(1) => empty(),
             ref_resources(1) => empty()
         },
+        Bytecode::LdU8(_) => type_transition! { empty() => u8s(1) },
         Bytecode::LdConst(_) => type_transition! { empty() => u64s(1) },
+        Bytecode::LdU128(_) => type_transition! { empty() => u128s(1) },
+        // Only the casts that cannot overflow are generated, so that the cost of a successful
+        // cast is measured.
+        Bytecode::CastU8 => type_transition! { u8s(1) => u8s(1) },
+        Bytecode::CastU64 => type_transition! {
+            u8s(1) => u64s(1),
+            u64s(1) => u64s(1)
+        },
+        Bytecode::CastU128 => type_transition! {
+            u8s(1) => u128s(1),
+            u64s(1) => u128s(1),
+            u128s(1) => u128s(1)
+        },
         Bytecode::LdAddr(_) => type_transition! { empty() => simple_addrs(1) },
         Bytecode::LdByteArray(_) => type_transition! { empty() => byte_arrays(1) },
         Bytecode::LdStr(_) => type_transition! { empty() => strs(1) },
@@ -281,5 +309,12 @@ pub fn call_details(op: &Bytecode) -> Vec<CallDetails> {
         Bytecode::GetTxnPublicKey => type_transition! { empty() => byte_arrays(1) },
         Bytecode::FreezeRef => type_transition! { ref_values(1) => ref_values(1) },
         Bytecode::EmitEvent => unimplemented!(),
+        // The vector instructions are not synthesized yet.
+        Bytecode::VecPack(_, _)
+        | Bytecode::VecLen(_)
+        | Bytecode::VecBorrow(_)
+        | Bytecode::VecPush(_)
+        | Bytecode::VecPop(_)
+        | Bytecode::VecSwap(_) => unimplemented!(),
     }
 }
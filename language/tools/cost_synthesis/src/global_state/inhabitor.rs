@@ -145,7 +145,9 @@ where
     pub fn inhabit(&mut self, sig_token: &SignatureToken) -> Local {
         match sig_token {
             SignatureToken::Bool => Local::bool(self.next_bool()),
+            SignatureToken::U8 => Local::u8(self.gen.gen()),
             SignatureToken::U64 => Local::u64(self.next_int()),
+            SignatureToken::U128 => Local::u128(self.gen.gen()),
             SignatureToken::String => Local::string(self.next_str()),
             SignatureToken::Address => Local::address(self.next_addr()),
             SignatureToken::Reference(sig) | SignatureToken::MutableReference(sig) => {
@@ -155,6 +157,13 @@ where
                     .expect("Unable to generate valid reference value")
             }
             SignatureToken::ByteArray => Local::bytearray(self.next_bytearray()),
+            SignatureToken::Vector(sig) => {
+                // Vectors are inhabited with a single element so that index 0 is always valid.
+                let elem = self.inhabit(&*sig)
+                    .value()
+                    .expect("[Vector Generation] Unable to get underlying value for vector element.");
+                Local::vector(vec![elem])
+            }
             SignatureToken::Struct(struct_handle_idx, _) => {
                 assert!(self.root_module.struct_defs().len() > 1);
                 let struct_definition = self
@@ -227,7 +227,7 @@ impl ModuleBuilder {
     fn with_random_functions(&mut self) {
         use SignatureToken::*;
         // The base signature tokens that we can use for our types.
-        let sig_toks = vec![Bool, U64, String, ByteArray, Address];
+        let sig_toks = vec![Bool, U8, U64, U128, String, ByteArray, Address];
         // Generate a bunch of random function signatures over these types.
         let functions = (0..self.table_size)
             .map(|_| {
@@ -387,7 +387,7 @@ impl ModuleBuilder {
     fn default_module_with_types() -> CompiledModuleMut {
         use SignatureToken::*;
         let mut module = CompiledModuleMut::default();
-        module.type_signatures = vec![Bool, U64, String, ByteArray, Address]
+        module.type_signatures = vec![Bool, U8, U64, U128, String, ByteArray, Address]
             .into_iter()
             .map(TypeSignature)
             .collect();
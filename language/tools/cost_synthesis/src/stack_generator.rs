@@ -375,7 +375,9 @@ where
                 let index = self.next_bounded_index(frame_len as TableIndex);
                 Branch(index as CodeOffset)
             }
+            LdU8(_) => LdU8(self.gen.gen()),
             LdConst(_) => LdConst(self.next_int(&[])),
+            LdU128(_) => LdU128(self.gen.gen()),
             LdStr(_) => LdStr(self.next_string_idx()),
             LdByteArray(_) => LdByteArray(self.next_bytearray_idx()),
             LdAddr(_) => LdAddr(self.next_address_idx()),
@@ -492,7 +494,9 @@ where
     fn resolve_to_value(&mut self, sig_token: &SignatureToken, stk: &[Local]) -> Local {
         match sig_token {
             SignatureToken::Bool => Local::bool(self.next_bool()),
+            SignatureToken::U8 => Local::u8(self.gen.gen()),
             SignatureToken::U64 => Local::u64(self.next_int(stk)),
+            SignatureToken::U128 => Local::u128(self.gen.gen()),
             SignatureToken::String => Local::string(self.next_str(false)),
             SignatureToken::Address => Local::address(self.next_addr(false)),
             SignatureToken::Reference(sig) | SignatureToken::MutableReference(sig) => {
@@ -502,6 +506,13 @@ where
                     .expect("Unable to generate valid reference value")
             }
             SignatureToken::ByteArray => Local::bytearray(self.next_bytearray()),
+            SignatureToken::Vector(sig) => {
+                // Vectors are inhabited with a single element so that index 0 is always valid.
+                let elem = self.resolve_to_value(sig, stk)
+                    .value()
+                    .expect("[Vector Generation] Unable to get underlying value for vector element.");
+                Local::vector(vec![elem])
+            }
             SignatureToken::Struct(struct_handle_idx, _) => {
                 assert!(self.root_module.struct_defs().len() > 1);
                 let struct_definition = self
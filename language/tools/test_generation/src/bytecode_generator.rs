@@ -282,7 +282,9 @@ impl BytecodeGenerator {
                 SignatureToken::Address => {
                     bytecode.push(Bytecode::LdAddr(AddressPoolIndex::new(0)))
                 }
+                SignatureToken::U8 => bytecode.push(Bytecode::LdU8(0)),
                 SignatureToken::U64 => bytecode.push(Bytecode::LdConst(0)),
+                SignatureToken::U128 => bytecode.push(Bytecode::LdU128(0)),
                 SignatureToken::Bool => bytecode.push(Bytecode::LdFalse),
                 SignatureToken::ByteArray => {
                     bytecode.push(Bytecode::LdByteArray(ByteArrayPoolIndex::new(0)))
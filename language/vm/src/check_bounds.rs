@@ -339,6 +339,16 @@ impl FunctionDefinition {
                     | MoveToSender(idx, _) => {
                         check_code_unit_bounds_impl(&module.struct_defs, bytecode_offset, *idx)
                     }
+                    VecPack(idx, _)
+                    | VecLen(idx)
+                    | VecBorrow(idx)
+                    | VecPush(idx)
+                    | VecPop(idx)
+                    | VecSwap(idx) => check_code_unit_bounds_impl(
+                        &module.locals_signatures,
+                        bytecode_offset,
+                        *idx,
+                    ),
                     // Instructions that refer to this code block.
                     BrTrue(offset) | BrFalse(offset) | Branch(offset) => {
                         let offset = *offset as usize;
@@ -370,7 +380,8 @@ impl FunctionDefinition {
 
                     // List out the other options explicitly so there's a compile error if a new
                     // bytecode gets added.
-                    FreezeRef | ReleaseRef | Pop | Ret | LdConst(_) | LdTrue | LdFalse
+                    FreezeRef | ReleaseRef | Pop | Ret | LdConst(_) | LdU8(_) | LdU128(_)
+                    | LdTrue | LdFalse | CastU8 | CastU64 | CastU128
                     | ReadRef | WriteRef | Add | Sub | Mul | Mod | Div | BitOr | BitAnd | Xor
                     | Or | And | Not | Eq | Neq | Lt | Gt | Le | Ge | Abort
                     | GetTxnGasUnitPrice | GetTxnMaxGasUnits | GetGasRemaining
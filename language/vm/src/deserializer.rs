@@ -653,10 +653,16 @@ fn load_signature_token(cursor: &mut Cursor<&[u8]>) -> BinaryLoaderResult<Signat
     if let Ok(byte) = cursor.read_u8() {
         match SerializedType::from_u8(byte)? {
             SerializedType::BOOL => Ok(SignatureToken::Bool),
+            SerializedType::U8 => Ok(SignatureToken::U8),
             SerializedType::INTEGER => Ok(SignatureToken::U64),
+            SerializedType::U128 => Ok(SignatureToken::U128),
             SerializedType::STRING => Ok(SignatureToken::String),
             SerializedType::BYTEARRAY => Ok(SignatureToken::ByteArray),
             SerializedType::ADDRESS => Ok(SignatureToken::Address),
+            SerializedType::VECTOR => {
+                let elem_token = load_signature_token(cursor)?;
+                Ok(SignatureToken::Vector(Box::new(elem_token)))
+            }
             SerializedType::REFERENCE => {
                 let ref_token = load_signature_token(cursor)?;
                 Ok(SignatureToken::Reference(Box::new(ref_token)))
@@ -856,6 +862,14 @@ fn load_code(cursor: &mut Cursor<&[u8]>, code: &mut Vec<Bytecode>) -> BinaryLoad
                 let value = read_u64_internal(cursor)?;
                 Bytecode::LdConst(value)
             }
+            Opcodes::LD_U8 => {
+                let value = cursor.read_u8().map_err(|_| BinaryError::Malformed)?;
+                Bytecode::LdU8(value)
+            }
+            Opcodes::LD_U128 => {
+                let value = read_u128_internal(cursor)?;
+                Bytecode::LdU128(value)
+            }
             Opcodes::LD_ADDR => {
                 let idx = read_uleb_u16_internal(cursor)?;
                 Bytecode::LdAddr(AddressPoolIndex(idx))
@@ -955,6 +969,34 @@ fn load_code(cursor: &mut Cursor<&[u8]>, code: &mut Vec<Bytecode>) -> BinaryLoad
             Opcodes::GET_TXN_SEQUENCE_NUMBER => Bytecode::GetTxnSequenceNumber,
             Opcodes::GET_TXN_PUBLIC_KEY => Bytecode::GetTxnPublicKey,
             Opcodes::FREEZE_REF => Bytecode::FreezeRef,
+            Opcodes::VEC_PACK => {
+                let types_idx = read_uleb_u16_internal(cursor)?;
+                let num = read_u64_internal(cursor)?;
+                Bytecode::VecPack(LocalsSignatureIndex(types_idx), num)
+            }
+            Opcodes::VEC_LEN => {
+                let types_idx = read_uleb_u16_internal(cursor)?;
+                Bytecode::VecLen(LocalsSignatureIndex(types_idx))
+            }
+            Opcodes::VEC_BORROW => {
+                let types_idx = read_uleb_u16_internal(cursor)?;
+                Bytecode::VecBorrow(LocalsSignatureIndex(types_idx))
+            }
+            Opcodes::VEC_PUSH => {
+                let types_idx = read_uleb_u16_internal(cursor)?;
+                Bytecode::VecPush(LocalsSignatureIndex(types_idx))
+            }
+            Opcodes::VEC_POP => {
+                let types_idx = read_uleb_u16_internal(cursor)?;
+                Bytecode::VecPop(LocalsSignatureIndex(types_idx))
+            }
+            Opcodes::VEC_SWAP => {
+                let types_idx = read_uleb_u16_internal(cursor)?;
+                Bytecode::VecSwap(LocalsSignatureIndex(types_idx))
+            }
+            Opcodes::CAST_U8 => Bytecode::CastU8,
+            Opcodes::CAST_U64 => Bytecode::CastU64,
+            Opcodes::CAST_U128 => Bytecode::CastU128,
         };
         code.push(bytecode);
     }
@@ -991,6 +1033,12 @@ fn read_u64_internal(cursor: &mut Cursor<&[u8]>) -> BinaryLoaderResult<u64> {
         .map_err(|_| BinaryError::Malformed)
 }
 
+fn read_u128_internal(cursor: &mut Cursor<&[u8]>) -> BinaryLoaderResult<u128> {
+    cursor
+        .read_u128::<LittleEndian>()
+        .map_err(|_| BinaryError::Malformed)
+}
+
 impl TableType {
     fn from_u8(value: u8) -> BinaryLoaderResult<TableType> {
         match value {
@@ -1036,6 +1084,9 @@ impl SerializedType {
             0x7 => Ok(SerializedType::STRUCT),
             0x8 => Ok(SerializedType::BYTEARRAY),
             0x9 => Ok(SerializedType::TYPE_PARAMETER),
+            0xA => Ok(SerializedType::VECTOR),
+            0xB => Ok(SerializedType::U8),
+            0xC => Ok(SerializedType::U128),
             _ => Err(BinaryError::UnknownSerializedType),
         }
     }
@@ -1128,6 +1179,17 @@ impl Opcodes {
             0x33 => Ok(Opcodes::GET_TXN_SEQUENCE_NUMBER),
             0x34 => Ok(Opcodes::GET_TXN_PUBLIC_KEY),
             0x35 => Ok(Opcodes::FREEZE_REF),
+            0x36 => Ok(Opcodes::VEC_PACK),
+            0x37 => Ok(Opcodes::VEC_LEN),
+            0x38 => Ok(Opcodes::VEC_BORROW),
+            0x39 => Ok(Opcodes::VEC_PUSH),
+            0x3A => Ok(Opcodes::VEC_POP),
+            0x3B => Ok(Opcodes::VEC_SWAP),
+            0x3C => Ok(Opcodes::LD_U8),
+            0x3D => Ok(Opcodes::LD_U128),
+            0x3E => Ok(Opcodes::CAST_U8),
+            0x3F => Ok(Opcodes::CAST_U64),
+            0x40 => Ok(Opcodes::CAST_U128),
             _ => Err(BinaryError::UnknownOpcode),
         }
     }
@@ -44,6 +44,8 @@ pub enum VMErrorKind {
     GlobalAlreadyBorrowed,
     MissingData,
     DuplicateModuleName,
+    VectorIndexOutOfBounds,
+    VectorTooLarge,
     DataFormatError,
     InvalidData,
     RemoteDataError,
@@ -305,6 +307,15 @@ pub enum VMStaticViolation {
 
     #[fail(display = "Illegal global operation at offset {}", _0)]
     GlobalReferenceError(usize),
+
+    #[fail(display = "Unable to verify VecPack at offset {}", _0)]
+    VecPackTypeMismatchError(usize),
+
+    #[fail(display = "Unable to verify vector operation at offset {}", _0)]
+    VecTypeMismatchError(usize),
+
+    #[fail(display = "Unable to verify vector operation at offset {}", _0)]
+    VecExistsBorrowError(usize),
 }
 
 #[derive(Clone, Debug, Eq, Fail, Ord, PartialEq, PartialOrd)]
@@ -719,6 +730,15 @@ impl From<&VerificationError> for VMVerificationError {
             VMStaticViolation::GlobalReferenceError(_) => {
                 VMVerificationError::GlobalReferenceError(message)
             }
+            VMStaticViolation::VecPackTypeMismatchError(_) => {
+                VMVerificationError::VecPackTypeMismatchError(message)
+            }
+            VMStaticViolation::VecTypeMismatchError(_) => {
+                VMVerificationError::VecTypeMismatchError(message)
+            }
+            VMStaticViolation::VecExistsBorrowError(_) => {
+                VMVerificationError::VecExistsBorrowError(message)
+            }
         }
     }
 }
@@ -776,6 +796,8 @@ impl From<&VMErrorKind> for VMStatus {
             VMErrorKind::ValueSerializerError => ExecutionStatus::ValueSerializationError,
             VMErrorKind::ValueDeserializerError => ExecutionStatus::ValueDeserializationError,
             VMErrorKind::DuplicateModuleName => ExecutionStatus::DuplicateModuleName,
+            VMErrorKind::VectorIndexOutOfBounds => ExecutionStatus::VectorIndexOutOfBounds,
+            VMErrorKind::VectorTooLarge => ExecutionStatus::VectorTooLarge,
             // The below errors already have top-level VMStatus variants associated with them, so
             // return those.
             VMErrorKind::CodeSerializerError(err) => return VMStatus::from(err),
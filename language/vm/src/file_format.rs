@@ -449,14 +449,20 @@ pub enum Kind {
 pub enum SignatureToken {
     /// Boolean, `true` or `false`.
     Bool,
+    /// Unsigned integers, 8 bits length.
+    U8,
     /// Unsigned integers, 64 bits length.
     U64,
+    /// Unsigned integers, 128 bits length.
+    U128,
     /// Strings, immutable, utf8 representation.
     String,
     /// ByteArray, variable size, immutable byte array.
     ByteArray,
     /// Address, a 32 bytes immutable type.
     Address,
+    /// Vector, variable size, homogeneous collection of values of the inner type.
+    Vector(Box<SignatureToken>),
     /// MOVE user type, resource or unrestricted
     Struct(StructHandleIndex, Vec<SignatureToken>),
     /// Reference to a type.
@@ -478,7 +484,9 @@ impl Arbitrary for SignatureToken {
 
         let leaf = prop_oneof![
             Just(Bool),
+            Just(U8),
             Just(U64),
+            Just(U128),
             Just(String),
             Just(ByteArray),
             Just(Address),
@@ -492,6 +500,7 @@ impl Arbitrary for SignatureToken {
             1,  // items per collection
             |inner| {
                 prop_oneof![
+                    inner.clone().prop_map(|token| Vector(Box::new(token))),
                     inner.clone().prop_map(|token| Reference(Box::new(token))),
                     inner
                         .clone()
@@ -507,10 +516,13 @@ impl ::std::fmt::Debug for SignatureToken {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         match self {
             SignatureToken::Bool => write!(f, "Bool"),
+            SignatureToken::U8 => write!(f, "U8"),
             SignatureToken::U64 => write!(f, "U64"),
+            SignatureToken::U128 => write!(f, "U128"),
             SignatureToken::String => write!(f, "String"),
             SignatureToken::ByteArray => write!(f, "ByteArray"),
             SignatureToken::Address => write!(f, "Address"),
+            SignatureToken::Vector(boxed) => write!(f, "Vector({:?})", boxed),
             SignatureToken::Struct(idx, types) => write!(f, "Struct({:?}, {:?})", idx, types),
             SignatureToken::Reference(boxed) => write!(f, "Reference({:?})", boxed),
             SignatureToken::MutableReference(boxed) => write!(f, "MutableReference({:?})", boxed),
@@ -549,7 +561,9 @@ impl SignatureToken {
         match self {
             Reference(_) => SignatureTokenKind::Reference,
             MutableReference(_) => SignatureTokenKind::MutableReference,
-            Bool | U64 | ByteArray | String | Address | Struct(_, _) => SignatureTokenKind::Value,
+            Bool | U8 | U64 | U128 | ByteArray | String | Address | Vector(_) | Struct(_, _) => {
+                SignatureTokenKind::Value
+            }
             TypeParameter(_) => unimplemented!(),
         }
     }
@@ -562,8 +576,8 @@ impl SignatureToken {
 
         match self {
             Struct(sh_idx, _) => Some(*sh_idx),
-            Reference(token) | MutableReference(token) => token.struct_index(),
-            Bool | U64 | ByteArray | String | Address | TypeParameter(_) => None,
+            Vector(token) | Reference(token) | MutableReference(token) => token.struct_index(),
+            Bool | U8 | U64 | U128 | ByteArray | String | Address | TypeParameter(_) => None,
         }
     }
 
@@ -571,8 +585,19 @@ impl SignatureToken {
     pub fn is_primitive(&self) -> bool {
         use SignatureToken::*;
         match self {
-            Bool | U64 | String | ByteArray | Address => true,
-            Struct(_, _) | Reference(_) | MutableReference(_) | TypeParameter(_) => false,
+            Bool | U8 | U64 | U128 | String | ByteArray | Address => true,
+            Vector(_) | Struct(_, _) | Reference(_) | MutableReference(_) | TypeParameter(_) => {
+                false
+            }
+        }
+    }
+
+    /// Returns `true` if the `SignatureToken` is an unsigned integer type (U8, U64 or U128).
+    pub fn is_integer(&self) -> bool {
+        use SignatureToken::*;
+        match self {
+            U8 | U64 | U128 => true,
+            _ => false,
         }
     }
 
@@ -580,7 +605,7 @@ impl SignatureToken {
     ///
     /// Currently equality operations are only allowed on:
     /// - Bool
-    /// - U64
+    /// - U8, U64 and U128
     /// - String
     /// - ByteArray
     /// - Address
@@ -588,7 +613,7 @@ impl SignatureToken {
     pub fn allows_equality(&self) -> bool {
         use SignatureToken::*;
         match self {
-            Struct(_, _) => false,
+            Vector(_) | Struct(_, _) => false,
             Reference(token) | MutableReference(token) => token.is_primitive(),
             token => token.is_primitive(),
         }
@@ -620,7 +645,8 @@ impl SignatureToken {
     pub fn debug_set_sh_idx(&mut self, sh_idx: StructHandleIndex) {
         match self {
             SignatureToken::Struct(ref mut wrapped, _) => *wrapped = sh_idx,
-            SignatureToken::Reference(ref mut token)
+            SignatureToken::Vector(ref mut token)
+            | SignatureToken::Reference(ref mut token)
             | SignatureToken::MutableReference(ref mut token) => token.debug_set_sh_idx(sh_idx),
             other => panic!(
                 "debug_set_sh_idx (to {}) called for non-struct token {:?}",
@@ -705,6 +731,18 @@ pub enum Bytecode {
     ///
     /// ```... -> ..., u64_value```
     LdConst(u64),
+    /// Push a u8 constant onto the stack.
+    ///
+    /// Stack transition:
+    ///
+    /// ```... -> ..., u8_value```
+    LdU8(u8),
+    /// Push a u128 constant onto the stack.
+    ///
+    /// Stack transition:
+    ///
+    /// ```... -> ..., u128_value```
+    LdU128(u128),
     /// Push a `string` literal onto the stack. The string is loaded from the `StringPool` via
     /// `StringPoolIndex`.
     ///
@@ -849,58 +887,65 @@ pub enum Bytecode {
     ///
     /// ```..., address_value -> ..., reference_value```
     BorrowGlobal(StructDefinitionIndex, LocalsSignatureIndex),
-    /// Add the 2 u64 at the top of the stack and pushes the result on the stack.
+    /// Add the 2 integers of the same type at the top of the stack and pushes the result on
+    /// the stack.
     /// The operation aborts the transaction in case of overflow.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., u64_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., integer_value```
     Add,
-    /// Subtract the 2 u64 at the top of the stack and pushes the result on the stack.
+    /// Subtract the 2 integers of the same type at the top of the stack and pushes the result on
+    /// the stack.
     /// The operation aborts the transaction in case of underflow.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., u64_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., integer_value```
     Sub,
-    /// Multiply the 2 u64 at the top of the stack and pushes the result on the stack.
+    /// Multiply the 2 integers of the same type at the top of the stack and pushes the result on
+    /// the stack.
     /// The operation aborts the transaction in case of overflow.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., u64_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., integer_value```
     Mul,
-    /// Perform a modulo operation on the 2 u64 at the top of the stack and pushes the
-    /// result on the stack.
+    /// Perform a modulo operation on the 2 integers of the same type at the top of the stack and
+    /// pushes the result on the stack.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., u64_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., integer_value```
     Mod,
-    /// Divide the 2 u64 at the top of the stack and pushes the result on the stack.
+    /// Divide the 2 integers of the same type at the top of the stack and pushes the result on
+    /// the stack.
     /// The operation aborts the transaction in case of "divide by 0".
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., u64_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., integer_value```
     Div,
-    /// Bitwise OR the 2 u64 at the top of the stack and pushes the result on the stack.
+    /// Bitwise OR the 2 integers of the same type at the top of the stack and pushes the result
+    /// on the stack.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., u64_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., integer_value```
     BitOr,
-    /// Bitwise AND the 2 u64 at the top of the stack and pushes the result on the stack.
+    /// Bitwise AND the 2 integers of the same type at the top of the stack and pushes the result
+    /// on the stack.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., u64_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., integer_value```
     BitAnd,
-    /// Bitwise XOR the 2 u64 at the top of the stack and pushes the result on the stack.
+    /// Bitwise XOR the 2 integers of the same type at the top of the stack and pushes the result
+    /// on the stack.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., u64_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., integer_value```
     Xor,
     /// Logical OR the 2 bool at the top of the stack and pushes the result on the stack.
     ///
@@ -936,33 +981,33 @@ pub enum Bytecode {
     ///
     /// ```..., value(1), value(2) -> ..., bool_value```
     Neq,
-    /// Perform a "less than" operation of the 2 u64 at the top of the stack and pushes the
-    /// result on the stack.
+    /// Perform a "less than" operation of the 2 integers of the same type at the top of the
+    /// stack and pushes the result on the stack.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., bool_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., bool_value```
     Lt,
-    /// Perform a "greater than" operation of the 2 u64 at the top of the stack and pushes the
-    /// result on the stack.
+    /// Perform a "greater than" operation of the 2 integers of the same type at the top of the
+    /// stack and pushes the result on the stack.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., bool_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., bool_value```
     Gt,
-    /// Perform a "less than or equal" operation of the 2 u64 at the top of the stack and pushes
-    /// the result on the stack.
+    /// Perform a "less than or equal" operation of the 2 integers of the same type at the top of
+    /// the stack and pushes the result on the stack.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., bool_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., bool_value```
     Le,
-    /// Perform a "greater than or equal" than operation of the 2 u64 at the top of the stack
-    /// and pushes the result on the stack.
+    /// Perform a "greater than or equal" than operation of the 2 integers of the same type at the
+    /// top of the stack and pushes the result on the stack.
     ///
     /// Stack transition:
     ///
-    /// ```..., u64_value(1), u64_value(2) -> ..., bool_value```
+    /// ```..., integer_value(1), integer_value(2) -> ..., bool_value```
     Ge,
     /// Abort execution with errorcode
     ///
@@ -1042,12 +1087,77 @@ pub enum Bytecode {
     ///
     /// ```..., -> ..., bytearray_value```
     GetTxnPublicKey,
+    /// Create a vector of the element type specified via `LocalsSignatureIndex` from the
+    /// `u64` number of values at the top of the stack. The locals signature must contain exactly
+    /// one type, the element type of the vector.
+    ///
+    /// The first element pushed on the stack becomes the element at index 0 of the vector.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., value(1), value(2), ..., value(n) -> ..., vector_value```
+    VecPack(LocalsSignatureIndex, u64),
+    /// Return the length of the vector referenced by the reference at the top of the stack.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., vector_reference -> ..., u64_value```
+    VecLen(LocalsSignatureIndex),
+    /// Load a reference to the element at the given index of the referenced vector. The
+    /// reference returned is mutable if and only if the vector reference is mutable.
+    /// Abort execution if the index is out of bounds.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., vector_reference, u64_value -> ..., element_reference```
+    VecBorrow(LocalsSignatureIndex),
+    /// Add the value at the top of the stack to the back of the vector referenced by the
+    /// mutable reference below it.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., vector_reference, value -> ...```
+    VecPush(LocalsSignatureIndex),
+    /// Remove the last element of the vector referenced by the mutable reference at the top of
+    /// the stack and push it on the stack. Abort execution if the vector is empty.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., vector_reference -> ..., value```
+    VecPop(LocalsSignatureIndex),
+    /// Swap the elements at the two given indexes of the vector referenced by the mutable
+    /// reference. Abort execution if either index is out of bounds.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., vector_reference, u64_value(1), u64_value(2) -> ...```
+    VecSwap(LocalsSignatureIndex),
+    /// Convert the integer at the top of the stack into a u8.
+    /// The operation aborts the transaction if the value does not fit in a u8.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., integer_value -> ..., u8_value```
+    CastU8,
+    /// Convert the integer at the top of the stack into a u64.
+    /// The operation aborts the transaction if the value does not fit in a u64.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., integer_value -> ..., u64_value```
+    CastU64,
+    /// Convert the integer at the top of the stack into a u128.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., integer_value -> ..., u128_value```
+    CastU128,
 }
 
 /// The number of bytecode instructions.
 /// This is necessary for checking that all instructions are covered since Rust
 /// does not provide a way of determining the number of variants of an enum.
-pub const NUMBER_OF_BYTECODE_INSTRUCTIONS: usize = 53;
+pub const NUMBER_OF_BYTECODE_INSTRUCTIONS: usize = 64;
 
 impl ::std::fmt::Debug for Bytecode {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -1058,6 +1168,8 @@ impl ::std::fmt::Debug for Bytecode {
             Bytecode::BrFalse(a) => write!(f, "BrFalse({})", a),
             Bytecode::Branch(a) => write!(f, "Branch({})", a),
             Bytecode::LdConst(a) => write!(f, "LdConst({})", a),
+            Bytecode::LdU8(a) => write!(f, "LdU8({})", a),
+            Bytecode::LdU128(a) => write!(f, "LdU128({})", a),
             Bytecode::LdStr(a) => write!(f, "LdStr({})", a),
             Bytecode::LdByteArray(a) => write!(f, "LdByteArray({})", a),
             Bytecode::LdAddr(a) => write!(f, "LdAddr({})", a),
@@ -1105,6 +1217,15 @@ impl ::std::fmt::Debug for Bytecode {
             Bytecode::EmitEvent => write!(f, "EmitEvent"),
             Bytecode::GetTxnSequenceNumber => write!(f, "GetTxnSequenceNumber"),
             Bytecode::GetTxnPublicKey => write!(f, "GetTxnPublicKey"),
+            Bytecode::VecPack(a, b) => write!(f, "VecPack({:?}, {})", a, b),
+            Bytecode::VecLen(a) => write!(f, "VecLen({:?})", a),
+            Bytecode::VecBorrow(a) => write!(f, "VecBorrow({:?})", a),
+            Bytecode::VecPush(a) => write!(f, "VecPush({:?})", a),
+            Bytecode::VecPop(a) => write!(f, "VecPop({:?})", a),
+            Bytecode::VecSwap(a) => write!(f, "VecSwap({:?})", a),
+            Bytecode::CastU8 => write!(f, "CastU8"),
+            Bytecode::CastU64 => write!(f, "CastU64"),
+            Bytecode::CastU128 => write!(f, "CastU128"),
         }
     }
 }
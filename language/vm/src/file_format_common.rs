@@ -81,6 +81,9 @@ pub enum SerializedType {
     STRUCT                  = 0x7,
     BYTEARRAY               = 0x8,
     TYPE_PARAMETER          = 0x9,
+    VECTOR                  = 0xA,
+    U8                      = 0xB,
+    U128                    = 0xC,
 }
 
 #[rustfmt::skip]
@@ -170,6 +173,17 @@ pub enum Opcodes {
     GET_TXN_SEQUENCE_NUMBER = 0x33,
     GET_TXN_PUBLIC_KEY      = 0x34,
     FREEZE_REF              = 0x35,
+    VEC_PACK                = 0x36,
+    VEC_LEN                 = 0x37,
+    VEC_BORROW              = 0x38,
+    VEC_PUSH                = 0x39,
+    VEC_POP                 = 0x3A,
+    VEC_SWAP                = 0x3B,
+    LD_U8                   = 0x3C,
+    LD_U128                 = 0x3D,
+    CAST_U8                 = 0x3E,
+    CAST_U64                = 0x3F,
+    CAST_U128               = 0x40,
 }
 
 /// Upper limit on the binary size
@@ -285,6 +299,11 @@ pub fn write_u64(binary: &mut BinaryData, value: u64) -> Result<()> {
     binary.extend(&value.to_le_bytes())
 }
 
+/// Write a `u128` in Little Endian format.
+pub fn write_u128(binary: &mut BinaryData, value: u128) -> Result<()> {
+    binary.extend(&value.to_le_bytes())
+}
+
 /// Reads a `u16` in ULEB128 format from a `binary`.
 ///
 /// Takes a `&mut Cursor<&[u8]>` and returns a pair:
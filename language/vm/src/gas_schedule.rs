@@ -323,6 +323,19 @@ lazy_static! {
             (Pack(StructDefinitionIndex::new(0), NO_TYPE_ACTUALS), 73, 1),
             // TODO/XXX: Need to get the cost for this still
             (EmitEvent, 1, 1),
+            // VecPack, VecPush and VecPop are also charged per unit of size of the elements they
+            // move, so these only cover the fixed cost of the instruction.
+            (VecPack(NO_TYPE_ACTUALS, 0), 73, 1),
+            (VecLen(NO_TYPE_ACTUALS), 51, 1),
+            (VecBorrow(NO_TYPE_ACTUALS), 58, 1),
+            (VecPush(NO_TYPE_ACTUALS), 65, 1),
+            (VecPop(NO_TYPE_ACTUALS), 65, 1),
+            (VecSwap(NO_TYPE_ACTUALS), 65, 1),
+            (LdU8(0), 29, 1),
+            (LdU128(0), 29, 1),
+            (CastU8, 29, 1),
+            (CastU64, 29, 1),
+            (CastU128, 29, 1),
             ];
         CostTable::new(instrs)
     };
@@ -594,10 +594,17 @@ fn display_signature_token<T: TableAccess>(
 ) -> fmt::Result {
     match token {
         SignatureToken::Bool => write!(f, "Bool"),
+        SignatureToken::U8 => write!(f, "U8"),
         SignatureToken::U64 => write!(f, "Integer"),
+        SignatureToken::U128 => write!(f, "U128"),
         SignatureToken::String => write!(f, "String"),
         SignatureToken::ByteArray => write!(f, "ByteArray"),
         SignatureToken::Address => write!(f, "Address"),
+        SignatureToken::Vector(token) => {
+            write!(f, "Vector<")?;
+            display_signature_token(token, tables, f)?;
+            write!(f, ">")
+        }
         SignatureToken::Struct(idx, types) => {
             display_struct_handle(tables.get_struct_at(*idx).unwrap(), tables, f)?;
             display_type_parameters(&types, tables, f)
@@ -391,8 +391,10 @@ impl StDefnMaterializeState {
                 self.struct_handles[struct_handle_index.0 as usize].is_nominal_resource
                     || targs.iter().any(|t| self.contains_nominal_resource(t))
             }
-            Reference(token) | MutableReference(token) => self.contains_nominal_resource(token),
-            Bool | U64 | ByteArray | String | Address | TypeParameter(_) => false,
+            Vector(token) | Reference(token) | MutableReference(token) => {
+                self.contains_nominal_resource(token)
+            }
+            Bool | U8 | U64 | U128 | ByteArray | String | Address | TypeParameter(_) => false,
         }
     }
 }
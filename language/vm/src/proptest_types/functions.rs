@@ -360,6 +360,8 @@ impl BytecodeGen {
             // The numbers are relative weights, somewhat arbitrarily picked.
             9 => Self::just_bytecode_strategy(),
             1 => any::<u64>().prop_map(Bytecode::LdConst),
+            1 => any::<u8>().prop_map(Bytecode::LdU8),
+            1 => any::<u128>().prop_map(Bytecode::LdU128),
         ]
     }
 
@@ -399,6 +401,9 @@ impl BytecodeGen {
             EmitEvent,
             GetTxnSequenceNumber,
             GetTxnPublicKey,
+            CastU8,
+            CastU64,
+            CastU128,
         ];
         select(JUST_BYTECODES)
     }
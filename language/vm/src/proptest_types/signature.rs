@@ -77,13 +77,16 @@ impl FunctionSignatureGen {
 pub enum SignatureTokenGen {
     // Atomic signature tokens.
     Bool,
+    U8,
     Integer,
+    U128,
     String,
     ByteArray,
     Address,
     TypeParameter(PropIndex),
 
     // Composite signature tokens.
+    Vector(Box<SignatureTokenGen>),
     Struct(PropIndex, Vec<SignatureTokenGen>),
     Reference(Box<SignatureTokenGen>),
     MutableReference(Box<SignatureTokenGen>),
@@ -93,6 +96,7 @@ impl SignatureTokenGen {
     pub fn strategy() -> impl Strategy<Value = Self> {
         prop::strategy::Union::new_weighted(vec![
             (5, Self::atom_strategy().boxed()),
+            (1, Self::vector_strategy().boxed()),
             (1, Self::reference_strategy().boxed()),
             (1, Self::mutable_reference_strategy().boxed()),
         ])
@@ -120,7 +124,7 @@ impl SignatureTokenGen {
         use SignatureTokenGen::*;
 
         static OWNED_NON_STRUCTS: &[SignatureTokenGen] =
-            &[Bool, Integer, String, ByteArray, Address];
+            &[Bool, U8, Integer, U128, String, ByteArray, Address];
 
         select(OWNED_NON_STRUCTS)
     }
@@ -138,6 +142,11 @@ impl SignatureTokenGen {
         any::<PropIndex>().prop_map(|idx| Struct(idx, vec![]))
     }
 
+    pub fn vector_strategy() -> impl Strategy<Value = Self> {
+        // Vectors of references are not supported.
+        Self::owned_strategy().prop_map(|atom| SignatureTokenGen::Vector(Box::new(atom)))
+    }
+
     pub fn reference_strategy() -> impl Strategy<Value = Self> {
         // References to references are not supported.
         Self::owned_strategy().prop_map(|atom| SignatureTokenGen::Reference(Box::new(atom)))
@@ -153,10 +162,13 @@ impl SignatureTokenGen {
 
         match self {
             Bool => SignatureToken::Bool,
+            U8 => SignatureToken::U8,
             Integer => SignatureToken::U64,
+            U128 => SignatureToken::U128,
             String => SignatureToken::String,
             ByteArray => SignatureToken::ByteArray,
             Address => SignatureToken::Address,
+            Vector(token) => SignatureToken::Vector(Box::new(token.materialize(struct_handles_len))),
             Struct(idx, types) => SignatureToken::Struct(
                 StructHandleIndex::new(idx.index(struct_handles_len) as TableIndex),
                 types
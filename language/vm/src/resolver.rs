@@ -58,7 +58,9 @@ impl Resolver {
     ) -> Result<SignatureToken, VMStaticViolation> {
         match sig_token {
             SignatureToken::Bool
+            | SignatureToken::U8
             | SignatureToken::U64
+            | SignatureToken::U128
             | SignatureToken::String
             | SignatureToken::ByteArray
             | SignatureToken::Address
@@ -102,6 +104,9 @@ impl Resolver {
                         .collect::<Result<Vec<_>, VMStaticViolation>>()?,
                 ))
             }
+            SignatureToken::Vector(sub_sig_token) => Ok(SignatureToken::Vector(Box::new(
+                self.import_signature_token(dependency, sub_sig_token)?,
+            ))),
             SignatureToken::Reference(sub_sig_token) => Ok(SignatureToken::Reference(Box::new(
                 self.import_signature_token(dependency, sub_sig_token)?,
             ))),
@@ -456,10 +456,16 @@ fn serialize_signature_tokens(binary: &mut BinaryData, tokens: &[SignatureToken]
 fn serialize_signature_token(binary: &mut BinaryData, token: &SignatureToken) -> Result<()> {
     match token {
         SignatureToken::Bool => binary.push(SerializedType::BOOL as u8)?,
+        SignatureToken::U8 => binary.push(SerializedType::U8 as u8)?,
         SignatureToken::U64 => binary.push(SerializedType::INTEGER as u8)?,
+        SignatureToken::U128 => binary.push(SerializedType::U128 as u8)?,
         SignatureToken::String => binary.push(SerializedType::STRING as u8)?,
         SignatureToken::ByteArray => binary.push(SerializedType::BYTEARRAY as u8)?,
         SignatureToken::Address => binary.push(SerializedType::ADDRESS as u8)?,
+        SignatureToken::Vector(boxed_token) => {
+            binary.push(SerializedType::VECTOR as u8)?;
+            serialize_signature_token(binary, boxed_token.deref())?;
+        }
         SignatureToken::Struct(idx, types) => {
             binary.push(SerializedType::STRUCT as u8)?;
             write_u16_as_uleb128(binary, idx.0)?;
@@ -553,6 +559,14 @@ fn serialize_instruction_inner(binary: &mut BinaryData, opcode: &Bytecode) -> Re
             binary.push(Opcodes::LD_CONST as u8)?;
             write_u64(binary, *value)
         }
+        Bytecode::LdU8(value) => {
+            binary.push(Opcodes::LD_U8 as u8)?;
+            binary.push(*value)
+        }
+        Bytecode::LdU128(value) => {
+            binary.push(Opcodes::LD_U128 as u8)?;
+            write_u128(binary, *value)
+        }
         Bytecode::LdAddr(address_idx) => {
             binary.push(Opcodes::LD_ADDR as u8)?;
             write_u16_as_uleb128(binary, address_idx.0)
@@ -651,6 +665,34 @@ fn serialize_instruction_inner(binary: &mut BinaryData, opcode: &Bytecode) -> Re
         Bytecode::EmitEvent => binary.push(Opcodes::EMIT_EVENT as u8),
         Bytecode::GetTxnSequenceNumber => binary.push(Opcodes::GET_TXN_SEQUENCE_NUMBER as u8),
         Bytecode::GetTxnPublicKey => binary.push(Opcodes::GET_TXN_PUBLIC_KEY as u8),
+        Bytecode::VecPack(types_idx, num) => {
+            binary.push(Opcodes::VEC_PACK as u8)?;
+            write_u16_as_uleb128(binary, types_idx.0)?;
+            write_u64(binary, *num)
+        }
+        Bytecode::VecLen(types_idx) => {
+            binary.push(Opcodes::VEC_LEN as u8)?;
+            write_u16_as_uleb128(binary, types_idx.0)
+        }
+        Bytecode::VecBorrow(types_idx) => {
+            binary.push(Opcodes::VEC_BORROW as u8)?;
+            write_u16_as_uleb128(binary, types_idx.0)
+        }
+        Bytecode::VecPush(types_idx) => {
+            binary.push(Opcodes::VEC_PUSH as u8)?;
+            write_u16_as_uleb128(binary, types_idx.0)
+        }
+        Bytecode::VecPop(types_idx) => {
+            binary.push(Opcodes::VEC_POP as u8)?;
+            write_u16_as_uleb128(binary, types_idx.0)
+        }
+        Bytecode::VecSwap(types_idx) => {
+            binary.push(Opcodes::VEC_SWAP as u8)?;
+            write_u16_as_uleb128(binary, types_idx.0)
+        }
+        Bytecode::CastU8 => binary.push(Opcodes::CAST_U8 as u8),
+        Bytecode::CastU64 => binary.push(Opcodes::CAST_U64 as u8),
+        Bytecode::CastU128 => binary.push(Opcodes::CAST_U128 as u8),
     };
     res?;
     Ok(())
@@ -532,10 +532,14 @@ impl<'a, T: ModuleAccess> SignatureTokenView<'a, T> {
                     }
                 })
             }
+            // A vector has the kind of its elements, a vector of resources is a resource.
+            SignatureToken::Vector(token) => Self::new(self.module, token).kind(),
             SignatureToken::Reference(_)
             | SignatureToken::MutableReference(_)
             | SignatureToken::Bool
+            | SignatureToken::U8
             | SignatureToken::U64
+            | SignatureToken::U128
             | SignatureToken::String
             | SignatureToken::ByteArray
             | SignatureToken::Address => Kind::Unrestricted,
@@ -555,10 +559,15 @@ impl<'a, T: ModuleAccess> SignatureTokenView<'a, T> {
                         .iter()
                         .any(|token| Self::new(self.module, token).contains_nominal_resource())
             }
+            SignatureToken::Vector(token) => {
+                Self::new(self.module, token).contains_nominal_resource()
+            }
             SignatureToken::Reference(_)
             | SignatureToken::MutableReference(_)
             | SignatureToken::Bool
+            | SignatureToken::U8
             | SignatureToken::U64
+            | SignatureToken::U128
             | SignatureToken::String
             | SignatureToken::ByteArray
             | SignatureToken::Address
@@ -246,7 +246,9 @@ impl<'alloc> VMModuleCache<'alloc> {
     ) -> VMResult<Option<Type>> {
         match tok {
             SignatureToken::Bool => Ok(Ok(Some(Type::Bool))),
+            SignatureToken::U8 => Ok(Ok(Some(Type::U8))),
             SignatureToken::U64 => Ok(Ok(Some(Type::U64))),
+            SignatureToken::U128 => Ok(Ok(Some(Type::U128))),
             SignatureToken::String => Ok(Ok(Some(Type::String))),
             SignatureToken::ByteArray => Ok(Ok(Some(Type::ByteArray))),
             SignatureToken::Address => Ok(Ok(Some(Type::Address))),
@@ -269,6 +271,12 @@ impl<'alloc> VMModuleCache<'alloc> {
                         .resolve_signature_token_with_fetcher(module, sub_tok, gas_meter, fetcher));
                 Ok(Ok(inner_ty.map(|t| Type::MutableReference(Box::new(t)))))
             }
+            SignatureToken::Vector(sub_tok) => {
+                let inner_ty =
+                    try_runtime!(self
+                        .resolve_signature_token_with_fetcher(module, sub_tok, gas_meter, fetcher));
+                Ok(Ok(inner_ty.map(|t| Type::Vector(Box::new(t)))))
+            }
         }
     }
 
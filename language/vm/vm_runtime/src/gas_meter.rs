@@ -6,6 +6,7 @@ use crate::{
     code_cache::module_cache::ModuleCache, execution_stack::ExecutionStack,
     loaded_data::function::FunctionReference,
 };
+use std::cmp;
 use types::account_address::ADDRESS_LENGTH;
 use vm::{access::ModuleAccess, errors::*, file_format::Bytecode, gas_schedule::*};
 use vm_runtime_types::value::Local;
@@ -126,7 +127,9 @@ impl GasMeter {
             | Bytecode::Le
             | Bytecode::LdTrue
             | Bytecode::LdFalse
+            | Bytecode::LdU8(_)
             | Bytecode::LdConst(_)
+            | Bytecode::LdU128(_)
             | Bytecode::Branch(_)
             | Bytecode::Abort
             | Bytecode::Pop
@@ -140,6 +143,12 @@ impl GasMeter {
             | Bytecode::GetTxnSequenceNumber
             | Bytecode::Ge
             | Bytecode::EmitEvent
+            | Bytecode::VecLen(_)
+            | Bytecode::VecBorrow(_)
+            | Bytecode::VecSwap(_)
+            | Bytecode::CastU8
+            | Bytecode::CastU64
+            | Bytecode::CastU128
             | Bytecode::FreezeRef => {
                 let default_gas = static_cost_instr(instr, AbstractMemorySize::new(1));
                 Self::gas_of(default_gas)
@@ -217,6 +226,26 @@ impl GasMeter {
                 let new_gas = static_cost_instr(instr, total_size);
                 Self::gas_of(new_gas)
             }
+            // Packing moves the elements on the stack into the vector, so we charge based on the
+            // total size of the elements.
+            Bytecode::VecPack(_, num) => {
+                let stack = stk.get_value_stack();
+                let num = cmp::min(*num, stack.len() as u64) as usize;
+                let size = stack[stack.len() - num..]
+                    .iter()
+                    .fold(*STRUCT_SIZE, |acc, local| acc.add(local.size()));
+                Self::gas_of(static_cost_instr(instr, size))
+            }
+            // Pushing and popping move a single element into or out of the vector, so we charge
+            // based on the size of that element.
+            Bytecode::VecPush(_) => {
+                let size = stk.peek()?.size();
+                Self::gas_of(static_cost_instr(instr, size))
+            }
+            Bytecode::VecPop(_) => {
+                let size = stk.peek()?.vector_last_size().unwrap_or(*CONST_SIZE);
+                Self::gas_of(static_cost_instr(instr, size))
+            }
             Bytecode::WriteRef => {
                 // Get a reference to the value that we are going to write
                 let write_val = stk.peek_at(1)?;
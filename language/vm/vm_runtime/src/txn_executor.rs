@@ -14,7 +14,7 @@ use crate::{
     },
 };
 use bytecode_verifier::{VerifiedModule, VerifiedScript};
-use std::collections::VecDeque;
+use std::{collections::VecDeque, convert::TryFrom};
 use types::{
     access_path::AccessPath,
     account_address::AccountAddress,
@@ -36,7 +36,7 @@ use vm::{
 use vm_cache_map::Arena;
 use vm_runtime_types::{
     native_functions::dispatch::{dispatch_native_function, NativeReturnStatus},
-    value::{Local, MutVal, Reference, Value},
+    value::{IntegerValue, Local, MutVal, Reference, Value},
 };
 
 #[cfg(test)]
@@ -139,12 +139,39 @@ where
         }
     }
 
-    fn binop_int<F, T>(&mut self, f: F) -> VMResult<()>
+    /// Perform an arithmetic operation on two integers of the same type. `f` operates on u128
+    /// values and the result is range checked against the type of the operands.
+    fn binop_int<F>(&mut self, f: F) -> VMResult<()>
     where
-        Option<T>: From<MutVal>,
-        F: FnOnce(T, T) -> Option<u64>,
+        F: FnOnce(u128, u128) -> Option<u128>,
+    {
+        self.binop(|lhs: IntegerValue, rhs| lhs.binop(rhs, f).map(Local::from))
+    }
+
+    /// Perform a comparison between two integers of the same type.
+    fn binop_int_bool<F>(&mut self, f: F) -> VMResult<()>
+    where
+        F: FnOnce(u128, u128) -> bool,
     {
-        self.binop(|lhs, rhs| f(lhs, rhs).map(Local::u64))
+        self.binop(|lhs: IntegerValue, rhs| lhs.compare(rhs, f).map(Local::bool))
+    }
+
+    /// Cast the integer at the top of the stack with `f`, failing with an `ArithmeticError` if
+    /// the value does not fit in the target type.
+    fn cast_int<F>(&mut self, f: F) -> VMResult<()>
+    where
+        F: FnOnce(u128) -> Option<IntegerValue>,
+    {
+        let value = try_runtime!(self.execution_stack.pop_as::<IntegerValue>());
+        if let Some(v) = f(value.value()) {
+            self.execution_stack.push(Local::from(v));
+            Ok(Ok(()))
+        } else {
+            Ok(Err(VMRuntimeError {
+                loc: self.execution_stack.location()?,
+                err: VMErrorKind::ArithmeticError,
+            }))
+        }
     }
 
     fn binop_bool<F, T>(&mut self, f: F) -> VMResult<()>
@@ -155,6 +182,21 @@ where
         self.binop(|lhs, rhs| Some(Local::bool(f(lhs, rhs))))
     }
 
+    /// Checks that `idx` is a valid index into the vector referenced by `vec_ref`.
+    fn check_vector_index(&self, vec_ref: &Local, idx: u64) -> VMResult<()> {
+        match vec_ref.vector_len() {
+            Some(len) if idx < len => Ok(Ok(())),
+            Some(_) => Ok(Err(VMRuntimeError {
+                loc: self.execution_stack.location()?,
+                err: VMErrorKind::VectorIndexOutOfBounds,
+            })),
+            None => Ok(Err(VMRuntimeError {
+                loc: self.execution_stack.location()?,
+                err: VMErrorKind::TypeError,
+            })),
+        }
+    }
+
     /// This function will execute the code sequence starting from the beginning_offset, and return
     /// Ok(Ok(offset)) when the instruction sequence hit a branch, either by calling into a new
     /// function, branches, function return, etc. The return value will be the pc for the next
@@ -199,9 +241,15 @@ where
                     }
                 }
                 Bytecode::Branch(offset) => return Ok(Ok(offset)),
+                Bytecode::LdU8(int_const) => {
+                    self.execution_stack.push(Local::u8(int_const));
+                }
                 Bytecode::LdConst(int_const) => {
                     self.execution_stack.push(Local::u64(int_const));
                 }
+                Bytecode::LdU128(int_const) => {
+                    self.execution_stack.push(Local::u128(int_const));
+                }
                 Bytecode::LdAddr(idx) => {
                     let top_frame = self.execution_stack.top_frame()?;
                     let addr_ref = top_frame.module().address_at(idx);
@@ -427,20 +475,23 @@ where
                     }
                 }
                 // Arithmetic Operations
-                Bytecode::Add => try_runtime!(self.binop_int(u64::checked_add)),
-                Bytecode::Sub => try_runtime!(self.binop_int(u64::checked_sub)),
-                Bytecode::Mul => try_runtime!(self.binop_int(u64::checked_mul)),
-                Bytecode::Mod => try_runtime!(self.binop_int(u64::checked_rem)),
-                Bytecode::Div => try_runtime!(self.binop_int(u64::checked_div)),
-                Bytecode::BitOr => try_runtime!(self.binop_int(|l: u64, r| Some(l | r))),
-                Bytecode::BitAnd => try_runtime!(self.binop_int(|l: u64, r| Some(l & r))),
-                Bytecode::Xor => try_runtime!(self.binop_int(|l: u64, r| Some(l ^ r))),
+                Bytecode::Add => try_runtime!(self.binop_int(u128::checked_add)),
+                Bytecode::Sub => try_runtime!(self.binop_int(u128::checked_sub)),
+                Bytecode::Mul => try_runtime!(self.binop_int(u128::checked_mul)),
+                Bytecode::Mod => try_runtime!(self.binop_int(u128::checked_rem)),
+                Bytecode::Div => try_runtime!(self.binop_int(u128::checked_div)),
+                Bytecode::BitOr => try_runtime!(self.binop_int(|l, r| Some(l | r))),
+                Bytecode::BitAnd => try_runtime!(self.binop_int(|l, r| Some(l & r))),
+                Bytecode::Xor => try_runtime!(self.binop_int(|l, r| Some(l ^ r))),
                 Bytecode::Or => try_runtime!(self.binop_bool(|l, r| l || r)),
                 Bytecode::And => try_runtime!(self.binop_bool(|l, r| l && r)),
-                Bytecode::Lt => try_runtime!(self.binop_bool(|l: u64, r| l < r)),
-                Bytecode::Gt => try_runtime!(self.binop_bool(|l: u64, r| l > r)),
-                Bytecode::Le => try_runtime!(self.binop_bool(|l: u64, r| l <= r)),
-                Bytecode::Ge => try_runtime!(self.binop_bool(|l: u64, r| l >= r)),
+                Bytecode::Lt => try_runtime!(self.binop_int_bool(|l, r| l < r)),
+                Bytecode::Gt => try_runtime!(self.binop_int_bool(|l, r| l > r)),
+                Bytecode::Le => try_runtime!(self.binop_int_bool(|l, r| l <= r)),
+                Bytecode::Ge => try_runtime!(self.binop_int_bool(|l, r| l >= r)),
+                Bytecode::CastU8 => try_runtime!(self.cast_int(IntegerValue::cast_u8)),
+                Bytecode::CastU64 => try_runtime!(self.cast_int(IntegerValue::cast_u64)),
+                Bytecode::CastU128 => try_runtime!(self.cast_int(IntegerValue::cast_u128)),
                 Bytecode::Abort => {
                     let error_code = try_runtime!(self.execution_stack.pop_as::<u64>());
                     return Ok(Err(VMRuntimeError {
@@ -483,6 +534,109 @@ where
                         self.txn_data.public_key().to_bytes().to_vec(),
                     )));
                 }
+                Bytecode::VecPack(_, num) => {
+                    // The verifier guarantees that `num` values are on the stack. A vector with
+                    // more elements than the stack can ever hold is rejected as too large.
+                    let num = match u16::try_from(num) {
+                        Ok(num) => num,
+                        Err(_) => {
+                            return Ok(Err(VMRuntimeError {
+                                loc: self.execution_stack.location()?,
+                                err: VMErrorKind::VectorTooLarge,
+                            }))
+                        }
+                    };
+                    let elems = self
+                        .execution_stack
+                        .popn(num)?
+                        .into_iter()
+                        .map(Local::value)
+                        .collect();
+                    match elems {
+                        Some(elems) => {
+                            self.execution_stack.push(Local::vector(elems));
+                        }
+                        None => {
+                            return Ok(Err(VMRuntimeError {
+                                loc: self.execution_stack.location()?,
+                                err: VMErrorKind::TypeError,
+                            }))
+                        }
+                    }
+                }
+                Bytecode::VecLen(_) => {
+                    let vec_ref = self.execution_stack.pop()?;
+                    match vec_ref.vector_len() {
+                        Some(len) => {
+                            if let Err(e) = vec_ref.release_reference() {
+                                return Ok(Err(e));
+                            }
+                            self.execution_stack.push(Local::u64(len));
+                        }
+                        None => {
+                            return Ok(Err(VMRuntimeError {
+                                loc: self.execution_stack.location()?,
+                                err: VMErrorKind::TypeError,
+                            }))
+                        }
+                    }
+                }
+                Bytecode::VecBorrow(_) => {
+                    let idx = try_runtime!(self.execution_stack.pop_as::<u64>());
+                    let vec_ref = self.execution_stack.pop()?;
+                    try_runtime!(self.check_vector_index(&vec_ref, idx));
+                    match vec_ref.borrow_element(idx) {
+                        Some(v) => {
+                            self.execution_stack.push(v);
+                        }
+                        None => {
+                            return Ok(Err(VMRuntimeError {
+                                loc: self.execution_stack.location()?,
+                                err: VMErrorKind::TypeError,
+                            }))
+                        }
+                    }
+                }
+                Bytecode::VecPush(_) => {
+                    let val = self.execution_stack.pop()?;
+                    let vec_ref = self.execution_stack.pop()?;
+                    let pushed = val.value().and_then(|v| vec_ref.vector_push(v));
+                    if pushed.is_none() {
+                        return Ok(Err(VMRuntimeError {
+                            loc: self.execution_stack.location()?,
+                            err: VMErrorKind::TypeError,
+                        }));
+                    }
+                }
+                Bytecode::VecPop(_) => {
+                    let vec_ref = self.execution_stack.pop()?;
+                    // Popping from an empty vector is the same as accessing index 0.
+                    try_runtime!(self.check_vector_index(&vec_ref, 0));
+                    match vec_ref.vector_pop() {
+                        Some(v) => {
+                            self.execution_stack.push(v);
+                        }
+                        None => {
+                            return Ok(Err(VMRuntimeError {
+                                loc: self.execution_stack.location()?,
+                                err: VMErrorKind::TypeError,
+                            }))
+                        }
+                    }
+                }
+                Bytecode::VecSwap(_) => {
+                    let idx2 = try_runtime!(self.execution_stack.pop_as::<u64>());
+                    let idx1 = try_runtime!(self.execution_stack.pop_as::<u64>());
+                    let vec_ref = self.execution_stack.pop()?;
+                    try_runtime!(self.check_vector_index(&vec_ref, idx1));
+                    try_runtime!(self.check_vector_index(&vec_ref, idx2));
+                    if vec_ref.vector_swap(idx1, idx2).is_none() {
+                        return Ok(Err(VMRuntimeError {
+                            loc: self.execution_stack.location()?,
+                            err: VMErrorKind::TypeError,
+                        }));
+                    }
+                }
                 Bytecode::BorrowGlobal(idx, _) => {
                     let address = try_runtime!(self.execution_stack.pop_as::<AccountAddress>());
                     let curr_module = self.execution_stack.top_frame()?.module();
@@ -624,7 +778,9 @@ where
     pub(crate) fn setup_main_args(&mut self, args: Vec<TransactionArgument>) {
         for arg in args.into_iter() {
             self.execution_stack.push(match arg {
+                TransactionArgument::U8(i) => Local::u8(i),
                 TransactionArgument::U64(i) => Local::u64(i),
+                TransactionArgument::U128(i) => Local::u128(i),
                 TransactionArgument::Address(a) => Local::address(a),
                 TransactionArgument::ByteArray(b) => Local::bytearray(b),
                 TransactionArgument::String(s) => Local::string(s),
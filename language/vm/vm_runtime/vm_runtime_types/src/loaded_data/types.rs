@@ -14,11 +14,14 @@ mod type_prop_tests;
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Type {
     Bool,
+    U8,
     U64,
+    U128,
     String,
     ByteArray,
     Address,
     Struct(StructDef),
+    Vector(Box<Type>),
     Reference(Box<Type>),
     MutableReference(Box<Type>),
 }
@@ -51,6 +54,13 @@ impl CanonicalSerialize for Type {
                 ty.serialize(serializer)?;
                 serializer
             }
+            Vector(ty) => {
+                serializer.encode_u8(0x09)?;
+                ty.serialize(serializer)?;
+                serializer
+            }
+            U8 => serializer.encode_u8(0x0A)?,
+            U128 => serializer.encode_u8(0x0B)?,
         };
         Ok(())
     }
@@ -72,6 +82,9 @@ impl CanonicalDeserialize for Type {
             0x06 => Struct(StructDef::deserialize(deserializer)?),
             0x07 => Reference(Box::new(Type::deserialize(deserializer)?)),
             0x08 => MutableReference(Box::new(Type::deserialize(deserializer)?)),
+            0x09 => Vector(Box::new(Type::deserialize(deserializer)?)),
+            0x0A => U8,
+            0x0B => U128,
             other => bail!(
                 "Error while deserializing type: found unexpected tag {:#x}",
                 other
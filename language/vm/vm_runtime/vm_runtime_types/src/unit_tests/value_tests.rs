@@ -190,3 +190,49 @@ fn test_simple_global_ref_err() {
     // error on another ReleaseRef
     assert!(root.release_reference().is_err());
 }
+
+#[test]
+fn test_integer_binop() {
+    let add = |l: IntegerValue, r: IntegerValue| l.binop(r, u128::checked_add);
+    let sub = |l: IntegerValue, r: IntegerValue| l.binop(r, u128::checked_sub);
+
+    assert_eq!(
+        add(IntegerValue::U8(200), IntegerValue::U8(55)),
+        Some(IntegerValue::U8(255))
+    );
+    assert_eq!(add(IntegerValue::U8(200), IntegerValue::U8(56)), None);
+    assert_eq!(sub(IntegerValue::U8(1), IntegerValue::U8(2)), None);
+    assert_eq!(
+        add(IntegerValue::U64(u64::max_value()), IntegerValue::U64(1)),
+        None
+    );
+    assert_eq!(
+        add(IntegerValue::U128(u128::from(u64::max_value())), IntegerValue::U128(1)),
+        Some(IntegerValue::U128(u128::from(u64::max_value()) + 1))
+    );
+    assert_eq!(
+        add(IntegerValue::U128(u128::max_value()), IntegerValue::U128(1)),
+        None
+    );
+    // Operands of different widths are rejected.
+    assert_eq!(add(IntegerValue::U8(1), IntegerValue::U64(1)), None);
+    assert_eq!(
+        IntegerValue::U8(1).compare(IntegerValue::U128(1), |l, r| l == r),
+        None
+    );
+}
+
+#[test]
+fn test_integer_casts() {
+    assert_eq!(IntegerValue::cast_u8(255), Some(IntegerValue::U8(255)));
+    assert_eq!(IntegerValue::cast_u8(256), None);
+    assert_eq!(
+        IntegerValue::cast_u64(u128::from(u64::max_value())),
+        Some(IntegerValue::U64(u64::max_value()))
+    );
+    assert_eq!(IntegerValue::cast_u64(u128::from(u64::max_value()) + 1), None);
+    assert_eq!(
+        IntegerValue::cast_u128(u128::max_value()),
+        Some(IntegerValue::U128(u128::max_value()))
+    );
+}
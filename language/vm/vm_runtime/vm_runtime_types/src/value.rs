@@ -4,6 +4,7 @@
 use crate::loaded_data::{struct_def::StructDef, types::Type};
 use std::{
     cell::{Ref, RefCell},
+    convert::TryFrom,
     ops::Add,
     rc::Rc,
 };
@@ -34,17 +35,20 @@ mod vm_types;
 #[derive(Debug, Clone)]
 pub enum Value {
     Address(AccountAddress),
+    U8(u8),
     U64(u64),
+    U128(u128),
     Bool(bool),
     String(String),
     Struct(Vec<MutVal>),
     ByteArray(ByteArray),
+    Vector(Vec<MutVal>),
 }
 
 impl Value {
     fn size(&self) -> AbstractMemorySize<GasCarrier> {
         match self {
-            Value::U64(_) | Value::Bool(_) => *CONST_SIZE,
+            Value::U8(_) | Value::U64(_) | Value::U128(_) | Value::Bool(_) => *CONST_SIZE,
             Value::Address(_) => AbstractMemorySize::new(ADDRESS_LENGTH as u64),
             // Possible debate topic: Should we charge based upon the size of the string.
             // At this moment, we take the view that you should be charged as though you are
@@ -57,6 +61,9 @@ impl Value {
                 .iter()
                 .fold(*STRUCT_SIZE, |acc, vl| acc.map2(vl.size(), Add::add)),
             Value::ByteArray(key) => AbstractMemorySize::new(key.len() as u64),
+            Value::Vector(vals) => vals
+                .iter()
+                .fold(*STRUCT_SIZE, |acc, vl| acc.map2(vl.size(), Add::add)),
         }
     }
 
@@ -77,10 +84,13 @@ impl Value {
                 match val {
                     Value::Bool(_) => Type::Bool,
                     Value::Address(_) => Type::Address,
+                    Value::U8(_) => Type::U8,
                     Value::U64(_) => Type::U64,
+                    Value::U128(_) => Type::U128,
                     Value::String(_) => Type::String,
                     Value::ByteArray(_) => Type::ByteArray,
                     Value::Struct(_) => Type::Struct(val.to_struct_def_FOR_TESTING()),
+                    Value::Vector(_) => panic!("Vector fields are not supported {:?}", val),
                 }
             })
             .collect();
@@ -94,7 +104,9 @@ impl Value {
         Ok(match (self, v2) {
             (Value::Bool(b1), Value::Bool(b2)) => b1 == b2,
             (Value::Address(a1), Value::Address(a2)) => a1 == a2,
+            (Value::U8(u1), Value::U8(u2)) => u1 == u2,
             (Value::U64(u1), Value::U64(u2)) => u1 == u2,
+            (Value::U128(u1), Value::U128(u2)) => u1 == u2,
             (Value::String(s1), Value::String(s2)) => s1 == s2,
             (Value::Struct(s1), Value::Struct(s2)) => {
                 if s1.len() != s2.len() {
@@ -108,6 +120,17 @@ impl Value {
                 true
             }
             (Value::ByteArray(ba1), Value::ByteArray(ba2)) => ba1 == ba2,
+            (Value::Vector(v1), Value::Vector(v2)) => {
+                if v1.len() != v2.len() {
+                    return Ok(false);
+                }
+                for (mv1, mv2) in v1.iter().zip(v2) {
+                    if !MutVal::equals(mv1, mv2)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
             _ => return Err(VMInvariantViolation::InternalTypeError),
         })
     }
@@ -118,7 +141,9 @@ impl Value {
         Ok(match (self, v2) {
             (Value::Bool(b1), Value::Bool(b2)) => b1 != b2,
             (Value::Address(a1), Value::Address(a2)) => a1 != a2,
+            (Value::U8(u1), Value::U8(u2)) => u1 != u2,
             (Value::U64(u1), Value::U64(u2)) => u1 != u2,
+            (Value::U128(u1), Value::U128(u2)) => u1 != u2,
             (Value::String(s1), Value::String(s2)) => s1 != s2,
             (Value::Struct(s1), Value::Struct(s2)) => {
                 if s1.len() != s2.len() {
@@ -132,18 +157,112 @@ impl Value {
                 false
             }
             (Value::ByteArray(ba1), Value::ByteArray(ba2)) => ba1 != ba2,
+            (Value::Vector(v1), Value::Vector(v2)) => {
+                if v1.len() != v2.len() {
+                    return Ok(true);
+                }
+                for (mv1, mv2) in v1.iter().zip(v2) {
+                    if MutVal::not_equals(mv1, mv2)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
             _ => return Err(VMInvariantViolation::InternalTypeError),
         })
     }
 }
 
+/// An unsigned integer of any of the widths supported by the VM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegerValue {
+    U8(u8),
+    U64(u64),
+    U128(u128),
+}
+
+impl IntegerValue {
+    /// Returns the value of the integer as a u128.
+    pub fn value(self) -> u128 {
+        match self {
+            IntegerValue::U8(i) => u128::from(i),
+            IntegerValue::U64(i) => u128::from(i),
+            IntegerValue::U128(i) => i,
+        }
+    }
+
+    /// Applies `f` to two integers of the same width. The operation is performed on u128 values
+    /// and the result is checked to fit in the width of the operands, so `f` only has to detect
+    /// overflows of u128.
+    ///
+    /// Returns `None` if the operands have different widths or if the operation overflows.
+    pub fn binop<F>(self, other: IntegerValue, f: F) -> Option<IntegerValue>
+    where
+        F: FnOnce(u128, u128) -> Option<u128>,
+    {
+        let result = f(self.value(), other.value())?;
+        match (self, other) {
+            (IntegerValue::U8(_), IntegerValue::U8(_)) => IntegerValue::cast_u8(result),
+            (IntegerValue::U64(_), IntegerValue::U64(_)) => IntegerValue::cast_u64(result),
+            (IntegerValue::U128(_), IntegerValue::U128(_)) => Some(IntegerValue::U128(result)),
+            _ => None,
+        }
+    }
+
+    /// Compares two integers of the same width with `f`.
+    ///
+    /// Returns `None` if the operands have different widths.
+    pub fn compare<F>(self, other: IntegerValue, f: F) -> Option<bool>
+    where
+        F: FnOnce(u128, u128) -> bool,
+    {
+        match (self, other) {
+            (IntegerValue::U8(_), IntegerValue::U8(_))
+            | (IntegerValue::U64(_), IntegerValue::U64(_))
+            | (IntegerValue::U128(_), IntegerValue::U128(_)) => {
+                Some(f(self.value(), other.value()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts `value` into a u8, returns `None` if it does not fit.
+    pub fn cast_u8(value: u128) -> Option<IntegerValue> {
+        u8::try_from(value).ok().map(IntegerValue::U8)
+    }
+
+    /// Converts `value` into a u64, returns `None` if it does not fit.
+    pub fn cast_u64(value: u128) -> Option<IntegerValue> {
+        u64::try_from(value).ok().map(IntegerValue::U64)
+    }
+
+    /// Converts `value` into a u128, this never fails.
+    pub fn cast_u128(value: u128) -> Option<IntegerValue> {
+        Some(IntegerValue::U128(value))
+    }
+}
+
+impl From<IntegerValue> for Local {
+    fn from(value: IntegerValue) -> Local {
+        match value {
+            IntegerValue::U8(i) => Local::u8(i),
+            IntegerValue::U64(i) => Local::u64(i),
+            IntegerValue::U128(i) => Local::u128(i),
+        }
+    }
+}
+
 pub trait Reference
 where
     Self: std::marker::Sized + Clone,
 {
     fn borrow_field(&self, idx: u32) -> Option<Self>;
+    fn borrow_element(&self, idx: u64) -> Option<Self>;
     fn read_reference(self) -> MutVal;
     fn mutate_reference(self, v: MutVal);
+    fn vector_push(self, v: MutVal) -> Option<()>;
+    fn vector_pop(self) -> Option<MutVal>;
+    fn vector_swap(self, idx1: u64, idx2: u64) -> Option<()>;
 
     fn size(&self) -> AbstractMemorySize<GasCarrier>;
 }
@@ -231,10 +350,18 @@ impl MutVal {
         MutVal::new(Value::Address(addr))
     }
 
+    fn u8(i: u8) -> Self {
+        MutVal::new(Value::U8(i))
+    }
+
     fn u64(i: u64) -> Self {
         MutVal::new(Value::U64(i))
     }
 
+    fn u128(i: u128) -> Self {
+        MutVal::new(Value::U128(i))
+    }
+
     fn bool(b: bool) -> Self {
         MutVal::new(Value::Bool(b))
     }
@@ -251,6 +378,24 @@ impl MutVal {
         MutVal::new(Value::ByteArray(v))
     }
 
+    fn vector(v: Vec<MutVal>) -> Self {
+        MutVal::new(Value::Vector(v))
+    }
+
+    fn vector_len(&self) -> Option<u64> {
+        match &*self.peek() {
+            Value::Vector(ref vec) => Some(vec.len() as u64),
+            _ => None,
+        }
+    }
+
+    fn vector_last_size(&self) -> Option<AbstractMemorySize<GasCarrier>> {
+        match &*self.peek() {
+            Value::Vector(ref vec) => Some(vec.last().map_or(*CONST_SIZE, MutVal::size)),
+            _ => None,
+        }
+    }
+
     fn size(&self) -> AbstractMemorySize<GasCarrier> {
         self.peek().size()
     }
@@ -277,6 +422,13 @@ impl Reference for MutVal {
         }
     }
 
+    fn borrow_element(&self, idx: u64) -> Option<Self> {
+        match &*self.peek() {
+            Value::Vector(ref vec) => vec.get(idx as usize).map(MutVal::shallow_clone),
+            _ => None,
+        }
+    }
+
     fn read_reference(self) -> MutVal {
         self.clone()
     }
@@ -285,6 +437,36 @@ impl Reference for MutVal {
         self.0.replace(v.peek().clone());
     }
 
+    fn vector_push(self, v: MutVal) -> Option<()> {
+        match &mut *self.0.borrow_mut() {
+            Value::Vector(ref mut vec) => {
+                vec.push(v);
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    fn vector_pop(self) -> Option<MutVal> {
+        match &mut *self.0.borrow_mut() {
+            Value::Vector(ref mut vec) => vec.pop(),
+            _ => None,
+        }
+    }
+
+    fn vector_swap(self, idx1: u64, idx2: u64) -> Option<()> {
+        match &mut *self.0.borrow_mut() {
+            Value::Vector(ref mut vec) => {
+                if idx1 as usize >= vec.len() || idx2 as usize >= vec.len() {
+                    return None;
+                }
+                vec.swap(idx1 as usize, idx2 as usize);
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
     fn size(&self) -> AbstractMemorySize<GasCarrier> {
         words_in(*REFERENCE_SIZE)
     }
@@ -295,10 +477,18 @@ impl Local {
         Local::Value(MutVal::address(addr))
     }
 
+    pub fn u8(i: u8) -> Self {
+        Local::Value(MutVal::u8(i))
+    }
+
     pub fn u64(i: u64) -> Self {
         Local::Value(MutVal::u64(i))
     }
 
+    pub fn u128(i: u128) -> Self {
+        Local::Value(MutVal::u128(i))
+    }
+
     pub fn bool(b: bool) -> Self {
         Local::Value(MutVal::bool(b))
     }
@@ -315,6 +505,10 @@ impl Local {
         Local::Value(MutVal::bytearray(v))
     }
 
+    pub fn vector(v: Vec<MutVal>) -> Self {
+        Local::Value(MutVal::vector(v))
+    }
+
     pub fn borrow_local(&self) -> Option<Self> {
         match self {
             Local::Value(v) => Some(Local::Ref(v.shallow_clone())),
@@ -330,6 +524,57 @@ impl Local {
         }
     }
 
+    pub fn borrow_element(&self, idx: u64) -> Option<Self> {
+        match self {
+            Local::Ref(v) => v.borrow_element(idx).map(Local::Ref),
+            Local::GlobalRef(v) => v.borrow_element(idx).map(Local::GlobalRef),
+            _ => None,
+        }
+    }
+
+    /// Returns the length of the vector this local refers to without consuming the reference.
+    pub fn vector_len(&self) -> Option<u64> {
+        match self {
+            Local::Ref(r) => r.vector_len(),
+            Local::GlobalRef(gr) => gr.reference.vector_len(),
+            _ => None,
+        }
+    }
+
+    /// Returns the size of the last element of the vector this local refers to, or the size of a
+    /// constant if the vector is empty, without consuming the reference.
+    pub fn vector_last_size(&self) -> Option<AbstractMemorySize<GasCarrier>> {
+        match self {
+            Local::Ref(r) => r.vector_last_size(),
+            Local::GlobalRef(gr) => gr.reference.vector_last_size(),
+            _ => None,
+        }
+    }
+
+    pub fn vector_push(self, v: MutVal) -> Option<()> {
+        match self {
+            Local::Ref(r) => r.vector_push(v),
+            Local::GlobalRef(gr) => gr.vector_push(v),
+            _ => None,
+        }
+    }
+
+    pub fn vector_pop(self) -> Option<Self> {
+        match self {
+            Local::Ref(r) => r.vector_pop().map(Local::Value),
+            Local::GlobalRef(gr) => gr.vector_pop().map(Local::Value),
+            _ => None,
+        }
+    }
+
+    pub fn vector_swap(self, idx1: u64, idx2: u64) -> Option<()> {
+        match self {
+            Local::Ref(r) => r.vector_swap(idx1, idx2),
+            Local::GlobalRef(gr) => gr.vector_swap(idx1, idx2),
+            _ => None,
+        }
+    }
+
     pub fn read_reference(self) -> Option<Self> {
         match self {
             Local::Ref(r) => Some(Local::Value(r.read_reference())),
@@ -579,6 +824,19 @@ impl Reference for GlobalRef {
         }
     }
 
+    fn borrow_element(&self, idx: u64) -> Option<Self> {
+        match &*self.reference.peek() {
+            Value::Vector(ref vec) => match vec.get(idx as usize) {
+                Some(elem_ref) => {
+                    self.root.borrow_mut().dec_ref_count();
+                    Some(GlobalRef::new_ref(self, elem_ref.shallow_clone()))
+                }
+                None => None,
+            },
+            _ => None,
+        }
+    }
+
     fn read_reference(self) -> MutVal {
         self.root.borrow_mut().dec_ref_count();
         self.reference.clone()
@@ -590,6 +848,24 @@ impl Reference for GlobalRef {
         self.reference.mutate_reference(v);
     }
 
+    fn vector_push(self, v: MutVal) -> Option<()> {
+        self.root.borrow_mut().dec_ref_count();
+        self.root.borrow_mut().mark_dirty();
+        self.reference.vector_push(v)
+    }
+
+    fn vector_pop(self) -> Option<MutVal> {
+        self.root.borrow_mut().dec_ref_count();
+        self.root.borrow_mut().mark_dirty();
+        self.reference.vector_pop()
+    }
+
+    fn vector_swap(self, idx1: u64, idx2: u64) -> Option<()> {
+        self.root.borrow_mut().dec_ref_count();
+        self.root.borrow_mut().mark_dirty();
+        self.reference.vector_swap(idx1, idx2)
+    }
+
     fn size(&self) -> AbstractMemorySize<GasCarrier> {
         words_in(*REFERENCE_SIZE)
     }
@@ -599,6 +875,15 @@ impl Reference for GlobalRef {
 // Conversion routines for the interpreter
 //
 
+impl From<MutVal> for Option<u8> {
+    fn from(value: MutVal) -> Option<u8> {
+        match &*value.peek() {
+            Value::U8(i) => Some(*i),
+            _ => None,
+        }
+    }
+}
+
 impl From<MutVal> for Option<u64> {
     fn from(value: MutVal) -> Option<u64> {
         match &*value.peek() {
@@ -608,6 +893,26 @@ impl From<MutVal> for Option<u64> {
     }
 }
 
+impl From<MutVal> for Option<u128> {
+    fn from(value: MutVal) -> Option<u128> {
+        match &*value.peek() {
+            Value::U128(i) => Some(*i),
+            _ => None,
+        }
+    }
+}
+
+impl From<MutVal> for Option<IntegerValue> {
+    fn from(value: MutVal) -> Option<IntegerValue> {
+        match &*value.peek() {
+            Value::U8(i) => Some(IntegerValue::U8(*i)),
+            Value::U64(i) => Some(IntegerValue::U64(*i)),
+            Value::U128(i) => Some(IntegerValue::U128(*i)),
+            _ => None,
+        }
+    }
+}
+
 impl From<MutVal> for Option<bool> {
     fn from(value: MutVal) -> Option<bool> {
         match &*value.peek() {
@@ -30,86 +30,79 @@ fn deserialize_struct(
 ) -> VMRuntimeResult<Value> {
     let mut s_vals: Vec<MutVal> = Vec::new();
     for field_type in struct_def.field_definitions() {
-        match field_type {
-            Type::Bool => {
-                if let Ok(b) = deserializer.decode_bool() {
-                    s_vals.push(MutVal::new(Value::Bool(b)));
-                } else {
-                    return Err(VMRuntimeError {
-                        loc: Location::new(),
-                        err: VMErrorKind::DataFormatError,
-                    });
-                }
+        s_vals.push(MutVal::new(deserialize_value(deserializer, field_type)?));
+    }
+    Ok(Value::Struct(s_vals))
+}
+
+fn deserialize_value(deserializer: &mut SimpleDeserializer, ty: &Type) -> VMRuntimeResult<Value> {
+    match ty {
+        Type::Bool => {
+            if let Ok(b) = deserializer.decode_bool() {
+                return Ok(Value::Bool(b));
             }
-            Type::U64 => {
-                if let Ok(val) = deserializer.decode_u64() {
-                    s_vals.push(MutVal::new(Value::U64(val)));
-                } else {
-                    return Err(VMRuntimeError {
-                        loc: Location::new(),
-                        err: VMErrorKind::DataFormatError,
-                    });
-                }
+        }
+        Type::U8 => {
+            if let Ok(val) = deserializer.decode_u8() {
+                return Ok(Value::U8(val));
             }
-            Type::String => {
-                if let Ok(bytes) = deserializer.decode_variable_length_bytes() {
-                    if let Ok(s) = String::from_utf8(bytes) {
-                        s_vals.push(MutVal::new(Value::String(s)));
-                        continue;
-                    }
-                }
-                return Err(VMRuntimeError {
-                    loc: Location::new(),
-                    err: VMErrorKind::DataFormatError,
-                });
-            }
-            Type::ByteArray => {
-                if let Ok(bytes) = deserializer.decode_variable_length_bytes() {
-                    s_vals.push(MutVal::new(Value::ByteArray(ByteArray::new(bytes))));
-                    continue;
-                }
-                return Err(VMRuntimeError {
-                    loc: Location::new(),
-                    err: VMErrorKind::DataFormatError,
-                });
-            }
-            Type::Address => {
-                if let Ok(bytes) = deserializer.decode_variable_length_bytes() {
-                    if let Ok(addr) = AccountAddress::try_from(bytes) {
-                        s_vals.push(MutVal::new(Value::Address(addr)));
-                        continue;
-                    }
+        }
+        Type::U64 => {
+            if let Ok(val) = deserializer.decode_u64() {
+                return Ok(Value::U64(val));
+            }
+        }
+        Type::U128 => {
+            if let Ok(val) = deserializer.decode_u128() {
+                return Ok(Value::U128(val));
+            }
+        }
+        Type::String => {
+            if let Ok(bytes) = deserializer.decode_variable_length_bytes() {
+                if let Ok(s) = String::from_utf8(bytes) {
+                    return Ok(Value::String(s));
                 }
-                return Err(VMRuntimeError {
-                    loc: Location::new(),
-                    err: VMErrorKind::DataFormatError,
-                });
-            }
-            Type::Struct(s_fields) => {
-                if let Ok(s) = deserialize_struct(deserializer, s_fields) {
-                    s_vals.push(MutVal::new(s));
-                } else {
-                    return Err(VMRuntimeError {
-                        loc: Location::new(),
-                        err: VMErrorKind::DataFormatError,
-                    });
+            }
+        }
+        Type::ByteArray => {
+            if let Ok(bytes) = deserializer.decode_variable_length_bytes() {
+                return Ok(Value::ByteArray(ByteArray::new(bytes)));
+            }
+        }
+        Type::Address => {
+            if let Ok(bytes) = deserializer.decode_variable_length_bytes() {
+                if let Ok(addr) = AccountAddress::try_from(bytes) {
+                    return Ok(Value::Address(addr));
                 }
             }
-            Type::Reference(_) => {
-                return Err(VMRuntimeError {
-                    loc: Location::new(),
-                    err: VMErrorKind::InvalidData,
-                })
+        }
+        Type::Struct(s_fields) => {
+            if let Ok(s) = deserialize_struct(deserializer, s_fields) {
+                return Ok(s);
             }
-            Type::MutableReference(_) => {
-                return Err(VMRuntimeError {
-                    loc: Location::new(),
-                    err: VMErrorKind::InvalidData,
-                })
+        }
+        Type::Vector(elem_type) => {
+            // Vectors are serialized the same way `encode_vec` does: a u32 length prefix followed
+            // by the elements.
+            if let Ok(len) = deserializer.decode_u32() {
+                let mut elems: Vec<MutVal> = Vec::new();
+                for _ in 0..len {
+                    elems.push(MutVal::new(deserialize_value(deserializer, elem_type)?));
+                }
+                return Ok(Value::Vector(elems));
             }
         }
+        Type::Reference(_) | Type::MutableReference(_) => {
+            return Err(VMRuntimeError {
+                loc: Location::new(),
+                err: VMErrorKind::InvalidData,
+            })
+        }
     }
-    Ok(Value::Struct(s_vals))
+    Err(VMRuntimeError {
+        loc: Location::new(),
+        err: VMErrorKind::DataFormatError,
+    })
 }
 
 impl CanonicalSerialize for Value {
@@ -123,9 +116,15 @@ impl CanonicalSerialize for Value {
             Value::Bool(b) => {
                 serializer.encode_bool(*b)?;
             }
+            Value::U8(val) => {
+                serializer.encode_u8(*val)?;
+            }
             Value::U64(val) => {
                 serializer.encode_u64(*val)?;
             }
+            Value::U128(val) => {
+                serializer.encode_u128(*val)?;
+            }
             Value::String(s) => {
                 // TODO: must define an api for canonical serializations of string.
                 // Right now we are just using Rust to serialize the string
@@ -139,6 +138,12 @@ impl CanonicalSerialize for Value {
             Value::ByteArray(bytearray) => {
                 serializer.encode_variable_length_bytes(bytearray.as_bytes())?;
             }
+            Value::Vector(vals) => {
+                serializer.encode_u32(vals.len() as u32)?;
+                for mut_val in vals {
+                    (*mut_val.peek()).serialize(serializer)?;
+                }
+            }
         }
         Ok(())
     }
@@ -7,7 +7,10 @@ use admission_control_proto::proto::admission_control_grpc::{
 use admission_control_service::admission_control_service::AdmissionControlService;
 use config::config::{NodeConfig, RoleType};
 use consensus::consensus_provider::{make_consensus_provider, ConsensusProvider};
-use debug_interface::{node_debug_service::NodeDebugService, proto::node_debug_interface_grpc};
+use debug_interface::{
+    node_debug_service::{ConsensusDebugInfo, NodeDebugService},
+    proto::node_debug_interface_grpc,
+};
 use execution_proto::proto::execution_grpc;
 use execution_service::ExecutionService;
 use grpc_helpers::ServerHandle;
@@ -20,6 +23,7 @@ use network::{
     validator_network::{
         network_builder::{NetworkBuilder, TransportType},
         ConsensusNetworkEvents, ConsensusNetworkSender, MempoolNetworkEvents, MempoolNetworkSender,
+        CONSENSUS_BATCH_DIRECT_SEND_PROTOCOL, CONSENSUS_BATCH_RPC_PROTOCOL,
         CONSENSUS_DIRECT_SEND_PROTOCOL, CONSENSUS_RPC_PROTOCOL, MEMPOOL_DIRECT_SEND_PROTOCOL,
     },
     NetworkPublicKeys, ProtocolId,
@@ -131,11 +135,17 @@ fn setup_executor(config: &NodeConfig) -> ::grpcio::Server {
         .expect("Unable to create grpc server")
 }
 
-fn setup_debug_interface(config: &NodeConfig) -> ::grpcio::Server {
+fn setup_debug_interface(
+    config: &NodeConfig,
+    consensus_debug_info: Option<Arc<dyn ConsensusDebugInfo>>,
+) -> ::grpcio::Server {
     let env = Arc::new(EnvBuilder::new().name_prefix("grpc-debug-").build());
     // Start Debug interface
-    let debug_service =
-        node_debug_interface_grpc::create_node_debug_interface(NodeDebugService::new());
+    let debug_service = match consensus_debug_info {
+        Some(consensus_debug_info) => NodeDebugService::new_with_consensus(consensus_debug_info),
+        None => NodeDebugService::new(),
+    };
+    let debug_service = node_debug_interface_grpc::create_node_debug_interface(debug_service);
     ::grpcio::ServerBuilder::new(env)
         .register_service(debug_service)
         .bind(
@@ -209,13 +219,19 @@ pub fn setup_network(
         .consensus_protocols(vec![
             ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL),
             ProtocolId::from_static(CONSENSUS_DIRECT_SEND_PROTOCOL),
+            ProtocolId::from_static(CONSENSUS_BATCH_RPC_PROTOCOL),
+            ProtocolId::from_static(CONSENSUS_BATCH_DIRECT_SEND_PROTOCOL),
         ])
         .mempool_protocols(vec![ProtocolId::from_static(MEMPOOL_DIRECT_SEND_PROTOCOL)])
         .direct_send_protocols(vec![
             ProtocolId::from_static(CONSENSUS_DIRECT_SEND_PROTOCOL),
+            ProtocolId::from_static(CONSENSUS_BATCH_DIRECT_SEND_PROTOCOL),
             ProtocolId::from_static(MEMPOOL_DIRECT_SEND_PROTOCOL),
         ])
-        .rpc_protocols(vec![ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL)])
+        .rpc_protocols(vec![
+            ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL),
+            ProtocolId::from_static(CONSENSUS_BATCH_RPC_PROTOCOL),
+        ])
         .build();
 
     (
@@ -283,7 +299,10 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> (AdmissionControlClien
         debug!("Consensus started in {} ms", instant.elapsed().as_millis());
     }
 
-    let debug_if = ServerHandle::setup(setup_debug_interface(&node_config));
+    let consensus_debug_info = consensus
+        .as_ref()
+        .and_then(|consensus| consensus.debug_info());
+    let debug_if = ServerHandle::setup(setup_debug_interface(&node_config, consensus_debug_info));
 
     let metrics_port = node_config.debug_interface.metrics_server_port;
     let metric_host = node_config.debug_interface.address.clone();
@@ -0,0 +1,111 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use failure::prelude::*;
+use proto_conv::{FromProto, IntoProto};
+
+/// Percentiles of the gas unit prices of a set of transactions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasPricePercentiles {
+    /// Number of transactions in the set
+    pub num_transactions: u64,
+    /// Lowest gas unit price
+    pub min: u64,
+    /// 25th percentile of gas unit prices
+    pub p25: u64,
+    /// Median gas unit price
+    pub p50: u64,
+    /// 75th percentile of gas unit prices
+    pub p75: u64,
+    /// 90th percentile of gas unit prices
+    pub p90: u64,
+    /// Highest gas unit price
+    pub max: u64,
+}
+
+impl GasPricePercentiles {
+    /// Computes percentiles (nearest-rank) of given gas unit prices
+    /// All of them are 0 if there's no gas price
+    pub(crate) fn new(mut gas_prices: Vec<u64>) -> Self {
+        if gas_prices.is_empty() {
+            return Self::default();
+        }
+        gas_prices.sort();
+        let percentile = |p: usize| gas_prices[(gas_prices.len() * p + 99) / 100 - 1];
+        Self {
+            num_transactions: gas_prices.len() as u64,
+            min: gas_prices[0],
+            p25: percentile(25),
+            p50: percentile(50),
+            p75: percentile(75),
+            p90: percentile(90),
+            max: gas_prices[gas_prices.len() - 1],
+        }
+    }
+}
+
+/// Gas unit prices observed by Mempool
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasPriceEstimate {
+    /// Gas unit prices of transactions that are ready to be included in next block
+    pub pending: GasPricePercentiles,
+    /// Gas unit prices of recently committed transactions
+    pub recently_included: GasPricePercentiles,
+}
+
+//***********************************
+// Decoding/Encoding to Protobuffers
+//***********************************
+impl IntoProto for GasPricePercentiles {
+    type ProtoType = crate::proto::shared::gas_price::GasPricePercentiles;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_num_transactions(self.num_transactions);
+        proto.set_min(self.min);
+        proto.set_p25(self.p25);
+        proto.set_p50(self.p50);
+        proto.set_p75(self.p75);
+        proto.set_p90(self.p90);
+        proto.set_max(self.max);
+        proto
+    }
+}
+
+impl FromProto for GasPricePercentiles {
+    type ProtoType = crate::proto::shared::gas_price::GasPricePercentiles;
+
+    fn from_proto(proto: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            num_transactions: proto.get_num_transactions(),
+            min: proto.get_min(),
+            p25: proto.get_p25(),
+            p50: proto.get_p50(),
+            p75: proto.get_p75(),
+            p90: proto.get_p90(),
+            max: proto.get_max(),
+        })
+    }
+}
+
+impl IntoProto for GasPriceEstimate {
+    type ProtoType = crate::proto::shared::gas_price::GasPriceEstimate;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_pending(self.pending.into_proto());
+        proto.set_recently_included(self.recently_included.into_proto());
+        proto
+    }
+}
+
+impl FromProto for GasPriceEstimate {
+    type ProtoType = crate::proto::shared::gas_price::GasPriceEstimate;
+
+    fn from_proto(mut proto: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            pending: GasPricePercentiles::from_proto(proto.take_pending())?,
+            recently_included: GasPricePercentiles::from_proto(proto.take_recently_included())?,
+        })
+    }
+}
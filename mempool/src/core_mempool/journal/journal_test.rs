@@ -0,0 +1,62 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::core_mempool::unit_tests::common::TestTransaction;
+use tempfile::tempdir;
+
+#[test]
+fn test_put_get_delete() {
+    let tmp_dir = tempdir().unwrap();
+    let journal = MempoolJournal::new(&tmp_dir);
+    assert!(journal.get_all().unwrap().is_empty());
+
+    let txns: Vec<_> = vec![
+        TestTransaction::new(0, 1, 1),
+        TestTransaction::new(0, 0, 1),
+        TestTransaction::new(1, 0, 1),
+    ]
+    .into_iter()
+    .map(|t| t.make_signed_transaction())
+    .collect();
+    for txn in &txns {
+        journal.put(txn).unwrap();
+    }
+    let journaled = journal.get_all().unwrap();
+    assert_eq!(journaled.len(), 3);
+    for txn in &txns {
+        assert!(journaled.contains(txn));
+    }
+
+    journal
+        .delete(&[(TestTransaction::get_address(0), 0)])
+        .unwrap();
+    let journaled = journal.get_all().unwrap();
+    assert_eq!(journaled.len(), 2);
+    assert!(!journaled.contains(&txns[1]));
+}
+
+#[test]
+fn test_overwrite() {
+    let tmp_dir = tempdir().unwrap();
+    let journal = MempoolJournal::new(&tmp_dir);
+
+    journal
+        .put(&TestTransaction::new(0, 0, 1).make_signed_transaction())
+        .unwrap();
+    let replacement = TestTransaction::new(0, 0, 5).make_signed_transaction();
+    journal.put(&replacement).unwrap();
+    assert_eq!(journal.get_all().unwrap(), vec![replacement]);
+}
+
+#[test]
+fn test_reopen() {
+    let tmp_dir = tempdir().unwrap();
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    {
+        let journal = MempoolJournal::new(&tmp_dir);
+        journal.put(&txn).unwrap();
+    }
+    let journal = MempoolJournal::new(&tmp_dir);
+    assert_eq!(journal.get_all().unwrap(), vec![txn]);
+}
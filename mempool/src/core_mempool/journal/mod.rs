@@ -0,0 +1,186 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! MempoolJournal persists transactions accepted by mempool on disk, so pending transactions
+//! survive node restart. On startup journaled transactions are re-validated and added back to
+//! mempool (see `shared_mempool`)
+//!
+//! Updates are only queued by the caller, which usually holds the mempool lock, and written by a
+//! dedicated thread that batches all updates queued since its previous write
+
+#[cfg(test)]
+mod journal_test;
+mod schema;
+
+use crate::{
+    core_mempool::{
+        index::TxnPointer,
+        journal::schema::{TransactionSchema, TRANSACTION_CF_NAME},
+    },
+    OP_COUNTERS,
+};
+use failure::prelude::*;
+use logger::prelude::*;
+use schemadb::{
+    ColumnFamilyOptions, ColumnFamilyOptionsMap, ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME,
+};
+use std::{
+    fs, iter,
+    path::Path,
+    sync::{mpsc, Arc},
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+use types::transaction::SignedTransaction;
+
+/// max number of queued updates written to disk at once
+const MAX_WRITE_BATCH_SIZE: usize = 1000;
+
+enum JournalUpdate {
+    Put(SignedTransaction),
+    Delete(Vec<TxnPointer>),
+    /// acknowledged once all updates queued before it are written
+    Flush(mpsc::Sender<()>),
+}
+
+/// on-disk journal of transactions accepted by mempool
+pub struct MempoolJournal {
+    db: Arc<DB>,
+    update_sender: Option<mpsc::Sender<JournalUpdate>>,
+    writer_thread: Option<JoinHandle<()>>,
+}
+
+impl MempoolJournal {
+    pub(crate) fn new<P: AsRef<Path>>(db_root_path: P) -> Self {
+        let cf_opts_map: ColumnFamilyOptionsMap = [
+            (
+                /* UNUSED CF = */ DEFAULT_CF_NAME,
+                ColumnFamilyOptions::default(),
+            ),
+            (TRANSACTION_CF_NAME, ColumnFamilyOptions::default()),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let path = db_root_path.as_ref().join("mempooldb");
+        // storage dir might not be created yet, when mempool starts before storage service
+        fs::create_dir_all(&path).unwrap_or_else(|e| {
+            panic!(
+                "Unable to create MempoolJournal dir {:?} due to {:?}",
+                path, e
+            )
+        });
+        let instant = Instant::now();
+        let db = Arc::new(DB::open(path.clone(), cf_opts_map).unwrap_or_else(|e| {
+            panic!(
+                "MempoolJournal open failed due to {:?}, unable to continue",
+                e
+            )
+        }));
+
+        info!(
+            "Opened MempoolJournal at {:?} in {} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        let (update_sender, update_receiver) = mpsc::channel();
+        let writer_db = Arc::clone(&db);
+        let writer_thread = thread::Builder::new()
+            .name("mempool_journal".into())
+            .spawn(move || Self::write_updates(&writer_db, update_receiver))
+            .expect("Failed to create MempoolJournal writer thread");
+
+        Self {
+            db,
+            update_sender: Some(update_sender),
+            writer_thread: Some(writer_thread),
+        }
+    }
+
+    /// persists transaction
+    /// it overwrites journaled transaction with same sender and sequence number (if any)
+    pub(crate) fn put(&self, txn: &SignedTransaction) -> Result<()> {
+        self.send(JournalUpdate::Put(txn.clone()))
+    }
+
+    /// removes given transactions from journal
+    pub(crate) fn delete(&self, txns: &[TxnPointer]) -> Result<()> {
+        self.send(JournalUpdate::Delete(txns.to_vec()))
+    }
+
+    /// returns all journaled transactions ordered by sender and sequence number
+    /// all updates queued before the call are written first
+    pub(crate) fn get_all(&self) -> Result<Vec<SignedTransaction>> {
+        let (flushed_sender, flushed_receiver) = mpsc::channel();
+        self.send(JournalUpdate::Flush(flushed_sender))?;
+        flushed_receiver
+            .recv()
+            .map_err(|_| format_err!("MempoolJournal writer stopped before flush"))?;
+
+        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        iter.map(|res| res.map(|(_, txn)| txn)).collect()
+    }
+
+    fn send(&self, update: JournalUpdate) -> Result<()> {
+        self.update_sender
+            .as_ref()
+            .expect("MempoolJournal update sender is only taken on drop")
+            .send(update)
+            .map_err(|_| format_err!("MempoolJournal writer stopped"))
+    }
+
+    /// runs on writer thread until the journal is dropped
+    /// blocks for the next update and writes it together with the ones queued behind it
+    fn write_updates(db: &DB, update_receiver: mpsc::Receiver<JournalUpdate>) {
+        while let Ok(update) = update_receiver.recv() {
+            let mut batch = SchemaBatch::new();
+            let mut flushed_senders = vec![];
+            for update in iter::once(update)
+                .chain(update_receiver.try_iter())
+                .take(MAX_WRITE_BATCH_SIZE)
+            {
+                let res = match update {
+                    JournalUpdate::Put(txn) => {
+                        batch.put::<TransactionSchema>(&(txn.sender(), txn.sequence_number()), &txn)
+                    }
+                    JournalUpdate::Delete(txns) => txns
+                        .iter()
+                        .map(|txn| batch.delete::<TransactionSchema>(txn))
+                        .collect(),
+                    JournalUpdate::Flush(flushed_sender) => {
+                        flushed_senders.push(flushed_sender);
+                        Ok(())
+                    }
+                };
+                if let Err(e) = res {
+                    OP_COUNTERS.inc("journal.encode.failure");
+                    error!("[Mempool] Failed to encode journal update: {:?}", e);
+                }
+            }
+            if let Err(e) = db.write_schemas(batch) {
+                OP_COUNTERS.inc("journal.write.failure");
+                error!("[Mempool] Failed to write journal: {:?}", e);
+            }
+            for flushed_sender in flushed_senders {
+                // the caller might have given up waiting
+                let _ = flushed_sender.send(());
+            }
+        }
+    }
+}
+
+impl Drop for MempoolJournal {
+    /// writes all queued updates and waits for the writer thread, so the journal can be reopened
+    /// right away
+    fn drop(&mut self) {
+        self.update_sender.take();
+        if let Some(writer_thread) = self.writer_thread.take() {
+            if writer_thread.join().is_err() {
+                error!("[Mempool] MempoolJournal writer thread panicked");
+            }
+        }
+    }
+}
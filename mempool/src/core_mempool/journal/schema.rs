@@ -0,0 +1,73 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for transactions accepted by mempool.
+//!
+//! Serialized signed transaction bytes identified by sender and sequence number.
+//! ```text
+//! |<---------key--------->|<--value-->|
+//! | sender | sequence_num | txn bytes |
+//! ```
+//!
+//! `sequence_num` is serialized in big endian so that transactions of an account are stored in
+//! order of their sequence numbers.
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use failure::prelude::*;
+use proto_conv::{FromProtoBytes, IntoProtoBytes};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName,
+};
+use std::{convert::TryFrom, mem::size_of};
+use types::{
+    account_address::{AccountAddress, ADDRESS_LENGTH},
+    transaction::SignedTransaction,
+};
+
+pub(super) const TRANSACTION_CF_NAME: ColumnFamilyName = "transaction";
+
+define_schema!(
+    TransactionSchema,
+    Key,
+    SignedTransaction,
+    TRANSACTION_CF_NAME
+);
+
+type SeqNum = u64;
+type Key = (AccountAddress, SeqNum);
+
+impl KeyCodec<TransactionSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref address, seq_num) = *self;
+
+        let mut encoded = address.to_vec();
+        encoded.write_u64::<BigEndian>(seq_num)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == ADDRESS_LENGTH + size_of::<SeqNum>(),
+            "Unexpected data len {}, expected {}.",
+            data.len(),
+            ADDRESS_LENGTH + size_of::<SeqNum>(),
+        );
+        let address = AccountAddress::try_from(&data[..ADDRESS_LENGTH])?;
+        let seq_num = (&data[ADDRESS_LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((address, seq_num))
+    }
+}
+
+impl ValueCodec<TransactionSchema> for SignedTransaction {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.clone().into_proto_bytes()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::from_proto_bytes(data)
+    }
+}
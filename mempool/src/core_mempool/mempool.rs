@@ -7,11 +7,13 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::{
     core_mempool::{
+        gas_price::{GasPriceEstimate, GasPricePercentiles},
         index::TxnPointer,
+        journal::MempoolJournal,
         transaction::{MempoolAddTransactionStatus, MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
     },
-    proto::shared::mempool_status::MempoolAddTransactionStatusCode,
+    proto::shared::mempool_status::{MempoolAddTransactionStatusCode, MempoolTransactionStatus},
     OP_COUNTERS,
 };
 use chrono::Utc;
@@ -20,7 +22,7 @@ use logger::prelude::*;
 use lru_cache::LruCache;
 use std::{
     cmp::{max, min},
-    collections::HashSet,
+    collections::{HashSet, VecDeque},
 };
 use ttl_cache::TtlCache;
 use types::{account_address::AccountAddress, transaction::SignedTransaction};
@@ -36,17 +38,29 @@ pub struct Mempool {
     // by consensus
     pub(crate) metrics_cache: TtlCache<(AccountAddress, u64), i64>,
     pub system_transaction_timeout: Duration,
+
+    // gas prices of most recently committed transactions, oldest first
+    // used to estimate gas price for new transactions
+    recently_included_gas_prices: VecDeque<u64>,
+    gas_price_history_size: usize,
 }
 
 impl Mempool {
     pub(crate) fn new(config: &NodeConfig) -> Self {
+        let journal = if config.mempool.persist_transactions {
+            Some(MempoolJournal::new(&config.storage.dir))
+        } else {
+            None
+        };
         Mempool {
-            transactions: TransactionStore::new(&config.mempool),
+            transactions: TransactionStore::new(&config.mempool, journal),
             sequence_number_cache: LruCache::new(config.mempool.sequence_cache_capacity),
             metrics_cache: TtlCache::new(config.mempool.capacity),
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            recently_included_gas_prices: VecDeque::new(),
+            gas_price_history_size: config.mempool.gas_price_history_size,
         }
     }
 
@@ -78,8 +92,26 @@ impl Mempool {
         self.sequence_number_cache
             .insert(sender.clone(), new_sequence_number);
 
-        self.transactions
-            .commit_transaction(&sender, sequence_number);
+        if is_rejected {
+            self.transactions
+                .reject_transaction(&sender, sequence_number);
+        } else {
+            if let Some(txn) = self.transactions.get(sender, sequence_number) {
+                self.record_included_gas_price(txn.gas_unit_price());
+            }
+            self.transactions
+                .commit_transaction(&sender, sequence_number);
+        }
+    }
+
+    fn record_included_gas_price(&mut self, gas_price: u64) {
+        if self.gas_price_history_size == 0 {
+            return;
+        }
+        if self.recently_included_gas_prices.len() == self.gas_price_history_size {
+            self.recently_included_gas_prices.pop_front();
+        }
+        self.recently_included_gas_prices.push_back(gas_price);
     }
 
     fn log_latency(&mut self, account: AccountAddress, sequence_number: u64, metric: &str) {
@@ -92,7 +124,10 @@ impl Mempool {
     }
 
     fn get_required_balance(&mut self, txn: &SignedTransaction, gas_amount: u64) -> u64 {
-        txn.gas_unit_price() * gas_amount + self.transactions.get_required_balance(&txn.sender())
+        txn.gas_unit_price() * gas_amount
+            + self
+                .transactions
+                .get_required_balance(&txn.sender(), txn.sequence_number())
     }
 
     /// Used to add a transaction to the Mempool
@@ -247,6 +282,41 @@ impl Mempool {
         self.transactions.read_timeline(timeline_id, count)
     }
 
+    /// Returns the status of the transaction identified by `sender` and `sequence_number`
+    pub(crate) fn get_transaction_status(
+        &mut self,
+        sender: &AccountAddress,
+        sequence_number: u64,
+    ) -> MempoolTransactionStatus {
+        self.transactions.get_status(sender, sequence_number)
+    }
+
+    /// Returns percentiles of gas prices of ready transactions and recently committed ones
+    pub(crate) fn get_gas_price_estimate(&self) -> GasPriceEstimate {
+        GasPriceEstimate {
+            pending: GasPricePercentiles::new(
+                self.transactions
+                    .iter_queue()
+                    .map(|key| key.gas_price)
+                    .collect(),
+            ),
+            recently_included: GasPricePercentiles::new(
+                self.recently_included_gas_prices.iter().cloned().collect(),
+            ),
+        }
+    }
+
+    /// Returns transactions persisted in journal before node restart
+    pub(crate) fn journaled_transactions(&self) -> Vec<SignedTransaction> {
+        self.transactions.journaled_transactions()
+    }
+
+    /// Removes given transactions from journal
+    /// Used to prune journaled transactions that are no longer valid (e.g. expired or committed)
+    pub(crate) fn prune_journal(&self, txns: &[TxnPointer]) {
+        self.transactions.journal_remove(txns);
+    }
+
     /// Check the health of core mempool.
     pub(crate) fn health_check(&self) -> bool {
         self.transactions.health_check()
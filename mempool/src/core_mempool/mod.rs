@@ -1,12 +1,15 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod gas_price;
 mod index;
+mod journal;
 mod mempool;
 mod transaction;
 mod transaction_store;
 
 pub use self::{
+    gas_price::{GasPriceEstimate, GasPricePercentiles},
     index::TxnPointer,
     mempool::Mempool as CoreMempool,
     transaction::{MempoolAddTransactionStatus, TimelineState},
@@ -5,15 +5,18 @@ use crate::{
     core_mempool::{
         index::{
             AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            TimelineIndex, TxnPointer,
         },
+        journal::MempoolJournal,
         transaction::{MempoolAddTransactionStatus, MempoolTransaction, TimelineState},
     },
-    proto::shared::mempool_status::MempoolAddTransactionStatusCode,
+    proto::shared::mempool_status::{MempoolAddTransactionStatusCode, MempoolTransactionStatus},
     OP_COUNTERS,
 };
 use config::config::MempoolConfig;
 use failure::prelude::*;
+use logger::prelude::*;
+use lru_cache::LruCache;
 use std::{
     collections::HashMap,
     ops::Bound,
@@ -38,13 +41,21 @@ pub struct TransactionStore {
     // keeps track of "non-ready" txns (transactions that can't be included in next block)
     parking_lot_index: ParkingLotIndex,
 
+    // keeps track of why recently removed txns were removed (e.g. expired or evicted),
+    // so clients can find out what happened to transactions that are no longer in mempool.
+    // Committed txns are not tracked since they can be found in storage
+    removed_transactions: LruCache<TxnPointer, MempoolTransactionStatus>,
+
+    // on-disk copy of all transactions in store, so they survive node restart (optional)
+    journal: Option<MempoolJournal>,
+
     // configuration
     capacity: usize,
     capacity_per_user: usize,
 }
 
 impl TransactionStore {
-    pub(crate) fn new(config: &MempoolConfig) -> Self {
+    pub(crate) fn new(config: &MempoolConfig, journal: Option<MempoolJournal>) -> Self {
         Self {
             // main DS
             transactions: HashMap::new(),
@@ -58,6 +69,10 @@ impl TransactionStore {
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
 
+            removed_transactions: LruCache::new(config.removed_transaction_cache_capacity),
+
+            journal,
+
             // configuration
             capacity: config.capacity,
             capacity_per_user: config.capacity_per_user,
@@ -78,6 +93,30 @@ impl TransactionStore {
         None
     }
 
+    /// returns status of transaction identified by account address + sequence_number
+    pub(crate) fn get_status(
+        &mut self,
+        address: &AccountAddress,
+        sequence_number: u64,
+    ) -> MempoolTransactionStatus {
+        if let Some(txns) = self.transactions.get(&address) {
+            if let Some(txn) = txns.get(&sequence_number) {
+                return if self.priority_index.contains(txn) {
+                    MempoolTransactionStatus::Ready
+                } else {
+                    MempoolTransactionStatus::Parked
+                };
+            }
+        }
+        match self
+            .removed_transactions
+            .get_mut(&(*address, sequence_number))
+        {
+            Some(status) => *status,
+            None => MempoolTransactionStatus::Unknown,
+        }
+    }
+
     /// insert transaction into TransactionStore
     /// performs validation checks and updates indexes
     pub(crate) fn insert(
@@ -85,14 +124,29 @@ impl TransactionStore {
         txn: MempoolTransaction,
         current_sequence_number: u64,
     ) -> MempoolAddTransactionStatus {
-        if self.handle_gas_price_update(&txn).is_err() {
-            return MempoolAddTransactionStatus::new(
-                MempoolAddTransactionStatusCode::InvalidUpdate,
-                format!("Failed to update gas price to {}", txn.get_gas_price()),
-            );
-        }
+        let address = txn.get_sender();
+        let sequence_number = txn.get_sequence_number();
+
+        // check if given request is update (replace-by-fee)
+        let is_replacement = match self
+            .transactions
+            .get(&address)
+            .and_then(|txns| txns.get(&sequence_number))
+        {
+            Some(current_version) => {
+                if Self::check_gas_price_update(current_version, &txn).is_err() {
+                    return MempoolAddTransactionStatus::new(
+                        MempoolAddTransactionStatusCode::InvalidUpdate,
+                        format!("Failed to update gas price to {}", txn.get_gas_price()),
+                    );
+                }
+                true
+            }
+            None => false,
+        };
 
-        if self.check_if_full() {
+        // replacement takes the place of current version, so it doesn't need extra capacity
+        if !is_replacement && self.check_if_full() {
             return MempoolAddTransactionStatus::new(
                 MempoolAddTransactionStatusCode::MempoolIsFull,
                 format!(
@@ -103,16 +157,13 @@ impl TransactionStore {
             );
         }
 
-        let address = txn.get_sender();
-        let sequence_number = txn.get_sequence_number();
-
         self.transactions
             .entry(address)
             .or_insert_with(AccountTransactions::new);
 
         if let Some(txns) = self.transactions.get_mut(&address) {
             // capacity check
-            if txns.len() >= self.capacity_per_user {
+            if !is_replacement && txns.len() >= self.capacity_per_user {
                 return MempoolAddTransactionStatus::new(
                     MempoolAddTransactionStatusCode::TooManyTransactions,
                     format!(
@@ -123,9 +174,26 @@ impl TransactionStore {
                 );
             }
 
+            // swap out current version. It's removed from indexes before the replacement is
+            // inserted, since some index keys (e.g. client-specified expiration time) are equal
+            if let Some(current_version) = txns.remove(&sequence_number) {
+                self.index_remove(&current_version);
+                OP_COUNTERS.inc("txn.replaced");
+            }
+        }
+
+        if let Some(txns) = self.transactions.get_mut(&address) {
             // insert into storage and other indexes
             self.system_ttl_index.insert(&txn);
             self.expiration_time_index.insert(&txn);
+            self.removed_transactions
+                .remove(&(address, sequence_number));
+            if let Some(journal) = &self.journal {
+                if let Err(e) = journal.put(&txn.txn) {
+                    OP_COUNTERS.inc("journal.put.failure");
+                    error!("[Mempool] Failed to persist transaction: {:?}", e);
+                }
+            }
             txns.insert(sequence_number, txn);
             OP_COUNTERS.set("txn.system_ttl_index", self.system_ttl_index.size());
         }
@@ -147,6 +215,11 @@ impl TransactionStore {
                 if let Some(txns) = self.transactions.get_mut(&address) {
                     if let Some(txn) = txns.remove(&sequence_number) {
                         self.index_remove(&txn);
+                        self.journal_remove(&[(address, sequence_number)]);
+                        self.removed_transactions.insert(
+                            (address, sequence_number),
+                            MempoolTransactionStatus::Evicted,
+                        );
                     }
                 }
             }
@@ -154,27 +227,22 @@ impl TransactionStore {
         self.system_ttl_index.size() >= self.capacity
     }
 
-    /// check if transaction is already present in Mempool
-    /// e.g. given request is update
-    /// we allow increase in gas price to speed up process
-    fn handle_gas_price_update(&mut self, txn: &MempoolTransaction) -> Result<()> {
-        if let Some(txns) = self.transactions.get_mut(&txn.get_sender()) {
-            if let Some(current_version) = txns.get_mut(&txn.get_sequence_number()) {
-                if current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
-                    && current_version.txn.payload() == txn.txn.payload()
-                    && current_version.txn.expiration_time() == txn.txn.expiration_time()
-                    && current_version.get_gas_price() < txn.get_gas_price()
-                {
-                    if let Some(txn) = txns.remove(&txn.get_sequence_number()) {
-                        self.index_remove(&txn);
-                    }
-                } else {
-                    return Err(format_err!("Invalid gas price update. txn gas price: {}, current_version gas price: {}",
-                            txn.get_gas_price(),
-                            current_version.get_gas_price()));
-                }
-            }
-        }
+    /// checks if transaction can replace the current version with same sequence number
+    /// we allow increase in gas price to speed up process (replace-by-fee)
+    /// everything else has to stay the same
+    fn check_gas_price_update(
+        current_version: &MempoolTransaction,
+        txn: &MempoolTransaction,
+    ) -> Result<()> {
+        ensure!(
+            current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
+                && current_version.txn.payload() == txn.txn.payload()
+                && current_version.txn.expiration_time() == txn.txn.expiration_time()
+                && current_version.get_gas_price() < txn.get_gas_price(),
+            "Invalid gas price update. txn gas price: {}, current_version gas price: {}",
+            txn.get_gas_price(),
+            current_version.get_gas_price()
+        );
         Ok(())
     }
 
@@ -226,10 +294,25 @@ impl TransactionStore {
             for transaction in txns_for_removal.values() {
                 self.index_remove(transaction);
             }
+            let removed: Vec<_> = txns_for_removal
+                .keys()
+                .map(|sequence_number| (*account, *sequence_number))
+                .collect();
+            self.journal_remove(&removed);
         }
         self.process_ready_transactions(account, sequence_number + 1);
     }
 
+    /// handles rejection of transaction during execution
+    /// it's handled as commit, but the rejected transaction is remembered as such
+    pub(crate) fn reject_transaction(&mut self, account: &AccountAddress, sequence_number: u64) {
+        self.commit_transaction(account, sequence_number);
+        self.removed_transactions.insert(
+            (*account, sequence_number),
+            MempoolTransactionStatus::Rejected,
+        );
+    }
+
     /// removes transaction from all indexes
     fn index_remove(&mut self, txn: &MempoolTransaction) {
         self.system_ttl_index.remove(&txn);
@@ -240,12 +323,47 @@ impl TransactionStore {
         OP_COUNTERS.set("txn.system_ttl_index", self.system_ttl_index.size());
     }
 
+    /// removes transactions from journal (if it's enabled)
+    pub(crate) fn journal_remove(&self, txns: &[TxnPointer]) {
+        if txns.is_empty() {
+            return;
+        }
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.delete(txns) {
+                OP_COUNTERS.inc("journal.delete.failure");
+                error!(
+                    "[Mempool] Failed to remove transactions from journal: {:?}",
+                    e
+                );
+            }
+        }
+    }
+
+    /// returns all transactions persisted in journal (if it's enabled)
+    pub(crate) fn journaled_transactions(&self) -> Vec<SignedTransaction> {
+        match &self.journal {
+            Some(journal) => journal.get_all().unwrap_or_else(|e| {
+                error!("[Mempool] Failed to read journal: {:?}", e);
+                vec![]
+            }),
+            None => vec![],
+        }
+    }
+
     /// returns gas amount required to process all transactions for given account
-    pub(crate) fn get_required_balance(&mut self, address: &AccountAddress) -> u64 {
+    /// except the one with `sequence_number`, which is about to be replaced
+    pub(crate) fn get_required_balance(
+        &mut self,
+        address: &AccountAddress,
+        sequence_number: u64,
+    ) -> u64 {
         match self.transactions.get_mut(&address) {
-            Some(txns) => txns.iter().fold(0, |acc, (_, txn)| {
-                acc + txn.txn.gas_unit_price() * txn.gas_amount
-            }),
+            Some(txns) => txns
+                .iter()
+                .filter(|(seq, _)| **seq != sequence_number)
+                .fold(0, |acc, (_, txn)| {
+                    acc + txn.txn.gas_unit_price() * txn.gas_amount
+                }),
             None => 0,
         }
     }
@@ -294,6 +412,7 @@ impl TransactionStore {
         };
         OP_COUNTERS.inc(index_name);
 
+        let mut removed = vec![];
         for key in index.gc(now) {
             if let Some(txns) = self.transactions.get_mut(&key.address) {
                 // mark all following transactions as non-ready
@@ -307,9 +426,15 @@ impl TransactionStore {
                     let status = if is_active { "active" } else { "parked" };
                     OP_COUNTERS.inc(&format!("{}.{}", index_name, status));
                     self.index_remove(&txn);
+                    self.removed_transactions.insert(
+                        (key.address, key.sequence_number),
+                        MempoolTransactionStatus::Expired,
+                    );
+                    removed.push((key.address, key.sequence_number));
                 }
             }
         }
+        self.journal_remove(&removed);
         OP_COUNTERS.set("txn.system_ttl_index", self.system_ttl_index.size());
     }
 
@@ -7,9 +7,9 @@ use crate::{
             add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
             TestTransaction,
         },
-        CoreMempool, TimelineState,
+        CoreMempool, GasPricePercentiles, TimelineState,
     },
-    proto::shared::mempool_status::MempoolAddTransactionStatusCode,
+    proto::shared::mempool_status::{MempoolAddTransactionStatusCode, MempoolTransactionStatus},
 };
 use config::config::NodeConfigHelpers;
 use std::{collections::HashSet, time::Duration};
@@ -115,6 +115,68 @@ fn test_update_invalid_transaction_in_mempool() {
     assert_eq!(next_tnx[0].gas_unit_price(), 1);
 }
 
+#[test]
+fn test_update_transaction_with_same_gas_price() {
+    let (mut mempool, _) = setup_mempool();
+    add_txn(&mut mempool, TestTransaction::new(0, 0, 1)).unwrap();
+    // replacement needs strictly higher gas price
+    assert!(add_txn(&mut mempool, TestTransaction::new(0, 0, 1)).is_err());
+}
+
+#[test]
+fn test_update_transaction_timeline() {
+    let (mut mempool, _) = setup_mempool();
+    add_txns_to_mempool(
+        &mut mempool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(0, 1, 1)],
+    );
+    let (_, last_timeline_id) = mempool.read_timeline(0, 10);
+
+    // replacement is the only txn left to broadcast, its current version is gone from timeline
+    let fixed_txns = add_txns_to_mempool(&mut mempool, vec![TestTransaction::new(0, 0, 5)]);
+    let (timeline, _) = mempool.read_timeline(last_timeline_id, 10);
+    assert_eq!(timeline, fixed_txns);
+    let (timeline, _) = mempool.read_timeline(0, 10);
+    assert_eq!(timeline.len(), 2);
+    assert!(timeline
+        .iter()
+        .all(|txn| txn.gas_unit_price() != 1 || txn.sequence_number() != 0));
+}
+
+#[test]
+fn test_update_transaction_in_full_mempool() {
+    let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+    config.mempool.capacity = 2;
+    config.mempool.capacity_per_user = 1;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 1, 1)).is_err());
+
+    // replacement doesn't need extra capacity
+    add_txn(&mut pool, TestTransaction::new(1, 0, 5)).unwrap();
+    let block = pool.get_block(10, HashSet::new());
+    assert_eq!(block.len(), 2);
+    assert_eq!(block[0].sender(), TestTransaction::get_address(1));
+    assert_eq!(block[0].gas_unit_price(), 5);
+}
+
+#[test]
+fn test_update_transaction_balance_check() {
+    let mut pool = setup_mempool().0;
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    assert_eq!(
+        pool.add_txn(txn, 1, 0, 5, TimelineState::NotReady).code,
+        MempoolAddTransactionStatusCode::Valid
+    );
+    // current version doesn't count towards required balance of its replacement
+    let txn = TestTransaction::new(0, 0, 5).make_signed_transaction();
+    assert_eq!(
+        pool.add_txn(txn, 1, 0, 5, TimelineState::NotReady).code,
+        MempoolAddTransactionStatusCode::Valid
+    );
+}
+
 #[test]
 fn test_remove_transaction() {
     let (mut pool, mut consensus) = setup_mempool();
@@ -361,3 +423,181 @@ fn test_gc_ready_transaction() {
     assert_eq!(timeline.len(), 1);
     assert_eq!(timeline[0].sequence_number(), 0);
 }
+
+#[test]
+fn test_transaction_status() {
+    let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+    let address0 = TestTransaction::get_address(0);
+    let address1 = TestTransaction::get_address(1);
+
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 2, 1)).unwrap();
+    let txn = TestTransaction::new(0, 0, 1)
+        .make_signed_transaction_with_expiration_time(Duration::from_secs(0));
+    add_signed_txn(&mut pool, txn).unwrap();
+    assert_eq!(
+        pool.get_transaction_status(&address1, 0),
+        MempoolTransactionStatus::Ready
+    );
+    assert_eq!(
+        pool.get_transaction_status(&address1, 1),
+        MempoolTransactionStatus::Unknown
+    );
+    assert_eq!(
+        pool.get_transaction_status(&address1, 2),
+        MempoolTransactionStatus::Parked
+    );
+
+    // Mempool is full, so the parked transaction gets evicted
+    add_txn(&mut pool, TestTransaction::new(0, 1, 1)).unwrap();
+    assert_eq!(
+        pool.get_transaction_status(&address1, 2),
+        MempoolTransactionStatus::Evicted
+    );
+    assert_eq!(
+        pool.get_transaction_status(&address0, 1),
+        MempoolTransactionStatus::Ready
+    );
+
+    // gc expired transaction, the following one gets parked
+    pool.gc_by_expiration_time(Duration::from_secs(1));
+    assert_eq!(
+        pool.get_transaction_status(&address0, 0),
+        MempoolTransactionStatus::Expired
+    );
+    assert_eq!(
+        pool.get_transaction_status(&address0, 1),
+        MempoolTransactionStatus::Parked
+    );
+
+    pool.remove_transaction(&address1, 0, true);
+    assert_eq!(
+        pool.get_transaction_status(&address1, 0),
+        MempoolTransactionStatus::Rejected
+    );
+
+    // resubmitted transaction is back in mempool
+    add_txn(&mut pool, TestTransaction::new(1, 2, 1)).unwrap();
+    assert_eq!(
+        pool.get_transaction_status(&address1, 2),
+        MempoolTransactionStatus::Parked
+    );
+
+    // committed transactions are forgotten
+    pool.remove_transaction(&address0, 1, false);
+    assert_eq!(
+        pool.get_transaction_status(&address0, 1),
+        MempoolTransactionStatus::Unknown
+    );
+}
+
+#[test]
+fn test_gas_price_percentiles() {
+    assert_eq!(
+        GasPricePercentiles::new(vec![]),
+        GasPricePercentiles::default()
+    );
+    assert_eq!(
+        GasPricePercentiles::new((1..=10).rev().collect()),
+        GasPricePercentiles {
+            num_transactions: 10,
+            min: 1,
+            p25: 3,
+            p50: 5,
+            p75: 8,
+            p90: 9,
+            max: 10,
+        }
+    );
+}
+
+#[test]
+fn test_gas_price_estimate() {
+    let mut pool = setup_mempool().0;
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(1, 0, 2),
+            TestTransaction::new(1, 1, 3),
+            // parked transactions are not pending
+            TestTransaction::new(1, 5, 100),
+        ],
+    );
+    let estimate = pool.get_gas_price_estimate();
+    assert_eq!(
+        estimate.pending,
+        GasPricePercentiles {
+            num_transactions: 3,
+            min: 1,
+            p25: 1,
+            p50: 2,
+            p75: 3,
+            p90: 3,
+            max: 3,
+        }
+    );
+    assert_eq!(estimate.recently_included, GasPricePercentiles::default());
+
+    // committed transaction is moved from pending to recently included
+    pool.remove_transaction(&TestTransaction::get_address(1), 0, false);
+    // rejected transaction is dropped
+    pool.remove_transaction(&TestTransaction::get_address(0), 0, true);
+    let estimate = pool.get_gas_price_estimate();
+    assert_eq!(estimate.pending.num_transactions, 1);
+    assert_eq!(estimate.pending.p50, 3);
+    assert_eq!(estimate.recently_included.num_transactions, 1);
+    assert_eq!(estimate.recently_included.p50, 2);
+}
+
+#[test]
+fn test_journal() {
+    let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+    config.mempool.persist_transactions = true;
+    let address = TestTransaction::get_address(0);
+    {
+        let mut pool = CoreMempool::new(&config);
+        add_txns_to_mempool(
+            &mut pool,
+            vec![
+                TestTransaction::new(0, 0, 1),
+                TestTransaction::new(0, 1, 1),
+                TestTransaction::new(0, 2, 1),
+                TestTransaction::new(1, 0, 1),
+            ],
+        );
+        // replacement overwrites current version in journal
+        add_txn(&mut pool, TestTransaction::new(0, 1, 5)).unwrap();
+        // committed transactions are removed from journal
+        // (including older ones of same account)
+        pool.remove_transaction(&address, 2, false);
+        pool.remove_transaction(&TestTransaction::get_address(1), 0, false);
+        assert!(pool.journaled_transactions().is_empty());
+
+        add_txns_to_mempool(
+            &mut pool,
+            vec![TestTransaction::new(0, 1, 1), TestTransaction::new(0, 2, 1)],
+        );
+        add_txn(&mut pool, TestTransaction::new(0, 1, 5)).unwrap();
+    }
+
+    // journal survives restart
+    let pool = CoreMempool::new(&config);
+    let journaled = pool.journaled_transactions();
+    assert_eq!(journaled.len(), 2);
+    assert_eq!(journaled[0].sequence_number(), 1);
+    assert_eq!(journaled[0].gas_unit_price(), 5);
+    assert_eq!(journaled[1].sequence_number(), 2);
+
+    pool.prune_journal(&[(address, 1)]);
+    assert_eq!(pool.journaled_transactions().len(), 1);
+}
+
+#[test]
+fn test_journal_disabled() {
+    let (mut pool, _) = setup_mempool();
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    assert!(pool.journaled_transactions().is_empty());
+}
@@ -1,6 +1,6 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-mod common;
+pub(crate) mod common;
 mod core_mempool_test;
 mod shared_mempool_test;
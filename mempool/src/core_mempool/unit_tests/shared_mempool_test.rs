@@ -23,12 +23,46 @@ use network::{
 use proto_conv::FromProto;
 use std::{
     collections::{HashMap, HashSet},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
 use storage_service::mocks::mock_storage_client::MockStorageReadClient;
 use tokio::runtime::Runtime;
-use types::{transaction::SignedTransaction, PeerId};
-use vm_validator::mocks::mock_vm_validator::MockVMValidator;
+use types::{transaction::SignedTransaction, vm_error::VMStatus, PeerId};
+use vm_validator::{
+    mocks::mock_vm_validator::MockVMValidator, vm_validator::TransactionValidation,
+};
+
+/// validator that fails with a transient error for the first `failures` validations
+struct FlakyVMValidator {
+    failures: AtomicUsize,
+}
+
+impl FlakyVMValidator {
+    fn new(failures: usize) -> Self {
+        Self {
+            failures: AtomicUsize::new(failures),
+        }
+    }
+}
+
+impl TransactionValidation for FlakyVMValidator {
+    type ValidationInstance = MockVMValidator;
+    fn validate_transaction(
+        &self,
+        txn: SignedTransaction,
+    ) -> Box<dyn futures::Future<Item = Option<VMStatus>, Error = Error> + Send> {
+        let failures = self.failures.load(Ordering::SeqCst);
+        if failures > 0 {
+            self.failures.store(failures - 1, Ordering::SeqCst);
+            return Box::new(futures::future::err(format_err!("validator unavailable")));
+        }
+        MockVMValidator.validate_transaction(txn)
+    }
+}
 
 #[derive(Default)]
 struct SharedMempoolNetwork {
@@ -41,7 +75,18 @@ struct SharedMempoolNetwork {
 }
 
 impl SharedMempoolNetwork {
-    fn bootstrap_with_config(peers: Vec<PeerId>, mut config: NodeConfig) -> Self {
+    fn bootstrap_with_config(peers: Vec<PeerId>, config: NodeConfig) -> Self {
+        Self::bootstrap_with_validator(peers, config, Arc::new(MockVMValidator))
+    }
+
+    fn bootstrap_with_validator<V>(
+        peers: Vec<PeerId>,
+        mut config: NodeConfig,
+        validator: Arc<V>,
+    ) -> Self
+    where
+        V: TransactionValidation + 'static,
+    {
         let mut smp = Self::default();
         config.mempool.shared_mempool_batch_size = 1;
 
@@ -60,7 +105,7 @@ impl SharedMempoolNetwork {
                 network_sender,
                 network_events,
                 Arc::new(MockStorageReadClient),
-                Arc::new(MockVMValidator),
+                Arc::clone(&validator),
                 vec![sender],
                 Some(
                     timer_receiver
@@ -346,3 +391,89 @@ fn test_broadcast_updated_transaction() {
     assert_eq!(txn.sequence_number(), 0);
     assert_eq!(txn.gas_unit_price(), 5);
 }
+
+#[test]
+fn test_replay_journal() {
+    let peer = PeerId::random();
+    let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+    config.mempool.persist_transactions = true;
+    {
+        // emulate mempool state before restart
+        let mut mempool = CoreMempool::new(&config);
+        for txn in vec![TestTransaction::new(1, 0, 1), TestTransaction::new(1, 1, 1)] {
+            let transaction = txn.make_signed_transaction_with_max_gas_amount(5);
+            mempool.add_txn(transaction, 0, 0, 10, TimelineState::NotReady);
+        }
+        let expired = TestTransaction::new(0, 0, 1)
+            .make_signed_transaction_with_expiration_time(Duration::from_secs(0));
+        mempool.add_txn(expired, 0, 0, 10, TimelineState::NotReady);
+        assert_eq!(mempool.journaled_transactions().len(), 3);
+    }
+
+    let mut smp = SharedMempoolNetwork::bootstrap_with_config(vec![peer], config);
+    smp.wait_for_event(&peer, SharedMempoolNotification::NewTransactions);
+
+    // valid transactions are restored, expired one is pruned from journal
+    let mut mempool = smp.mempools.get(&peer).unwrap().lock().unwrap();
+    let block = mempool.get_block(100, HashSet::new());
+    assert_eq!(block.len(), 2);
+    assert!(block
+        .iter()
+        .all(|t| t.sender() == TestTransaction::get_address(1)));
+    assert_eq!(mempool.journaled_transactions().len(), 2);
+}
+
+/// writes given transactions to the journal, like mempool would before node restart
+fn journal_transactions(config: &NodeConfig, txns: Vec<TestTransaction>) {
+    let mut mempool = CoreMempool::new(config);
+    for txn in txns {
+        let transaction = txn.make_signed_transaction_with_max_gas_amount(5);
+        mempool.add_txn(transaction, 0, 0, 10, TimelineState::NotReady);
+    }
+}
+
+#[test]
+fn test_replay_journal_retries_transient_failures() {
+    let peer = PeerId::random();
+    let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+    config.mempool.persist_transactions = true;
+    journal_transactions(
+        &config,
+        vec![TestTransaction::new(1, 0, 1), TestTransaction::new(1, 1, 1)],
+    );
+
+    // both transactions fail validation on the first attempt and are accepted on the second
+    let mut smp = SharedMempoolNetwork::bootstrap_with_validator(
+        vec![peer],
+        config,
+        Arc::new(FlakyVMValidator::new(2)),
+    );
+    smp.wait_for_event(&peer, SharedMempoolNotification::NewTransactions);
+
+    let mut mempool = smp.mempools.get(&peer).unwrap().lock().unwrap();
+    assert_eq!(mempool.get_block(100, HashSet::new()).len(), 2);
+    assert_eq!(mempool.journaled_transactions().len(), 2);
+}
+
+#[test]
+fn test_replay_journal_keeps_unchecked_transactions() {
+    let peer = PeerId::random();
+    let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+    config.mempool.persist_transactions = true;
+    journal_transactions(
+        &config,
+        vec![TestTransaction::new(1, 0, 1), TestTransaction::new(1, 1, 1)],
+    );
+
+    // validation never succeeds, so nothing is restored, but nothing is lost either
+    let mut smp = SharedMempoolNetwork::bootstrap_with_validator(
+        vec![peer],
+        config,
+        Arc::new(FlakyVMValidator::new(usize::max_value())),
+    );
+    smp.wait_for_event(&peer, SharedMempoolNotification::NewTransactions);
+
+    let mut mempool = smp.mempools.get(&peer).unwrap().lock().unwrap();
+    assert!(mempool.get_block(100, HashSet::new()).is_empty());
+    assert_eq!(mempool.journaled_transactions().len(), 2);
+}
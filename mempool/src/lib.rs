@@ -52,6 +52,11 @@
 //! checked periodically in the background, while the client-specified expiration is checked on
 //! every Consensus commit request. We use a separate system TTL to ensure that a transaction won't
 //! remain stuck in Mempool forever, even if Consensus doesn't make progress
+//!
+//! Optionally (see `MempoolConfig::persist_transactions`) accepted transactions are also written
+//! to an on-disk journal, so pending transactions are not lost when the node restarts. On startup
+//! journaled transactions are re-validated and added back to Mempool, while expired, committed and
+//! invalid ones are pruned from the journal
 pub mod proto;
 pub use runtime::MempoolRuntime;
 
@@ -66,7 +71,7 @@ use metrics::OpMetrics;
 lazy_static! {
     static ref OP_COUNTERS: OpMetrics = OpMetrics::new_and_registered("mempool");
 }
-pub use crate::core_mempool::MempoolAddTransactionStatus;
+pub use crate::core_mempool::{GasPriceEstimate, GasPricePercentiles, MempoolAddTransactionStatus};
 
 #[cfg(test)]
 mod unit_tests;
@@ -154,4 +154,53 @@ impl Mempool for MempoolService {
         response.set_is_healthy(pool.health_check());
         ctx.spawn(sink.success(response).map_err(default_reply_error_logger));
     }
+
+    fn get_transaction_status(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        req: crate::proto::mempool::GetTransactionStatusRequest,
+        sink: ::grpcio::UnarySink<crate::proto::mempool::GetTransactionStatusResponse>,
+    ) {
+        trace!("[GRPC] Mempool::get_transaction_status");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        match AccountAddress::try_from(req.get_sender()) {
+            Err(e) => {
+                ctx.spawn(
+                    sink.fail(create_grpc_invalid_arg_status("get_transaction_status", e))
+                        .map_err(default_reply_error_logger),
+                );
+                SVC_COUNTERS.resp(&ctx, false);
+            }
+            Ok(sender) => {
+                let status = self
+                    .core_mempool
+                    .lock()
+                    .expect("[get_transaction_status] acquire mempool lock")
+                    .get_transaction_status(&sender, req.get_sequence_number());
+                let mut response = crate::proto::mempool::GetTransactionStatusResponse::new();
+                response.set_status(status);
+                ctx.spawn(sink.success(response).map_err(default_reply_error_logger));
+                SVC_COUNTERS.resp(&ctx, true);
+            }
+        }
+    }
+
+    fn get_gas_price_estimate(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        _req: crate::proto::mempool::GetGasPriceEstimateRequest,
+        sink: ::grpcio::UnarySink<crate::proto::mempool::GetGasPriceEstimateResponse>,
+    ) {
+        trace!("[GRPC] Mempool::get_gas_price_estimate");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let estimate = self
+            .core_mempool
+            .lock()
+            .expect("[get_gas_price_estimate] acquire mempool lock")
+            .get_gas_price_estimate();
+        let mut response = crate::proto::mempool::GetGasPriceEstimateResponse::new();
+        response.set_estimate(estimate.into_proto());
+        ctx.spawn(sink.success(response).map_err(default_reply_error_logger));
+        SVC_COUNTERS.resp(&ctx, true);
+    }
 }
@@ -1,4 +1,5 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod gas_price;
 pub mod mempool_status;
@@ -3,6 +3,7 @@
 
 use crate::{
     core_mempool::{CoreMempool, TimelineState},
+    proto::shared::mempool_status::MempoolAddTransactionStatusCode,
     OP_COUNTERS,
 };
 use bounded_executor::BoundedExecutor;
@@ -25,16 +26,21 @@ use std::{
     ops::Deref,
     pin::Pin,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use storage_client::StorageRead;
 use tokio::{
     runtime::{Builder, Runtime, TaskExecutor},
-    timer::Interval,
+    timer::{Delay, Interval},
 };
 use types::{transaction::SignedTransaction, PeerId};
 use vm_validator::vm_validator::{get_account_state, TransactionValidation};
 
+/// how many times journaled transactions that can't be checked are retried during replay
+const JOURNAL_REPLAY_MAX_ATTEMPTS: usize = 5;
+/// delay between attempts to replay journaled transactions
+const JOURNAL_REPLAY_RETRY_DELAY_MS: u64 = 1000;
+
 /// state of last sync with peer
 /// `timeline_id` is position in log of ready transactions
 /// `is_alive` - is connection healthy
@@ -267,6 +273,136 @@ async fn process_incoming_transactions<V>(
     notify_subscribers(SharedMempoolNotification::NewTransactions, &smp.subscribers);
 }
 
+/// restores transactions persisted in mempool journal before node restart
+/// they are re-validated against current ledger state and added back to local Mempool.
+/// Expired, committed and invalid ones are pruned from the journal. Transactions that can't be
+/// checked because of a transient failure (e.g. storage is not reachable yet) are retried a few
+/// times and kept in the journal otherwise, so they are not lost
+async fn replay_journal<V>(smp: SharedMempool<V>)
+where
+    V: TransactionValidation,
+{
+    let mut transactions = smp
+        .mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock")
+        .journaled_transactions();
+    if transactions.is_empty() {
+        return;
+    }
+    OP_COUNTERS.inc_by("smp.journal.replayed", transactions.len());
+
+    for attempt in 1..=JOURNAL_REPLAY_MAX_ATTEMPTS {
+        transactions = replay_journaled_transactions(&smp, transactions).await;
+        if transactions.is_empty() {
+            break;
+        }
+        warn!(
+            "[shared mempool] failed to replay {} journaled transactions, attempt {}",
+            transactions.len(),
+            attempt
+        );
+        if attempt < JOURNAL_REPLAY_MAX_ATTEMPTS {
+            let deadline = Instant::now() + Duration::from_millis(JOURNAL_REPLAY_RETRY_DELAY_MS);
+            if let Err(e) = Delay::new(deadline).compat().await {
+                error!("Error in replay_journal timer: {:?}", e);
+                break;
+            }
+        }
+    }
+    OP_COUNTERS.inc_by("smp.journal.kept", transactions.len());
+    notify_subscribers(SharedMempoolNotification::NewTransactions, &smp.subscribers);
+}
+
+/// re-validates journaled transactions and adds valid ones back to local Mempool
+/// the ones that are definitely invalid are pruned from the journal
+/// returns the ones that couldn't be checked and should be retried
+async fn replay_journaled_transactions<V>(
+    smp: &SharedMempool<V>,
+    transactions: Vec<SignedTransaction>,
+) -> Vec<SignedTransaction>
+where
+    V: TransactionValidation,
+{
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("init timestamp failure");
+    let mut pruned = vec![];
+    let mut retries = vec![];
+
+    let account_states = join_all(
+        transactions
+            .iter()
+            .map(|t| get_account_state(smp.storage_read_client.clone(), t.sender())),
+    )
+    .await;
+
+    // eagerly filter out transactions that expired or were committed while node was down
+    let transactions: Vec<_> = transactions
+        .into_iter()
+        .zip(account_states.into_iter())
+        .filter_map(|(t, account_state)| match account_state {
+            Ok((sequence_number, balance)) => {
+                if t.sequence_number() >= sequence_number && t.expiration_time() > now {
+                    Some((t, sequence_number, balance))
+                } else {
+                    pruned.push((t.sender(), t.sequence_number()));
+                    None
+                }
+            }
+            Err(_) => {
+                retries.push(t);
+                None
+            }
+        })
+        .collect();
+
+    let validations = join_all(
+        transactions
+            .iter()
+            .map(|t| smp.validator.validate_transaction(t.0.clone()).compat()),
+    )
+    .await;
+
+    {
+        let mut mempool = smp
+            .mempool
+            .lock()
+            .expect("[shared mempool] failed to acquire mempool lock");
+
+        for ((transaction, sequence_number, balance), validation) in
+            transactions.into_iter().zip(validations.into_iter())
+        {
+            let txn_pointer = (transaction.sender(), transaction.sequence_number());
+            match validation {
+                Ok(None) => {
+                    let gas_cost = transaction.max_gas_amount();
+                    let insertion_result = mempool.add_txn(
+                        transaction,
+                        gas_cost,
+                        sequence_number,
+                        balance,
+                        TimelineState::NotReady,
+                    );
+                    match insertion_result.code {
+                        MempoolAddTransactionStatusCode::Valid => {}
+                        // Mempool has no room right now, which says nothing about the
+                        // transaction, so it stays in the journal.
+                        MempoolAddTransactionStatusCode::MempoolIsFull
+                        | MempoolAddTransactionStatusCode::TooManyTransactions => {}
+                        _ => pruned.push(txn_pointer),
+                    }
+                }
+                Ok(Some(_)) => pruned.push(txn_pointer),
+                Err(_) => retries.push(transaction),
+            }
+        }
+        OP_COUNTERS.inc_by("smp.journal.pruned", pruned.len());
+        mempool.prune_journal(&pruned);
+    }
+    retries
+}
+
 /// This task handles [`SyncEvent`], which is periodically emitted for us to
 /// broadcast ready to go transactions to peers.
 async fn outbound_sync_task<V>(smp: SharedMempool<V>, mut interval: IntervalStream)
@@ -395,6 +531,7 @@ async fn gc_task(mempool: Arc<Mutex<CoreMempool>>, gc_interval_ms: u64) {
 
 /// bootstrap of SharedMempool
 /// creates separate Tokio Runtime that runs following routines:
+///   - replay_journal (one-off task that restores transactions persisted before node restart)
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers)
 ///   - inbound_network_task (task that handles inbound mempool messages and network events)
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL)
@@ -429,6 +566,8 @@ where
         subscribers,
     };
 
+    executor.spawn(replay_journal(smp.clone()).boxed().unit_error().compat());
+
     let interval =
         timer.unwrap_or_else(|| default_timer(config.mempool.shared_mempool_tick_interval_ms));
 
@@ -156,3 +156,53 @@ fn test_gc_by_expiration_time() {
     let response = client.get_block(&GetBlockRequest::new()).unwrap();
     assert_eq!(response.get_block().get_transactions().len(), 1);
 }
+
+#[test]
+fn test_get_transaction_status() {
+    let (server, client) = setup_mempool();
+    let _handle = ServerHandle::setup(server);
+
+    // add transaction with expiration time 1
+    let add_req = create_add_transaction_request(1);
+    client.add_transaction_with_validation(&add_req).unwrap();
+    let signed_txn = SignedTransaction::from_proto(add_req.get_signed_txn().clone()).unwrap();
+    let mut req = GetTransactionStatusRequest::new();
+    req.set_sender(signed_txn.sender().as_ref().to_vec());
+    req.set_sequence_number(0);
+    let response = client.get_transaction_status(&req).unwrap();
+    assert_eq!(response.get_status(), MempoolTransactionStatus::Ready);
+
+    // commit empty block with block_time 2, so the transaction expires
+    let mut commit_req = CommitTransactionsRequest::new();
+    commit_req.set_block_timestamp_usecs(Duration::from_secs(2).as_micros() as u64);
+    client.commit_transactions(&commit_req).unwrap();
+    let response = client.get_transaction_status(&req).unwrap();
+    assert_eq!(response.get_status(), MempoolTransactionStatus::Expired);
+
+    // malformed sender
+    req.set_sender(vec![1, 2, 3]);
+    assert!(client.get_transaction_status(&req).is_err());
+}
+
+#[test]
+fn test_get_gas_price_estimate() {
+    let (server, client) = setup_mempool();
+    let _handle = ServerHandle::setup(server);
+
+    let add_req = create_add_transaction_request(0);
+    client.add_transaction_with_validation(&add_req).unwrap();
+
+    let response = client
+        .get_gas_price_estimate(&GetGasPriceEstimateRequest::new())
+        .unwrap();
+    let pending = response.get_estimate().get_pending();
+    assert_eq!(pending.get_num_transactions(), 1);
+    assert_eq!(pending.get_p50(), 1);
+    assert_eq!(
+        response
+            .get_estimate()
+            .get_recently_included()
+            .get_num_transactions(),
+        0
+    );
+}
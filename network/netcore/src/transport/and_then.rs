@@ -29,7 +29,7 @@ impl<T, F> AndThen<T, F> {
 impl<T, F, Fut, O> Transport for AndThen<T, F>
 where
     T: Transport,
-    F: FnOnce(T::Output, ConnectionOrigin) -> Fut + Send + Unpin + Clone,
+    F: FnOnce(T::Output, Multiaddr, ConnectionOrigin) -> Fut + Send + Unpin + Clone,
     // Pin the error types to be the same for now
     // TODO don't require the error types to be the same
     Fut: Future<Output = Result<O, T::Error>> + Send,
@@ -48,11 +48,11 @@ where
     }
 
     fn dial(&self, addr: Multiaddr) -> Result<Self::Outbound, Self::Error> {
-        let fut = self.transport.dial(addr)?;
+        let fut = self.transport.dial(addr.clone())?;
         let origin = ConnectionOrigin::Outbound;
         let f = self.function.clone();
 
-        Ok(AndThenFuture::new(fut, f, origin))
+        Ok(AndThenFuture::new(fut, f, addr, origin))
     }
 }
 
@@ -69,7 +69,7 @@ where
     St: Stream<Item = Result<(Fut1, Multiaddr), E>>,
     Fut1: Future<Output = Result<O1, E>>,
     Fut2: Future<Output = Result<O2, E>>,
-    F: FnOnce(O1, ConnectionOrigin) -> Fut2 + Clone,
+    F: FnOnce(O1, Multiaddr, ConnectionOrigin) -> Fut2 + Clone,
     E: ::std::error::Error,
 {
     // This use of `unsafe_pinned` is safe because:
@@ -88,7 +88,7 @@ where
     St: Stream<Item = Result<(Fut1, Multiaddr), E>>,
     Fut1: Future<Output = Result<O1, E>>,
     Fut2: Future<Output = Result<O2, E>>,
-    F: FnOnce(O1, ConnectionOrigin) -> Fut2 + Clone,
+    F: FnOnce(O1, Multiaddr, ConnectionOrigin) -> Fut2 + Clone,
     E: ::std::error::Error,
 {
     type Item = Result<(AndThenFuture<Fut1, Fut2, F>, Multiaddr), E>;
@@ -99,7 +99,12 @@ where
             Poll::Ready(None) => Poll::Ready(None),
             Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
             Poll::Ready(Some(Ok((fut1, addr)))) => Poll::Ready(Some(Ok((
-                AndThenFuture::new(fut1, self.f.clone(), ConnectionOrigin::Inbound),
+                AndThenFuture::new(
+                    fut1,
+                    self.f.clone(),
+                    addr.clone(),
+                    ConnectionOrigin::Inbound,
+                ),
                 addr,
             )))),
         }
@@ -108,7 +113,7 @@ where
 
 #[derive(Debug)]
 enum AndThenChain<Fut1, Fut2, F> {
-    First(Fut1, Option<(F, ConnectionOrigin)>),
+    First(Fut1, Option<(F, Multiaddr, ConnectionOrigin)>),
     Second(Fut2),
     Empty,
 }
@@ -127,7 +132,7 @@ impl<Fut1, O1, Fut2, O2, E, F> AndThenFuture<Fut1, Fut2, F>
 where
     Fut1: Future<Output = Result<O1, E>>,
     Fut2: Future<Output = Result<O2, E>>,
-    F: FnOnce(O1, ConnectionOrigin) -> Fut2,
+    F: FnOnce(O1, Multiaddr, ConnectionOrigin) -> Fut2,
     E: ::std::error::Error,
 {
     // Ideally we'd want to use `unsafe_pinned` to get a pinned version of the `AndThenChain`,
@@ -143,9 +148,9 @@ where
     //      called on the future stored in First prior to advancing to Second.
     unsafe_unpinned!(chain: AndThenChain<Fut1, Fut2, F>);
 
-    fn new(fut1: Fut1, f: F, origin: ConnectionOrigin) -> Self {
+    fn new(fut1: Fut1, f: F, addr: Multiaddr, origin: ConnectionOrigin) -> Self {
         Self {
-            chain: AndThenChain::First(fut1, Some((f, origin))),
+            chain: AndThenChain::First(fut1, Some((f, addr, origin))),
         }
     }
 }
@@ -155,14 +160,14 @@ impl<Fut1, O1, Fut2, O2, E, F> Future for AndThenFuture<Fut1, Fut2, F>
 where
     Fut1: Future<Output = Result<O1, E>>,
     Fut2: Future<Output = Result<O2, E>>,
-    F: FnOnce(O1, ConnectionOrigin) -> Fut2,
+    F: FnOnce(O1, Multiaddr, ConnectionOrigin) -> Fut2,
     E: ::std::error::Error,
 {
     type Output = Result<O2, E>;
 
     fn poll(mut self: Pin<&mut Self>, mut context: &mut Context) -> Poll<Self::Output> {
         loop {
-            let (output, (f, origin)) = match self.as_mut().chain() {
+            let (output, (f, addr, origin)) = match self.as_mut().chain() {
                 // Step 1: Drive Fut1 to completion
                 AndThenChain::First(fut1, data) => {
                     // Safe to construct a Pin of the interior future because
@@ -187,7 +192,7 @@ where
             // Step 2: Ensure that Fut1 is dropped
             *self.as_mut().chain() = AndThenChain::Empty;
             // Step 3: Run F on the output of Fut1 to create Fut2
-            let fut2 = f(output, origin);
+            let fut2 = f(output, addr, origin);
             *self.as_mut().chain() = AndThenChain::Second(fut2)
         }
     }
@@ -51,7 +51,13 @@ fn parse_addr(addr: &Multiaddr) -> io::Result<u16> {
         ));
     };
 
-    if iter.next().is_some() {
+    // The address may end with a `/p2p` component naming the peer expected at the address
+    let has_valid_suffix = match iter.next() {
+        None => true,
+        Some(Protocol::P2p(_)) => iter.next().is_none(),
+        Some(_) => false,
+    };
+    if !has_valid_suffix {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
             format!("Invalid Multiaddr '{:?}'", addr),
@@ -81,6 +81,9 @@ pub trait Transport {
         Self: Sized;
 
     /// Dials the given [`Multiaddr`], returning a future for a pending outbound connection.
+    ///
+    /// The address may end with a `/p2p` component naming the peer expected at that address. It
+    /// isn't used to establish the connection, it is left to the upgrades to authenticate the peer.
     fn dial(&self, addr: Multiaddr) -> Result<Self::Outbound, Self::Error>
     where
         Self: Sized;
@@ -116,12 +119,14 @@ pub trait TransportExt: Transport {
     /// This function can be used for ad-hoc protocol upgrades on a transport
     /// or for processing or adapting the output of an earlier upgrade.  The
     /// provided function must take as input the output from the existing
-    /// transport and a [`ConnectionOrigin`] which can be used to identify the
-    /// origin of the connection (inbound vs outbound).
+    /// transport, the [`Multiaddr`] of the connection (the dialed address for
+    /// outbound connections, the address of the dialer for inbound ones) and a
+    /// [`ConnectionOrigin`] which can be used to identify the origin of the
+    /// connection (inbound vs outbound).
     fn and_then<F, Fut, O>(self, f: F) -> and_then::AndThen<Self, F>
     where
         Self: Sized,
-        F: FnOnce(Self::Output, ConnectionOrigin) -> Fut + Clone,
+        F: FnOnce(Self::Output, Multiaddr, ConnectionOrigin) -> Fut + Clone,
         // Pin the error types to be the same for now
         // TODO don't require the error types to be the same
         Fut: Future<Output = Result<O, Self::Error>>,
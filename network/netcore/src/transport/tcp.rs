@@ -233,7 +233,13 @@ fn multiaddr_to_socketaddr(addr: &Multiaddr) -> ::std::io::Result<SocketAddr> {
         )
     })?;
 
-    if iter.next().is_some() {
+    // The address may end with a `/p2p` component naming the peer expected at the address
+    let has_valid_suffix = match iter.next() {
+        None => true,
+        Some(Protocol::P2p(_)) => iter.next().is_none(),
+        Some(_) => false,
+    };
+    if !has_valid_suffix {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
             format!("Invalid Multiaddr '{:?}'", addr),
@@ -262,7 +268,7 @@ mod test {
 
     #[test]
     fn simple_listen_and_dial() -> Result<(), ::std::io::Error> {
-        let t = TcpTransport::default().and_then(|mut out, connection| {
+        let t = TcpTransport::default().and_then(|mut out, _addr, connection| {
             async move {
                 match connection {
                     ConnectionOrigin::Inbound => {
@@ -6,7 +6,10 @@
 //! [Noise protocol framework][noise] support for use in Libra.
 //!
 //! The main feature of this module is [`NoiseSocket`](crate::socket::NoiseSocket) which
-//! provides wire-framing for noise payloads.  Currently the only handshake pattern supported is IX.
+//! provides wire-framing for noise payloads and periodically rekeys the transport.  Currently the
+//! only handshake pattern supported is IK: the dialer needs to know the static public key of the
+//! listener ahead of time, and the listener can authenticate the static public key of the dialer
+//! during the handshake, so that untrusted peers are rejected before any data is exchanged.
 //!
 //! [noise]: http://noiseprotocol.org/
 
@@ -24,8 +27,8 @@ mod socket;
 pub use self::socket::NoiseSocket;
 use nextgen_crypto::ValidKey;
 
-const NOISE_IX_25519_AESGCM_SHA256_PROTOCOL_NAME: &[u8] = b"/noise_ix_25519_aesgcm_sha256/1.0.0";
-const NOISE_IX_PARAMETER: &str = "Noise_IX_25519_AESGCM_SHA256";
+const NOISE_IK_25519_AESGCM_SHA256_PROTOCOL_NAME: &[u8] = b"/noise_ik_25519_aesgcm_sha256/1.0.0";
+const NOISE_IK_PARAMETER: &str = "Noise_IK_25519_AESGCM_SHA256";
 
 /// The Noise protocol configuration to be used to perform a protocol upgrade on an underlying
 /// socket.
@@ -37,7 +40,7 @@ pub struct NoiseConfig {
 impl NoiseConfig {
     /// Create a new NoiseConfig with the provided keypair
     pub fn new(keypair: (X25519StaticPrivateKey, X25519StaticPublicKey)) -> Self {
-        let parameters: NoiseParams = NOISE_IX_PARAMETER.parse().expect("Invalid protocol name");
+        let parameters: NoiseParams = NOISE_IK_PARAMETER.parse().expect("Invalid protocol name");
         let keypair = Keypair {
             private: keypair.0.to_bytes().to_vec(),
             public: keypair.1.to_bytes().to_vec(),
@@ -50,7 +53,7 @@ impl NoiseConfig {
 
     /// Create a new NoiseConfig with an ephemeral static key.
     pub fn new_random() -> Self {
-        let parameters: NoiseParams = NOISE_IX_PARAMETER.parse().expect("Invalid protocol name");
+        let parameters: NoiseParams = NOISE_IK_PARAMETER.parse().expect("Invalid protocol name");
         let keypair = snow::Builder::new(parameters.clone())
             .generate_keypair()
             .expect("Noise failed to generate a random static keypair");
@@ -60,29 +63,65 @@ impl NoiseConfig {
         }
     }
 
-    /// Perform a protocol upgrade on an underlying connection. In addition perform the noise IX
-    /// handshake to establish a noise session and exchange static public keys. Upon success,
-    /// returns the static public key of the remote as well as a NoiseSocket.
+    /// Return the static public key of this NoiseConfig
+    pub fn public_key(&self) -> &[u8] {
+        &self.keypair.public
+    }
+
+    /// Perform a protocol upgrade on an underlying connection. In addition perform the noise IK
+    /// handshake to establish a noise session and exchange static public keys. The dialer must
+    /// provide the static public key of the listener it expects to reach. Upon success, returns
+    /// the static public key of the remote as well as a NoiseSocket.
     pub async fn upgrade_connection<TSocket>(
         &self,
         socket: TSocket,
         origin: ConnectionOrigin,
+        remote_public_key: Option<&[u8]>,
     ) -> io::Result<(Vec<u8>, NoiseSocket<TSocket>)>
     where
         TSocket: AsyncRead + AsyncWrite + Unpin,
+    {
+        self.upgrade_connection_authenticated(
+            socket,
+            origin,
+            remote_public_key,
+            |remote_static_key| Some(remote_static_key.to_vec()),
+        )
+        .await
+    }
+
+    /// Perform a protocol upgrade on an underlying connection, authenticating the static public
+    /// key of the remote during the noise IK handshake.
+    ///
+    /// The dialer must provide the static public key of the listener it expects to reach, the
+    /// handshake fails if the listener doesn't own it. `authenticate` maps the static public key
+    /// of the remote to its identity, or returns None if the key isn't trusted, in which case the
+    /// handshake is aborted. Upon success, returns the identity of the remote as well as a
+    /// NoiseSocket.
+    pub async fn upgrade_connection_authenticated<TSocket, F, T>(
+        &self,
+        socket: TSocket,
+        origin: ConnectionOrigin,
+        remote_public_key: Option<&[u8]>,
+        authenticate: F,
+    ) -> io::Result<(T, NoiseSocket<TSocket>)>
+    where
+        TSocket: AsyncRead + AsyncWrite + Unpin,
+        F: FnOnce(&[u8]) -> Option<T> + Send,
+        T: Send,
     {
         // Perform protocol negotiation
         let (socket, proto) = match origin {
             ConnectionOrigin::Inbound => {
-                negotiate_inbound(socket, [NOISE_IX_25519_AESGCM_SHA256_PROTOCOL_NAME]).await?
+                negotiate_inbound(socket, [NOISE_IK_25519_AESGCM_SHA256_PROTOCOL_NAME]).await?
             }
             ConnectionOrigin::Outbound => {
-                negotiate_outbound_interactive(socket, [NOISE_IX_25519_AESGCM_SHA256_PROTOCOL_NAME])
+                negotiate_outbound_interactive(socket, [NOISE_IK_25519_AESGCM_SHA256_PROTOCOL_NAME])
                     .await?
             }
         };
 
-        assert_eq!(proto, NOISE_IX_25519_AESGCM_SHA256_PROTOCOL_NAME);
+        assert_eq!(proto, NOISE_IK_25519_AESGCM_SHA256_PROTOCOL_NAME);
 
         // Instantiate the snow session
         // Note: We need to scope the Builder struct so that the compiler doesn't over eagerly
@@ -92,18 +131,23 @@ impl NoiseConfig {
                 .local_private_key(&self.keypair.private);
             match origin {
                 ConnectionOrigin::Inbound => builder.build_responder(),
-                ConnectionOrigin::Outbound => builder.build_initiator(),
+                ConnectionOrigin::Outbound => {
+                    let remote_public_key = remote_public_key.ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "Dialing requires the static public key of the remote",
+                        )
+                    })?;
+                    builder
+                        .remote_public_key(remote_public_key)
+                        .build_initiator()
+                }
             }
             .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{}", e)))?
         };
 
         let handshake = socket::Handshake::new(socket, session);
 
-        let socket = handshake.handshake_1rt().await?;
-        let remote_static_key = socket
-            .get_remote_static()
-            .expect("Noise remote static key already taken")
-            .to_owned();
-        Ok((remote_static_key, socket))
+        handshake.handshake_1rt_authenticated(authenticate).await
     }
 }
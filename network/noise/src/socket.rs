@@ -22,6 +22,10 @@ const MAX_PAYLOAD_LENGTH: usize = u16::max_value() as usize; // 65535
 // encrypted messages include a tag along with the payload.
 const MAX_WRITE_BUFFER_LENGTH: usize = u16::max_value() as usize - 16; // 65519
 
+/// Number of frames encrypted with a key before both sides replace it. It is part of the wire
+/// protocol: the sender and the receiver of a stream of frames rekey at the same frame.
+const REKEY_INTERVAL: u64 = 1 << 16;
+
 /// Collection of buffers used for buffering data during the various read/write states of a
 /// NoiseSocket
 struct NoiseBuffers {
@@ -69,7 +73,7 @@ enum ReadState {
     /// End of file reached, result indicated if EOF was expected or not
     Eof(Result<(), ()>),
     /// Decryption Error
-    DecryptionError(snow::SnowError),
+    DecryptionError(snow::Error),
 }
 
 /// Possible write states for a [NoiseSocket]
@@ -92,7 +96,44 @@ enum WriteState {
     /// End of file reached
     Eof,
     /// Encryption Error
-    EncryptionError(snow::SnowError),
+    EncryptionError(snow::Error),
+}
+
+/// The noise session of a [NoiseSocket], in handshake mode until the handshake is done
+#[derive(Debug)]
+enum NoiseSession {
+    Handshake(snow::HandshakeState),
+    Transport(snow::TransportState),
+}
+
+impl NoiseSession {
+    fn read_message(&mut self, message: &[u8], payload: &mut [u8]) -> Result<usize, snow::Error> {
+        match self {
+            NoiseSession::Handshake(session) => session.read_message(message, payload),
+            NoiseSession::Transport(session) => session.read_message(message, payload),
+        }
+    }
+
+    fn write_message(&mut self, payload: &[u8], message: &mut [u8]) -> Result<usize, snow::Error> {
+        match self {
+            NoiseSession::Handshake(session) => session.write_message(payload, message),
+            NoiseSession::Transport(session) => session.write_message(payload, message),
+        }
+    }
+
+    fn get_remote_static(&self) -> Option<&[u8]> {
+        match self {
+            NoiseSession::Handshake(session) => session.get_remote_static(),
+            NoiseSession::Transport(session) => session.get_remote_static(),
+        }
+    }
+
+    fn is_initiator(&self) -> bool {
+        match self {
+            NoiseSession::Handshake(session) => session.is_initiator(),
+            NoiseSession::Transport(session) => session.is_initiator(),
+        }
+    }
 }
 
 /// A Noise session with a remote
@@ -100,23 +141,36 @@ enum WriteState {
 /// Encrypts data to be written to and decrypts data that is read from the underlying socket using
 /// the noise protocol. This is done by wrapping noise payloads in u16 (big endian) length prefix
 /// frames.
+///
+/// Once the handshake is done, the keys of both directions are replaced with the deterministic
+/// REKEY function of the noise protocol every `REKEY_INTERVAL` frames. Both sides count the frames
+/// sent in each direction, so that no message is needed to agree on when to rekey.
 #[derive(Debug)]
 pub struct NoiseSocket<TSocket> {
     socket: TSocket,
-    session: snow::Session,
+    session: NoiseSession,
     buffers: Box<NoiseBuffers>,
     read_state: ReadState,
     write_state: WriteState,
+    /// Number of frames encrypted with a key before replacing it (None during the handshake)
+    rekey_interval: Option<u64>,
+    /// Number of frames written since the last rekey of the outgoing key
+    frames_written: u64,
+    /// Number of frames read since the last rekey of the incoming key
+    frames_read: u64,
 }
 
 impl<TSocket> NoiseSocket<TSocket> {
-    fn new(socket: TSocket, session: snow::Session) -> Self {
+    fn new(socket: TSocket, session: snow::HandshakeState) -> Self {
         Self {
             socket,
-            session,
+            session: NoiseSession::Handshake(session),
             buffers: Box::new(NoiseBuffers::new()),
             read_state: ReadState::Init,
             write_state: WriteState::Init,
+            rekey_interval: None,
+            frames_written: 0,
+            frames_read: 0,
         }
     }
 
@@ -124,6 +178,34 @@ impl<TSocket> NoiseSocket<TSocket> {
     pub fn get_remote_static(&self) -> Option<&[u8]> {
         self.session.get_remote_static()
     }
+
+    /// Count a frame written, replacing the outgoing key once `rekey_interval` frames were
+    /// encrypted with it.
+    fn frame_written(&mut self) {
+        if let (NoiseSession::Transport(session), Some(interval)) =
+            (&mut self.session, self.rekey_interval)
+        {
+            self.frames_written += 1;
+            if self.frames_written >= interval {
+                session.rekey_outgoing();
+                self.frames_written = 0;
+            }
+        }
+    }
+
+    /// Count a frame read, replacing the incoming key once `rekey_interval` frames were
+    /// decrypted with it.
+    fn frame_read(&mut self) {
+        if let (NoiseSession::Transport(session), Some(interval)) =
+            (&mut self.session, self.rekey_interval)
+        {
+            self.frames_read += 1;
+            if self.frames_read >= interval {
+                session.rekey_incoming();
+                self.frames_read = 0;
+            }
+        }
+    }
 }
 
 fn poll_write_all<TSocket>(
@@ -262,6 +344,7 @@ where
                                 &mut self.buffers.read_decrypted,
                             ) {
                                 Ok(decrypted_len) => {
+                                    self.frame_read();
                                     self.read_state = ReadState::CopyDecryptedFrame {
                                         decrypted_len,
                                         offset: 0,
@@ -378,6 +461,7 @@ where
                                 let frame_len = encrypted_len
                                     .try_into()
                                     .expect("offset should be able to fit in u16");
+                                self.frame_written();
                                 self.write_state = WriteState::WriteFrameLen {
                                     frame_len,
                                     buf: u16::to_be_bytes(frame_len),
@@ -503,8 +587,8 @@ where
 pub(super) struct Handshake<TSocket>(NoiseSocket<TSocket>);
 
 impl<TSocket> Handshake<TSocket> {
-    /// Build a new `Handshake` struct given a socket and a new snow Session
-    pub fn new(socket: TSocket, session: snow::Session) -> Self {
+    /// Build a new `Handshake` struct given a socket and a new snow HandshakeState
+    pub fn new(socket: TSocket, session: snow::HandshakeState) -> Self {
         let noise_socket = NoiseSocket::new(socket, session);
         Self(noise_socket)
     }
@@ -514,27 +598,66 @@ impl<TSocket> Handshake<TSocket>
 where
     TSocket: AsyncRead + AsyncWrite + Unpin,
 {
-    /// Perform a Single Round-Trip noise IX handshake returning the underlying [NoiseSocket]
+    /// Perform a Single Round-Trip noise IK handshake returning the underlying [NoiseSocket]
     /// (switched to transport mode) upon success.
-    pub async fn handshake_1rt(mut self) -> io::Result<NoiseSocket<TSocket>> {
+    pub async fn handshake_1rt(self) -> io::Result<NoiseSocket<TSocket>> {
+        let ((), socket) = self.handshake_1rt_authenticated(|_| Some(())).await?;
+        Ok(socket)
+    }
+
+    /// Perform a Single Round-Trip noise IK handshake, authenticating the static public key of
+    /// the remote: the listener rejects an unknown dialer before answering, and the dialer, which
+    /// already knows the static public key of the listener, only completes the handshake with the
+    /// owner of that key.
+    ///
+    /// `authenticate` maps the static public key of the remote to its identity, or returns None
+    /// if the key isn't trusted. Upon success, returns the identity of the remote along with the
+    /// underlying [NoiseSocket] (switched to transport mode).
+    pub async fn handshake_1rt_authenticated<F, T>(
+        mut self,
+        authenticate: F,
+    ) -> io::Result<(T, NoiseSocket<TSocket>)>
+    where
+        F: FnOnce(&[u8]) -> Option<T> + Send,
+        T: Send,
+    {
         // The Dialer
-        if self.0.session.is_initiator() {
-            // -> e, s
+        let remote = if self.0.session.is_initiator() {
+            // -> e, es, s, ss
             self.send().await?;
             self.flush().await?;
 
-            // <- e, ee, se, s, es
+            // <- e, ee, se
             self.receive().await?;
+            self.authenticate(authenticate)?
         } else {
-            // -> e, s
+            // -> e, es, s, ss
             self.receive().await?;
+            let remote = self.authenticate(authenticate)?;
 
-            // <- e, ee, se, s, es
+            // <- e, ee, se
             self.send().await?;
             self.flush().await?;
-        }
+            remote
+        };
 
-        self.finish()
+        Ok((remote, self.finish()?))
+    }
+
+    /// Authenticate the static public key received from the remote.
+    fn authenticate<F, T>(&self, authenticate: F) -> io::Result<T>
+    where
+        F: FnOnce(&[u8]) -> Option<T>,
+    {
+        let remote_static = self.0.get_remote_static().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "Missing remote static key")
+        })?;
+        authenticate(remote_static).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Untrusted remote static key",
+            )
+        })
     }
 
     /// Send handshake message to remote.
@@ -560,12 +683,22 @@ where
     ///
     /// Converts the noise session into transport mode and returns the NoiseSocket.
     fn finish(self) -> io::Result<NoiseSocket<TSocket>> {
-        let session = self
-            .0
-            .session
-            .into_transport_mode()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Noise error: {}", e)))?;
-        Ok(NoiseSocket { session, ..self.0 })
+        let session = match self.0.session {
+            NoiseSession::Handshake(session) => session
+                .into_transport_mode()
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Noise error: {}", e)))?,
+            NoiseSession::Transport(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "Noise error: handshake already finished",
+                ))
+            }
+        };
+        Ok(NoiseSocket {
+            session: NoiseSession::Transport(session),
+            rekey_interval: Some(REKEY_INTERVAL),
+            ..self.0
+        })
     }
 }
 
@@ -573,7 +706,7 @@ where
 mod test {
     use crate::{
         socket::{Handshake, NoiseSocket, MAX_PAYLOAD_LENGTH},
-        NOISE_IX_PARAMETER,
+        NOISE_IK_PARAMETER,
     };
     use futures::{
         executor::block_on,
@@ -581,7 +714,7 @@ mod test {
         io::{AsyncReadExt, AsyncWriteExt},
     };
     use memsocket::MemorySocket;
-    use snow::{params::NoiseParams, Builder, Keypair, SnowError};
+    use snow::{params::NoiseParams, Builder, Error, Keypair};
     use std::io;
 
     fn build_test_connection() -> Result<
@@ -589,15 +722,31 @@ mod test {
             (Keypair, Handshake<MemorySocket>),
             (Keypair, Handshake<MemorySocket>),
         ),
-        SnowError,
+        Error,
     > {
-        let parameters: NoiseParams = NOISE_IX_PARAMETER.parse().expect("Invalid protocol name");
+        let parameters: NoiseParams = NOISE_IK_PARAMETER.parse().expect("Invalid protocol name");
+        let listener_keypair = Builder::new(parameters.clone()).generate_keypair()?;
+        build_test_connection_to(&listener_keypair.public, listener_keypair)
+    }
+
+    // Builds a connection where the dialer expects the listener to own `remote_public_key`.
+    fn build_test_connection_to(
+        remote_public_key: &[u8],
+        listener_keypair: Keypair,
+    ) -> Result<
+        (
+            (Keypair, Handshake<MemorySocket>),
+            (Keypair, Handshake<MemorySocket>),
+        ),
+        Error,
+    > {
+        let parameters: NoiseParams = NOISE_IK_PARAMETER.parse().expect("Invalid protocol name");
 
         let dialer_keypair = Builder::new(parameters.clone()).generate_keypair()?;
-        let listener_keypair = Builder::new(parameters.clone()).generate_keypair()?;
 
         let dialer_session = Builder::new(parameters.clone())
             .local_private_key(&dialer_keypair.private)
+            .remote_public_key(remote_public_key)
             .build_initiator()?;
         let listener_session = Builder::new(parameters.clone())
             .local_private_key(&listener_keypair.private)
@@ -642,6 +791,112 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_handshake_authenticated() {
+        let ((dialer_keypair, dialer), (listener_keypair, listener)) =
+            build_test_connection().unwrap();
+
+        let (dialer_result, listener_result) = block_on(join(
+            dialer.handshake_1rt_authenticated(|key| {
+                if key == listener_keypair.public.as_slice() {
+                    Some("listener")
+                } else {
+                    None
+                }
+            }),
+            listener.handshake_1rt_authenticated(|key| {
+                if key == dialer_keypair.public.as_slice() {
+                    Some("dialer")
+                } else {
+                    None
+                }
+            }),
+        ));
+
+        assert_eq!(dialer_result.unwrap().0, "listener");
+        assert_eq!(listener_result.unwrap().0, "dialer");
+    }
+
+    #[test]
+    fn test_handshake_untrusted_dialer() {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) =
+            build_test_connection().unwrap();
+
+        let (dialer_result, listener_result) = block_on(join(
+            dialer.handshake_1rt(),
+            listener.handshake_1rt_authenticated(|_| None::<()>),
+        ));
+
+        // The listener rejects the dialer before answering.
+        assert_eq!(
+            listener_result.unwrap_err().kind(),
+            io::ErrorKind::PermissionDenied
+        );
+        assert!(dialer_result.is_err());
+    }
+
+    #[test]
+    fn test_handshake_untrusted_listener() {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) =
+            build_test_connection().unwrap();
+
+        let (dialer_result, _listener_result) = block_on(join(
+            dialer.handshake_1rt_authenticated(|_| None::<()>),
+            listener.handshake_1rt(),
+        ));
+
+        assert_eq!(
+            dialer_result.unwrap_err().kind(),
+            io::ErrorKind::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn test_handshake_wrong_listener_key() {
+        let parameters: NoiseParams = NOISE_IK_PARAMETER.parse().unwrap();
+        let expected_keypair = Builder::new(parameters.clone()).generate_keypair().unwrap();
+        let listener_keypair = Builder::new(parameters).generate_keypair().unwrap();
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) =
+            build_test_connection_to(&expected_keypair.public, listener_keypair).unwrap();
+
+        // The listener can't decrypt a first message meant for another key.
+        let (dialer_result, listener_result) =
+            block_on(join(dialer.handshake_1rt(), listener.handshake_1rt()));
+        assert!(listener_result.is_err());
+        assert!(dialer_result.is_err());
+    }
+
+    #[test]
+    fn rekey() -> io::Result<()> {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) =
+            build_test_connection().unwrap();
+
+        let (mut a, mut b) = perform_handshake(dialer, listener)?;
+        a.rekey_interval = Some(2);
+        b.rekey_interval = Some(2);
+
+        for i in 0..5u8 {
+            block_on(a.write_all(&[i; 10]))?;
+            block_on(a.flush())?;
+            block_on(b.write_all(&[i; 20]))?;
+            block_on(b.flush())?;
+
+            let mut buf = [0; 10];
+            block_on(b.read_exact(&mut buf))?;
+            assert_eq!(buf, [i; 10]);
+            let mut buf = [0; 20];
+            block_on(a.read_exact(&mut buf))?;
+            assert_eq!(buf, [i; 20]);
+        }
+        // Both sides went through two rekeys of each direction and agree on the next one.
+        assert_eq!(a.frames_written, 1);
+        assert_eq!(b.frames_read, 1);
+        assert_eq!(b.frames_written, 1);
+        assert_eq!(a.frames_read, 1);
+
+        Ok(())
+    }
+
     #[test]
     fn simple_test() -> io::Result<()> {
         let ((_dialer_keypair, dialer), (_listener_keypair, listener)) =
@@ -20,9 +20,10 @@ use netcore::{
         Transport, TransportExt,
     },
 };
+use nextgen_crypto::x25519::X25519StaticPrivateKey;
 use noise::{NoiseConfig, NoiseSocket};
 use parity_multiaddr::Multiaddr;
-use std::{convert::TryInto, env, ffi::OsString, sync::Arc};
+use std::{convert::TryInto, env, ffi::OsString};
 use tokio::{codec::Framed, runtime::TaskExecutor};
 use unsigned_varint::codec::UviBytes;
 
@@ -78,13 +79,24 @@ impl Args {
     }
 }
 
+/// Build the Noise configuration of the benchmark. The dialer needs to know the static public key
+/// of the listener, so the server and the clients share a keypair derived from a fixed seed.
+fn build_noise_config() -> NoiseConfig {
+    NoiseConfig::new(X25519StaticPrivateKey::derive_keypair_from_seed(
+        None,
+        b"socket_bench_server",
+        None,
+    ))
+}
+
 /// Build a MemorySocket + Noise transport
 pub fn build_memsocket_noise_transport() -> impl Transport<Output = NoiseSocket<MemorySocket>> {
-    MemoryTransport::default().and_then(move |socket, origin| {
+    MemoryTransport::default().and_then(move |socket, _addr, origin| {
         async move {
-            let noise_config = Arc::new(NoiseConfig::new_random());
-            let (_remote_static_key, socket) =
-                noise_config.upgrade_connection(socket, origin).await?;
+            let noise_config = build_noise_config();
+            let (_remote_static_key, socket) = noise_config
+                .upgrade_connection(socket, origin, Some(noise_config.public_key()))
+                .await?;
             Ok(socket)
         }
     })
@@ -92,30 +104,33 @@ pub fn build_memsocket_noise_transport() -> impl Transport<Output = NoiseSocket<
 
 /// Build a MemorySocket + Muxer transport
 pub fn build_memsocket_muxer_transport() -> impl Transport<Output = impl StreamMultiplexer> {
-    MemoryTransport::default().and_then(Yamux::upgrade_connection)
+    MemoryTransport::default()
+        .and_then(|socket, _addr, origin| Yamux::upgrade_connection(socket, origin))
 }
 
 /// Build a MemorySocket + Noise + Muxer transport
 pub fn build_memsocket_noise_muxer_transport() -> impl Transport<Output = impl StreamMultiplexer> {
     MemoryTransport::default()
-        .and_then(move |socket, origin| {
+        .and_then(move |socket, _addr, origin| {
             async move {
-                let noise_config = Arc::new(NoiseConfig::new_random());
-                let (_remote_static_key, socket) =
-                    noise_config.upgrade_connection(socket, origin).await?;
+                let noise_config = build_noise_config();
+                let (_remote_static_key, socket) = noise_config
+                    .upgrade_connection(socket, origin, Some(noise_config.public_key()))
+                    .await?;
                 Ok(socket)
             }
         })
-        .and_then(Yamux::upgrade_connection)
+        .and_then(|socket, _addr, origin| Yamux::upgrade_connection(socket, origin))
 }
 
 /// Build a Tcp + Noise transport
 pub fn build_tcp_noise_transport() -> impl Transport<Output = NoiseSocket<TcpSocket>> {
-    TcpTransport::default().and_then(move |socket, origin| {
+    TcpTransport::default().and_then(move |socket, _addr, origin| {
         async move {
-            let noise_config = Arc::new(NoiseConfig::new_random());
-            let (_remote_static_key, socket) =
-                noise_config.upgrade_connection(socket, origin).await?;
+            let noise_config = build_noise_config();
+            let (_remote_static_key, socket) = noise_config
+                .upgrade_connection(socket, origin, Some(noise_config.public_key()))
+                .await?;
             Ok(socket)
         }
     })
@@ -123,21 +138,23 @@ pub fn build_tcp_noise_transport() -> impl Transport<Output = NoiseSocket<TcpSoc
 
 /// Build a Tcp + Muxer transport
 pub fn build_tcp_muxer_transport() -> impl Transport<Output = impl StreamMultiplexer> {
-    TcpTransport::default().and_then(Yamux::upgrade_connection)
+    TcpTransport::default()
+        .and_then(|socket, _addr, origin| Yamux::upgrade_connection(socket, origin))
 }
 
 /// Build a Tcp + Noise + Muxer transport
 pub fn build_tcp_noise_muxer_transport() -> impl Transport<Output = impl StreamMultiplexer> {
     TcpTransport::default()
-        .and_then(move |socket, origin| {
+        .and_then(move |socket, _addr, origin| {
             async move {
-                let noise_config = Arc::new(NoiseConfig::new_random());
-                let (_remote_static_key, socket) =
-                    noise_config.upgrade_connection(socket, origin).await?;
+                let noise_config = build_noise_config();
+                let (_remote_static_key, socket) = noise_config
+                    .upgrade_connection(socket, origin, Some(noise_config.public_key()))
+                    .await?;
                 Ok(socket)
             }
         })
-        .and_then(Yamux::upgrade_connection)
+        .and_then(|socket, _addr, origin| Yamux::upgrade_connection(socket, origin))
 }
 
 /// Server side handler for send throughput benchmark when the messages are sent
@@ -12,7 +12,10 @@
 //!  * An actor responsible for dialing and listening for new connections.
 //!  * An actor per Peer which owns the underlying connection and is responsible for listening for
 //!  and opening substreams as well as negotiating particular protocols on those substreams.
-use crate::{common::NegotiatedSubstream, counters, protocols::identity::Identity, ProtocolId};
+use crate::{
+    common::NegotiatedSubstream, counters, protocols::identity::Identity, transport::with_peer_id,
+    ProtocolId,
+};
 use channel;
 use futures::{
     channel::oneshot,
@@ -597,7 +600,8 @@ where
     > {
         match dial_peer_request {
             ConnectionHandlerRequest::DialPeer(peer_id, address, response_tx) => {
-                match self.transport.dial(address.clone()) {
+                // The dialed address names the peer, so that the transport can authenticate it
+                match self.transport.dial(with_peer_id(&address, peer_id)) {
                     Ok(upgrade) => Some(
                         upgrade
                             .map(move |out| (out, address, peer_id, response_tx))
@@ -48,14 +48,16 @@ fn build_test_transport(
     let own_identity = Identity::new(own_peer_id, Vec::new());
 
     memory_transport
-        .and_then(move |socket, origin| peer_id_exchange_config.exchange_peer_id(socket, origin))
-        .and_then(|(peer_id, socket), origin| {
+        .and_then(move |socket, _addr, origin| {
+            peer_id_exchange_config.exchange_peer_id(socket, origin)
+        })
+        .and_then(|(peer_id, socket), _addr, origin| {
             async move {
                 let muxer = Yamux::upgrade_connection(socket, origin).await?;
                 Ok((peer_id, muxer))
             }
         })
-        .and_then(move |(peer_id, muxer), origin| {
+        .and_then(move |(peer_id, muxer), _addr, origin| {
             async move {
                 let (identity, muxer) = exchange_identity(&own_identity, muxer, origin).await?;
                 assert_eq!(identity.peer_id(), peer_id);
@@ -9,13 +9,14 @@ mod mempool;
 mod network;
 mod state_synchronizer;
 
-use types::proto::{ledger_info, transaction};
+use types::proto::{account_state_blob, ledger_info, transaction};
 
 pub use self::{
     consensus::{
         Block, BlockRetrievalStatus, ConsensusMsg, PacemakerTimeout, PacemakerTimeoutCertificate,
-        Proposal, QuorumCert, RequestBlock, RequestChunk, RespondBlock, RespondChunk, SyncInfo,
-        TimeoutMsg, Vote,
+        Proposal, QuorumCert, RequestAccountStateChunk, RequestBatches, RequestBlock, RequestChunk,
+        RespondAccountStateChunk, RespondBatches, RespondBlock, RespondChunk, SyncInfo, TimeoutMsg,
+        TxnBatch, Vote,
     },
     mempool::MempoolSyncMsg,
     network::{DiscoveryMsg, IdentityMsg, Note, PeerInfo, Ping, Pong},
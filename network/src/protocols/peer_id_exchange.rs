@@ -87,7 +87,9 @@ mod tests {
         let peer_identifier_config = PeerIdExchange::new(peer_id);
 
         transport
-            .and_then(move |socket, origin| peer_identifier_config.exchange_peer_id(socket, origin))
+            .and_then(move |socket, _addr, origin| {
+                peer_identifier_config.exchange_peer_id(socket, origin)
+            })
             .boxed()
     }
 
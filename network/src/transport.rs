@@ -6,26 +6,78 @@ use crate::{
     protocols::identity::{exchange_identity, Identity},
 };
 use logger::prelude::*;
+use multihash::Multihash;
 use netcore::{
     multiplexing::{yamux::Yamux, StreamMultiplexer},
-    transport::{boxed, memory, tcp, TransportExt},
+    transport::{boxed, memory, tcp, ConnectionOrigin, TransportExt},
 };
 use nextgen_crypto::{
     x25519::{X25519StaticPrivateKey, X25519StaticPublicKey},
     ValidKey,
 };
 use noise::NoiseConfig;
+use parity_multiaddr::{Multiaddr, Protocol};
 use std::{
     collections::HashMap,
+    convert::TryFrom,
     io,
     sync::{Arc, RwLock},
     time::Duration,
 };
-use types::PeerId;
+use types::{account_address::ADDRESS_LENGTH, PeerId};
 
 /// A timeout for the connection to open and complete all of the upgrade steps.
 const TRANSPORT_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Multihash code of the peer ids in the `/p2p` component of the dialed addresses (sha3-256, as
+/// peer ids are hashes of public keys).
+const PEER_ID_MULTIHASH_CODE: u8 = 0x16;
+
+/// Appends the peer expected at `addr` to it as a `/p2p` component. The noise transports need it
+/// to know the static public key of the listener before the handshake.
+pub fn with_peer_id(addr: &Multiaddr, peer_id: PeerId) -> Multiaddr {
+    let mut bytes = vec![PEER_ID_MULTIHASH_CODE, ADDRESS_LENGTH as u8];
+    bytes.extend_from_slice(peer_id.as_ref());
+    let peer_hash = Multihash::from_bytes(bytes).expect("A peer id is a valid multihash digest");
+    addr.clone().with(Protocol::P2p(peer_hash))
+}
+
+/// Returns the peer named by the `/p2p` component of `addr`, if any.
+fn addr_to_peer_id(addr: &Multiaddr) -> Option<PeerId> {
+    match addr.iter().last() {
+        Some(Protocol::P2p(peer_hash)) => PeerId::try_from(peer_hash.digest()).ok(),
+        _ => None,
+    }
+}
+
+/// Returns the static public key of the trusted peer named by the dialed address, which the noise
+/// IK handshake requires from the dialer.
+fn dialed_peer_static_key(
+    trusted_peers: &RwLock<HashMap<PeerId, NetworkPublicKeys>>,
+    addr: &Multiaddr,
+) -> io::Result<Vec<u8>> {
+    let peer_id = addr_to_peer_id(addr).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Dialed address '{}' doesn't name a peer", addr),
+        )
+    })?;
+    match trusted_peers.read().unwrap().get(&peer_id) {
+        Some(public_keys) => Ok(public_keys.identity_public_key.to_bytes()),
+        None => {
+            security_log(SecurityEvent::InvalidNetworkPeer)
+                .error("UntrustedPeer")
+                .data(&peer_id)
+                .data(&addr)
+                .log();
+            Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("Dialed peer {} isn't trusted", peer_id.short_str()),
+            ))
+        }
+    }
+}
+
 fn identity_key_to_peer_id(
     trusted_peers: &RwLock<HashMap<PeerId, NetworkPublicKeys>>,
     remote_static_key: &[u8],
@@ -39,6 +91,23 @@ fn identity_key_to_peer_id(
     None
 }
 
+/// Authenticates the static public key received during the Noise handshake against the trusted
+/// peers: the peer id of the remote is the one its key is registered with.
+fn authenticate_peer(
+    trusted_peers: &RwLock<HashMap<PeerId, NetworkPublicKeys>>,
+    remote_static_key: &[u8],
+) -> Option<PeerId> {
+    let peer_id = identity_key_to_peer_id(trusted_peers, remote_static_key);
+    if peer_id.is_none() {
+        security_log(SecurityEvent::InvalidNetworkPeer)
+            .error("UntrustedPeer")
+            .data(&trusted_peers)
+            .data(&remote_static_key)
+            .log();
+    }
+    peer_id
+}
+
 pub fn build_memory_noise_transport(
     own_identity: Identity,
     identity_keypair: (X25519StaticPrivateKey, X25519StaticPublicKey),
@@ -48,25 +117,33 @@ pub fn build_memory_noise_transport(
     let noise_config = Arc::new(NoiseConfig::new(identity_keypair));
 
     memory_transport
-        .and_then(move |socket, origin| {
+        .and_then(move |socket, addr, origin| {
             async move {
-                let (remote_static_key, socket) =
-                    noise_config.upgrade_connection(socket, origin).await?;
-
-                if let Some(peer_id) = identity_key_to_peer_id(&trusted_peers, &remote_static_key) {
-                    Ok((peer_id, socket))
-                } else {
-                    Err(io::Error::new(io::ErrorKind::Other, "Not a trusted peer"))
-                }
+                let remote_static_key = match origin {
+                    ConnectionOrigin::Outbound => {
+                        Some(dialed_peer_static_key(&trusted_peers, &addr)?)
+                    }
+                    ConnectionOrigin::Inbound => None,
+                };
+                // Untrusted peers are rejected during the handshake, before any substream is
+                // opened.
+                noise_config
+                    .upgrade_connection_authenticated(
+                        socket,
+                        origin,
+                        remote_static_key.as_ref().map(Vec::as_slice),
+                        |remote_static_key| authenticate_peer(&trusted_peers, remote_static_key),
+                    )
+                    .await
             }
         })
-        .and_then(|(peer_id, socket), origin| {
+        .and_then(|(peer_id, socket), _addr, origin| {
             async move {
                 let muxer = Yamux::upgrade_connection(socket, origin).await?;
                 Ok((peer_id, muxer))
             }
         })
-        .and_then(move |(peer_id, muxer), origin| {
+        .and_then(move |(peer_id, muxer), _addr, origin| {
             async move {
                 let (identity, muxer) = exchange_identity(&own_identity, muxer, origin).await?;
 
@@ -94,13 +171,13 @@ pub fn build_memory_transport(
     let memory_transport = memory::MemoryTransport::default();
 
     memory_transport
-        .and_then(|socket, origin| {
+        .and_then(|socket, _addr, origin| {
             async move {
                 let muxer = Yamux::upgrade_connection(socket, origin).await?;
                 Ok(muxer)
             }
         })
-        .and_then(move |muxer, origin| {
+        .and_then(move |muxer, _addr, origin| {
             async move {
                 let (identity, muxer) = exchange_identity(&own_identity, muxer, origin).await?;
 
@@ -121,30 +198,33 @@ pub fn build_tcp_noise_transport(
     let noise_config = Arc::new(NoiseConfig::new(identity_keypair));
 
     tcp_transport
-        .and_then(move |socket, origin| {
+        .and_then(move |socket, addr, origin| {
             async move {
-                let (remote_static_key, socket) =
-                    noise_config.upgrade_connection(socket, origin).await?;
-
-                if let Some(peer_id) = identity_key_to_peer_id(&trusted_peers, &remote_static_key) {
-                    Ok((peer_id, socket))
-                } else {
-                    security_log(SecurityEvent::InvalidNetworkPeer)
-                        .error("UntrustedPeer")
-                        .data(&trusted_peers)
-                        .data(&remote_static_key)
-                        .log();
-                    Err(io::Error::new(io::ErrorKind::Other, "Not a trusted peer"))
-                }
+                let remote_static_key = match origin {
+                    ConnectionOrigin::Outbound => {
+                        Some(dialed_peer_static_key(&trusted_peers, &addr)?)
+                    }
+                    ConnectionOrigin::Inbound => None,
+                };
+                // Untrusted peers are rejected during the handshake, before any substream is
+                // opened.
+                noise_config
+                    .upgrade_connection_authenticated(
+                        socket,
+                        origin,
+                        remote_static_key.as_ref().map(Vec::as_slice),
+                        |remote_static_key| authenticate_peer(&trusted_peers, remote_static_key),
+                    )
+                    .await
             }
         })
-        .and_then(|(peer_id, socket), origin| {
+        .and_then(|(peer_id, socket), _addr, origin| {
             async move {
                 let muxer = Yamux::upgrade_connection(socket, origin).await?;
                 Ok((peer_id, muxer))
             }
         })
-        .and_then(move |(peer_id, muxer), origin| {
+        .and_then(move |(peer_id, muxer), _addr, origin| {
             async move {
                 let (identity, muxer) = exchange_identity(&own_identity, muxer, origin).await?;
 
@@ -178,13 +258,13 @@ pub fn build_tcp_transport(
     let tcp_transport = tcp::TcpTransport::default();
 
     tcp_transport
-        .and_then(|socket, origin| {
+        .and_then(|socket, _addr, origin| {
             async move {
                 let muxer = Yamux::upgrade_connection(socket, origin).await?;
                 Ok(muxer)
             }
         })
-        .and_then(move |muxer, origin| {
+        .and_then(move |muxer, _addr, origin| {
             async move {
                 let (identity, muxer) = exchange_identity(&own_identity, muxer, origin).await?;
 
@@ -6,7 +6,10 @@
 use crate::{
     error::NetworkError,
     interface::{NetworkNotification, NetworkRequest},
-    proto::{ConsensusMsg, RequestBlock, RequestChunk, RespondBlock, RespondChunk},
+    proto::{
+        ConsensusMsg, RequestAccountStateChunk, RequestBatches, RequestBlock, RequestChunk,
+        RespondAccountStateChunk, RespondBatches, RespondBlock, RespondChunk, TxnBatch,
+    },
     protocols::{
         direct_send::Message,
         rpc::{self, error::RpcError},
@@ -30,6 +33,10 @@ use types::{validator_public_keys::ValidatorPublicKeys, PeerId};
 pub const CONSENSUS_RPC_PROTOCOL: &[u8] = b"/libra/consensus/rpc/0.1.0";
 /// Protocol id for consensus direct-send calls
 pub const CONSENSUS_DIRECT_SEND_PROTOCOL: &[u8] = b"/libra/consensus/direct-send/0.1.0";
+/// Protocol id for the direct-send of the transaction batches disseminated ahead of the proposals
+pub const CONSENSUS_BATCH_DIRECT_SEND_PROTOCOL: &[u8] = b"/libra/consensus/batch/direct-send/0.1.0";
+/// Protocol id for the RPC calls fetching the transaction batches referenced by the proposals
+pub const CONSENSUS_BATCH_RPC_PROTOCOL: &[u8] = b"/libra/consensus/batch/rpc/0.1.0";
 
 /// The interface from Network to Consensus layer.
 ///
@@ -186,6 +193,93 @@ impl ConsensusNetworkSender {
         }
     }
 
+    /// Send a RequestAccountStateChunk RPC request to remote peer `recipient`. Returns the
+    /// future `RespondAccountStateChunk` returned by the remote peer.
+    ///
+    /// The rpc request can be canceled at any point by dropping the returned
+    /// future.
+    pub async fn request_account_state_chunk(
+        &mut self,
+        recipient: PeerId,
+        req_msg: RequestAccountStateChunk,
+        timeout: Duration,
+    ) -> Result<RespondAccountStateChunk, RpcError> {
+        let protocol = ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL);
+        let mut req_msg_enum = ConsensusMsg::new();
+        req_msg_enum.set_request_account_state_chunk(req_msg);
+
+        let mut res_msg_enum = rpc::utils::unary_rpc(
+            self.inner.clone(),
+            recipient,
+            protocol,
+            req_msg_enum,
+            timeout,
+        )
+        .await?;
+
+        if res_msg_enum.has_respond_account_state_chunk() {
+            Ok(res_msg_enum.take_respond_account_state_chunk())
+        } else {
+            // TODO: context
+            Err(RpcError::InvalidRpcResponse)
+        }
+    }
+
+    /// Send a transaction batch to remote peer `recipient` over the batch dissemination
+    /// protocol, so that the proposals can refer to it by its digest.
+    ///
+    /// Like `send_to`, the returned Future resolves when the message has been enqueued on the
+    /// network actor's event queue.
+    pub async fn send_batch(
+        &mut self,
+        recipient: PeerId,
+        batch: TxnBatch,
+    ) -> Result<(), NetworkError> {
+        let mut message = ConsensusMsg::new();
+        message.set_txn_batch(batch);
+        self.inner
+            .send(NetworkRequest::SendMessage(
+                recipient,
+                Message {
+                    protocol: ProtocolId::from_static(CONSENSUS_BATCH_DIRECT_SEND_PROTOCOL),
+                    mdata: Bytes::from(message.write_to_bytes().unwrap()),
+                },
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Send a RequestBatches RPC request to remote peer `recipient`. Returns the
+    /// future `RespondBatches` returned by the remote peer.
+    ///
+    /// The rpc request can be canceled at any point by dropping the returned
+    /// future.
+    pub async fn request_batches(
+        &mut self,
+        recipient: PeerId,
+        req_msg: RequestBatches,
+        timeout: Duration,
+    ) -> Result<RespondBatches, RpcError> {
+        let protocol = ProtocolId::from_static(CONSENSUS_BATCH_RPC_PROTOCOL);
+        let mut req_msg_enum = ConsensusMsg::new();
+        req_msg_enum.set_request_batches(req_msg);
+
+        let mut res_msg_enum = rpc::utils::unary_rpc(
+            self.inner.clone(),
+            recipient,
+            protocol,
+            req_msg_enum,
+            timeout,
+        )
+        .await?;
+
+        if res_msg_enum.has_respond_batches() {
+            Ok(res_msg_enum.take_respond_batches())
+        } else {
+            Err(RpcError::InvalidRpcResponse)
+        }
+    }
+
     pub async fn update_eligible_nodes(
         &mut self,
         validators: Vec<ValidatorPublicKeys>,
@@ -285,6 +379,33 @@ mod tests {
         }
     }
 
+    // The transaction batches should be sent over the batch dissemination protocol
+    #[test]
+    fn test_consensus_send_batch() {
+        let (network_reqs_tx, mut network_reqs_rx) = channel::new_test(8);
+        let mut sender = ConsensusNetworkSender::new(network_reqs_tx);
+
+        let peer_id = PeerId::random();
+        let mut batch = TxnBatch::new();
+        batch.set_author(PeerId::random().into());
+        let mut expected_msg = ConsensusMsg::new();
+        expected_msg.set_txn_batch(batch.clone());
+        let expected_network_msg = Message {
+            protocol: ProtocolId::from_static(CONSENSUS_BATCH_DIRECT_SEND_PROTOCOL),
+            mdata: expected_msg.write_to_bytes().unwrap().into(),
+        };
+
+        block_on(sender.send_batch(peer_id, batch)).unwrap();
+
+        match block_on(network_reqs_rx.next()).unwrap() {
+            NetworkRequest::SendMessage(recv_peer_id, network_msg) => {
+                assert_eq!(recv_peer_id, peer_id);
+                assert_eq!(network_msg, expected_network_msg);
+            }
+            event => panic!("Unexpected event: {:?}", event),
+        }
+    }
+
     // `ConsensusNetworkEvents` should deserialize inbound RPC requests
     #[test]
     fn test_consensus_inbound_rpc() {
@@ -17,8 +17,8 @@ mod test;
 
 // Public re-exports
 pub use consensus::{
-    ConsensusNetworkEvents, ConsensusNetworkSender, CONSENSUS_DIRECT_SEND_PROTOCOL,
-    CONSENSUS_RPC_PROTOCOL,
+    ConsensusNetworkEvents, ConsensusNetworkSender, CONSENSUS_BATCH_DIRECT_SEND_PROTOCOL,
+    CONSENSUS_BATCH_RPC_PROTOCOL, CONSENSUS_DIRECT_SEND_PROTOCOL, CONSENSUS_RPC_PROTOCOL,
 };
 pub use mempool::{MempoolNetworkEvents, MempoolNetworkSender, MEMPOOL_DIRECT_SEND_PROTOCOL};
 pub use state_synchronizer::{
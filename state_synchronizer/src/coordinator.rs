@@ -1,10 +1,18 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters, downloader::FetchChunkMsg};
+use crate::{
+    counters,
+    downloader::{DownloaderMsg, FetchAccountStateChunkMsg, FetchChunkMsg},
+};
 use config::config::NodeConfig;
+use crypto::HashValue;
 use execution_proto::proto::{
-    execution::{ExecuteChunkRequest, ExecuteChunkResponse},
+    execution::{
+        ExecuteChunkRequest, ExecuteChunkResponse, GetStateSnapshotRestoreProgressRequest,
+        GetStateSnapshotRestoreProgressResponse, RestoreStateSnapshotChunkRequest,
+        RestoreStateSnapshotChunkResponse,
+    },
     execution_grpc::ExecutionClient,
 };
 use failure::prelude::*;
@@ -19,7 +27,10 @@ use nextgen_crypto::ed25519::*;
 use proto_conv::IntoProto;
 use std::{collections::BTreeMap, pin::Pin, sync::Arc};
 use storage_client::{StorageRead, StorageReadServiceClient};
-use types::{ledger_info::LedgerInfoWithSignatures, proto::transaction::TransactionListWithProof};
+use types::{
+    account_state_blob::AccountStateRangeWithProof, ledger_info::LedgerInfoWithSignatures,
+    proto::transaction::TransactionListWithProof,
+};
 
 /// unified message used for communication with Coordinator
 // TODO: remove lint whitelist
@@ -37,6 +48,12 @@ pub enum CoordinatorMsg {
         Result<TransactionListWithProof>,
         LedgerInfoWithSignatures<Ed25519Signature>,
     ),
+    // is sent from Downloader to Coordinator to indicate that a chunk of the state snapshot
+    // (with the frozen subtree hashes of the transaction accumulator) is ready
+    FetchedAccountStates(
+        Result<(AccountStateRangeWithProof, Vec<HashValue>)>,
+        LedgerInfoWithSignatures<Ed25519Signature>,
+    ),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -55,7 +72,9 @@ pub struct SyncCoordinator<T> {
     // communication with SyncCoordinator is done via this channel
     receiver: mpsc::UnboundedReceiver<CoordinatorMsg>,
     // connection to transaction fetcher
-    sender_to_downloader: mpsc::Sender<FetchChunkMsg>,
+    sender_to_downloader: mpsc::Sender<DownloaderMsg>,
+    // whether to bootstrap an empty node from a state snapshot instead of replaying transactions
+    enable_snapshot_sync: bool,
 
     // last committed version that validator is aware of
     known_version: u64,
@@ -72,12 +91,14 @@ pub struct SyncCoordinator<T> {
 impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
     pub fn new(
         receiver: mpsc::UnboundedReceiver<CoordinatorMsg>,
-        sender_to_downloader: mpsc::Sender<FetchChunkMsg>,
+        sender_to_downloader: mpsc::Sender<DownloaderMsg>,
+        enable_snapshot_sync: bool,
         executor_proxy: T,
     ) -> Self {
         Self {
             receiver,
             sender_to_downloader,
+            enable_snapshot_sync,
 
             known_version: 0,
             target: None,
@@ -101,6 +122,20 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                 CoordinatorMsg::Fetched(Err(_), _) => {
                     self.notify_subscribers(SyncStatus::DownloadFailed);
                 }
+                CoordinatorMsg::FetchedAccountStates(
+                    Ok((account_state_chunk, ledger_frozen_subtree_hashes)),
+                    ledger_info_with_sigs,
+                ) => {
+                    self.process_account_states(
+                        account_state_chunk,
+                        ledger_frozen_subtree_hashes,
+                        ledger_info_with_sigs,
+                    )
+                    .await;
+                }
+                CoordinatorMsg::FetchedAccountStates(Err(_), _) => {
+                    self.notify_subscribers(SyncStatus::DownloadFailed);
+                }
                 CoordinatorMsg::Commit(version) => {
                     self.handle_commit(version);
                 }
@@ -159,10 +194,29 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                 Ok(version) => {
                     self.known_version = version;
                     self.sync_position = self.known_version + 1;
-                    // send request to Downloader
-                    let fetch_request = FetchChunkMsg {
-                        start_version: self.sync_position,
-                        target,
+                    // send request to Downloader. A node that only has genesis fetches the
+                    // state snapshot at the target version instead if snapshot sync is enabled,
+                    // resuming from where the executor left off
+                    let fetch_request = if self.enable_snapshot_sync && version == 0 {
+                        let start_key = match self
+                            .get_state_snapshot_restore_next_key(target.ledger_info().version())
+                            .await
+                        {
+                            Ok(start_key) => start_key,
+                            Err(_) => {
+                                self.notify_subscribers(SyncStatus::ExecutionFailed);
+                                return;
+                            }
+                        };
+                        DownloaderMsg::FetchAccountStateChunk(FetchAccountStateChunkMsg {
+                            start_key,
+                            target,
+                        })
+                    } else {
+                        DownloaderMsg::FetchChunk(FetchChunkMsg {
+                            start_version: self.sync_position,
+                            target,
+                        })
                     };
                     if self.sender_to_downloader.send(fetch_request).await.is_err() {
                         self.notify_subscribers(SyncStatus::DownloaderNotAvailable);
@@ -190,10 +244,10 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
 
         if let Some(target) = self.target.clone() {
             if self.sync_position <= self.target_version() {
-                let fetch_msg = FetchChunkMsg {
+                let fetch_msg = DownloaderMsg::FetchChunk(FetchChunkMsg {
                     start_version: self.sync_position,
                     target,
-                };
+                });
                 // start download of next batch
                 if self.sender_to_downloader.send(fetch_msg).await.is_err() {
                     self.notify_subscribers(SyncStatus::DownloaderNotAvailable);
@@ -210,6 +264,64 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         self.notify_subscribers(status);
     }
 
+    /// processes a chunk of the state snapshot downloaded by fetcher
+    /// restores it via executor, and once the whole snapshot is restored updates progress state,
+    /// notifies subscribers and resumes normal sync of transactions if the target moved on
+    async fn process_account_states(
+        &mut self,
+        account_state_chunk: AccountStateRangeWithProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        target: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) {
+        let num_account_states = account_state_chunk.account_blobs.len();
+        let next_start_key = account_state_chunk.next_start_key();
+        let snapshot_version = target.ledger_info().version();
+        let req = execution_proto::RestoreStateSnapshotChunkRequest {
+            account_state_chunk,
+            ledger_frozen_subtree_hashes,
+            ledger_info_with_sigs: target.clone(),
+        };
+        if self
+            .executor_proxy
+            .restore_state_snapshot_chunk(req.into_proto())
+            .await
+            .is_err()
+        {
+            self.notify_subscribers(SyncStatus::ExecutionFailed);
+            return;
+        }
+        counters::STATE_SYNC_ACCOUNT_STATES_RESTORED.inc_by(num_account_states as i64);
+
+        if let Some(start_key) = next_start_key {
+            // start download of next chunk of the snapshot
+            let fetch_msg = DownloaderMsg::FetchAccountStateChunk(FetchAccountStateChunkMsg {
+                start_key,
+                target,
+            });
+            if self.sender_to_downloader.send(fetch_msg).await.is_err() {
+                self.notify_subscribers(SyncStatus::DownloaderNotAvailable);
+            }
+            return;
+        }
+
+        // the snapshot is complete, continue with transactions after it
+        self.known_version = snapshot_version;
+        self.sync_position = snapshot_version + 1;
+        if let Some(target) = self.target.clone() {
+            if self.sync_position <= self.target_version() {
+                let fetch_msg = DownloaderMsg::FetchChunk(FetchChunkMsg {
+                    start_version: self.sync_position,
+                    target,
+                });
+                if self.sender_to_downloader.send(fetch_msg).await.is_err() {
+                    self.notify_subscribers(SyncStatus::DownloaderNotAvailable);
+                    return;
+                }
+            }
+        }
+        self.notify_subscribers(SyncStatus::Finished);
+    }
+
     fn notify_subscribers(&mut self, result: SyncStatus) {
         let mut active_subscribers = match result {
             SyncStatus::Finished => self.subscribers.split_off(&self.sync_position),
@@ -245,6 +357,17 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         self.executor_proxy.execute_chunk(req).await
     }
 
+    /// returns the key the next chunk of the state snapshot at `version` should start with
+    async fn get_state_snapshot_restore_next_key(&self, version: u64) -> Result<HashValue> {
+        let mut req = GetStateSnapshotRestoreProgressRequest::new();
+        req.set_version(version);
+        let resp = self
+            .executor_proxy
+            .get_state_snapshot_restore_progress(req)
+            .await?;
+        HashValue::from_slice(resp.get_next_key())
+    }
+
     fn handle_commit(&self, _version: u64) {
         // TODO: add actual handler
     }
@@ -260,6 +383,18 @@ pub trait ExecutorProxyTrait: Sync + Send {
         &self,
         request: ExecuteChunkRequest,
     ) -> Pin<Box<dyn Future<Output = Result<ExecuteChunkResponse>> + Send>>;
+
+    /// Verify and save a chunk of the state snapshot at the version of the given ledger info
+    fn restore_state_snapshot_chunk(
+        &self,
+        request: RestoreStateSnapshotChunkRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<RestoreStateSnapshotChunkResponse>> + Send>>;
+
+    /// Get the key the next chunk of the state snapshot being restored should start with
+    fn get_state_snapshot_restore_progress(
+        &self,
+        request: GetStateSnapshotRestoreProgressRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<GetStateSnapshotRestoreProgressResponse>> + Send>>;
 }
 
 pub(crate) struct ExecutorProxy {
@@ -303,4 +438,20 @@ impl ExecutorProxyTrait for ExecutorProxy {
         let client = Arc::clone(&self.execution_client);
         convert_grpc_response(client.execute_chunk_async(&request)).boxed()
     }
+
+    fn restore_state_snapshot_chunk(
+        &self,
+        request: RestoreStateSnapshotChunkRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<RestoreStateSnapshotChunkResponse>> + Send>> {
+        let client = Arc::clone(&self.execution_client);
+        convert_grpc_response(client.restore_state_snapshot_chunk_async(&request)).boxed()
+    }
+
+    fn get_state_snapshot_restore_progress(
+        &self,
+        request: GetStateSnapshotRestoreProgressRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<GetStateSnapshotRestoreProgressResponse>> + Send>> {
+        let client = Arc::clone(&self.execution_client);
+        convert_grpc_response(client.get_state_snapshot_restore_progress_async(&request)).boxed()
+    }
 }
@@ -14,4 +14,8 @@ lazy_static::lazy_static! {
 /// Large values mean that a node has been significantly behind and had to replay a lot of txns.
 pub static ref STATE_SYNC_TXN_REPLAYED: IntCounter = OP_COUNTERS.counter("state_sync_txns_replayed");
 
+/// Count the overall number of account states state synchronizer has restored from state snapshots
+/// since last restart.
+pub static ref STATE_SYNC_ACCOUNT_STATES_RESTORED: IntCounter = OP_COUNTERS.counter("state_sync_account_states_restored");
+
 }
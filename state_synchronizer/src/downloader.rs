@@ -2,14 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{coordinator::CoordinatorMsg, counters::OP_COUNTERS, PeerId};
+use crypto::HashValue;
 use failure::prelude::*;
 use futures::{channel::mpsc, SinkExt, StreamExt};
 use logger::prelude::*;
-use network::{proto::RequestChunk, validator_network::ConsensusNetworkSender};
+use network::{
+    proto::{RequestAccountStateChunk, RequestChunk},
+    validator_network::ConsensusNetworkSender,
+};
 use nextgen_crypto::ed25519::*;
+use proto_conv::FromProto;
 use rand::{thread_rng, Rng};
 use std::time::Duration;
-use types::{ledger_info::LedgerInfoWithSignatures, proto::transaction::TransactionListWithProof};
+use types::{
+    account_state_blob::AccountStateRangeWithProof, ledger_info::LedgerInfoWithSignatures,
+    proto::transaction::TransactionListWithProof,
+};
 
 /// Used for communication between coordinator and downloader
 /// and represents a single fetch request
@@ -21,21 +29,39 @@ pub struct FetchChunkMsg {
     pub start_version: u64,
 }
 
+/// Used for communication between coordinator and downloader
+/// and represents a single request for a chunk of the state snapshot
+#[derive(Clone)]
+pub struct FetchAccountStateChunkMsg {
+    // target whose state snapshot we want to fetch
+    pub target: LedgerInfoWithSignatures<Ed25519Signature>,
+    // key (hashed account address) from which to start fetching
+    pub start_key: HashValue,
+}
+
+/// unified message sent from coordinator to downloader
+pub enum DownloaderMsg {
+    FetchChunk(FetchChunkMsg),
+    FetchAccountStateChunk(FetchAccountStateChunkMsg),
+}
+
 /// Used to download chunks of transactions from peers
 pub struct Downloader {
-    receiver_from_coordinator: mpsc::Receiver<FetchChunkMsg>,
+    receiver_from_coordinator: mpsc::Receiver<DownloaderMsg>,
     sender_to_coordinator: mpsc::UnboundedSender<CoordinatorMsg>,
     network: ConsensusNetworkSender,
     batch_size: u64,
+    account_state_chunk_size: u64,
     retries: usize,
 }
 
 impl Downloader {
     pub fn new(
-        receiver_from_coordinator: mpsc::Receiver<FetchChunkMsg>,
+        receiver_from_coordinator: mpsc::Receiver<DownloaderMsg>,
         sender_to_coordinator: mpsc::UnboundedSender<CoordinatorMsg>,
         network: ConsensusNetworkSender,
         batch_size: u64,
+        account_state_chunk_size: u64,
         retries: usize,
     ) -> Self {
         Self {
@@ -43,30 +69,64 @@ impl Downloader {
             sender_to_coordinator,
             network,
             batch_size,
+            account_state_chunk_size,
             retries,
         }
     }
 
-    /// Starts chunk downloader that listens to FetchChunkMsgs
+    /// Starts chunk downloader that listens to DownloaderMsgs
     pub async fn start(mut self) {
         while let Some(msg) = self.receiver_from_coordinator.next().await {
-            for attempt in 0..self.retries {
-                let peer_id = self.pick_peer_id(&msg);
-                let download_result = self.download_chunk(peer_id, msg.clone()).await;
-                if download_result.is_ok() || attempt == self.retries - 1 {
-                    let send_result = self
-                        .sender_to_coordinator
-                        .send(CoordinatorMsg::Fetched(download_result, msg.target))
-                        .await;
-                    if send_result.is_err() {
-                        log_collector_error!("[state synchronizer] failed to send chunk from downloader to coordinator");
-                    }
-                    break;
+            match msg {
+                DownloaderMsg::FetchChunk(msg) => self.fetch_chunk(msg).await,
+                DownloaderMsg::FetchAccountStateChunk(msg) => {
+                    self.fetch_account_state_chunk(msg).await
                 }
             }
         }
     }
 
+    async fn fetch_chunk(&mut self, msg: FetchChunkMsg) {
+        for attempt in 0..self.retries {
+            let peer_id = self.pick_peer_id(&msg.target);
+            let download_result = self.download_chunk(peer_id, msg.clone()).await;
+            if download_result.is_ok() || attempt == self.retries - 1 {
+                let send_result = self
+                    .sender_to_coordinator
+                    .send(CoordinatorMsg::Fetched(download_result, msg.target))
+                    .await;
+                if send_result.is_err() {
+                    log_collector_error!(
+                        "[state synchronizer] failed to send chunk from downloader to coordinator"
+                    );
+                }
+                break;
+            }
+        }
+    }
+
+    async fn fetch_account_state_chunk(&mut self, msg: FetchAccountStateChunkMsg) {
+        for attempt in 0..self.retries {
+            let peer_id = self.pick_peer_id(&msg.target);
+            let download_result = self
+                .download_account_state_chunk(peer_id, msg.clone())
+                .await;
+            if download_result.is_ok() || attempt == self.retries - 1 {
+                let send_result = self
+                    .sender_to_coordinator
+                    .send(CoordinatorMsg::FetchedAccountStates(
+                        download_result,
+                        msg.target,
+                    ))
+                    .await;
+                if send_result.is_err() {
+                    log_collector_error!("[state synchronizer] failed to send account state chunk from downloader to coordinator");
+                }
+                break;
+            }
+        }
+    }
+
     /// Downloads a chunk from another validator or from a cloud provider.
     /// It then verifies that the data in the chunk is valid and returns the validated data.
     async fn download_chunk(
@@ -93,8 +153,39 @@ impl Downloader {
         Ok(resp.take_txn_list_with_proof())
     }
 
-    fn pick_peer_id(&self, msg: &FetchChunkMsg) -> PeerId {
-        let signatures = msg.target.signatures();
+    /// Downloads a chunk of the state snapshot at the target version from another validator,
+    /// together with the frozen subtree hashes of the transaction accumulator at that version.
+    /// The data is verified by the executor when it is restored.
+    async fn download_account_state_chunk(
+        &mut self,
+        peer_id: PeerId,
+        msg: FetchAccountStateChunkMsg,
+    ) -> Result<(AccountStateRangeWithProof, Vec<HashValue>)> {
+        let mut req = RequestAccountStateChunk::new();
+        req.set_version(msg.target.ledger_info().version());
+        req.set_start_key(msg.start_key.to_vec().into());
+        req.set_limit(self.account_state_chunk_size);
+        let mut resp = self
+            .network
+            .request_account_state_chunk(peer_id, req, Duration::from_millis(1000))
+            .await?;
+
+        let account_state_chunk =
+            AccountStateRangeWithProof::from_proto(resp.take_account_state_chunk())?;
+        let ledger_frozen_subtree_hashes = resp
+            .get_ledger_frozen_subtree_hashes()
+            .iter()
+            .map(|hash| HashValue::from_slice(hash))
+            .collect::<Result<Vec<_>>>()?;
+        OP_COUNTERS.inc_by(
+            "download_account_states",
+            account_state_chunk.account_blobs.len(),
+        );
+        Ok((account_state_chunk, ledger_frozen_subtree_hashes))
+    }
+
+    fn pick_peer_id(&self, target: &LedgerInfoWithSignatures<Ed25519Signature>) -> PeerId {
+        let signatures = target.signatures();
         let idx = thread_rng().gen_range(0, signatures.len());
         signatures
             .keys()
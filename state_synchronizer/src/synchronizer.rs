@@ -6,6 +6,7 @@ use crate::{
     downloader::Downloader,
 };
 use config::config::NodeConfig;
+use crypto::HashValue;
 use failure::prelude::*;
 use futures::{
     channel::{mpsc, oneshot},
@@ -19,7 +20,10 @@ use nextgen_crypto::ed25519::*;
 use std::sync::Arc;
 use storage_client::{StorageRead, StorageReadServiceClient};
 use tokio::runtime::{Builder, Runtime};
-use types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionListWithProof};
+use types::{
+    account_state_blob::AccountStateRangeWithProof, ledger_info::LedgerInfoWithSignatures,
+    transaction::TransactionListWithProof,
+};
 
 pub struct StateSynchronizer {
     _runtime: Runtime,
@@ -48,13 +52,18 @@ impl StateSynchronizer {
         let (coordinator_sender, coordinator_receiver) = mpsc::unbounded();
         let (fetcher_sender, fetcher_receiver) = mpsc::channel(1);
 
-        let coordinator =
-            SyncCoordinator::new(coordinator_receiver, fetcher_sender, executor_proxy);
+        let coordinator = SyncCoordinator::new(
+            coordinator_receiver,
+            fetcher_sender,
+            config.state_sync.enable_snapshot_sync,
+            executor_proxy,
+        );
         let downloader = Downloader::new(
             fetcher_receiver,
             coordinator_sender.clone(),
             network,
             config.base.node_sync_batch_size,
+            config.state_sync.snapshot_chunk_size,
             config.base.node_sync_retries,
         );
 
@@ -144,4 +153,21 @@ impl StateSyncClient {
             Ok(txn_list_with_proof)
         }
     }
+
+    /// Get a chunk of account states of the state snapshot at `version`, together with the
+    /// frozen subtree root hashes of the transaction accumulator at that version
+    pub fn get_account_state_chunk(
+        &self,
+        version: u64,
+        start_key: HashValue,
+        limit: u64,
+    ) -> impl Future<Output = Result<(AccountStateRangeWithProof, Vec<HashValue>)>> {
+        // TODO: shouldn't be part of a client. Remove it once we move out of Consensus p2p stack
+        let client = Arc::clone(&self.storage_read_client);
+        async move {
+            client
+                .get_account_state_chunk_async(start_key, limit, version)
+                .await
+        }
+    }
 }
@@ -9,11 +9,15 @@ use bytes::Bytes;
 use config::config::NodeConfig;
 use config_builder::util::get_test_config;
 use crypto::HashValue;
-use execution_proto::proto::execution::{ExecuteChunkRequest, ExecuteChunkResponse};
+use execution_proto::proto::execution::{
+    ExecuteChunkRequest, ExecuteChunkResponse, GetStateSnapshotRestoreProgressRequest,
+    GetStateSnapshotRestoreProgressResponse, RestoreStateSnapshotChunkRequest,
+    RestoreStateSnapshotChunkResponse,
+};
 use failure::{prelude::*, Result};
 use futures::{executor::block_on, future::TryFutureExt, stream::StreamExt, Future, FutureExt};
 use network::{
-    proto::{ConsensusMsg, RespondChunk},
+    proto::{ConsensusMsg, RespondAccountStateChunk, RespondChunk},
     validator_network::{
         network_builder::{NetworkBuilder, TransportType},
         Event, RpcError, CONSENSUS_RPC_PROTOCOL,
@@ -36,8 +40,9 @@ use std::{
 use tokio::runtime::{Builder, Runtime};
 use types::{
     account_address::AccountAddress,
+    account_state_blob::AccountStateRangeWithProof,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    proof::AccumulatorProof,
+    proof::{AccountStateRangeProof, AccumulatorProof, SparseMerkleProof, SparseMerkleRangeProof},
     test_helpers::transaction_test_helpers::get_test_signed_txn,
     transaction::{SignedTransaction, TransactionInfo, TransactionListWithProof},
 };
@@ -61,6 +66,34 @@ impl ExecutorProxyTrait for MockExecutorProxy {
         self.version.fetch_add(1, Ordering::Relaxed);
         async move { Ok(ExecuteChunkResponse::new()) }.boxed()
     }
+
+    fn restore_state_snapshot_chunk(
+        &self,
+        request: RestoreStateSnapshotChunkRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<RestoreStateSnapshotChunkResponse>> + Send>> {
+        if request
+            .get_account_state_chunk()
+            .get_account_blobs()
+            .is_empty()
+        {
+            // the last chunk of the snapshot commits the ledger info
+            let version = request
+                .get_ledger_info_with_sigs()
+                .get_ledger_info()
+                .get_version();
+            self.version.store(version, Ordering::Relaxed);
+        }
+        async move { Ok(RestoreStateSnapshotChunkResponse::new()) }.boxed()
+    }
+
+    fn get_state_snapshot_restore_progress(
+        &self,
+        _request: GetStateSnapshotRestoreProgressRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<GetStateSnapshotRestoreProgressResponse>> + Send>> {
+        let mut resp = GetStateSnapshotRestoreProgressResponse::new();
+        resp.set_next_key(HashValue::zero().to_vec());
+        async move { Ok(resp) }.boxed()
+    }
 }
 
 pub fn gen_txn_list(sequence_number: u64) -> TransactionListWithProof {
@@ -89,6 +122,17 @@ pub fn gen_txn_list(sequence_number: u64) -> TransactionListWithProof {
     )
 }
 
+/// Returns an empty chunk of the state snapshot, i.e. the last one.
+fn gen_empty_account_state_chunk(version: u64) -> AccountStateRangeWithProof {
+    let txn_info = TransactionInfo::new(HashValue::zero(), HashValue::zero(), HashValue::zero(), 0);
+    let proof = AccountStateRangeProof::new(
+        AccumulatorProof::new(vec![]),
+        txn_info,
+        SparseMerkleRangeProof::new(SparseMerkleProof::new(None, vec![]), vec![]),
+    );
+    AccountStateRangeWithProof::new(version, vec![], proof)
+}
+
 struct SynchronizerEnv {
     peers: Vec<PeerId>,
     clients: Vec<Arc<StateSyncClient>>,
@@ -192,7 +236,19 @@ impl SynchronizerEnv {
 
         let rpc_handler = async move {
             while let Some(event) = events_b.next().await {
-                if let Ok(Event::RpcRequest((_, _, callback))) = event {
+                if let Ok(Event::RpcRequest((_, msg, callback))) = event {
+                    if msg.has_request_account_state_chunk() {
+                        let version = msg.get_request_account_state_chunk().get_version();
+                        let mut response_msg = ConsensusMsg::new();
+                        let mut response = RespondAccountStateChunk::new();
+                        response.set_account_state_chunk(
+                            gen_empty_account_state_chunk(version).into_proto(),
+                        );
+                        response_msg.set_respond_account_state_chunk(response);
+                        let response_data = Bytes::from(response_msg.write_to_bytes().unwrap());
+                        callback.send(Ok(response_data)).unwrap();
+                        continue;
+                    }
                     match handler() {
                         Ok(txn_list) => {
                             let mut response_msg = ConsensusMsg::new();
@@ -278,3 +334,18 @@ fn test_download_retry() {
     let status = env.sync_to(0, 1);
     assert_eq!(status, SyncStatus::Finished);
 }
+
+#[test]
+fn test_snapshot_sync() {
+    let (mut config, _) = get_test_config();
+    config.state_sync.enable_snapshot_sync = true;
+    let handler = Box::new(|| -> Result<TransactionListWithProof> { Ok(gen_txn_list(0)) });
+    let env = SynchronizerEnv::new_with(handler, Some(config));
+
+    // the empty node restores the snapshot at the target version instead of replaying txns
+    let status = env.sync_to(0, 5);
+    assert_eq!(status, SyncStatus::Finished);
+    // then normal sync resumes after the snapshot version
+    let status = env.sync_to(0, 7);
+    assert_eq!(status, SyncStatus::Finished);
+}
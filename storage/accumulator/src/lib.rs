@@ -105,7 +105,9 @@ use crypto::hash::{CryptoHash, CryptoHasher, HashValue, ACCUMULATOR_PLACEHOLDER_
 use failure::prelude::*;
 use std::marker::PhantomData;
 use types::proof::{
-    position::Position, treebits::NodeDirection, AccumulatorProof, MerkleTreeInternalNode,
+    position::{FrozenSubTreeIterator, Position},
+    treebits::NodeDirection,
+    AccumulatorConsistencyProof, AccumulatorProof, MerkleTreeInternalNode,
 };
 
 /// Defines the interface between `MerkleAccumulator` and underlying storage.
@@ -147,6 +149,19 @@ where
     pub fn get_proof(reader: &R, num_leaves: u64, leaf_index: u64) -> Result<AccumulatorProof> {
         MerkleAccumulatorView::<R, H>::new(reader, num_leaves).get_proof(leaf_index)
     }
+
+    /// Get proof that this Merkle Accumulator of `new_num_leaves` leaves in total is the result of
+    /// appending leaves to the one of its first `old_num_leaves` leaves.
+    ///
+    /// See [`types::proof::AccumulatorConsistencyProof`] for proof format.
+    pub fn get_consistency_proof(
+        reader: &R,
+        old_num_leaves: u64,
+        new_num_leaves: u64,
+    ) -> Result<AccumulatorConsistencyProof> {
+        MerkleAccumulatorView::<R, H>::new(reader, new_num_leaves)
+            .get_consistency_proof(old_num_leaves)
+    }
 }
 
 /// Actual implementation of Merkle Accumulator algorithms, which carries the `reader` and
@@ -318,6 +333,44 @@ where
 
         Ok(AccumulatorProof::new(siblings))
     }
+
+    /// implementation for pub interface `MerkleAccumulator::get_consistency_proof`
+    fn get_consistency_proof(&self, old_num_leaves: u64) -> Result<AccumulatorConsistencyProof> {
+        ensure!(
+            old_num_leaves <= self.num_leaves,
+            "invalid old_num_leaves {}, num_leaves {}",
+            old_num_leaves,
+            self.num_leaves
+        );
+
+        let frozen_subtrees = FrozenSubTreeIterator::new(old_num_leaves).collect::<Vec<_>>();
+        let frozen_subtree_roots = frozen_subtrees
+            .iter()
+            .map(|p| self.reader.get(*p))
+            .collect::<Result<Vec<HashValue>>>()?;
+
+        // Right siblings on the path from the rightmost old frozen subtree to the current root.
+        let right_siblings = match frozen_subtrees.last() {
+            Some(rightmost_subtree) => {
+                let root_pos = Position::get_root_position(self.num_leaves - 1);
+                rightmost_subtree
+                    .iter_ancestor()
+                    .take_while(|p| *p != root_pos)
+                    .filter(|p| p.get_direction_for_self() == NodeDirection::Left)
+                    .map(|p| self.get_hash(p.get_sibling()))
+                    .collect::<Result<Vec<HashValue>>>()?
+                    .into_iter()
+                    .rev()
+                    .collect()
+            }
+            None => vec![],
+        };
+
+        Ok(AccumulatorConsistencyProof::new(
+            frozen_subtree_roots,
+            right_siblings,
+        ))
+    }
 }
 
 #[cfg(test)]
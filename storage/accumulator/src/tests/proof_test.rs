@@ -3,13 +3,14 @@
 
 use super::*;
 use proptest::{collection::vec, prelude::*};
-use types::proof::verify_test_accumulator_element;
+use types::proof::{verify_test_accumulator_consistency, verify_test_accumulator_element};
 
 #[test]
 fn test_error_on_bad_parameters() {
     let store = MockHashStore::new();
     assert!(TestAccumulator::get_proof(&store, 0, 0).is_err());
     assert!(TestAccumulator::get_proof(&store, 100, 101).is_err());
+    assert!(TestAccumulator::get_consistency_proof(&store, 101, 100).is_err());
 }
 
 #[test]
@@ -47,6 +48,56 @@ proptest! {
         // verify proofs for all leaves of a subtree towards subtree root
         verify(&store, batch1.len(), root_hash1, &batch1, 0);
     }
+
+    #[test]
+    fn test_consistency_proof(
+        batch1 in vec(any::<HashValue>(), 0..100),
+        batch2 in vec(any::<HashValue>(), 0..100),
+        other_leaf in any::<HashValue>(),
+    ) {
+        let batch1_size = batch1.len() as u64;
+        let total_leaves = batch1_size + batch2.len() as u64;
+        let mut store = MockHashStore::new();
+
+        let (root_hash1, writes1) = TestAccumulator::append(&store, 0, &batch1).unwrap();
+        store.put_many(&writes1);
+        let (root_hash2, writes2) = TestAccumulator::append(&store, batch1_size, &batch2).unwrap();
+        store.put_many(&writes2);
+
+        // the accumulator is consistent with itself before and after the second batch
+        for (old_num_leaves, old_root_hash) in &[(batch1_size, root_hash1), (total_leaves, root_hash2)] {
+            let proof =
+                TestAccumulator::get_consistency_proof(&store, *old_num_leaves, total_leaves).unwrap();
+            verify_test_accumulator_consistency(
+                *old_num_leaves,
+                *old_root_hash,
+                total_leaves,
+                root_hash2,
+                &proof,
+            )
+            .unwrap();
+        }
+
+        // but not with an accumulator that has a different leaf
+        if !batch1.is_empty() {
+            let mut other_batch1 = batch1.clone();
+            other_batch1[0] = other_leaf;
+            prop_assume!(other_batch1 != batch1);
+            let other_store = MockHashStore::new();
+            let (other_root_hash1, _) =
+                TestAccumulator::append(&other_store, 0, &other_batch1).unwrap();
+            let proof =
+                TestAccumulator::get_consistency_proof(&store, batch1_size, total_leaves).unwrap();
+            prop_assert!(verify_test_accumulator_consistency(
+                batch1_size,
+                other_root_hash1,
+                total_leaves,
+                root_hash2,
+                &proof,
+            )
+            .is_err());
+        }
+    }
 }
 
 fn verify(
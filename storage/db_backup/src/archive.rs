@@ -0,0 +1,296 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the format of the archives the backup tool produces.
+//!
+//! An archive is a header followed by a sequence of records, each of them carrying a checksum of
+//! its payload so that a corrupted archive is detected before anything is restored from it.
+//! ```text
+//! |<-------header------->|
+//! | magic | format version |
+//! |<-------------------------------record-------------------------------->|
+//! | kind (u8) | payload length (u64) | payload | SHA3-256 hash of payload |
+//! ```
+//!
+//! Integers are serialized in big endian. The first record is the [`Manifest`], followed by the
+//! transactions with their events, the state snapshot and the ledger infos, in this order. The
+//! ledger infos of an earlier epoch than the one of the manifest are preceded by the validator set
+//! on chain in their epoch. The last record counts the records before it, so that a truncated
+//! archive is detected as well.
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use crypto::HashValue;
+use failure::prelude::*;
+use nextgen_crypto::ed25519::*;
+use proto_conv::{FromProtoBytes, IntoProtoBytes};
+use std::io::{self, Cursor, Read, Write};
+use tiny_keccak::Keccak;
+use types::{
+    account_state_blob::{AccountStateRangeWithProof, AccountStateWithProof},
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionListWithProof, Version},
+};
+
+/// The bytes every archive starts with.
+const MAGIC: &[u8; 8] = b"LIBRADB\x00";
+
+/// The version of the archive format. Archives of other versions are rejected.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Records larger than this are rejected rather than read into memory.
+const MAX_PAYLOAD_LENGTH: u64 = 1 << 30;
+
+const MANIFEST_KIND: u8 = 0;
+const TRANSACTIONS_KIND: u8 = 1;
+const ACCOUNT_STATES_KIND: u8 = 2;
+const LEDGER_INFO_KIND: u8 = 3;
+const END_KIND: u8 = 4;
+const VALIDATOR_SET_KIND: u8 = 5;
+
+/// Describes what an archive contains.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Manifest {
+    /// The version of the first transaction in the archive, which is 0 unless the backup is
+    /// incremental.
+    pub first_version: Version,
+    /// The ledger info the archive is taken at: the transactions and the state snapshot in the
+    /// archive are verified against it.
+    pub ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+    /// The frozen subtree root hashes of the transaction accumulator at the version of the ledger
+    /// info, needed to append transactions after the state snapshot.
+    pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+}
+
+impl Manifest {
+    fn encode(self) -> Result<Vec<u8>> {
+        let mut encoded = vec![];
+        encoded.write_u64::<BigEndian>(self.first_version)?;
+        encoded.write_u32::<BigEndian>(self.ledger_frozen_subtree_hashes.len() as u32)?;
+        for hash in &self.ledger_frozen_subtree_hashes {
+            encoded.write_all(hash.as_ref())?;
+        }
+        encoded.write_all(&self.ledger_info_with_sigs.into_proto_bytes()?)?;
+        Ok(encoded)
+    }
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        let mut cursor = Cursor::new(data);
+        let first_version = cursor.read_u64::<BigEndian>()?;
+        let num_hashes = cursor.read_u32::<BigEndian>()?;
+        let ledger_frozen_subtree_hashes = (0..num_hashes)
+            .map(|_| {
+                let mut hash = [0; HashValue::LENGTH];
+                cursor.read_exact(&mut hash)?;
+                Ok(HashValue::new(hash))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let ledger_info_with_sigs =
+            LedgerInfoWithSignatures::from_proto_bytes(&data[cursor.position() as usize..])?;
+        Ok(Self {
+            first_version,
+            ledger_info_with_sigs,
+            ledger_frozen_subtree_hashes,
+        })
+    }
+}
+
+/// A record of an archive.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Record {
+    Manifest(Manifest),
+    /// A chunk of transactions with their transaction infos and events, and a proof towards the
+    /// ledger info of the manifest.
+    Transactions(TransactionListWithProof),
+    /// A chunk of the state snapshot at the version of the ledger info of the manifest.
+    AccountStates(AccountStateRangeWithProof),
+    LedgerInfo(LedgerInfoWithSignatures<Ed25519Signature>),
+    /// The state of the core code account right before the next ledger info, with a proof towards
+    /// the ledger info of the manifest. It carries the validator set that signed the next ledger
+    /// info.
+    ValidatorSet(AccountStateWithProof),
+    /// Ends the archive.
+    End {
+        num_records: u64,
+    },
+}
+
+impl Record {
+    fn kind(&self) -> u8 {
+        match self {
+            Record::Manifest(_) => MANIFEST_KIND,
+            Record::Transactions(_) => TRANSACTIONS_KIND,
+            Record::AccountStates(_) => ACCOUNT_STATES_KIND,
+            Record::LedgerInfo(_) => LEDGER_INFO_KIND,
+            Record::ValidatorSet(_) => VALIDATOR_SET_KIND,
+            Record::End { .. } => END_KIND,
+        }
+    }
+
+    fn encode_payload(self) -> Result<Vec<u8>> {
+        match self {
+            Record::Manifest(manifest) => manifest.encode(),
+            Record::Transactions(txn_list_with_proof) => txn_list_with_proof.into_proto_bytes(),
+            Record::AccountStates(account_states) => account_states.into_proto_bytes(),
+            Record::LedgerInfo(ledger_info_with_sigs) => ledger_info_with_sigs.into_proto_bytes(),
+            Record::ValidatorSet(account_state) => account_state.into_proto_bytes(),
+            Record::End { num_records } => Ok(num_records.to_be_bytes().to_vec()),
+        }
+    }
+
+    fn decode_payload(kind: u8, payload: &[u8]) -> Result<Self> {
+        Ok(match kind {
+            MANIFEST_KIND => Record::Manifest(Manifest::decode(payload)?),
+            TRANSACTIONS_KIND => {
+                Record::Transactions(TransactionListWithProof::from_proto_bytes(payload)?)
+            }
+            ACCOUNT_STATES_KIND => {
+                Record::AccountStates(AccountStateRangeWithProof::from_proto_bytes(payload)?)
+            }
+            LEDGER_INFO_KIND => {
+                Record::LedgerInfo(LedgerInfoWithSignatures::from_proto_bytes(payload)?)
+            }
+            VALIDATOR_SET_KIND => {
+                Record::ValidatorSet(AccountStateWithProof::from_proto_bytes(payload)?)
+            }
+            END_KIND => Record::End {
+                num_records: Cursor::new(payload).read_u64::<BigEndian>()?,
+            },
+            _ => bail!("Unknown record kind {}.", kind),
+        })
+    }
+}
+
+/// Writes the records of an archive.
+pub struct ArchiveWriter<W> {
+    writer: W,
+    num_records: u64,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Starts an archive by writing its header.
+    pub fn new(mut writer: W) -> Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_u32::<BigEndian>(FORMAT_VERSION)?;
+        Ok(Self {
+            writer,
+            num_records: 0,
+        })
+    }
+
+    pub fn write_record(&mut self, record: Record) -> Result<()> {
+        let kind = record.kind();
+        ensure!(
+            kind != END_KIND,
+            "The end of the archive is written by finish."
+        );
+        self.write_record_impl(kind, record)
+    }
+
+    /// Ends the archive and returns the underlying writer, flushed.
+    pub fn finish(mut self) -> Result<W> {
+        let num_records = self.num_records;
+        self.write_record_impl(END_KIND, Record::End { num_records })?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_record_impl(&mut self, kind: u8, record: Record) -> Result<()> {
+        let payload = record.encode_payload()?;
+        self.writer.write_u8(kind)?;
+        self.writer.write_u64::<BigEndian>(payload.len() as u64)?;
+        self.writer.write_all(&payload)?;
+        self.writer.write_all(checksum(&payload).as_ref())?;
+        self.num_records += 1;
+        Ok(())
+    }
+}
+
+/// Reads the records of an archive, checking their checksums.
+pub struct ArchiveReader<R> {
+    reader: R,
+    num_records: u64,
+    finished: bool,
+}
+
+impl<R: Read> ArchiveReader<R> {
+    /// Reads the header of the archive.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut magic = [0; 8];
+        read_exact(&mut reader, &mut magic)?;
+        ensure!(&magic == MAGIC, "Not a LibraDB backup archive.");
+        let format_version = reader.read_u32::<BigEndian>()?;
+        ensure!(
+            format_version == FORMAT_VERSION,
+            "Unsupported archive format version {}, expected {}.",
+            format_version,
+            FORMAT_VERSION,
+        );
+        Ok(Self {
+            reader,
+            num_records: 0,
+            finished: false,
+        })
+    }
+
+    /// Returns the next record, or `None` once the end of the archive is reached.
+    pub fn read_record(&mut self) -> Result<Option<Record>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let kind = read_exact(&mut self.reader, &mut [0; 1]).map(|buf| buf[0])?;
+        let payload_len = self.reader.read_u64::<BigEndian>()?;
+        ensure!(
+            payload_len <= MAX_PAYLOAD_LENGTH,
+            "Record {} is too large: {} bytes.",
+            self.num_records,
+            payload_len,
+        );
+        let mut payload = vec![0; payload_len as usize];
+        read_exact(&mut self.reader, &mut payload)?;
+        let mut expected_checksum = [0; HashValue::LENGTH];
+        read_exact(&mut self.reader, &mut expected_checksum)?;
+        ensure!(
+            HashValue::new(expected_checksum) == checksum(&payload),
+            "Checksum mismatch for record {}.",
+            self.num_records,
+        );
+
+        match Record::decode_payload(kind, &payload)? {
+            Record::End { num_records } => {
+                ensure!(
+                    num_records == self.num_records,
+                    "The archive ends after {} records but {} were read.",
+                    num_records,
+                    self.num_records,
+                );
+                self.finished = true;
+                Ok(None)
+            }
+            record => {
+                self.num_records += 1;
+                Ok(Some(record))
+            }
+        }
+    }
+}
+
+/// The SHA3-256 hash of a record payload.
+fn checksum(payload: &[u8]) -> HashValue {
+    let mut sha3 = Keccak::new_sha3_256();
+    sha3.update(payload);
+    let mut hash = [0; HashValue::LENGTH];
+    sha3.finalize(&mut hash);
+    HashValue::new(hash)
+}
+
+/// Like `Read::read_exact`, but with an explicit error if the archive ends prematurely.
+fn read_exact<'a, R: Read>(reader: &mut R, buf: &'a mut [u8]) -> Result<&'a mut [u8]> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(buf),
+        Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+            bail!("The archive is truncated.")
+        }
+        Err(e) => Err(e.into()),
+    }
+}
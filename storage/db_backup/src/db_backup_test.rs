@@ -0,0 +1,242 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{backup, restore, verify, TrustedValidators};
+use config::config::StorageMode;
+use failure::prelude::*;
+use libradb::{mock_genesis::db_with_mock_genesis, test_helper::arb_blocks_to_commit, LibraDB};
+use nextgen_crypto::ed25519::*;
+use proptest::prelude::*;
+use types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionToCommit, Version},
+    validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+
+type BlockToCommit = (
+    Vec<TransactionToCommit>,
+    LedgerInfoWithSignatures<Ed25519Signature>,
+);
+
+/// Commits `blocks` on top of the latest version `cur_ver` of `db` and returns the new one.
+fn commit_blocks(db: &LibraDB, blocks: &[BlockToCommit], mut cur_ver: Version) -> Result<Version> {
+    for (txns_to_commit, ledger_info_with_sigs) in blocks {
+        db.save_transactions(
+            &txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )?;
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    Ok(cur_ver)
+}
+
+/// Checks that `restore_db` holds the same transactions, events, state and ledger infos as `db`.
+fn assert_same_db(db: &LibraDB, restore_db: &LibraDB, blocks: &[BlockToCommit]) -> Result<()> {
+    let (_, ledger_info_with_sigs, _) = db.update_to_latest_ledger(0, vec![])?;
+    let version = ledger_info_with_sigs.ledger_info().version();
+    assert_eq!(
+        restore_db.update_to_latest_ledger(0, vec![])?.1,
+        ledger_info_with_sigs
+    );
+    assert_eq!(
+        restore_db.get_executor_startup_info()?,
+        db.get_executor_startup_info()?
+    );
+    assert_eq!(
+        restore_db.get_transactions(0, version + 1, version, true)?,
+        db.get_transactions(0, version + 1, version, true)?
+    );
+    assert_eq!(
+        restore_db.get_ledger_infos(0, version + 1)?,
+        db.get_ledger_infos(0, version + 1)?
+    );
+    for (txns_to_commit, _) in blocks {
+        for txn_to_commit in txns_to_commit {
+            for addr in txn_to_commit.account_states().keys() {
+                assert_eq!(
+                    restore_db.get_account_state_with_proof_by_version(*addr, version)?,
+                    db.get_account_state_with_proof_by_version(*addr, version)?
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn test_backup_and_restore_impl(blocks: Vec<BlockToCommit>) -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let db = db_with_mock_genesis(&tmp_dir)?;
+    commit_blocks(&db, &blocks, 0)?;
+
+    let mut archive = vec![];
+    let version = backup(&db, 0 /* first_version */, &mut archive)?;
+    assert_eq!(
+        verify(archive.as_slice(), TrustedValidators::Insecure)?,
+        version
+    );
+
+    let restore_tmp_dir = tempfile::tempdir()?;
+    let restore_db = LibraDB::new_with_storage_mode(&restore_tmp_dir, StorageMode::Archive);
+    assert_eq!(
+        restore(&restore_db, archive.as_slice(), TrustedValidators::Insecure)?,
+        version
+    );
+    assert_same_db(&db, &restore_db, &blocks)
+}
+
+fn test_incremental_backup_impl(blocks: Vec<BlockToCommit>) -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let db = db_with_mock_genesis(&tmp_dir)?;
+    let (first_half, second_half) = blocks.split_at(blocks.len() / 2);
+    let first_version = commit_blocks(&db, first_half, 0)?;
+    let mut full_archive = vec![];
+    backup(&db, 0 /* first_version */, &mut full_archive)?;
+
+    let version = commit_blocks(&db, second_half, first_version)?;
+    let mut incremental_archive = vec![];
+    if version == first_version {
+        // Nothing new to back up.
+        ensure!(
+            backup(&db, first_version + 1, &mut incremental_archive).is_err(),
+            "Empty incremental backup should fail."
+        );
+        return Ok(());
+    }
+    backup(&db, first_version + 1, &mut incremental_archive)?;
+
+    let restore_tmp_dir = tempfile::tempdir()?;
+    let restore_db = LibraDB::new_with_storage_mode(&restore_tmp_dir, StorageMode::Archive);
+    // The incremental archive only applies on top of the full one.
+    ensure!(
+        restore(
+            &restore_db,
+            incremental_archive.as_slice(),
+            TrustedValidators::Insecure
+        )
+        .is_err(),
+        "Incremental archive should not apply to an empty DB."
+    );
+    restore(
+        &restore_db,
+        full_archive.as_slice(),
+        TrustedValidators::Insecure,
+    )?;
+    assert_eq!(
+        restore(
+            &restore_db,
+            incremental_archive.as_slice(),
+            TrustedValidators::Insecure
+        )?,
+        version
+    );
+    assert_same_db(&db, &restore_db, &blocks)
+}
+
+fn test_reject_incremental_archive_on_mismatched_db_impl(blocks: Vec<BlockToCommit>) -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let db = db_with_mock_genesis(&tmp_dir)?;
+    let (first_half, second_half) = blocks.split_at(blocks.len() / 2);
+    let first_version = commit_blocks(&db, first_half, 0)?;
+    let version = commit_blocks(&db, second_half, first_version)?;
+    if version == first_version {
+        // Nothing new to back up.
+        return Ok(());
+    }
+    let mut incremental_archive = vec![];
+    backup(&db, first_version + 1, &mut incremental_archive)?;
+
+    // A DB with as many transactions as the one backed up, but committed in another order.
+    let txns_to_commit: Vec<_> = first_half
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit.iter().cloned())
+        .collect();
+    let mut reversed = txns_to_commit.clone();
+    reversed.reverse();
+    if reversed == txns_to_commit {
+        // The order of the transactions makes no difference.
+        return Ok(());
+    }
+    let mismatched_tmp_dir = tempfile::tempdir()?;
+    let mismatched_db = db_with_mock_genesis(&mismatched_tmp_dir)?;
+    mismatched_db.save_transactions(&reversed, 1 /* first_version */, &None)?;
+
+    ensure!(
+        restore(
+            &mismatched_db,
+            incremental_archive.as_slice(),
+            TrustedValidators::Insecure
+        )
+        .is_err(),
+        "Incremental archive should not apply to a DB with other transactions."
+    );
+    Ok(())
+}
+
+fn test_reject_untrusted_archive_impl(blocks: Vec<BlockToCommit>) -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let db = db_with_mock_genesis(&tmp_dir)?;
+    commit_blocks(&db, &blocks, 0)?;
+    let mut archive = vec![];
+    backup(&db, 0 /* first_version */, &mut archive)?;
+
+    // None of the ledger infos is signed by this validator.
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([1u8; 32]);
+    let validator = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+    let trusted_validators = TrustedValidators::ValidatorSet(&validator);
+    ensure!(
+        verify(archive.as_slice(), trusted_validators).is_err(),
+        "Archive not signed by the trusted validators should not verify."
+    );
+    let restore_tmp_dir = tempfile::tempdir()?;
+    let restore_db = LibraDB::new_with_storage_mode(&restore_tmp_dir, StorageMode::Archive);
+    ensure!(
+        restore(&restore_db, archive.as_slice(), trusted_validators).is_err(),
+        "Archive not signed by the trusted validators should not be restored."
+    );
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_backup_and_restore(blocks in arb_blocks_to_commit()) {
+        test_backup_and_restore_impl(blocks).unwrap();
+    }
+
+    #[test]
+    fn test_incremental_backup(blocks in arb_blocks_to_commit()) {
+        test_incremental_backup_impl(blocks).unwrap();
+    }
+
+    #[test]
+    fn test_reject_incremental_archive_on_mismatched_db(blocks in arb_blocks_to_commit()) {
+        test_reject_incremental_archive_on_mismatched_db_impl(blocks).unwrap();
+    }
+
+    #[test]
+    fn test_reject_untrusted_archive(blocks in arb_blocks_to_commit()) {
+        test_reject_untrusted_archive_impl(blocks).unwrap();
+    }
+
+    #[test]
+    fn test_reject_corrupted_archive(blocks in arb_blocks_to_commit(), index in any::<prop::sample::Index>()) {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let db = db_with_mock_genesis(&tmp_dir).unwrap();
+        commit_blocks(&db, &blocks, 0).unwrap();
+        let mut archive = vec![];
+        backup(&db, 0 /* first_version */, &mut archive).unwrap();
+
+        let mut corrupted = archive.clone();
+        corrupted[index.index(archive.len())] ^= 1;
+        prop_assert!(verify(corrupted.as_slice(), TrustedValidators::Insecure).is_err());
+
+        let truncated = &archive[..index.index(archive.len())];
+        prop_assert!(verify(truncated, TrustedValidators::Insecure).is_err());
+        let restore_tmp_dir = tempfile::tempdir().unwrap();
+        let restore_db = LibraDB::new_with_storage_mode(&restore_tmp_dir, StorageMode::Archive);
+        prop_assert!(restore(&restore_db, truncated, TrustedValidators::Insecure).is_err());
+    }
+}
@@ -0,0 +1,418 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This crate implements an offline backup and restore tool for [`LibraDB`].
+//!
+//! A backup is a self-contained [archive](archive) of the transactions and their events, the state
+//! snapshot and the ledger infos of a DB up to its latest ledger info, with everything needed to
+//! verify it against that ledger info. Restoring an archive verifies every record before persisting
+//! it, so that a corrupted or forged archive is rejected, and [`verify`] does the same checks
+//! without a DB.
+//!
+//! The signatures of the ledger infos are checked against a trusted validator set, unless the
+//! archive is explicitly trusted with [`TrustedValidators::Insecure`].
+//!
+//! A backup can be incremental: it then carries the transactions and the ledger infos starting
+//! from a given version, plus the full state snapshot at its latest version, and is restored on top
+//! of a DB restored from the earlier archives.
+
+pub mod archive;
+
+#[cfg(test)]
+mod db_backup_test;
+
+use crate::archive::{ArchiveReader, ArchiveWriter, Manifest, Record};
+use crypto::{hash::TransactionAccumulatorHasher, HashValue};
+use failure::prelude::*;
+use libradb::LibraDB;
+use logger::prelude::*;
+use nextgen_crypto::ed25519::*;
+use scratchpad::Accumulator;
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    io::{Read, Write},
+};
+use types::{
+    account_config::core_code_address,
+    account_state_blob::AccountStateWithProof,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::Version,
+    validator_set::ValidatorSet,
+    validator_verifier::ValidatorVerifier,
+};
+
+/// The number of transactions, account states or ledger infos read from the DB at a time, which
+/// is also the maximum number of them in a record.
+const CHUNK_SIZE: u64 = 1000;
+
+/// How the signatures of the ledger infos of an archive are checked.
+#[derive(Clone, Copy)]
+pub enum TrustedValidators<'a> {
+    /// The ledger info of the archive and the older ones of the same epoch must be signed by this
+    /// validator set. The ledger infos of earlier epochs must be signed by the validator set on
+    /// chain in their epoch, which the archive proves against its ledger info.
+    ValidatorSet(&'a ValidatorVerifier<Ed25519PublicKey>),
+    /// No signature is checked: the archive is trusted as is.
+    Insecure,
+}
+
+/// Writes a backup of `db` taken at its latest ledger info to `writer`. The backup carries the
+/// transactions and ledger infos starting from `first_version`, which is 0 for a full backup.
+/// Returns the version of the backup.
+pub fn backup<W: Write>(db: &LibraDB, first_version: Version, writer: W) -> Result<Version> {
+    let (_, ledger_info_with_sigs, _) = db.update_to_latest_ledger(0, vec![])?;
+    let version = ledger_info_with_sigs.ledger_info().version();
+    let epoch = ledger_info_with_sigs.ledger_info().epoch_num();
+    ensure!(
+        first_version <= version,
+        "Nothing to back up from version {}, the latest version is {}.",
+        first_version,
+        version,
+    );
+    info!(
+        "Backing up transactions {} to {} and the state at version {}.",
+        first_version, version, version
+    );
+
+    let mut archive = ArchiveWriter::new(writer)?;
+    let (mut account_states, ledger_frozen_subtree_hashes) =
+        db.get_account_state_chunk(HashValue::zero(), CHUNK_SIZE, version)?;
+    archive.write_record(Record::Manifest(Manifest {
+        first_version,
+        ledger_info_with_sigs,
+        ledger_frozen_subtree_hashes,
+    }))?;
+
+    let mut start_version = first_version;
+    while start_version <= version {
+        let txns = db.get_transactions(start_version, CHUNK_SIZE, version, true)?;
+        start_version += txns.transaction_and_infos.len() as u64;
+        archive.write_record(Record::Transactions(txns))?;
+    }
+
+    // The last chunk, possibly empty, proves that there is no account after it.
+    loop {
+        let next_start_key = account_states.next_start_key();
+        archive.write_record(Record::AccountStates(account_states))?;
+        match next_start_key {
+            Some(key) => account_states = db.get_account_state_chunk(key, CHUNK_SIZE, version)?.0,
+            None => break,
+        }
+    }
+
+    // The ledger info at `version` is in the manifest already.
+    let mut start_version = first_version;
+    let mut last_epoch = None;
+    'ledger_infos: loop {
+        let ledger_infos = db.get_ledger_infos(start_version, CHUNK_SIZE)?;
+        if ledger_infos.is_empty() {
+            break;
+        }
+        for ledger_info_with_sigs in ledger_infos {
+            let ledger_info = ledger_info_with_sigs.ledger_info();
+            let ledger_info_version = ledger_info.version();
+            if ledger_info_version >= version {
+                break 'ledger_infos;
+            }
+            // The validator set that signed a ledger info of an earlier epoch is the one on chain
+            // right before it.
+            if ledger_info_version > 0
+                && ledger_info.epoch_num() != epoch
+                && last_epoch != Some(ledger_info.epoch_num())
+            {
+                let account_state = db.get_account_state_with_proof(
+                    core_code_address(),
+                    ledger_info_version - 1,
+                    version,
+                )?;
+                archive.write_record(Record::ValidatorSet(account_state))?;
+            }
+            last_epoch = Some(ledger_info.epoch_num());
+            start_version = ledger_info_version + 1;
+            archive.write_record(Record::LedgerInfo(ledger_info_with_sigs))?;
+        }
+    }
+
+    archive.finish()?;
+    Ok(version)
+}
+
+/// Restores the archive read from `reader` into `db`, which must hold exactly the transactions
+/// before the first one of the archive. The signatures of the ledger infos are verified as
+/// `trusted_validators` says. Returns the version of the archive.
+pub fn restore<R: Read>(
+    db: &LibraDB,
+    reader: R,
+    trusted_validators: TrustedValidators,
+) -> Result<Version> {
+    process_archive(Some(db), reader, trusted_validators)
+}
+
+/// Does the same checks as [`restore`], without persisting anything. The ledger infos older than
+/// the one of the archive can only be verified against the transactions when restored though.
+pub fn verify<R: Read>(reader: R, trusted_validators: TrustedValidators) -> Result<Version> {
+    process_archive(None, reader, trusted_validators)
+}
+
+fn process_archive<R: Read>(
+    db: Option<&LibraDB>,
+    reader: R,
+    trusted_validators: TrustedValidators,
+) -> Result<Version> {
+    let mut archive = ArchiveReader::new(reader)?;
+    let manifest = match archive.read_record()? {
+        Some(Record::Manifest(manifest)) => manifest,
+        _ => bail!("The archive doesn't start with a manifest."),
+    };
+    verify_manifest(&manifest, trusted_validators)?;
+    let ledger_info = manifest.ledger_info_with_sigs.ledger_info();
+    let version = ledger_info.version();
+    info!(
+        "Restoring transactions {} to {} and the state at version {}.",
+        manifest.first_version, version, version
+    );
+
+    let mut next_version = manifest.first_version;
+    let mut next_start_key = Some(HashValue::zero());
+    let mut num_ledger_infos = 0;
+    let mut ledger_info_verifier = LedgerInfoVerifier::new(trusted_validators, ledger_info);
+    while let Some(record) = archive.read_record()? {
+        match record {
+            Record::Transactions(txns) => {
+                ensure!(
+                    next_version <= version,
+                    "Transactions beyond the version of the archive {}.",
+                    version,
+                );
+                ensure!(
+                    !txns.transaction_and_infos.is_empty(),
+                    "Empty chunk of transactions at version {}.",
+                    next_version,
+                );
+                // The events are verified against the transaction infos as well.
+                let events = match &txns.events {
+                    Some(events) => events,
+                    None => bail!(
+                        "Missing events of the transactions at version {}.",
+                        next_version
+                    ),
+                };
+                txns.verify(ledger_info, Some(next_version))?;
+                let num_txns = txns.transaction_and_infos.len() as u64;
+                if let Some(db) = db {
+                    let root_hash =
+                        db.restore_transactions(next_version, &txns.transaction_and_infos, events)?;
+                    // The chunks only prove the transactions of the archive, the ones already in
+                    // the DB are checked once the ledger is complete.
+                    if next_version + num_txns == version + 1 {
+                        ensure!(
+                            root_hash == ledger_info.transaction_accumulator_hash(),
+                            "The transactions don't extend the ledger of the DB: root hash {:?} \
+                             vs {:?} in the ledger info of the archive.",
+                            root_hash,
+                            ledger_info.transaction_accumulator_hash(),
+                        );
+                    }
+                }
+                next_version += num_txns;
+            }
+            Record::AccountStates(account_states) => {
+                ensure!(
+                    next_version == version + 1,
+                    "Account states before the end of the transactions: expected version {}.",
+                    next_version,
+                );
+                let start_key = match next_start_key {
+                    Some(key) => key,
+                    None => bail!("Account states after the end of the state snapshot."),
+                };
+                account_states.verify(ledger_info, version, start_key, CHUNK_SIZE)?;
+                next_start_key = account_states.next_start_key();
+                if let Some(db) = db {
+                    let txn_info = account_states.proof.transaction_info().clone();
+                    db.save_account_state_chunk(version, account_states.account_blobs)?;
+                    if next_start_key.is_none() {
+                        db.finalize_state_snapshot(
+                            txn_info,
+                            manifest.ledger_frozen_subtree_hashes.clone(),
+                            manifest.ledger_info_with_sigs.clone(),
+                        )?;
+                    }
+                }
+            }
+            Record::LedgerInfo(ledger_info_with_sigs) => {
+                ensure!(
+                    next_start_key.is_none(),
+                    "Ledger info before the end of the state snapshot.",
+                );
+                let ledger_info_version = ledger_info_with_sigs.ledger_info().version();
+                ensure!(
+                    ledger_info_version >= manifest.first_version && ledger_info_version < version,
+                    "Ledger info at version {} is out of the range of the archive.",
+                    ledger_info_version,
+                );
+                ledger_info_verifier.verify(&ledger_info_with_sigs)?;
+                if let Some(db) = db {
+                    db.restore_ledger_info(&ledger_info_with_sigs)?;
+                }
+                num_ledger_infos += 1;
+            }
+            Record::ValidatorSet(account_state) => {
+                ensure!(
+                    next_start_key.is_none(),
+                    "Validator set before the end of the state snapshot.",
+                );
+                ledger_info_verifier.add_validator_set(account_state)?;
+            }
+            Record::Manifest(_) => bail!("More than one manifest in the archive."),
+            Record::End { .. } => unreachable!("The end of the archive is never returned."),
+        }
+    }
+
+    ensure!(
+        next_version == version + 1,
+        "The archive ends before the transaction at version {}.",
+        next_version,
+    );
+    ensure!(
+        next_start_key.is_none(),
+        "The archive ends before the end of the state snapshot.",
+    );
+    info!(
+        "Restored {} transactions and {} earlier ledger infos, up to version {}.",
+        version + 1 - manifest.first_version,
+        num_ledger_infos,
+        version
+    );
+    Ok(version)
+}
+
+/// Checks that the ledger info of the archive is signed by the trusted validators, unless the
+/// archive is trusted as is, and that the frozen subtrees match it.
+fn verify_manifest(manifest: &Manifest, trusted_validators: TrustedValidators) -> Result<()> {
+    let ledger_info_with_sigs = &manifest.ledger_info_with_sigs;
+    let ledger_info = ledger_info_with_sigs.ledger_info();
+    let version = ledger_info.version();
+    match trusted_validators {
+        TrustedValidators::ValidatorSet(validator) => ledger_info_with_sigs.verify(validator)?,
+        TrustedValidators::Insecure => warn!(
+            "The archive is trusted as is, the signatures of its ledger infos are not verified."
+        ),
+    }
+
+    ensure!(
+        manifest.first_version <= version,
+        "First version {} is greater than the version of the archive {}.",
+        manifest.first_version,
+        version,
+    );
+    ensure!(
+        manifest.ledger_frozen_subtree_hashes.len() == (version + 1).count_ones() as usize,
+        "Wrong number of frozen subtrees for version {}: {}.",
+        version,
+        manifest.ledger_frozen_subtree_hashes.len(),
+    );
+    let accumulator = Accumulator::<TransactionAccumulatorHasher>::new(
+        manifest.ledger_frozen_subtree_hashes.clone(),
+        version + 1,
+    );
+    ensure!(
+        accumulator.root_hash() == ledger_info.transaction_accumulator_hash(),
+        "Frozen subtrees do not match the root hash in ledger info."
+    );
+    Ok(())
+}
+
+/// Verifies the signatures of the ledger infos older than the one of an archive, which are read in
+/// increasing versions.
+struct LedgerInfoVerifier<'a> {
+    trusted_validators: TrustedValidators<'a>,
+    /// The ledger info of the archive.
+    ledger_info: &'a LedgerInfo,
+    /// The validator set on chain at the version of the last validator set record, which signed
+    /// the ledger info right after it.
+    pending_validator_set: Option<(Version, ValidatorVerifier<Ed25519PublicKey>)>,
+    /// The validator set of the earlier epoch the last ledger info read is in.
+    epoch_validator_set: Option<(u64, ValidatorVerifier<Ed25519PublicKey>)>,
+}
+
+impl<'a> LedgerInfoVerifier<'a> {
+    fn new(trusted_validators: TrustedValidators<'a>, ledger_info: &'a LedgerInfo) -> Self {
+        Self {
+            trusted_validators,
+            ledger_info,
+            pending_validator_set: None,
+            epoch_validator_set: None,
+        }
+    }
+
+    /// Checks the proof of a validator set record against the ledger info of the archive, and
+    /// keeps the validator set to verify the next ledger info.
+    fn add_validator_set(&mut self, account_state: AccountStateWithProof) -> Result<()> {
+        ensure!(
+            account_state.version < self.ledger_info.version(),
+            "Validator set at version {} is out of the range of the archive.",
+            account_state.version,
+        );
+        account_state.verify(self.ledger_info, account_state.version, core_code_address())?;
+        if let TrustedValidators::ValidatorSet(_) = self.trusted_validators {
+            let blob = account_state.blob.as_ref().ok_or_else(|| {
+                format_err!(
+                    "No validator set on chain at version {}.",
+                    account_state.version
+                )
+            })?;
+            let validator_set = ValidatorSet::make_from(&BTreeMap::try_from(blob)?)?;
+            let validator = ValidatorVerifier::new(
+                validator_set
+                    .payload()
+                    .iter()
+                    .map(|keys| (*keys.account_address(), keys.consensus_public_key().clone()))
+                    .collect(),
+            );
+            self.pending_validator_set = Some((account_state.version, validator));
+        }
+        Ok(())
+    }
+
+    /// Checks that `ledger_info_with_sigs` is signed by the validator set of its epoch. The
+    /// genesis ledger info isn't signed, it is only checked against the transactions.
+    fn verify(
+        &mut self,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Result<()> {
+        let trusted_validator = match self.trusted_validators {
+            TrustedValidators::ValidatorSet(validator) => validator,
+            TrustedValidators::Insecure => return Ok(()),
+        };
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let (version, epoch) = (ledger_info.version(), ledger_info.epoch_num());
+        if version == 0 {
+            return Ok(());
+        }
+        if epoch == self.ledger_info.epoch_num() {
+            return Ok(ledger_info_with_sigs.verify(trusted_validator)?);
+        }
+
+        if let Some((validator_set_version, validator)) = self.pending_validator_set.take() {
+            ensure!(
+                validator_set_version + 1 == version,
+                "Validator set at version {} doesn't precede the ledger info at version {}.",
+                validator_set_version,
+                version,
+            );
+            self.epoch_validator_set = Some((epoch, validator));
+        }
+        match &self.epoch_validator_set {
+            Some((validator_epoch, validator)) if *validator_epoch == epoch => {
+                Ok(ledger_info_with_sigs.verify(validator)?)
+            }
+            _ => bail!(
+                "No validator set to verify the ledger info at version {} in epoch {}.",
+                version,
+                epoch,
+            ),
+        }
+    }
+}
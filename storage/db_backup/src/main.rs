@@ -0,0 +1,159 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Command line tool to back up and restore LibraDB offline.
+
+use config::{config::StorageMode, trusted_peers::TrustedPeersConfig};
+use db_backup::TrustedValidators;
+use failure::prelude::*;
+use libradb::LibraDB;
+use nextgen_crypto::ed25519::*;
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    process,
+};
+use structopt::StructOpt;
+use types::{transaction::Version, validator_verifier::ValidatorVerifier};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "db_backup", about = "LibraDB backup and restore tool")]
+enum Command {
+    /// Back up a DB, which must not be in use, to an archive
+    #[structopt(name = "backup")]
+    Backup {
+        /// Directory of the DB to back up
+        #[structopt(long = "db-dir", parse(from_os_str))]
+        db_dir: PathBuf,
+        /// Path of the archive to write
+        #[structopt(short = "o", long = "output", parse(from_os_str))]
+        output: PathBuf,
+        /// Version of the first transaction to back up, for an incremental backup on top of an
+        /// earlier one ending right before it
+        #[structopt(long = "start-version", default_value = "0")]
+        start_version: Version,
+    },
+    /// Restore an archive into a DB, which must be empty or restored from the earlier archives
+    #[structopt(name = "restore")]
+    Restore {
+        /// Directory of the DB to restore into
+        #[structopt(long = "db-dir", parse(from_os_str))]
+        db_dir: PathBuf,
+        /// Path of the archive to restore
+        #[structopt(short = "i", long = "input", parse(from_os_str))]
+        input: PathBuf,
+        /// Trusted peers config file of the validator set that signed the archive
+        #[structopt(long = "validator-set", parse(from_os_str))]
+        validator_set: Option<PathBuf>,
+        /// Trust the archive as is, without verifying the signatures of its ledger infos
+        #[structopt(long = "insecure")]
+        insecure: bool,
+    },
+    /// Verify an archive without restoring it
+    #[structopt(name = "verify")]
+    Verify {
+        /// Path of the archive to verify
+        #[structopt(short = "i", long = "input", parse(from_os_str))]
+        input: PathBuf,
+        /// Trusted peers config file of the validator set that signed the archive
+        #[structopt(long = "validator-set", parse(from_os_str))]
+        validator_set: Option<PathBuf>,
+        /// Trust the archive as is, without verifying the signatures of its ledger infos
+        #[structopt(long = "insecure")]
+        insecure: bool,
+    },
+}
+
+fn load_validator_verifier(path: &Path) -> Result<ValidatorVerifier<Ed25519PublicKey>> {
+    let validators = TrustedPeersConfig::load_config(path).get_trusted_consensus_peers();
+    ensure!(
+        !validators.is_empty(),
+        "Not able to load validators from trusted peers config!"
+    );
+    Ok(ValidatorVerifier::new(validators))
+}
+
+/// Loads the validator set the archive is verified against. Not verifying the archive has to be
+/// asked for explicitly.
+fn load_trusted_validators(
+    validator_set: Option<PathBuf>,
+    insecure: bool,
+) -> Result<Option<ValidatorVerifier<Ed25519PublicKey>>> {
+    match (validator_set, insecure) {
+        (Some(path), false) => Ok(Some(load_validator_verifier(&path)?)),
+        (None, true) => Ok(None),
+        (Some(_), true) => bail!("--validator-set and --insecure can't be used together."),
+        (None, false) => bail!(
+            "The archive is verified against the validator set given with --validator-set. Pass \
+             --insecure to trust it as is."
+        ),
+    }
+}
+
+fn trusted_validators(
+    validator: &Option<ValidatorVerifier<Ed25519PublicKey>>,
+) -> TrustedValidators {
+    validator
+        .as_ref()
+        .map_or(TrustedValidators::Insecure, TrustedValidators::ValidatorSet)
+}
+
+/// Opens the DB in archive mode: the tool must not prune the history it backs up or restores.
+fn open_db(db_dir: &Path) -> LibraDB {
+    LibraDB::new_with_storage_mode(db_dir, StorageMode::Archive)
+}
+
+fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Backup {
+            db_dir,
+            output,
+            start_version,
+        } => {
+            let db = open_db(&db_dir);
+            let version =
+                db_backup::backup(&db, start_version, BufWriter::new(File::create(&output)?))?;
+            println!(
+                "Backed up transactions {} to {} to {:?}.",
+                start_version, version, output
+            );
+        }
+        Command::Restore {
+            db_dir,
+            input,
+            validator_set,
+            insecure,
+        } => {
+            let validator = load_trusted_validators(validator_set, insecure)?;
+            let db = open_db(&db_dir);
+            let version = db_backup::restore(
+                &db,
+                BufReader::new(File::open(&input)?),
+                trusted_validators(&validator),
+            )?;
+            println!("Restored {:?} up to version {}.", input, version);
+        }
+        Command::Verify {
+            input,
+            validator_set,
+            insecure,
+        } => {
+            let validator = load_trusted_validators(validator_set, insecure)?;
+            let version = db_backup::verify(
+                BufReader::new(File::open(&input)?),
+                trusted_validators(&validator),
+            )?;
+            println!("Verified {:?} up to version {}.", input, version);
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    logger::set_simple_logger("db_backup");
+    if let Err(e) = run(Command::from_args()) {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{mock_tree_store::MockTreeStore, JellyfishMerkleTree};
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::BTreeMap;
+
+fn plus_one(key: HashValue) -> HashValue {
+    let mut buf = key.to_vec();
+    for i in (0..buf.len()).rev() {
+        if buf[i] == 255 {
+            buf[i] = 0;
+        } else {
+            buf[i] += 1;
+            break;
+        }
+    }
+    HashValue::from_slice(&buf).unwrap()
+}
+
+fn minus_one(key: HashValue) -> HashValue {
+    let mut buf = key.to_vec();
+    for i in (0..buf.len()).rev() {
+        if buf[i] == 0 {
+            buf[i] = 255;
+        } else {
+            buf[i] -= 1;
+            break;
+        }
+    }
+    HashValue::from_slice(&buf).unwrap()
+}
+
+fn run_tests(db: &MockTreeStore, btree: &BTreeMap<HashValue, AccountStateBlob>, version: Version) {
+    {
+        let iter = JellyfishMerkleIterator::new(db, version, HashValue::zero()).unwrap();
+        assert_eq!(
+            iter.collect::<Result<Vec<_>>>().unwrap(),
+            btree.clone().into_iter().collect::<Vec<_>>(),
+        );
+    }
+
+    for i in 0..btree.len() {
+        {
+            let iter =
+                JellyfishMerkleIterator::new(db, version, *btree.keys().nth(i).unwrap()).unwrap();
+            assert_eq!(
+                iter.collect::<Result<Vec<_>>>().unwrap(),
+                btree.clone().into_iter().skip(i).collect::<Vec<_>>(),
+            );
+        }
+
+        let ith_key = *btree.keys().nth(i).unwrap();
+
+        {
+            // Start from the key right before the i-th key, which does not exist in the tree.
+            let before_ith_key = minus_one(ith_key);
+            if i == 0 || before_ith_key != *btree.keys().nth(i - 1).unwrap() {
+                let iter = JellyfishMerkleIterator::new(db, version, before_ith_key).unwrap();
+                assert_eq!(
+                    iter.collect::<Result<Vec<_>>>().unwrap(),
+                    btree.clone().into_iter().skip(i).collect::<Vec<_>>(),
+                );
+            }
+        }
+
+        {
+            // Start from the key right after the i-th key.
+            let after_ith_key = plus_one(ith_key);
+            let iter = JellyfishMerkleIterator::new(db, version, after_ith_key).unwrap();
+            assert_eq!(
+                iter.collect::<Result<Vec<_>>>().unwrap(),
+                btree.clone().into_iter().skip(i + 1).collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    {
+        let iter =
+            JellyfishMerkleIterator::new(db, version, HashValue::new([0xff; HashValue::LENGTH]))
+                .unwrap();
+        let expected = btree
+            .iter()
+            .filter(|(key, _)| **key == HashValue::new([0xff; HashValue::LENGTH]))
+            .map(|(key, blob)| (*key, blob.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(iter.collect::<Result<Vec<_>>>().unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_iterator_empty_tree() {
+    let db = MockTreeStore::default();
+    db.put_node(NodeKey::new_empty_path(0 /* version */), Node::new_null())
+        .unwrap();
+
+    run_tests(&db, &BTreeMap::new(), 0 /* version */);
+}
+
+#[test]
+fn test_iterator_single_leaf() {
+    let db = MockTreeStore::default();
+    let tree = JellyfishMerkleTree::new(&db);
+
+    let key = HashValue::new([0x12; HashValue::LENGTH]);
+    let blob = AccountStateBlob::from(vec![1u8, 2u8]);
+    let (_root_hash, batch) = tree
+        .put_blob_set(vec![(key, blob.clone())], 0 /* version */)
+        .unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+
+    let mut btree = BTreeMap::new();
+    btree.insert(key, blob);
+    run_tests(&db, &btree, 0 /* version */);
+}
+
+#[test]
+fn test_iterator_same_version() {
+    for i in (1..100).step_by(11) {
+        test_n_leaves_same_version(i);
+    }
+}
+
+#[test]
+fn test_iterator_multiple_versions() {
+    test_n_leaves_multiple_versions(50);
+}
+
+fn test_n_leaves_same_version(n: usize) {
+    let db = MockTreeStore::default();
+    let tree = JellyfishMerkleTree::new(&db);
+
+    let mut rng = StdRng::from_seed([1; 32]);
+
+    let mut btree = BTreeMap::new();
+    for i in 0..n {
+        let key = HashValue::random_with_rng(&mut rng);
+        let blob = AccountStateBlob::from(i.to_be_bytes().to_vec());
+        assert_eq!(btree.insert(key, blob), None);
+    }
+
+    let (_root_hash, batch) = tree
+        .put_blob_set(btree.clone().into_iter().collect(), 0 /* version */)
+        .unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+
+    run_tests(&db, &btree, 0 /* version */);
+}
+
+fn test_n_leaves_multiple_versions(n: usize) {
+    let db = MockTreeStore::default();
+    let tree = JellyfishMerkleTree::new(&db);
+
+    let mut rng = StdRng::from_seed([1; 32]);
+
+    let mut btree = BTreeMap::new();
+    for i in 0..n {
+        let key = HashValue::random_with_rng(&mut rng);
+        let blob = AccountStateBlob::from(i.to_be_bytes().to_vec());
+        assert_eq!(btree.insert(key, blob.clone()), None);
+        let (_root_hash, batch) = tree.put_blob_set(vec![(key, blob)], i as Version).unwrap();
+        db.write_tree_update_batch(batch).unwrap();
+        run_tests(&db, &btree, i as Version);
+    }
+}
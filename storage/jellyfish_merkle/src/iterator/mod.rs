@@ -0,0 +1,230 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements `JellyfishMerkleIterator`. Initialized with a version and a key, the
+//! iterator generates all the key-value pairs in this version of the tree, starting from the
+//! smallest key that is greater or equal to the given key, by performing a depth first traversal
+//! on the tree.
+
+#[cfg(test)]
+mod iterator_test;
+
+use crate::{
+    nibble::{Nibble, NibblePath},
+    node_type::{InternalNode, Node, NodeKey},
+    TreeReader, ROOT_NIBBLE_HEIGHT,
+};
+use crypto::HashValue;
+use failure::prelude::*;
+use types::{account_state_blob::AccountStateBlob, transaction::Version};
+
+/// `NodeVisitInfo` keeps track of the status of an internal node during the iteration process. It
+/// indicates which ones of its children have been visited.
+#[derive(Debug)]
+struct NodeVisitInfo {
+    /// The key to this node.
+    node_key: NodeKey,
+
+    /// The node itself.
+    node: InternalNode,
+
+    /// The index of the next child to visit. All children with smaller indices have been visited
+    /// already. This is 16 once all the children have been visited.
+    next_child_to_visit: u8,
+}
+
+impl NodeVisitInfo {
+    /// Constructs a new `NodeVisitInfo` with given node key and node. All children are considered
+    /// unvisited.
+    fn new(node_key: NodeKey, node: InternalNode) -> Self {
+        Self {
+            node_key,
+            node,
+            next_child_to_visit: 0,
+        }
+    }
+
+    /// Returns the key of the next existing child to visit and marks it as visited. Returns `None`
+    /// if all existing children have been visited.
+    fn advance(&mut self) -> Option<NodeKey> {
+        while self.next_child_to_visit < 16 {
+            let index = Nibble::from(self.next_child_to_visit);
+            self.next_child_to_visit += 1;
+            if let Some(child) = self.node.child(index) {
+                return Some(self.node_key.gen_child_node_key(child.version, index));
+            }
+        }
+        None
+    }
+}
+
+/// The `JellyfishMerkleIterator` implementation.
+pub struct JellyfishMerkleIterator<'a, R: 'a + TreeReader> {
+    /// The storage engine from which we can read nodes using node keys.
+    reader: &'a R,
+
+    /// The version of the tree this iterator is running on.
+    version: Version,
+
+    /// The stack used for depth first traversal. The internal nodes on the path from root to the
+    /// node being visited are pushed into it.
+    parent_stack: Vec<NodeVisitInfo>,
+
+    /// Whether the iteration has finished. Usually this can be determined by checking whether
+    /// `self.parent_stack` is empty. But in case of a tree with a single leaf, we need this
+    /// additional bit.
+    done: bool,
+}
+
+impl<'a, R> JellyfishMerkleIterator<'a, R>
+where
+    R: 'a + TreeReader,
+{
+    /// Constructs a new iterator. This puts the internal state in the correct position, so the
+    /// following `next` call will yield the smallest key that is greater or equal to
+    /// `starting_key`.
+    pub fn new(reader: &'a R, version: Version, starting_key: HashValue) -> Result<Self> {
+        let mut parent_stack = vec![];
+        let mut done = false;
+
+        let mut current_node_key = NodeKey::new_empty_path(version);
+        let nibble_path = NibblePath::new(starting_key.to_vec());
+        let mut nibble_iter = nibble_path.nibbles();
+
+        // We limit the number of loops here deliberately to avoid potential cyclic graph bugs
+        // in the tree structure.
+        for nibble_depth in 0..=ROOT_NIBBLE_HEIGHT {
+            match reader.get_node(&current_node_key)? {
+                Node::Internal(internal_node) => {
+                    let child_index = match nibble_iter.next() {
+                        Some(nibble) => nibble,
+                        // Shouldn't happen
+                        None => bail!("ran out of nibbles"),
+                    };
+                    let child = internal_node.child(child_index).cloned();
+                    let mut visit_info = NodeVisitInfo::new(current_node_key, internal_node);
+                    // All children to the left of the queried one only have smaller keys, so they
+                    // are skipped. The queried child, if it exists, is visited right below.
+                    visit_info.next_child_to_visit = u8::from(child_index) + 1;
+                    let child_node_key = child.map(|child| {
+                        visit_info
+                            .node_key
+                            .gen_child_node_key(child.version, child_index)
+                    });
+                    parent_stack.push(visit_info);
+                    current_node_key = match child_node_key {
+                        Some(node_key) => node_key,
+                        // The queried child doesn't exist, so the next key to yield is in the
+                        // next existing child.
+                        None => {
+                            return Ok(Self {
+                                reader,
+                                version,
+                                parent_stack,
+                                done,
+                            })
+                        }
+                    };
+                }
+                Node::Leaf(leaf_node) => {
+                    if leaf_node.account_key() >= starting_key {
+                        // This leaf needs to be yielded first, so we mark it as unvisited in its
+                        // parent. If it is the root, `done` stays false so it is visited anyway.
+                        if let Some(parent) = parent_stack.last_mut() {
+                            parent.next_child_to_visit -= 1;
+                        }
+                    } else if parent_stack.is_empty() {
+                        // The root is the only leaf and it is smaller than `starting_key`.
+                        done = true;
+                    }
+                    return Ok(Self {
+                        reader,
+                        version,
+                        parent_stack,
+                        done,
+                    });
+                }
+                Node::Null => {
+                    if nibble_depth == 0 {
+                        return Ok(Self {
+                            reader,
+                            version,
+                            parent_stack,
+                            done: true,
+                        });
+                    } else {
+                        bail!(
+                            "Non-root null node exists with node key {:?}",
+                            current_node_key
+                        );
+                    }
+                }
+            }
+        }
+
+        bail!("Jellyfish Merkle tree has cyclic graph inside.");
+    }
+}
+
+impl<'a, R> Iterator for JellyfishMerkleIterator<'a, R>
+where
+    R: 'a + TreeReader,
+{
+    type Item = Result<(HashValue, AccountStateBlob)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if self.parent_stack.is_empty() {
+            // The tree has a single leaf at the root that has not been visited.
+            self.done = true;
+            let root_node_key = NodeKey::new_empty_path(self.version);
+            return match self.reader.get_node(&root_node_key) {
+                Ok(Node::Leaf(leaf_node)) => {
+                    Some(Ok((leaf_node.account_key(), leaf_node.blob().clone())))
+                }
+                Ok(_) => Some(Err(format_err!(
+                    "Expected a leaf at the root with node key {:?}",
+                    root_node_key
+                ))),
+                Err(err) => Some(Err(err)),
+            };
+        }
+
+        loop {
+            let child_node_key = match self.parent_stack.last_mut() {
+                Some(parent) => match parent.advance() {
+                    Some(node_key) => node_key,
+                    None => {
+                        // All children of this node have been visited. Go back to its parent.
+                        self.parent_stack.pop();
+                        continue;
+                    }
+                },
+                None => {
+                    self.done = true;
+                    return None;
+                }
+            };
+
+            match self.reader.get_node(&child_node_key) {
+                Ok(Node::Internal(internal_node)) => {
+                    self.parent_stack
+                        .push(NodeVisitInfo::new(child_node_key, internal_node));
+                }
+                Ok(Node::Leaf(leaf_node)) => {
+                    return Some(Ok((leaf_node.account_key(), leaf_node.blob().clone())));
+                }
+                Ok(Node::Null) => {
+                    return Some(Err(format_err!(
+                        "Non-root null node exists with node key {:?}",
+                        child_node_key
+                    )));
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
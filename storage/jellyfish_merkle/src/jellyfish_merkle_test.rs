@@ -2,11 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::nibble::Nibble;
-use crypto::HashValue;
+use crate::{iterator::JellyfishMerkleIterator, nibble::Nibble};
+use crypto::{hash::CryptoHash, HashValue};
 use mock_tree_store::MockTreeStore;
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use types::proof::verify_sparse_merkle_element;
+use types::proof::{verify_sparse_merkle_element, verify_sparse_merkle_range};
 
 fn update_nibble(original_key: &HashValue, n: usize, nibble: u8) -> HashValue {
     assert!(nibble < 16);
@@ -593,3 +593,85 @@ fn test_1000_versions() {
     let seed: &[_] = &[1, 2, 3, 4];
     many_versions_get_proof_and_verify_tree_root(seed, 1000);
 }
+
+fn many_keys_get_range_proof_and_verify(seed: &[u8], num_keys: usize) {
+    assert!(seed.len() < 32);
+    let mut actual_seed = [0u8; 32];
+    actual_seed[..seed.len()].copy_from_slice(&seed);
+    let mut rng: StdRng = StdRng::from_seed(actual_seed);
+
+    let db = MockTreeStore::default();
+    let tree = JellyfishMerkleTree::new(&db);
+
+    let mut kvs = vec![];
+    for _i in 0..num_keys {
+        let key = HashValue::random_with_rng(&mut rng);
+        let value = AccountStateBlob::from(HashValue::random_with_rng(&mut rng).to_vec());
+        kvs.push((key, value));
+    }
+
+    let (root, batch) = tree.put_blob_set(kvs, 0 /* version */).unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+
+    for _i in 0..100 {
+        let start_key = HashValue::random_with_rng(&mut rng);
+        let limit = rng.gen_range(1, 20);
+        let leaves = JellyfishMerkleIterator::new(&db, 0 /* version */, start_key)
+            .unwrap()
+            .take(limit)
+            .map(|res| res.map(|(key, blob)| (key, blob.hash())))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let proof = tree
+            .get_range_proof(
+                start_key,
+                leaves.last().map(|(key, _)| *key),
+                0, /* version */
+            )
+            .unwrap();
+        assert!(verify_sparse_merkle_range(root, start_key, &leaves, &proof).is_ok());
+
+        // Omitting any leaf from the range must be detected.
+        for j in 0..leaves.len() {
+            let mut incomplete_leaves = leaves.clone();
+            incomplete_leaves.remove(j);
+            assert!(
+                verify_sparse_merkle_range(root, start_key, &incomplete_leaves, &proof).is_err()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_range_proof_1000_keys() {
+    let seed: &[_] = &[1, 2, 3, 4];
+    many_keys_get_range_proof_and_verify(seed, 1000);
+}
+
+#[test]
+fn test_range_proof_beyond_last_key() {
+    let db = MockTreeStore::default();
+    let tree = JellyfishMerkleTree::new(&db);
+
+    let key = HashValue::new([0x12; HashValue::LENGTH]);
+    let value = AccountStateBlob::from(vec![1u8, 2u8]);
+    let (root, batch) = tree
+        .put_blob_set(vec![(key, value.clone())], 0 /* version */)
+        .unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+
+    // Nothing exists at or after `start_key`.
+    let start_key = HashValue::new([0x13; HashValue::LENGTH]);
+    let proof = tree.get_range_proof(start_key, None, 0).unwrap();
+    assert!(verify_sparse_merkle_range(root, start_key, &[], &proof).is_ok());
+
+    // The only leaf cannot be claimed to be in a range that starts after it.
+    assert!(tree.get_range_proof(start_key, Some(key), 0).is_err());
+
+    // Starting before the leaf, the empty range is rejected.
+    let start_key = HashValue::new([0x11; HashValue::LENGTH]);
+    let proof = tree.get_range_proof(start_key, None, 0).unwrap();
+    assert!(verify_sparse_merkle_range(root, start_key, &[], &proof).is_err());
+    let proof = tree.get_range_proof(start_key, Some(key), 0).unwrap();
+    assert!(verify_sparse_merkle_range(root, start_key, &[(key, value.hash())], &proof).is_ok());
+}
@@ -2,12 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(clippy::unit_arg)]
 
+pub mod iterator;
 #[cfg(test)]
 mod jellyfish_merkle_test;
 #[cfg(test)]
 mod mock_tree_store;
 mod nibble;
 pub mod node_type;
+pub mod restore;
 mod tree_cache;
 
 use crypto::{hash::CryptoHash, HashValue};
@@ -18,7 +20,8 @@ use proptest_derive::Arbitrary;
 use std::collections::{HashMap, HashSet};
 use tree_cache::TreeCache;
 use types::{
-    account_state_blob::AccountStateBlob, proof::definition::SparseMerkleProof,
+    account_state_blob::AccountStateBlob,
+    proof::definition::{SparseMerkleProof, SparseMerkleRangeProof},
     transaction::Version,
 };
 
@@ -460,6 +463,43 @@ where
         bail!("Jellyfish Merkle tree has cyclic graph inside.");
     }
 
+    /// Returns the proof that the leaves with keys from `start_key` to `rightmost_key`, both
+    /// inclusive, are all the leaves in that range in the tree at `version`. `rightmost_key` is
+    /// the key of the last leaf in the range, usually obtained via
+    /// [`JellyfishMerkleIterator`](iterator::JellyfishMerkleIterator). If it is `None`, the proof
+    /// shows that there is no leaf with a key equal to or greater than `start_key`.
+    pub fn get_range_proof(
+        &self,
+        start_key: HashValue,
+        rightmost_key: Option<HashValue>,
+        version: Version,
+    ) -> Result<SparseMerkleRangeProof> {
+        let (_, left_boundary_proof) = self.get_with_proof(start_key, version)?;
+        let right_siblings = match rightmost_key {
+            Some(key) => {
+                ensure!(
+                    key >= start_key,
+                    "Rightmost key {:x} is smaller than start key {:x}.",
+                    key,
+                    start_key
+                );
+                let (blob, proof) = self.get_with_proof(key, version)?;
+                ensure!(
+                    blob.is_some(),
+                    "Rightmost key {:x} doesn't exist at version {}.",
+                    key,
+                    version
+                );
+                proof.siblings().to_vec()
+            }
+            None => vec![],
+        };
+        Ok(SparseMerkleRangeProof::new(
+            left_boundary_proof,
+            right_siblings,
+        ))
+    }
+
     #[cfg(test)]
     pub fn get(&self, key: HashValue, version: Version) -> Result<Option<AccountStateBlob>> {
         Ok(self.get_with_proof(key, version)?.0)
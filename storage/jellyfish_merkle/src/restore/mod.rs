@@ -0,0 +1,353 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements `JellyfishMerkleRestore`, which rebuilds a Jellyfish Merkle tree at a
+//! given version from all the leaves in it, e.g. when a node bootstraps from a state snapshot
+//! instead of replaying all the transactions. The leaves are fed in ascending key order, possibly
+//! in multiple chunks, and the nodes that are complete are handed back to the caller to persist
+//! after each chunk, so only the rightmost path of the tree is kept in memory. The remaining
+//! state can be encoded and persisted together with the handed back nodes, so the restoration can
+//! be resumed after a restart.
+
+#[cfg(test)]
+mod restore_test;
+
+use crate::{
+    nibble::Nibble,
+    node_type::{Child, Children, InternalNode, LeafNode, Node, NodeKey},
+    NodeBatch, TreeUpdateBatch,
+};
+use crypto::{hash::CryptoHash, HashValue};
+use failure::prelude::*;
+use serde::{Deserialize, Serialize};
+use types::{account_state_blob::AccountStateBlob, transaction::Version};
+
+/// A child of an internal node that is still being built.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+enum ChildInfo {
+    /// This child is an internal node. The hash is `None` until the child itself is frozen.
+    Internal { hash: Option<HashValue> },
+
+    /// This child is a leaf node. It is not persisted until its parent is frozen, since a leaf
+    /// added later may share a longer prefix with it and push it down to a lower level.
+    Leaf { node: LeafNode },
+}
+
+impl ChildInfo {
+    /// Converts this child into a [`Child`] of a complete internal node.
+    fn into_child(self, version: Version) -> Child {
+        match self {
+            ChildInfo::Internal { hash } => Child::new(
+                hash.expect("Internal child must have been frozen."),
+                version,
+                false, /* is_leaf */
+            ),
+            ChildInfo::Leaf { node } => Child::new(node.hash(), version, true /* is_leaf */),
+        }
+    }
+}
+
+/// An internal node on the path from the root to the last added leaf. More children may still be
+/// added to it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct InternalInfo {
+    /// The key to this node.
+    node_key: NodeKey,
+
+    /// The children of this node, indexed by nibble.
+    children: [Option<ChildInfo>; 16],
+}
+
+impl InternalInfo {
+    /// Creates an internal node without any child.
+    fn new_empty(node_key: NodeKey) -> Self {
+        Self {
+            node_key,
+            children: Default::default(),
+        }
+    }
+
+    /// Sets the `index`-th child.
+    fn set_child(&mut self, index: Nibble, child_info: ChildInfo) {
+        self.children[u8::from(index) as usize] = Some(child_info);
+    }
+}
+
+/// The `JellyfishMerkleRestore` implementation.
+#[derive(Deserialize, Serialize)]
+pub struct JellyfishMerkleRestore {
+    /// The version of the tree being restored.
+    version: Version,
+
+    /// The internal nodes on the path from the root to the last added leaf, starting from the
+    /// root. The node at index `i` is at nibble depth `i`. All of them can still get new children.
+    partial_nodes: Vec<InternalInfo>,
+
+    /// The nodes that are complete and not yet handed back to the caller. They are always handed
+    /// back before the state is encoded, so they are never part of it.
+    #[serde(skip)]
+    frozen_nodes: NodeBatch,
+
+    /// The number of leaves in `frozen_nodes`.
+    #[serde(skip)]
+    num_frozen_leaves: usize,
+
+    /// The key of the last added leaf. Keys must be strictly increasing.
+    previous_key: Option<HashValue>,
+}
+
+impl JellyfishMerkleRestore {
+    /// Creates a `JellyfishMerkleRestore` that rebuilds the tree at `version`.
+    pub fn new(version: Version) -> Self {
+        Self {
+            version,
+            partial_nodes: vec![InternalInfo::new_empty(NodeKey::new_empty_path(version))],
+            frozen_nodes: NodeBatch::new(),
+            num_frozen_leaves: 0,
+            previous_key: None,
+        }
+    }
+
+    /// Returns the version of the tree being restored.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the key of the last added leaf, if any. The next chunk must start after it.
+    pub fn previous_key(&self) -> Option<HashValue> {
+        self.previous_key
+    }
+
+    /// Returns the smallest key the next chunk can start with, or `None` if the largest possible
+    /// key has been added.
+    pub fn next_key(&self) -> Option<HashValue> {
+        let previous_key = match self.previous_key {
+            Some(key) => key,
+            None => return Some(HashValue::zero()),
+        };
+        let mut buf = previous_key.to_vec();
+        for byte in buf.iter_mut().rev() {
+            if *byte == std::u8::MAX {
+                *byte = 0;
+            } else {
+                *byte += 1;
+                return Some(HashValue::from_slice(&buf).expect("Length is always correct."));
+            }
+        }
+        None
+    }
+
+    /// Encodes the state of the restoration, so it can be persisted between chunks and resumed
+    /// via [`decode`](JellyfishMerkleRestore::decode).
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        ensure!(
+            self.frozen_nodes.is_empty(),
+            "Frozen nodes must be handed back before encoding."
+        );
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Recovers the restoration encoded by [`encode`](JellyfishMerkleRestore::encode).
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(data)?)
+    }
+
+    /// Adds a chunk of leaves. The keys in `chunk` must be sorted and greater than all the keys
+    /// added before. Returns the nodes that have become complete, which will never be changed
+    /// again.
+    pub fn add_chunk(
+        &mut self,
+        chunk: Vec<(HashValue, AccountStateBlob)>,
+    ) -> Result<TreeUpdateBatch> {
+        for (key, blob) in chunk {
+            if let Some(previous_key) = self.previous_key {
+                ensure!(
+                    key > previous_key,
+                    "Account keys must come in increasing order. Got {:x} after {:x}.",
+                    key,
+                    previous_key
+                );
+            }
+            self.add_one(key, blob)?;
+            self.previous_key = Some(key);
+        }
+        Ok(self.take_frozen_nodes())
+    }
+
+    /// Finishes the restoration. Returns the root hash of the tree together with the remaining
+    /// nodes, including the root node.
+    pub fn finish(mut self) -> Result<(HashValue, TreeUpdateBatch)> {
+        while self.partial_nodes.len() > 1 {
+            self.freeze_last_partial_node();
+        }
+
+        let root_info = self
+            .partial_nodes
+            .pop()
+            .expect("Root node must always exist.");
+        let children = root_info.children.iter().flatten().collect::<Vec<_>>();
+        let num_children = children.len();
+        let single_leaf = match children.as_slice() {
+            [ChildInfo::Leaf { node }] => Some(node.clone()),
+            _ => None,
+        };
+        let root_hash = match single_leaf {
+            // A tree with a single leaf has the leaf itself as the root.
+            Some(leaf) => {
+                let root_hash = leaf.hash();
+                self.frozen_nodes
+                    .insert(root_info.node_key, Node::Leaf(leaf));
+                self.num_frozen_leaves += 1;
+                root_hash
+            }
+            // An empty tree is represented by a null root.
+            None if num_children == 0 => {
+                let root_node = Node::new_null();
+                let root_hash = root_node.hash();
+                self.frozen_nodes.insert(root_info.node_key, root_node);
+                root_hash
+            }
+            None => self.freeze_internal_node(root_info),
+        };
+
+        Ok((root_hash, self.take_frozen_nodes()))
+    }
+
+    fn add_one(&mut self, new_key: HashValue, new_blob: AccountStateBlob) -> Result<()> {
+        // The partial nodes that are not on the path to the new key will never get a new child
+        // since the keys are increasing, so they can be frozen. The node at depth `i` is on the
+        // path if the new key shares at least `i` nibbles with the previous key.
+        let common_prefix_nibbles_len = self.previous_key.map_or(0, |previous_key| {
+            previous_key.common_prefix_bits_len(new_key) / 4
+        });
+        while self.partial_nodes.len() > common_prefix_nibbles_len + 1 {
+            self.freeze_last_partial_node();
+        }
+
+        let depth = self.partial_nodes.len() - 1;
+        let child_index = get_nibble(new_key, depth);
+        let new_leaf = LeafNode::new(new_key, new_blob);
+        let existing_child = self
+            .partial_nodes
+            .last_mut()
+            .expect("Root node must always exist.")
+            .children[u8::from(child_index) as usize]
+            .take();
+        match existing_child {
+            None => self
+                .partial_nodes
+                .last_mut()
+                .expect("Root node must always exist.")
+                .set_child(child_index, ChildInfo::Leaf { node: new_leaf }),
+            Some(ChildInfo::Leaf {
+                node: existing_leaf,
+            }) => {
+                // The existing leaf is the previous one. Both leaves need to be pushed down to
+                // the depth where their keys diverge, with a new internal node at each level on
+                // the way.
+                self.partial_nodes
+                    .last_mut()
+                    .expect("Root node must always exist.")
+                    .set_child(child_index, ChildInfo::Internal { hash: None });
+                for i in depth + 1..=common_prefix_nibbles_len {
+                    let parent_key = &self
+                        .partial_nodes
+                        .last()
+                        .expect("Root node must always exist.")
+                        .node_key;
+                    let node_key =
+                        parent_key.gen_child_node_key(self.version, get_nibble(new_key, i - 1));
+                    let mut internal_info = InternalInfo::new_empty(node_key);
+                    if i < common_prefix_nibbles_len {
+                        internal_info
+                            .set_child(get_nibble(new_key, i), ChildInfo::Internal { hash: None });
+                    }
+                    self.partial_nodes.push(internal_info);
+                }
+                let last_info = self
+                    .partial_nodes
+                    .last_mut()
+                    .expect("Root node must always exist.");
+                last_info.set_child(
+                    get_nibble(existing_leaf.account_key(), common_prefix_nibbles_len),
+                    ChildInfo::Leaf {
+                        node: existing_leaf,
+                    },
+                );
+                last_info.set_child(
+                    get_nibble(new_key, common_prefix_nibbles_len),
+                    ChildInfo::Leaf { node: new_leaf },
+                );
+            }
+            Some(ChildInfo::Internal { .. }) => {
+                bail!(
+                    "Found an internal child on the path to the new key {:x}, which should have \
+                     been frozen.",
+                    new_key
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Freezes the deepest partial node and sets the hash in its parent.
+    fn freeze_last_partial_node(&mut self) {
+        let info = self
+            .partial_nodes
+            .pop()
+            .expect("Can only freeze when there are partial nodes.");
+        let index = info
+            .node_key
+            .nibble_path()
+            .clone()
+            .pop()
+            .expect("Only the root node has an empty nibble path.");
+        let hash = self.freeze_internal_node(info);
+        self.partial_nodes
+            .last_mut()
+            .expect("A non-root node must have a parent.")
+            .set_child(index, ChildInfo::Internal { hash: Some(hash) });
+    }
+
+    /// Moves an internal node and its leaf children to `frozen_nodes`, and returns its hash.
+    fn freeze_internal_node(&mut self, info: InternalInfo) -> HashValue {
+        let mut children = Children::new();
+        for (i, child_info) in info.children.iter().enumerate() {
+            if let Some(child_info) = child_info {
+                let index = Nibble::from(i as u8);
+                if let ChildInfo::Leaf { node } = child_info {
+                    self.frozen_nodes.insert(
+                        info.node_key.gen_child_node_key(self.version, index),
+                        Node::Leaf(node.clone()),
+                    );
+                    self.num_frozen_leaves += 1;
+                }
+                children.insert(index, child_info.clone().into_child(self.version));
+            }
+        }
+        let internal_node = InternalNode::new(children);
+        let hash = internal_node.hash();
+        self.frozen_nodes
+            .insert(info.node_key, Node::Internal(internal_node));
+        hash
+    }
+
+    /// Hands over the frozen nodes to the caller.
+    fn take_frozen_nodes(&mut self) -> TreeUpdateBatch {
+        TreeUpdateBatch {
+            node_batch: std::mem::replace(&mut self.frozen_nodes, NodeBatch::new()),
+            num_new_leaves: std::mem::replace(&mut self.num_frozen_leaves, 0),
+            ..TreeUpdateBatch::default()
+        }
+    }
+}
+
+/// Returns the `index`-th nibble of `key`.
+fn get_nibble(key: HashValue, index: usize) -> Nibble {
+    Nibble::from(if index % 2 == 0 {
+        key[index / 2] >> 4
+    } else {
+        key[index / 2] & 0x0f
+    })
+}
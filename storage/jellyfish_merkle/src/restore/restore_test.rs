@@ -0,0 +1,131 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{mock_tree_store::MockTreeStore, JellyfishMerkleTree};
+use crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+/// Restores the tree from `btree` in chunks of random sizes, and checks that the result is exactly
+/// the same as building the tree via `JellyfishMerkleTree::put_blob_set`. The restoration is
+/// encoded and decoded between chunks as if it was resumed after a restart.
+fn restore_and_compare(btree: &BTreeMap<HashValue, AccountStateBlob>) {
+    // The tree is built from scratch, so both sides start from version 0.
+    let version = 0;
+    let mut rng = StdRng::from_seed([1; 32]);
+
+    let mut restore = JellyfishMerkleRestore::new(version);
+    let mut node_batch = NodeBatch::new();
+    let mut num_new_leaves = 0;
+    let mut kvs = btree.clone().into_iter().collect::<Vec<_>>();
+    while !kvs.is_empty() {
+        let chunk_size = rng.gen_range(1, 10);
+        let rest = kvs.split_off(std::cmp::min(chunk_size, kvs.len()));
+        let batch = restore.add_chunk(kvs).unwrap();
+        assert!(batch.stale_node_index_batch.is_empty());
+        for (node_key, node) in batch.node_batch {
+            // Frozen nodes are never changed afterwards.
+            assert!(node_batch.insert(node_key, node).is_none());
+        }
+        num_new_leaves += batch.num_new_leaves;
+        restore = JellyfishMerkleRestore::decode(&restore.encode().unwrap()).unwrap();
+        kvs = rest;
+    }
+    let (root_hash, batch) = restore.finish().unwrap();
+    for (node_key, node) in batch.node_batch {
+        assert!(node_batch.insert(node_key, node).is_none());
+    }
+    num_new_leaves += batch.num_new_leaves;
+    assert_eq!(num_new_leaves, btree.len());
+
+    if btree.is_empty() {
+        assert_eq!(root_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH);
+        return;
+    }
+
+    let db = MockTreeStore::default();
+    let tree = JellyfishMerkleTree::new(&db);
+    let (expected_root_hash, expected_batch) = tree
+        .put_blob_set(btree.clone().into_iter().collect(), version)
+        .unwrap();
+    assert_eq!(root_hash, expected_root_hash);
+    assert_eq!(node_batch, expected_batch.node_batch);
+}
+
+#[test]
+fn test_restore_empty_tree() {
+    restore_and_compare(&BTreeMap::new());
+}
+
+#[test]
+fn test_restore_single_leaf() {
+    let mut btree = BTreeMap::new();
+    btree.insert(
+        HashValue::random(),
+        AccountStateBlob::from(vec![1u8, 2u8, 3u8]),
+    );
+    restore_and_compare(&btree);
+}
+
+#[test]
+fn test_restore_long_common_prefix() {
+    // Keys sharing all but the last nibble force a chain of internal nodes.
+    let mut btree = BTreeMap::new();
+    let mut key = [0x12; HashValue::LENGTH];
+    for i in 0..3u8 {
+        key[HashValue::LENGTH - 1] = i;
+        btree.insert(HashValue::new(key), AccountStateBlob::from(vec![i]));
+    }
+    key[0] = 0xff;
+    btree.insert(HashValue::new(key), AccountStateBlob::from(vec![0xff]));
+    restore_and_compare(&btree);
+}
+
+#[test]
+fn test_restore_random_keys() {
+    let mut rng = StdRng::from_seed([2; 32]);
+    for num_keys in &[2, 17, 100, 1000] {
+        let mut btree = BTreeMap::new();
+        for _i in 0..*num_keys {
+            let key = HashValue::random_with_rng(&mut rng);
+            let blob = AccountStateBlob::from(HashValue::random_with_rng(&mut rng).to_vec());
+            btree.insert(key, blob);
+        }
+        restore_and_compare(&btree);
+    }
+}
+
+#[test]
+fn test_restore_unordered_keys() {
+    let key1 = HashValue::new([0x01; HashValue::LENGTH]);
+    let key2 = HashValue::new([0x02; HashValue::LENGTH]);
+    let blob = AccountStateBlob::from(vec![1u8]);
+
+    let mut restore = JellyfishMerkleRestore::new(0 /* version */);
+    restore.add_chunk(vec![(key2, blob.clone())]).unwrap();
+    assert!(restore.add_chunk(vec![(key1, blob.clone())]).is_err());
+    assert!(restore.add_chunk(vec![(key2, blob)]).is_err());
+}
+
+#[test]
+fn test_restore_next_key() {
+    let blob = AccountStateBlob::from(vec![1u8]);
+    let mut restore = JellyfishMerkleRestore::new(0 /* version */);
+    assert_eq!(restore.next_key(), Some(HashValue::zero()));
+
+    let mut key = [0x01; HashValue::LENGTH];
+    key[HashValue::LENGTH - 1] = 0xff;
+    restore
+        .add_chunk(vec![(HashValue::new(key), blob.clone())])
+        .unwrap();
+    let mut next_key = [0x01; HashValue::LENGTH];
+    next_key[HashValue::LENGTH - 2] = 0x02;
+    next_key[HashValue::LENGTH - 1] = 0x00;
+    assert_eq!(restore.next_key(), Some(HashValue::new(next_key)));
+
+    restore
+        .add_chunk(vec![(HashValue::new([0xff; HashValue::LENGTH]), blob)])
+        .unwrap();
+    assert_eq!(restore.next_key(), None);
+}
@@ -171,6 +171,14 @@ impl EventStore {
             if path != *access_path || ver > ledger_version {
                 break;
             }
+            // The events on an access path are pruned from the oldest on.
+            if seq > start_seq_num && cur_seq == start_seq_num {
+                return Err(LibraDbError::Pruned(format!(
+                    "Event {} on {}",
+                    start_seq_num, access_path
+                ))
+                .into());
+            }
             ensure!(
                 seq == cur_seq,
                 "DB corrupt: Sequence number not continuous, expected: {}, actual: {}.",
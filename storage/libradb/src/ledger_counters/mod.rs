@@ -19,12 +19,16 @@ use strum_macros::{AsRefStr, EnumIter};
 #[strum(serialize_all = "snake_case")]
 pub(crate) enum LedgerCounter {
     EventsCreated = 101,
+    EventsPruned = 102,
 
     StateBlobsCreated = 201,
     StateBlobsRetired = 202,
 
     StateNodesCreated = 301,
     StateNodesRetired = 302,
+    StateNodesPruned = 303,
+
+    TransactionsPruned = 401,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -92,6 +96,15 @@ impl LedgerCounterBumps {
     pub fn get(&mut self, counter: LedgerCounter) -> usize {
         self.bumps.get(counter)
     }
+
+    /// Adds all the bumps in `other` to these.
+    pub fn merge(&mut self, other: LedgerCounterBumps) -> &mut Self {
+        for (key, value) in other.bumps.counters.into_iter() {
+            self.bumps.raw_inc(key, value);
+        }
+
+        self
+    }
 }
 
 /// Represents ledger counter values at a certain version.
@@ -25,3 +25,22 @@ fn test_ledger_counters() {
     assert_eq!(counters.get(LedgerCounter::StateBlobsCreated), 2);
     assert_eq!(counters.get(LedgerCounter::StateBlobsRetired), 1);
 }
+
+#[test]
+fn test_merge_ledger_counter_bumps() {
+    let mut bumps = LedgerCounterBumps::new();
+    bumps
+        .bump(LedgerCounter::EventsCreated, 1)
+        .bump(LedgerCounter::TransactionsPruned, 2);
+
+    let mut other = LedgerCounterBumps::new();
+    other
+        .bump(LedgerCounter::TransactionsPruned, 3)
+        .bump(LedgerCounter::EventsPruned, 4);
+    bumps.merge(other);
+
+    assert_eq!(bumps.get(LedgerCounter::EventsCreated), 1);
+    assert_eq!(bumps.get(LedgerCounter::TransactionsPruned), 5);
+    assert_eq!(bumps.get(LedgerCounter::EventsPruned), 4);
+    assert_eq!(bumps.get(LedgerCounter::StateNodesPruned), 0);
+}
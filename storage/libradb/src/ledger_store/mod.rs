@@ -26,7 +26,7 @@ use types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{
         position::{FrozenSubTreeIterator, Position},
-        AccumulatorProof,
+        AccumulatorConsistencyProof, AccumulatorProof,
     },
     transaction::{TransactionInfo, Version},
 };
@@ -40,18 +40,21 @@ impl LedgerStore {
         Self { db }
     }
 
-    /// Return the ledger infos with their least 2f+1 signatures starting from `start_version` to
-    /// the most recent one.
+    /// Return up to `limit` ledger infos with their least 2f+1 signatures starting from
+    /// `start_version` towards the most recent one.
     /// Note: ledger infos and signatures are only available at the last version of each earlier
     /// epoch and at the latest version of current epoch.
-    #[cfg(test)]
-    fn get_ledger_infos(
+    pub fn get_ledger_infos(
         &self,
         start_version: Version,
+        limit: u64,
     ) -> Result<Vec<LedgerInfoWithSignatures<Ed25519Signature>>> {
         let mut iter = self.db.iter::<LedgerInfoSchema>(ReadOptions::default())?;
         iter.seek(&start_version)?;
-        Ok(iter.map(|kv| Ok(kv?.1)).collect::<Result<Vec<_>>>()?)
+        Ok(iter
+            .take(limit as usize)
+            .map(|kv| Ok(kv?.1))
+            .collect::<Result<Vec<_>>>()?)
     }
 
     pub fn get_latest_ledger_info_option(
@@ -101,6 +104,13 @@ impl LedgerStore {
         ))
     }
 
+    /// Get the root hash of the transaction accumulator right after the transaction at `version`
+    /// is committed.
+    pub fn get_root_hash(&self, version: Version) -> Result<HashValue> {
+        let (root_hash, _) = Accumulator::append(self, version + 1 /* num_leaves */, &[])?;
+        Ok(root_hash)
+    }
+
     /// Get proof for transaction at `version` towards root of ledger at `ledger_version`.
     pub fn get_transaction_proof(
         &self,
@@ -110,6 +120,19 @@ impl LedgerStore {
         Accumulator::get_proof(self, ledger_version + 1 /* num_leaves */, version)
     }
 
+    /// Get proof that the ledger at `ledger_version` extends the ledger at `known_version`.
+    pub fn get_consistency_proof(
+        &self,
+        known_version: Version,
+        ledger_version: Version,
+    ) -> Result<AccumulatorConsistencyProof> {
+        Accumulator::get_consistency_proof(
+            self,
+            known_version + 1,  /* old_num_leaves */
+            ledger_version + 1, /* new_num_leaves */
+        )
+    }
+
     /// Write `txn_infos` to `batch`. Assigned `first_version` to the the version number of the
     /// first transaction, and so on.
     pub fn put_transaction_infos(
@@ -138,6 +161,34 @@ impl LedgerStore {
         Ok(root_hash)
     }
 
+    /// Write `txn_info` at `version` and the frozen subtree root hashes of the transaction
+    /// accumulator with `version + 1` leaves to `cs`. This is used when the ledger is restored from
+    /// a state snapshot, in which case the transactions before `version` are not available, but
+    /// new transactions can still be appended to the accumulator.
+    pub fn put_transaction_info_and_frozen_subtrees(
+        &self,
+        version: Version,
+        txn_info: &TransactionInfo,
+        frozen_subtree_hashes: &[HashValue],
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        cs.batch.put::<TransactionInfoSchema>(&version, txn_info)?;
+
+        let positions = FrozenSubTreeIterator::new(version + 1).collect::<Vec<_>>();
+        ensure!(
+            positions.len() == frozen_subtree_hashes.len(),
+            "Expected {} frozen subtrees for {} transactions. Got {}.",
+            positions.len(),
+            version + 1,
+            frozen_subtree_hashes.len()
+        );
+        positions
+            .iter()
+            .zip(frozen_subtree_hashes.iter())
+            .map(|(pos, hash)| cs.batch.put::<TransactionAccumulatorSchema>(pos, hash))
+            .collect::<Result<()>>()
+    }
+
     /// Write `ledger_info` to `cs`.
     pub fn put_ledger_info(
         &self,
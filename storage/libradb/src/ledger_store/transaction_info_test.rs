@@ -5,7 +5,9 @@ use super::*;
 use crate::LibraDB;
 use proptest::{collection::vec, prelude::*};
 use tempfile::tempdir;
-use types::proof::verify_transaction_accumulator_element;
+use types::proof::{
+    verify_transaction_accumulator_consistency, verify_transaction_accumulator_element,
+};
 
 fn verify(
     store: &LedgerStore,
@@ -64,5 +66,17 @@ proptest! {
 
         // retrieve batch1 and verify against root_hash after batch1 was interted
         verify(store, &batch1, 0, ledger_version1, root_hash1);
+
+        // the ledger after batch2 extends the ledger after batch1
+        let proof = store.get_consistency_proof(ledger_version1, ledger_version2).unwrap();
+        verify_transaction_accumulator_consistency(
+            ledger_version1 + 1,
+            root_hash1,
+            ledger_version2 + 1,
+            root_hash2,
+            &proof,
+        )
+        .unwrap();
+        prop_assert!(store.get_consistency_proof(ledger_version2, ledger_version1).is_err());
     }
 }
@@ -40,28 +40,37 @@ use crate::{
     system_store::SystemStore,
     transaction_store::TransactionStore,
 };
+use config::config::StorageMode;
 use crypto::{hash::CryptoHash, HashValue};
 use failure::prelude::*;
+use futures::channel::mpsc;
 use itertools::{izip, zip_eq};
 use lazy_static::lazy_static;
 use logger::prelude::*;
 use metrics::OpMetrics;
 use nextgen_crypto::ed25519::*;
 use schemadb::{ColumnFamilyOptions, ColumnFamilyOptionsMap, DB, DEFAULT_CF_NAME};
-use std::{iter::Iterator, path::Path, sync::Arc, time::Instant};
-use storage_proto::ExecutorStartupInfo;
+use std::{
+    iter::Iterator,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use storage_proto::{ExecutorStartupInfo, StateSnapshotRestoreProgress};
 use types::{
     access_path::AccessPath,
     account_address::AccountAddress,
-    account_config::get_account_resource_or_default,
-    account_state_blob::{AccountStateBlob, AccountStateWithProof},
-    contract_event::EventWithProof,
+    account_state_blob::{AccountStateBlob, AccountStateRangeWithProof, AccountStateWithProof},
+    contract_event::{ContractEvent, EventFilter, EventWithProof},
     get_with_proof::{RequestItem, ResponseItem},
     ledger_info::LedgerInfoWithSignatures,
-    proof::{AccountStateProof, EventProof, SignedTransactionProof, SparseMerkleProof},
+    proof::{
+        AccountStateProof, AccountStateRangeProof, EventProof, SignedTransactionProof,
+        SparseMerkleProof, SparseMerkleRangeProof,
+    },
     transaction::{
-        SignedTransactionWithProof, TransactionInfo, TransactionListWithProof, TransactionToCommit,
-        Version,
+        EventListWithProof, SignedTransaction, SignedTransactionWithProof, TransactionInfo,
+        TransactionListWithProof, TransactionToCommit, Version,
     },
     validator_change::ValidatorChangeEventWithProof,
 };
@@ -72,6 +81,9 @@ lazy_static! {
 
 const MAX_LIMIT: u64 = 1000;
 const MAX_REQUEST_ITEMS: u64 = 100;
+const MAX_COMMIT_SUBSCRIBERS: u64 = 1000;
+/// The number of commit notifications a subscriber can fall behind before it is disconnected.
+const COMMIT_SUBSCRIBER_BUFFER_SIZE: usize = 100;
 
 fn error_if_too_many_requested(num_requested: u64, max_allowed: u64) -> Result<()> {
     if num_requested > max_allowed {
@@ -90,15 +102,25 @@ pub struct LibraDB {
     state_store: StateStore,
     event_store: EventStore,
     system_store: SystemStore,
-    pruner: Pruner,
+    /// The pruner, which only runs in pruned mode.
+    pruner: Option<Pruner>,
+    /// Subscribers to be notified of the version of each newly committed ledger info.
+    commit_subscribers: Mutex<Vec<mpsc::Sender<Version>>>,
 }
 
 impl LibraDB {
-    /// Config parameter for the pruner.
-    const NUM_HISTORICAL_VERSIONS_TO_KEEP: u64 = 1_000_000;
-
-    /// This creates an empty LibraDB instance on disk or opens one if it already exists.
+    /// This creates an empty LibraDB instance on disk or opens one if it already exists, keeping
+    /// the full history. Pruning is only enabled through [`LibraDB::new_with_storage_mode`].
     pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+        Self::new_with_storage_mode(db_root_path, StorageMode::Archive)
+    }
+
+    /// This creates an empty LibraDB instance on disk or opens one if it already exists, keeping
+    /// the history as `storage_mode` says.
+    pub fn new_with_storage_mode<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        storage_mode: StorageMode,
+    ) -> Self {
         let cf_opts_map: ColumnFamilyOptionsMap = [
             (
                 /* LedgerInfo CF = */ DEFAULT_CF_NAME,
@@ -114,10 +136,18 @@ impl LibraDB {
             (LEDGER_COUNTERS_CF_NAME, ColumnFamilyOptions::default()),
             (STALE_NODE_INDEX_CF_NAME, ColumnFamilyOptions::default()),
             (SIGNED_TRANSACTION_CF_NAME, ColumnFamilyOptions::default()),
+            (
+                STATE_SNAPSHOT_RESTORE_CF_NAME,
+                ColumnFamilyOptions::default(),
+            ),
             (
                 TRANSACTION_ACCUMULATOR_CF_NAME,
                 ColumnFamilyOptions::default(),
             ),
+            (
+                TRANSACTION_BY_ACCOUNT_CF_NAME,
+                ColumnFamilyOptions::default(),
+            ),
             (TRANSACTION_INFO_CF_NAME, ColumnFamilyOptions::default()),
             (VALIDATOR_CF_NAME, ColumnFamilyOptions::default()),
         ]
@@ -138,21 +168,51 @@ impl LibraDB {
             instant.elapsed().as_millis()
         );
 
-        LibraDB {
+        let pruner = match storage_mode {
+            StorageMode::Archive => None,
+            StorageMode::Pruned(num_historical_versions_to_keep) => Some(Pruner::new(
+                Arc::clone(&db),
+                num_historical_versions_to_keep,
+            )),
+        };
+        let libra_db = LibraDB {
             db: Arc::clone(&db),
             event_store: EventStore::new(Arc::clone(&db)),
             ledger_store: LedgerStore::new(Arc::clone(&db)),
             state_store: StateStore::new(Arc::clone(&db)),
             transaction_store: TransactionStore::new(Arc::clone(&db)),
             system_store: SystemStore::new(Arc::clone(&db)),
-            pruner: Pruner::new(Arc::clone(&db), Self::NUM_HISTORICAL_VERSIONS_TO_KEEP),
+            pruner,
+            commit_subscribers: Mutex::new(Vec::new()),
+        };
+
+        // Index the transactions committed before the index by account was introduced.
+        let num_backfilled = libra_db
+            .transaction_store
+            .backfill_account_index()
+            .unwrap_or_else(|e| panic!("Failed to index transactions by account: {:?}", e));
+        if num_backfilled > 0 {
+            info!("Indexed {} transactions by account.", num_backfilled);
+        }
+
+        // Pick up pruning where it was left off when the DB was last closed.
+        if let Some(pruner) = &libra_db.pruner {
+            let latest_txn_info = libra_db
+                .ledger_store
+                .get_latest_transaction_info_option()
+                .unwrap_or_else(|e| panic!("Failed to read the latest transaction info: {:?}", e));
+            if let Some((latest_version, _)) = latest_txn_info {
+                pruner.wake(latest_version);
+            }
         }
+
+        libra_db
     }
 
     // ================================== Public API ==================================
     /// Returns the account state corresponding to the given version and account address with proof
     /// based on `ledger_version`
-    fn get_account_state_with_proof(
+    pub fn get_account_state_with_proof(
         &self,
         address: AccountAddress,
         version: Version,
@@ -171,6 +231,7 @@ impl LibraDB {
             ledger_version,
             latest_version
         );
+        self.error_if_pruned("State", version)?;
 
         let (txn_info, txn_info_accumulator_proof) = self
             .ledger_store
@@ -185,6 +246,39 @@ impl LibraDB {
         ))
     }
 
+    /// Returns up to `limit` account states with keys (hashed account addresses) equal to or
+    /// greater than `start_key` at the given version, sorted by key, with a range proof based on
+    /// `ledger_version`.
+    fn get_account_states_in_range(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<AccountStateRangeWithProof> {
+        ensure!(
+            version <= ledger_version,
+            "The queried version {} should be equal to or older than ledger version {}.",
+            version,
+            ledger_version
+        );
+
+        let (txn_info, txn_info_accumulator_proof) = self
+            .ledger_store
+            .get_transaction_info_with_proof(version, ledger_version)?;
+        let (account_blobs, sparse_merkle_range_proof) =
+            self.get_account_states_in_range_by_version(start_key, limit, version)?;
+        Ok(AccountStateRangeWithProof::new(
+            version,
+            account_blobs,
+            AccountStateRangeProof::new(
+                txn_info_accumulator_proof,
+                txn_info,
+                sparse_merkle_range_proof,
+            ),
+        ))
+    }
+
     /// Returns events specified by `access_path` with sequence number in range designated by
     /// `start_seq_num`, `ascending` and `limit`. If ascending is true this query will return up to
     /// `limit` events that were emitted after `start_event_seq_num`. Otherwise it will return up to
@@ -238,20 +332,15 @@ impl LibraDB {
         let mut events_with_proof = event_keys
             .into_iter()
             .map(|(seq, ver, idx)| {
-                let (event, event_proof) = self
-                    .event_store
-                    .get_event_with_proof_by_version_and_index(ver, idx)?;
+                self.error_if_pruned("Event", ver)?;
+                let event_with_proof = self.get_event_with_proof(ver, idx, ledger_version)?;
                 ensure!(
-                    seq == event.sequence_number(),
+                    seq == event_with_proof.event.sequence_number(),
                     "Index broken, expected seq:{}, actual:{}",
                     seq,
-                    event.sequence_number()
+                    event_with_proof.event.sequence_number()
                 );
-                let (txn_info, txn_info_proof) = self
-                    .ledger_store
-                    .get_transaction_info_with_proof(ver, ledger_version)?;
-                let proof = EventProof::new(txn_info_proof, txn_info, event_proof);
-                Ok(EventWithProof::new(ver, idx, event, proof))
+                Ok(event_with_proof)
             })
             .collect::<Result<Vec<_>>>()?;
         if !ascending {
@@ -281,9 +370,63 @@ impl LibraDB {
         Ok((events_with_proof, proof_of_latest_event))
     }
 
+    /// Returns the events yielded by the transactions starting from `start_version`, up to the one
+    /// yielding the `limit`-th event matching `filter`, with the infos of the transactions and a
+    /// proof of them. All the events yielded by each transaction are returned, so that they can be
+    /// verified against its event root hash. At most `MAX_LIMIT` transactions are returned.
+    fn get_events_by_version_range(
+        &self,
+        start_version: Version,
+        limit: u64,
+        filter: &EventFilter,
+        ledger_version: Version,
+    ) -> Result<EventListWithProof> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+        if start_version > ledger_version || limit == 0 {
+            return Ok(EventListWithProof::new_empty());
+        }
+        self.error_if_pruned("Event", start_version)?;
+
+        let end_version = std::cmp::min(start_version + MAX_LIMIT, ledger_version + 1);
+        let mut transaction_infos = Vec::new();
+        let mut event_lists = Vec::new();
+        let mut num_matching_events = 0;
+        for version in start_version..end_version {
+            if num_matching_events >= limit {
+                break;
+            }
+            let events = self.event_store.get_events_by_version(version)?;
+            num_matching_events += events.iter().filter(|e| filter.matches(e)).count() as u64;
+            transaction_infos.push(self.ledger_store.get_transaction_info(version)?);
+            event_lists.push(events);
+        }
+
+        let num_txns = transaction_infos.len() as u64;
+        let proof_of_first_transaction = Some(
+            self.ledger_store
+                .get_transaction_proof(start_version, ledger_version)?,
+        );
+        let proof_of_last_transaction = if num_txns == 1 {
+            None
+        } else {
+            Some(
+                self.ledger_store
+                    .get_transaction_proof(start_version + num_txns - 1, ledger_version)?,
+            )
+        };
+
+        Ok(EventListWithProof::new(
+            transaction_infos,
+            event_lists,
+            Some(start_version),
+            proof_of_first_transaction,
+            proof_of_last_transaction,
+        ))
+    }
+
     /// Returns a signed transaction that is the `seq_num`-th one associated with the given account.
     /// If the signed transaction with given `seq_num` doesn't exist, returns `None`.
-    // TODO(gzh): Use binary search for now. We may create seq_num index in the future.
     fn get_txn_by_account_and_seq(
         &self,
         address: AccountAddress,
@@ -291,35 +434,48 @@ impl LibraDB {
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<Option<SignedTransactionWithProof>> {
-        // If txn with seq_num n is at some version, the corresponding account state at the
-        // same version will be the first account state that has seq_num n + 1.
-        let seq_num = seq_num + 1;
-        let (mut start_version, mut end_version) = (0, ledger_version);
-        while start_version < end_version {
-            let mid_version = start_version + (end_version - start_version) / 2;
-            let account_seq_num = self.get_account_seq_num_by_version(address, mid_version)?;
-            if account_seq_num >= seq_num {
-                end_version = mid_version;
+        self.transaction_store
+            .lookup_transactions_by_account(address, seq_num, 1 /* limit */, ledger_version)?
+            .first()
+            .map(|version| self.get_transaction_with_proof(*version, ledger_version, fetch_events))
+            .transpose()
+    }
+
+    /// Returns up to `limit` signed transactions sent by the given account, with consecutive
+    /// sequence numbers starting from `start_seq_num`. If fewer than `limit` transactions are
+    /// returned, the account state is also returned to prove the current sequence number of the
+    /// account.
+    fn get_txns_by_account(
+        &self,
+        address: AccountAddress,
+        start_seq_num: u64,
+        limit: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<(
+        Vec<SignedTransactionWithProof>,
+        Option<AccountStateWithProof>,
+    )> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+        let signed_transactions_with_proof = self
+            .transaction_store
+            .lookup_transactions_by_account(address, start_seq_num, limit, ledger_version)?
+            .into_iter()
+            .map(|version| self.get_transaction_with_proof(version, ledger_version, fetch_events))
+            .collect::<Result<Vec<_>>>()?;
+
+        let proof_of_current_sequence_number =
+            if (signed_transactions_with_proof.len() as u64) < limit {
+                Some(self.get_account_state_with_proof(address, ledger_version, ledger_version)?)
             } else {
-                start_version = mid_version + 1;
-            }
-        }
-        assert_eq!(start_version, end_version);
-
-        let seq_num_found = self.get_account_seq_num_by_version(address, start_version)?;
-        if seq_num_found < seq_num {
-            return Ok(None);
-        } else if seq_num_found > seq_num {
-            // log error
-            bail!("internal error: seq_num is not continuous.")
-        }
-        // start_version cannot be 0 (genesis version).
-        assert_eq!(
-            self.get_account_seq_num_by_version(address, start_version - 1)?,
-            seq_num_found - 1
-        );
-        self.get_transaction_with_proof(start_version, ledger_version, fetch_events)
-            .map(Some)
+                None
+            };
+
+        Ok((
+            signed_transactions_with_proof,
+            proof_of_current_sequence_number,
+        ))
     }
 
     /// Gets the latest version number available in the ledger.
@@ -382,6 +538,14 @@ impl LibraDB {
             self.ledger_store.put_ledger_info(x, &mut cs)?;
         }
 
+        // Report what has been pruned since the last commit. Counters are only saved along with
+        // transactions.
+        if num_txns > 0 {
+            if let Some(pruner) = &self.pruner {
+                cs.counter_bumps.merge(pruner.take_counter_bumps());
+            }
+        }
+
         // Persist.
         let (sealed_cs, counters) = self.seal_change_set(first_version, num_txns, cs)?;
         self.commit(sealed_cs)?;
@@ -396,7 +560,12 @@ impl LibraDB {
                 .expect("Counters should be bumped with transactions being saved.")
                 .bump_op_counters();
 
-            self.pruner.wake(last_version);
+            if let Some(pruner) = &self.pruner {
+                pruner.wake(last_version);
+            }
+        }
+        if let Some(x) = ledger_info_with_sigs {
+            self.notify_commit_subscribers(x.ledger_info().version());
         }
 
         Ok(())
@@ -517,6 +686,26 @@ impl LibraDB {
                     })
                 }
 
+                RequestItem::GetAccountTransactions {
+                    account,
+                    start_sequence_number,
+                    limit,
+                    fetch_events,
+                } => {
+                    let (signed_transactions_with_proof, proof_of_current_sequence_number) = self
+                        .get_txns_by_account(
+                        account,
+                        start_sequence_number,
+                        limit,
+                        ledger_version,
+                        fetch_events,
+                    )?;
+
+                    Ok(ResponseItem::GetAccountTransactions {
+                        signed_transactions_with_proof,
+                        proof_of_current_sequence_number,
+                    })
+                }
                 RequestItem::GetEventsByEventAccessPath {
                     access_path,
                     start_event_seq_num,
@@ -536,6 +725,22 @@ impl LibraDB {
                         proof_of_latest_event,
                     })
                 }
+                RequestItem::GetEventsByVersionRange {
+                    start_version,
+                    limit,
+                    filter,
+                } => {
+                    let event_list_with_proof = self.get_events_by_version_range(
+                        start_version,
+                        limit,
+                        &filter,
+                        ledger_version,
+                    )?;
+
+                    Ok(ResponseItem::GetEventsByVersionRange {
+                        event_list_with_proof,
+                    })
+                }
                 RequestItem::GetTransactions {
                     start_version,
                     limit,
@@ -548,6 +753,33 @@ impl LibraDB {
                         txn_list_with_proof,
                     })
                 }
+                RequestItem::GetAccountStatesInRange { start_key, limit } => {
+                    let account_state_range_with_proof = self.get_account_states_in_range(
+                        start_key,
+                        limit,
+                        ledger_version,
+                        ledger_version,
+                    )?;
+
+                    Ok(ResponseItem::GetAccountStatesInRange {
+                        account_state_range_with_proof,
+                    })
+                }
+                RequestItem::GetAccumulatorConsistencyProof { known_version, .. } => {
+                    ensure!(
+                        known_version <= ledger_version,
+                        "The known version {} is newer than the latest version {}.",
+                        known_version,
+                        ledger_version,
+                    );
+                    let accumulator_consistency_proof = self
+                        .ledger_store
+                        .get_consistency_proof(known_version, ledger_version)?;
+
+                    Ok(ResponseItem::GetAccumulatorConsistencyProof {
+                        accumulator_consistency_proof,
+                    })
+                }
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -569,10 +801,28 @@ impl LibraDB {
         address: AccountAddress,
         version: Version,
     ) -> Result<(Option<AccountStateBlob>, SparseMerkleProof)> {
+        self.error_if_pruned("State", version)?;
         self.state_store
             .get_account_state_with_proof_by_version(address, version)
     }
 
+    /// Gets up to `limit` account states with keys (hashed account addresses) equal to or greater
+    /// than `start_key`, out of the ledger state at `version`, together with a proof that no
+    /// account in the range is left out.
+    ///
+    /// This is used by the storage service to serve a range of account states at a given version.
+    pub fn get_account_states_in_range_by_version(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+        self.error_if_pruned("State", version)?;
+        self.state_store
+            .get_account_states_in_range_by_version(start_key, limit, version)
+    }
+
     /// Gets information needed from storage during the startup of the executor module.
     ///
     /// This is used by the executor module internally.
@@ -592,14 +842,38 @@ impl LibraDB {
             .ledger_store
             .get_ledger_frozen_subtree_hashes(latest_version)?;
 
+        let state_snapshot_restore = self.get_state_snapshot_restore_progress()?;
+
         Ok(Some(ExecutorStartupInfo {
             ledger_info,
             latest_version,
             account_state_root_hash,
             ledger_frozen_subtree_hashes,
+            state_snapshot_restore,
         }))
     }
 
+    /// Gets the progress of restoring a state snapshot, if one is being restored.
+    ///
+    /// This is used by the executor module to continue a restoration where it left off.
+    pub fn get_state_snapshot_restore_progress(
+        &self,
+    ) -> Result<Option<StateSnapshotRestoreProgress>> {
+        // A restoration that has taken the largest possible key only misses finalization, which
+        // needs the last chunk again, so it starts over.
+        Ok(self
+            .state_store
+            .get_account_state_restore()?
+            .and_then(|restore| {
+                restore
+                    .next_key()
+                    .map(|next_key| StateSnapshotRestoreProgress {
+                        version: restore.version(),
+                        next_key,
+                    })
+            }))
+    }
+
     // ======================= State Synchronizer Internal APIs ===================================
     /// Gets a batch of transactions for the purpose of synchronizing state to another node.
     ///
@@ -616,6 +890,7 @@ impl LibraDB {
         if start_version > ledger_version || limit == 0 {
             return Ok(TransactionListWithProof::new_empty());
         }
+        self.error_if_pruned("Transaction", start_version)?;
 
         let limit = std::cmp::min(limit, ledger_version - start_version + 1);
         let txn_and_txn_info_list = (start_version..start_version + limit)
@@ -659,6 +934,220 @@ impl LibraDB {
         ))
     }
 
+    /// Gets up to `limit` account states with keys (hashed account addresses) equal to or greater
+    /// than `start_key` at `version`, with a range proof based on the ledger at the same version,
+    /// and the frozen subtree root hashes of the transaction accumulator at that version. These
+    /// are what another node needs to bootstrap from a state snapshot at `version`.
+    ///
+    /// This is used by the State Synchronizer module internally.
+    pub fn get_account_state_chunk(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Result<(AccountStateRangeWithProof, Vec<HashValue>)> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+        let latest_version = self.get_latest_version()?;
+        ensure!(
+            version <= latest_version,
+            "The queried version {} is greater than the latest version currently in ledger: {}",
+            version,
+            latest_version
+        );
+
+        let account_state_range_with_proof =
+            self.get_account_states_in_range(start_key, limit, version, version)?;
+        let ledger_frozen_subtree_hashes = self
+            .ledger_store
+            .get_ledger_frozen_subtree_hashes(version)?;
+        Ok((account_state_range_with_proof, ledger_frozen_subtree_hashes))
+    }
+
+    /// Persists a chunk of account states of the state snapshot at `version`. The chunks are
+    /// expected to come in the order of keys, starting from the smallest one, and have been
+    /// verified by the caller.
+    ///
+    /// This is used by the executor module when bootstrapping from a state snapshot.
+    pub fn save_account_state_chunk(
+        &self,
+        version: Version,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+    ) -> Result<()> {
+        let mut cs = ChangeSet::new();
+        self.state_store
+            .put_account_state_chunk(version, account_blobs, &mut cs)?;
+        self.commit(SealedChangeSet { batch: cs.batch })
+    }
+
+    /// Finishes bootstrapping from the state snapshot at the version of `ledger_info_with_sigs`,
+    /// after all the account states have been saved via
+    /// [`save_account_state_chunk`](LibraDB::save_account_state_chunk). `txn_info` is the
+    /// transaction info at that version, and `ledger_frozen_subtree_hashes` the frozen subtree
+    /// root hashes of the transaction accumulator, which are enough to append new transactions.
+    ///
+    /// This is used by the executor module when bootstrapping from a state snapshot.
+    pub fn finalize_state_snapshot(
+        &self,
+        txn_info: TransactionInfo,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Result<()> {
+        let version = ledger_info_with_sigs.ledger_info().version();
+        if let Some(x) = self.ledger_store.get_latest_ledger_info_option()? {
+            ensure!(
+                version > x.ledger_info().version(),
+                "State snapshot at version {} is not newer than the latest version {}.",
+                version,
+                x.ledger_info().version(),
+            );
+        }
+
+        let mut cs = ChangeSet::new();
+        let state_root_hash = self
+            .state_store
+            .finish_account_state_restore(version, &mut cs)?;
+        ensure!(
+            state_root_hash == txn_info.state_root_hash(),
+            "Restored state root hash {:x} doesn't match the one in transaction info {:x}.",
+            state_root_hash,
+            txn_info.state_root_hash(),
+        );
+        self.ledger_store.put_transaction_info_and_frozen_subtrees(
+            version,
+            &txn_info,
+            &ledger_frozen_subtree_hashes,
+            &mut cs,
+        )?;
+        self.ledger_store
+            .put_ledger_info(&ledger_info_with_sigs, &mut cs)?;
+        self.system_store
+            .reset_ledger_counters(version, &mut cs.batch)?;
+        self.commit(SealedChangeSet { batch: cs.batch })?;
+
+        OP_COUNTER.set("latest_transaction_version", version as usize);
+        self.notify_commit_subscribers(version);
+        Ok(())
+    }
+
+    /// Returns a stream of versions of committed ledger infos, so that readers can learn about new
+    /// data (e.g. new events) without polling. The first item is the version of the latest ledger
+    /// info at the time of the call if there is one, and the following ones are yielded as new
+    /// ledger infos are committed. The same version may be yielded twice if a commit races with
+    /// this call. The subscription ends when the receiver is dropped, or when the receiver falls
+    /// too far behind, in which case the stream ends and the reader needs to subscribe again.
+    /// Errors if there are too many subscriptions already.
+    pub fn subscribe_to_commits(&self) -> Result<mpsc::Receiver<Version>> {
+        let (mut sender, receiver) = mpsc::channel(COMMIT_SUBSCRIBER_BUFFER_SIZE);
+        // Hold the lock so that no commit notification is missed after reading the latest version.
+        let mut subscribers = self
+            .commit_subscribers
+            .lock()
+            .expect("Failed to lock commit subscribers.");
+        subscribers.retain(|sender| !sender.is_closed());
+        error_if_too_many_requested(subscribers.len() as u64 + 1, MAX_COMMIT_SUBSCRIBERS)?;
+        if let Some(x) = self.ledger_store.get_latest_ledger_info_option()? {
+            sender
+                .try_send(x.ledger_info().version())
+                .expect("The receiver must be alive.");
+        }
+        subscribers.push(sender);
+        Ok(receiver)
+    }
+
+    // ========================== Backup and Restore Internal APIs ================================
+    /// Gets up to `limit` ledger infos with versions equal to or greater than `start_version`, in
+    /// the order of their versions.
+    ///
+    /// This is used by the backup tool internally.
+    pub fn get_ledger_infos(
+        &self,
+        start_version: Version,
+        limit: u64,
+    ) -> Result<Vec<LedgerInfoWithSignatures<Ed25519Signature>>> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+        self.ledger_store.get_ledger_infos(start_version, limit)
+    }
+
+    /// Persists a chunk of transactions restored from a backup together with their transaction
+    /// infos and the events they emitted, without the account states they produced.
+    /// `first_version` must be the number of transactions already in the ledger, and the chunk is
+    /// expected to have been verified by the caller. Returns the root hash of the transaction
+    /// accumulator after the chunk is appended.
+    ///
+    /// This is used by the backup tool internally.
+    pub fn restore_transactions(
+        &self,
+        first_version: Version,
+        txns_and_infos: &[(SignedTransaction, TransactionInfo)],
+        events: &[Vec<ContractEvent>],
+    ) -> Result<HashValue> {
+        let num_existing_txns = self
+            .ledger_store
+            .get_latest_transaction_info_option()?
+            .map_or(0, |(version, _)| version + 1);
+        ensure!(
+            first_version == num_existing_txns,
+            "Transactions not applicable: first_version {}, number of existing transactions {}.",
+            first_version,
+            num_existing_txns,
+        );
+
+        ensure!(
+            events.len() == txns_and_infos.len(),
+            "Number of event lists ({}) doesn't match the number of transactions ({}).",
+            events.len(),
+            txns_and_infos.len(),
+        );
+
+        let mut cs = ChangeSet::new();
+        (first_version..)
+            .zip(txns_and_infos.iter().zip(events.iter()))
+            .map(|(ver, ((txn, txn_info), txn_events))| {
+                self.transaction_store.put_transaction(ver, txn, &mut cs)?;
+                let event_root_hash = self.event_store.put_events(ver, txn_events, &mut cs)?;
+                ensure!(
+                    event_root_hash == txn_info.event_root_hash(),
+                    "Events of the transaction at version {} don't match its transaction info.",
+                    ver,
+                );
+                Ok(())
+            })
+            .collect::<Result<()>>()?;
+        let txn_infos = txns_and_infos
+            .iter()
+            .map(|(_, txn_info)| txn_info.clone())
+            .collect::<Vec<_>>();
+        let new_root_hash =
+            self.ledger_store
+                .put_transaction_infos(first_version, &txn_infos, &mut cs)?;
+        self.commit(SealedChangeSet { batch: cs.batch })?;
+        Ok(new_root_hash)
+    }
+
+    /// Persists a ledger info restored from a backup, after checking that the transaction
+    /// accumulator root hash it carries matches the transactions in the ledger.
+    ///
+    /// This is used by the backup tool internally.
+    pub fn restore_ledger_info(
+        &self,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Result<()> {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let root_hash = self.ledger_store.get_root_hash(ledger_info.version())?;
+        ensure!(
+            root_hash == ledger_info.transaction_accumulator_hash(),
+            "Ledger info at version {} doesn't match the transaction accumulator. {:?} vs {:?}",
+            ledger_info.version(),
+            ledger_info.transaction_accumulator_hash(),
+            root_hash,
+        );
+
+        let mut cs = ChangeSet::new();
+        self.ledger_store
+            .put_ledger_info(ledger_info_with_sigs, &mut cs)?;
+        self.commit(SealedChangeSet { batch: cs.batch })
+    }
+
     // ================================== Private APIs ==================================
     /// Convert a `ChangeSet` to `SealedChangeSet`.
     ///
@@ -706,17 +1195,45 @@ impl LibraDB {
         Ok(())
     }
 
-    fn get_account_seq_num_by_version(
+    /// Notifies the commit subscribers of `version`. The subscribers that have gone away or fallen
+    /// too far behind are disconnected, so a slow reader can not make storage buffer without bound.
+    fn notify_commit_subscribers(&self, version: Version) {
+        let mut subscribers = self
+            .commit_subscribers
+            .lock()
+            .expect("Failed to lock commit subscribers.");
+        // Dropping the sender ends the stream once the receiver has consumed what is buffered.
+        *subscribers = subscribers
+            .drain(..)
+            .filter_map(|mut sender| sender.try_send(version).ok().map(|()| sender))
+            .collect();
+    }
+
+    /// Errors if the data at `version` is older than the versions kept in pruned mode, so it might
+    /// have been pruned already.
+    fn error_if_pruned(&self, data_type: &str, version: Version) -> Result<()> {
+        match &self.pruner {
+            Some(pruner) if version < pruner.least_readable_version() => {
+                Err(LibraDbError::Pruned(format!("{} at version {}", data_type, version)).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn get_event_with_proof(
         &self,
-        address: AccountAddress,
         version: Version,
-    ) -> Result<u64> {
-        let (account_state_blob, _proof) = self
-            .state_store
-            .get_account_state_with_proof_by_version(address, version)?;
-
-        // If an account does not exist, we treat it as if it has sequence number 0.
-        Ok(get_account_resource_or_default(&account_state_blob)?.sequence_number())
+        index: u64,
+        ledger_version: Version,
+    ) -> Result<EventWithProof> {
+        let (event, event_proof) = self
+            .event_store
+            .get_event_with_proof_by_version_and_index(version, index)?;
+        let (txn_info, txn_info_proof) = self
+            .ledger_store
+            .get_transaction_info_with_proof(version, ledger_version)?;
+        let proof = EventProof::new(txn_info_proof, txn_info, event_proof);
+        Ok(EventWithProof::new(version, index, event, proof))
     }
 
     fn get_transaction_with_proof(
@@ -725,6 +1242,7 @@ impl LibraDB {
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<SignedTransactionWithProof> {
+        self.error_if_pruned("Transaction", version)?;
         let proof = {
             let (txn_info, txn_info_accumulator_proof) = self
                 .ledger_store
@@ -11,7 +11,10 @@ use nextgen_crypto::ed25519::*;
 use proptest::prelude::*;
 use rusty_fork::{rusty_fork_id, rusty_fork_test, rusty_fork_test_name};
 use std::collections::HashMap;
-use types::{contract_event::ContractEvent, ledger_info::LedgerInfo};
+use types::{
+    contract_event::{ContractEvent, EventFilter},
+    ledger_info::LedgerInfo,
+};
 
 fn test_save_blocks_impl(
     input: Vec<(
@@ -109,6 +112,93 @@ fn test_sync_transactions_impl(
     Ok(())
 }
 
+fn test_restore_state_snapshot_impl(
+    input: Vec<(
+        Vec<TransactionToCommit>,
+        LedgerInfoWithSignatures<Ed25519Signature>,
+    )>,
+) -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let db = db_with_mock_genesis(&tmp_dir)?;
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            &txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )?;
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let (_, ledger_info_with_sigs) = input.last().unwrap();
+    let ledger_info = ledger_info_with_sigs.ledger_info();
+    let version = ledger_info.version();
+
+    // Restore the state at the latest version into another DB in chunks.
+    let restore_tmp_dir = tempfile::tempdir()?;
+    let restore_db = db_with_mock_genesis(&restore_tmp_dir)?;
+    let mut start_key = Some(HashValue::zero());
+    let mut txn_info_and_frozen_subtrees = None;
+    while let Some(key) = start_key {
+        let (account_state_range_with_proof, frozen_subtree_hashes) =
+            db.get_account_state_chunk(key, 3 /* limit */, version)?;
+        account_state_range_with_proof.verify(ledger_info, version, key, 3 /* limit */)?;
+        start_key = account_state_range_with_proof.next_start_key();
+        if start_key.is_none() {
+            txn_info_and_frozen_subtrees = Some((
+                account_state_range_with_proof
+                    .proof
+                    .transaction_info()
+                    .clone(),
+                frozen_subtree_hashes,
+            ));
+        }
+        restore_db
+            .save_account_state_chunk(version, account_state_range_with_proof.account_blobs)?;
+        // The progress is persisted, so the restoration can be resumed from the next chunk.
+        let progress = restore_db
+            .get_executor_startup_info()?
+            .unwrap()
+            .state_snapshot_restore;
+        if let Some(next_key) = start_key {
+            assert_eq!(
+                progress,
+                Some(StateSnapshotRestoreProgress { version, next_key })
+            );
+        }
+    }
+    let (txn_info, frozen_subtree_hashes) = txn_info_and_frozen_subtrees.unwrap();
+    restore_db.finalize_state_snapshot(
+        txn_info,
+        frozen_subtree_hashes,
+        ledger_info_with_sigs.clone(),
+    )?;
+    assert!(restore_db
+        .get_executor_startup_info()?
+        .unwrap()
+        .state_snapshot_restore
+        .is_none());
+
+    assert_eq!(
+        restore_db.get_executor_startup_info()?,
+        db.get_executor_startup_info()?
+    );
+    for (txns_to_commit, _) in &input {
+        for txn_to_commit in txns_to_commit {
+            for addr in txn_to_commit.account_states().keys() {
+                let account_state_with_proof =
+                    restore_db.get_account_state_with_proof(*addr, version, version)?;
+                assert_eq!(
+                    account_state_with_proof,
+                    db.get_account_state_with_proof(*addr, version, version)?
+                );
+                account_state_with_proof.verify(ledger_info, version, *addr)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn get_events_by_access_path(
     db: &LibraDB,
     ledger_info: &LedgerInfo,
@@ -292,6 +382,39 @@ fn verify_committed_transactions(
             db.get_transactions(cur_ver, 1, ledger_version, true /* fetch_events */)?;
         txn_list_with_proof.verify(ledger_info, Some(cur_ver))?;
 
+        // Fetch and verify the events yielded by the transaction, which ends the range if it
+        // yields any.
+        let event_list_with_proof = db.get_events_by_version_range(
+            cur_ver,
+            1, /* limit */
+            &EventFilter::default(),
+            ledger_version,
+        )?;
+        event_list_with_proof.verify(ledger_info, Some(cur_ver))?;
+        assert_eq!(&event_list_with_proof.events[0][..], txn_to_commit.events());
+        if !txn_to_commit.events().is_empty() {
+            assert_eq!(event_list_with_proof.events.len(), 1);
+        }
+
+        // Fetch and verify the transaction by its sender and sequence number.
+        let signed_txn = txn_to_commit.signed_txn();
+        let (txns_with_proof, proof_of_current_sequence_number) = db.get_txns_by_account(
+            signed_txn.sender(),
+            signed_txn.sequence_number(),
+            1, /* limit */
+            ledger_version,
+            false, /* fetch_events */
+        )?;
+        assert!(proof_of_current_sequence_number.is_none());
+        assert_eq!(txns_with_proof.len(), 1);
+        assert_eq!(txns_with_proof[0].version, cur_ver);
+        txns_with_proof[0].verify(
+            ledger_info,
+            cur_ver,
+            signed_txn.sender(),
+            signed_txn.sequence_number(),
+        )?;
+
         // Fetch and verify account states.
         for (addr, expected_blob) in txn_to_commit.account_states() {
             let account_state_with_proof =
@@ -328,6 +451,11 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit()) {
         test_sync_transactions_impl(input).unwrap();
     }
+
+    #[test]
+    fn test_restore_state_snapshot(input in arb_blocks_to_commit()) {
+        test_restore_state_snapshot_impl(input).unwrap();
+    }
 }
 
 #[test]
@@ -357,6 +485,67 @@ fn test_bootstrap() {
     );
 }
 
+#[test]
+fn test_subscribe_to_commits() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let db = LibraDB::new(&tmp_dir);
+    let mut receiver = db.subscribe_to_commits().unwrap();
+    let dropped_receiver = db.subscribe_to_commits().unwrap();
+    drop(dropped_receiver);
+
+    let genesis_ledger_info_with_sigs = GENESIS_INFO.1.clone();
+    let genesis_txn = GENESIS_INFO.2.clone();
+    db.save_transactions(
+        &[genesis_txn],
+        0, /* first_version */
+        &Some(genesis_ledger_info_with_sigs),
+    )
+    .unwrap();
+
+    assert_eq!(receiver.try_next().unwrap(), Some(0));
+    assert!(receiver.try_next().is_err());
+    // The subscriber that has gone away is dropped.
+    assert_eq!(db.commit_subscribers.lock().unwrap().len(), 1);
+
+    // A new subscriber gets the latest version first.
+    let mut receiver = db.subscribe_to_commits().unwrap();
+    assert_eq!(receiver.try_next().unwrap(), Some(0));
+    assert!(receiver.try_next().is_err());
+}
+
+#[test]
+fn test_subscribe_to_commits_lagging() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let db = LibraDB::new(&tmp_dir);
+    let mut receiver = db.subscribe_to_commits().unwrap();
+
+    // A subscriber that doesn't keep up is disconnected after what is buffered.
+    for version in 0..=COMMIT_SUBSCRIBER_BUFFER_SIZE as u64 * 2 {
+        db.notify_commit_subscribers(version);
+    }
+    assert!(db.commit_subscribers.lock().unwrap().is_empty());
+    let mut num_received = 0;
+    while let Some(version) = receiver.try_next().unwrap() {
+        assert_eq!(version, num_received);
+        num_received += 1;
+    }
+    assert!(num_received > 0);
+}
+
+#[test]
+fn test_subscribe_to_commits_too_many() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let db = LibraDB::new(&tmp_dir);
+    let mut receivers = (0..MAX_COMMIT_SUBSCRIBERS)
+        .map(|_| db.subscribe_to_commits().unwrap())
+        .collect::<Vec<_>>();
+    assert!(db.subscribe_to_commits().is_err());
+
+    // The subscription that has gone away makes room for a new one.
+    receivers.pop();
+    assert!(db.subscribe_to_commits().is_ok());
+}
+
 rusty_fork_test! {
 #[test]
 fn test_committed_txns_counter() {
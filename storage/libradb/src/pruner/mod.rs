@@ -3,11 +3,19 @@
 
 //! This module provides `Pruner` which manages a thread pruning old data in the background and is
 //! meant to be triggered by other threads as they commit new data to the DB.
+//!
+//! Pruned are the state Merkle tree nodes retired before the least readable version, and the
+//! transactions before it together with their events, event accumulators and index entries. The
+//! numbers of pruned items are handed over to the `LibraDB` to be committed as ledger counters.
 
 use crate::{
+    ledger_counters::{LedgerCounter, LedgerCounterBumps},
     schema::{
+        event::EventSchema, event_accumulator::EventAccumulatorSchema,
+        event_by_access_path::EventByAccessPathSchema,
         jellyfish_merkle_node::JellyfishMerkleNodeSchema,
-        retired_state_record::StaleNodeIndexSchema,
+        retired_state_record::StaleNodeIndexSchema, signed_transaction::SignedTransactionSchema,
+        transaction_by_account::TransactionByAccountSchema,
     },
     OP_COUNTER,
 };
@@ -21,7 +29,7 @@ use std::{
     },
     thread::JoinHandle,
 };
-use types::transaction::Version;
+use types::{proof::position::Position, transaction::Version};
 
 use failure::_core::sync::atomic::Ordering;
 use std::sync::atomic::AtomicU64;
@@ -44,6 +52,11 @@ pub(crate) struct Pruner {
     worker_thread: Option<JoinHandle<()>>,
     /// The sender side of the channel talking to the worker thread.
     command_sender: Mutex<Sender<Command>>,
+    /// The least readable version as of the latest `wake()`. Versions before it might have been
+    /// pruned already.
+    least_readable_version: AtomicU64,
+    /// The numbers of items pruned by the worker thread that are not reported yet.
+    counter_bumps: Arc<Mutex<LedgerCounterBumps>>,
     /// (For tests) A way for the worker thread to inform the `Pruner` the pruning progress. If it
     /// sets this atomic value to `V`, all versions before `V` can no longer be accessed.
     #[allow(dead_code)]
@@ -56,24 +69,54 @@ impl Pruner {
         let (command_sender, command_receiver) = channel();
         let worker_progress = Arc::new(AtomicU64::new(0));
         let worker_progress_clone = Arc::clone(&worker_progress);
+        let counter_bumps = Arc::new(Mutex::new(LedgerCounterBumps::new()));
+        let counter_bumps_clone = Arc::clone(&counter_bumps);
 
         let worker_thread = std::thread::Builder::new()
             .name("libradb_pruner".into())
-            .spawn(move || Worker::new(db, command_receiver, worker_progress_clone).work_loop())
+            .spawn(move || {
+                Worker::new(
+                    db,
+                    command_receiver,
+                    worker_progress_clone,
+                    counter_bumps_clone,
+                )
+                .work_loop()
+            })
             .expect("Creating pruner thread should succeed.");
 
         Self {
             num_historical_versions_to_keep,
             worker_thread: Some(worker_thread),
             command_sender: Mutex::new(command_sender),
+            least_readable_version: AtomicU64::new(0),
+            counter_bumps,
             worker_progress,
         }
     }
 
+    /// Returns the least readable version as of the latest `wake()`.
+    pub fn least_readable_version(&self) -> Version {
+        self.least_readable_version.load(Ordering::Relaxed)
+    }
+
+    /// Takes the numbers of items pruned since the last call, to be reported as ledger counters.
+    pub fn take_counter_bumps(&self) -> LedgerCounterBumps {
+        std::mem::replace(
+            &mut *self
+                .counter_bumps
+                .lock()
+                .expect("counter_bumps of pruner should lock."),
+            LedgerCounterBumps::new(),
+        )
+    }
+
     /// Sends pruning command to the worker thread when necessary.
     pub fn wake(&self, latest_version: Version) {
         if latest_version > self.num_historical_versions_to_keep {
             let least_readable_version = latest_version - self.num_historical_versions_to_keep;
+            self.least_readable_version
+                .store(least_readable_version, Ordering::Relaxed);
             self.command_sender
                 .lock()
                 .expect("command_sender to pruner thread should lock.")
@@ -131,9 +174,13 @@ struct Worker {
     db: Arc<DB>,
     command_receiver: Receiver<Command>,
     least_readable_version: Version,
+    /// The first version of which the transaction has not been pruned.
+    txn_progress: Version,
     /// (For tests) a way for the worker thread to inform the `Pruner` the pruning progress. If we
     /// set this atomic value to `V`, all versions before `V` can no longer be accessed.
     progress: Arc<AtomicU64>,
+    /// Where the numbers of items pruned are handed over to the `Pruner`.
+    counter_bumps: Arc<Mutex<LedgerCounterBumps>>,
     /// indicates if there's NOT any pending work to do currently, to hint
     /// `Self::receive_commands()` to `recv()` blocking-ly.
     blocking_recv: bool,
@@ -142,12 +189,19 @@ struct Worker {
 impl Worker {
     const BATCH_SIZE: usize = 1024;
 
-    fn new(db: Arc<DB>, command_receiver: Receiver<Command>, progress: Arc<AtomicU64>) -> Self {
+    fn new(
+        db: Arc<DB>,
+        command_receiver: Receiver<Command>,
+        progress: Arc<AtomicU64>,
+        counter_bumps: Arc<Mutex<LedgerCounterBumps>>,
+    ) -> Self {
         Self {
             db,
             command_receiver,
             progress,
+            counter_bumps,
             least_readable_version: 0,
+            txn_progress: 0,
             blocking_recv: true,
         }
     }
@@ -156,13 +210,29 @@ impl Worker {
         while self.receive_commands() {
             // Process a reasonably small batch of work before trying to receive commands again,
             // in case `Command::Quit` is received (that's when we should quit.)
+            let mut counter_bumps = LedgerCounterBumps::new();
             match prune_state(
                 Arc::clone(&self.db),
                 self.progress.load(Ordering::Relaxed),
                 self.least_readable_version,
                 Self::BATCH_SIZE,
-            ) {
-                Ok((num_pruned, last_seen_version)) => {
+            )
+            .and_then(|(num_pruned, last_seen_version)| {
+                counter_bumps.bump(LedgerCounter::StateNodesPruned, num_pruned);
+                let (num_txns_pruned, txn_progress) = prune_transactions(
+                    Arc::clone(&self.db),
+                    self.txn_progress,
+                    self.least_readable_version,
+                    Self::BATCH_SIZE,
+                    &mut counter_bumps,
+                )?;
+                Ok((
+                    std::cmp::max(num_pruned, num_txns_pruned),
+                    last_seen_version,
+                    txn_progress,
+                ))
+            }) {
+                Ok((num_pruned, last_seen_version, txn_progress)) => {
                     // Make next recv() blocking if all done.
                     self.blocking_recv = num_pruned < Self::BATCH_SIZE;
 
@@ -172,6 +242,11 @@ impl Worker {
                         "pruner.least_readable_state_version",
                         last_seen_version as usize,
                     );
+                    self.txn_progress = txn_progress;
+                    OP_COUNTER.set(
+                        "pruner.least_readable_transaction_version",
+                        txn_progress as usize,
+                    );
                 }
                 Err(e) => {
                     crit!("Error purging db records. {:?}", e);
@@ -179,6 +254,10 @@ impl Worker {
                     self.blocking_recv = true;
                 }
             }
+            self.counter_bumps
+                .lock()
+                .expect("counter_bumps of pruner should lock.")
+                .merge(counter_bumps);
         }
     }
 
@@ -259,5 +338,82 @@ pub fn prune_state(
     Ok((num_pruned, last_seen_version))
 }
 
+/// Prunes the transactions at versions from `first_version` to before `least_readable_version`,
+/// as many as `limit`, together with their events, event accumulators and index entries. Returns
+/// the number of transactions pruned and the version of the first one left, and adds the numbers
+/// of items pruned to `counter_bumps`.
+pub fn prune_transactions(
+    db: Arc<DB>,
+    first_version: Version,
+    least_readable_version: Version,
+    limit: usize,
+    counter_bumps: &mut LedgerCounterBumps,
+) -> Result<(usize, Version)> {
+    let mut batch = SchemaBatch::new();
+    let mut num_pruned = 0;
+    let mut num_events_pruned = 0;
+    let mut iter = db.iter::<SignedTransactionSchema>(ReadOptions::default())?;
+    iter.seek(&first_version)?;
+
+    // Collect records to prune, as many as `limit` transactions.
+    let mut iter = iter.take(limit);
+    let mut next_version = first_version;
+    while let Some((version, txn)) = iter.next().transpose()? {
+        if version >= least_readable_version {
+            break;
+        }
+        next_version = version + 1;
+        // A write set transaction, like the genesis one, doesn't bump the sequence number of its
+        // sender, so its entry might have been overwritten by a later transaction.
+        let key = (txn.sender(), txn.sequence_number());
+        if db.get::<TransactionByAccountSchema>(&key)? == Some(version) {
+            batch.delete::<TransactionByAccountSchema>(&key)?;
+        }
+        batch.delete::<SignedTransactionSchema>(&version)?;
+        num_events_pruned += prune_events(&db, version, &mut batch)?;
+        num_pruned += 1;
+    }
+
+    // Persist.
+    if num_pruned > 0 {
+        db.write_schemas(batch)?;
+        counter_bumps
+            .bump(LedgerCounter::TransactionsPruned, num_pruned)
+            .bump(LedgerCounter::EventsPruned, num_events_pruned);
+    }
+
+    Ok((num_pruned, next_version))
+}
+
+/// Adds to `batch` the deletion of the events yielded by the transaction at `version`, their index
+/// entries and their accumulator. Returns the number of events.
+fn prune_events(db: &DB, version: Version, batch: &mut SchemaBatch) -> Result<usize> {
+    let mut num_events = 0;
+    let mut iter = db.iter::<EventSchema>(ReadOptions::default())?;
+    iter.seek(&version)?;
+    while let Some(((ver, index), event)) = iter.next().transpose()? {
+        if ver != version {
+            break;
+        }
+        batch.delete::<EventByAccessPathSchema>(&(
+            event.access_path().clone(),
+            event.sequence_number(),
+        ))?;
+        batch.delete::<EventSchema>(&(ver, index))?;
+        num_events += 1;
+    }
+
+    let mut iter = db.iter::<EventAccumulatorSchema>(ReadOptions::default())?;
+    iter.seek(&(version, Position::from_inorder_index(0)))?;
+    while let Some(((ver, position), _)) = iter.next().transpose()? {
+        if ver != version {
+            break;
+        }
+        batch.delete::<EventAccumulatorSchema>(&(ver, position))?;
+    }
+
+    Ok(num_events)
+}
+
 #[cfg(test)]
 mod test;
@@ -2,13 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::{change_set::ChangeSet, state_store::StateStore, LibraDB};
+use crate::{
+    change_set::ChangeSet, event_store::EventStore, state_store::StateStore,
+    transaction_store::TransactionStore, LibraDB,
+};
 use crypto::HashValue;
+use proptest::{collection::vec, prelude::*};
 use std::collections::HashMap;
 use tempfile::tempdir;
 use types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
     account_state_blob::AccountStateBlob,
+    transaction::TransactionToCommit,
 };
 
 fn put_account_state_set(
@@ -142,6 +147,7 @@ fn test_worker_quit_eagerly() {
             Arc::clone(&db),
             command_receiver,
             Arc::new(AtomicU64::new(0)), /* progress */
+            Arc::new(Mutex::new(LedgerCounterBumps::new())), /* counter_bumps */
         );
         command_sender
             .send(Command::Prune {
@@ -161,3 +167,84 @@ fn test_worker_quit_eagerly() {
         verify_state_in_store(state_store, address, Some(&value2), 2);
     }
 }
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_prune_transactions(
+        txns_to_commit in vec(any::<TransactionToCommit>(), 1..10),
+        least_readable_version in 0u64..10,
+        limit in 1usize..10,
+    ) {
+        let tmp_dir = tempdir().unwrap();
+        let db = LibraDB::new(&tmp_dir).db;
+        let transaction_store = TransactionStore::new(Arc::clone(&db));
+        let event_store = EventStore::new(Arc::clone(&db));
+        let mut cs = ChangeSet::new();
+        for (i, txn_to_commit) in txns_to_commit.iter().enumerate() {
+            transaction_store
+                .put_transaction(i as u64, txn_to_commit.signed_txn(), &mut cs)
+                .unwrap();
+            event_store
+                .put_events(i as u64, txn_to_commit.events(), &mut cs)
+                .unwrap();
+        }
+        db.write_schemas(cs.batch).unwrap();
+
+        let num_txns = txns_to_commit.len() as u64;
+        let expected_num_pruned =
+            std::cmp::min(std::cmp::min(least_readable_version, num_txns), limit as u64);
+        let mut counter_bumps = LedgerCounterBumps::new();
+        prop_assert_eq!(
+            prune_transactions(
+                Arc::clone(&db),
+                0, /* first_version */
+                least_readable_version,
+                limit,
+                &mut counter_bumps,
+            )
+            .unwrap(),
+            (expected_num_pruned as usize, expected_num_pruned)
+        );
+        prop_assert_eq!(
+            counter_bumps.get(LedgerCounter::TransactionsPruned),
+            expected_num_pruned as usize
+        );
+        prop_assert_eq!(
+            counter_bumps.get(LedgerCounter::EventsPruned),
+            txns_to_commit[..expected_num_pruned as usize]
+                .iter()
+                .map(|t| t.events().len())
+                .sum::<usize>()
+        );
+
+        for (i, txn_to_commit) in txns_to_commit.iter().enumerate() {
+            let version = i as u64;
+            let txn = txn_to_commit.signed_txn();
+            let versions = transaction_store
+                .lookup_transactions_by_account(
+                    txn.sender(),
+                    txn.sequence_number(),
+                    1, /* limit */
+                    num_txns - 1,
+                )
+                .unwrap();
+            let events = event_store.get_events_by_version(version).unwrap();
+            if version < expected_num_pruned {
+                prop_assert!(versions.is_empty());
+                prop_assert!(transaction_store.get_transaction(version).is_err());
+                prop_assert!(events.is_empty());
+            } else {
+                prop_assert_eq!(versions, vec![version]);
+                prop_assert_eq!(&transaction_store.get_transaction(version).unwrap(), txn);
+                prop_assert_eq!(&events[..], txn_to_commit.events());
+                if !events.is_empty() {
+                    prop_assert!(event_store
+                        .get_event_with_proof_by_version_and_index(version, 0)
+                        .is_ok());
+                }
+            }
+        }
+    }
+}
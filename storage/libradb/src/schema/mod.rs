@@ -14,7 +14,9 @@ pub(crate) mod ledger_counters;
 pub(crate) mod ledger_info;
 pub(crate) mod retired_state_record;
 pub(crate) mod signed_transaction;
+pub(crate) mod state_snapshot_restore;
 pub(crate) mod transaction_accumulator;
+pub(crate) mod transaction_by_account;
 pub(crate) mod transaction_info;
 pub(crate) mod validator;
 
@@ -28,7 +30,9 @@ pub(super) const JELLYFISH_MERKLE_NODE_CF_NAME: ColumnFamilyName = "jellyfish_me
 pub(super) const LEDGER_COUNTERS_CF_NAME: ColumnFamilyName = "ledger_counters";
 pub(super) const STALE_NODE_INDEX_CF_NAME: ColumnFamilyName = "stale_node_index";
 pub(super) const SIGNED_TRANSACTION_CF_NAME: ColumnFamilyName = "signed_transaction";
+pub(super) const STATE_SNAPSHOT_RESTORE_CF_NAME: ColumnFamilyName = "state_snapshot_restore";
 pub(super) const TRANSACTION_ACCUMULATOR_CF_NAME: ColumnFamilyName = "transaction_accumulator";
+pub(super) const TRANSACTION_BY_ACCOUNT_CF_NAME: ColumnFamilyName = "transaction_by_account";
 pub(super) const TRANSACTION_INFO_CF_NAME: ColumnFamilyName = "transaction_info";
 pub(super) const VALIDATOR_CF_NAME: ColumnFamilyName = "validator";
 
@@ -0,0 +1,57 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the progress of restoring the state Merkle tree
+//! from a state snapshot, so the restoration can be resumed after a restart. At most one snapshot
+//! is being restored at a time.
+//!
+//! ```text
+//! |<--key-->|<-----value----->|
+//! | version | restore progress |
+//! ```
+//!
+//! `Version` is serialized in big endian so that records in RocksDB will be in order of it's
+//! numeric value.
+
+use super::STATE_SNAPSHOT_RESTORE_CF_NAME;
+use crate::schema::ensure_slice_len_eq;
+use byteorder::{BigEndian, ReadBytesExt};
+use failure::prelude::*;
+use jellyfish_merkle::restore::JellyfishMerkleRestore;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+use types::transaction::Version;
+
+define_schema!(
+    StateSnapshotRestoreSchema,
+    Version,
+    JellyfishMerkleRestore,
+    STATE_SNAPSHOT_RESTORE_CF_NAME
+);
+
+impl KeyCodec<StateSnapshotRestoreSchema> for Version {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Version>())?;
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
+impl ValueCodec<StateSnapshotRestoreSchema> for JellyfishMerkleRestore {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+#[cfg(test)]
+mod test;
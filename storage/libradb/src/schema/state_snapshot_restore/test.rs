@@ -0,0 +1,33 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crypto::HashValue;
+use types::account_state_blob::AccountStateBlob;
+
+#[test]
+fn test_encode_decode() {
+    let mut restore = JellyfishMerkleRestore::new(1);
+    restore
+        .add_chunk(vec![
+            (HashValue::zero(), AccountStateBlob::from(vec![1])),
+            (HashValue::random(), AccountStateBlob::from(vec![2])),
+        ])
+        .unwrap();
+
+    let key = 1;
+    let encoded_key = <Version as KeyCodec<StateSnapshotRestoreSchema>>::encode_key(&key).unwrap();
+    assert_eq!(
+        <Version as KeyCodec<StateSnapshotRestoreSchema>>::decode_key(&encoded_key).unwrap(),
+        key
+    );
+
+    let encoded_value = restore.encode_value().unwrap();
+    let decoded = <JellyfishMerkleRestore as ValueCodec<StateSnapshotRestoreSchema>>::decode_value(
+        &encoded_value,
+    )
+    .unwrap();
+    assert_eq!(decoded.version(), restore.version());
+    assert_eq!(decoded.previous_key(), restore.previous_key());
+    assert_eq!(decoded.encode_value().unwrap(), encoded_value);
+}
@@ -0,0 +1,69 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an index via which the version of a committed
+//! transaction can be found by the <sender, sequence_number> tuple of the transaction.
+//!
+//! ```text
+//! |<-------key------->|<-value->|
+//! | sender | seq_num  | txn_ver |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, TRANSACTION_BY_ACCOUNT_CF_NAME};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use core::convert::TryFrom;
+use failure::prelude::*;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::{io::Write, mem::size_of};
+use types::{
+    account_address::{AccountAddress, ADDRESS_LENGTH},
+    transaction::Version,
+};
+
+define_schema!(
+    TransactionByAccountSchema,
+    Key,
+    Version,
+    TRANSACTION_BY_ACCOUNT_CF_NAME
+);
+
+type SeqNum = u64;
+type Key = (AccountAddress, SeqNum);
+
+impl KeyCodec<TransactionByAccountSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref account_address, seq_num) = *self;
+
+        let mut encoded = Vec::with_capacity(ADDRESS_LENGTH + size_of::<SeqNum>());
+        encoded.write_all(account_address.as_ref())?;
+        encoded.write_u64::<BigEndian>(seq_num)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, ADDRESS_LENGTH + size_of::<SeqNum>())?;
+
+        let account_address = AccountAddress::try_from(&data[..ADDRESS_LENGTH])?;
+        let seq_num = (&data[ADDRESS_LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((account_address, seq_num))
+    }
+}
+
+impl ValueCodec<TransactionByAccountSchema> for Version {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
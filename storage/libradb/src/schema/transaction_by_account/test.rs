@@ -0,0 +1,17 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::schema::assert_encode_decode;
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        account_address in any::<AccountAddress>(),
+        seq_num in any::<u64>(),
+        version in any::<Version>(),
+    ) {
+        assert_encode_decode::<TransactionByAccountSchema>(&(account_address, seq_num), &version);
+    }
+}
@@ -12,19 +12,24 @@ use crate::{
     schema::{
         jellyfish_merkle_node::JellyfishMerkleNodeSchema,
         retired_state_record::StaleNodeIndexSchema,
+        state_snapshot_restore::StateSnapshotRestoreSchema,
     },
 };
 use crypto::{hash::CryptoHash, HashValue};
 use failure::prelude::*;
 use jellyfish_merkle::{
+    iterator::JellyfishMerkleIterator,
     node_type::{Node, NodeKey},
-    JellyfishMerkleTree, TreeReader,
+    restore::JellyfishMerkleRestore,
+    JellyfishMerkleTree, TreeReader, TreeUpdateBatch,
 };
-use schemadb::DB;
+use schemadb::{ReadOptions, DB};
 use std::{collections::HashMap, sync::Arc};
 use types::{
-    account_address::AccountAddress, account_state_blob::AccountStateBlob,
-    proof::SparseMerkleProof, transaction::Version,
+    account_address::AccountAddress,
+    account_state_blob::AccountStateBlob,
+    proof::{SparseMerkleProof, SparseMerkleRangeProof},
+    transaction::Version,
 };
 
 pub(crate) struct StateStore {
@@ -47,6 +52,29 @@ impl StateStore {
         Ok((blob, proof))
     }
 
+    /// Get up to `limit` account state blobs with keys equal to or greater than `start_key` in the
+    /// state Merkle tree at `version`, sorted by key, together with a range proof for them.
+    pub fn get_account_states_in_range_by_version(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        // An empty range proves that there is no account after `start_key`, which can only be
+        // the case if the iteration is not cut short.
+        ensure!(limit > 0, "At least one account state should be requested.");
+        let account_blobs = JellyfishMerkleIterator::new(self, version, start_key)?
+            .take(limit as usize)
+            .collect::<Result<Vec<_>>>()?;
+
+        let proof = JellyfishMerkleTree::new(self).get_range_proof(
+            start_key,
+            account_blobs.last().map(|(key, _)| *key),
+            version,
+        )?;
+        Ok((account_blobs, proof))
+    }
+
     /// Put the results generated by `account_state_sets` to `batch` and return the result root
     /// hashes for each write set.
     pub fn put_account_state_sets(
@@ -68,6 +96,90 @@ impl StateStore {
         let (new_root_hash_vec, tree_update_batch) =
             JellyfishMerkleTree::new(self).put_blob_sets(blob_sets, first_version)?;
 
+        Self::put_tree_update_batch(tree_update_batch, cs)?;
+
+        Ok(new_root_hash_vec)
+    }
+
+    /// Put a chunk of account state blobs of the state snapshot at `version` to `cs`, together
+    /// with the progress of the restoration. The keys in `account_blobs` must be sorted and greater
+    /// than those in the previous chunk. A chunk for a different version than the ongoing
+    /// restoration, or after the largest possible key, starts over, and the nodes written by the
+    /// abandoned restoration are deleted.
+    pub fn put_account_state_chunk(
+        &self,
+        version: Version,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        let mut restore = match self.get_account_state_restore()? {
+            Some(restore) if restore.version() == version && restore.next_key().is_some() => {
+                restore
+            }
+            Some(restore) => {
+                self.delete_nodes_at_version(restore.version(), cs)?;
+                cs.batch
+                    .delete::<StateSnapshotRestoreSchema>(&restore.version())?;
+                JellyfishMerkleRestore::new(version)
+            }
+            None => JellyfishMerkleRestore::new(version),
+        };
+        let tree_update_batch = restore.add_chunk(account_blobs)?;
+        Self::put_tree_update_batch(tree_update_batch, cs)?;
+        cs.batch
+            .put::<StateSnapshotRestoreSchema>(&version, &restore)
+    }
+
+    /// Finish restoring the state snapshot at `version` and put the remaining nodes to `cs`.
+    /// Returns the root hash of the restored state Merkle tree.
+    pub fn finish_account_state_restore(
+        &self,
+        version: Version,
+        cs: &mut ChangeSet,
+    ) -> Result<HashValue> {
+        let restore = self
+            .get_account_state_restore()?
+            .ok_or_else(|| format_err!("No state snapshot is being restored."))?;
+        ensure!(
+            restore.version() == version,
+            "State snapshot being restored is at version {}, not {}.",
+            restore.version(),
+            version
+        );
+        let (root_hash, tree_update_batch) = restore.finish()?;
+        Self::put_tree_update_batch(tree_update_batch, cs)?;
+        cs.batch.delete::<StateSnapshotRestoreSchema>(&version)?;
+        Ok(root_hash)
+    }
+
+    /// Returns the progress of restoring the state snapshot, if one is being restored.
+    pub fn get_account_state_restore(&self) -> Result<Option<JellyfishMerkleRestore>> {
+        let mut iter = self
+            .db
+            .iter::<StateSnapshotRestoreSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        Ok(iter.next().transpose()?.map(|(_version, restore)| restore))
+    }
+
+    /// Deletes all nodes at `version`. This is only used to clean up after an abandoned
+    /// restoration, whose version is above the latest committed one, so no committed state tree
+    /// refers to these nodes.
+    fn delete_nodes_at_version(&self, version: Version, cs: &mut ChangeSet) -> Result<()> {
+        let mut iter = self
+            .db
+            .iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())?;
+        iter.seek(&NodeKey::new_empty_path(version))?;
+        for res in iter {
+            let (node_key, _node) = res?;
+            if node_key.version() != version {
+                break;
+            }
+            cs.batch.delete::<JellyfishMerkleNodeSchema>(&node_key)?;
+        }
+        Ok(())
+    }
+
+    fn put_tree_update_batch(tree_update_batch: TreeUpdateBatch, cs: &mut ChangeSet) -> Result<()> {
         cs.counter_bumps.bump(
             LedgerCounter::StateNodesCreated,
             tree_update_batch.node_batch.len(),
@@ -96,7 +208,7 @@ impl StateStore {
             .map(|row| cs.batch.put::<StaleNodeIndexSchema>(row, &()))
             .collect::<Result<Vec<()>>>()?;
 
-        Ok(new_root_hash_vec)
+        Ok(())
     }
 }
 
@@ -8,7 +8,7 @@ use tempfile::tempdir;
 use types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
     account_state_blob::AccountStateBlob,
-    proof::verify_sparse_merkle_element,
+    proof::{verify_sparse_merkle_element, verify_sparse_merkle_range},
 };
 
 fn put_account_state_set(
@@ -74,6 +74,25 @@ fn verify_state_in_store(
     verify_sparse_merkle_element(root, address.hash(), &value, &proof).unwrap();
 }
 
+fn verify_states_in_range_in_store(
+    store: &StateStore,
+    start_key: HashValue,
+    limit: u64,
+    expected_blobs: &[(HashValue, AccountStateBlob)],
+    version: Version,
+    root: HashValue,
+) {
+    let (account_blobs, proof) = store
+        .get_account_states_in_range_by_version(start_key, limit, version)
+        .unwrap();
+    assert_eq!(account_blobs, expected_blobs);
+    let leaves = account_blobs
+        .iter()
+        .map(|(key, blob)| (*key, blob.hash()))
+        .collect::<Vec<_>>();
+    verify_sparse_merkle_range(root, start_key, &leaves, &proof).unwrap();
+}
+
 #[test]
 fn test_empty_store() {
     let tmp_dir = tempdir().unwrap();
@@ -223,3 +242,113 @@ fn test_retired_records() {
         verify_state_in_store(store, address3, Some(&value3_update), 2, root2);
     }
 }
+
+#[test]
+fn test_get_account_states_in_range() {
+    let tmp_dir = tempdir().unwrap();
+    let db = LibraDB::new(&tmp_dir);
+    let store = &db.state_store;
+
+    let account_state_set = (0..20u8)
+        .map(|i| {
+            (
+                AccountAddress::new([i; ADDRESS_LENGTH]),
+                AccountStateBlob::from(vec![i]),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let mut sorted_blobs = account_state_set
+        .iter()
+        .map(|(address, blob)| (address.hash(), blob.clone()))
+        .collect::<Vec<_>>();
+    sorted_blobs.sort_by_key(|(key, _)| *key);
+
+    let mut cs = ChangeSet::new();
+    let root = store
+        .put_account_state_sets(vec![account_state_set], 0 /* first_version */, &mut cs)
+        .unwrap()[0];
+    store.db.write_schemas(cs.batch).unwrap();
+
+    // All accounts in one page.
+    verify_states_in_range_in_store(store, HashValue::zero(), 100, &sorted_blobs, 0, root);
+    // Pages starting from existing keys.
+    verify_states_in_range_in_store(store, sorted_blobs[0].0, 5, &sorted_blobs[0..5], 0, root);
+    verify_states_in_range_in_store(store, sorted_blobs[7].0, 5, &sorted_blobs[7..12], 0, root);
+    verify_states_in_range_in_store(store, sorted_blobs[17].0, 5, &sorted_blobs[17..], 0, root);
+    // A page starting from a key that doesn't exist.
+    let mut start_key_bytes = sorted_blobs[9].0.to_vec();
+    *start_key_bytes.last_mut().unwrap() ^= 1;
+    let start_key = HashValue::from_slice(&start_key_bytes).unwrap();
+    let first_index = if start_key < sorted_blobs[9].0 { 9 } else { 10 };
+    verify_states_in_range_in_store(
+        store,
+        start_key,
+        3,
+        &sorted_blobs[first_index..first_index + 3],
+        0,
+        root,
+    );
+    // Nothing after the last key.
+    verify_states_in_range_in_store(store, HashValue::new([0xff; 32]), 3, &[], 0, root);
+    // A range proof can't be given if nothing is requested.
+    assert!(store
+        .get_account_states_in_range_by_version(HashValue::zero(), 0, 0)
+        .is_err());
+}
+
+fn num_nodes_at_version(store: &StateStore, version: Version) -> usize {
+    let mut iter = store
+        .db
+        .iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())
+        .unwrap();
+    iter.seek(&NodeKey::new_empty_path(version)).unwrap();
+    iter.map(|res| res.unwrap().0)
+        .take_while(|node_key| node_key.version() == version)
+        .count()
+}
+
+#[test]
+fn test_restore_switches_version() {
+    let tmp_dir = tempdir().unwrap();
+    let db = LibraDB::new(&tmp_dir);
+    let store = &db.state_store;
+
+    let mut sorted_blobs = (0..=255u8)
+        .map(|i| {
+            (
+                AccountAddress::new([i; ADDRESS_LENGTH]).hash(),
+                AccountStateBlob::from(vec![i]),
+            )
+        })
+        .collect::<Vec<_>>();
+    sorted_blobs.sort_by_key(|(key, _)| *key);
+
+    // Restore most of the snapshot at version 5.
+    let mut cs = ChangeSet::new();
+    store
+        .put_account_state_chunk(5, sorted_blobs[..200].to_vec(), &mut cs)
+        .unwrap();
+    store.db.write_schemas(cs.batch).unwrap();
+    assert!(num_nodes_at_version(store, 5) > 0);
+
+    // Switching to version 7 cleans up everything written for version 5.
+    let mut cs = ChangeSet::new();
+    store
+        .put_account_state_chunk(7, sorted_blobs.clone(), &mut cs)
+        .unwrap();
+    store.db.write_schemas(cs.batch).unwrap();
+    assert_eq!(num_nodes_at_version(store, 5), 0);
+    assert_eq!(
+        store
+            .get_account_state_restore()
+            .unwrap()
+            .unwrap()
+            .version(),
+        7
+    );
+
+    let mut cs = ChangeSet::new();
+    let root = store.finish_account_state_restore(7, &mut cs).unwrap();
+    store.db.write_schemas(cs.batch).unwrap();
+    verify_states_in_range_in_store(store, HashValue::zero(), 1000, &sorted_blobs, 7, root);
+}
@@ -54,6 +54,12 @@ impl SystemStore {
 
         Ok(counters)
     }
+
+    /// Start ledger counters over from zeros at `version`, e.g. when the ledger is restored from a
+    /// state snapshot and the history before `version` is not available.
+    pub fn reset_ledger_counters(&self, version: Version, batch: &mut SchemaBatch) -> Result<()> {
+        batch.put::<LedgerCountersSchema>(&version, &LedgerCounters::new())
+    }
 }
 
 #[cfg(test)]
@@ -3,12 +3,19 @@
 
 //! This file defines transaction store APIs that are related to committed signed transactions.
 
-use super::schema::signed_transaction::*;
+use super::schema::{signed_transaction::*, transaction_by_account::*};
 use crate::{change_set::ChangeSet, errors::LibraDbError};
 use failure::prelude::*;
-use schemadb::DB;
-use std::sync::Arc;
-use types::transaction::{SignedTransaction, Version};
+use logger::prelude::*;
+use schemadb::{ReadOptions, SchemaBatch, DB};
+use std::{collections::HashMap, sync::Arc};
+use types::{
+    account_address::AccountAddress,
+    transaction::{SignedTransaction, Version},
+};
+
+/// The number of transactions indexed by account in one write when backfilling the index.
+const BACKFILL_BATCH_SIZE: usize = 10_000;
 
 pub(crate) struct TransactionStore {
     db: Arc<DB>,
@@ -26,13 +33,129 @@ impl TransactionStore {
             .ok_or_else(|| LibraDbError::NotFound(format!("Txn {}", version)).into())
     }
 
-    /// Save signed transaction at `version`
+    /// Given the sender and a start sequence number, return the versions of up to `limit`
+    /// transactions sent by the account with consecutive sequence numbers. Result won't contain
+    /// records with a version > `ledger_version` and is in ascending order.
+    pub fn lookup_transactions_by_account(
+        &self,
+        address: AccountAddress,
+        start_seq_num: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let mut iter = self
+            .db
+            .iter::<TransactionByAccountSchema>(ReadOptions::default())?;
+        iter.seek(&(address, start_seq_num))?;
+
+        let mut result = Vec::new();
+        let mut cur_seq = start_seq_num;
+        for res in iter.take(limit as usize) {
+            let ((sender, seq), ver) = res?;
+            if sender != address || ver > ledger_version {
+                break;
+            }
+            // The entries of an account are pruned from the oldest on.
+            if seq > start_seq_num && cur_seq == start_seq_num {
+                return Err(LibraDbError::Pruned(format!(
+                    "Transaction {} sent by {}",
+                    start_seq_num, address
+                ))
+                .into());
+            }
+            ensure!(
+                seq == cur_seq,
+                "DB corrupt: Sequence number not continuous, expected: {}, actual: {}.",
+                cur_seq,
+                seq
+            );
+            result.push(ver);
+            cur_seq += 1;
+        }
+
+        Ok(result)
+    }
+
+    /// Indexes by sender and sequence number the transactions committed before the index was
+    /// introduced, and returns the number of them.
+    ///
+    /// Transactions are indexed from the newest on and each batch is written atomically, so the
+    /// indexed ones are always the newest and an interrupted backfill resumes where it stopped. A
+    /// transaction counts as indexed if its entry points to it or to a later transaction, since a
+    /// write set transaction doesn't bump the sequence number of its sender.
+    pub fn backfill_account_index(&self) -> Result<usize> {
+        let mut iter = self
+            .db
+            .iter::<SignedTransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        let first_version = match iter.next().transpose()? {
+            Some((version, txn)) => {
+                if self.is_indexed(version, &txn)? {
+                    return Ok(0);
+                }
+                version
+            }
+            None => return Ok(0),
+        };
+        iter.seek_to_last();
+        let latest_version = match iter.next().transpose()? {
+            Some((version, _)) => version,
+            None => return Ok(0),
+        };
+
+        let mut num_indexed = 0;
+        let mut batch = SchemaBatch::new();
+        let mut batch_entries = HashMap::new();
+        for version in (first_version..=latest_version).rev() {
+            let txn = self.get_transaction(version)?;
+            let key = (txn.sender(), txn.sequence_number());
+            if batch_entries.contains_key(&key) || self.is_indexed(version, &txn)? {
+                continue;
+            }
+            batch.put::<TransactionByAccountSchema>(&key, &version)?;
+            batch_entries.insert(key, version);
+            if batch_entries.len() == BACKFILL_BATCH_SIZE {
+                self.db.write_schemas(batch)?;
+                num_indexed += batch_entries.len();
+                batch = SchemaBatch::new();
+                batch_entries.clear();
+                info!(
+                    "Indexed {} transactions by account, down to version {}.",
+                    num_indexed, version
+                );
+            }
+        }
+        if !batch_entries.is_empty() {
+            self.db.write_schemas(batch)?;
+            num_indexed += batch_entries.len();
+        }
+
+        Ok(num_indexed)
+    }
+
+    /// Returns whether the index entry of `txn`, committed at `version`, points to it or to a later
+    /// transaction.
+    fn is_indexed(&self, version: Version, txn: &SignedTransaction) -> Result<bool> {
+        Ok(self
+            .db
+            .get::<TransactionByAccountSchema>(&(txn.sender(), txn.sequence_number()))?
+            .map_or(false, |indexed_version| indexed_version >= version))
+    }
+
+    /// Save signed transaction at `version`, and index it by its sender and sequence number.
     pub fn put_transaction(
         &self,
         version: Version,
         signed_transaction: &SignedTransaction,
         cs: &mut ChangeSet,
     ) -> Result<()> {
+        cs.batch.put::<TransactionByAccountSchema>(
+            &(
+                signed_transaction.sender(),
+                signed_transaction.sequence_number(),
+            ),
+            &version,
+        )?;
         cs.batch
             .put::<SignedTransactionSchema>(&version, signed_transaction)
     }
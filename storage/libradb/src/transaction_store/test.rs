@@ -30,3 +30,94 @@ proptest! {
         prop_assert!(store.get_transaction(txns.len() as u64).is_err());
     }
 }
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_lookup_transactions_by_account(txns in vec(any::<SignedTransaction>(), 1..10)) {
+        let tmp_dir = tempdir().unwrap();
+        let db = LibraDB::new(&tmp_dir);
+        let store = &db.transaction_store;
+
+        let mut cs = ChangeSet::new();
+        for (i, txn) in txns.iter().enumerate() {
+            store.put_transaction(i as u64, &txn, &mut cs).unwrap();
+        }
+        store.db.write_schemas(cs.batch).unwrap();
+
+        let ledger_version = txns.len() as u64 - 1;
+        for (i, txn) in txns.iter().enumerate() {
+            let version = i as u64;
+            prop_assert_eq!(
+                store
+                    .lookup_transactions_by_account(
+                        txn.sender(),
+                        txn.sequence_number(),
+                        10, /* limit */
+                        ledger_version,
+                    )
+                    .unwrap(),
+                vec![version]
+            );
+            // Transactions after the ledger version are not visible.
+            if version > 0 {
+                prop_assert!(store
+                    .lookup_transactions_by_account(
+                        txn.sender(),
+                        txn.sequence_number(),
+                        10, /* limit */
+                        version - 1,
+                    )
+                    .unwrap()
+                    .is_empty());
+            }
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_backfill_account_index(
+        txns in vec(any::<SignedTransaction>(), 1..10),
+        num_indexed in any::<prop::sample::Index>(),
+    ) {
+        let tmp_dir = tempdir().unwrap();
+        let db = LibraDB::new(&tmp_dir);
+        let store = &db.transaction_store;
+
+        // Only the newest transactions are indexed, as if they were committed after the index was
+        // introduced or the backfill was interrupted.
+        let num_indexed = num_indexed.index(txns.len());
+        let first_indexed = txns.len() - num_indexed;
+        let mut cs = ChangeSet::new();
+        for (i, txn) in txns.iter().enumerate() {
+            if i < first_indexed {
+                cs.batch.put::<SignedTransactionSchema>(&(i as u64), txn).unwrap();
+            } else {
+                store.put_transaction(i as u64, &txn, &mut cs).unwrap();
+            }
+        }
+        store.db.write_schemas(cs.batch).unwrap();
+
+        prop_assert_eq!(store.backfill_account_index().unwrap(), first_indexed);
+        prop_assert_eq!(store.backfill_account_index().unwrap(), 0);
+
+        let ledger_version = txns.len() as u64 - 1;
+        for (i, txn) in txns.iter().enumerate() {
+            prop_assert_eq!(
+                store
+                    .lookup_transactions_by_account(
+                        txn.sender(),
+                        txn.sequence_number(),
+                        1, /* limit */
+                        ledger_version,
+                    )
+                    .unwrap(),
+                vec![i as u64]
+            );
+        }
+    }
+}
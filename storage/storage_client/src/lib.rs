@@ -9,8 +9,13 @@
 
 mod state_view;
 
+use crypto::HashValue;
 use failure::prelude::*;
-use futures::{compat::Future01CompatExt, executor::block_on, prelude::*};
+use futures::{
+    compat::{Future01CompatExt, Stream01CompatExt},
+    executor::block_on,
+    prelude::*,
+};
 use futures_01::future::Future as Future01;
 use grpcio::{ChannelBuilder, Environment};
 use metrics::counters::SVC_COUNTERS;
@@ -20,20 +25,29 @@ use protobuf::Message;
 use rand::Rng;
 use std::{pin::Pin, sync::Arc};
 use storage_proto::{
-    proto::{storage::GetExecutorStartupInfoRequest, storage_grpc},
-    ExecutorStartupInfo, GetAccountStateWithProofByVersionRequest,
-    GetAccountStateWithProofByVersionResponse, GetExecutorStartupInfoResponse,
-    GetTransactionsRequest, GetTransactionsResponse, SaveTransactionsRequest,
+    proto::{
+        storage::{
+            GetExecutorStartupInfoRequest, GetStateSnapshotRestoreProgressRequest,
+            SubscribeToCommitsRequest,
+        },
+        storage_grpc,
+    },
+    ExecutorStartupInfo, FinalizeStateSnapshotRequest, GetAccountStateChunkRequest,
+    GetAccountStateChunkResponse, GetAccountStateWithProofByVersionRequest,
+    GetAccountStateWithProofByVersionResponse, GetAccountStatesInRangeByVersionRequest,
+    GetAccountStatesInRangeByVersionResponse, GetExecutorStartupInfoResponse,
+    GetStateSnapshotRestoreProgressResponse, GetTransactionsRequest, GetTransactionsResponse,
+    SaveAccountStateChunkRequest, SaveTransactionsRequest, StateSnapshotRestoreProgress,
 };
 use types::{
     account_address::AccountAddress,
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateRangeWithProof},
     get_with_proof::{
         RequestItem, ResponseItem, UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse,
     },
     ledger_info::LedgerInfoWithSignatures,
-    proof::SparseMerkleProof,
-    transaction::{TransactionListWithProof, TransactionToCommit, Version},
+    proof::{SparseMerkleProof, SparseMerkleRangeProof},
+    transaction::{TransactionInfo, TransactionListWithProof, TransactionToCommit, Version},
     validator_change::ValidatorChangeEventWithProof,
 };
 
@@ -203,6 +217,67 @@ impl StorageRead for StorageReadServiceClient {
         .boxed()
     }
 
+    fn get_account_states_in_range_by_version(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        block_on(self.get_account_states_in_range_by_version_async(start_key, limit, version))
+    }
+
+    fn get_account_states_in_range_by_version_async(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Pin<
+        Box<
+            dyn Future<
+                    Output = Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)>,
+                > + Send,
+        >,
+    > {
+        let req = GetAccountStatesInRangeByVersionRequest::new(start_key, limit, version);
+        convert_grpc_response(
+            self.client()
+                .get_account_states_in_range_by_version_async(&log_and_convert(req)),
+        )
+        .map(|resp| {
+            let resp = GetAccountStatesInRangeByVersionResponse::from_proto(resp?)?;
+            Ok(resp.into())
+        })
+        .boxed()
+    }
+
+    fn get_account_state_chunk(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Result<(AccountStateRangeWithProof, Vec<HashValue>)> {
+        block_on(self.get_account_state_chunk_async(start_key, limit, version))
+    }
+
+    fn get_account_state_chunk_async(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateRangeWithProof, Vec<HashValue>)>> + Send>>
+    {
+        let req = GetAccountStateChunkRequest::new(start_key, limit, version);
+        convert_grpc_response(
+            self.client()
+                .get_account_state_chunk_async(&log_and_convert(req)),
+        )
+        .map(|resp| {
+            let resp = GetAccountStateChunkResponse::from_proto(resp?)?;
+            Ok(resp.into())
+        })
+        .boxed()
+    }
+
     fn get_executor_startup_info(&self) -> Result<Option<ExecutorStartupInfo>> {
         block_on(self.get_executor_startup_info_async())
     }
@@ -218,6 +293,36 @@ impl StorageRead for StorageReadServiceClient {
             })
             .boxed()
     }
+
+    fn get_state_snapshot_restore_progress(&self) -> Result<Option<StateSnapshotRestoreProgress>> {
+        block_on(self.get_state_snapshot_restore_progress_async())
+    }
+
+    fn get_state_snapshot_restore_progress_async(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<StateSnapshotRestoreProgress>>> + Send>> {
+        let proto_req = GetStateSnapshotRestoreProgressRequest::new();
+        convert_grpc_response(
+            self.client()
+                .get_state_snapshot_restore_progress_async(&proto_req),
+        )
+        .map(|resp| {
+            let resp = GetStateSnapshotRestoreProgressResponse::from_proto(resp?)?;
+            Ok(resp.progress)
+        })
+        .boxed()
+    }
+
+    fn subscribe_to_commits(&self) -> Result<Pin<Box<dyn Stream<Item = Result<Version>> + Send>>> {
+        let proto_req = SubscribeToCommitsRequest::new();
+        let stream = self.client().subscribe_to_commits(&proto_req)?;
+        Ok(Box::pin(
+            stream
+                .compat()
+                .map_err(convert_grpc_err)
+                .map_ok(|resp| resp.get_committed_version()),
+        ))
+    }
 }
 
 /// This provides storage write interfaces backed by real storage service.
@@ -265,6 +370,60 @@ impl StorageWrite for StorageWriteServiceClient {
             .map_ok(|_| ())
             .boxed()
     }
+
+    fn save_account_state_chunk(
+        &self,
+        version: Version,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+    ) -> Result<()> {
+        block_on(self.save_account_state_chunk_async(version, account_blobs))
+    }
+
+    fn save_account_state_chunk_async(
+        &self,
+        version: Version,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        let req = SaveAccountStateChunkRequest::new(version, account_blobs);
+        convert_grpc_response(
+            self.client()
+                .save_account_state_chunk_async(&log_and_convert(req)),
+        )
+        .map_ok(|_| ())
+        .boxed()
+    }
+
+    fn finalize_state_snapshot(
+        &self,
+        txn_info: TransactionInfo,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Result<()> {
+        block_on(self.finalize_state_snapshot_async(
+            txn_info,
+            ledger_frozen_subtree_hashes,
+            ledger_info_with_sigs,
+        ))
+    }
+
+    fn finalize_state_snapshot_async(
+        &self,
+        txn_info: TransactionInfo,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        let req = FinalizeStateSnapshotRequest::new(
+            txn_info,
+            ledger_frozen_subtree_hashes,
+            ledger_info_with_sigs,
+        );
+        convert_grpc_response(
+            self.client()
+                .finalize_state_snapshot_async(&log_and_convert(req)),
+        )
+        .map_ok(|_| ())
+        .boxed()
+    }
 }
 
 /// This trait defines interfaces to be implemented by a storage read client.
@@ -349,6 +508,56 @@ pub trait StorageRead: Send + Sync {
         version: Version,
     ) -> Pin<Box<dyn Future<Output = Result<(Option<AccountStateBlob>, SparseMerkleProof)>> + Send>>;
 
+    /// See [`LibraDB::get_account_states_in_range_by_version`].
+    ///
+    /// [`LibraDB::get_account_states_in_range_by_version`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_states_in_range_by_version
+    fn get_account_states_in_range_by_version(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)>;
+
+    /// See [`LibraDB::get_account_states_in_range_by_version`].
+    ///
+    /// [`LibraDB::get_account_states_in_range_by_version`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_states_in_range_by_version
+    fn get_account_states_in_range_by_version_async(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Pin<
+        Box<
+            dyn Future<
+                    Output = Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)>,
+                > + Send,
+        >,
+    >;
+
+    /// See [`LibraDB::get_account_state_chunk`].
+    ///
+    /// [`LibraDB::get_account_state_chunk`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_state_chunk
+    fn get_account_state_chunk(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Result<(AccountStateRangeWithProof, Vec<HashValue>)>;
+
+    /// See [`LibraDB::get_account_state_chunk`].
+    ///
+    /// [`LibraDB::get_account_state_chunk`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_state_chunk
+    fn get_account_state_chunk_async(
+        &self,
+        start_key: HashValue,
+        limit: u64,
+        version: Version,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateRangeWithProof, Vec<HashValue>)>> + Send>>;
+
     /// See [`LibraDB::get_executor_startup_info`].
     ///
     /// [`LibraDB::get_executor_startup_info`]:
@@ -362,6 +571,26 @@ pub trait StorageRead: Send + Sync {
     fn get_executor_startup_info_async(
         &self,
     ) -> Pin<Box<dyn Future<Output = Result<Option<ExecutorStartupInfo>>> + Send>>;
+
+    /// See [`LibraDB::get_state_snapshot_restore_progress`].
+    ///
+    /// [`LibraDB::get_state_snapshot_restore_progress`]:
+    /// ../libradb/struct.LibraDB.html#method.get_state_snapshot_restore_progress
+    fn get_state_snapshot_restore_progress(&self) -> Result<Option<StateSnapshotRestoreProgress>>;
+
+    /// See [`LibraDB::get_state_snapshot_restore_progress`].
+    ///
+    /// [`LibraDB::get_state_snapshot_restore_progress`]:
+    /// ../libradb/struct.LibraDB.html#method.get_state_snapshot_restore_progress
+    fn get_state_snapshot_restore_progress_async(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<StateSnapshotRestoreProgress>>> + Send>>;
+
+    /// See [`LibraDB::subscribe_to_commits`].
+    ///
+    /// [`LibraDB::subscribe_to_commits`]:
+    /// ../libradb/struct.LibraDB.html#method.subscribe_to_commits
+    fn subscribe_to_commits(&self) -> Result<Pin<Box<dyn Stream<Item = Result<Version>> + Send>>>;
 }
 
 /// This trait defines interfaces to be implemented by a storage write client.
@@ -389,6 +618,48 @@ pub trait StorageWrite: Send + Sync {
         first_version: Version,
         ledger_info_with_sigs: Option<LedgerInfoWithSignatures<Ed25519Signature>>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+    /// See [`LibraDB::save_account_state_chunk`].
+    ///
+    /// [`LibraDB::save_account_state_chunk`]:
+    /// ../libradb/struct.LibraDB.html#method.save_account_state_chunk
+    fn save_account_state_chunk(
+        &self,
+        version: Version,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+    ) -> Result<()>;
+
+    /// See [`LibraDB::save_account_state_chunk`].
+    ///
+    /// [`LibraDB::save_account_state_chunk`]:
+    /// ../libradb/struct.LibraDB.html#method.save_account_state_chunk
+    fn save_account_state_chunk_async(
+        &self,
+        version: Version,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+    /// See [`LibraDB::finalize_state_snapshot`].
+    ///
+    /// [`LibraDB::finalize_state_snapshot`]:
+    /// ../libradb/struct.LibraDB.html#method.finalize_state_snapshot
+    fn finalize_state_snapshot(
+        &self,
+        txn_info: TransactionInfo,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Result<()>;
+
+    /// See [`LibraDB::finalize_state_snapshot`].
+    ///
+    /// [`LibraDB::finalize_state_snapshot`]:
+    /// ../libradb/struct.LibraDB.html#method.finalize_state_snapshot
+    fn finalize_state_snapshot_async(
+        &self,
+        txn_info: TransactionInfo,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
 }
 
 fn convert_grpc_err(e: grpcio::Error) -> Error {
@@ -35,10 +35,10 @@ use proptest_derive::Arbitrary;
 use proto_conv::{FromProto, IntoProto};
 use types::{
     account_address::AccountAddress,
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateRangeWithProof},
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    proof::definition::SparseMerkleProof,
-    transaction::{TransactionListWithProof, TransactionToCommit, Version},
+    proof::definition::{SparseMerkleProof, SparseMerkleRangeProof},
+    transaction::{TransactionInfo, TransactionListWithProof, TransactionToCommit, Version},
 };
 
 /// Helper to construct and parse [`proto::storage::GetAccountStateWithProofByVersionRequest`]
@@ -138,6 +138,132 @@ impl Into<(Option<AccountStateBlob>, SparseMerkleProof)>
     }
 }
 
+/// Helper to construct and parse [`proto::storage::GetAccountStatesInRangeByVersionRequest`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct GetAccountStatesInRangeByVersionRequest {
+    /// The key (hashed account address) to start with.
+    pub start_key: HashValue,
+
+    /// The maximum number of account states to return.
+    pub limit: u64,
+
+    /// The version the query is based on.
+    pub version: Version,
+}
+
+impl GetAccountStatesInRangeByVersionRequest {
+    /// Constructor.
+    pub fn new(start_key: HashValue, limit: u64, version: Version) -> Self {
+        Self {
+            start_key,
+            limit,
+            version,
+        }
+    }
+}
+
+impl FromProto for GetAccountStatesInRangeByVersionRequest {
+    type ProtoType = crate::proto::storage::GetAccountStatesInRangeByVersionRequest;
+
+    fn from_proto(object: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            start_key: HashValue::from_slice(object.get_start_key())?,
+            limit: object.get_limit(),
+            version: object.get_version(),
+        })
+    }
+}
+
+impl IntoProto for GetAccountStatesInRangeByVersionRequest {
+    type ProtoType = crate::proto::storage::GetAccountStatesInRangeByVersionRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_start_key(self.start_key.to_vec());
+        out.set_limit(self.limit);
+        out.set_version(self.version);
+        out
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::GetAccountStatesInRangeByVersionResponse`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct GetAccountStatesInRangeByVersionResponse {
+    /// The account state blobs in the range together with their keys, sorted by key.
+    pub account_blobs: Vec<(HashValue, AccountStateBlob)>,
+
+    /// The proof that no account state in the range is left out.
+    pub sparse_merkle_range_proof: SparseMerkleRangeProof,
+}
+
+impl GetAccountStatesInRangeByVersionResponse {
+    /// Constructor.
+    pub fn new(
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+        sparse_merkle_range_proof: SparseMerkleRangeProof,
+    ) -> Self {
+        Self {
+            account_blobs,
+            sparse_merkle_range_proof,
+        }
+    }
+}
+
+impl FromProto for GetAccountStatesInRangeByVersionResponse {
+    type ProtoType = crate::proto::storage::GetAccountStatesInRangeByVersionResponse;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let account_blobs = object
+            .take_account_blobs()
+            .into_iter()
+            .map(|mut keyed_blob| {
+                Ok((
+                    HashValue::from_slice(keyed_blob.get_key())?,
+                    AccountStateBlob::from_proto(keyed_blob.take_blob())?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            account_blobs,
+            sparse_merkle_range_proof: SparseMerkleRangeProof::from_proto(
+                object.take_sparse_merkle_range_proof(),
+            )?,
+        })
+    }
+}
+
+impl IntoProto for GetAccountStatesInRangeByVersionResponse {
+    type ProtoType = crate::proto::storage::GetAccountStatesInRangeByVersionResponse;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut object = Self::ProtoType::new();
+        for (key, blob) in self.account_blobs {
+            let mut keyed_blob = types::proto::account_state_blob::KeyedAccountStateBlob::new();
+            keyed_blob.set_key(key.to_vec());
+            keyed_blob.set_blob(blob.into_proto());
+            object.mut_account_blobs().push(keyed_blob);
+        }
+        object.set_sparse_merkle_range_proof(self.sparse_merkle_range_proof.into_proto());
+        object
+    }
+}
+
+impl Into<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)>
+    for GetAccountStatesInRangeByVersionResponse
+{
+    fn into(self) -> (Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof) {
+        (self.account_blobs, self.sparse_merkle_range_proof)
+    }
+}
+
 /// Helper to construct and parse [`proto::storage::SaveTransactionsRequest`]
 ///
 /// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
@@ -209,6 +335,131 @@ impl IntoProto for SaveTransactionsRequest {
     }
 }
 
+/// Helper to construct and parse [`proto::storage::SaveAccountStateChunkRequest`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct SaveAccountStateChunkRequest {
+    pub version: Version,
+    pub account_blobs: Vec<(HashValue, AccountStateBlob)>,
+}
+
+impl SaveAccountStateChunkRequest {
+    /// Constructor.
+    pub fn new(version: Version, account_blobs: Vec<(HashValue, AccountStateBlob)>) -> Self {
+        SaveAccountStateChunkRequest {
+            version,
+            account_blobs,
+        }
+    }
+}
+
+impl FromProto for SaveAccountStateChunkRequest {
+    type ProtoType = crate::proto::storage::SaveAccountStateChunkRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let version = object.get_version();
+        let account_blobs = object
+            .take_account_blobs()
+            .into_iter()
+            .map(|mut keyed_blob| {
+                Ok((
+                    HashValue::from_slice(keyed_blob.get_key())?,
+                    AccountStateBlob::from_proto(keyed_blob.take_blob())?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            version,
+            account_blobs,
+        })
+    }
+}
+
+impl IntoProto for SaveAccountStateChunkRequest {
+    type ProtoType = crate::proto::storage::SaveAccountStateChunkRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_version(self.version);
+        for (key, blob) in self.account_blobs {
+            let mut keyed_blob = types::proto::account_state_blob::KeyedAccountStateBlob::new();
+            keyed_blob.set_key(key.to_vec());
+            keyed_blob.set_blob(blob.into_proto());
+            proto.mut_account_blobs().push(keyed_blob);
+        }
+        proto
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::FinalizeStateSnapshotRequest`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct FinalizeStateSnapshotRequest {
+    pub txn_info: TransactionInfo,
+    pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+    pub ledger_info_with_signatures: LedgerInfoWithSignatures<Ed25519Signature>,
+}
+
+impl FinalizeStateSnapshotRequest {
+    /// Constructor.
+    pub fn new(
+        txn_info: TransactionInfo,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        ledger_info_with_signatures: LedgerInfoWithSignatures<Ed25519Signature>,
+    ) -> Self {
+        FinalizeStateSnapshotRequest {
+            txn_info,
+            ledger_frozen_subtree_hashes,
+            ledger_info_with_signatures,
+        }
+    }
+}
+
+impl FromProto for FinalizeStateSnapshotRequest {
+    type ProtoType = crate::proto::storage::FinalizeStateSnapshotRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let txn_info = TransactionInfo::from_proto(object.take_txn_info())?;
+        let ledger_frozen_subtree_hashes = object
+            .take_ledger_frozen_subtree_hashes()
+            .into_iter()
+            .map(HashValue::from_proto)
+            .collect::<Result<Vec<_>>>()?;
+        let ledger_info_with_signatures =
+            LedgerInfoWithSignatures::from_proto(object.take_ledger_info_with_signatures())?;
+
+        Ok(Self {
+            txn_info,
+            ledger_frozen_subtree_hashes,
+            ledger_info_with_signatures,
+        })
+    }
+}
+
+impl IntoProto for FinalizeStateSnapshotRequest {
+    type ProtoType = crate::proto::storage::FinalizeStateSnapshotRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_txn_info(self.txn_info.into_proto());
+        proto.set_ledger_frozen_subtree_hashes(protobuf::RepeatedField::from_vec(
+            self.ledger_frozen_subtree_hashes
+                .into_iter()
+                .map(HashValue::into_proto)
+                .collect::<Vec<_>>(),
+        ));
+        proto.set_ledger_info_with_signatures(self.ledger_info_with_signatures.into_proto());
+        proto
+    }
+}
+
 /// Helper to construct and parse [`proto::storage::GetTransactionsRequest`]
 ///
 /// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
@@ -285,6 +536,121 @@ impl GetTransactionsResponse {
     }
 }
 
+/// Helper to construct and parse [`proto::storage::GetAccountStateChunkRequest`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct GetAccountStateChunkRequest {
+    pub start_key: HashValue,
+    pub limit: u64,
+    pub version: Version,
+}
+
+impl GetAccountStateChunkRequest {
+    /// Constructor.
+    pub fn new(start_key: HashValue, limit: u64, version: Version) -> Self {
+        GetAccountStateChunkRequest {
+            start_key,
+            limit,
+            version,
+        }
+    }
+}
+
+impl FromProto for GetAccountStateChunkRequest {
+    type ProtoType = crate::proto::storage::GetAccountStateChunkRequest;
+
+    fn from_proto(object: Self::ProtoType) -> Result<Self> {
+        Ok(GetAccountStateChunkRequest {
+            start_key: HashValue::from_slice(object.get_start_key())?,
+            limit: object.get_limit(),
+            version: object.get_version(),
+        })
+    }
+}
+
+impl IntoProto for GetAccountStateChunkRequest {
+    type ProtoType = crate::proto::storage::GetAccountStateChunkRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_start_key(self.start_key.to_vec());
+        out.set_limit(self.limit);
+        out.set_version(self.version);
+        out
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::GetAccountStateChunkResponse`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct GetAccountStateChunkResponse {
+    pub account_state_range_with_proof: AccountStateRangeWithProof,
+    pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+}
+
+impl GetAccountStateChunkResponse {
+    /// Constructor.
+    pub fn new(
+        account_state_range_with_proof: AccountStateRangeWithProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Self {
+        GetAccountStateChunkResponse {
+            account_state_range_with_proof,
+            ledger_frozen_subtree_hashes,
+        }
+    }
+}
+
+impl FromProto for GetAccountStateChunkResponse {
+    type ProtoType = crate::proto::storage::GetAccountStateChunkResponse;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let account_state_range_with_proof =
+            AccountStateRangeWithProof::from_proto(object.take_account_state_range_with_proof())?;
+        let ledger_frozen_subtree_hashes = object
+            .take_ledger_frozen_subtree_hashes()
+            .into_iter()
+            .map(HashValue::from_proto)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            account_state_range_with_proof,
+            ledger_frozen_subtree_hashes,
+        })
+    }
+}
+
+impl IntoProto for GetAccountStateChunkResponse {
+    type ProtoType = crate::proto::storage::GetAccountStateChunkResponse;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_account_state_range_with_proof(self.account_state_range_with_proof.into_proto());
+        proto.set_ledger_frozen_subtree_hashes(protobuf::RepeatedField::from_vec(
+            self.ledger_frozen_subtree_hashes
+                .into_iter()
+                .map(HashValue::into_proto)
+                .collect::<Vec<_>>(),
+        ));
+        proto
+    }
+}
+
+impl Into<(AccountStateRangeWithProof, Vec<HashValue>)> for GetAccountStateChunkResponse {
+    fn into(self) -> (AccountStateRangeWithProof, Vec<HashValue>) {
+        (
+            self.account_state_range_with_proof,
+            self.ledger_frozen_subtree_hashes,
+        )
+    }
+}
+
 /// Helper to construct and parse [`proto::storage::ExecutorStartupInfo`]
 ///
 /// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
@@ -296,6 +662,7 @@ pub struct ExecutorStartupInfo {
     pub latest_version: Version,
     pub account_state_root_hash: HashValue,
     pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+    pub state_snapshot_restore: Option<StateSnapshotRestoreProgress>,
 }
 
 impl FromProto for ExecutorStartupInfo {
@@ -310,12 +677,20 @@ impl FromProto for ExecutorStartupInfo {
             .into_iter()
             .map(HashValue::from_proto)
             .collect::<Result<Vec<_>>>()?;
+        let state_snapshot_restore = if object.has_state_snapshot_restore() {
+            Some(StateSnapshotRestoreProgress::from_proto(
+                object.take_state_snapshot_restore(),
+            )?)
+        } else {
+            None
+        };
 
         Ok(Self {
             ledger_info,
             latest_version,
             account_state_root_hash,
             ledger_frozen_subtree_hashes,
+            state_snapshot_restore,
         })
     }
 }
@@ -334,10 +709,49 @@ impl IntoProto for ExecutorStartupInfo {
                 .map(HashValue::into_proto)
                 .collect::<Vec<_>>(),
         ));
+        if let Some(state_snapshot_restore) = self.state_snapshot_restore {
+            proto.set_state_snapshot_restore(state_snapshot_restore.into_proto());
+        }
         proto
     }
 }
 
+/// Helper to construct and parse [`proto::storage::StateSnapshotRestoreProgress`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct StateSnapshotRestoreProgress {
+    /// The version of the state snapshot being restored.
+    pub version: Version,
+
+    /// The key the next chunk of account states is expected to start with.
+    pub next_key: HashValue,
+}
+
+impl FromProto for StateSnapshotRestoreProgress {
+    type ProtoType = crate::proto::storage::StateSnapshotRestoreProgress;
+
+    fn from_proto(object: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            version: object.get_version(),
+            next_key: HashValue::from_slice(object.get_next_key())?,
+        })
+    }
+}
+
+impl IntoProto for StateSnapshotRestoreProgress {
+    type ProtoType = crate::proto::storage::StateSnapshotRestoreProgress;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_version(self.version);
+        out.set_next_key(self.next_key.to_vec());
+        out
+    }
+}
+
 /// Helper to construct and parse [`proto::storage::GetExecutorStartupInfoResponse`]
 ///
 /// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
@@ -374,6 +788,44 @@ impl IntoProto for GetExecutorStartupInfoResponse {
     }
 }
 
+/// Helper to construct and parse [`proto::storage::GetStateSnapshotRestoreProgressResponse`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct GetStateSnapshotRestoreProgressResponse {
+    pub progress: Option<StateSnapshotRestoreProgress>,
+}
+
+impl FromProto for GetStateSnapshotRestoreProgressResponse {
+    type ProtoType = crate::proto::storage::GetStateSnapshotRestoreProgressResponse;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let progress = if object.has_progress() {
+            Some(StateSnapshotRestoreProgress::from_proto(
+                object.take_progress(),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(Self { progress })
+    }
+}
+
+impl IntoProto for GetStateSnapshotRestoreProgressResponse {
+    type ProtoType = crate::proto::storage::GetStateSnapshotRestoreProgressResponse;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        if let Some(progress) = self.progress {
+            proto.set_progress(progress.into_proto())
+        }
+        proto
+    }
+}
+
 pub mod prelude {
     pub use super::*;
 }
@@ -13,6 +13,40 @@ proptest! {
         assert_protobuf_encode_decode(&req);
     }
 
+    #[test]
+    fn test_get_account_states_in_range_by_version_request(
+        req in any::<GetAccountStatesInRangeByVersionRequest>()
+    ) {
+        assert_protobuf_encode_decode(&req);
+    }
+
+    #[test]
+    fn test_get_account_states_in_range_by_version_response(
+        resp in any::<GetAccountStatesInRangeByVersionResponse>()
+    ) {
+        assert_protobuf_encode_decode(&resp);
+    }
+
+    #[test]
+    fn test_save_account_state_chunk_request(req in any::<SaveAccountStateChunkRequest>()) {
+        assert_protobuf_encode_decode(&req);
+    }
+
+    #[test]
+    fn test_finalize_state_snapshot_request(req in any::<FinalizeStateSnapshotRequest>()) {
+        assert_protobuf_encode_decode(&req);
+    }
+
+    #[test]
+    fn test_get_account_state_chunk_request(req in any::<GetAccountStateChunkRequest>()) {
+        assert_protobuf_encode_decode(&req);
+    }
+
+    #[test]
+    fn test_get_account_state_chunk_response(resp in any::<GetAccountStateChunkResponse>()) {
+        assert_protobuf_encode_decode(&resp);
+    }
+
     #[test]
     fn test_get_transactions_request(req in any::<GetTransactionsRequest>()) {
         assert_protobuf_encode_decode(&req);
@@ -32,4 +66,11 @@ proptest! {
     fn test_get_executor_startup_info_response(res in any::<GetExecutorStartupInfoResponse>()) {
         assert_protobuf_encode_decode(&res);
     }
+
+    #[test]
+    fn test_get_state_snapshot_restore_progress_response(
+        res in any::<GetStateSnapshotRestoreProgressResponse>()
+    ) {
+        assert_protobuf_encode_decode(&res);
+    }
 }
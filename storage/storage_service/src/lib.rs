@@ -9,9 +9,13 @@
 
 pub mod mocks;
 
-use config::config::NodeConfig;
+use config::config::{NodeConfig, StorageMode};
 use failure::prelude::*;
-use grpc_helpers::{provide_grpc_response, spawn_service_thread_with_drop_closure, ServerHandle};
+use futures::stream::{Stream, StreamExt};
+use grpc_helpers::{
+    provide_grpc_response, provide_grpc_stream_response, spawn_service_thread_with_drop_closure,
+    ServerHandle,
+};
 use libradb::LibraDB;
 use logger::prelude::*;
 use metrics::counters::SVC_COUNTERS;
@@ -23,9 +27,14 @@ use std::{
 };
 use storage_proto::proto::{
     storage::{
-        GetAccountStateWithProofByVersionRequest, GetAccountStateWithProofByVersionResponse,
-        GetExecutorStartupInfoRequest, GetExecutorStartupInfoResponse, GetTransactionsRequest,
-        GetTransactionsResponse, SaveTransactionsRequest, SaveTransactionsResponse,
+        FinalizeStateSnapshotRequest, FinalizeStateSnapshotResponse, GetAccountStateChunkRequest,
+        GetAccountStateChunkResponse, GetAccountStateWithProofByVersionRequest,
+        GetAccountStateWithProofByVersionResponse, GetAccountStatesInRangeByVersionRequest,
+        GetAccountStatesInRangeByVersionResponse, GetExecutorStartupInfoRequest,
+        GetExecutorStartupInfoResponse, GetStateSnapshotRestoreProgressRequest,
+        GetStateSnapshotRestoreProgressResponse, GetTransactionsRequest, GetTransactionsResponse,
+        SaveAccountStateChunkRequest, SaveAccountStateChunkResponse, SaveTransactionsRequest,
+        SaveTransactionsResponse, SubscribeToCommitsRequest, SubscribeToCommitsResponse,
     },
     storage_grpc::{create_storage, Storage},
 };
@@ -33,7 +42,8 @@ use types::proto::get_with_proof::{UpdateToLatestLedgerRequest, UpdateToLatestLe
 
 /// Starts storage service according to config.
 pub fn start_storage_service(config: &NodeConfig) -> ServerHandle {
-    let (storage_service, shutdown_receiver) = StorageService::new(&config.storage.get_dir());
+    let (storage_service, shutdown_receiver) =
+        StorageService::new(&config.storage.get_dir(), config.storage.storage_mode);
     spawn_service_thread_with_drop_closure(
         create_storage(storage_service),
         config.storage.address.clone(),
@@ -73,8 +83,8 @@ struct LibraDBWrapper {
 }
 
 impl LibraDBWrapper {
-    pub fn new<P: AsRef<Path>>(path: &P) -> (Self, mpsc::Receiver<()>) {
-        let db = LibraDB::new(path);
+    pub fn new<P: AsRef<Path>>(path: &P, storage_mode: StorageMode) -> (Self, mpsc::Receiver<()>) {
+        let db = LibraDB::new_with_storage_mode(path, storage_mode);
         let (shutdown_sender, shutdown_receiver) = mpsc::channel();
         (
             Self {
@@ -116,17 +126,19 @@ impl StorageService {
     ///
     /// example:
     /// ```no_run,
+    ///    # use config::config::StorageMode;
     ///    # use storage_service::*;
     ///    # use std::path::Path;
-    ///    let (service, shutdown_receiver) = StorageService::new(&Path::new("path/to/db"));
+    ///    let (service, shutdown_receiver) =
+    ///        StorageService::new(&Path::new("path/to/db"), StorageMode::Archive);
     ///
     ///    drop(service);
     ///    shutdown_receiver.recv().expect("recv() should succeed.");
     ///
     ///    // LibraDB instance is guaranteed to be properly dropped at this point.
     /// ```
-    pub fn new<P: AsRef<Path>>(path: &P) -> (Self, mpsc::Receiver<()>) {
-        let (db_wrapper, shutdown_receiver) = LibraDBWrapper::new(path);
+    pub fn new<P: AsRef<Path>>(path: &P, storage_mode: StorageMode) -> (Self, mpsc::Receiver<()>) {
+        let (db_wrapper, shutdown_receiver) = LibraDBWrapper::new(path, storage_mode);
         (
             Self {
                 db: Arc::new(db_wrapper),
@@ -192,6 +204,27 @@ impl StorageService {
         Ok(rust_resp.into_proto())
     }
 
+    fn get_account_states_in_range_by_version_inner(
+        &self,
+        req: GetAccountStatesInRangeByVersionRequest,
+    ) -> Result<GetAccountStatesInRangeByVersionResponse> {
+        let rust_req = storage_proto::GetAccountStatesInRangeByVersionRequest::from_proto(req)?;
+
+        let (account_blobs, sparse_merkle_range_proof) =
+            self.db.get_account_states_in_range_by_version(
+                rust_req.start_key,
+                rust_req.limit,
+                rust_req.version,
+            )?;
+
+        let rust_resp = storage_proto::GetAccountStatesInRangeByVersionResponse {
+            account_blobs,
+            sparse_merkle_range_proof,
+        };
+
+        Ok(rust_resp.into_proto())
+    }
+
     fn save_transactions_inner(
         &self,
         req: SaveTransactionsRequest,
@@ -205,11 +238,70 @@ impl StorageService {
         Ok(SaveTransactionsResponse::new())
     }
 
+    fn save_account_state_chunk_inner(
+        &self,
+        req: SaveAccountStateChunkRequest,
+    ) -> Result<SaveAccountStateChunkResponse> {
+        let rust_req = storage_proto::SaveAccountStateChunkRequest::from_proto(req)?;
+        self.db
+            .save_account_state_chunk(rust_req.version, rust_req.account_blobs)?;
+        Ok(SaveAccountStateChunkResponse::new())
+    }
+
+    fn finalize_state_snapshot_inner(
+        &self,
+        req: FinalizeStateSnapshotRequest,
+    ) -> Result<FinalizeStateSnapshotResponse> {
+        let rust_req = storage_proto::FinalizeStateSnapshotRequest::from_proto(req)?;
+        self.db.finalize_state_snapshot(
+            rust_req.txn_info,
+            rust_req.ledger_frozen_subtree_hashes,
+            rust_req.ledger_info_with_signatures,
+        )?;
+        Ok(FinalizeStateSnapshotResponse::new())
+    }
+
+    fn get_account_state_chunk_inner(
+        &self,
+        req: GetAccountStateChunkRequest,
+    ) -> Result<GetAccountStateChunkResponse> {
+        let rust_req = storage_proto::GetAccountStateChunkRequest::from_proto(req)?;
+
+        let (account_state_range_with_proof, ledger_frozen_subtree_hashes) = self
+            .db
+            .get_account_state_chunk(rust_req.start_key, rust_req.limit, rust_req.version)?;
+
+        let rust_resp = storage_proto::GetAccountStateChunkResponse::new(
+            account_state_range_with_proof,
+            ledger_frozen_subtree_hashes,
+        );
+
+        Ok(rust_resp.into_proto())
+    }
+
     fn get_executor_startup_info_inner(&self) -> Result<GetExecutorStartupInfoResponse> {
         let info = self.db.get_executor_startup_info()?;
         let rust_resp = storage_proto::GetExecutorStartupInfoResponse { info };
         Ok(rust_resp.into_proto())
     }
+
+    fn get_state_snapshot_restore_progress_inner(
+        &self,
+    ) -> Result<GetStateSnapshotRestoreProgressResponse> {
+        let progress = self.db.get_state_snapshot_restore_progress()?;
+        let rust_resp = storage_proto::GetStateSnapshotRestoreProgressResponse { progress };
+        Ok(rust_resp.into_proto())
+    }
+
+    fn subscribe_to_commits_inner(
+        &self,
+    ) -> Result<impl Stream<Item = Result<SubscribeToCommitsResponse>>> {
+        Ok(self.db.subscribe_to_commits()?.map(|version| {
+            let mut resp = SubscribeToCommitsResponse::new();
+            resp.set_committed_version(version);
+            Ok(resp)
+        }))
+    }
 }
 
 impl Storage for StorageService {
@@ -225,6 +317,30 @@ impl Storage for StorageService {
         provide_grpc_response(resp, ctx, sink);
     }
 
+    fn save_account_state_chunk(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: SaveAccountStateChunkRequest,
+        sink: grpcio::UnarySink<SaveAccountStateChunkResponse>,
+    ) {
+        debug!("[GRPC] Storage::save_account_state_chunk");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.save_account_state_chunk_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    fn finalize_state_snapshot(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: FinalizeStateSnapshotRequest,
+        sink: grpcio::UnarySink<FinalizeStateSnapshotResponse>,
+    ) {
+        debug!("[GRPC] Storage::finalize_state_snapshot");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.finalize_state_snapshot_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
     fn update_to_latest_ledger(
         &mut self,
         ctx: grpcio::RpcContext<'_>,
@@ -249,6 +365,18 @@ impl Storage for StorageService {
         provide_grpc_response(resp, ctx, sink);
     }
 
+    fn get_account_state_chunk(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: GetAccountStateChunkRequest,
+        sink: grpcio::UnarySink<GetAccountStateChunkResponse>,
+    ) {
+        debug!("[GRPC] Storage::get_account_state_chunk");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.get_account_state_chunk_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
     fn get_account_state_with_proof_by_version(
         &mut self,
         ctx: grpcio::RpcContext,
@@ -261,6 +389,18 @@ impl Storage for StorageService {
         provide_grpc_response(resp, ctx, sink);
     }
 
+    fn get_account_states_in_range_by_version(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: GetAccountStatesInRangeByVersionRequest,
+        sink: grpcio::UnarySink<GetAccountStatesInRangeByVersionResponse>,
+    ) {
+        debug!("[GRPC] Storage::get_account_states_in_range_by_version");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.get_account_states_in_range_by_version_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
     fn get_executor_startup_info(
         &mut self,
         ctx: grpcio::RpcContext,
@@ -272,6 +412,30 @@ impl Storage for StorageService {
         let resp = self.get_executor_startup_info_inner();
         provide_grpc_response(resp, ctx, sink);
     }
+
+    fn get_state_snapshot_restore_progress(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        _req: GetStateSnapshotRestoreProgressRequest,
+        sink: grpcio::UnarySink<GetStateSnapshotRestoreProgressResponse>,
+    ) {
+        debug!("[GRPC] Storage::get_state_snapshot_restore_progress");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.get_state_snapshot_restore_progress_inner();
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    fn subscribe_to_commits(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        _req: SubscribeToCommitsRequest,
+        sink: grpcio::ServerStreamingSink<SubscribeToCommitsResponse>,
+    ) {
+        debug!("[GRPC] Storage::subscribe_to_commits");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.subscribe_to_commits_inner();
+        provide_grpc_stream_response(resp, ctx, sink);
+    }
 }
 
 #[cfg(test)]
@@ -15,19 +15,21 @@ use rand::{
 };
 use std::{collections::BTreeMap, pin::Pin};
 use storage_client::StorageRead;
-use storage_proto::ExecutorStartupInfo;
+use storage_proto::{ExecutorStartupInfo, StateSnapshotRestoreProgress};
 use types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateRangeWithProof},
     get_with_proof::{RequestItem, ResponseItem},
     ledger_info::LedgerInfoWithSignatures,
-    proof::definition::SparseMerkleProof,
+    proof::definition::{SparseMerkleProof, SparseMerkleRangeProof},
     proto::{
         account_state_blob::AccountStateWithProof,
         get_with_proof::{
-            GetAccountStateResponse, GetTransactionsResponse, RequestItem as ProtoRequestItem,
-            RequestItem_oneof_requested_items, ResponseItem as ProtoResponseItem,
-            UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse,
+            GetAccountStateResponse, GetAccountTransactionBySequenceNumberResponse,
+            GetEventsByEventAccessPathResponse, GetTransactionsResponse,
+            RequestItem as ProtoRequestItem, RequestItem_oneof_requested_items,
+            ResponseItem as ProtoResponseItem, UpdateToLatestLedgerRequest,
+            UpdateToLatestLedgerResponse,
         },
         ledger_info::LedgerInfoWithSignatures as ProtoLedgerInfoWithSignatures,
         proof::AccumulatorProof,
@@ -131,6 +133,49 @@ impl StorageRead for MockStorageReadClient {
         unimplemented!();
     }
 
+    fn get_account_states_in_range_by_version(
+        &self,
+        _start_key: HashValue,
+        _limit: u64,
+        _version: Version,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        unimplemented!()
+    }
+
+    fn get_account_states_in_range_by_version_async(
+        &self,
+        _start_key: HashValue,
+        _limit: u64,
+        _version: Version,
+    ) -> Pin<
+        Box<
+            dyn Future<
+                    Output = Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)>,
+                > + Send,
+        >,
+    > {
+        unimplemented!();
+    }
+
+    fn get_account_state_chunk(
+        &self,
+        _start_key: HashValue,
+        _limit: u64,
+        _version: Version,
+    ) -> Result<(AccountStateRangeWithProof, Vec<HashValue>)> {
+        unimplemented!()
+    }
+
+    fn get_account_state_chunk_async(
+        &self,
+        _start_key: HashValue,
+        _limit: u64,
+        _version: Version,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateRangeWithProof, Vec<HashValue>)>> + Send>>
+    {
+        unimplemented!()
+    }
+
     fn get_executor_startup_info(&self) -> Result<Option<ExecutorStartupInfo>> {
         unimplemented!()
     }
@@ -140,8 +185,34 @@ impl StorageRead for MockStorageReadClient {
     ) -> Pin<Box<dyn Future<Output = Result<Option<ExecutorStartupInfo>>> + Send>> {
         unimplemented!()
     }
+
+    fn get_state_snapshot_restore_progress(&self) -> Result<Option<StateSnapshotRestoreProgress>> {
+        unimplemented!()
+    }
+
+    fn get_state_snapshot_restore_progress_async(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<StateSnapshotRestoreProgress>>> + Send>> {
+        unimplemented!()
+    }
+
+    fn subscribe_to_commits(&self) -> Result<Pin<Box<dyn Stream<Item = Result<Version>> + Send>>> {
+        // The mock ledger only ever has the ledger info at `MOCK_LEDGER_VERSION`.
+        Ok(Box::pin(futures::stream::iter(vec![Ok(
+            MOCK_LEDGER_VERSION,
+        )])))
+    }
 }
 
+/// The version of the ledger info in all mock responses.
+pub const MOCK_LEDGER_VERSION: Version = 7;
+
+/// The number of events on every event access path in the mock ledger.
+pub const MOCK_NUM_EVENTS_PER_ACCESS_PATH: u64 = 2;
+
+/// The number of committed transactions sent by every account in the mock ledger.
+pub const MOCK_NUM_TXNS_PER_ACCOUNT: u64 = 1;
+
 fn get_mock_update_to_latest_ledger(
     req: &UpdateToLatestLedgerRequest,
 ) -> UpdateToLatestLedgerResponse {
@@ -154,7 +225,7 @@ fn get_mock_update_to_latest_ledger(
     ledger_info.set_transaction_accumulator_hash(HashValue::zero().to_vec());
     ledger_info.set_consensus_data_hash(HashValue::zero().to_vec());
     ledger_info.set_consensus_block_id(HashValue::zero().to_vec());
-    ledger_info.set_version(7);
+    ledger_info.set_version(MOCK_LEDGER_VERSION);
     let mut ledger_info_with_sigs = ProtoLedgerInfoWithSignatures::new();
     ledger_info_with_sigs.set_ledger_info(ledger_info);
     resp.set_ledger_info_with_sigs(ledger_info_with_sigs);
@@ -205,11 +276,48 @@ fn get_mock_response_item(request_item: &ProtoRequestItem) -> Result<ProtoRespon
                 resp.set_account_state_with_proof(account_state_with_proof);
                 response_item.set_get_account_state_response(resp);
             }
-            RequestItem_oneof_requested_items::get_account_transaction_by_sequence_number_request(_request) => {
-                unimplemented!();
+            RequestItem_oneof_requested_items::get_account_transaction_by_sequence_number_request(request) => {
+                let mut resp = GetAccountTransactionBySequenceNumberResponse::new();
+                if request.sequence_number < MOCK_NUM_TXNS_PER_ACCOUNT {
+                    let account = AccountAddress::from_proto(request.get_account().to_vec())?;
+                    let (mut txns, _) = get_mock_txn_data(account, request.sequence_number, request.sequence_number);
+                    let signed_transaction_with_proof = types::transaction::SignedTransactionWithProof {
+                        version: 0,
+                        signed_transaction: types::transaction::SignedTransaction::from_proto(txns.remove(0))?,
+                        events: if request.fetch_events { Some(vec![]) } else { None },
+                        proof: types::proof::SignedTransactionProof::new(
+                            types::proof::AccumulatorProof::new(vec![]),
+                            get_transaction_info(),
+                        ),
+                    };
+                    resp.set_signed_transaction_with_proof(signed_transaction_with_proof.into_proto());
+                }
+                response_item.set_get_account_transaction_by_sequence_number_response(resp);
             }
-            RequestItem_oneof_requested_items::get_events_by_event_access_path_request(_request) => {
-                unimplemented!();
+            RequestItem_oneof_requested_items::get_events_by_event_access_path_request(request) => {
+                assert!(request.ascending, "Only ascending queries are supported by the mock.");
+                let access_path = types::access_path::AccessPath::from_proto(request.get_access_path().clone())?;
+                let end_event_seq_num = std::cmp::min(
+                    request.start_event_seq_num.saturating_add(request.limit),
+                    MOCK_NUM_EVENTS_PER_ACCESS_PATH,
+                );
+                let events_with_proof = (request.start_event_seq_num..end_event_seq_num)
+                    .map(|seq_num| {
+                        types::contract_event::EventWithProof::new(
+                            seq_num, /* transaction_version */
+                            0, /* event_index */
+                            types::contract_event::ContractEvent::new(access_path.clone(), seq_num, vec![]),
+                            types::proof::EventProof::new(
+                                types::proof::AccumulatorProof::new(vec![]),
+                                get_transaction_info(),
+                                types::proof::AccumulatorProof::new(vec![]),
+                            ),
+                        ).into_proto()
+                    })
+                    .collect::<Vec<_>>();
+                let mut resp = GetEventsByEventAccessPathResponse::new();
+                resp.set_events_with_proof(protobuf::RepeatedField::from_vec(events_with_proof));
+                response_item.set_get_events_by_event_access_path_response(resp);
             }
             RequestItem_oneof_requested_items::get_transactions_request(request) => {
                 let mut ret = TransactionListWithProof::new();
@@ -231,6 +339,9 @@ fn get_mock_response_item(request_item: &ProtoRequestItem) -> Result<ProtoRespon
 
                 response_item.set_get_transactions_response(resp);
             }
+            RequestItem_oneof_requested_items::get_account_states_in_range_request(_request) => {
+                unimplemented!();
+            }
         }
     }
     Ok(response_item)
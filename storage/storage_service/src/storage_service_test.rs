@@ -3,6 +3,7 @@
 
 use super::*;
 use config::config::NodeConfigHelpers;
+use futures::executor::block_on;
 use grpcio::EnvBuilder;
 use itertools::zip_eq;
 use libradb::mock_genesis::db_with_mock_genesis;
@@ -106,4 +107,25 @@ proptest! {
             prop_assert_eq!(ledger_info_with_sigs, &response_ledger_info_with_sigs);
          }
     }
+
+    #[test]
+    fn test_storage_service_subscribe_to_commits(blocks in arb_blocks_to_commit().no_shrink()) {
+        let(_tmp_dir, _server_handler, read_client, write_client) =
+            start_test_storage_with_read_write_client(/* need_to_use_genesis = */ true);
+
+        let mut commits = read_client.subscribe_to_commits().unwrap();
+        // The latest version at the time of the subscription comes first.
+        prop_assert_eq!(block_on(commits.next()).unwrap().unwrap(), 0);
+
+        let mut version = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &blocks {
+            write_client
+                .save_transactions(txns_to_commit.clone(),
+                                   version + 1, /* first_version */
+                                   Some(ledger_info_with_sigs.clone()),
+                ).unwrap();
+            version += txns_to_commit.len() as u64;
+            prop_assert_eq!(block_on(commits.next()).unwrap().unwrap(), version);
+        }
+    }
 }
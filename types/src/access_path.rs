@@ -301,6 +301,12 @@ impl AccessPath {
         Self::new(address, account_received_event_path())
     }
 
+    /// Returns true if this access path is under the same account as `prefix` and its path starts
+    /// with the path of `prefix`.
+    pub fn starts_with(&self, prefix: &AccessPath) -> bool {
+        self.address == prefix.address && self.path.starts_with(&prefix.path)
+    }
+
     pub fn resource_access_vec(tag: &StructTag, accesses: &Accesses) -> Vec<u8> {
         let mut key = vec![];
         key.push(Self::RESOURCE_TAG);
@@ -26,6 +26,35 @@ proptest! {
     fn account_state_with_proof(account_state_with_proof in any::<AccountStateWithProof>()) {
         assert_protobuf_encode_decode(&account_state_with_proof);
     }
+
+    #[test]
+    fn account_state_range_with_proof(
+        account_state_range_with_proof in any::<AccountStateRangeWithProof>()
+    ) {
+        assert_protobuf_encode_decode(&account_state_range_with_proof);
+    }
+
+    #[test]
+    fn account_state_range_next_start_key(
+        mut range in any::<AccountStateRangeWithProof>()
+    ) {
+        range.account_blobs = vec![];
+        prop_assert_eq!(range.next_start_key(), None);
+
+        let mut key = [0u8; HashValue::LENGTH];
+        key[HashValue::LENGTH - 2] = 0x12;
+        key[HashValue::LENGTH - 1] = 0xff;
+        range.account_blobs = vec![(HashValue::new(key), AccountStateBlob::from(vec![1u8]))];
+        let mut expected_key = [0u8; HashValue::LENGTH];
+        expected_key[HashValue::LENGTH - 2] = 0x13;
+        prop_assert_eq!(range.next_start_key(), Some(HashValue::new(expected_key)));
+
+        range.account_blobs = vec![(
+            HashValue::new([0xff; HashValue::LENGTH]),
+            AccountStateBlob::from(vec![1u8]),
+        )];
+        prop_assert_eq!(range.next_start_key(), None);
+    }
 }
 
 #[test]
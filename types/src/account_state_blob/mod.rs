@@ -9,7 +9,9 @@ use crate::{
     account_address::AccountAddress,
     account_config::get_account_resource_or_default,
     ledger_info::LedgerInfo,
-    proof::{verify_account_state, AccountStateProof},
+    proof::{
+        verify_account_state, verify_account_state_range, AccountStateProof, AccountStateRangeProof,
+    },
     transaction::Version,
 };
 
@@ -201,5 +203,125 @@ impl IntoProto for AccountStateWithProof {
     }
 }
 
+/// The states of all accounts whose keys (hashed account addresses) fall in a contiguous range,
+/// together with a proof that none of them is left out.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct AccountStateRangeWithProof {
+    /// The transaction version at which these account states are seen.
+    pub version: Version,
+    /// The account state blobs in the range together with their keys, sorted by key.
+    pub account_blobs: Vec<(HashValue, AccountStateBlob)>,
+    /// The proof the client can use to authenticate the range.
+    pub proof: AccountStateRangeProof,
+}
+
+impl AccountStateRangeWithProof {
+    /// Constructor.
+    pub fn new(
+        version: Version,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+        proof: AccountStateRangeProof,
+    ) -> Self {
+        Self {
+            version,
+            account_blobs,
+            proof,
+        }
+    }
+
+    /// Verifies the account state blobs with the proof, both carried by `self`.
+    ///
+    /// Two things are ensured if no error is raised:
+    ///   1. These account states exist in the ledger represented by `ledger_info` and are seen at
+    /// the time the transaction at version `version` is just committed.
+    ///   2. They are the states of all the accounts with keys from `start_key` to the last key in
+    /// `account_blobs`, and there are no more than `limit` of them. If `account_blobs` is empty,
+    /// no account with a key equal to or greater than `start_key` exists.
+    pub fn verify(
+        &self,
+        ledger_info: &LedgerInfo,
+        version: Version,
+        start_key: HashValue,
+        limit: u64,
+    ) -> Result<()> {
+        ensure!(
+            self.version == version,
+            "State version ({}) is not expected ({}).",
+            self.version,
+            version,
+        );
+        ensure!(
+            self.account_blobs.len() as u64 <= limit,
+            "Number of account states returned ({}) exceeds limit ({}).",
+            self.account_blobs.len(),
+            limit,
+        );
+
+        verify_account_state_range(
+            ledger_info,
+            version,
+            start_key,
+            &self.account_blobs,
+            &self.proof,
+        )
+    }
+
+    /// Returns the key the next range should start with in order to continue right after this
+    /// one, or `None` if there is nothing after this range, i.e. `account_blobs` is empty or ends
+    /// with the largest possible key.
+    pub fn next_start_key(&self) -> Option<HashValue> {
+        let (last_key, _blob) = self.account_blobs.last()?;
+        let mut buf = last_key.to_vec();
+        for byte in buf.iter_mut().rev() {
+            if *byte == std::u8::MAX {
+                *byte = 0;
+            } else {
+                *byte += 1;
+                return Some(HashValue::from_slice(&buf).expect("Length is always correct."));
+            }
+        }
+        None
+    }
+}
+
+impl FromProto for AccountStateRangeWithProof {
+    type ProtoType = crate::proto::account_state_blob::AccountStateRangeWithProof;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(AccountStateRangeWithProof {
+            version: object.get_version(),
+            account_blobs: object
+                .take_account_blobs()
+                .into_iter()
+                .map(|mut keyed_blob| {
+                    Ok((
+                        HashValue::from_slice(keyed_blob.get_key())?,
+                        AccountStateBlob::from_proto(keyed_blob.take_blob())?,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            proof: AccountStateRangeProof::from_proto(object.take_proof())?,
+        })
+    }
+}
+
+impl IntoProto for AccountStateRangeWithProof {
+    type ProtoType = crate::proto::account_state_blob::AccountStateRangeWithProof;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_version(self.version);
+        for (key, blob) in self.account_blobs {
+            let mut keyed_blob = crate::proto::account_state_blob::KeyedAccountStateBlob::new();
+            keyed_blob.set_key(key.to_vec());
+            keyed_blob.set_blob(blob.into_proto());
+            out.mut_account_blobs().push(keyed_blob);
+        }
+        out.set_proof(self.proof.into_proto());
+        out
+    }
+}
+
 #[cfg(test)]
 mod account_state_blob_test;
@@ -3,8 +3,9 @@
 
 #![allow(clippy::unit_arg)]
 use crate::{
-    access_path::AccessPath,
+    access_path::{AccessPath, Accesses},
     account_config::AccountEvent,
+    language_storage::StructTag,
     ledger_info::LedgerInfo,
     proof::{verify_event, EventProof},
     transaction::Version,
@@ -191,3 +192,67 @@ impl EventWithProof {
         Ok(())
     }
 }
+
+/// Selects events by where they are emitted to. An event matches if it matches all the criteria
+/// that are set, so the default filter matches all events.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct EventFilter {
+    /// Only the events on access paths starting with this one.
+    pub access_path_prefix: Option<AccessPath>,
+    /// Only the events emitted to the event handles in resources of this type, thus by the module
+    /// declaring it.
+    pub resource_type: Option<StructTag>,
+}
+
+impl EventFilter {
+    /// Returns true if `event` matches all the criteria of the filter.
+    pub fn matches(&self, event: &ContractEvent) -> bool {
+        self.access_path_prefix
+            .as_ref()
+            .map_or(true, |prefix| event.access_path().starts_with(prefix))
+            && self.resource_type.as_ref().map_or(true, |resource_type| {
+                event
+                    .access_path()
+                    .path
+                    .starts_with(&AccessPath::resource_access_vec(
+                        resource_type,
+                        &Accesses::empty(),
+                    ))
+            })
+    }
+}
+
+impl FromProto for EventFilter {
+    type ProtoType = crate::proto::events::EventFilter;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            access_path_prefix: object
+                .access_path_prefix
+                .take()
+                .map(AccessPath::from_proto)
+                .transpose()?,
+            resource_type: object
+                .resource_type
+                .take()
+                .map(StructTag::from_proto)
+                .transpose()?,
+        })
+    }
+}
+
+impl IntoProto for EventFilter {
+    type ProtoType = crate::proto::events::EventFilter;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        if let Some(access_path_prefix) = self.access_path_prefix {
+            out.set_access_path_prefix(access_path_prefix.into_proto());
+        }
+        if let Some(resource_type) = self.resource_type {
+            out.set_resource_type(resource_type.into_proto());
+        }
+        out
+    }
+}
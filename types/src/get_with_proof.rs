@@ -9,20 +9,26 @@ use crate::{
     account_config::{
         account_received_event_path, account_sent_event_path, get_account_resource_or_default,
     },
-    account_state_blob::{AccountStateBlob, AccountStateWithProof},
-    contract_event::EventWithProof,
+    account_state_blob::{AccountStateBlob, AccountStateRangeWithProof, AccountStateWithProof},
+    contract_event::{EventFilter, EventWithProof},
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{verify_transaction_accumulator_consistency, AccumulatorConsistencyProof},
     proto::get_with_proof::{
-        GetAccountStateRequest, GetAccountStateResponse,
-        GetAccountTransactionBySequenceNumberRequest,
-        GetAccountTransactionBySequenceNumberResponse, GetEventsByEventAccessPathRequest,
-        GetEventsByEventAccessPathResponse, GetTransactionsRequest, GetTransactionsResponse,
+        GetAccountStateRequest, GetAccountStateResponse, GetAccountStatesInRangeRequest,
+        GetAccountStatesInRangeResponse, GetAccountTransactionBySequenceNumberRequest,
+        GetAccountTransactionBySequenceNumberResponse, GetAccountTransactionsRequest,
+        GetAccountTransactionsResponse, GetAccumulatorConsistencyProofRequest,
+        GetAccumulatorConsistencyProofResponse, GetEventsByEventAccessPathRequest,
+        GetEventsByEventAccessPathResponse, GetEventsByVersionRangeRequest,
+        GetEventsByVersionRangeResponse, GetTransactionsRequest, GetTransactionsResponse,
+    },
+    transaction::{
+        EventListWithProof, SignedTransactionWithProof, TransactionListWithProof, Version,
     },
-    transaction::{SignedTransactionWithProof, TransactionListWithProof, Version},
     validator_change::ValidatorChangeEventWithProof,
     validator_verifier::ValidatorVerifier,
 };
-use crypto::hash::CryptoHash;
+use crypto::{hash::CryptoHash, HashValue};
 use failure::prelude::*;
 use nextgen_crypto::*;
 #[cfg(any(test, feature = "testing"))]
@@ -192,6 +198,27 @@ fn verify_response_item(
             signed_transaction_with_proof.as_ref(),
             proof_of_current_sequence_number.as_ref(),
         ),
+        // GetAccountTransactions
+        (
+            RequestItem::GetAccountTransactions {
+                account,
+                start_sequence_number,
+                limit,
+                fetch_events,
+            },
+            ResponseItem::GetAccountTransactions {
+                signed_transactions_with_proof,
+                proof_of_current_sequence_number,
+            },
+        ) => verify_get_account_txns_resp(
+            ledger_info,
+            *account,
+            *start_sequence_number,
+            *limit,
+            *fetch_events,
+            signed_transactions_with_proof,
+            proof_of_current_sequence_number.as_ref(),
+        ),
         // GetEventsByEventAccessPath
         (
             RequestItem::GetEventsByEventAccessPath {
@@ -213,6 +240,23 @@ fn verify_response_item(
             events_with_proof,
             proof_of_latest_event.as_ref(),
         ),
+        // GetEventsByVersionRange
+        (
+            RequestItem::GetEventsByVersionRange {
+                start_version,
+                limit,
+                filter,
+            },
+            ResponseItem::GetEventsByVersionRange {
+                event_list_with_proof,
+            },
+        ) => verify_get_events_by_version_range_resp(
+            ledger_info,
+            *start_version,
+            *limit,
+            filter,
+            event_list_with_proof,
+        ),
         // GetTransactions
         (
             RequestItem::GetTransactions {
@@ -230,6 +274,33 @@ fn verify_response_item(
             *fetch_events,
             txn_list_with_proof,
         ),
+        // GetAccountStatesInRange
+        (
+            RequestItem::GetAccountStatesInRange { start_key, limit },
+            ResponseItem::GetAccountStatesInRange {
+                account_state_range_with_proof,
+            },
+        ) => account_state_range_with_proof.verify(
+            ledger_info,
+            ledger_info.version(),
+            *start_key,
+            *limit,
+        ),
+        // GetAccumulatorConsistencyProof
+        (
+            RequestItem::GetAccumulatorConsistencyProof {
+                known_version,
+                known_accumulator_hash,
+            },
+            ResponseItem::GetAccumulatorConsistencyProof {
+                accumulator_consistency_proof,
+            },
+        ) => verify_get_accumulator_consistency_proof_resp(
+            ledger_info,
+            *known_version,
+            *known_accumulator_hash,
+            accumulator_consistency_proof,
+        ),
         // Request-response item types mismatch.
         _ => bail!(
             "RequestItem/ResponseItem types mismatch. request: {:?}, response: {:?}",
@@ -282,6 +353,75 @@ fn verify_get_txn_by_seq_num_resp(
     }
 }
 
+fn verify_get_account_txns_resp(
+    ledger_info: &LedgerInfo,
+    req_account: AccountAddress,
+    req_start_sequence_number: u64,
+    req_limit: u64,
+    req_fetch_events: bool,
+    signed_transactions_with_proof: &[SignedTransactionWithProof],
+    proof_of_current_sequence_number: Option<&AccountStateWithProof>,
+) -> Result<()> {
+    let num_txns = signed_transactions_with_proof.len() as u64;
+    match proof_of_current_sequence_number {
+        Some(proof_of_current_sequence_number) => {
+            proof_of_current_sequence_number.verify(
+                ledger_info,
+                ledger_info.version(),
+                req_account,
+            )?;
+            let sequence_number_in_ledger =
+                get_account_resource_or_default(&proof_of_current_sequence_number.blob)?
+                    .sequence_number();
+            let expected_num_txns = cmp::min(
+                req_limit,
+                sequence_number_in_ledger.saturating_sub(req_start_sequence_number),
+            );
+            ensure!(
+                num_txns == expected_num_txns,
+                "Bad GetAccountTransactions response. Expecting {} transactions, got {}. Seq num \
+                 requested: {}, latest seq num in ledger: {}.",
+                expected_num_txns,
+                num_txns,
+                req_start_sequence_number,
+                sequence_number_in_ledger,
+            );
+        }
+        None => ensure!(
+            num_txns == req_limit,
+            "Bad GetAccountTransactions response. Expecting {} transactions or the proof of the \
+             current sequence number, got {} transactions.",
+            req_limit,
+            num_txns,
+        ),
+    }
+
+    let end_sequence_number = req_start_sequence_number
+        .checked_add(num_txns)
+        .ok_or_else(|| format_err!("Sequence number overflow."))?;
+    itertools::zip_eq(
+        signed_transactions_with_proof,
+        req_start_sequence_number..end_sequence_number,
+    )
+    .map(|(signed_transaction_with_proof, sequence_number)| {
+        ensure!(
+            req_fetch_events == signed_transaction_with_proof.events.is_some(),
+            "Bad GetAccountTransactions response. Events requested: {}, events returned: {}.",
+            req_fetch_events,
+            signed_transaction_with_proof.events.is_some(),
+        );
+        signed_transaction_with_proof.verify(
+            ledger_info,
+            signed_transaction_with_proof.version,
+            req_account,
+            sequence_number,
+        )
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+    Ok(())
+}
+
 fn verify_get_events_by_access_path_resp(
     ledger_info: &LedgerInfo,
     req_access_path: &AccessPath,
@@ -340,6 +480,36 @@ fn verify_get_events_by_access_path_resp(
     Ok(())
 }
 
+fn verify_get_events_by_version_range_resp(
+    ledger_info: &LedgerInfo,
+    req_start_version: Version,
+    req_limit: u64,
+    req_filter: &EventFilter,
+    event_list_with_proof: &EventListWithProof,
+) -> Result<()> {
+    let expected_first_version = if req_start_version > ledger_info.version() || req_limit == 0 {
+        None
+    } else {
+        Some(req_start_version)
+    };
+    event_list_with_proof.verify(ledger_info, expected_first_version)?;
+
+    // The range ends at the transaction emitting the `limit`-th matching event.
+    let mut num_matching_events = 0;
+    for events in &event_list_with_proof.events {
+        ensure!(
+            num_matching_events < req_limit,
+            "Bad GetEventsByVersionRange response. Transactions returned after {} matching \
+             events, expecting at most {}.",
+            num_matching_events,
+            req_limit,
+        );
+        num_matching_events += events.iter().filter(|e| req_filter.matches(e)).count() as u64;
+    }
+
+    Ok(())
+}
+
 fn get_next_event_seq_num(
     account_state_blob: &Option<AccountStateBlob>,
     access_path: &AccessPath,
@@ -384,6 +554,27 @@ fn verify_get_txns_resp(
     }
 }
 
+fn verify_get_accumulator_consistency_proof_resp(
+    ledger_info: &LedgerInfo,
+    req_known_version: Version,
+    req_known_accumulator_hash: HashValue,
+    accumulator_consistency_proof: &AccumulatorConsistencyProof,
+) -> Result<()> {
+    ensure!(
+        req_known_version <= ledger_info.version(),
+        "Known version {} is newer than LedgerInfo version {}.",
+        req_known_version,
+        ledger_info.version(),
+    );
+    verify_transaction_accumulator_consistency(
+        req_known_version + 1,
+        req_known_accumulator_hash,
+        ledger_info.version() + 1,
+        ledger_info.transaction_accumulator_hash(),
+        accumulator_consistency_proof,
+    )
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
 pub enum RequestItem {
@@ -407,6 +598,25 @@ pub enum RequestItem {
         limit: u64,
         fetch_events: bool,
     },
+    GetAccountStatesInRange {
+        start_key: HashValue,
+        limit: u64,
+    },
+    GetAccumulatorConsistencyProof {
+        known_version: Version,
+        known_accumulator_hash: HashValue,
+    },
+    GetAccountTransactions {
+        account: AccountAddress,
+        start_sequence_number: u64,
+        limit: u64,
+        fetch_events: bool,
+    },
+    GetEventsByVersionRange {
+        start_version: Version,
+        limit: u64,
+        filter: EventFilter,
+    },
 }
 
 impl FromProto for RequestItem {
@@ -453,6 +663,45 @@ impl FromProto for RequestItem {
                 limit,
                 fetch_events,
             }
+        } else if object.has_get_account_states_in_range_request() {
+            let req = object.get_get_account_states_in_range_request();
+            let start_key = HashValue::from_slice(req.get_start_key())?;
+            let limit = req.get_limit();
+
+            RequestItem::GetAccountStatesInRange { start_key, limit }
+        } else if object.has_get_accumulator_consistency_proof_request() {
+            let req = object.get_get_accumulator_consistency_proof_request();
+            let known_version = req.get_known_version();
+            let known_accumulator_hash = HashValue::from_slice(req.get_known_accumulator_hash())?;
+
+            RequestItem::GetAccumulatorConsistencyProof {
+                known_version,
+                known_accumulator_hash,
+            }
+        } else if object.has_get_account_transactions_request() {
+            let mut req = object.take_get_account_transactions_request();
+            let account = AccountAddress::from_proto(req.take_account())?;
+            let start_sequence_number = req.get_start_sequence_number();
+            let limit = req.get_limit();
+            let fetch_events = req.get_fetch_events();
+
+            RequestItem::GetAccountTransactions {
+                account,
+                start_sequence_number,
+                limit,
+                fetch_events,
+            }
+        } else if object.has_get_events_by_version_range_request() {
+            let mut req = object.take_get_events_by_version_range_request();
+            let start_version = req.get_start_version();
+            let limit = req.get_limit();
+            let filter = EventFilter::from_proto(req.take_filter())?;
+
+            RequestItem::GetEventsByVersionRange {
+                start_version,
+                limit,
+                filter,
+            }
         } else {
             bail!("Unknown RequestItem type.")
         })
@@ -508,6 +757,49 @@ impl IntoProto for RequestItem {
 
                 out.set_get_transactions_request(req);
             }
+            RequestItem::GetAccountStatesInRange { start_key, limit } => {
+                let mut req = GetAccountStatesInRangeRequest::new();
+                req.set_start_key(start_key.to_vec());
+                req.set_limit(limit);
+
+                out.set_get_account_states_in_range_request(req);
+            }
+            RequestItem::GetAccumulatorConsistencyProof {
+                known_version,
+                known_accumulator_hash,
+            } => {
+                let mut req = GetAccumulatorConsistencyProofRequest::new();
+                req.set_known_version(known_version);
+                req.set_known_accumulator_hash(known_accumulator_hash.to_vec());
+
+                out.set_get_accumulator_consistency_proof_request(req);
+            }
+            RequestItem::GetAccountTransactions {
+                account,
+                start_sequence_number,
+                limit,
+                fetch_events,
+            } => {
+                let mut req = GetAccountTransactionsRequest::new();
+                req.set_account(account.into_proto());
+                req.set_start_sequence_number(start_sequence_number);
+                req.set_limit(limit);
+                req.set_fetch_events(fetch_events);
+
+                out.set_get_account_transactions_request(req);
+            }
+            RequestItem::GetEventsByVersionRange {
+                start_version,
+                limit,
+                filter,
+            } => {
+                let mut req = GetEventsByVersionRangeRequest::new();
+                req.set_start_version(start_version);
+                req.set_limit(limit);
+                req.set_filter(filter.into_proto());
+
+                out.set_get_events_by_version_range_request(req);
+            }
         }
         out
     }
@@ -532,6 +824,19 @@ pub enum ResponseItem {
     GetTransactions {
         txn_list_with_proof: TransactionListWithProof,
     },
+    GetAccountStatesInRange {
+        account_state_range_with_proof: AccountStateRangeWithProof,
+    },
+    GetAccumulatorConsistencyProof {
+        accumulator_consistency_proof: AccumulatorConsistencyProof,
+    },
+    GetAccountTransactions {
+        signed_transactions_with_proof: Vec<SignedTransactionWithProof>,
+        proof_of_current_sequence_number: Option<AccountStateWithProof>,
+    },
+    GetEventsByVersionRange {
+        event_list_with_proof: EventListWithProof,
+    },
 }
 
 impl ResponseItem {
@@ -582,6 +887,53 @@ impl ResponseItem {
             _ => bail!("Not ResponseItem::GetTransactions."),
         }
     }
+
+    pub fn into_get_account_states_in_range_response(self) -> Result<AccountStateRangeWithProof> {
+        match self {
+            ResponseItem::GetAccountStatesInRange {
+                account_state_range_with_proof,
+            } => Ok(account_state_range_with_proof),
+            _ => bail!("Not ResponseItem::GetAccountStatesInRange."),
+        }
+    }
+
+    pub fn into_get_accumulator_consistency_proof_response(
+        self,
+    ) -> Result<AccumulatorConsistencyProof> {
+        match self {
+            ResponseItem::GetAccumulatorConsistencyProof {
+                accumulator_consistency_proof,
+            } => Ok(accumulator_consistency_proof),
+            _ => bail!("Not ResponseItem::GetAccumulatorConsistencyProof."),
+        }
+    }
+
+    pub fn into_get_account_txns_response(
+        self,
+    ) -> Result<(
+        Vec<SignedTransactionWithProof>,
+        Option<AccountStateWithProof>,
+    )> {
+        match self {
+            ResponseItem::GetAccountTransactions {
+                signed_transactions_with_proof,
+                proof_of_current_sequence_number,
+            } => Ok((
+                signed_transactions_with_proof,
+                proof_of_current_sequence_number,
+            )),
+            _ => bail!("Not ResponseItem::GetAccountTransactions."),
+        }
+    }
+
+    pub fn into_get_events_by_version_range_response(self) -> Result<EventListWithProof> {
+        match self {
+            ResponseItem::GetEventsByVersionRange {
+                event_list_with_proof,
+            } => Ok(event_list_with_proof),
+            _ => bail!("Not ResponseItem::GetEventsByVersionRange."),
+        }
+    }
 }
 
 impl FromProto for ResponseItem {
@@ -642,6 +994,47 @@ impl FromProto for ResponseItem {
             ResponseItem::GetTransactions {
                 txn_list_with_proof,
             }
+        } else if object.has_get_account_states_in_range_response() {
+            let mut res = object.take_get_account_states_in_range_response();
+            let account_state_range_with_proof =
+                AccountStateRangeWithProof::from_proto(res.take_account_state_range_with_proof())?;
+
+            ResponseItem::GetAccountStatesInRange {
+                account_state_range_with_proof,
+            }
+        } else if object.has_get_accumulator_consistency_proof_response() {
+            let mut res = object.take_get_accumulator_consistency_proof_response();
+            let accumulator_consistency_proof =
+                AccumulatorConsistencyProof::from_proto(res.take_accumulator_consistency_proof())?;
+
+            ResponseItem::GetAccumulatorConsistencyProof {
+                accumulator_consistency_proof,
+            }
+        } else if object.has_get_account_transactions_response() {
+            let mut res = object.take_get_account_transactions_response();
+            let signed_transactions_with_proof = res
+                .take_signed_transactions_with_proof()
+                .into_iter()
+                .map(SignedTransactionWithProof::from_proto)
+                .collect::<Result<Vec<_>>>()?;
+            let proof_of_current_sequence_number = res
+                .proof_of_current_sequence_number
+                .take()
+                .map(AccountStateWithProof::from_proto)
+                .transpose()?;
+
+            ResponseItem::GetAccountTransactions {
+                signed_transactions_with_proof,
+                proof_of_current_sequence_number,
+            }
+        } else if object.has_get_events_by_version_range_response() {
+            let mut res = object.take_get_events_by_version_range_response();
+            let event_list_with_proof =
+                EventListWithProof::from_proto(res.take_event_list_with_proof())?;
+
+            ResponseItem::GetEventsByVersionRange {
+                event_list_with_proof,
+            }
         } else {
             bail!("Unknown ResponseItem type.")
         })
@@ -702,6 +1095,47 @@ impl IntoProto for ResponseItem {
 
                 out.set_get_transactions_response(res)
             }
+            ResponseItem::GetAccountStatesInRange {
+                account_state_range_with_proof,
+            } => {
+                let mut res = GetAccountStatesInRangeResponse::new();
+                res.set_account_state_range_with_proof(account_state_range_with_proof.into_proto());
+
+                out.set_get_account_states_in_range_response(res)
+            }
+            ResponseItem::GetAccumulatorConsistencyProof {
+                accumulator_consistency_proof,
+            } => {
+                let mut res = GetAccumulatorConsistencyProofResponse::new();
+                res.set_accumulator_consistency_proof(accumulator_consistency_proof.into_proto());
+
+                out.set_get_accumulator_consistency_proof_response(res)
+            }
+            ResponseItem::GetAccountTransactions {
+                signed_transactions_with_proof,
+                proof_of_current_sequence_number,
+            } => {
+                let mut res = GetAccountTransactionsResponse::new();
+                res.set_signed_transactions_with_proof(::protobuf::RepeatedField::from_vec(
+                    signed_transactions_with_proof
+                        .into_iter()
+                        .map(SignedTransactionWithProof::into_proto)
+                        .collect(),
+                ));
+                if let Some(p) = proof_of_current_sequence_number {
+                    res.set_proof_of_current_sequence_number(p.into_proto());
+                }
+
+                out.set_get_account_transactions_response(res)
+            }
+            ResponseItem::GetEventsByVersionRange {
+                event_list_with_proof,
+            } => {
+                let mut res = GetEventsByVersionRangeResponse::new();
+                res.set_event_list_with_proof(event_list_with_proof.into_proto());
+
+                out.set_get_events_by_version_range_response(res)
+            }
         }
         out
     }
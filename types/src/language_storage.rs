@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    access_path::AccessPath, account_address::AccountAddress,
-    proto::language_storage::ModuleId as ProtoModuleId,
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    proto::language_storage::{ModuleId as ProtoModuleId, StructTag as ProtoStructTag},
 };
 use canonical_serialization::{
     CanonicalDeserialize, CanonicalDeserializer, CanonicalSerialize, CanonicalSerializer,
@@ -25,6 +26,41 @@ pub struct StructTag {
     pub type_params: Vec<StructTag>,
 }
 
+impl FromProto for StructTag {
+    type ProtoType = ProtoStructTag;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            address: AccountAddress::from_proto(object.take_address())?,
+            module: object.take_module(),
+            name: object.take_name(),
+            type_params: object
+                .take_type_params()
+                .into_iter()
+                .map(StructTag::from_proto)
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
+impl IntoProto for StructTag {
+    type ProtoType = ProtoStructTag;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_address(self.address.into_proto());
+        out.set_module(self.module);
+        out.set_name(self.name);
+        out.set_type_params(::protobuf::RepeatedField::from_vec(
+            self.type_params
+                .into_iter()
+                .map(StructTag::into_proto)
+                .collect(),
+        ));
+        out
+    }
+}
+
 /// Represents the intitial key into global storage where we first index by the address, and then
 /// the struct tag
 #[derive(Serialize, Deserialize, Debug, PartialEq, Hash, Eq, Clone, PartialOrd, Ord)]
@@ -241,6 +241,151 @@ impl IntoProto for SparseMerkleProof {
     }
 }
 
+/// A proof that can be used to authenticate a contiguous range of leaves in a Sparse Merkle Tree,
+/// given the key the range starts at and trusted root hash. It shows that no leaf with a key
+/// between the starting key and the last leaf in the range is left out. If the range is empty, it
+/// shows that there is no leaf with a key equal to or greater than the starting key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseMerkleRangeProof {
+    /// The proof for the starting key of the range. Its leaf, if present, is either the first leaf
+    /// in the range or, when its key is smaller than the starting key, the only leaf in the
+    /// subtree the starting key would have been placed in. All left siblings on this path
+    /// authenticate the leaves to the left of the range.
+    left_boundary_proof: SparseMerkleProof,
+
+    /// All siblings on the path from root to the last leaf in the range, including the default
+    /// ones. Siblings near the root are at the beginning of the vector. All right siblings on this
+    /// path authenticate the leaves to the right of the range. This is empty if the range is
+    /// empty.
+    right_siblings: Vec<HashValue>,
+}
+
+impl SparseMerkleRangeProof {
+    /// Constructs a new `SparseMerkleRangeProof` using the proof for the starting key and the
+    /// siblings of the last leaf in the range.
+    pub fn new(left_boundary_proof: SparseMerkleProof, right_siblings: Vec<HashValue>) -> Self {
+        SparseMerkleRangeProof {
+            left_boundary_proof,
+            right_siblings,
+        }
+    }
+
+    /// Returns the proof for the starting key of the range.
+    pub fn left_boundary_proof(&self) -> &SparseMerkleProof {
+        &self.left_boundary_proof
+    }
+
+    /// Returns the list of siblings on the path to the last leaf in the range.
+    pub fn right_siblings(&self) -> &[HashValue] {
+        &self.right_siblings
+    }
+}
+
+impl FromProto for SparseMerkleRangeProof {
+    type ProtoType = crate::proto::proof::SparseMerkleRangeProof;
+
+    fn from_proto(mut proto_proof: Self::ProtoType) -> Result<Self> {
+        let left_boundary_proof =
+            SparseMerkleProof::from_proto(proto_proof.take_left_boundary_proof())?;
+        let right_siblings = proto_proof
+            .take_right_siblings()
+            .into_iter()
+            .map(|hash_bytes| HashValue::from_slice(&hash_bytes))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(SparseMerkleRangeProof::new(
+            left_boundary_proof,
+            right_siblings,
+        ))
+    }
+}
+
+impl IntoProto for SparseMerkleRangeProof {
+    type ProtoType = crate::proto::proof::SparseMerkleRangeProof;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto_proof = Self::ProtoType::new();
+        proto_proof.set_left_boundary_proof(self.left_boundary_proof.into_proto());
+        for sibling in self.right_siblings {
+            proto_proof.mut_right_siblings().push(sibling.to_vec());
+        }
+        proto_proof
+    }
+}
+
+/// A proof that can be used to show that an accumulator is consistent with a smaller one, i.e. it
+/// is the result of appending leaves to the smaller one, given trusted root hashes of both. The
+/// frozen subtrees of the smaller accumulator authenticate its leaves, and combined with the right
+/// siblings of the rightmost frozen subtree they yield the root hash of the bigger accumulator.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccumulatorConsistencyProof {
+    /// The root hashes of the frozen subtrees of the smaller accumulator, from left to right.
+    frozen_subtree_roots: Vec<HashValue>,
+
+    /// The right siblings on the path from the rightmost frozen subtree of the smaller accumulator
+    /// to the root of the bigger one, including the default ones. Siblings near the root are at
+    /// the beginning of the vector.
+    right_siblings: Vec<HashValue>,
+}
+
+impl AccumulatorConsistencyProof {
+    /// Constructs a new `AccumulatorConsistencyProof` using the frozen subtree roots of the
+    /// smaller accumulator and the right siblings on the path to the bigger one.
+    pub fn new(frozen_subtree_roots: Vec<HashValue>, right_siblings: Vec<HashValue>) -> Self {
+        AccumulatorConsistencyProof {
+            frozen_subtree_roots,
+            right_siblings,
+        }
+    }
+
+    /// Returns the list of frozen subtree roots of the smaller accumulator.
+    pub fn frozen_subtree_roots(&self) -> &[HashValue] {
+        &self.frozen_subtree_roots
+    }
+
+    /// Returns the list of right siblings on the path to the root of the bigger accumulator.
+    pub fn right_siblings(&self) -> &[HashValue] {
+        &self.right_siblings
+    }
+}
+
+impl FromProto for AccumulatorConsistencyProof {
+    type ProtoType = crate::proto::proof::AccumulatorConsistencyProof;
+
+    fn from_proto(mut proto_proof: Self::ProtoType) -> Result<Self> {
+        let frozen_subtree_roots = proto_proof
+            .take_frozen_subtree_roots()
+            .into_iter()
+            .map(|hash_bytes| HashValue::from_slice(&hash_bytes))
+            .collect::<Result<Vec<_>>>()?;
+        let right_siblings = proto_proof
+            .take_right_siblings()
+            .into_iter()
+            .map(|hash_bytes| HashValue::from_slice(&hash_bytes))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(AccumulatorConsistencyProof::new(
+            frozen_subtree_roots,
+            right_siblings,
+        ))
+    }
+}
+
+impl IntoProto for AccumulatorConsistencyProof {
+    type ProtoType = crate::proto::proof::AccumulatorConsistencyProof;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto_proof = Self::ProtoType::new();
+        for root in self.frozen_subtree_roots {
+            proto_proof.mut_frozen_subtree_roots().push(root.to_vec());
+        }
+        for sibling in self.right_siblings {
+            proto_proof.mut_right_siblings().push(sibling.to_vec());
+        }
+        proto_proof
+    }
+}
+
 /// The complete proof used to authenticate a `SignedTransaction` object.  This structure consists
 /// of an `AccumulatorProof` from `LedgerInfo` to `TransactionInfo` the verifier needs to verify
 /// the correctness of the `TransactionInfo` object, and the `TransactionInfo` object that is
@@ -328,6 +473,55 @@ impl AccountStateProof {
     }
 }
 
+/// The complete proof used to authenticate the states of a range of accounts. This structure
+/// consists of the `AccumulatorProof` from `LedgerInfo` to `TransactionInfo`, the
+/// `TransactionInfo` object and the `SparseMerkleRangeProof` from state root to the accounts.
+#[derive(Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[ProtoType(crate::proto::proof::AccountStateRangeProof)]
+pub struct AccountStateRangeProof {
+    /// The accumulator proof from ledger info root to leaf that authenticates the hash of the
+    /// `TransactionInfo` object.
+    ledger_info_to_transaction_info_proof: AccumulatorProof,
+
+    /// The `TransactionInfo` object at the leaf of the accumulator.
+    transaction_info: TransactionInfo,
+
+    /// The sparse merkle range proof from state root to the account states.
+    transaction_info_to_account_range_proof: SparseMerkleRangeProof,
+}
+
+impl AccountStateRangeProof {
+    /// Constructs a new `AccountStateRangeProof` using given
+    /// `ledger_info_to_transaction_info_proof`, `transaction_info` and
+    /// `transaction_info_to_account_range_proof`.
+    pub fn new(
+        ledger_info_to_transaction_info_proof: AccumulatorProof,
+        transaction_info: TransactionInfo,
+        transaction_info_to_account_range_proof: SparseMerkleRangeProof,
+    ) -> Self {
+        AccountStateRangeProof {
+            ledger_info_to_transaction_info_proof,
+            transaction_info,
+            transaction_info_to_account_range_proof,
+        }
+    }
+
+    /// Returns the `ledger_info_to_transaction_info_proof` object in this proof.
+    pub fn ledger_info_to_transaction_info_proof(&self) -> &AccumulatorProof {
+        &self.ledger_info_to_transaction_info_proof
+    }
+
+    /// Returns the `transaction_info` object in this proof.
+    pub fn transaction_info(&self) -> &TransactionInfo {
+        &self.transaction_info
+    }
+
+    /// Returns the `transaction_info_to_account_range_proof` object in this proof.
+    pub fn transaction_info_to_account_range_proof(&self) -> &SparseMerkleRangeProof {
+        &self.transaction_info_to_account_range_proof
+    }
+}
+
 /// The complete proof used to authenticate a contract event. This structure consists of the
 /// `AccumulatorProof` from `LedgerInfo` to `TransactionInfo`, the `TransactionInfo` object and the
 /// `AccumulatorProof` from event accumulator root to the event.
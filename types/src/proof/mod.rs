@@ -17,7 +17,11 @@ use crate::{
     account_state_blob::AccountStateBlob,
     contract_event::ContractEvent,
     ledger_info::LedgerInfo,
-    transaction::{TransactionInfo, TransactionListWithProof, Version},
+    proof::{
+        position::{FrozenSubTreeIterator, Position},
+        treebits::NodeDirection,
+    },
+    transaction::{EventListWithProof, TransactionInfo, TransactionListWithProof, Version},
 };
 use crypto::{
     hash::{
@@ -31,7 +35,8 @@ use failure::prelude::*;
 use std::{collections::VecDeque, marker::PhantomData};
 
 pub use crate::proof::definition::{
-    AccountStateProof, AccumulatorProof, EventProof, SignedTransactionProof, SparseMerkleProof,
+    AccountStateProof, AccountStateRangeProof, AccumulatorConsistencyProof, AccumulatorProof,
+    EventProof, SignedTransactionProof, SparseMerkleProof, SparseMerkleRangeProof,
 };
 
 /// Verifies that a `SignedTransaction` with hash value of `signed_transaction_hash`
@@ -100,6 +105,40 @@ pub fn verify_account_state(
     Ok(())
 }
 
+/// Verifies that `account_blobs`, a list of account states together with their keys (hashed
+/// account addresses) sorted by key, are the states of all accounts at version `state_version`
+/// with keys in the range from `start_key` to the last key in the list, both inclusive. If
+/// `account_blobs` is empty, verifies that no account with a key equal to or greater than
+/// `start_key` exists.
+pub fn verify_account_state_range(
+    ledger_info: &LedgerInfo,
+    state_version: Version,
+    start_key: HashValue,
+    account_blobs: &[(HashValue, AccountStateBlob)],
+    account_state_range_proof: &AccountStateRangeProof,
+) -> Result<()> {
+    let transaction_info = account_state_range_proof.transaction_info();
+
+    let leaves = account_blobs
+        .iter()
+        .map(|(key, blob)| (*key, blob.hash()))
+        .collect::<Vec<_>>();
+    verify_sparse_merkle_range(
+        transaction_info.state_root_hash(),
+        start_key,
+        &leaves,
+        account_state_range_proof.transaction_info_to_account_range_proof(),
+    )?;
+
+    verify_transaction_info(
+        ledger_info,
+        state_version,
+        transaction_info,
+        account_state_range_proof.ledger_info_to_transaction_info_proof(),
+    )?;
+    Ok(())
+}
+
 /// Verifies that a given event is correct using provided proof.
 pub(crate) fn verify_event(
     ledger_info: &LedgerInfo,
@@ -151,8 +190,95 @@ pub(crate) fn verify_transaction_list(
             num_txns,
             event_lists.len(),
         );
+        // Verify event root hashes match what is carried on the transaction infos.
+        verify_event_root_hashes(
+            event_lists,
+            transaction_and_infos
+                .iter()
+                .map(|(_txn, txn_info)| txn_info),
+        )?;
     }
 
+    // Get the hashes of all nodes at the accumulator leaf level.
+    let hashes = transaction_and_infos
+        .iter()
+        .map(|(txn, txn_info)| {
+            // Verify all transaction_infos and signed_transactions are consistent.
+            ensure!(
+                txn.hash() == txn_info.signed_transaction_hash(),
+                "Some hash of signed transaction does not match the corresponding transaction info in proof"
+            );
+            Ok(txn_info.hash())
+        })
+        .collect::<Result<VecDeque<_>>>()?;
+
+    verify_transaction_info_range(
+        ledger_info,
+        first_transaction_version,
+        hashes,
+        first_proof,
+        last_proof,
+    )
+}
+
+pub(crate) fn verify_event_list(
+    ledger_info: &LedgerInfo,
+    event_list_with_proof: &EventListWithProof,
+) -> Result<()> {
+    let (transaction_infos, event_lists) = (
+        &event_list_with_proof.transaction_infos,
+        &event_list_with_proof.events,
+    );
+    ensure!(
+        transaction_infos.len() == event_lists.len(),
+        "Number of the event lists doesn't match that of the transaction infos: {} vs {}",
+        transaction_infos.len(),
+        event_lists.len(),
+    );
+    verify_event_root_hashes(event_lists, transaction_infos.iter())?;
+
+    verify_transaction_info_range(
+        ledger_info,
+        event_list_with_proof.first_transaction_version,
+        transaction_infos.iter().map(CryptoHash::hash).collect(),
+        event_list_with_proof.proof_of_first_transaction.as_ref(),
+        event_list_with_proof.proof_of_last_transaction.as_ref(),
+    )
+}
+
+/// Verifies that the event root hash carried on each transaction info is the root hash of the
+/// accumulator of the corresponding list of events.
+fn verify_event_root_hashes<'a>(
+    event_lists: &[Vec<ContractEvent>],
+    transaction_infos: impl Iterator<Item = &'a TransactionInfo>,
+) -> Result<()> {
+    itertools::zip_eq(event_lists, transaction_infos)
+        .map(|(events, txn_info)| {
+            let event_hashes: Vec<_> = events.iter().map(ContractEvent::hash).collect();
+            let event_root_hash =
+                get_accumulator_root_hash::<EventAccumulatorHasher>(&event_hashes);
+            ensure!(
+                event_root_hash == txn_info.event_root_hash(),
+                "Some event root hash calculated doesn't match that carried on the transaction \
+                 info.",
+            );
+            Ok(())
+        })
+        .collect()
+}
+
+/// Verifies that the transaction infos with hashes `hashes` have consecutive versions starting
+/// from `first_transaction_version` on the ledger represented by `ledger_info`, using the proofs
+/// of the first and the last ones.
+fn verify_transaction_info_range(
+    ledger_info: &LedgerInfo,
+    first_transaction_version: Option<Version>,
+    mut hashes: VecDeque<HashValue>,
+    first_proof: Option<&AccumulatorProof>,
+    last_proof: Option<&AccumulatorProof>,
+) -> Result<()> {
+    let num_txns = hashes.len();
+
     // 1. Empty list;
     if num_txns == 0 {
         ensure!(
@@ -172,49 +298,23 @@ pub(crate) fn verify_transaction_list(
 
     // 2. Non-empty list.
     let first_version = first_transaction_version.ok_or_else(|| {
-        format_err!("Invalid TransactionListWithProof: First_transaction_version is None.")
+        format_err!("Invalid transaction list proof: First_transaction_version is None.")
     })?;
     let siblings_of_first_txn = first_proof
         .ok_or_else(|| {
-            format_err!("Invalid TransactionListWithProof: First transaction proof is None")
+            format_err!("Invalid transaction list proof: First transaction proof is None")
         })?
         .siblings();
     let siblings_of_last_txn = match (num_txns, last_proof) {
         (1, None) => siblings_of_first_txn,
         (_, Some(last_proof)) => last_proof.siblings(),
         _ => bail!(
-            "Invalid TransactionListWithProof: Last transaction proof is_none:{}, num_txns:{}",
+            "Invalid transaction list proof: Last transaction proof is_none:{}, num_txns:{}",
             last_proof.is_none(),
             num_txns
         ),
     };
 
-    // Verify event root hashes match what is carried on the transaction infos.
-    if let Some(event_lists) = event_lists {
-        itertools::zip_eq(event_lists, transaction_and_infos).map(|(events, (_txn, txn_info))| {
-            let event_hashes: Vec<_> = events.iter().map(ContractEvent::hash).collect();
-            let event_root_hash = get_accumulator_root_hash::<EventAccumulatorHasher>(&event_hashes);
-            ensure!(
-                event_root_hash == txn_info.event_root_hash(),
-                "Some event root hash calculated doesn't match that carried on the transaction info.",
-            );
-            Ok(())
-        }).collect::<Result<Vec<_>>>()?;
-    }
-
-    // Get the hashes of all nodes at the accumulator leaf level.
-    let mut hashes = transaction_and_infos
-        .iter()
-        .map(|(txn, txn_info)| {
-            // Verify all transaction_infos and signed_transactions are consistent.
-            ensure!(
-                txn.hash() == txn_info.signed_transaction_hash(),
-                "Some hash of signed transaction does not match the corresponding transaction info in proof"
-            );
-            Ok(txn_info.hash())
-        })
-        .collect::<Result<VecDeque<_>>>()?;
-
     let mut first_index = first_version;
 
     // Verify level by level from the leaf level upwards.
@@ -237,14 +337,14 @@ pub(crate) fn verify_transaction_list(
                 // Note: if we check `first_index` first we cannot use num_nodes to index because
                 // hashes length may change.
                 ensure!(hashes[num_nodes - 2] == *last_sibling,
-                        "Invalid TransactionListWithProof: Last transaction proof doesn't match provided siblings");
+                        "Invalid transaction list proof: Last transaction proof doesn't match provided siblings");
             }
             // We haven't reached the first common ancester of all transactions in the list.
             if first_index % 2 == 0 {
                 // if `first_index` is even, it is the left child of its parent so the sibling must
                 // be the next node.
                 ensure!(hashes[1] == *first_sibling,
-                            "Invalid TransactionListWithProof: First transaction proof doesn't match provided siblings");
+                            "Invalid transaction list proof: First transaction proof doesn't match provided siblings");
             } else {
                 // Otherwise, the sibling is not in `hashes`, we have to prepend it to `hashes` to
                 // generate parent nodes' hashes.
@@ -254,7 +354,7 @@ pub(crate) fn verify_transaction_list(
             // We have reached the first common ancestor of all the transactions in the list.
             ensure!(
                 first_sibling == last_sibling,
-                "Invalid TransactionListWithProof: Either proof is invalid."
+                "Invalid transaction list proof: Either proof is invalid."
             );
             if first_index % 2 == 0 {
                 hashes.push_back(*first_sibling);
@@ -403,6 +503,123 @@ pub const verify_transaction_accumulator_element: AccumulatorElementVerifier =
 pub const verify_test_accumulator_element: AccumulatorElementVerifier =
     verify_accumulator_element::<TestOnlyHasher>;
 
+/// Verifies that the accumulator with `new_num_leaves` leaves and root hash `new_root_hash` is the
+/// result of appending leaves to the accumulator with `old_num_leaves` leaves and root hash
+/// `old_root_hash`, using the provided proof.
+fn verify_accumulator_consistency<H: Clone + CryptoHasher>(
+    old_num_leaves: u64,
+    old_root_hash: HashValue,
+    new_num_leaves: u64,
+    new_root_hash: HashValue,
+    consistency_proof: &AccumulatorConsistencyProof,
+) -> Result<()> {
+    ensure!(
+        old_num_leaves <= new_num_leaves,
+        "The old accumulator ({} leaves) is bigger than the new one ({} leaves).",
+        old_num_leaves,
+        new_num_leaves,
+    );
+    ensure!(
+        new_num_leaves <= 1 << 63,
+        "Accumulator has more than 2^63 ({}) leaves.",
+        new_num_leaves,
+    );
+    let frozen_subtree_roots = consistency_proof.frozen_subtree_roots();
+    ensure!(
+        frozen_subtree_roots.len() == old_num_leaves.count_ones() as usize,
+        "Consistency proof has {} frozen subtrees, expected {} for {} leaves.",
+        frozen_subtree_roots.len(),
+        old_num_leaves.count_ones(),
+        old_num_leaves,
+    );
+    if old_num_leaves == 0 {
+        // Any accumulator is the result of appending leaves to the empty one.
+        ensure!(
+            old_root_hash == *ACCUMULATOR_PLACEHOLDER_HASH,
+            "Root hash of the empty accumulator is not the placeholder hash: {:x}.",
+            old_root_hash,
+        );
+        ensure!(
+            consistency_proof.right_siblings().is_empty(),
+            "Consistency proof from the empty accumulator has right siblings.",
+        );
+        return Ok(());
+    }
+
+    // Walk from the root of the rightmost frozen subtree of the old accumulator up to the root of
+    // the new one. The left siblings on the way are the other frozen subtrees, and the right
+    // siblings are placeholders in the old accumulator.
+    let mut position = FrozenSubTreeIterator::new(old_num_leaves)
+        .last()
+        .expect("The old accumulator is not empty.");
+    let old_root_level = Position::get_root_position(old_num_leaves - 1).get_level();
+    let new_root_position = Position::get_root_position(new_num_leaves - 1);
+    let mut left_siblings = frozen_subtree_roots.iter().rev();
+    let mut right_siblings = consistency_proof.right_siblings().iter().rev();
+    let mut old_hash = *left_siblings
+        .next()
+        .expect("The old accumulator has at least one frozen subtree.");
+    let mut new_hash = old_hash;
+    while position != new_root_position {
+        let below_old_root = position.get_level() < old_root_level;
+        match position.get_direction_for_self() {
+            NodeDirection::Left => {
+                let sibling = right_siblings
+                    .next()
+                    .ok_or_else(|| format_err!("Consistency proof has too few right siblings."))?;
+                if below_old_root {
+                    old_hash =
+                        MerkleTreeInternalNode::<H>::new(old_hash, *ACCUMULATOR_PLACEHOLDER_HASH)
+                            .hash();
+                }
+                new_hash = MerkleTreeInternalNode::<H>::new(new_hash, *sibling).hash();
+            }
+            NodeDirection::Right => {
+                let sibling = left_siblings
+                    .next()
+                    .expect("The left siblings are the frozen subtrees, whose number is checked.");
+                old_hash = MerkleTreeInternalNode::<H>::new(*sibling, old_hash).hash();
+                new_hash = MerkleTreeInternalNode::<H>::new(*sibling, new_hash).hash();
+            }
+        }
+        position = position.get_parent();
+    }
+    ensure!(
+        right_siblings.next().is_none(),
+        "Consistency proof has too many right siblings.",
+    );
+
+    ensure!(
+        old_hash == old_root_hash,
+        "Old root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+        old_hash,
+        old_root_hash,
+    );
+    ensure!(
+        new_hash == new_root_hash,
+        "New root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+        new_hash,
+        new_root_hash,
+    );
+    Ok(())
+}
+
+type AccumulatorConsistencyVerifier = fn(
+    old_num_leaves: u64,
+    old_root_hash: HashValue,
+    new_num_leaves: u64,
+    new_root_hash: HashValue,
+    consistency_proof: &AccumulatorConsistencyProof,
+) -> Result<()>;
+
+#[allow(non_upper_case_globals)]
+pub const verify_transaction_accumulator_consistency: AccumulatorConsistencyVerifier =
+    verify_accumulator_consistency::<TransactionAccumulatorHasher>;
+
+#[allow(non_upper_case_globals)]
+pub const verify_test_accumulator_consistency: AccumulatorConsistencyVerifier =
+    verify_accumulator_consistency::<TestOnlyHasher>;
+
 /// If `element_blob` is present, verifies an element whose key is `element_key` and value
 /// is `element_blob` exists in the Sparse Merkle Tree using the provided proof.
 /// Otherwise verifies the proof is a valid non-inclusion proof that shows this key doesn't exist
@@ -495,6 +712,223 @@ pub fn verify_sparse_merkle_element(
     Ok(())
 }
 
+/// Verifies that `leaves`, a list of `(key, value_hash)` pairs sorted by key, are all the leaves
+/// in the Sparse Merkle Tree whose root hash is `expected_root_hash` with keys in the range from
+/// `start_key` to the key of the last leaf in the list, both inclusive. If `leaves` is empty,
+/// verifies that the tree has no leaf with a key equal to or greater than `start_key`.
+///
+/// The root hash is rebuilt from the leaves in the range, the left siblings on the path to
+/// `start_key` and the right siblings on the path to the last leaf. Every other subtree lies
+/// between the two paths, so its hash can only be computed from `leaves`, which means that
+/// omitting any leaf in the range results in a different root hash.
+pub fn verify_sparse_merkle_range(
+    expected_root_hash: HashValue,
+    start_key: HashValue,
+    leaves: &[(HashValue, HashValue)],
+    sparse_merkle_range_proof: &SparseMerkleRangeProof,
+) -> Result<()> {
+    let left_siblings = sparse_merkle_range_proof.left_boundary_proof().siblings();
+    let right_siblings = sparse_merkle_range_proof.right_siblings();
+    ensure!(
+        left_siblings.len() <= HashValue::LENGTH_IN_BITS,
+        "Sparse Merkle Tree range proof has more than {} ({}) left siblings.",
+        HashValue::LENGTH_IN_BITS,
+        left_siblings.len()
+    );
+    ensure!(
+        right_siblings.len() <= HashValue::LENGTH_IN_BITS,
+        "Sparse Merkle Tree range proof has more than {} ({}) right siblings.",
+        HashValue::LENGTH_IN_BITS,
+        right_siblings.len()
+    );
+
+    match leaves.first() {
+        Some((first_key, _)) => ensure!(
+            *first_key >= start_key,
+            "First key in range {:x} is smaller than start key {:x}.",
+            first_key,
+            start_key
+        ),
+        None => ensure!(
+            right_siblings.is_empty(),
+            "Range is empty but the proof has {} right siblings.",
+            right_siblings.len()
+        ),
+    }
+    ensure!(
+        leaves.windows(2).all(|pair| pair[0].0 < pair[1].0),
+        "Keys in range are not strictly increasing."
+    );
+
+    let verifier = SparseMerkleRangeVerifier {
+        start_key,
+        last_key: leaves.last().map(|(key, _)| *key),
+        left_boundary_proof: sparse_merkle_range_proof.left_boundary_proof(),
+        right_siblings,
+    };
+    let actual_root_hash = verifier.subtree_hash(0, leaves, true, !leaves.is_empty())?;
+    ensure!(
+        actual_root_hash == expected_root_hash,
+        "Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+        actual_root_hash,
+        expected_root_hash
+    );
+
+    Ok(())
+}
+
+/// Rebuilds the root hash of a Sparse Merkle Tree from a [`SparseMerkleRangeProof`] and the leaves
+/// in the range. The path from root to `start_key` is called the left path and the one from root
+/// to `last_key` the right path.
+struct SparseMerkleRangeVerifier<'a> {
+    start_key: HashValue,
+    last_key: Option<HashValue>,
+    left_boundary_proof: &'a SparseMerkleProof,
+    right_siblings: &'a [HashValue],
+}
+
+impl<'a> SparseMerkleRangeVerifier<'a> {
+    /// Computes the hash of the subtree at `depth` containing `leaves`.
+    fn subtree_hash(
+        &self,
+        depth: usize,
+        leaves: &[(HashValue, HashValue)],
+        on_left_path: bool,
+        on_right_path: bool,
+    ) -> Result<HashValue> {
+        let left_siblings = self.left_boundary_proof.siblings();
+        if on_left_path && depth == left_siblings.len() {
+            return self.left_boundary_hash(depth, leaves, on_right_path);
+        }
+        if on_right_path && depth == self.right_siblings.len() {
+            ensure!(
+                !on_left_path,
+                "The path to the last leaf in range ends above the path to the start key."
+            );
+            ensure!(
+                leaves.len() == 1,
+                "Expected only the last leaf at the end of its path. Found {} leaves.",
+                leaves.len()
+            );
+            let (key, value_hash) = leaves[0];
+            return Ok(SparseMerkleLeafNode::new(key, value_hash).hash());
+        }
+        if !on_left_path && !on_right_path {
+            return Ok(Self::covered_subtree_hash(depth, leaves));
+        }
+
+        let start_bit = get_bit(self.start_key, depth);
+        let last_bit = self.last_key.map_or(false, |key| get_bit(key, depth));
+        let (left_leaves, right_leaves) = split_leaves(depth, leaves);
+        // A left child off the left path holds keys smaller than `start_key`, and a right child
+        // off the right path holds keys greater than `last_key`. Both are given by the proof.
+        let left_child_hash = if on_left_path && start_bit {
+            left_siblings[depth]
+        } else {
+            self.subtree_hash(
+                depth + 1,
+                left_leaves,
+                on_left_path,
+                on_right_path && !last_bit,
+            )?
+        };
+        let right_child_hash = if on_right_path && !last_bit {
+            self.right_siblings[depth]
+        } else {
+            self.subtree_hash(
+                depth + 1,
+                right_leaves,
+                on_left_path && start_bit,
+                on_right_path,
+            )?
+        };
+        Ok(SparseMerkleInternalNode::new(left_child_hash, right_child_hash).hash())
+    }
+
+    /// Computes the hash of the subtree at the end of the left path, which holds at most one leaf.
+    fn left_boundary_hash(
+        &self,
+        depth: usize,
+        leaves: &[(HashValue, HashValue)],
+        on_right_path: bool,
+    ) -> Result<HashValue> {
+        match self.left_boundary_proof.leaf() {
+            Some((key, value_hash)) if key < self.start_key => {
+                // The only leaf in this subtree is to the left of the range.
+                ensure!(
+                    key.common_prefix_bits_len(self.start_key) >= depth,
+                    "Leaf in left boundary proof is not in the subtree of the start key."
+                );
+                ensure!(
+                    leaves.is_empty(),
+                    "Leaf in left boundary proof is the only leaf in its subtree, but {} leaves in \
+                     range are also in it.",
+                    leaves.len()
+                );
+                Ok(SparseMerkleLeafNode::new(key, value_hash).hash())
+            }
+            Some((key, value_hash)) => {
+                // The only leaf in this subtree is the first one in the range.
+                ensure!(
+                    leaves == [(key, value_hash)],
+                    "Leaf in left boundary proof does not match the first leaf in range."
+                );
+                ensure!(
+                    !on_right_path || depth == self.right_siblings.len(),
+                    "The path to the last leaf in range does not end at the only leaf in its \
+                     subtree."
+                );
+                Ok(SparseMerkleLeafNode::new(key, value_hash).hash())
+            }
+            None => {
+                ensure!(
+                    leaves.is_empty(),
+                    "Left boundary proof shows an empty subtree, but {} leaves in range are in it.",
+                    leaves.len()
+                );
+                Ok(*SPARSE_MERKLE_PLACEHOLDER_HASH)
+            }
+        }
+    }
+
+    /// Computes the hash of a subtree at `depth` lying entirely within the range, so `leaves` are
+    /// all the leaves in it.
+    fn covered_subtree_hash(depth: usize, leaves: &[(HashValue, HashValue)]) -> HashValue {
+        match leaves {
+            [] => *SPARSE_MERKLE_PLACEHOLDER_HASH,
+            [(key, value_hash)] => SparseMerkleLeafNode::new(*key, *value_hash).hash(),
+            _ => {
+                let (left_leaves, right_leaves) = split_leaves(depth, leaves);
+                SparseMerkleInternalNode::new(
+                    Self::covered_subtree_hash(depth + 1, left_leaves),
+                    Self::covered_subtree_hash(depth + 1, right_leaves),
+                )
+                .hash()
+            }
+        }
+    }
+}
+
+/// Returns the `depth`-th bit of `key`, where the 0-th bit is the most significant one.
+fn get_bit(key: HashValue, depth: usize) -> bool {
+    key.iter_bits()
+        .nth(depth)
+        .expect("Depth should be less than the number of bits in a key.")
+}
+
+/// Splits sorted `leaves` sharing the same `depth`-bit prefix into the ones in the left subtree
+/// and the ones in the right subtree.
+fn split_leaves(
+    depth: usize,
+    leaves: &[(HashValue, HashValue)],
+) -> (&[(HashValue, HashValue)], &[(HashValue, HashValue)]) {
+    let split_index = leaves
+        .iter()
+        .position(|(key, _)| get_bit(*key, depth))
+        .unwrap_or_else(|| leaves.len());
+    leaves.split_at(split_index)
+}
+
 pub struct MerkleTreeInternalNode<H> {
     left_child: HashValue,
     right_child: HashValue,
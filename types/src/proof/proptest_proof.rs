@@ -6,7 +6,8 @@
 
 use crate::{
     proof::{
-        AccountStateProof, AccumulatorProof, EventProof, SignedTransactionProof, SparseMerkleProof,
+        AccountStateProof, AccountStateRangeProof, AccumulatorConsistencyProof, AccumulatorProof,
+        EventProof, SignedTransactionProof, SparseMerkleProof, SparseMerkleRangeProof,
     },
     transaction::TransactionInfo,
 };
@@ -54,6 +55,24 @@ prop_compose! {
     }
 }
 
+prop_compose! {
+    fn arb_sparse_merkle_range_proof()(
+        left_boundary_proof in any::<SparseMerkleProof>(),
+        right_siblings in vec(any::<HashValue>(), 0..257usize),
+    ) -> SparseMerkleRangeProof {
+        SparseMerkleRangeProof::new(left_boundary_proof, right_siblings)
+    }
+}
+
+prop_compose! {
+    fn arb_accumulator_consistency_proof()(
+        frozen_subtree_roots in vec(any::<HashValue>(), 0..64usize),
+        right_siblings in vec(any::<HashValue>(), 0..64usize),
+    ) -> AccumulatorConsistencyProof {
+        AccumulatorConsistencyProof::new(frozen_subtree_roots, right_siblings)
+    }
+}
+
 prop_compose! {
     fn arb_signed_transaction_proof()(
         ledger_info_to_transaction_info_proof in any::<AccumulatorProof>(),
@@ -77,6 +96,20 @@ prop_compose! {
     }
 }
 
+prop_compose! {
+    fn arb_account_state_range_proof()(
+        ledger_info_to_transaction_info_proof in any::<AccumulatorProof>(),
+        transaction_info in any::<TransactionInfo>(),
+        transaction_info_to_account_range_proof in any::<SparseMerkleRangeProof>(),
+    ) -> AccountStateRangeProof {
+        AccountStateRangeProof::new(
+            ledger_info_to_transaction_info_proof,
+            transaction_info,
+            transaction_info_to_account_range_proof,
+        )
+    }
+}
+
 prop_compose! {
     fn arb_event_proof()(
         ledger_info_to_transaction_info_proof in any::<AccumulatorProof>(),
@@ -106,6 +139,12 @@ macro_rules! impl_arbitrary_for_proof {
 
 impl_arbitrary_for_proof!(AccumulatorProof, arb_accumulator_proof);
 impl_arbitrary_for_proof!(SparseMerkleProof, arb_sparse_merkle_proof);
+impl_arbitrary_for_proof!(SparseMerkleRangeProof, arb_sparse_merkle_range_proof);
+impl_arbitrary_for_proof!(
+    AccumulatorConsistencyProof,
+    arb_accumulator_consistency_proof
+);
 impl_arbitrary_for_proof!(SignedTransactionProof, arb_signed_transaction_proof);
 impl_arbitrary_for_proof!(AccountStateProof, arb_account_state_proof);
+impl_arbitrary_for_proof!(AccountStateRangeProof, arb_account_state_range_proof);
 impl_arbitrary_for_proof!(EventProof, arb_event_proof);
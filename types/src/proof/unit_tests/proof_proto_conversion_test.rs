@@ -3,7 +3,8 @@
 
 use crate::proof::{
     definition::bitmap::{AccumulatorBitmap, SparseMerkleBitmap},
-    AccountStateProof, AccumulatorProof, EventProof, SignedTransactionProof, SparseMerkleProof,
+    AccountStateProof, AccountStateRangeProof, AccumulatorConsistencyProof, AccumulatorProof,
+    EventProof, SignedTransactionProof, SparseMerkleProof, SparseMerkleRangeProof,
 };
 use crypto::{
     hash::{TestOnlyHash, ACCUMULATOR_PLACEHOLDER_HASH, SPARSE_MERKLE_PLACEHOLDER_HASH},
@@ -313,6 +314,16 @@ proptest! {
         assert_protobuf_encode_decode(&proof);
     }
 
+    #[test]
+    fn test_sparse_merkle_range_protobuf_conversion_roundtrip(proof in any::<SparseMerkleRangeProof>()) {
+        assert_protobuf_encode_decode(&proof);
+    }
+
+    #[test]
+    fn test_accumulator_consistency_proof_protobuf_conversion_roundtrip(proof in any::<AccumulatorConsistencyProof>()) {
+        assert_protobuf_encode_decode(&proof);
+    }
+
     #[test]
     fn test_signed_transaction_proof_protobuf_conversion_roundtrip(proof in any::<SignedTransactionProof>()) {
         assert_protobuf_encode_decode(&proof);
@@ -323,6 +334,11 @@ proptest! {
         assert_protobuf_encode_decode(&proof);
     }
 
+    #[test]
+    fn test_account_state_range_proof_protobuf_conversion_roundtrip(proof in any::<AccountStateRangeProof>()) {
+        assert_protobuf_encode_decode(&proof);
+    }
+
     #[test]
     fn test_event_proof_protobuf_conversion_roundtrip(proof in any::<EventProof>()) {
         assert_protobuf_encode_decode(&proof);
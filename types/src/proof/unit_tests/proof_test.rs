@@ -7,9 +7,11 @@ use crate::{
     ledger_info::LedgerInfo,
     proof::{
         verify_account_state, verify_event, verify_signed_transaction,
-        verify_sparse_merkle_element, verify_test_accumulator_element, AccountStateProof,
-        AccumulatorProof, EventAccumulatorInternalNode, EventProof, MerkleTreeInternalNode,
-        SignedTransactionProof, SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleProof,
+        verify_sparse_merkle_element, verify_sparse_merkle_range,
+        verify_test_accumulator_consistency, verify_test_accumulator_element, AccountStateProof,
+        AccumulatorConsistencyProof, AccumulatorProof, EventAccumulatorInternalNode, EventProof,
+        MerkleTreeInternalNode, SignedTransactionProof, SparseMerkleInternalNode,
+        SparseMerkleLeafNode, SparseMerkleProof, SparseMerkleRangeProof,
         TestAccumulatorInternalNode, TransactionAccumulatorInternalNode,
     },
     transaction::{
@@ -97,6 +99,87 @@ fn test_verify_three_element_accumulator() {
     .is_ok());
 }
 
+#[test]
+fn test_verify_accumulator_consistency() {
+    let element0_hash = b"hello".test_only_hash();
+    let element1_hash = b"world".test_only_hash();
+    let element2_hash = b"!".test_only_hash();
+    let internal0_hash = TestAccumulatorInternalNode::new(element0_hash, element1_hash).hash();
+    let internal1_hash =
+        TestAccumulatorInternalNode::new(element2_hash, *ACCUMULATOR_PLACEHOLDER_HASH).hash();
+    let root_hash = TestAccumulatorInternalNode::new(internal0_hash, internal1_hash).hash();
+
+    assert!(verify_test_accumulator_consistency(
+        0,
+        *ACCUMULATOR_PLACEHOLDER_HASH,
+        3,
+        root_hash,
+        &AccumulatorConsistencyProof::new(vec![], vec![]),
+    )
+    .is_ok());
+    let proof =
+        AccumulatorConsistencyProof::new(vec![element0_hash], vec![internal1_hash, element1_hash]);
+    assert!(verify_test_accumulator_consistency(1, element0_hash, 3, root_hash, &proof).is_ok());
+    // The old accumulator doesn't match the proof.
+    assert!(verify_test_accumulator_consistency(1, element1_hash, 3, root_hash, &proof).is_err());
+    // The new accumulator doesn't match the proof.
+    assert!(
+        verify_test_accumulator_consistency(1, element0_hash, 3, internal0_hash, &proof).is_err()
+    );
+    // The new accumulator is smaller than the old one.
+    assert!(verify_test_accumulator_consistency(3, root_hash, 1, element0_hash, &proof).is_err());
+
+    assert!(verify_test_accumulator_consistency(
+        2,
+        internal0_hash,
+        3,
+        root_hash,
+        &AccumulatorConsistencyProof::new(vec![internal0_hash], vec![internal1_hash]),
+    )
+    .is_ok());
+    // Too many or too few right siblings.
+    assert!(verify_test_accumulator_consistency(
+        2,
+        internal0_hash,
+        3,
+        root_hash,
+        &AccumulatorConsistencyProof::new(
+            vec![internal0_hash],
+            vec![internal1_hash, *ACCUMULATOR_PLACEHOLDER_HASH]
+        ),
+    )
+    .is_err());
+    assert!(verify_test_accumulator_consistency(
+        2,
+        internal0_hash,
+        3,
+        root_hash,
+        &AccumulatorConsistencyProof::new(vec![internal0_hash], vec![]),
+    )
+    .is_err());
+
+    assert!(verify_test_accumulator_consistency(
+        3,
+        root_hash,
+        3,
+        root_hash,
+        &AccumulatorConsistencyProof::new(
+            vec![internal0_hash, element2_hash],
+            vec![*ACCUMULATOR_PLACEHOLDER_HASH]
+        ),
+    )
+    .is_ok());
+    // Wrong number of frozen subtrees.
+    assert!(verify_test_accumulator_consistency(
+        3,
+        root_hash,
+        3,
+        root_hash,
+        &AccumulatorConsistencyProof::new(vec![internal0_hash], vec![internal1_hash]),
+    )
+    .is_err());
+}
+
 #[test]
 fn test_accumulator_proof_63_siblings_leftmost() {
     let element_hash = b"hello".test_only_hash();
@@ -260,6 +343,105 @@ fn test_verify_three_element_sparse_merkle() {
     }
 }
 
+#[test]
+fn test_verify_three_element_sparse_merkle_range() {
+    // Same tree as in `test_verify_three_element_sparse_merkle`.
+    //            root
+    //           /    \
+    //          a      default
+    //         / \
+    //     key1   b
+    //           / \
+    //       key2   key3
+    let key1 = b"hello".test_only_hash();
+    let key2 = b"world".test_only_hash();
+    let key3 = b"!".test_only_hash();
+    let value1_hash = AccountStateBlob::from(b"1".to_vec()).hash();
+    let value2_hash = AccountStateBlob::from(b"2".to_vec()).hash();
+    let value3_hash = AccountStateBlob::from(b"3".to_vec()).hash();
+
+    let leaf1_hash = SparseMerkleLeafNode::new(key1, value1_hash).hash();
+    let leaf2_hash = SparseMerkleLeafNode::new(key2, value2_hash).hash();
+    let leaf3_hash = SparseMerkleLeafNode::new(key3, value3_hash).hash();
+    let internal_b_hash = SparseMerkleInternalNode::new(leaf2_hash, leaf3_hash).hash();
+    let internal_a_hash = SparseMerkleInternalNode::new(leaf1_hash, internal_b_hash).hash();
+    let root_hash =
+        SparseMerkleInternalNode::new(internal_a_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH).hash();
+
+    let non_existing_key1 = b"abc".test_only_hash();
+    let non_existing_key2 = b"def".test_only_hash();
+    assert_eq!(non_existing_key1[0], 0b0011_1010);
+    assert_eq!(non_existing_key2[0], 0b1000_1110);
+
+    let key1_proof = SparseMerkleProof::new(
+        Some((key1, value1_hash)),
+        vec![*SPARSE_MERKLE_PLACEHOLDER_HASH, internal_b_hash],
+    );
+    let key2_siblings = vec![*SPARSE_MERKLE_PLACEHOLDER_HASH, leaf1_hash, leaf3_hash];
+    let key3_siblings = vec![*SPARSE_MERKLE_PLACEHOLDER_HASH, leaf1_hash, leaf2_hash];
+    let all_leaves = vec![
+        (key1, value1_hash),
+        (key2, value2_hash),
+        (key3, value3_hash),
+    ];
+
+    {
+        // The whole tree.
+        let proof = SparseMerkleRangeProof::new(key1_proof.clone(), key3_siblings.clone());
+        assert!(verify_sparse_merkle_range(root_hash, key1, &all_leaves, &proof).is_ok());
+        // Omitting key2 is detected.
+        assert!(verify_sparse_merkle_range(
+            root_hash,
+            key1,
+            &[all_leaves[0], all_leaves[2]],
+            &proof
+        )
+        .is_err());
+        // Omitting key3 is detected.
+        assert!(verify_sparse_merkle_range(root_hash, key1, &all_leaves[..2], &proof).is_err());
+        // The proof does not start at key2.
+        assert!(verify_sparse_merkle_range(root_hash, key2, &all_leaves[1..], &proof).is_err());
+    }
+
+    {
+        // The first two leaves.
+        let proof = SparseMerkleRangeProof::new(key1_proof.clone(), key2_siblings);
+        assert!(verify_sparse_merkle_range(root_hash, key1, &all_leaves[..2], &proof).is_ok());
+        assert!(verify_sparse_merkle_range(root_hash, key1, &all_leaves[..1], &proof).is_err());
+        assert!(verify_sparse_merkle_range(root_hash, key1, &all_leaves, &proof).is_err());
+    }
+
+    {
+        // A range starting at a non-existing key whose path ends at key1.
+        let proof = SparseMerkleRangeProof::new(key1_proof.clone(), key3_siblings);
+        assert!(
+            verify_sparse_merkle_range(root_hash, non_existing_key1, &all_leaves[1..], &proof)
+                .is_ok()
+        );
+        // key1 is smaller than the start key so it can't be in the range.
+        assert!(
+            verify_sparse_merkle_range(root_hash, non_existing_key1, &all_leaves, &proof).is_err()
+        );
+        // Omitting key2 is detected.
+        assert!(
+            verify_sparse_merkle_range(root_hash, non_existing_key1, &all_leaves[2..], &proof)
+                .is_err()
+        );
+    }
+
+    {
+        // Nothing exists at or after non_existing_key2.
+        let proof = SparseMerkleRangeProof::new(
+            SparseMerkleProof::new(None, vec![internal_a_hash]),
+            vec![],
+        );
+        assert!(verify_sparse_merkle_range(root_hash, non_existing_key2, &[], &proof).is_ok());
+        // But something does exist at or after key1.
+        let proof = SparseMerkleRangeProof::new(key1_proof, vec![]);
+        assert!(verify_sparse_merkle_range(root_hash, key1, &[], &proof).is_err());
+    }
+}
+
 #[test]
 fn test_verify_signed_transaction() {
     //            root
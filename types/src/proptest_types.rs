@@ -9,12 +9,13 @@ use crate::{
     byte_array::ByteArray,
     contract_event::ContractEvent,
     get_with_proof::{ResponseItem, UpdateToLatestLedgerResponse},
+    language_storage::StructTag,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     proof::AccumulatorProof,
     transaction::{
-        Program, RawTransaction, SignatureCheckedTransaction, SignedTransaction,
-        TransactionArgument, TransactionInfo, TransactionListWithProof, TransactionPayload,
-        TransactionStatus, TransactionToCommit, Version,
+        EventListWithProof, Program, RawTransaction, SignatureCheckedTransaction,
+        SignedTransaction, TransactionArgument, TransactionInfo, TransactionListWithProof,
+        TransactionPayload, TransactionStatus, TransactionToCommit, Version,
     },
     validator_change::ValidatorChangeEventWithProof,
     vm_error::VMStatus,
@@ -280,7 +281,9 @@ impl Arbitrary for TransactionArgument {
     type Parameters = ();
     fn arbitrary_with(_args: ()) -> Self::Strategy {
         prop_oneof![
+            any::<u8>().prop_map(TransactionArgument::U8),
             any::<u64>().prop_map(TransactionArgument::U64),
+            any::<u128>().prop_map(TransactionArgument::U128),
             any::<AccountAddress>().prop_map(TransactionArgument::Address),
             any::<ByteArray>().prop_map(TransactionArgument::ByteArray),
             ".*".prop_map(TransactionArgument::String),
@@ -536,3 +539,89 @@ impl Arbitrary for TransactionListWithProof {
 
     type Strategy = BoxedStrategy<Self>;
 }
+
+fn arb_event_list_with_proof() -> impl Strategy<Value = EventListWithProof> {
+    vec(
+        (any::<TransactionInfo>(), vec(any::<ContractEvent>(), 0..10)),
+        0..10,
+    )
+    .prop_flat_map(|infos_and_events| {
+        (
+            Just(infos_and_events),
+            any::<Version>(),
+            any::<AccumulatorProof>(),
+            any::<AccumulatorProof>(),
+        )
+    })
+    .prop_map(
+        |(infos_and_events, first_txn_version, proof_of_first_txn, proof_of_last_txn)| {
+            let num_txns = infos_and_events.len();
+            let (transaction_infos, events) = infos_and_events.into_iter().unzip();
+            match num_txns {
+                0 => EventListWithProof::new_empty(),
+                1 => EventListWithProof::new(
+                    transaction_infos,
+                    events,
+                    Some(first_txn_version),
+                    Some(proof_of_first_txn),
+                    None,
+                ),
+                _ => EventListWithProof::new(
+                    transaction_infos,
+                    events,
+                    Some(first_txn_version),
+                    Some(proof_of_first_txn),
+                    Some(proof_of_last_txn),
+                ),
+            }
+        },
+    )
+}
+
+impl Arbitrary for EventListWithProof {
+    type Parameters = ();
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        arb_event_list_with_proof().boxed()
+    }
+
+    type Strategy = BoxedStrategy<Self>;
+}
+
+fn arb_struct_tag() -> impl Strategy<Value = StructTag> {
+    let leaf = (any::<AccountAddress>(), "[a-zA-Z]{1,8}", "[a-zA-Z]{1,8}").prop_map(
+        |(address, module, name)| StructTag {
+            address,
+            module,
+            name,
+            type_params: vec![],
+        },
+    );
+    leaf.prop_recursive(
+        2, /* depth */
+        8, /* desired_size */
+        2, /* expected_branch_size */
+        |inner| {
+            (
+                any::<AccountAddress>(),
+                "[a-zA-Z]{1,8}",
+                "[a-zA-Z]{1,8}",
+                vec(inner, 0..3),
+            )
+                .prop_map(|(address, module, name, type_params)| StructTag {
+                    address,
+                    module,
+                    name,
+                    type_params,
+                })
+        },
+    )
+}
+
+impl Arbitrary for StructTag {
+    type Parameters = ();
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        arb_struct_tag().boxed()
+    }
+
+    type Strategy = BoxedStrategy<Self>;
+}
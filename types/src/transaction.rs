@@ -9,8 +9,8 @@ use crate::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfo,
     proof::{
-        get_accumulator_root_hash, verify_signed_transaction, verify_transaction_list,
-        AccumulatorProof, SignedTransactionProof,
+        get_accumulator_root_hash, verify_event_list, verify_signed_transaction,
+        verify_transaction_list, AccumulatorProof, SignedTransactionProof,
     },
     proto::events::{EventsForVersions, EventsList},
     vm_error::VMStatus,
@@ -938,43 +938,17 @@ impl FromProto for TransactionListWithProof {
             num_txns,
             num_infos
         );
-        let (has_first, has_last, has_first_version) = (
+        ensure_list_proofs_exist(
+            num_txns,
             object.has_proof_of_first_transaction(),
             object.has_proof_of_last_transaction(),
             object.has_first_transaction_version(),
-        );
-        match num_txns {
-            0 => ensure!(
-                !has_first && !has_last && !has_first_version,
-                "Some proof exists with 0 transactions"
-            ),
-            1 => ensure!(
-                has_first && !has_last && has_first_version,
-                "Proof of last transaction exists with 1 transaction"
-            ),
-            _ => ensure!(
-                has_first && has_last && has_first_version,
-                "Both proofs of first and last transactions must exist with 2+ transactions"
-            ),
-        }
+        )?;
 
         let events = object
             .events_for_versions
             .take() // Option<EventsForVersions>
-            .map(|mut events_for_versions| {
-                // EventsForVersion
-                events_for_versions
-                    .take_events_for_version()
-                    .into_iter()
-                    .map(|mut events_for_version| {
-                        events_for_version
-                            .take_events()
-                            .into_iter()
-                            .map(ContractEvent::from_proto)
-                            .collect::<Result<Vec<_>>>()
-                    })
-                    .collect::<Result<Vec<_>>>()
-            })
+            .map(events_for_versions_from_proto)
             .transpose()?;
 
         let transaction_and_infos = itertools::zip_eq(
@@ -1025,20 +999,7 @@ impl IntoProto for TransactionListWithProof {
         out.set_infos(protobuf::RepeatedField::from_vec(infos));
 
         if let Some(all_events) = self.events {
-            let mut events_for_versions = EventsForVersions::new();
-            for events_for_version in all_events {
-                let mut events_this_version = EventsList::new();
-                events_this_version.set_events(protobuf::RepeatedField::from_vec(
-                    events_for_version
-                        .into_iter()
-                        .map(ContractEvent::into_proto)
-                        .collect(),
-                ));
-                events_for_versions
-                    .events_for_version
-                    .push(events_this_version);
-            }
-            out.set_events_for_versions(events_for_versions);
+            out.set_events_for_versions(events_for_versions_into_proto(all_events));
         }
 
         if let Some(first_transaction_version) = self.first_transaction_version {
@@ -1055,3 +1016,193 @@ impl IntoProto for TransactionListWithProof {
         out
     }
 }
+
+/// The events emitted by a list of consecutive transactions, with the infos of the transactions
+/// and the proofs of the first and last ones. The list may have the same three states as
+/// [`TransactionListWithProof`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventListWithProof {
+    pub transaction_infos: Vec<TransactionInfo>,
+    pub events: Vec<Vec<ContractEvent>>,
+    pub first_transaction_version: Option<Version>,
+    pub proof_of_first_transaction: Option<AccumulatorProof>,
+    pub proof_of_last_transaction: Option<AccumulatorProof>,
+}
+
+impl EventListWithProof {
+    /// Constructor.
+    pub fn new(
+        transaction_infos: Vec<TransactionInfo>,
+        events: Vec<Vec<ContractEvent>>,
+        first_transaction_version: Option<Version>,
+        proof_of_first_transaction: Option<AccumulatorProof>,
+        proof_of_last_transaction: Option<AccumulatorProof>,
+    ) -> Self {
+        Self {
+            transaction_infos,
+            events,
+            first_transaction_version,
+            proof_of_first_transaction,
+            proof_of_last_transaction,
+        }
+    }
+
+    /// Creates an empty event list.
+    pub fn new_empty() -> Self {
+        Self::new(Vec::new(), Vec::new(), None, None, None)
+    }
+
+    /// Verifies the event list with the proofs, both carried on `self`.
+    ///
+    /// Two things are ensured if no error is raised:
+    ///   1. The transaction infos have consecutive versions starting from
+    /// `first_transaction_version` on the ledger represented by `ledger_info`. When
+    /// `first_transaction_version` is None, ensures the list is empty.
+    ///   2. And the events of each transaction are all the events it emitted, in order.
+    pub fn verify(
+        &self,
+        ledger_info: &LedgerInfo,
+        first_transaction_version: Option<Version>,
+    ) -> Result<()> {
+        ensure!(
+            self.first_transaction_version == first_transaction_version,
+            "First transaction version ({}) not expected ({}).",
+            TransactionListWithProof::display_option_version(self.first_transaction_version),
+            TransactionListWithProof::display_option_version(first_transaction_version),
+        );
+
+        verify_event_list(ledger_info, self)
+    }
+}
+
+impl FromProto for EventListWithProof {
+    type ProtoType = crate::proto::transaction::EventListWithProof;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let num_infos = object.get_infos().len();
+        let num_event_lists = object
+            .get_events_for_versions()
+            .get_events_for_version()
+            .len();
+        ensure!(
+            num_infos == num_event_lists,
+            "Number of transaction infos ({}) does not match the number of event lists ({}).",
+            num_infos,
+            num_event_lists
+        );
+        ensure_list_proofs_exist(
+            num_infos,
+            object.has_proof_of_first_transaction(),
+            object.has_proof_of_last_transaction(),
+            object.has_first_transaction_version(),
+        )?;
+
+        Ok(EventListWithProof {
+            transaction_infos: object
+                .take_infos()
+                .into_iter()
+                .map(TransactionInfo::from_proto)
+                .collect::<Result<Vec<_>>>()?,
+            events: events_for_versions_from_proto(object.take_events_for_versions())?,
+            proof_of_first_transaction: object
+                .proof_of_first_transaction
+                .take()
+                .map(AccumulatorProof::from_proto)
+                .transpose()?,
+            proof_of_last_transaction: object
+                .proof_of_last_transaction
+                .take()
+                .map(AccumulatorProof::from_proto)
+                .transpose()?,
+            first_transaction_version: object
+                .first_transaction_version
+                .take()
+                .map(|v| v.get_value()),
+        })
+    }
+}
+
+impl IntoProto for EventListWithProof {
+    type ProtoType = crate::proto::transaction::EventListWithProof;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_infos(protobuf::RepeatedField::from_vec(
+            self.transaction_infos
+                .into_iter()
+                .map(TransactionInfo::into_proto)
+                .collect(),
+        ));
+        out.set_events_for_versions(events_for_versions_into_proto(self.events));
+        if let Some(first_transaction_version) = self.first_transaction_version {
+            let mut ver = UInt64Value::new();
+            ver.set_value(first_transaction_version);
+            out.set_first_transaction_version(ver);
+        }
+        if let Some(proof_of_first_transaction) = self.proof_of_first_transaction {
+            out.set_proof_of_first_transaction(proof_of_first_transaction.into_proto());
+        }
+        if let Some(proof_of_last_transaction) = self.proof_of_last_transaction {
+            out.set_proof_of_last_transaction(proof_of_last_transaction.into_proto());
+        }
+        out
+    }
+}
+
+/// Ensures the proofs and the first version of a list of `num_txns` transactions exist as the
+/// three states of [`TransactionListWithProof`] require.
+fn ensure_list_proofs_exist(
+    num_txns: usize,
+    has_first: bool,
+    has_last: bool,
+    has_first_version: bool,
+) -> Result<()> {
+    match num_txns {
+        0 => ensure!(
+            !has_first && !has_last && !has_first_version,
+            "Some proof exists with 0 transactions"
+        ),
+        1 => ensure!(
+            has_first && !has_last && has_first_version,
+            "Proof of last transaction exists with 1 transaction"
+        ),
+        _ => ensure!(
+            has_first && has_last && has_first_version,
+            "Both proofs of first and last transactions must exist with 2+ transactions"
+        ),
+    }
+    Ok(())
+}
+
+fn events_for_versions_from_proto(
+    mut events_for_versions: EventsForVersions,
+) -> Result<Vec<Vec<ContractEvent>>> {
+    events_for_versions
+        .take_events_for_version()
+        .into_iter()
+        .map(|mut events_for_version| {
+            events_for_version
+                .take_events()
+                .into_iter()
+                .map(ContractEvent::from_proto)
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect()
+}
+
+fn events_for_versions_into_proto(all_events: Vec<Vec<ContractEvent>>) -> EventsForVersions {
+    let mut events_for_versions = EventsForVersions::new();
+    for events_for_version in all_events {
+        let mut events_this_version = EventsList::new();
+        events_this_version.set_events(protobuf::RepeatedField::from_vec(
+            events_for_version
+                .into_iter()
+                .map(ContractEvent::into_proto)
+                .collect(),
+        ));
+        events_for_versions
+            .events_for_version
+            .push(events_this_version);
+    }
+    events_for_versions
+}
@@ -78,6 +78,27 @@ impl FromProto for Program {
                     let amount = u64::from_le_bytes(bytes);
                     TransactionArgument::U64(amount)
                 }
+                TransactionArgument_ArgType::U8 => {
+                    let data = arg.get_data();
+                    ensure!(
+                        data.len() == 1,
+                        "data has incorrect length: expected 1 byte, found {} bytes",
+                        data.len()
+                    );
+                    TransactionArgument::U8(data[0])
+                }
+                TransactionArgument_ArgType::U128 => {
+                    let mut bytes = [0u8; 16];
+                    let data = arg.get_data();
+                    ensure!(
+                        bytes.len() == data.len(),
+                        "data has incorrect length: expected {} bytes, found {} bytes",
+                        bytes.len(),
+                        data.len()
+                    );
+                    bytes.copy_from_slice(arg.get_data());
+                    TransactionArgument::U128(u128::from_le_bytes(bytes))
+                }
                 TransactionArgument_ArgType::ADDRESS => {
                     TransactionArgument::Address(AccountAddress::try_from(arg.get_data())?)
                 }
@@ -120,6 +141,18 @@ impl IntoProto for Program {
                         .expect("Writing to a vec is guaranteed to work");
                     argument.set_data(amount_vec);
                 }
+                TransactionArgument::U8(value) => {
+                    argument.set_field_type(TransactionArgument_ArgType::U8);
+                    argument.set_data(vec![value]);
+                }
+                TransactionArgument::U128(value) => {
+                    argument.set_field_type(TransactionArgument_ArgType::U128);
+                    let mut value_vec = vec![];
+                    value_vec
+                        .write_u128::<LittleEndian>(value)
+                        .expect("Writing to a vec is guaranteed to work");
+                    argument.set_data(value_vec);
+                }
                 TransactionArgument::Address(address) => {
                     argument.set_field_type(TransactionArgument_ArgType::ADDRESS);
                     argument.set_data(address.as_ref().to_vec());
@@ -144,7 +177,9 @@ impl IntoProto for Program {
 
 #[derive(Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum TransactionArgument {
+    U8(u8),
     U64(u64),
+    U128(u128),
     Address(AccountAddress),
     ByteArray(ByteArray),
     String(String),
@@ -153,7 +188,9 @@ pub enum TransactionArgument {
 impl fmt::Debug for TransactionArgument {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            TransactionArgument::U8(value) => write!(f, "{{U8: {}}}", value),
             TransactionArgument::U64(value) => write!(f, "{{U64: {}}}", value),
+            TransactionArgument::U128(value) => write!(f, "{{U128: {}}}", value),
             TransactionArgument::Address(address) => write!(f, "{{ADDRESS: {:?}}}", address),
             TransactionArgument::String(string) => write!(f, "{{STRING: {}}}", string),
             TransactionArgument::ByteArray(byte_array) => {
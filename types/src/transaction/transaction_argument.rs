@@ -61,11 +61,29 @@ pub fn parse_as_byte_array(s: &str) -> Result<TransactionArgument> {
     }
 }
 
+/// Parses the given string as u8. The number must be suffixed with `u8`, e.g. `42u8`.
+pub fn parse_as_u8(s: &str) -> Result<TransactionArgument> {
+    if s.ends_with("u8") {
+        Ok(TransactionArgument::U8(s[..s.len() - 2].parse::<u8>()?))
+    } else {
+        Err(ErrorKind::ParseError(format!("\"{}\" is not a u8", s)).into())
+    }
+}
+
 /// Parses the given string as u64.
 pub fn parse_as_u64(s: &str) -> Result<TransactionArgument> {
     Ok(TransactionArgument::U64(s.parse::<u64>()?))
 }
 
+/// Parses the given string as u128. The number must be suffixed with `u128`, e.g. `42u128`.
+pub fn parse_as_u128(s: &str) -> Result<TransactionArgument> {
+    if s.ends_with("u128") {
+        Ok(TransactionArgument::U128(s[..s.len() - 4].parse::<u128>()?))
+    } else {
+        Err(ErrorKind::ParseError(format!("\"{}\" is not a u128", s)).into())
+    }
+}
+
 macro_rules! return_if_ok {
     ($e: expr) => {{
         if let Ok(res) = $e {
@@ -77,7 +95,9 @@ macro_rules! return_if_ok {
 /// Parses the given string as any transaction argument type.
 pub fn parse_as_transaction_argument(s: &str) -> Result<TransactionArgument> {
     return_if_ok!(parse_as_address(s));
+    return_if_ok!(parse_as_u8(s));
     return_if_ok!(parse_as_u64(s));
+    return_if_ok!(parse_as_u128(s));
     return_if_ok!(parse_as_byte_array(s));
     Err(ErrorKind::ParseError(format!("cannot parse \"{}\" as transaction argument", s)).into())
 }
@@ -96,6 +116,26 @@ mod test_transaction_argument {
         }
     }
 
+    #[test]
+    fn parse_u8() {
+        for s in &["0u8", "42u8", "255u8"] {
+            parse_as_u8(s).unwrap();
+        }
+        for s in &["42", "256u8", "u8", "-3u8"] {
+            parse_as_u8(s).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn parse_u128() {
+        for s in &["0u128", "42u128", "340282366920938463463374607431768211455u128"] {
+            parse_as_u128(s).unwrap();
+        }
+        for s in &["42", "340282366920938463463374607431768211456u128", "u128"] {
+            parse_as_u128(s).unwrap_err();
+        }
+    }
+
     #[test]
     fn parse_address() {
         for s in &[
@@ -133,7 +173,7 @@ mod test_transaction_argument {
 
     #[test]
     fn parse_args() {
-        for s in &["123", "0xf", "b\"aaa\""] {
+        for s in &["123", "12u8", "12u128", "0xf", "b\"aaa\""] {
             parse_as_transaction_argument(s).unwrap();
         }
 
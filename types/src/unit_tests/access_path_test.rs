@@ -4,6 +4,9 @@
 use crate::{
     access_path::AccessPath,
     account_address::{AccountAddress, ADDRESS_LENGTH},
+    account_config::{account_struct_tag, core_code_address},
+    contract_event::{ContractEvent, EventFilter},
+    language_storage::StructTag,
 };
 use proptest::prelude::*;
 use proto_conv::{test_helper::assert_protobuf_encode_decode, FromProto, IntoProto};
@@ -31,6 +34,50 @@ fn access_path_ord() {
     assert!(ap3 < ap4);
 }
 
+#[test]
+fn access_path_starts_with() {
+    let address1 = AccountAddress::new([1u8; ADDRESS_LENGTH]);
+    let address2 = AccountAddress::new([2u8; ADDRESS_LENGTH]);
+    let ap = AccessPath::new(address1, b"/foo/b".to_vec());
+
+    assert!(ap.starts_with(&AccessPath::new(address1, vec![])));
+    assert!(ap.starts_with(&AccessPath::new(address1, b"/foo/".to_vec())));
+    assert!(ap.starts_with(&ap));
+    assert!(!ap.starts_with(&AccessPath::new(address1, b"/foo/c".to_vec())));
+    assert!(!ap.starts_with(&AccessPath::new(address1, b"/foo/b/".to_vec())));
+    assert!(!ap.starts_with(&AccessPath::new(address2, vec![])));
+}
+
+#[test]
+fn event_filter_matches() {
+    let address1 = AccountAddress::new([1u8; ADDRESS_LENGTH]);
+    let address2 = AccountAddress::new([2u8; ADDRESS_LENGTH]);
+    let event = ContractEvent::new(AccessPath::new_for_sent_event(address1), 0, vec![]);
+    let other_resource_type = StructTag {
+        address: core_code_address(),
+        module: "LibraCoin".to_string(),
+        name: "T".to_string(),
+        type_params: vec![],
+    };
+
+    assert!(EventFilter::default().matches(&event));
+    assert!(EventFilter {
+        access_path_prefix: Some(AccessPath::new(address1, vec![])),
+        resource_type: Some(account_struct_tag()),
+    }
+    .matches(&event));
+    assert!(!EventFilter {
+        access_path_prefix: Some(AccessPath::new(address2, vec![])),
+        resource_type: None,
+    }
+    .matches(&event));
+    assert!(!EventFilter {
+        access_path_prefix: None,
+        resource_type: Some(other_resource_type),
+    }
+    .matches(&event));
+}
+
 #[test]
 fn test_access_path_protobuf_conversion() {
     let address = AccountAddress::new([1u8; ADDRESS_LENGTH]);
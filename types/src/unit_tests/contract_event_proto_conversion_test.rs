@@ -1,7 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::contract_event::{ContractEvent, EventWithProof};
+use crate::contract_event::{ContractEvent, EventFilter, EventWithProof};
 use proptest::prelude::*;
 use proto_conv::test_helper::assert_protobuf_encode_decode;
 
@@ -15,4 +15,9 @@ proptest! {
     fn test_event_with_proof(event_with_proof in any::<EventWithProof>()) {
         assert_protobuf_encode_decode(&event_with_proof);
     }
+
+    #[test]
+    fn test_event_filter(filter in any::<EventFilter>()) {
+        assert_protobuf_encode_decode(&filter);
+    }
 }
@@ -44,4 +44,9 @@ proptest! {
     fn test_transaction_list_with_proof(txn_list in any::<TransactionListWithProof>()) {
         assert_protobuf_encode_decode(&txn_list);
     }
+
+    #[test]
+    fn test_event_list_with_proof(event_list in any::<EventListWithProof>()) {
+        assert_protobuf_encode_decode(&event_list);
+    }
 }
@@ -29,7 +29,7 @@ pub fn validator_set_tag() -> StructTag {
     }
 }
 
-pub(crate) fn validator_set_path() -> Vec<u8> {
+pub fn validator_set_path() -> Vec<u8> {
     AccessPath::resource_access_vec(&validator_set_tag(), &Accesses::empty())
 }
 
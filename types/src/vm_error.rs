@@ -110,6 +110,9 @@ pub enum VMVerificationError {
     MoveToSenderNoResourceError(String),
     CreateAccountTypeMismatchError(String),
     GlobalReferenceError(String),
+    VecPackTypeMismatchError(String),
+    VecTypeMismatchError(String),
+    VecExistsBorrowError(String),
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
@@ -192,6 +195,8 @@ pub enum ExecutionStatus {
     ArithmeticError(ArithmeticErrorType),
     DynamicReferenceError(DynamicReferenceErrorType),
     DuplicateModuleName,
+    VectorIndexOutOfBounds,
+    VectorTooLarge,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
@@ -571,6 +576,15 @@ impl IntoProto for VMVerificationError {
             VMVerificationError::GlobalReferenceError(message) => {
                 (ProtoKind::GlobalReferenceError, message)
             }
+            VMVerificationError::VecPackTypeMismatchError(message) => {
+                (ProtoKind::VecPackTypeMismatchError, message)
+            }
+            VMVerificationError::VecTypeMismatchError(message) => {
+                (ProtoKind::VecTypeMismatchError, message)
+            }
+            VMVerificationError::VecExistsBorrowError(message) => {
+                (ProtoKind::VecExistsBorrowError, message)
+            }
         }
     }
 }
@@ -757,6 +771,15 @@ impl FromProto for VMVerificationError {
             ProtoKind::GlobalReferenceError => {
                 Ok(VMVerificationError::GlobalReferenceError(message))
             }
+            ProtoKind::VecPackTypeMismatchError => {
+                Ok(VMVerificationError::VecPackTypeMismatchError(message))
+            }
+            ProtoKind::VecTypeMismatchError => {
+                Ok(VMVerificationError::VecTypeMismatchError(message))
+            }
+            ProtoKind::VecExistsBorrowError => {
+                Ok(VMVerificationError::VecExistsBorrowError(message))
+            }
             ProtoKind::UnknownVerificationError => {
                 bail_err!(DecodingError::UnknownVerificationErrorEncountered)
             }
@@ -1023,6 +1046,12 @@ impl IntoProto for ExecutionStatus {
             ExecutionStatus::DuplicateModuleName => {
                 exec_status.set_runtime_status(RuntimeStatus::DuplicateModuleName)
             }
+            ExecutionStatus::VectorIndexOutOfBounds => {
+                exec_status.set_runtime_status(RuntimeStatus::VectorIndexOutOfBounds)
+            }
+            ExecutionStatus::VectorTooLarge => {
+                exec_status.set_runtime_status(RuntimeStatus::VectorTooLarge)
+            }
             ExecutionStatus::DynamicReferenceError(err_type) => {
                 let mut ref_err = DynamicReferenceError::new();
                 let err_code = DynamicReferenceErrorType::into_proto(err_type);
@@ -1081,6 +1110,10 @@ impl FromProto for ExecutionStatus {
                     Ok(ExecutionStatus::ValueDeserializationError)
                 }
                 ProtoRuntimeStatus::DuplicateModuleName => Ok(ExecutionStatus::DuplicateModuleName),
+                ProtoRuntimeStatus::VectorIndexOutOfBounds => {
+                    Ok(ExecutionStatus::VectorIndexOutOfBounds)
+                }
+                ProtoRuntimeStatus::VectorTooLarge => Ok(ExecutionStatus::VectorTooLarge),
                 ProtoRuntimeStatus::UnknownRuntimeStatus => {
                     bail_err!(DecodingError::UnknownRuntimeStatusEncountered)
                 }